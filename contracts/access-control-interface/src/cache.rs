@@ -0,0 +1,178 @@
+//! Short-lived cache for cross-contract role checks.
+//!
+//! Consumer contracts on hot paths can remember that a user held a role when
+//! last verified against the access control contract and skip the
+//! cross-contract call until the entry is older than the configured max
+//! staleness. Only positive results are cached, so grants take effect
+//! immediately. Revocations are bounded by the staleness window; consumers
+//! that expose an entrypoint calling [`invalidate`] can drop entries as soon
+//! as the access control contract emits `cache_invalidated`.
+//!
+//! Caching is off until a max staleness is set.
+
+use crate::{AccessControlClient, Role};
+use soroban_sdk::{contracttype, Address, Env};
+
+/// Storage keys used by the role check cache.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CacheKey {
+    /// Ledgers a cached check stays valid (0 disables caching)
+    MaxStaleness,
+    /// Ledger at which a user was last verified to hold a role
+    RoleCheck(Address, Role),
+}
+
+/// Sets how many ledgers a verified role check may be reused (0 disables
+/// caching).
+pub fn set_max_staleness(env: &Env, ledgers: u32) {
+    env.storage().instance().set(&CacheKey::MaxStaleness, &ledgers);
+}
+
+/// Returns the configured max staleness in ledgers (0 when disabled).
+pub fn get_max_staleness(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&CacheKey::MaxStaleness)
+        .unwrap_or(0)
+}
+
+/// Returns whether `user` holds `role`, answering from the cache when a
+/// fresh entry exists and asking the access control contract otherwise.
+pub fn has_role(env: &Env, access_control: &Address, user: &Address, role: Role) -> bool {
+    let max_staleness = get_max_staleness(env);
+    let key = CacheKey::RoleCheck(user.clone(), role.clone());
+    let now = env.ledger().sequence();
+
+    if max_staleness > 0 {
+        let verified_at: Option<u32> = env.storage().temporary().get(&key);
+        if let Some(verified_at) = verified_at {
+            if now.saturating_sub(verified_at) <= max_staleness {
+                return true;
+            }
+        }
+    }
+
+    let granted = AccessControlClient::new(env, access_control).has_role(user, &role);
+    if granted && max_staleness > 0 {
+        // Let the entry expire on its own once it can no longer be used
+        let ttl = max_staleness.min(env.storage().max_ttl());
+        env.storage().temporary().set(&key, &now);
+        env.storage().temporary().extend_ttl(&key, ttl, ttl);
+    } else {
+        env.storage().temporary().remove(&key);
+    }
+    granted
+}
+
+/// Drops any cached check for `user` and `role`.
+pub fn invalidate(env: &Env, user: &Address, role: Role) {
+    env.storage()
+        .temporary()
+        .remove(&CacheKey::RoleCheck(user.clone(), role));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{contract, contractimpl, testutils::Address as _, testutils::Ledger};
+
+    #[contracttype]
+    enum MockKey {
+        Granted,
+        Calls,
+    }
+
+    /// Access control stand-in that counts role checks.
+    #[contract]
+    struct MockAccessControl;
+
+    #[contractimpl]
+    impl MockAccessControl {
+        pub fn set_granted(env: Env, granted: bool) {
+            env.storage().instance().set(&MockKey::Granted, &granted);
+        }
+
+        pub fn has_role(env: Env, _user: Address, _role: Role) -> bool {
+            let calls: u32 = env.storage().instance().get(&MockKey::Calls).unwrap_or(0);
+            env.storage().instance().set(&MockKey::Calls, &(calls + 1));
+            env.storage()
+                .instance()
+                .get(&MockKey::Granted)
+                .unwrap_or(false)
+        }
+
+        pub fn calls(env: Env) -> u32 {
+            env.storage().instance().get(&MockKey::Calls).unwrap_or(0)
+        }
+    }
+
+    #[contract]
+    struct Consumer;
+
+    fn setup() -> (Env, Address, Address, MockAccessControlClient<'static>) {
+        let env = Env::default();
+        let access_control = env.register(MockAccessControl, ());
+        let consumer = env.register(Consumer, ());
+        let mock = MockAccessControlClient::new(&env, &access_control);
+        mock.set_granted(&true);
+        (env, access_control, consumer, mock)
+    }
+
+    #[test]
+    fn test_disabled_cache_always_calls_through() {
+        let (env, access_control, consumer, mock) = setup();
+        let user = Address::generate(&env);
+
+        env.as_contract(&consumer, || {
+            assert!(has_role(&env, &access_control, &user, Role::Operator));
+            assert!(has_role(&env, &access_control, &user, Role::Operator));
+        });
+        assert_eq!(mock.calls(), 2);
+    }
+
+    #[test]
+    fn test_cache_reuses_fresh_checks_until_stale() {
+        let (env, access_control, consumer, mock) = setup();
+        let user = Address::generate(&env);
+
+        env.as_contract(&consumer, || {
+            set_max_staleness(&env, 10);
+            assert!(has_role(&env, &access_control, &user, Role::Operator));
+            assert!(has_role(&env, &access_control, &user, Role::Operator));
+        });
+        assert_eq!(mock.calls(), 1);
+
+        // Revoked upstream: still served from cache inside the window
+        mock.set_granted(&false);
+        env.ledger().with_mut(|li| li.sequence_number += 10);
+        env.as_contract(&consumer, || {
+            assert!(has_role(&env, &access_control, &user, Role::Operator));
+        });
+        assert_eq!(mock.calls(), 1);
+
+        env.ledger().with_mut(|li| li.sequence_number += 1);
+        env.as_contract(&consumer, || {
+            assert!(!has_role(&env, &access_control, &user, Role::Operator));
+        });
+        assert_eq!(mock.calls(), 2);
+    }
+
+    #[test]
+    fn test_invalidate_forces_recheck() {
+        let (env, access_control, consumer, mock) = setup();
+        let user = Address::generate(&env);
+
+        env.as_contract(&consumer, || {
+            set_max_staleness(&env, 100);
+            assert!(has_role(&env, &access_control, &user, Role::Operator));
+        });
+
+        mock.set_granted(&false);
+        env.as_contract(&consumer, || {
+            invalidate(&env, &user, Role::Operator);
+            assert!(!has_role(&env, &access_control, &user, Role::Operator));
+        });
+        assert_eq!(mock.calls(), 2);
+    }
+}
@@ -0,0 +1,116 @@
+//! # Access Control Interface
+//!
+//! Shared types and the client interface of the access control contract.
+//!
+//! Consumer contracts depend on this crate instead of the implementation, so
+//! they call the deployed contract through [`AccessControlClient`] by address
+//! and keep working when the implementation is upgraded in place. Contracts
+//! that would rather keep roles in their own storage can embed the [`rbac`]
+//! module instead, and hot paths can put the [`cache`] in front of the client.
+//! Contracts that keep derived permissions can implement [`RoleSubscriber`] to
+//! be told about every role change.
+
+#![no_std]
+
+pub mod cache;
+pub mod rbac;
+
+use soroban_sdk::{contractclient, contracttype, Address, Env, Map, Symbol, Vec};
+
+/// Available roles in the system
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Role {
+    /// Super administrator with all permissions
+    Admin = 0,
+    /// Regular user with limited permissions
+    User = 1,
+    /// Operator with elevated permissions for operations
+    Operator = 2,
+    /// Auditor with read-only access
+    Auditor = 3,
+}
+
+/// A single resource a role can be granted on, such as one budget.
+///
+/// Identified by the contract that owns the resource and the resource's id
+/// within that contract.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Scope {
+    pub contract: Address,
+    pub id: u64,
+}
+
+/// Public interface of the access control contract.
+#[allow(dead_code)]
+#[contractclient(name = "AccessControlClient")]
+pub trait AccessControlInterface {
+    /// Assign a role to a user (admin only)
+    fn grant_role(env: Env, caller: Address, user: Address, role: Role);
+
+    /// Revoke a role from a user (admin only)
+    fn revoke_role(env: Env, caller: Address, user: Address, role: Role);
+
+    /// Check if a user has a specific role
+    fn has_role(env: Env, user: Address, role: Role) -> bool;
+
+    /// Get all roles for a user
+    fn get_user_roles(env: Env, user: Address) -> Map<Role, bool>;
+
+    /// Grant a role on a single scope (admin only)
+    fn grant_scoped_role(env: Env, caller: Address, user: Address, role: Role, scope: Scope);
+
+    /// Revoke a role on a single scope (admin only)
+    fn revoke_scoped_role(env: Env, caller: Address, user: Address, role: Role, scope: Scope);
+
+    /// Check if a user holds a role on a scope, either globally or scoped to it
+    fn has_scoped_role(env: Env, user: Address, role: Role, scope: Scope) -> bool;
+
+    /// Check if a user was granted a role on the scope itself, ignoring
+    /// global grants
+    fn has_scope_grant(env: Env, user: Address, role: Role, scope: Scope) -> bool;
+
+    /// Check if a user holds a custom role defined at runtime under `name`
+    fn has_custom_role(env: Env, user: Address, name: Symbol) -> bool;
+
+    /// Check whether an operator is inside their operating hours
+    fn is_within_operating_hours(env: Env, operator: Address) -> bool;
+
+    /// Fail unless `operator` holds the Operator role and is inside their
+    /// operating hours
+    fn require_operator(env: Env, operator: Address);
+
+    /// Check a role for several users in one call
+    fn has_role_batch(env: Env, users: Vec<Address>, role: Role) -> Vec<bool>;
+
+    /// Get the admin address
+    fn get_admin(env: Env) -> Address;
+
+    /// Check whether a feature flag is enabled
+    fn is_flag_enabled(env: Env, flag: Symbol) -> bool;
+
+    /// Returns the storage layout version currently stored
+    fn get_storage_version(env: Env) -> u32;
+}
+
+/// Interface the access control contract calls on its subscribers whenever a
+/// built-in role is granted or revoked.
+///
+/// `source` is the access control contract itself, which authorizes each
+/// call, so a subscriber can ignore calls from anywhere else. A subscriber
+/// using the [`cache`] would typically drop the cached entry here.
+#[allow(dead_code)]
+#[contractclient(name = "RoleSubscriberClient")]
+pub trait RoleSubscriber {
+    /// `role` was granted to (or revoked from) `user`, globally when `scope`
+    /// is `None`.
+    fn on_role_changed(
+        env: Env,
+        source: Address,
+        user: Address,
+        role: Role,
+        scope: Option<Scope>,
+        granted: bool,
+    );
+}
@@ -0,0 +1,249 @@
+//! Embeddable role-based access control.
+//!
+//! Stores roles in the embedding contract's own instance storage, using the
+//! same layout, `Role` type and events as the access control contract, so a
+//! small contract can authorize callers without a cross-contract call per
+//! check. Errors are returned to the caller to map onto its own error type.
+//!
+//! Roles can also be granted on a single [`Scope`]. Scoped grants are
+//! unbounded in number, so they live in persistent storage.
+
+use crate::{Role, Scope};
+use common::events;
+use soroban_sdk::{contracttype, Address, Env, Map};
+
+/// Storage keys used by the embedded RBAC module.
+///
+/// Encodes identically to the access control contract's `UserRoles` key.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RbacKey {
+    /// Map of address to their roles
+    UserRoles(Address),
+    /// Whether an address holds a role on one scope
+    ScopedRole(Address, Role, Scope),
+}
+
+/// Errors raised by embedded RBAC operations.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RbacError {
+    /// Caller does not hold the required role
+    Unauthorized,
+    /// User already has the role
+    RoleAlreadyAssigned,
+    /// User does not have the role
+    RoleNotAssigned,
+}
+
+/// Returns every role recorded for `user`.
+pub fn get_user_roles(env: &Env, user: &Address) -> Map<Role, bool> {
+    env.storage()
+        .instance()
+        .get(&RbacKey::UserRoles(user.clone()))
+        .unwrap_or(Map::new(env))
+}
+
+/// Returns whether `user` holds `role`.
+pub fn has_role(env: &Env, user: &Address, role: Role) -> bool {
+    get_user_roles(env, user).get(role).unwrap_or(false)
+}
+
+/// Grants `role` to `user` and emits `role_granted`.
+pub fn grant_role(env: &Env, user: &Address, role: Role) -> Result<(), RbacError> {
+    let mut roles = get_user_roles(env, user);
+    if roles.get(role.clone()).unwrap_or(false) {
+        return Err(RbacError::RoleAlreadyAssigned);
+    }
+
+    roles.set(role.clone(), true);
+    set_user_roles(env, user, &roles);
+
+    events::publish(env, ("access_control", "role_granted"), (user.clone(), role));
+    Ok(())
+}
+
+/// Revokes `role` from `user` and emits `role_revoked`.
+pub fn revoke_role(env: &Env, user: &Address, role: Role) -> Result<(), RbacError> {
+    let mut roles = get_user_roles(env, user);
+    if !roles.get(role.clone()).unwrap_or(false) {
+        return Err(RbacError::RoleNotAssigned);
+    }
+
+    roles.set(role.clone(), false);
+    set_user_roles(env, user, &roles);
+
+    events::publish(env, ("access_control", "role_revoked"), (user.clone(), role));
+    Ok(())
+}
+
+/// Returns whether `user` holds `role` on `scope`, either globally or through
+/// a grant on that scope.
+pub fn has_scoped_role(env: &Env, user: &Address, role: Role, scope: &Scope) -> bool {
+    has_role(env, user, role.clone()) || has_scope_grant(env, user, role, scope)
+}
+
+/// Returns whether `user` was granted `role` on `scope` itself, ignoring
+/// global grants.
+pub fn has_scope_grant(env: &Env, user: &Address, role: Role, scope: &Scope) -> bool {
+    env.storage()
+        .persistent()
+        .has(&RbacKey::ScopedRole(user.clone(), role, scope.clone()))
+}
+
+/// Grants `role` on `scope` to `user` and emits `scoped_role_granted`.
+pub fn grant_scoped_role(
+    env: &Env,
+    user: &Address,
+    role: Role,
+    scope: &Scope,
+) -> Result<(), RbacError> {
+    let key = RbacKey::ScopedRole(user.clone(), role.clone(), scope.clone());
+    if env.storage().persistent().has(&key) {
+        return Err(RbacError::RoleAlreadyAssigned);
+    }
+    env.storage().persistent().set(&key, &true);
+
+    events::publish(
+        env,
+        ("access_control", "scoped_role_granted"),
+        (user.clone(), role, scope.clone()),
+    );
+    Ok(())
+}
+
+/// Revokes `role` on `scope` from `user` and emits `scoped_role_revoked`.
+pub fn revoke_scoped_role(
+    env: &Env,
+    user: &Address,
+    role: Role,
+    scope: &Scope,
+) -> Result<(), RbacError> {
+    let key = RbacKey::ScopedRole(user.clone(), role.clone(), scope.clone());
+    if !env.storage().persistent().has(&key) {
+        return Err(RbacError::RoleNotAssigned);
+    }
+    env.storage().persistent().remove(&key);
+
+    events::publish(
+        env,
+        ("access_control", "scoped_role_revoked"),
+        (user.clone(), role, scope.clone()),
+    );
+    Ok(())
+}
+
+/// Fails unless `caller` holds `role`.
+pub fn require_role(env: &Env, caller: &Address, role: Role) -> Result<(), RbacError> {
+    if !has_role(env, caller, role) {
+        return Err(RbacError::Unauthorized);
+    }
+    Ok(())
+}
+
+/// Fails unless `caller` holds `Role::Admin` or `role`.
+pub fn require_admin_or_role(env: &Env, caller: &Address, role: Role) -> Result<(), RbacError> {
+    let roles = get_user_roles(env, caller);
+    let is_admin = roles.get(Role::Admin).unwrap_or(false);
+    if !is_admin && !roles.get(role).unwrap_or(false) {
+        return Err(RbacError::Unauthorized);
+    }
+    Ok(())
+}
+
+// Internal helper to store a user's role map
+fn set_user_roles(env: &Env, user: &Address, roles: &Map<Role, bool>) {
+    env.storage()
+        .instance()
+        .set(&RbacKey::UserRoles(user.clone()), roles);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{contract, testutils::Address as _};
+
+    #[contract]
+    struct RbacHost;
+
+    fn with_host<T>(f: impl FnOnce(&Env) -> T) -> T {
+        let env = Env::default();
+        let host = env.register(RbacHost, ());
+        env.as_contract(&host, || f(&env))
+    }
+
+    #[test]
+    fn test_grant_and_revoke() {
+        with_host(|env| {
+            let user = Address::generate(env);
+            assert!(!has_role(env, &user, Role::Operator));
+
+            grant_role(env, &user, Role::Operator).unwrap();
+            assert!(has_role(env, &user, Role::Operator));
+            assert_eq!(
+                grant_role(env, &user, Role::Operator),
+                Err(RbacError::RoleAlreadyAssigned)
+            );
+
+            revoke_role(env, &user, Role::Operator).unwrap();
+            assert!(!has_role(env, &user, Role::Operator));
+            assert_eq!(
+                revoke_role(env, &user, Role::Operator),
+                Err(RbacError::RoleNotAssigned)
+            );
+        });
+    }
+
+    #[test]
+    fn test_require_role() {
+        with_host(|env| {
+            let admin = Address::generate(env);
+            let auditor = Address::generate(env);
+            grant_role(env, &admin, Role::Admin).unwrap();
+            grant_role(env, &auditor, Role::Auditor).unwrap();
+
+            assert_eq!(require_role(env, &auditor, Role::Auditor), Ok(()));
+            assert_eq!(
+                require_role(env, &admin, Role::Auditor),
+                Err(RbacError::Unauthorized)
+            );
+            assert_eq!(require_admin_or_role(env, &admin, Role::Auditor), Ok(()));
+            assert_eq!(
+                require_admin_or_role(env, &auditor, Role::Operator),
+                Err(RbacError::Unauthorized)
+            );
+        });
+    }
+
+    #[test]
+    fn test_scoped_roles() {
+        with_host(|env| {
+            let owner = Address::generate(env);
+            let operator = Address::generate(env);
+            let budgets = Address::generate(env);
+            let scope = Scope { contract: budgets.clone(), id: 7 };
+            let other = Scope { contract: budgets, id: 8 };
+
+            grant_scoped_role(env, &owner, Role::Operator, &scope).unwrap();
+            assert!(has_scoped_role(env, &owner, Role::Operator, &scope));
+            assert!(!has_scoped_role(env, &owner, Role::Operator, &other));
+            assert!(!has_role(env, &owner, Role::Operator));
+            assert_eq!(
+                grant_scoped_role(env, &owner, Role::Operator, &scope),
+                Err(RbacError::RoleAlreadyAssigned)
+            );
+
+            // A global grant covers every scope, but is not a grant on it
+            grant_role(env, &operator, Role::Operator).unwrap();
+            assert!(has_scoped_role(env, &operator, Role::Operator, &other));
+            assert!(!has_scope_grant(env, &operator, Role::Operator, &other));
+            assert!(has_scope_grant(env, &owner, Role::Operator, &scope));
+
+            revoke_scoped_role(env, &owner, Role::Operator, &scope).unwrap();
+            assert!(!has_scoped_role(env, &owner, Role::Operator, &scope));
+            assert_eq!(
+                revoke_scoped_role(env, &owner, Role::Operator, &scope),
+                Err(RbacError::RoleNotAssigned)
+            );
+        });
+    }
+}
@@ -0,0 +1,114 @@
+//! Adversarial tests for the Access Control Contract.
+//!
+//! Each test plays out a concrete attack on role assignment and asserts that
+//! it fails without changing who holds which role.
+
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{symbol_short, testutils::Address as _, Address, Env};
+
+fn create_contract() -> (Env, AccessControlContractClient<'static>, Address) {
+    let env = Env::default();
+    let contract_id = env.register(AccessControlContract, ());
+    let client = AccessControlContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    (env, client, admin)
+}
+
+/// Expected result of a call rejected with `error`.
+fn rejected(error: AccessControlError) -> Result<soroban_sdk::Error, soroban_sdk::InvokeError> {
+    Ok(error.into())
+}
+
+// ============================================
+// Privilege Escalation
+// ============================================
+
+#[test]
+fn test_operator_cannot_grant_itself_admin() {
+    let (env, client, admin) = create_contract();
+    let operator = Address::generate(&env);
+    client.grant_role(&admin, &operator, &Role::Operator);
+
+    let res = client.try_grant_role(&operator, &operator, &Role::Admin);
+    assert_eq!(res, Err(rejected(AccessControlError::Unauthorized)));
+    let res = client.try_transfer_admin(&operator, &operator);
+    assert_eq!(res, Err(rejected(AccessControlError::Unauthorized)));
+
+    assert!(!client.has_role(&operator, &Role::Admin));
+    assert_eq!(client.get_admin(), admin);
+}
+
+#[test]
+fn test_forged_admin_call_without_signature_fails() {
+    let (env, client, admin) = create_contract();
+    let attacker = Address::generate(&env);
+
+    // Naming the admin as caller is not enough without the admin's signature
+    env.set_auths(&[]);
+    assert!(client.try_grant_role(&admin, &attacker, &Role::Admin).is_err());
+    assert!(client.try_transfer_admin(&admin, &attacker).is_err());
+
+    assert!(!client.has_role(&attacker, &Role::Admin));
+    assert_eq!(client.get_admin(), admin);
+}
+
+#[test]
+fn test_attacker_cannot_lock_out_admin() {
+    let (env, client, admin) = create_contract();
+    let attacker = Address::generate(&env);
+
+    let res = client.try_revoke_role(&attacker, &admin, &Role::Admin);
+    assert_eq!(res, Err(rejected(AccessControlError::Unauthorized)));
+    let res = client.try_set_stale_admin_period(&attacker, &1);
+    assert_eq!(res, Err(rejected(AccessControlError::Unauthorized)));
+
+    assert!(client.has_role(&admin, &Role::Admin));
+}
+
+// ============================================
+// Admin Key Compromise
+// ============================================
+
+#[test]
+fn test_rotated_out_admin_key_loses_all_powers() {
+    let (env, client, leaked) = create_contract();
+
+    // Owners rotate away from the leaked key
+    let new_admin = Address::generate(&env);
+    client.transfer_admin(&leaked, &new_admin);
+
+    let attacker = Address::generate(&env);
+    let res = client.try_grant_role(&leaked, &attacker, &Role::Operator);
+    assert_eq!(res, Err(rejected(AccessControlError::Unauthorized)));
+    let res = client.try_set_flag(&leaked, &symbol_short!("payouts"), &true);
+    assert_eq!(res, Err(rejected(AccessControlError::Unauthorized)));
+    let res = client.try_transfer_admin(&leaked, &leaked);
+    assert_eq!(res, Err(rejected(AccessControlError::Unauthorized)));
+
+    assert!(!client.has_role(&attacker, &Role::Operator));
+    assert!(!client.is_flag_enabled(&symbol_short!("payouts")));
+    assert_eq!(client.get_admin(), new_admin);
+}
+
+#[test]
+fn test_revoked_operator_cannot_reuse_role() {
+    let (env, client, admin) = create_contract();
+    let operator = Address::generate(&env);
+    client.grant_role(&admin, &operator, &Role::Operator);
+
+    // A compromised operator is cut off at once
+    client.revoke_role(&admin, &operator, &Role::Operator);
+
+    assert!(!client.has_role(&operator, &Role::Operator));
+    let res = env.as_contract(&client.address, || {
+        rbac::require_role(&env, &operator, Role::Operator)
+    });
+    assert_eq!(res, Err(RbacError::Unauthorized));
+}
@@ -5,7 +5,159 @@
 
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, contracttype, panic_with_error, Address, Env, Map};
+pub use access_control_interface::{
+    AccessControlClient, AccessControlInterface, Role, RoleSubscriber, RoleSubscriberClient, Scope,
+};
+use access_control_interface::rbac::{self, RbacError, RbacKey};
+use common::events;
+use common::footprint::{self, StorageFamily};
+use common::ids::{self, IdScope};
+use common::limits::{self, Limit};
+use common::merkle;
+use common::versioning;
+use soroban_sdk::{
+    contract, contractimpl, contracttype, panic_with_error, symbol_short, Address, BytesN, Env,
+    IntoVal, Map, String, Symbol, TryFromVal, Val, Vec,
+};
+
+/// Longest lifetime of an export token (7 days), in seconds.
+pub const MAX_EXPORT_TOKEN_SECS: u64 = 7 * 86_400;
+
+/// Authorization for an auditor to pull one scope of reports from the
+/// off-chain export service
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExportToken {
+    /// Auditor the token was issued to
+    pub holder: Address,
+    /// Report scope the token covers, e.g. `payroll`
+    pub scope: Symbol,
+    pub issued_at: u64,
+    /// Ledger timestamp the token stops being valid at
+    pub expires_at: u64,
+    pub revoked: bool,
+}
+
+/// Seconds in a day, over which operating hours repeat.
+pub const SECONDS_PER_DAY: u32 = 86_400;
+
+/// Daily window, in seconds since midnight UTC of the ledger timestamp, during
+/// which an operator may use the Operator role. A window whose start is after
+/// its end wraps past midnight.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OperatingHours {
+    /// Start of the window, inclusive
+    pub start: u32,
+    /// End of the window, exclusive
+    pub end: u32,
+}
+
+impl OperatingHours {
+    /// Whether ledger timestamp `timestamp` falls inside the window
+    pub fn contains(&self, timestamp: u64) -> bool {
+        let time_of_day = (timestamp % SECONDS_PER_DAY as u64) as u32;
+        if self.start <= self.end {
+            self.start <= time_of_day && time_of_day < self.end
+        } else {
+            time_of_day >= self.start || time_of_day < self.end
+        }
+    }
+}
+
+/// Deepest Merkle proof accepted when claiming a role.
+pub use common::merkle::MAX_PROOF_DEPTH;
+
+/// Merkle root of `(address, role)` leaves that users claim their own role
+/// from, for migrating a large user base without a grant per user
+///
+/// A leaf is the SHA-256 of the XDR encoding of the `(Address, Role)` tuple;
+/// each parent is the SHA-256 of its two children in ascending byte order.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoleDrop {
+    pub root: BytesN<32>,
+    pub posted_at: u64,
+    /// Ledger timestamp after which roles can no longer be claimed
+    pub expires_at: u64,
+    /// Number of roles claimed so far
+    pub claimed: u32,
+}
+
+/// Maximum number of contracts notified of role changes.
+pub const MAX_ROLE_SUBSCRIBERS: u32 = 5;
+
+/// Longest description accepted for a custom role, in bytes.
+pub const MAX_ROLE_METADATA_LEN: u32 = 256;
+
+/// A role defined at runtime under a Symbol name, alongside the built-in
+/// `Role` variants
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CustomRole {
+    /// Distinguishes this definition from an earlier role of the same name,
+    /// so grants made before a delete do not carry over to a re-created role
+    pub id: u64,
+    /// Free-form description of what the role is for
+    pub metadata: String,
+    pub created_at: u64,
+}
+
+/// Read-only mode a user is placed in during an investigation
+///
+/// Until `until`, a user holding any role is treated as holding only the
+/// Auditor role, and their custom roles lapse. Their stored roles are kept
+/// and take effect again once the suspension ends.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Suspension {
+    pub suspended_at: u64,
+    /// Ledger timestamp at which the user's roles are restored
+    pub until: u64,
+}
+
+/// One user's stored roles, exported as-is for seeding a new deployment
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoleRecord {
+    pub user: Address,
+    /// Role map exactly as stored, including revoked and expired entries
+    pub roles: Map<Role, bool>,
+    /// Expiry ledgers of the user's time-limited roles
+    pub expiries: Vec<(Role, u32)>,
+    /// Roles granted on single scopes
+    pub scoped: Vec<(Role, Scope)>,
+    /// Custom role grants with the id of the definition each was made under
+    pub custom: Vec<(Symbol, u64)>,
+    /// Start of the stored suspension, even if it has ended; 0 if none
+    pub suspended_at: u64,
+    /// End of the stored suspension; 0 if none
+    pub suspended_until: u64,
+}
+
+/// Grants made before version 2 indexed the role table, listed by the admin
+/// when migrating so that exports cover them
+///
+/// Entries that do not match a stored grant are skipped.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoleBackfill {
+    /// Users holding a global role
+    pub users: Vec<Address>,
+    pub scoped: Vec<(Address, Role, Scope)>,
+    /// Names of defined custom roles
+    pub custom_roles: Vec<Symbol>,
+    pub custom_grants: Vec<(Address, Symbol)>,
+}
+
+/// One chunk of the role table export
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoleExportChunk {
+    pub records: Vec<RoleRecord>,
+    /// Cursor for the next chunk; `None` once every user has been exported
+    pub next_cursor: Option<u64>,
+}
 
 /// Storage keys for the access control contract
 #[contracttype]
@@ -17,20 +169,52 @@ pub enum DataKey {
     UserRoles(Address),
     /// Total number of role assignments
     TotalRoleAssignments,
-}
-
-/// Available roles in the system
-#[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub enum Role {
-    /// Super administrator with all permissions
-    Admin = 0,
-    /// Regular user with limited permissions
-    User = 1,
-    /// Operator with elevated permissions for operations
-    Operator = 2,
-    /// Auditor with read-only access
-    Auditor = 3,
+    /// Map of feature flag to whether it is enabled
+    FeatureFlags,
+    /// Ledger at which an admin last performed an action
+    AdminLastActive,
+    /// Ledgers of admin inactivity after which the stale admin alarm fires
+    StaleAdminPeriod,
+    /// Export token by id
+    ExportToken(u64),
+    /// Operating hours applying to operators without their own
+    DefaultOperatingHours,
+    /// Operating hours of one operator
+    OperatingHours(Address),
+    /// Role drop by id
+    RoleDrop(u64),
+    /// Whether a user has claimed a role from a drop
+    RoleClaimed(u64, Address, Role),
+    /// Custom role definition by name
+    CustomRole(Symbol),
+    /// Id of the custom role definition a user was granted a role under
+    CustomRoleGrant(Address, Symbol),
+    /// Read-only suspension of a user
+    Suspension(Address),
+    /// Contracts notified of role changes
+    RoleSubscribers,
+    /// Number of users with any role record
+    RoleUserCount,
+    /// User with any role record by position, from 1 in the order first seen
+    RoleUserAt(u64),
+    /// Position of a user in the role user index
+    RoleUserPosition(Address),
+    /// Roles a user was granted on single scopes
+    UserScopedRoles(Address),
+    /// Custom roles a user was granted, by name
+    UserCustomRoles(Address),
+    /// Names of the defined custom roles
+    CustomRoleNames,
+    /// Number of users holding a role
+    RoleMemberCount(Role),
+    /// Holder of a role by position, from 0
+    RoleMemberAt(Role, u64),
+    /// Position of a user in a role's member index
+    RoleMemberIndex(Address, Role),
+    /// Members of a role holding it until an expiry ledger
+    RoleExpiringMembers(Role),
+    /// Ledger from which a time-limited role grant no longer counts
+    RoleExpiry(Address, Role),
 }
 
 /// Error codes for access control operations
@@ -49,6 +233,40 @@ pub enum AccessControlError {
     RoleNotAssigned = 5,
     /// Cannot revoke admin from self
     CannotRevokeSelfAdmin = 6,
+    /// Feature flag is not enabled
+    FeatureDisabled = 7,
+    /// Storage was written by a different layout version
+    StorageVersionMismatch = 8,
+    /// Migration source version does not match stored version
+    InvalidMigration = 9,
+    /// Batch exceeds maximum size
+    BatchTooLarge = 10,
+    /// Expiry is in the past or too far ahead
+    InvalidExpiry = 11,
+    /// Export token does not exist
+    ExportTokenNotFound = 12,
+    /// Export token id space is exhausted
+    IdsExhausted = 13,
+    /// Operator role used outside the operator's operating hours
+    OutsideOperatingHours = 14,
+    /// Operating hours window is empty or out of range
+    InvalidOperatingHours = 15,
+    /// Role drop does not exist or was cancelled
+    RoleDropNotFound = 16,
+    /// Role drop has expired
+    RoleDropExpired = 17,
+    /// Merkle proof does not lead to the drop's root
+    InvalidProof = 18,
+    /// Role already claimed from this drop
+    AlreadyClaimed = 19,
+    /// Custom role is not defined
+    RoleNotFound = 20,
+    /// Custom role with this name is already defined
+    RoleExists = 21,
+    /// Suspension has no duration or targets the admin
+    InvalidSuspension = 22,
+    /// More role subscribers than `MAX_ROLE_SUBSCRIBERS`
+    TooManySubscribers = 23,
 }
 
 impl From<AccessControlError> for soroban_sdk::Error {
@@ -57,6 +275,9 @@ impl From<AccessControlError> for soroban_sdk::Error {
     }
 }
 
+/// Storage layout version this code expects.
+pub const STORAGE_VERSION: u32 = 3;
+
 #[contract]
 pub struct AccessControlContract;
 
@@ -72,130 +293,248 @@ impl AccessControlContract {
 
         // Set the admin
         env.storage().instance().set(&DataKey::Admin, &admin);
+        versioning::set_version(&env, STORAGE_VERSION);
         
         // Assign admin role to the initializer
+        Self::track_role_user(&env, &admin);
         let mut roles = Map::new(&env);
         roles.set(Role::Admin, true);
         env.storage()
             .instance()
             .set(&DataKey::UserRoles(admin.clone()), &roles);
+        Self::index_role_member(&env, &admin, Role::Admin);
         
         // Initialize counters
         env.storage()
             .instance()
             .set(&DataKey::TotalRoleAssignments, &1u64);
+        Self::record_admin_activity(&env);
 
         // Emit initialization event
-        env.events()
-            .publish(("access_control", "initialized"), admin);
+        events::publish(&env, ("access_control", "initialized"), admin);
+    }
+
+    /// Migrates storage written by an older version of this contract to the
+    /// current layout (admin only). `from_version` must match the stored
+    /// version.
+    ///
+    /// Version 2 indexes the role table for export. Grants made before it
+    /// cannot be enumerated, so `backfill` must list every one of them.
+    /// Version 3 indexes the members of each role from that table.
+    pub fn migrate(env: Env, caller: Address, from_version: u32, backfill: RoleBackfill) {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        // Version 1 only added the version stamp
+        versioning::migrate(&env, from_version, STORAGE_VERSION, |env, version| {
+            if version == 1 {
+                Self::backfill_role_table(env, &backfill);
+            }
+            if version == 2 {
+                Self::backfill_role_members(env);
+            }
+        })
+        .unwrap_or_else(|_| panic_with_error!(&env, AccessControlError::InvalidMigration));
+        Self::record_admin_activity(&env);
+    }
+
+    /// Returns the storage layout version currently stored.
+    pub fn get_storage_version(env: Env) -> u32 {
+        versioning::stored_version(&env)
     }
 
     /// Assign a role to a user (admin only)
     pub fn grant_role(env: Env, caller: Address, user: Address, role: Role) {
+        Self::require_storage_version(&env);
         caller.require_auth();
         Self::require_admin(&env, &caller);
+        Self::record_admin_activity(&env);
 
-        // Get or create user's role map
-        let mut roles: Map<Role, bool> = env
-            .storage()
-            .instance()
-            .get(&DataKey::UserRoles(user.clone()))
-            .unwrap_or(Map::new(&env));
+        Self::assign_role(&env, &user, role, None);
+    }
 
-        // Check if role already assigned
-        if roles.get(role.clone()).unwrap_or(false) {
-            panic_with_error!(&env, AccessControlError::RoleAlreadyAssigned);
+    /// Assign a role to a user until `expiry_ledger` (admin only)
+    ///
+    /// From that ledger on the role counts as absent, and `purge_expired`
+    /// removes it from storage. The Admin role cannot be time-limited.
+    pub fn grant_role_until(
+        env: Env,
+        caller: Address,
+        user: Address,
+        role: Role,
+        expiry_ledger: u32,
+    ) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+        Self::record_admin_activity(&env);
+
+        if role == Role::Admin {
+            panic_with_error!(&env, AccessControlError::InvalidRole);
+        }
+        if expiry_ledger <= env.ledger().sequence() {
+            panic_with_error!(&env, AccessControlError::InvalidExpiry);
         }
 
-        // Assign the role
-        roles.set(role.clone(), true);
-        env.storage()
-            .instance()
-            .set(&DataKey::UserRoles(user.clone()), &roles);
+        Self::assign_role(&env, &user, role, Some(expiry_ledger));
+    }
 
-        // Update counter
-        let count: u64 = env
-            .storage()
-            .instance()
-            .get(&DataKey::TotalRoleAssignments)
-            .unwrap_or(0);
+    /// Get the ledger a user's time-limited role expires at, if it has one
+    pub fn get_role_expiry(env: Env, user: Address, role: Role) -> Option<u32> {
         env.storage()
-            .instance()
-            .set(&DataKey::TotalRoleAssignments, &(count + 1));
+            .persistent()
+            .get(&DataKey::RoleExpiry(user, role))
+    }
+
+    /// Remove a user's expired roles from storage (anyone may call)
+    ///
+    /// Emits a `role_expired` event for each role removed.
+    ///
+    /// # Returns
+    /// * The roles removed
+    pub fn purge_expired(env: Env, user: Address) -> Vec<Role> {
+        Self::require_storage_version(&env);
 
-        // Emit role granted event
-        env.events()
-            .publish(("access_control", "role_granted"), (user, role));
+        let mut purged = Vec::new(&env);
+        for (role, held) in rbac::get_user_roles(&env, &user).iter() {
+            if held && Self::role_expired(&env, &user, &role) {
+                Self::remove_role(&env, &user, role.clone(), true);
+                purged.push_back(role);
+            }
+        }
+        purged
     }
 
     /// Revoke a role from a user (admin only)
     pub fn revoke_role(env: Env, caller: Address, user: Address, role: Role) {
+        Self::require_storage_version(&env);
         caller.require_auth();
         Self::require_admin(&env, &caller);
+        Self::record_admin_activity(&env);
 
         // Prevent admin from revoking their own admin role
         if caller == user && role == Role::Admin {
             panic_with_error!(&env, AccessControlError::CannotRevokeSelfAdmin);
         }
 
-        // Get user's role map
-        let mut roles: Map<Role, bool> = env
-            .storage()
-            .instance()
-            .get(&DataKey::UserRoles(user.clone()))
-            .unwrap_or(Map::new(&env));
+        Self::remove_role(&env, &user, role, false);
+    }
 
-        // Check if role is assigned
-        if !roles.get(role.clone()).unwrap_or(false) {
-            panic_with_error!(&env, AccessControlError::RoleNotAssigned);
-        }
+    /// Assign a role on a single scope, such as one budget (admin only)
+    ///
+    /// Lets a user act on that resource without holding the role globally.
+    pub fn grant_scoped_role(env: Env, caller: Address, user: Address, role: Role, scope: Scope) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+        Self::record_admin_activity(&env);
 
-        // Revoke the role
-        roles.set(role.clone(), false);
-        env.storage()
-            .instance()
-            .set(&DataKey::UserRoles(user.clone()), &roles);
+        rbac::grant_scoped_role(&env, &user, role.clone(), &scope)
+            .unwrap_or_else(|e| Self::raise(&env, e));
+        Self::track_role_user(&env, &user);
+        let scoped = DataKey::UserScopedRoles(user.clone());
+        Self::record_in_list(&env, &scoped, (role.clone(), scope.clone()), true);
+        Self::notify_subscribers(&env, &user, role, Some(scope), true);
+    }
 
-        // Update counter
-        let count: u64 = env
-            .storage()
-            .instance()
-            .get(&DataKey::TotalRoleAssignments)
-            .unwrap_or(0);
-        if count > 0 {
-            env.storage()
-                .instance()
-                .set(&DataKey::TotalRoleAssignments, &(count - 1));
+    /// Revoke a role on a single scope (admin only)
+    pub fn revoke_scoped_role(env: Env, caller: Address, user: Address, role: Role, scope: Scope) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+        Self::record_admin_activity(&env);
+
+        rbac::revoke_scoped_role(&env, &user, role.clone(), &scope)
+            .unwrap_or_else(|e| Self::raise(&env, e));
+        let scoped = DataKey::UserScopedRoles(user.clone());
+        Self::record_in_list(&env, &scoped, (role.clone(), scope.clone()), false);
+        Self::notify_subscribers(&env, &user, role, Some(scope), false);
+    }
+
+    /// Check if a user holds a role on a scope, either globally or scoped to it
+    ///
+    /// A suspended user holding any role on the scope only holds Auditor.
+    pub fn has_scoped_role(env: Env, user: Address, role: Role, scope: Scope) -> bool {
+        if Self::get_suspension(env.clone(), user.clone()).is_none() {
+            return Self::holds_on_scope(&env, &user, role, &scope);
         }
+        role == Role::Auditor
+            && [Role::Admin, Role::User, Role::Operator, Role::Auditor]
+                .into_iter()
+                .any(|held| Self::holds_on_scope(&env, &user, held, &scope))
+    }
 
-        // Emit role revoked event
-        env.events()
-            .publish(("access_control", "role_revoked"), (user, role));
+    /// Check if a user was granted a role on the scope itself, ignoring
+    /// global grants
+    ///
+    /// A suspended user holding any grant on the scope only holds Auditor.
+    pub fn has_scope_grant(env: Env, user: Address, role: Role, scope: Scope) -> bool {
+        if Self::get_suspension(env.clone(), user.clone()).is_none() {
+            return rbac::has_scope_grant(&env, &user, role, &scope);
+        }
+        role == Role::Auditor
+            && [Role::Admin, Role::User, Role::Operator, Role::Auditor]
+                .into_iter()
+                .any(|held| rbac::has_scope_grant(&env, &user, held, &scope))
     }
 
     /// Check if a user has a specific role
+    ///
+    /// Expired time-limited roles count as absent. A suspended user holding
+    /// any role only holds Auditor.
     pub fn has_role(env: Env, user: Address, role: Role) -> bool {
-        let roles: Map<Role, bool> = env
-            .storage()
-            .instance()
-            .get(&DataKey::UserRoles(user))
-            .unwrap_or(Map::new(&env));
-
-        roles.get(role).unwrap_or(false)
+        let roles = Self::get_user_roles(env.clone(), user.clone());
+        if Self::get_suspension(env, user).is_none() {
+            return roles.get(role).unwrap_or(false);
+        }
+        role == Role::Auditor && roles.values().contains(true)
     }
 
-    /// Get all roles for a user
+    /// Get all roles for a user, with expired time-limited roles marked absent
     pub fn get_user_roles(env: Env, user: Address) -> Map<Role, bool> {
-        env.storage()
-            .instance()
-            .get(&DataKey::UserRoles(user))
-            .unwrap_or(Map::new(&env))
+        let mut roles = rbac::get_user_roles(&env, &user);
+        for (role, held) in roles.clone().iter() {
+            if held && Self::role_expired(&env, &user, &role) {
+                roles.set(role, false);
+            }
+        }
+        roles
+    }
+
+    /// Check one role for many users in a single call
+    ///
+    /// Results are in the same order as `users`. The number of users is capped
+    /// by the batch size limit.
+    pub fn has_role_batch(env: Env, users: Vec<Address>, role: Role) -> Vec<bool> {
+        Self::require_batch_size(&env, users.len());
+
+        let mut results = Vec::new(&env);
+        for user in users.iter() {
+            results.push_back(Self::has_role(env.clone(), user, role.clone()));
+        }
+        results
+    }
+
+    /// Get all roles for many users in a single call
+    ///
+    /// Results are in the same order as `users`. The number of users is capped
+    /// by the batch size limit.
+    pub fn get_user_roles_batch(env: Env, users: Vec<Address>) -> Vec<Map<Role, bool>> {
+        Self::require_batch_size(&env, users.len());
+
+        let mut results = Vec::new(&env);
+        for user in users.iter() {
+            results.push_back(Self::get_user_roles(env.clone(), user));
+        }
+        results
     }
 
     /// Transfer admin role to a new address (current admin only)
     pub fn transfer_admin(env: Env, current_admin: Address, new_admin: Address) {
+        Self::require_storage_version(&env);
         current_admin.require_auth();
         Self::require_admin(&env, &current_admin);
+        Self::record_admin_activity(&env);
 
         // Revoke admin role from current admin
         let mut current_roles: Map<Role, bool> = env
@@ -207,8 +546,10 @@ impl AccessControlContract {
         env.storage()
             .instance()
             .set(&DataKey::UserRoles(current_admin.clone()), &current_roles);
+        Self::unindex_role_member(&env, &current_admin, Role::Admin);
 
         // Grant admin role to new admin
+        Self::track_role_user(&env, &new_admin);
         let mut new_roles: Map<Role, bool> = env
             .storage()
             .instance()
@@ -218,15 +559,20 @@ impl AccessControlContract {
         env.storage()
             .instance()
             .set(&DataKey::UserRoles(new_admin.clone()), &new_roles);
+        Self::index_role_member(&env, &new_admin, Role::Admin);
 
         // Update admin storage
         env.storage().instance().set(&DataKey::Admin, &new_admin);
 
         // Emit admin transfer event
-        env.events().publish(
+        events::publish(
+            &env,
             ("access_control", "admin_transferred"),
-            (current_admin, new_admin),
+            (current_admin.clone(), new_admin.clone()),
         );
+        Self::publish_cache_invalidation(&env, current_admin.clone(), Role::Admin);
+        Self::notify_subscribers(&env, &current_admin, Role::Admin, None, false);
+        Self::notify_subscribers(&env, &new_admin, Role::Admin, None, true);
     }
 
     /// Get the current admin address
@@ -245,6 +591,1004 @@ impl AccessControlContract {
             .unwrap_or(0)
     }
 
+    /// Enable or disable a feature flag (admin role only)
+    ///
+    /// Flags let risky code paths ship disabled and be switched on per
+    /// deployment without a separate build.
+    pub fn set_flag(env: Env, caller: Address, flag: Symbol, enabled: bool) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_role(&env, &caller, Role::Admin);
+        Self::record_admin_activity(&env);
+
+        let mut flags = Self::get_flags(env.clone());
+        flags.set(flag.clone(), enabled);
+        env.storage().instance().set(&DataKey::FeatureFlags, &flags);
+
+        // Emit flag update event
+        events::publish(&env, ("access_control", "flag_set"), (flag, enabled));
+    }
+
+    /// Check if a feature flag is enabled (flags default to disabled)
+    pub fn is_flag_enabled(env: Env, flag: Symbol) -> bool {
+        Self::get_flags(env).get(flag).unwrap_or(false)
+    }
+
+    /// Get all feature flags that have been set
+    pub fn get_flags(env: Env) -> Map<Symbol, bool> {
+        env.storage()
+            .instance()
+            .get(&DataKey::FeatureFlags)
+            .unwrap_or(Map::new(&env))
+    }
+
+    /// Set how many ledgers of admin inactivity raise the stale admin alarm
+    /// (admin only). A period of 0 disables the alarm.
+    pub fn set_stale_admin_period(env: Env, caller: Address, ledgers: u32) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+        Self::record_admin_activity(&env);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::StaleAdminPeriod, &ledgers);
+    }
+
+    /// Get the stale admin period in ledgers (0 when disabled)
+    pub fn get_stale_admin_period(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::StaleAdminPeriod)
+            .unwrap_or(0)
+    }
+
+    /// Get the ledger at which an admin last performed an action
+    pub fn get_admin_last_active(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::AdminLastActive)
+            .unwrap_or(0)
+    }
+
+    /// Check for admin inactivity (anyone may call)
+    ///
+    /// Emits a `stale_admin` alarm event and returns true when no admin has
+    /// acted for longer than the stale admin period. Guardians watch for the
+    /// event to start admin recovery.
+    pub fn check_admin_activity(env: Env) -> bool {
+        let period = Self::get_stale_admin_period(env.clone());
+        if period == 0 {
+            return false;
+        }
+
+        let last_active = Self::get_admin_last_active(env.clone());
+        let idle = env.ledger().sequence().saturating_sub(last_active);
+        if idle <= period {
+            return false;
+        }
+
+        // Emit stale admin alarm
+        events::publish(
+            &env,
+            ("access_control", "stale_admin"),
+            (Self::get_admin(env.clone()), last_active, period),
+        );
+        true
+    }
+    /// Issue an export token to an auditor for one report scope (Auditor role
+    /// only)
+    ///
+    /// The off-chain export service serves a report only to a holder who
+    /// presents a token that `verify_export_token` accepts for its scope.
+    pub fn request_export_token(env: Env, auditor: Address, scope: Symbol, expires_at: u64) -> u64 {
+        Self::require_storage_version(&env);
+        auditor.require_auth();
+        Self::require_role(&env, &auditor, Role::Auditor);
+
+        let now = env.ledger().timestamp();
+        if expires_at <= now || expires_at - now > MAX_EXPORT_TOKEN_SECS {
+            panic_with_error!(&env, AccessControlError::InvalidExpiry);
+        }
+
+        let token_id = ids::next_id(&env, &IdScope::new(0, symbol_short!("export")))
+            .unwrap_or_else(|_| panic_with_error!(&env, AccessControlError::IdsExhausted));
+        let token = ExportToken {
+            holder: auditor.clone(),
+            scope: scope.clone(),
+            issued_at: now,
+            expires_at,
+            revoked: false,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::ExportToken(token_id), &token);
+
+        // Emit export token event
+        events::publish(
+            &env,
+            ("access_control", "export_token"),
+            (token_id, auditor, scope, expires_at),
+        );
+        token_id
+    }
+
+    /// Check an export token presented by `holder` for `scope`
+    ///
+    /// Valid only while unexpired and unrevoked, and while the holder still
+    /// has the Auditor role.
+    pub fn verify_export_token(env: Env, token_id: u64, holder: Address, scope: Symbol) -> bool {
+        match Self::get_export_token(env.clone(), token_id) {
+            Some(token) => {
+                token.holder == holder
+                    && token.scope == scope
+                    && !token.revoked
+                    && env.ledger().timestamp() < token.expires_at
+                    && Self::has_role(env.clone(), holder, Role::Auditor)
+            }
+            None => false,
+        }
+    }
+
+    /// Revoke an export token (its holder or the admin)
+    pub fn revoke_export_token(env: Env, caller: Address, token_id: u64) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+
+        let mut token = Self::get_export_token(env.clone(), token_id)
+            .unwrap_or_else(|| panic_with_error!(&env, AccessControlError::ExportTokenNotFound));
+        if caller != token.holder {
+            Self::require_admin(&env, &caller);
+            Self::record_admin_activity(&env);
+        }
+
+        token.revoked = true;
+        env.storage()
+            .persistent()
+            .set(&DataKey::ExportToken(token_id), &token);
+
+        // Emit revocation event
+        events::publish(&env, ("access_control", "export_revoked"), (token_id, caller));
+    }
+
+    /// Set the operating hours of one operator, or the default for all
+    /// operators when `operator` is `None` (admin only)
+    ///
+    /// An operator's own hours take precedence over the default. Passing
+    /// `None` for `hours` removes the restriction.
+    pub fn set_operating_hours(
+        env: Env,
+        caller: Address,
+        operator: Option<Address>,
+        hours: Option<OperatingHours>,
+    ) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+        Self::record_admin_activity(&env);
+
+        let key = match &operator {
+            Some(operator) => DataKey::OperatingHours(operator.clone()),
+            None => DataKey::DefaultOperatingHours,
+        };
+        match &hours {
+            Some(window) => {
+                if window.start >= SECONDS_PER_DAY
+                    || window.end >= SECONDS_PER_DAY
+                    || window.start == window.end
+                {
+                    panic_with_error!(&env, AccessControlError::InvalidOperatingHours);
+                }
+                env.storage().persistent().set(&key, window);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+
+        events::publish(&env, ("access_control", "operating_hours"), (operator, hours));
+    }
+
+    /// Get the operating hours that apply to an operator, if restricted
+    pub fn get_operating_hours(env: Env, operator: Address) -> Option<OperatingHours> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::OperatingHours(operator))
+            .or_else(|| env.storage().persistent().get(&DataKey::DefaultOperatingHours))
+    }
+
+    /// Check whether the current ledger time is inside an operator's
+    /// operating hours (always true when unrestricted)
+    pub fn is_within_operating_hours(env: Env, operator: Address) -> bool {
+        match Self::get_operating_hours(env.clone(), operator) {
+            Some(hours) => hours.contains(env.ledger().timestamp()),
+            None => true,
+        }
+    }
+
+    /// Fail unless `operator` holds the Operator role and is inside their
+    /// operating hours
+    ///
+    /// Consumer contracts call this before privileged operator actions.
+    pub fn require_operator(env: Env, operator: Address) {
+        Self::require_role(&env, &operator, Role::Operator);
+    }
+
+    /// Post the Merkle root of a role drop (admin only)
+    ///
+    /// Each user listed in the tree can then claim their own role with
+    /// `claim_role` until `expires_at`. The Admin role cannot be dropped.
+    pub fn post_role_drop(env: Env, caller: Address, root: BytesN<32>, expires_at: u64) -> u64 {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+        Self::record_admin_activity(&env);
+
+        let now = env.ledger().timestamp();
+        if expires_at <= now {
+            panic_with_error!(&env, AccessControlError::InvalidExpiry);
+        }
+
+        let drop_id = ids::next_id(&env, &IdScope::new(0, symbol_short!("roledrop")))
+            .unwrap_or_else(|_| panic_with_error!(&env, AccessControlError::IdsExhausted));
+        let role_drop = RoleDrop {
+            root: root.clone(),
+            posted_at: now,
+            expires_at,
+            claimed: 0,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::RoleDrop(drop_id), &role_drop);
+
+        events::publish(
+            &env,
+            ("access_control", "role_drop"),
+            (drop_id, root, expires_at),
+        );
+        drop_id
+    }
+
+    /// Cancel a role drop so no further roles can be claimed from it (admin
+    /// only). Roles already claimed are kept.
+    pub fn cancel_role_drop(env: Env, caller: Address, drop_id: u64) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+        Self::record_admin_activity(&env);
+
+        if !env.storage().persistent().has(&DataKey::RoleDrop(drop_id)) {
+            panic_with_error!(&env, AccessControlError::RoleDropNotFound);
+        }
+        env.storage()
+            .persistent()
+            .remove(&DataKey::RoleDrop(drop_id));
+
+        events::publish(&env, ("access_control", "role_drop_cancelled"), drop_id);
+    }
+
+    /// Claim a role listed in a role drop, proving the `(user, role)` leaf
+    /// is in the drop's tree with the sibling hashes from leaf to root
+    ///
+    /// A role can be claimed once per drop, so revoking it later is final.
+    pub fn claim_role(env: Env, user: Address, drop_id: u64, role: Role, proof: Vec<BytesN<32>>) {
+        Self::require_storage_version(&env);
+        user.require_auth();
+
+        let mut role_drop = Self::get_role_drop(env.clone(), drop_id)
+            .unwrap_or_else(|| panic_with_error!(&env, AccessControlError::RoleDropNotFound));
+        if env.ledger().timestamp() >= role_drop.expires_at {
+            panic_with_error!(&env, AccessControlError::RoleDropExpired);
+        }
+        if role == Role::Admin {
+            panic_with_error!(&env, AccessControlError::InvalidRole);
+        }
+        let claimed = DataKey::RoleClaimed(drop_id, user.clone(), role.clone());
+        if env.storage().persistent().has(&claimed) {
+            panic_with_error!(&env, AccessControlError::AlreadyClaimed);
+        }
+        let leaf = merkle::leaf(&env, (user.clone(), role.clone()));
+        if !merkle::verify(&env, leaf, &proof, &role_drop.root) {
+            panic_with_error!(&env, AccessControlError::InvalidProof);
+        }
+
+        Self::assign_role(&env, &user, role, None);
+        env.storage().persistent().set(&claimed, &true);
+        role_drop.claimed += 1;
+        env.storage()
+            .persistent()
+            .set(&DataKey::RoleDrop(drop_id), &role_drop);
+    }
+
+    /// Sets the contracts notified whenever a built-in role is granted or
+    /// revoked (admin only). Replaces the previous list.
+    ///
+    /// Each subscriber must implement `RoleSubscriber`. A subscriber that
+    /// fails is reported with a `subscriber_failed` event and never blocks
+    /// the role change.
+    pub fn set_role_subscribers(env: Env, caller: Address, subscribers: Vec<Address>) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+        Self::record_admin_activity(&env);
+
+        if subscribers.len() > MAX_ROLE_SUBSCRIBERS {
+            panic_with_error!(&env, AccessControlError::TooManySubscribers);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::RoleSubscribers, &subscribers);
+
+        events::publish(&env, ("access_control", "subscribers_set"), subscribers);
+    }
+
+    /// Report the entry counts and approximate sizes of the contract's main
+    /// storage families, for forecasting rent and TTL costs
+    ///
+    /// Role maps live in instance storage, which is loaded on every call and
+    /// bounded in size, so `role_users` is the family to watch.
+    pub fn get_storage_report(env: Env) -> Vec<StorageFamily> {
+        let admin = Self::get_admin(env.clone());
+        let admin_roles = DataKey::UserRoles(admin.clone());
+        let role_user_size =
+            footprint::entry_size(&env, admin_roles, rbac::get_user_roles(&env, &admin));
+        let role_users = Self::role_user_count(&env);
+
+        let tokens = ids::last_id(&env, &IdScope::new(0, symbol_short!("export"))).unwrap_or(0);
+        let token_size = Self::get_export_token(env.clone(), tokens)
+            .map_or(0, |token| footprint::entry_size(&env, DataKey::ExportToken(tokens), token));
+
+        Vec::from_array(
+            &env,
+            [
+                footprint::family(symbol_short!("role_user"), role_users, role_user_size),
+                footprint::family(symbol_short!("export"), tokens, token_size),
+            ],
+        )
+    }
+
+    /// Returns the holders of `role` among the `limit` index positions
+    /// starting at `offset`.
+    ///
+    /// A `limit` of zero or above the page size limit spans a full page.
+    /// Members whose time-limited grant has expired are skipped, so a page can
+    /// hold fewer than `limit` members. Order is not stable across
+    /// revocations, which move the last member into the freed position.
+    pub fn get_role_members(env: Env, role: Role, offset: u64, limit: u32) -> Vec<Address> {
+        let max_page = limits::get(&env, Limit::PageSize);
+        let limit = if limit == 0 || limit > max_page {
+            max_page
+        } else {
+            limit
+        };
+        let count = Self::role_member_slots(&env, &role);
+        let end = count.min(offset.saturating_add(limit as u64));
+
+        let mut members = Vec::new(&env);
+        for position in offset..end {
+            let key = DataKey::RoleMemberAt(role.clone(), position);
+            if let Some(member) = env.storage().persistent().get::<_, Address>(&key) {
+                if !Self::role_expired(&env, &member, &role) {
+                    members.push_back(member);
+                }
+            }
+        }
+        members
+    }
+
+    /// Returns the number of users holding `role`, not counting expired
+    /// time-limited grants
+    pub fn get_role_member_count(env: Env, role: Role) -> u64 {
+        let expiring = DataKey::RoleExpiringMembers(role.clone());
+        let expired = Self::stored_list::<Address>(&env, &expiring)
+            .iter()
+            .filter(|member| Self::role_expired(&env, member, &role))
+            .count();
+        Self::role_member_slots(&env, &role).saturating_sub(expired as u64)
+    }
+
+    /// Export the raw role table in chunks for seeding a new deployment
+    /// (admin only)
+    ///
+    /// Pass `None` for the first chunk and each returned `next_cursor` after
+    /// that. Users appear in the order they were first granted a role or
+    /// suspended, and a chunk holds up to the page size limit. Custom role
+    /// definitions are exported by `export_custom_roles`.
+    pub fn export_chunk(env: Env, caller: Address, cursor: Option<u64>) -> RoleExportChunk {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        let count = Self::role_user_count(&env);
+        let first = cursor.map_or(1, |cursor| cursor.saturating_add(1));
+        let last = count.min(first.saturating_add(limits::get(&env, Limit::PageSize) as u64) - 1);
+
+        let mut records = Vec::new(&env);
+        for position in first..=last {
+            let Some(user) = env
+                .storage()
+                .persistent()
+                .get::<_, Address>(&DataKey::RoleUserAt(position))
+            else {
+                continue;
+            };
+            records.push_back(Self::role_record(&env, user));
+        }
+
+        RoleExportChunk {
+            records,
+            next_cursor: if last < count { Some(last) } else { None },
+        }
+    }
+
+    /// Export every custom role definition by name (admin only)
+    pub fn export_custom_roles(env: Env, caller: Address) -> Vec<(Symbol, CustomRole)> {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        let mut roles = Vec::new(&env);
+        for name in Self::stored_list::<Symbol>(&env, &DataKey::CustomRoleNames).iter() {
+            if let Some(role) = Self::get_role(env.clone(), name.clone()) {
+                roles.push_back((name, role));
+            }
+        }
+        roles
+    }
+
+    /// Get the contracts notified of role changes
+    pub fn get_role_subscribers(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::RoleSubscribers)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Define a custom role under `name` (admin only)
+    ///
+    /// Lets integrators add org-specific roles without a contract upgrade.
+    /// `metadata` describes the role and is capped at `MAX_ROLE_METADATA_LEN`
+    /// bytes.
+    pub fn create_role(env: Env, caller: Address, name: Symbol, metadata: String) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+        Self::record_admin_activity(&env);
+
+        if env
+            .storage()
+            .persistent()
+            .has(&DataKey::CustomRole(name.clone()))
+        {
+            panic_with_error!(&env, AccessControlError::RoleExists);
+        }
+        if metadata.len() > MAX_ROLE_METADATA_LEN {
+            panic_with_error!(&env, AccessControlError::InvalidRole);
+        }
+
+        let id = ids::next_id(&env, &IdScope::new(0, symbol_short!("role")))
+            .unwrap_or_else(|_| panic_with_error!(&env, AccessControlError::IdsExhausted));
+        let role = CustomRole {
+            id,
+            metadata: metadata.clone(),
+            created_at: env.ledger().timestamp(),
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::CustomRole(name.clone()), &role);
+        Self::record_in_list(&env, &DataKey::CustomRoleNames, name.clone(), true);
+
+        events::publish(&env, ("access_control", "role_created"), (name, metadata));
+    }
+
+    /// Delete a custom role (admin only)
+    ///
+    /// Every grant of the role lapses at once; re-creating a role with the
+    /// same name does not restore them.
+    pub fn delete_role(env: Env, caller: Address, name: Symbol) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+        Self::record_admin_activity(&env);
+
+        Self::load_custom_role(&env, &name);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::CustomRole(name.clone()));
+        Self::record_in_list(&env, &DataKey::CustomRoleNames, name.clone(), false);
+
+        events::publish(&env, ("access_control", "role_deleted"), name);
+    }
+
+    /// Get a custom role's definition, if defined
+    pub fn get_role(env: Env, name: Symbol) -> Option<CustomRole> {
+        env.storage().persistent().get(&DataKey::CustomRole(name))
+    }
+
+    /// Assign a custom role to a user (admin only)
+    pub fn grant_custom_role(env: Env, caller: Address, user: Address, name: Symbol) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+        Self::record_admin_activity(&env);
+
+        let role = Self::load_custom_role(&env, &name);
+        if Self::has_custom_role(env.clone(), user.clone(), name.clone()) {
+            panic_with_error!(&env, AccessControlError::RoleAlreadyAssigned);
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::CustomRoleGrant(user.clone(), name.clone()), &role.id);
+        Self::track_role_user(&env, &user);
+        let custom = DataKey::UserCustomRoles(user.clone());
+        Self::record_in_list(&env, &custom, name.clone(), true);
+
+        events::publish(&env, ("access_control", "custom_role_granted"), (user, name));
+    }
+
+    /// Revoke a custom role from a user (admin only)
+    pub fn revoke_custom_role(env: Env, caller: Address, user: Address, name: Symbol) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+        Self::record_admin_activity(&env);
+
+        if !Self::has_custom_role(env.clone(), user.clone(), name.clone()) {
+            panic_with_error!(&env, AccessControlError::RoleNotAssigned);
+        }
+        env.storage()
+            .persistent()
+            .remove(&DataKey::CustomRoleGrant(user.clone(), name.clone()));
+        let custom = DataKey::UserCustomRoles(user.clone());
+        Self::record_in_list(&env, &custom, name.clone(), false);
+
+        events::publish(&env, ("access_control", "custom_role_revoked"), (user, name));
+    }
+
+    /// Check if a user holds a custom role that is still defined (never
+    /// while the user is suspended)
+    pub fn has_custom_role(env: Env, user: Address, name: Symbol) -> bool {
+        if Self::get_suspension(env.clone(), user.clone()).is_some() {
+            return false;
+        }
+        let granted: Option<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::CustomRoleGrant(user, name.clone()));
+        match (granted, Self::get_role(env, name)) {
+            (Some(granted), Some(role)) => granted == role.id,
+            _ => false,
+        }
+    }
+
+    /// Place a user in read-only mode for `duration` seconds (admin only)
+    ///
+    /// The user's roles are downgraded to Auditor rather than revoked, and
+    /// are restored automatically when the suspension ends. Suspending a user
+    /// who is already suspended replaces the end time, so the suspension can
+    /// be extended. The admin cannot be suspended.
+    pub fn suspend_user(env: Env, caller: Address, user: Address, duration: u64) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+        Self::record_admin_activity(&env);
+
+        if duration == 0 || user == Self::get_admin(env.clone()) {
+            panic_with_error!(&env, AccessControlError::InvalidSuspension);
+        }
+
+        let now = env.ledger().timestamp();
+        let suspension = Suspension {
+            suspended_at: now,
+            until: now.saturating_add(duration),
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Suspension(user.clone()), &suspension);
+        Self::track_role_user(&env, &user);
+
+        events::publish(
+            &env,
+            ("access_control", "user_suspended"),
+            (user.clone(), suspension.until),
+        );
+        for (role, held) in rbac::get_user_roles(&env, &user).iter() {
+            if held && role != Role::Auditor {
+                Self::publish_cache_invalidation(&env, user.clone(), role);
+            }
+        }
+    }
+
+    /// End a user's suspension early, restoring their roles (admin only)
+    pub fn lift_suspension(env: Env, caller: Address, user: Address) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+        Self::record_admin_activity(&env);
+
+        if Self::get_suspension(env.clone(), user.clone()).is_none() {
+            panic_with_error!(&env, AccessControlError::InvalidSuspension);
+        }
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Suspension(user.clone()));
+
+        events::publish(&env, ("access_control", "suspension_lifted"), user);
+    }
+
+    /// Get a user's suspension while it is in effect
+    pub fn get_suspension(env: Env, user: Address) -> Option<Suspension> {
+        env.storage()
+            .persistent()
+            .get::<_, Suspension>(&DataKey::Suspension(user))
+            .filter(|suspension| env.ledger().timestamp() < suspension.until)
+    }
+
+    /// Get a role drop by id
+    pub fn get_role_drop(env: Env, drop_id: u64) -> Option<RoleDrop> {
+        env.storage().persistent().get(&DataKey::RoleDrop(drop_id))
+    }
+
+    /// Get an export token by id
+    pub fn get_export_token(env: Env, token_id: u64) -> Option<ExportToken> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ExportToken(token_id))
+    }
+}
+
+impl AccessControlContract {
+    // Internal helper refusing to run against storage written by a different
+    // layout version
+    fn require_storage_version(env: &Env) {
+        versioning::require_version(env, STORAGE_VERSION)
+            .unwrap_or_else(|_| panic_with_error!(env, AccessControlError::StorageVersionMismatch));
+    }
+
+    // Internal helper loading a custom role that must be defined
+    fn load_custom_role(env: &Env, name: &Symbol) -> CustomRole {
+        Self::get_role(env.clone(), name.clone())
+            .unwrap_or_else(|| panic_with_error!(env, AccessControlError::RoleNotFound))
+    }
+
+    // Internal helper granting a role, until `expiry` when given, clearing
+    // out an expired grant of the same role first
+    fn assign_role(env: &Env, user: &Address, role: Role, expiry: Option<u32>) {
+        if Self::role_expired(env, user, &role) {
+            Self::remove_role(env, user, role.clone(), true);
+        }
+
+        Self::track_role_user(env, user);
+        rbac::grant_role(env, user, role.clone()).unwrap_or_else(|e| Self::raise(env, e));
+        Self::index_role_member(env, user, role.clone());
+        let key = DataKey::RoleExpiry(user.clone(), role.clone());
+        match expiry {
+            Some(expiry_ledger) => env.storage().persistent().set(&key, &expiry_ledger),
+            None => env.storage().persistent().remove(&key),
+        }
+        let expiring = DataKey::RoleExpiringMembers(role.clone());
+        Self::record_in_list(env, &expiring, user.clone(), expiry.is_some());
+
+        // Update counter
+        let count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalRoleAssignments)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalRoleAssignments, &(count + 1));
+
+        Self::notify_subscribers(env, user, role, None, true);
+    }
+
+    // Internal helper revoking a role, reporting it as expired rather than
+    // revoked when `expired` is set
+    fn remove_role(env: &Env, user: &Address, role: Role, expired: bool) {
+        rbac::revoke_role(env, user, role.clone()).unwrap_or_else(|e| Self::raise(env, e));
+        Self::unindex_role_member(env, user, role.clone());
+        let expiring = DataKey::RoleExpiringMembers(role.clone());
+        Self::record_in_list(env, &expiring, user.clone(), false);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::RoleExpiry(user.clone(), role.clone()));
+
+        // Update counter
+        let count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalRoleAssignments)
+            .unwrap_or(0);
+        if count > 0 {
+            env.storage()
+                .instance()
+                .set(&DataKey::TotalRoleAssignments, &(count - 1));
+        }
+
+        if expired {
+            events::publish(env, ("access_control", "role_expired"), (user.clone(), role.clone()));
+        }
+        Self::publish_cache_invalidation(env, user.clone(), role.clone());
+        Self::notify_subscribers(env, user, role, None, false);
+    }
+
+    // Internal helper checking whether a time-limited grant of `role` has
+    // reached its expiry ledger
+    fn role_expired(env: &Env, user: &Address, role: &Role) -> bool {
+        Self::get_role_expiry(env.clone(), user.clone(), role.clone())
+            .is_some_and(|expiry_ledger| env.ledger().sequence() >= expiry_ledger)
+    }
+
+    // Internal helper checking a role on a scope, ignoring suspension
+    fn holds_on_scope(env: &Env, user: &Address, role: Role, scope: &Scope) -> bool {
+        let scoped = RbacKey::ScopedRole(user.clone(), role.clone(), scope.clone());
+        let global = Self::get_user_roles(env.clone(), user.clone()).get(role);
+        global.unwrap_or(false) || env.storage().persistent().has(&scoped)
+    }
+
+    // Internal helper counting and indexing `user` as a role user the first
+    // time any role record is stored for them
+    fn track_role_user(env: &Env, user: &Address) {
+        let position_key = DataKey::RoleUserPosition(user.clone());
+        if env.storage().persistent().has(&position_key) {
+            return;
+        }
+        let count = Self::role_user_count(env) + 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::RoleUserCount, &count);
+        env.storage()
+            .persistent()
+            .set(&DataKey::RoleUserAt(count), user);
+        env.storage().persistent().set(&position_key, &count);
+    }
+
+    // Internal helper rebuilding the role user index and the per-user grant
+    // lists on migration to version 2, from the users version 1 indexed and
+    // the grants the admin lists
+    fn backfill_role_table(env: &Env, backfill: &RoleBackfill) {
+        // Version 1 indexed role maps created after it without positions
+        let mut users = Vec::new(env);
+        for position in 1..=Self::role_user_count(env) {
+            let key = DataKey::RoleUserAt(position);
+            if let Some(user) = env.storage().persistent().get::<_, Address>(&key) {
+                users.push_back(user);
+                env.storage().persistent().remove(&key);
+            }
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::RoleUserCount, &0u64);
+        users.append(&backfill.users);
+        for user in users.iter() {
+            if env.storage().instance().has(&DataKey::UserRoles(user.clone())) {
+                Self::track_role_user(env, &user);
+            }
+        }
+
+        for (user, role, scope) in backfill.scoped.iter() {
+            if rbac::has_scope_grant(env, &user, role.clone(), &scope) {
+                Self::track_role_user(env, &user);
+                let scoped = DataKey::UserScopedRoles(user.clone());
+                Self::record_in_list(env, &scoped, (role, scope), true);
+            }
+        }
+        for name in backfill.custom_roles.iter() {
+            if env.storage().persistent().has(&DataKey::CustomRole(name.clone())) {
+                Self::record_in_list(env, &DataKey::CustomRoleNames, name, true);
+            }
+        }
+        for (user, name) in backfill.custom_grants.iter() {
+            let grant = DataKey::CustomRoleGrant(user.clone(), name.clone());
+            if env.storage().persistent().has(&grant) {
+                Self::track_role_user(env, &user);
+                let custom = DataKey::UserCustomRoles(user.clone());
+                Self::record_in_list(env, &custom, name, true);
+            }
+        }
+    }
+
+    // Internal helper assembling one user's raw role record for export
+    fn role_record(env: &Env, user: Address) -> RoleRecord {
+        let roles = rbac::get_user_roles(env, &user);
+        let mut expiries = Vec::new(env);
+        for role in roles.keys().iter() {
+            let key = DataKey::RoleExpiry(user.clone(), role.clone());
+            if let Some(expiry) = env.storage().persistent().get::<_, u32>(&key) {
+                expiries.push_back((role, expiry));
+            }
+        }
+
+        let mut custom = Vec::new(env);
+        let names = Self::stored_list::<Symbol>(env, &DataKey::UserCustomRoles(user.clone()));
+        for name in names.iter() {
+            let grant = DataKey::CustomRoleGrant(user.clone(), name.clone());
+            if let Some(role_id) = env.storage().persistent().get::<_, u64>(&grant) {
+                custom.push_back((name, role_id));
+            }
+        }
+
+        let suspension: Option<Suspension> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Suspension(user.clone()));
+        RoleRecord {
+            scoped: Self::stored_list(env, &DataKey::UserScopedRoles(user.clone())),
+            suspended_at: suspension.as_ref().map_or(0, |s| s.suspended_at),
+            suspended_until: suspension.map_or(0, |s| s.until),
+            user,
+            roles,
+            expiries,
+            custom,
+        }
+    }
+
+    // Internal helper loading a list kept in persistent storage
+    fn stored_list<T>(env: &Env, key: &DataKey) -> Vec<T>
+    where
+        T: IntoVal<Env, Val> + TryFromVal<Env, Val>,
+    {
+        env.storage()
+            .persistent()
+            .get(key)
+            .unwrap_or(Vec::new(env))
+    }
+
+    // Internal helper adding `item` to (or removing it from) a list kept in
+    // persistent storage, dropping the list once empty
+    fn record_in_list<T>(env: &Env, key: &DataKey, item: T, present: bool)
+    where
+        T: IntoVal<Env, Val> + TryFromVal<Env, Val> + Clone,
+    {
+        let mut items = Self::stored_list::<T>(env, key);
+        match (items.first_index_of(&item), present) {
+            (None, true) => items.push_back(item),
+            (Some(index), false) => {
+                items.remove(index);
+            }
+            _ => return,
+        }
+        if items.is_empty() {
+            env.storage().persistent().remove(key);
+        } else {
+            env.storage().persistent().set(key, &items);
+        }
+    }
+
+    // Internal helper loading the number of positions in a role's member
+    // index, expired grants included
+    fn role_member_slots(env: &Env, role: &Role) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::RoleMemberCount(role.clone()))
+            .unwrap_or(0)
+    }
+
+    // Internal helper indexing the holders of every role on migration to
+    // version 3, from the role user index
+    fn backfill_role_members(env: &Env) {
+        for position in 1..=Self::role_user_count(env) {
+            let key = DataKey::RoleUserAt(position);
+            let Some(user) = env.storage().persistent().get::<_, Address>(&key) else {
+                continue;
+            };
+            for (role, held) in rbac::get_user_roles(env, &user).iter() {
+                if !held {
+                    continue;
+                }
+                Self::index_role_member(env, &user, role.clone());
+                let expiry = DataKey::RoleExpiry(user.clone(), role.clone());
+                if env.storage().persistent().has(&expiry) {
+                    let expiring = DataKey::RoleExpiringMembers(role);
+                    Self::record_in_list(env, &expiring, user.clone(), true);
+                }
+            }
+        }
+    }
+
+    // Internal helper adding `user` to the member index of `role` unless
+    // already listed
+    fn index_role_member(env: &Env, user: &Address, role: Role) {
+        let index_key = DataKey::RoleMemberIndex(user.clone(), role.clone());
+        if env.storage().persistent().has(&index_key) {
+            return;
+        }
+        let count = Self::role_member_slots(env, &role);
+        env.storage()
+            .persistent()
+            .set(&DataKey::RoleMemberAt(role.clone(), count), user);
+        env.storage().persistent().set(&index_key, &count);
+        env.storage()
+            .persistent()
+            .set(&DataKey::RoleMemberCount(role), &(count + 1));
+    }
+
+    // Internal helper removing `user` from the member index of `role`,
+    // moving the last member into the freed position
+    fn unindex_role_member(env: &Env, user: &Address, role: Role) {
+        let index_key = DataKey::RoleMemberIndex(user.clone(), role.clone());
+        let Some(position) = env.storage().persistent().get::<_, u64>(&index_key) else {
+            return;
+        };
+        let last = Self::role_member_slots(env, &role) - 1;
+        let last_key = DataKey::RoleMemberAt(role.clone(), last);
+        if position != last {
+            let moved: Address = env.storage().persistent().get(&last_key).unwrap();
+            env.storage()
+                .persistent()
+                .set(&DataKey::RoleMemberAt(role.clone(), position), &moved);
+            env.storage()
+                .persistent()
+                .set(&DataKey::RoleMemberIndex(moved, role.clone()), &position);
+        }
+        env.storage().persistent().remove(&last_key);
+        env.storage().persistent().remove(&index_key);
+        env.storage()
+            .persistent()
+            .set(&DataKey::RoleMemberCount(role), &last);
+    }
+
+    // Internal helper loading the number of users with a role map
+    fn role_user_count(env: &Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::RoleUserCount)
+            .unwrap_or(0)
+    }
+
+    // Internal helper recording the current ledger as the last admin action
+    fn record_admin_activity(env: &Env) {
+        env.storage()
+            .instance()
+            .set(&DataKey::AdminLastActive, &env.ledger().sequence());
+    }
+
+    // Internal helper raising the contract error for an RBAC failure
+    fn raise(env: &Env, error: RbacError) -> ! {
+        let error = match error {
+            RbacError::Unauthorized => AccessControlError::Unauthorized,
+            RbacError::RoleAlreadyAssigned => AccessControlError::RoleAlreadyAssigned,
+            RbacError::RoleNotAssigned => AccessControlError::RoleNotAssigned,
+        };
+        panic_with_error!(env, error)
+    }
+
+    // Internal helper telling consumers that cache role checks to drop any
+    // cached grant of `role` to `user`
+    fn publish_cache_invalidation(env: &Env, user: Address, role: Role) {
+        events::publish(env, ("access_control", "cache_invalidated"), (user, role));
+    }
+
+    // Internal helper telling each subscriber about a role change. A failing
+    // subscriber is reported but never blocks the change, so no subscriber
+    // can stop a revocation.
+    fn notify_subscribers(
+        env: &Env,
+        user: &Address,
+        role: Role,
+        scope: Option<Scope>,
+        granted: bool,
+    ) {
+        let contract = env.current_contract_address();
+        for subscriber in Self::get_role_subscribers(env.clone()).iter() {
+            let delivered = RoleSubscriberClient::new(env, &subscriber)
+                .try_on_role_changed(&contract, user, &role, &scope, &granted)
+                .is_ok_and(|inner| inner.is_ok());
+            if !delivered {
+                events::publish(
+                    env,
+                    ("access_control", "subscriber_failed"),
+                    (subscriber, user.clone(), role.clone()),
+                );
+            }
+        }
+    }
+
+    // Internal helper capping batch reads at the batch size limit
+    fn require_batch_size(env: &Env, len: u32) {
+        if len > limits::get(env, Limit::BatchSize) {
+            panic_with_error!(env, AccessControlError::BatchTooLarge);
+        }
+    }
+
     /// Require that the caller has admin role
     pub fn require_admin(env: &Env, caller: &Address) {
         let admin: Address = env
@@ -259,34 +1603,47 @@ impl AccessControlContract {
     }
 
     /// Require that the caller has a specific role
+    ///
+    /// The Operator role only counts inside the caller's operating hours.
     pub fn require_role(env: &Env, caller: &Address, role: Role) {
-        let roles: Map<Role, bool> = env
-            .storage()
-            .instance()
-            .get(&DataKey::UserRoles(caller.clone()))
-            .unwrap_or(Map::new(env));
-
-        if !roles.get(role).unwrap_or(false) {
-            panic_with_error!(env, AccessControlError::Unauthorized);
+        if !Self::has_role(env.clone(), caller.clone(), role.clone()) {
+            Self::raise(env, RbacError::Unauthorized);
+        }
+        if role == Role::Operator {
+            Self::require_operating_hours(env, caller);
         }
     }
 
     /// Require that the caller has admin OR a specific role
+    ///
+    /// Admins are not bound by operating hours.
     pub fn require_admin_or_role(env: &Env, caller: &Address, role: Role) {
-        let roles: Map<Role, bool> = env
-            .storage()
-            .instance()
-            .get(&DataKey::UserRoles(caller.clone()))
-            .unwrap_or(Map::new(env));
+        let is_admin = Self::has_role(env.clone(), caller.clone(), Role::Admin);
+        if !is_admin && !Self::has_role(env.clone(), caller.clone(), role.clone()) {
+            Self::raise(env, RbacError::Unauthorized);
+        }
+        if role == Role::Operator && !is_admin {
+            Self::require_operating_hours(env, caller);
+        }
+    }
 
-        let is_admin = roles.get(Role::Admin).unwrap_or(false);
-        let has_role = roles.get(role).unwrap_or(false);
+    // Internal helper failing if `operator` is outside their operating hours
+    fn require_operating_hours(env: &Env, operator: &Address) {
+        if !Self::is_within_operating_hours(env.clone(), operator.clone()) {
+            panic_with_error!(env, AccessControlError::OutsideOperatingHours);
+        }
+    }
 
-        if !is_admin && !has_role {
-            panic_with_error!(env, AccessControlError::Unauthorized);
+    /// Require that a feature flag is enabled before entering a gated code path
+    pub fn require_flag(env: &Env, flag: Symbol) {
+        if !Self::is_flag_enabled(env.clone(), flag) {
+            panic_with_error!(env, AccessControlError::FeatureDisabled);
         }
     }
 }
 
 #[cfg(test)]
 mod test;
+
+#[cfg(test)]
+mod attack_test;
@@ -1,11 +1,16 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env};
+use soroban_sdk::{
+    testutils::{Address as _, Events as _, Ledger},
+    vec,
+    xdr::ToXdr,
+    Address, Bytes, BytesN, Env, IntoVal, String, Symbol,
+};
 
 fn create_contract() -> (Env, Address, Address) {
     let env = Env::default();
-    let contract_id = env.register_contract(None, AccessControlContract);
+    let contract_id = env.register(AccessControlContract, ());
     let client = AccessControlContractClient::new(&env, &contract_id);
     
     let admin = Address::generate(&env);
@@ -187,7 +192,7 @@ fn test_get_user_roles() {
     
     assert_eq!(roles.get(Role::User), Some(true));
     assert_eq!(roles.get(Role::Operator), Some(true));
-    assert_eq!(roles.get(Role::Admin), Some(false));
+    assert_eq!(roles.get(Role::Admin), None);
 }
 
 #[test]
@@ -254,7 +259,9 @@ fn test_role_events_emitted() {
     let events = env.events().all();
     let event = events.last().unwrap();
     
-    assert!(event.topics.contains(&("access_control", "role_granted")));
+    let topics: soroban_sdk::Vec<soroban_sdk::Val> =
+        (String::from_str(&env, "access_control"), String::from_str(&env, "role_granted")).into_val(&env);
+    assert_eq!(event.1, topics);
 }
 
 #[test]
@@ -325,3 +332,898 @@ fn test_complex_role_management() {
     assert!(client.has_role(&user2, &Role::Auditor));
     assert!(!client.has_role(&user3, &Role::User));
 }
+
+#[test]
+fn test_feature_flags() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    
+    let flag = Symbol::new(&env, "auto_approve");
+    
+    env.mock_all_auths();
+    
+    // Flags default to disabled
+    assert!(!client.is_flag_enabled(&flag));
+    
+    client.set_flag(&admin, &flag, &true);
+    assert!(client.is_flag_enabled(&flag));
+    assert_eq!(client.get_flags().len(), 1);
+    
+    client.set_flag(&admin, &flag, &false);
+    assert!(!client.is_flag_enabled(&flag));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_set_flag_requires_admin_role() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    
+    let operator = Address::generate(&env);
+    
+    env.mock_all_auths();
+    client.grant_role(&admin, &operator, &Role::Operator);
+    
+    // Operators cannot toggle flags
+    client.set_flag(&operator, &Symbol::new(&env, "streaming"), &true);
+}
+
+#[test]
+fn test_require_flag() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    
+    let flag = Symbol::new(&env, "streaming");
+    
+    env.mock_all_auths();
+    client.set_flag(&admin, &flag, &true);
+    
+    // Gated code paths pass once the flag is on
+    env.as_contract(&contract_id, || {
+        AccessControlContract::require_flag(&env, flag.clone());
+    });
+}
+
+#[test]
+fn test_has_role_batch() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    
+    let operator = Address::generate(&env);
+    let user = Address::generate(&env);
+    
+    env.mock_all_auths();
+    client.grant_role(&admin, &operator, &Role::Operator);
+    
+    let users = soroban_sdk::vec![&env, admin.clone(), operator.clone(), user.clone()];
+    let results = client.has_role_batch(&users, &Role::Operator);
+    assert_eq!(results, soroban_sdk::vec![&env, false, true, false]);
+    
+    // An empty batch returns no results
+    assert_eq!(client.has_role_batch(&Vec::new(&env), &Role::Admin).len(), 0);
+}
+
+#[test]
+fn test_get_user_roles_batch() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    
+    let auditor = Address::generate(&env);
+    let unknown = Address::generate(&env);
+    
+    env.mock_all_auths();
+    client.grant_role(&admin, &auditor, &Role::Auditor);
+    
+    let users = soroban_sdk::vec![&env, admin.clone(), auditor.clone(), unknown.clone()];
+    let results = client.get_user_roles_batch(&users);
+    
+    assert_eq!(results.len(), 3);
+    assert_eq!(results.get(0).unwrap().get(Role::Admin), Some(true));
+    assert_eq!(results.get(1).unwrap().get(Role::Auditor), Some(true));
+    assert_eq!(results.get(1).unwrap().get(Role::Admin), None);
+    assert_eq!(results.get(2).unwrap().len(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")]
+fn test_role_batch_too_large() {
+    let (env, contract_id, _) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    
+    let mut users = Vec::new(&env);
+    for _ in 0..=Limit::BatchSize.default_value() {
+        users.push_back(Address::generate(&env));
+    }
+    client.has_role_batch(&users, &Role::User);
+}
+
+#[test]
+fn test_admin_actions_update_heartbeat() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    
+    assert_eq!(client.get_admin_last_active(), env.ledger().sequence());
+    
+    env.ledger().with_mut(|li| li.sequence_number += 500);
+    env.mock_all_auths();
+    client.grant_role(&admin, &Address::generate(&env), &Role::User);
+    
+    assert_eq!(client.get_admin_last_active(), env.ledger().sequence());
+}
+
+#[test]
+fn test_stale_admin_alarm() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    
+    // Disabled until a period is configured
+    env.ledger().with_mut(|li| li.sequence_number += 2_000);
+    assert!(!client.check_admin_activity());
+    
+    env.mock_all_auths();
+    client.set_stale_admin_period(&admin, &1_000);
+    let last_active = client.get_admin_last_active();
+    
+    env.ledger().with_mut(|li| li.sequence_number += 1_000);
+    assert!(!client.check_admin_activity());
+    
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+    assert!(client.check_admin_activity());
+    
+    let events = env.events().all();
+    let alarm = events.last().unwrap();
+    assert_eq!(alarm.0, contract_id);
+    assert_eq!(alarm.1, ("access_control", "stale_admin").into_val(&env));
+    let (_sequence, data): (u64, (Address, u32, u32)) = alarm.2.into_val(&env);
+    assert_eq!(data, (admin, last_active, 1_000));
+}
+
+#[test]
+fn test_interface_client_matches_contract() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlClient::new(&env, &contract_id);
+    let user = Address::generate(&env);
+    
+    env.mock_all_auths();
+    client.grant_role(&admin, &user, &Role::Operator);
+    assert!(client.has_role(&user, &Role::Operator));
+    assert_eq!(client.get_admin(), admin);
+    assert_eq!(client.get_storage_version(), STORAGE_VERSION);
+    
+    client.revoke_role(&admin, &user, &Role::Operator);
+    assert!(!client.has_role(&user, &Role::Operator));
+}
+
+#[test]
+fn test_revoke_emits_cache_invalidation() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    
+    let user = Address::generate(&env);
+    
+    env.mock_all_auths();
+    client.grant_role(&admin, &user, &Role::Operator);
+    client.revoke_role(&admin, &user, &Role::Operator);
+    
+    let events = env.events().all();
+    let event = events.last().unwrap();
+    assert_eq!(event.1, ("access_control", "cache_invalidated").into_val(&env));
+    let (_sequence, data): (u64, (Address, Role)) = event.2.into_val(&env);
+    assert_eq!(data, (user, Role::Operator));
+}
+
+#[test]
+fn test_scoped_roles() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    
+    let owner = Address::generate(&env);
+    let scope = Scope { contract: Address::generate(&env), id: 7 };
+    
+    env.mock_all_auths();
+    client.grant_scoped_role(&admin, &owner, &Role::Operator, &scope);
+    assert!(client.has_scoped_role(&owner, &Role::Operator, &scope));
+    assert!(!client.has_role(&owner, &Role::Operator));
+    
+    // Global holders pass every scope
+    assert!(!client.has_scoped_role(&admin, &Role::Operator, &scope));
+    client.grant_role(&admin, &admin, &Role::Operator);
+    assert!(client.has_scoped_role(&admin, &Role::Operator, &scope));
+    
+    client.revoke_scoped_role(&admin, &owner, &Role::Operator, &scope);
+    assert!(!client.has_scoped_role(&owner, &Role::Operator, &scope));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_non_admin_cannot_grant_scoped_role() {
+    let (env, contract_id, _) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    
+    let attacker = Address::generate(&env);
+    let scope = Scope { contract: Address::generate(&env), id: 7 };
+    
+    env.mock_all_auths();
+    client.grant_scoped_role(&attacker, &attacker, &Role::Operator, &scope);
+}
+
+#[test]
+fn test_export_token_lifecycle() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let auditor = Address::generate(&env);
+    let payroll = Symbol::new(&env, "payroll");
+    env.mock_all_auths();
+    client.grant_role(&admin, &auditor, &Role::Auditor);
+
+    let token_id = client.request_export_token(&auditor, &payroll, &4_600);
+    assert!(client.verify_export_token(&token_id, &auditor, &payroll));
+
+    // Tokens are bound to their holder and scope
+    let stranger = Address::generate(&env);
+    assert!(!client.verify_export_token(&token_id, &stranger, &payroll));
+    assert!(!client.verify_export_token(&token_id, &auditor, &Symbol::new(&env, "budgets")));
+
+    // Losing the Auditor role invalidates outstanding tokens
+    client.revoke_role(&admin, &auditor, &Role::Auditor);
+    assert!(!client.verify_export_token(&token_id, &auditor, &payroll));
+    client.grant_role(&admin, &auditor, &Role::Auditor);
+
+    env.ledger().with_mut(|li| li.timestamp = 4_600);
+    assert!(!client.verify_export_token(&token_id, &auditor, &payroll));
+}
+
+#[test]
+fn test_export_token_revocation() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+
+    let auditor = Address::generate(&env);
+    let payroll = Symbol::new(&env, "payroll");
+    env.mock_all_auths();
+    client.grant_role(&admin, &auditor, &Role::Auditor);
+
+    let first = client.request_export_token(&auditor, &payroll, &3_600);
+    let second = client.request_export_token(&auditor, &payroll, &3_600);
+    assert_ne!(first, second);
+
+    client.revoke_export_token(&auditor, &first);
+    client.revoke_export_token(&admin, &second);
+    assert!(client.get_export_token(&first).unwrap().revoked);
+    assert!(!client.verify_export_token(&second, &auditor, &payroll));
+
+    let result = client.try_revoke_export_token(&Address::generate(&env), &first);
+    assert_eq!(result, Err(Ok(AccessControlError::Unauthorized.into())));
+}
+
+#[test]
+fn test_export_token_requires_auditor_and_bounded_expiry() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let payroll = Symbol::new(&env, "payroll");
+    env.mock_all_auths();
+
+    let result = client.try_request_export_token(&user, &payroll, &3_600);
+    assert_eq!(result, Err(Ok(AccessControlError::Unauthorized.into())));
+
+    client.grant_role(&admin, &user, &Role::Auditor);
+    let too_long = MAX_EXPORT_TOKEN_SECS + 1;
+    let result = client.try_request_export_token(&user, &payroll, &too_long);
+    assert_eq!(result, Err(Ok(AccessControlError::InvalidExpiry.into())));
+}
+
+#[test]
+fn test_operating_hours_gate_operator() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    let operator = Address::generate(&env);
+    client.grant_role(&admin, &operator, &Role::Operator);
+
+    // 09:00-17:00 for everyone, 22:00-06:00 for the night-shift operator
+    let day = OperatingHours { start: 9 * 3_600, end: 17 * 3_600 };
+    let night = OperatingHours { start: 22 * 3_600, end: 6 * 3_600 };
+    client.set_operating_hours(&admin, &None, &Some(day.clone()));
+
+    env.ledger().with_mut(|li| li.timestamp = 10 * 86_400 + 12 * 3_600);
+    client.require_operator(&operator);
+    env.ledger().with_mut(|li| li.timestamp = 10 * 86_400 + 23 * 3_600);
+    assert_eq!(
+        client.try_require_operator(&operator),
+        Err(Ok(AccessControlError::OutsideOperatingHours.into()))
+    );
+
+    client.set_operating_hours(&admin, &Some(operator.clone()), &Some(night.clone()));
+    assert_eq!(client.get_operating_hours(&operator), Some(night));
+    assert!(client.is_within_operating_hours(&operator));
+    client.require_operator(&operator);
+
+    client.set_operating_hours(&admin, &Some(operator.clone()), &None);
+    client.set_operating_hours(&admin, &None, &None);
+    assert_eq!(client.get_operating_hours(&operator), None);
+    client.require_operator(&operator);
+}
+
+#[test]
+fn test_operating_hours_validation() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+
+    let empty = OperatingHours { start: 3_600, end: 3_600 };
+    let result = client.try_set_operating_hours(&admin, &None, &Some(empty));
+    assert_eq!(result, Err(Ok(AccessControlError::InvalidOperatingHours.into())));
+    let out_of_range = OperatingHours { start: 0, end: SECONDS_PER_DAY };
+    let result = client.try_set_operating_hours(&admin, &None, &Some(out_of_range));
+    assert_eq!(result, Err(Ok(AccessControlError::InvalidOperatingHours.into())));
+
+    // Operators without the role fail as unauthorized, whatever the hour
+    let result = client.try_require_operator(&Address::generate(&env));
+    assert_eq!(result, Err(Ok(AccessControlError::Unauthorized.into())));
+}
+
+fn drop_leaf(env: &Env, user: &Address, role: Role) -> BytesN<32> {
+    env.crypto().sha256(&(user.clone(), role).to_xdr(env)).into()
+}
+
+fn drop_parent(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    let mut pair = Bytes::from(left.clone());
+    pair.append(&Bytes::from(right.clone()));
+    env.crypto().sha256(&pair).into()
+}
+
+#[test]
+fn test_claim_role_from_drop() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    let operator = Address::generate(&env);
+    let auditor = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let operator_leaf = drop_leaf(&env, &operator, Role::Operator);
+    let auditor_leaf = drop_leaf(&env, &auditor, Role::Auditor);
+    let user_leaf = drop_leaf(&env, &user, Role::User);
+    let branch = drop_parent(&env, &operator_leaf, &auditor_leaf);
+    let root = drop_parent(&env, &branch, &user_leaf);
+    let drop_id = client.post_role_drop(&admin, &root, &1_000);
+
+    let proof = vec![&env, auditor_leaf.clone(), user_leaf];
+    client.claim_role(&operator, &drop_id, &Role::Operator, &proof);
+    client.claim_role(&user, &drop_id, &Role::User, &vec![&env, branch]);
+    assert!(client.has_role(&operator, &Role::Operator));
+    assert!(client.has_role(&user, &Role::User));
+    assert_eq!(client.get_role_drop(&drop_id).unwrap().claimed, 2);
+    assert_eq!(client.get_total_role_assignments(), 3);
+
+    // A leaf cannot be claimed for a different role, or twice
+    let proof = vec![&env, operator_leaf];
+    let result = client.try_claim_role(&auditor, &drop_id, &Role::Operator, &proof);
+    assert_eq!(result, Err(Ok(AccessControlError::InvalidProof.into())));
+    client.revoke_role(&admin, &operator, &Role::Operator);
+    let proof = vec![&env, auditor_leaf, drop_leaf(&env, &user, Role::User)];
+    let result = client.try_claim_role(&operator, &drop_id, &Role::Operator, &proof);
+    assert_eq!(result, Err(Ok(AccessControlError::AlreadyClaimed.into())));
+}
+
+#[test]
+fn test_role_drop_expiry_and_cancellation() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    let user = Address::generate(&env);
+    let root = drop_leaf(&env, &user, Role::User);
+
+    let drop_id = client.post_role_drop(&admin, &root, &1_000);
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let result = client.try_claim_role(&user, &drop_id, &Role::User, &vec![&env]);
+    assert_eq!(result, Err(Ok(AccessControlError::RoleDropExpired.into())));
+
+    let drop_id = client.post_role_drop(&admin, &root, &2_000);
+    client.cancel_role_drop(&admin, &drop_id);
+    let result = client.try_claim_role(&user, &drop_id, &Role::User, &vec![&env]);
+    assert_eq!(result, Err(Ok(AccessControlError::RoleDropNotFound.into())));
+
+    // The Admin role cannot be handed out through a drop
+    let admin_root = drop_leaf(&env, &user, Role::Admin);
+    let drop_id = client.post_role_drop(&admin, &admin_root, &2_000);
+    let result = client.try_claim_role(&user, &drop_id, &Role::Admin, &vec![&env]);
+    assert_eq!(result, Err(Ok(AccessControlError::InvalidRole.into())));
+}
+
+#[test]
+fn test_custom_role_lifecycle() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    let user = Address::generate(&env);
+    let approver = Symbol::new(&env, "approver");
+    let metadata = String::from_str(&env, "Approves vendor payments");
+
+    let result = client.try_grant_custom_role(&admin, &user, &approver);
+    assert_eq!(result, Err(Ok(AccessControlError::RoleNotFound.into())));
+
+    client.create_role(&admin, &approver, &metadata);
+    assert_eq!(client.get_role(&approver).unwrap().metadata, metadata);
+    let result = client.try_create_role(&admin, &approver, &metadata);
+    assert_eq!(result, Err(Ok(AccessControlError::RoleExists.into())));
+
+    client.grant_custom_role(&admin, &user, &approver);
+    assert!(client.has_custom_role(&user, &approver));
+    assert!(AccessControlClient::new(&env, &contract_id).has_custom_role(&user, &approver));
+    let result = client.try_grant_custom_role(&admin, &user, &approver);
+    assert_eq!(result, Err(Ok(AccessControlError::RoleAlreadyAssigned.into())));
+
+    client.revoke_custom_role(&admin, &user, &approver);
+    assert!(!client.has_custom_role(&user, &approver));
+    let result = client.try_revoke_custom_role(&admin, &user, &approver);
+    assert_eq!(result, Err(Ok(AccessControlError::RoleNotAssigned.into())));
+}
+
+#[test]
+fn test_deleting_custom_role_lapses_grants() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    let user = Address::generate(&env);
+    let approver = Symbol::new(&env, "approver");
+    let metadata = String::from_str(&env, "Approves vendor payments");
+
+    client.create_role(&admin, &approver, &metadata);
+    client.grant_custom_role(&admin, &user, &approver);
+    client.delete_role(&admin, &approver);
+    assert!(!client.has_custom_role(&user, &approver));
+    assert_eq!(client.get_role(&approver), None);
+
+    // A role re-created under the same name starts with no holders
+    client.create_role(&admin, &approver, &metadata);
+    assert!(!client.has_custom_role(&user, &approver));
+    client.grant_custom_role(&admin, &user, &approver);
+    assert!(client.has_custom_role(&user, &approver));
+
+    let attacker = Address::generate(&env);
+    let result = client.try_delete_role(&attacker, &approver);
+    assert_eq!(result, Err(Ok(AccessControlError::Unauthorized.into())));
+}
+
+#[test]
+fn test_suspension_downgrades_roles_until_it_ends() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let operator = Address::generate(&env);
+    let approver = Symbol::new(&env, "approver");
+    client.grant_role(&admin, &operator, &Role::Operator);
+    client.create_role(&admin, &approver, &String::from_str(&env, "Approves payments"));
+    client.grant_custom_role(&admin, &operator, &approver);
+
+    client.suspend_user(&admin, &operator, &3_600);
+    assert_eq!(client.get_suspension(&operator).unwrap().until, 4_600);
+    assert!(!client.has_role(&operator, &Role::Operator));
+    assert!(client.has_role(&operator, &Role::Auditor));
+    assert!(!client.has_custom_role(&operator, &approver));
+    assert_eq!(client.get_user_roles(&operator).get(Role::Operator), Some(true));
+    let result = client.try_require_operator(&operator);
+    assert_eq!(result, Err(Ok(AccessControlError::Unauthorized.into())));
+
+    // Extending replaces the end time
+    env.ledger().with_mut(|li| li.timestamp = 4_000);
+    client.suspend_user(&admin, &operator, &3_600);
+    env.ledger().with_mut(|li| li.timestamp = 4_600);
+    assert!(!client.has_role(&operator, &Role::Operator));
+
+    env.ledger().with_mut(|li| li.timestamp = 7_600);
+    assert_eq!(client.get_suspension(&operator), None);
+    assert!(client.has_role(&operator, &Role::Operator));
+    assert!(!client.has_role(&operator, &Role::Auditor));
+    assert!(client.has_custom_role(&operator, &approver));
+}
+
+#[test]
+fn test_lift_suspension_and_validation() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    let user = Address::generate(&env);
+    let scope = Scope { contract: Address::generate(&env), id: 7 };
+    client.grant_scoped_role(&admin, &user, &Role::Operator, &scope);
+
+    client.suspend_user(&admin, &user, &3_600);
+    assert!(!client.has_scoped_role(&user, &Role::Operator, &scope));
+    assert!(client.has_scoped_role(&user, &Role::Auditor, &scope));
+    // A user with no roles gains nothing from a suspension
+    assert!(!client.has_role(&user, &Role::Auditor));
+
+    client.lift_suspension(&admin, &user);
+    assert!(client.has_scoped_role(&user, &Role::Operator, &scope));
+    let result = client.try_lift_suspension(&admin, &user);
+    assert_eq!(result, Err(Ok(AccessControlError::InvalidSuspension.into())));
+
+    let result = client.try_suspend_user(&admin, &user, &0);
+    assert_eq!(result, Err(Ok(AccessControlError::InvalidSuspension.into())));
+    let result = client.try_suspend_user(&admin, &admin, &3_600);
+    assert_eq!(result, Err(Ok(AccessControlError::InvalidSuspension.into())));
+}
+
+/// Subscriber recording the last role change it was told about, or rejecting
+/// every call when deployed broken.
+#[contract]
+struct MockSubscriber;
+
+#[contractimpl]
+impl MockSubscriber {
+    pub fn __constructor(env: Env, broken: bool) {
+        env.storage().instance().set(&symbol_short!("broken"), &broken);
+    }
+
+    pub fn on_role_changed(
+        env: Env,
+        source: Address,
+        user: Address,
+        role: Role,
+        scope: Option<Scope>,
+        granted: bool,
+    ) {
+        source.require_auth();
+        if env.storage().instance().get(&symbol_short!("broken")).unwrap_or(false) {
+            panic!("subscriber unavailable");
+        }
+        let calls: u32 = env.storage().instance().get(&symbol_short!("calls")).unwrap_or(0);
+        env.storage().instance().set(&symbol_short!("calls"), &(calls + 1));
+        env.storage()
+            .instance()
+            .set(&symbol_short!("last"), &(user, role, scope, granted));
+    }
+
+    pub fn calls(env: Env) -> u32 {
+        env.storage().instance().get(&symbol_short!("calls")).unwrap_or(0)
+    }
+
+    pub fn last(env: Env) -> (Address, Role, Option<Scope>, bool) {
+        env.storage().instance().get(&symbol_short!("last")).unwrap()
+    }
+}
+
+#[test]
+fn test_subscribers_notified_of_role_changes() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    let broken = env.register(MockSubscriber, (true,));
+    let subscriber = MockSubscriberClient::new(&env, &env.register(MockSubscriber, (false,)));
+    let subscribers = vec![&env, broken.clone(), subscriber.address.clone()];
+    client.set_role_subscribers(&admin, &subscribers);
+    assert_eq!(client.get_role_subscribers(), subscribers);
+
+    let user = Address::generate(&env);
+    client.grant_role(&admin, &user, &Role::Operator);
+    let failed = env.events().all().iter().any(|event| {
+        event.1 == ("access_control", "subscriber_failed").into_val(&env)
+    });
+    assert!(failed);
+    // Subscribers after the broken one are still notified
+    assert_eq!(subscriber.calls(), 1);
+    assert_eq!(subscriber.last(), (user.clone(), Role::Operator, None, true));
+
+    client.revoke_role(&admin, &user, &Role::Operator);
+    assert_eq!(subscriber.last(), (user.clone(), Role::Operator, None, false));
+
+    let scope = Scope { contract: Address::generate(&env), id: 7 };
+    client.grant_scoped_role(&admin, &user, &Role::Auditor, &scope);
+    assert_eq!(subscriber.last(), (user, Role::Auditor, Some(scope), true));
+    assert_eq!(subscriber.calls(), 3);
+}
+
+#[test]
+fn test_role_subscribers_are_bounded() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+
+    let mut subscribers = Vec::new(&env);
+    for _ in 0..=MAX_ROLE_SUBSCRIBERS {
+        subscribers.push_back(Address::generate(&env));
+    }
+    let result = client.try_set_role_subscribers(&admin, &subscribers);
+    assert_eq!(result, Err(Ok(AccessControlError::TooManySubscribers.into())));
+}
+
+#[test]
+fn test_storage_report_counts_role_users() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    let user = Address::generate(&env);
+
+    client.grant_role(&admin, &user, &Role::Operator);
+    client.grant_role(&admin, &user, &Role::Auditor);
+    client.revoke_role(&admin, &user, &Role::Operator);
+    client.request_export_token(&user, &Symbol::new(&env, "payroll"), &3_600);
+
+    let report = client.get_storage_report();
+    let role_users = report.get(0).unwrap();
+    assert_eq!(role_users.family, symbol_short!("role_user"));
+    assert_eq!(role_users.entries, 2);
+    assert!(role_users.approx_bytes > 0);
+    let tokens = report.get(1).unwrap();
+    assert_eq!(tokens.entries, 1);
+    assert!(tokens.approx_bytes > 0);
+}
+
+#[test]
+fn test_time_limited_role_expires() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    let contractor = Address::generate(&env);
+    let expiry = env.ledger().sequence() + 100;
+
+    client.grant_role_until(&admin, &contractor, &Role::Operator, &expiry);
+    assert_eq!(client.get_role_expiry(&contractor, &Role::Operator), Some(expiry));
+    assert!(client.has_role(&contractor, &Role::Operator));
+    client.require_operator(&contractor);
+
+    env.ledger().with_mut(|li| li.sequence_number = expiry);
+    assert!(!client.has_role(&contractor, &Role::Operator));
+    assert_eq!(client.get_user_roles(&contractor).get(Role::Operator), Some(false));
+    let result = client.try_require_operator(&contractor);
+    assert_eq!(result, Err(Ok(AccessControlError::Unauthorized.into())));
+
+    assert_eq!(client.purge_expired(&contractor), vec![&env, Role::Operator]);
+    let expired = env.events().all().iter().any(|event| {
+        event.1 == ("access_control", "role_expired").into_val(&env)
+    });
+    assert!(expired);
+    assert_eq!(client.get_role_expiry(&contractor, &Role::Operator), None);
+    assert_eq!(client.purge_expired(&contractor).len(), 0);
+    assert_eq!(client.get_total_role_assignments(), 1);
+}
+
+#[test]
+fn test_time_limited_role_regrant_and_validation() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    let contractor = Address::generate(&env);
+    let now = env.ledger().sequence();
+
+    let result = client.try_grant_role_until(&admin, &contractor, &Role::Operator, &now);
+    assert_eq!(result, Err(Ok(AccessControlError::InvalidExpiry.into())));
+    let result = client.try_grant_role_until(&admin, &contractor, &Role::Admin, &(now + 10));
+    assert_eq!(result, Err(Ok(AccessControlError::InvalidRole.into())));
+
+    // An expired grant can be renewed without purging it first
+    client.grant_role_until(&admin, &contractor, &Role::Operator, &(now + 10));
+    env.ledger().with_mut(|li| li.sequence_number = now + 10);
+    client.grant_role(&admin, &contractor, &Role::Operator);
+    assert!(client.has_role(&contractor, &Role::Operator));
+    assert_eq!(client.get_role_expiry(&contractor, &Role::Operator), None);
+}
+
+#[test]
+fn test_export_chunks_cover_role_table() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    env.as_contract(&contract_id, || {
+        common::limits::set(&env, common::limits::Limit::PageSize, 2).unwrap();
+    });
+    let operator = Address::generate(&env);
+    let contractor = Address::generate(&env);
+    let expiry = env.ledger().sequence() + 100;
+    client.grant_role(&admin, &operator, &Role::Operator);
+    client.grant_role(&admin, &operator, &Role::Auditor);
+    client.revoke_role(&admin, &operator, &Role::Auditor);
+    client.grant_role_until(&admin, &contractor, &Role::Operator, &expiry);
+
+    let first = client.export_chunk(&admin, &None);
+    assert_eq!(first.records.len(), 2);
+    assert_eq!(first.records.get(0).unwrap().user, admin);
+    let record = first.records.get(1).unwrap();
+    assert_eq!(record.user, operator);
+    assert_eq!(record.roles.get(Role::Auditor), Some(false));
+    assert_eq!(first.next_cursor, Some(2));
+
+    let second = client.export_chunk(&admin, &first.next_cursor);
+    assert_eq!(second.records.len(), 1);
+    let record = second.records.get(0).unwrap();
+    assert_eq!(record.user, contractor);
+    assert_eq!(record.expiries, vec![&env, (Role::Operator, expiry)]);
+    assert_eq!(second.next_cursor, None);
+
+    let result = client.try_export_chunk(&operator, &None);
+    assert_eq!(result, Err(Ok(AccessControlError::Unauthorized.into())));
+}
+
+#[test]
+fn test_export_covers_scoped_custom_and_suspended_users() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let reviewer = Address::generate(&env);
+    let suspect = Address::generate(&env);
+    let scope = Scope {
+        contract: Address::generate(&env),
+        id: 7,
+    };
+    let name = Symbol::new(&env, "reviewer");
+    client.grant_scoped_role(&admin, &owner, &Role::Operator, &scope);
+    client.create_role(&admin, &name, &String::from_str(&env, "Reviews payouts"));
+    client.grant_custom_role(&admin, &reviewer, &name);
+    client.suspend_user(&admin, &suspect, &3_600);
+
+    let records = client.export_chunk(&admin, &None).records;
+    assert_eq!(records.len(), 4);
+    let owner_record = records.get(1).unwrap();
+    assert_eq!(owner_record.user, owner);
+    assert_eq!(owner_record.scoped, vec![&env, (Role::Operator, scope.clone())]);
+    let reviewer_record = records.get(2).unwrap();
+    let role_id = client.get_role(&name).unwrap().id;
+    assert_eq!(reviewer_record.custom, vec![&env, (name.clone(), role_id)]);
+    let suspect_record = records.get(3).unwrap();
+    let suspension = client.get_suspension(&suspect).unwrap();
+    assert_eq!(suspect_record.suspended_at, suspension.suspended_at);
+    assert_eq!(suspect_record.suspended_until, suspension.until);
+    assert!(suspect_record.roles.is_empty());
+
+    let definitions = client.export_custom_roles(&admin);
+    assert_eq!(definitions, vec![&env, (name.clone(), client.get_role(&name).unwrap())]);
+
+    client.revoke_scoped_role(&admin, &owner, &Role::Operator, &scope);
+    client.revoke_custom_role(&admin, &reviewer, &name);
+    let records = client.export_chunk(&admin, &None).records;
+    assert!(records.get(1).unwrap().scoped.is_empty());
+    assert!(records.get(2).unwrap().custom.is_empty());
+}
+
+#[test]
+fn test_migration_backfills_role_table() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    let legacy = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let reviewer = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let scope = Scope {
+        contract: Address::generate(&env),
+        id: 7,
+    };
+    let name = Symbol::new(&env, "reviewer");
+
+    // Grants written by version 1, which indexed none of them
+    env.as_contract(&contract_id, || {
+        versioning::set_version(&env, 1);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::RoleUserPosition(admin.clone()));
+        rbac::grant_role(&env, &legacy, Role::Operator).unwrap();
+        rbac::grant_scoped_role(&env, &owner, Role::Operator, &scope).unwrap();
+        let role = CustomRole {
+            id: 9,
+            metadata: String::from_str(&env, "Reviews payouts"),
+            created_at: 0,
+        };
+        env.storage().persistent().set(&DataKey::CustomRole(name.clone()), &role);
+        env.storage()
+            .persistent()
+            .set(&DataKey::CustomRoleGrant(reviewer.clone(), name.clone()), &9u64);
+    });
+    assert_eq!(
+        client.try_export_chunk(&admin, &None),
+        Err(Ok(AccessControlError::StorageVersionMismatch.into()))
+    );
+
+    // Entries without a matching grant are skipped
+    let backfill = RoleBackfill {
+        users: vec![&env, legacy.clone(), stranger.clone()],
+        scoped: vec![
+            &env,
+            (owner.clone(), Role::Operator, scope.clone()),
+            (stranger.clone(), Role::Admin, scope.clone()),
+        ],
+        custom_roles: vec![&env, name.clone(), Symbol::new(&env, "bogus")],
+        custom_grants: vec![&env, (reviewer.clone(), name.clone())],
+    };
+    client.migrate(&admin, &1, &backfill);
+    assert_eq!(client.get_storage_version(), STORAGE_VERSION);
+
+    let records = client.export_chunk(&admin, &None).records;
+    let mut users = Vec::new(&env);
+    for record in records.iter() {
+        users.push_back(record.user);
+    }
+    assert_eq!(users, vec![&env, admin.clone(), legacy, owner, reviewer]);
+    assert_eq!(records.get(2).unwrap().scoped, vec![&env, (Role::Operator, scope)]);
+    assert_eq!(records.get(3).unwrap().custom, vec![&env, (name.clone(), 9)]);
+    assert_eq!(client.export_custom_roles(&admin).len(), 1);
+}
+
+#[test]
+fn test_role_member_index_pages_holders() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    let third = Address::generate(&env);
+    for operator in [&first, &second, &third] {
+        client.grant_role(&admin, operator, &Role::Operator);
+    }
+    assert_eq!(client.get_role_member_count(&Role::Operator), 3);
+    assert_eq!(
+        client.get_role_members(&Role::Operator, &1, &5),
+        vec![&env, second.clone(), third.clone()]
+    );
+
+    // Revoking moves the last member into the freed position
+    client.revoke_role(&admin, &first, &Role::Operator);
+    assert_eq!(client.get_role_member_count(&Role::Operator), 2);
+    assert_eq!(
+        client.get_role_members(&Role::Operator, &0, &0),
+        vec![&env, third.clone(), second.clone()]
+    );
+    assert_eq!(client.get_role_members(&Role::Operator, &2, &5).len(), 0);
+
+    client.transfer_admin(&admin, &first);
+    assert_eq!(
+        client.get_role_members(&Role::Admin, &0, &5),
+        vec![&env, first.clone()]
+    );
+    assert_eq!(client.get_role_member_count(&Role::Auditor), 0);
+}
+
+#[test]
+fn test_role_members_skip_expired_grants() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    let operator = Address::generate(&env);
+    let contractor = Address::generate(&env);
+    let expiry = env.ledger().sequence() + 100;
+    client.grant_role(&admin, &operator, &Role::Operator);
+    client.grant_role_until(&admin, &contractor, &Role::Operator, &expiry);
+    assert_eq!(client.get_role_member_count(&Role::Operator), 2);
+
+    env.ledger().with_mut(|li| li.sequence_number = expiry);
+    assert_eq!(client.get_role_member_count(&Role::Operator), 1);
+    assert_eq!(
+        client.get_role_members(&Role::Operator, &0, &0),
+        vec![&env, operator.clone()]
+    );
+
+    // Purging drops the expired grant from the index itself
+    client.purge_expired(&contractor);
+    assert_eq!(client.get_role_member_count(&Role::Operator), 1);
+
+    // Re-granting without an expiry counts the member again
+    client.grant_role_until(&admin, &contractor, &Role::Operator, &(expiry + 100));
+    client.revoke_role(&admin, &contractor, &Role::Operator);
+    client.grant_role(&admin, &contractor, &Role::Operator);
+    env.ledger().with_mut(|li| li.sequence_number = expiry + 100);
+    assert_eq!(client.get_role_member_count(&Role::Operator), 2);
+}
+
+#[test]
+fn test_migration_backfills_role_members() {
+    let (env, contract_id, admin) = create_contract();
+    let client = AccessControlContractClient::new(&env, &contract_id);
+    let auditor = Address::generate(&env);
+    let contractor = Address::generate(&env);
+    let expiry = env.ledger().sequence() + 100;
+
+    // Grants written by version 2, which indexed users but not role members
+    env.as_contract(&contract_id, || {
+        versioning::set_version(&env, 2);
+        for (user, role) in [(&auditor, Role::Auditor), (&contractor, Role::Operator)] {
+            AccessControlContract::track_role_user(&env, user);
+            rbac::grant_role(&env, user, role).unwrap();
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::RoleExpiry(contractor.clone(), Role::Operator), &expiry);
+    });
+
+    let backfill = RoleBackfill {
+        users: Vec::new(&env),
+        scoped: Vec::new(&env),
+        custom_roles: Vec::new(&env),
+        custom_grants: Vec::new(&env),
+    };
+    client.migrate(&admin, &2, &backfill);
+
+    assert_eq!(client.get_role_members(&Role::Auditor, &0, &0), vec![&env, auditor]);
+    assert_eq!(client.get_role_members(&Role::Admin, &0, &0), vec![&env, admin]);
+    assert_eq!(client.get_role_member_count(&Role::Operator), 1);
+    env.ledger().with_mut(|li| li.sequence_number = expiry);
+    assert_eq!(client.get_role_member_count(&Role::Operator), 0);
+}
@@ -8,6 +8,8 @@
 //! - Detailed event emission for each conversion
 //! - Gas optimized with batched storage updates
 //! - Validates all amounts and currency types
+//! - Pins daily closing rates so past conversions can be audited at the rate
+//!   that applied on their day
 //!
 //! ## Note on Conversion Mechanism
 //! This implementation uses a simplified conversion model where users specify
@@ -19,6 +21,8 @@
 mod types;
 mod validation;
 
+use common::normalize::{self, ClosingRate, NormalizeError};
+use common::versioning;
 use soroban_sdk::{contract, contractimpl, panic_with_error, token, Address, Env, Vec};
 
 pub use crate::types::{
@@ -49,6 +53,18 @@ pub enum BatchConversionError {
     InsufficientBalance = 7,
     /// Slippage tolerance exceeded
     SlippageExceeded = 8,
+    /// Storage was written by a different layout version
+    StorageVersionMismatch = 9,
+    /// Migration source version does not match stored version
+    InvalidMigration = 10,
+    /// No price oracle is configured
+    OracleNotSet = 11,
+    /// The oracle has no fresh price for an asset
+    PriceUnavailable = 12,
+    /// No closing rate is pinned for the pair on that day
+    RateUnavailable = 13,
+    /// The converted amount does not fit in i128
+    ConversionOverflow = 14,
 }
 
 impl From<BatchConversionError> for soroban_sdk::Error {
@@ -57,6 +73,9 @@ impl From<BatchConversionError> for soroban_sdk::Error {
     }
 }
 
+/// Storage layout version this code expects.
+pub const STORAGE_VERSION: u32 = 1;
+
 #[contract]
 pub struct BatchConversionContract;
 
@@ -69,6 +88,7 @@ impl BatchConversionContract {
         }
 
         env.storage().instance().set(&DataKey::Admin, &admin);
+        versioning::set_version(&env, STORAGE_VERSION);
         env.storage().instance().set(&DataKey::TotalBatches, &0u64);
         env.storage()
             .instance()
@@ -78,6 +98,24 @@ impl BatchConversionContract {
             .set(&DataKey::TotalVolumeConverted, &0i128);
     }
 
+    /// Migrates storage written by an older version of this contract to the
+    /// current layout (admin only). `from_version` must match the stored
+    /// version.
+    pub fn migrate(env: Env, caller: Address, from_version: u32) {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        // Version 1 only adds the version stamp; older layouts are otherwise
+        // unchanged, so there is nothing to rewrite
+        versioning::migrate(&env, from_version, STORAGE_VERSION, |_, _| {})
+            .unwrap_or_else(|_| panic_with_error!(&env, BatchConversionError::InvalidMigration));
+    }
+
+    /// Returns the storage layout version currently stored.
+    pub fn get_storage_version(env: Env) -> u32 {
+        versioning::stored_version(&env)
+    }
+
     /// Executes batch currency conversions for multiple users.
     ///
     /// # Arguments
@@ -96,6 +134,7 @@ impl BatchConversionContract {
         env: Env,
         conversions: Vec<ConversionRequest>,
     ) -> BatchConversionResult {
+        Self::require_storage_version(&env);
         // Validate batch size
         let request_count = conversions.len();
         if request_count == 0 {
@@ -172,7 +211,7 @@ impl BatchConversionContract {
                     request.from_asset.clone(),
                     request.to_asset.clone(),
                     request.amount_in,
-                    error_code.clone(),
+                    error_code,
                 ));
                 failed_count += 1;
                 ConversionEvents::conversion_failure(
@@ -182,7 +221,7 @@ impl BatchConversionContract {
                     &request.from_asset,
                     &request.to_asset,
                     request.amount_in,
-                    error_code.clone(),
+                    error_code,
                 );
                 continue;
             }
@@ -309,6 +348,113 @@ impl BatchConversionContract {
             .unwrap_or(0)
     }
 
+    /// Sets the SEP-40 price oracle closing rates are read from (admin only).
+    pub fn set_oracle(env: Env, caller: Address, oracle: Address) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        env.storage().instance().set(&DataKey::Oracle, &oracle);
+    }
+
+    /// Allowlists an asset for rate pinning, caching its decimals (admin
+    /// only).
+    pub fn allow_asset(env: Env, caller: Address, asset: Address) -> u32 {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        normalize::allow_token(&env, &asset).unwrap_or_else(|e| Self::rate_error(&env, e))
+    }
+
+    /// Pins today's closing rate of `from_asset` in `to_asset` from the
+    /// oracle (admin only). Recording again the same day replaces the day's
+    /// rate, so the last recording of the day is its close.
+    pub fn record_closing_rate(
+        env: Env,
+        caller: Address,
+        from_asset: Address,
+        to_asset: Address,
+    ) -> ClosingRate {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        let oracle: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Oracle)
+            .unwrap_or_else(|| panic_with_error!(&env, BatchConversionError::OracleNotSet));
+        let rate = normalize::record_close(&env, &oracle, &from_asset, &to_asset)
+            .unwrap_or_else(|e| Self::rate_error(&env, e));
+        ConversionEvents::rate_closed(&env, &from_asset, &to_asset, &rate);
+
+        rate
+    }
+
+    /// Returns the closing rate pinned for the pair on the day of
+    /// `timestamp`. Rates are kept for `normalize::RATE_HISTORY_DAYS` days.
+    pub fn rate_at(
+        env: Env,
+        from_asset: Address,
+        to_asset: Address,
+        timestamp: u64,
+    ) -> ClosingRate {
+        normalize::rate_at(&env, &from_asset, &to_asset, timestamp)
+            .unwrap_or_else(|e| Self::rate_error(&env, e))
+    }
+
+    /// Converts `amount` of `from_asset` into `to_asset` at the closing rate
+    /// pinned for the day of `timestamp`, rounding down.
+    pub fn convert_at(
+        env: Env,
+        amount: i128,
+        from_asset: Address,
+        to_asset: Address,
+        timestamp: u64,
+    ) -> i128 {
+        normalize::convert_at(&env, amount, &from_asset, &to_asset, timestamp)
+            .unwrap_or_else(|e| Self::rate_error(&env, e))
+    }
+
+    // Internal helper mapping normalization failures to contract errors
+    fn rate_error(env: &Env, error: NormalizeError) -> ! {
+        match error {
+            NormalizeError::TokenNotAllowed | NormalizeError::InvalidDecimals => {
+                panic_with_error!(env, BatchConversionError::InvalidAsset)
+            }
+            NormalizeError::PriceUnavailable | NormalizeError::StalePrice => {
+                panic_with_error!(env, BatchConversionError::PriceUnavailable)
+            }
+            NormalizeError::RateUnavailable => {
+                panic_with_error!(env, BatchConversionError::RateUnavailable)
+            }
+            NormalizeError::Overflow => {
+                panic_with_error!(env, BatchConversionError::ConversionOverflow)
+            }
+        }
+    }
+
+    // Internal helper to verify admin
+    fn require_admin(env: &Env, caller: &Address) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(env, BatchConversionError::NotInitialized));
+        if *caller != admin {
+            panic_with_error!(env, BatchConversionError::Unauthorized);
+        }
+    }
+
+    // Internal helper refusing to run against storage written by a different
+    // layout version
+    fn require_storage_version(env: &Env) {
+        versioning::require_version(env, STORAGE_VERSION).unwrap_or_else(|_| {
+            panic_with_error!(env, BatchConversionError::StorageVersionMismatch)
+        });
+    }
+
     // Internal helper to execute a single conversion
     fn execute_conversion(env: &Env, request: &ConversionRequest) -> Result<i128, u32> {
         // TODO: Implement actual conversion mechanism
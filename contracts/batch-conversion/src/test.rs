@@ -3,11 +3,14 @@
 #![cfg(test)]
 
 use crate::{
-    BatchConversionContract, BatchConversionContractClient, ConversionRequest, ConversionResult,
+    BatchConversionContract, BatchConversionContractClient, BatchConversionError,
+    ConversionRequest, ConversionResult,
 };
+use common::normalize::{Asset, PriceData};
 use soroban_sdk::{
+    contract, contractimpl,
     testutils::{Address as _, Events as _, Ledger},
-    token, Address, Env, Vec,
+    token, Address, Env, Map, Vec,
 };
 
 /// Creates a test environment with the contract deployed and initialized.
@@ -309,3 +312,122 @@ fn test_batch_convert_empty_batch() {
     let conversions: Vec<ConversionRequest> = Vec::new(&env);
     client.batch_convert_currency(&conversions);
 }
+
+// ============================================================================
+// Closing Rate Tests
+// ============================================================================
+
+/// Oracle serving whatever prices the test sets.
+#[contract]
+struct MockOracle;
+
+#[contractimpl]
+impl MockOracle {
+    pub fn set_price(env: Env, asset: Asset, price: i128, timestamp: u64) {
+        let mut prices: Map<Asset, PriceData> = env
+            .storage()
+            .instance()
+            .get(&0u32)
+            .unwrap_or(Map::new(&env));
+        prices.set(asset, PriceData { price, timestamp });
+        env.storage().instance().set(&0u32, &prices);
+    }
+
+    pub fn lastprice(env: Env, asset: Asset) -> Option<PriceData> {
+        let prices: Map<Asset, PriceData> = env.storage().instance().get(&0u32)?;
+        prices.get(asset)
+    }
+}
+
+/// Creates a contract with an oracle and two allowlisted 7-decimal assets.
+fn setup_rates() -> (
+    Env,
+    Address,
+    Address,
+    Address,
+    MockOracleClient<'static>,
+    BatchConversionContractClient<'static>,
+) {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 10_000);
+
+    let client =
+        BatchConversionContractClient::new(&env, &env.register(BatchConversionContract, ()));
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let oracle = MockOracleClient::new(&env, &env.register(MockOracle, ()));
+    client.set_oracle(&admin, &oracle.address);
+
+    let eurc = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let usdc = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.allow_asset(&admin, &eurc);
+    client.allow_asset(&admin, &usdc);
+
+    (env, admin, eurc, usdc, oracle, client)
+}
+
+#[test]
+fn test_conversion_uses_rate_of_its_day() {
+    let (env, admin, eurc, usdc, oracle, client) = setup_rates();
+    oracle.set_price(&Asset::Stellar(eurc.clone()), &108, &10_000);
+    oracle.set_price(&Asset::Stellar(usdc.clone()), &100, &10_000);
+    client.record_closing_rate(&admin, &eurc, &usdc);
+
+    // Ninety days later EUR has weakened
+    let later = 90 * 86_400 + 10_000;
+    env.ledger().with_mut(|li| li.timestamp = later);
+    oracle.set_price(&Asset::Stellar(eurc.clone()), &104, &later);
+    oracle.set_price(&Asset::Stellar(usdc.clone()), &100, &later);
+    let rate = client.record_closing_rate(&admin, &eurc, &usdc);
+    assert_eq!(rate.day, 90);
+
+    assert_eq!(
+        client.convert_at(&10_000_000_000, &eurc, &usdc, &50_000),
+        10_800_000_000
+    );
+    assert_eq!(
+        client.convert_at(&10_000_000_000, &eurc, &usdc, &later),
+        10_400_000_000
+    );
+    assert_eq!(client.rate_at(&eurc, &usdc, &50_000).from_price, 108);
+
+    let result = client.try_rate_at(&eurc, &usdc, &(45 * 86_400));
+    assert_eq!(
+        result,
+        Err(Ok(BatchConversionError::RateUnavailable.into()))
+    );
+}
+
+#[test]
+fn test_record_closing_rate_requires_fresh_prices() {
+    let (_env, admin, eurc, usdc, oracle, client) = setup_rates();
+    oracle.set_price(&Asset::Stellar(usdc.clone()), &100, &10_000);
+
+    let result = client.try_record_closing_rate(&admin, &eurc, &usdc);
+    assert_eq!(
+        result,
+        Err(Ok(BatchConversionError::PriceUnavailable.into()))
+    );
+
+    // Prices older than the oracle age limit are refused too
+    oracle.set_price(&Asset::Stellar(eurc.clone()), &108, &1_000);
+    let result = client.try_record_closing_rate(&admin, &eurc, &usdc);
+    assert_eq!(
+        result,
+        Err(Ok(BatchConversionError::PriceUnavailable.into()))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_record_closing_rate_requires_admin() {
+    let (env, _admin, eurc, usdc, _oracle, client) = setup_rates();
+
+    client.record_closing_rate(&Address::generate(&env), &eurc, &usdc);
+}
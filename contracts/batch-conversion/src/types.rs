@@ -1,3 +1,5 @@
+use common::events;
+use common::normalize::ClosingRate;
 use soroban_sdk::{contracttype, symbol_short, Address, Env, Vec};
 
 pub const MAX_BATCH_SIZE: u32 = 100;
@@ -36,6 +38,8 @@ pub enum DataKey {
     TotalBatches,
     TotalConversionsProcessed,
     TotalVolumeConverted,
+    /// SEP-40 price oracle closing rates are read from
+    Oracle,
 }
 
 pub struct ConversionEvents;
@@ -43,7 +47,7 @@ pub struct ConversionEvents;
 impl ConversionEvents {
     pub fn batch_started(env: &Env, batch_id: u64, request_count: u32) {
         let topics = (symbol_short!("batch"), symbol_short!("started"));
-        env.events().publish(topics, (batch_id, request_count));
+        events::publish(env, topics, (batch_id, request_count));
     }
 
     pub fn conversion_success(
@@ -56,7 +60,8 @@ impl ConversionEvents {
         amount_out: i128,
     ) {
         let topics = (symbol_short!("convert"), symbol_short!("success"), batch_id);
-        env.events().publish(
+        events::publish(
+            env,
             topics,
             (
                 user.clone(),
@@ -78,7 +83,8 @@ impl ConversionEvents {
         error_code: u32,
     ) {
         let topics = (symbol_short!("convert"), symbol_short!("failure"), batch_id);
-        env.events().publish(
+        events::publish(
+            env,
             topics,
             (
                 user.clone(),
@@ -98,7 +104,16 @@ impl ConversionEvents {
         total_converted: i128,
     ) {
         let topics = (symbol_short!("batch"), symbol_short!("completed"), batch_id);
-        env.events()
-            .publish(topics, (successful, failed, total_converted));
+        events::publish(env, topics, (successful, failed, total_converted));
+    }
+
+    /// Emitted when a day's closing rate is pinned for a pair.
+    pub fn rate_closed(env: &Env, from_asset: &Address, to_asset: &Address, rate: &ClosingRate) {
+        let topics = (symbol_short!("rate"), symbol_short!("closed"));
+        events::publish(
+            env,
+            topics,
+            (from_asset.clone(), to_asset.clone(), rate.clone()),
+        );
     }
 }
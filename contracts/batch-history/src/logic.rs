@@ -1,4 +1,5 @@
 use crate::types::UserHistory;
+use common::events;
 use soroban_sdk::{symbol_short, Address, Env, Vec};
 
 pub fn get_batch_history(env: Env, users: Vec<Address>) -> Vec<UserHistory> {
@@ -7,7 +8,8 @@ pub fn get_batch_history(env: Env, users: Vec<Address>) -> Vec<UserHistory> {
 
     for user in users.iter() {
         // Requirement: Emit events for retrieval (helps with off-chain indexing)
-        env.events().publish(
+        events::publish(
+            &env,
             (symbol_short!("history"), user.clone()),
             symbol_short!("retrieved"),
         );
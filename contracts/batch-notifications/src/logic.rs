@@ -1,4 +1,5 @@
 use crate::types::{BatchResult, NotificationPayload};
+use common::events;
 use soroban_sdk::{symbol_short, Env, Vec};
 
 pub fn execute_dispatch(env: Env, payloads: Vec<NotificationPayload>) -> BatchResult {
@@ -10,7 +11,8 @@ pub fn execute_dispatch(env: Env, payloads: Vec<NotificationPayload>) -> BatchRe
         // We consider an empty message a "soft failure" instead of panicking
         if !payload.message.is_empty() {
             // Requirement: Emit events for notification delivery
-            env.events().publish(
+            events::publish(
+                &env,
                 (symbol_short!("notif"), payload.user.clone()),
                 payload.message,
             );
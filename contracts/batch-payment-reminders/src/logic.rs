@@ -2,6 +2,7 @@
 
 use crate::types::{BatchReminderResult, PaymentReminderRequest};
 use crate::validation::{validate_reminder_request, ValidationError};
+use common::events;
 use soroban_sdk::{symbol_short, Env, Vec};
 
 pub fn execute_dispatch(
@@ -12,7 +13,8 @@ pub fn execute_dispatch(
     let mut successful_count: u32 = 0;
     let mut failed_addresses = Vec::new(&env);
 
-    env.events().publish(
+    events::publish(
+        &env,
         (
             symbol_short!("batch_rem"),
             symbol_short!("started"),
@@ -24,7 +26,8 @@ pub fn execute_dispatch(
     for request in requests.iter() {
         match validate_reminder_request(&env, &request.user, request.due_date) {
             Ok(()) => {
-                env.events().publish(
+                events::publish(
+                    &env,
                     (
                         symbol_short!("rem_sent"),
                         request.user.clone(),
@@ -35,7 +38,8 @@ pub fn execute_dispatch(
                 successful_count += 1;
             }
             Err(ValidationError::InvalidUser) | Err(ValidationError::InvalidDueDate) => {
-                env.events().publish(
+                events::publish(
+                    &env,
                     (
                         symbol_short!("rem_fail"),
                         request.user.clone(),
@@ -48,7 +52,8 @@ pub fn execute_dispatch(
         }
     }
 
-    env.events().publish(
+    events::publish(
+        &env,
         (
             symbol_short!("batch_rem"),
             symbol_short!("completed"),
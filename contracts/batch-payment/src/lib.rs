@@ -4,7 +4,8 @@ mod test;
 mod types;
 
 use crate::types::Payment;
-use soroban_sdk::{contract, contractimpl, symbol_short, token, Address, Env, Vec};
+use common::{events, idempotency};
+use soroban_sdk::{contract, contractimpl, symbol_short, token, Address, BytesN, Env, Vec};
 
 #[contract]
 pub struct BatchPaymentContract;
@@ -18,10 +19,31 @@ impl BatchPaymentContract {
     /// * `from` - The address sending the tokens (must authorize the call).
     /// * `token` - The address of the token contract (e.g., USDC).
     /// * `payments` - A vector of `Payment` structs containing recipients and amounts.
-    pub fn batch_transfer(env: Env, from: Address, token: Address, payments: Vec<Payment>) {
+    /// * `idempotency_key` - Optional client-chosen key. A retry of the same batch with the
+    ///   same key within the retention window pays nothing; reusing the key for a different
+    ///   batch panics.
+    pub fn batch_transfer(
+        env: Env,
+        from: Address,
+        token: Address,
+        payments: Vec<Payment>,
+        idempotency_key: Option<BytesN<32>>,
+    ) {
         // Require authorization from the sender
         from.require_auth();
 
+        let idempotency_key = idempotency_key.map(|key| {
+            let fingerprint = idempotency::fingerprint(&env, (token.clone(), payments.clone()));
+            (key, fingerprint)
+        });
+        if let Some((key, fingerprint)) = &idempotency_key {
+            match idempotency::recall::<()>(&env, &from, key, fingerprint) {
+                Ok(Some(())) => return,
+                Ok(None) => {}
+                Err(_) => panic!("Idempotency key reused for a different batch"),
+            }
+        }
+
         let token_client = token::Client::new(&env, &token);
 
         let mut total_amount: i128 = 0;
@@ -50,14 +72,17 @@ impl BatchPaymentContract {
                 batch_id,
                 payment.recipient.clone(),
             );
-            env.events()
-                .publish(topics, (token.clone(), payment.amount));
+            events::publish(&env, topics, (token.clone(), payment.amount));
         }
 
         // Emit batch completion event
         // Topics: (batch, complete, batch_id)
         // Data: (total_payments, total_amount)
         let topics = (symbol_short!("batch"), symbol_short!("complete"), batch_id);
-        env.events().publish(topics, (count, total_amount));
+        events::publish(&env, topics, (count, total_amount));
+
+        if let Some((key, fingerprint)) = &idempotency_key {
+            idempotency::remember(&env, &from, key, fingerprint, &());
+        }
     }
 }
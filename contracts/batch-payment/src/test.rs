@@ -4,7 +4,7 @@ extern crate std;
 use super::*;
 use soroban_sdk::{
     testutils::{Address as _, Events},
-    Address, Env, Vec,
+    Address, BytesN, Env, Vec,
 };
 
 #[test]
@@ -42,7 +42,7 @@ fn test_batch_transfer() {
     });
 
     // Execute batch transfer
-    client.batch_transfer(&sender, &token_contract.address(), &payments);
+    client.batch_transfer(&sender, &token_contract.address(), &payments, &None);
 
     // Verify balances
     assert_eq!(token_client.balance(&sender), 700);
@@ -85,5 +85,67 @@ fn test_batch_transfer_zero_amount() {
         amount: 0,
     });
 
-    client.batch_transfer(&sender, &token_contract.address(), &payments);
+    client.batch_transfer(&sender, &token_contract.address(), &payments, &None);
+}
+
+#[test]
+fn test_batch_transfer_retry_with_key_pays_once() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchPaymentContract, ());
+    let client = BatchPaymentContractClient::new(&env, &contract_id);
+
+    let token_contract = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let token_client = token::Client::new(&env, &token_contract.address());
+    let token_admin_client = token::StellarAssetClient::new(&env, &token_contract.address());
+
+    let sender = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    token_admin_client.mint(&sender, &1000);
+
+    let mut payments = Vec::new(&env);
+    payments.push_back(Payment {
+        recipient: user1.clone(),
+        amount: 100,
+    });
+
+    let key = Some(BytesN::from_array(&env, &[1; 32]));
+    client.batch_transfer(&sender, &token_contract.address(), &payments, &key);
+    client.batch_transfer(&sender, &token_contract.address(), &payments, &key);
+    assert_eq!(token_client.balance(&user1), 100);
+
+    // Without a key every call pays
+    client.batch_transfer(&sender, &token_contract.address(), &payments, &None);
+    assert_eq!(token_client.balance(&user1), 200);
+}
+
+#[test]
+#[should_panic(expected = "Idempotency key reused for a different batch")]
+fn test_batch_transfer_key_reused_for_different_batch() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchPaymentContract, ());
+    let client = BatchPaymentContractClient::new(&env, &contract_id);
+
+    let token_contract = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let token_admin_client = token::StellarAssetClient::new(&env, &token_contract.address());
+
+    let sender = Address::generate(&env);
+    token_admin_client.mint(&sender, &1000);
+
+    let mut payments = Vec::new(&env);
+    payments.push_back(Payment {
+        recipient: Address::generate(&env),
+        amount: 100,
+    });
+    let key = Some(BytesN::from_array(&env, &[1; 32]));
+    client.batch_transfer(&sender, &token_contract.address(), &payments, &key);
+
+    payments.set(0, Payment {
+        recipient: Address::generate(&env),
+        amount: 100,
+    });
+    client.batch_transfer(&sender, &token_contract.address(), &payments, &key);
 }
@@ -7,6 +7,7 @@ mod validation;
 #[cfg(test)]
 mod test;
 
+use common::events;
 use soroban_sdk::{contract, contractimpl, panic_with_error, token, Address, Env, Vec};
 
 pub use crate::types::{
@@ -102,7 +103,7 @@ impl BatchRewardsContract {
 
         env.storage().instance().set(&DataKey::Admin, &new_admin);
         let topics = (soroban_sdk::symbol_short!("admin"),);
-        env.events().publish(topics, (&new_admin,));
+        events::publish(&env, topics, (&new_admin,));
     }
 
     /// Distributes rewards to multiple recipients in a batch operation.
@@ -1,3 +1,4 @@
+use common::events;
 use soroban_sdk::{contracttype, symbol_short, Address, Env, Vec};
 
 pub const MAX_BATCH_SIZE: u32 = 100;
@@ -40,7 +41,7 @@ pub struct RewardEvents;
 impl RewardEvents {
     pub fn batch_started(env: &Env, batch_id: u64, request_count: u32) {
         let topics = (symbol_short!("batch"), symbol_short!("started"));
-        env.events().publish(topics, (batch_id, request_count));
+        events::publish(env, topics, (batch_id, request_count));
     }
 
     pub fn reward_success(env: &Env, batch_id: u64, recipient: &Address, amount: i128) {
@@ -49,7 +50,7 @@ impl RewardEvents {
             symbol_short!("success"),
             batch_id,
         );
-        env.events().publish(topics, (recipient, amount));
+        events::publish(env, topics, (recipient, amount));
     }
 
     pub fn reward_failure(env: &Env, batch_id: u64, recipient: &Address, amount: i128, error_code: u32) {
@@ -58,7 +59,7 @@ impl RewardEvents {
             symbol_short!("failure"),
             batch_id,
         );
-        env.events().publish(topics, (recipient, amount, error_code));
+        events::publish(env, topics, (recipient, amount, error_code));
     }
 
     pub fn batch_completed(
@@ -69,7 +70,10 @@ impl RewardEvents {
         total_distributed: i128,
     ) {
         let topics = (symbol_short!("batch"), symbol_short!("completed"));
-        env.events()
-            .publish(topics, (batch_id, successful, failed, total_distributed));
+        events::publish(
+            env,
+            topics,
+            (batch_id, successful, failed, total_distributed),
+        );
     }
 }
@@ -1,5 +1,6 @@
 //! Data types and events for batch token minting operations.
 
+use common::events;
 use soroban_sdk::{contracttype, symbol_short, Address, Env, Vec};
 
 /// Maximum number of mint operations in a single batch for optimization.
@@ -122,14 +123,14 @@ impl MintEvents {
     /// Event emitted when batch minting starts.
     pub fn batch_started(env: &Env, batch_id: u64, token: &Address, count: u32) {
         let topics = (symbol_short!("mint"), symbol_short!("start"));
-        env.events()
-            .publish(topics, (batch_id, token.clone(), count));
+        events::publish(env, topics, (batch_id, token.clone(), count));
     }
 
     /// Event emitted when tokens are successfully minted.
     pub fn tokens_minted(env: &Env, batch_id: u64, token: &Address, minted: &TokenMinted) {
         let topics = (symbol_short!("mint"), symbol_short!("success"));
-        env.events().publish(
+        events::publish(
+            env,
             topics,
             (
                 batch_id,
@@ -143,7 +144,8 @@ impl MintEvents {
     /// Event emitted when minting fails for a recipient.
     pub fn mint_failed(env: &Env, batch_id: u64, token: &Address, recipient: &Address, error_code: u32) {
         let topics = (symbol_short!("mint"), symbol_short!("failed"));
-        env.events().publish(
+        events::publish(
+            env,
             topics,
             (batch_id, token.clone(), recipient.clone(), error_code),
         );
@@ -159,7 +161,8 @@ impl MintEvents {
         total_amount: i128,
     ) {
         let topics = (symbol_short!("mint"), symbol_short!("done"));
-        env.events().publish(
+        events::publish(
+            env,
             topics,
             (
                 batch_id,
@@ -174,7 +177,8 @@ impl MintEvents {
     /// Event emitted for large mint operations (>= 1 billion stroops).
     pub fn large_mint(env: &Env, batch_id: u64, token: &Address, recipient: &Address, amount: i128) {
         let topics = (symbol_short!("mint"), symbol_short!("large"));
-        env.events().publish(
+        events::publish(
+            env,
             topics,
             (batch_id, token.clone(), recipient.clone(), amount),
         );
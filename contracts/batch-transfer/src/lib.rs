@@ -1,14 +1,23 @@
 //! # Batch Transfer Contract
 #![no_std]
 
+mod netting;
 mod types;
 mod validation;
 
-use soroban_sdk::{contract, contractimpl, panic_with_error, token, Address, Env, Vec};
+use common::idempotency;
+use common::pagination;
+use common::reentrancy::ReentrancyGuard;
+use common::token_io::{self, TransferError};
+use common::versioning;
+use soroban_sdk::{
+    contract, contractimpl, panic_with_error, token, Address, BytesN, Env, Vec,
+};
 
 pub use crate::types::{
-    BatchBurnResult, BatchTransferResult, BurnRequest, BurnResult, DataKey, TransferEvents,
-    TransferRequest, TransferResult, MAX_BATCH_SIZE,
+    BatchBurnResult, BatchTransferResult, BurnRequest, BurnResult, DataKey, NettedPair,
+    NettingOutcome, NettingRecord, Obligation, QuarantineReport, QuarantinedLeg, TransferEvents,
+    TransferRequest, TransferResult, MAX_BATCH_SIZE, MAX_QUARANTINED_LEGS,
 };
 use crate::validation::{validate_address, validate_amount};
 
@@ -28,6 +37,22 @@ pub enum BatchTransferError {
     BatchTooLarge = 5,
     /// Invalid token contract
     InvalidToken = 6,
+    /// Storage was written by a different layout version
+    StorageVersionMismatch = 7,
+    /// Migration source version does not match stored version
+    InvalidMigration = 8,
+    /// Idempotency key was already used for a different batch
+    IdempotencyKeyReused = 9,
+    /// Idempotency retention window is out of bounds
+    InvalidRetention = 10,
+    /// Obligation is not positive, or owed by a party to itself
+    InvalidObligation = 11,
+    /// No quarantined leg exists with this ID
+    LegNotFound = 12,
+    /// Replacement recipient is invalid
+    InvalidRecipient = 13,
+    /// Entrypoint was re-entered during an external call
+    Reentrancy = 14,
 }
 
 impl From<BatchTransferError> for soroban_sdk::Error {
@@ -36,6 +61,9 @@ impl From<BatchTransferError> for soroban_sdk::Error {
     }
 }
 
+/// Storage layout version this code expects.
+pub const STORAGE_VERSION: u32 = 1;
+
 #[contract]
 pub struct BatchTransferContract;
 
@@ -48,6 +76,7 @@ impl BatchTransferContract {
         }
 
         env.storage().instance().set(&DataKey::Admin, &admin);
+        versioning::set_version(&env, STORAGE_VERSION);
         env.storage().instance().set(&DataKey::TotalBatches, &0u64);
         env.storage()
             .instance()
@@ -57,17 +86,79 @@ impl BatchTransferContract {
             .set(&DataKey::TotalVolumeTransferred, &0i128);
     }
 
+    /// Migrates storage written by an older version of this contract to the
+    /// current layout (admin only). `from_version` must match the stored
+    /// version.
+    pub fn migrate(env: Env, caller: Address, from_version: u32) {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        // Version 1 only adds the version stamp; older layouts are otherwise
+        // unchanged, so there is nothing to rewrite
+        versioning::migrate(&env, from_version, STORAGE_VERSION, |_, _| {})
+            .unwrap_or_else(|_| panic_with_error!(&env, BatchTransferError::InvalidMigration));
+    }
+
+    /// Returns the storage layout version currently stored.
+    pub fn get_storage_version(env: Env) -> u32 {
+        versioning::stored_version(&env)
+    }
+
     /// Executes batch transfers of XLM to multiple recipients.
+    ///
+    /// With an `idempotency_key`, a retry of the same batch within the
+    /// retention window returns the original result without transferring
+    /// again. Reusing the key for a different batch fails.
     pub fn batch_transfer(
         env: Env,
         caller: Address,
         token: Address,
         transfers: Vec<TransferRequest>,
+        idempotency_key: Option<BytesN<32>>,
     ) -> BatchTransferResult {
+        Self::require_storage_version(&env);
         // Verify authorization
         caller.require_auth();
         Self::require_admin(&env, &caller);
+        let _guard = Self::enter_guard(&env);
+
+        let Some(key) = idempotency_key else {
+            return Self::process_batch_transfer(env, caller, token, transfers);
+        };
+        let fingerprint = idempotency::fingerprint(&env, (token.clone(), transfers.clone()));
+        let earlier = idempotency::recall(&env, &caller, &key, &fingerprint)
+            .unwrap_or_else(|_| panic_with_error!(&env, BatchTransferError::IdempotencyKeyReused));
+        if let Some(result) = earlier {
+            return result;
+        }
+
+        let result = Self::process_batch_transfer(env.clone(), caller.clone(), token, transfers);
+        idempotency::remember(&env, &caller, &key, &fingerprint, &result);
+        result
+    }
+
+    /// Sets how long idempotency keys are remembered, in seconds (admin only).
+    pub fn set_idempotency_retention(env: Env, caller: Address, secs: u64) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
 
+        idempotency::set_retention(&env, secs)
+            .unwrap_or_else(|_| panic_with_error!(&env, BatchTransferError::InvalidRetention));
+    }
+
+    /// Returns how long idempotency keys are remembered, in seconds.
+    pub fn get_idempotency_retention(env: Env) -> u64 {
+        idempotency::get_retention(&env)
+    }
+
+    // Internal helper executing an authorized batch transfer
+    fn process_batch_transfer(
+        env: Env,
+        caller: Address,
+        token: Address,
+        transfers: Vec<TransferRequest>,
+    ) -> BatchTransferResult {
         // Validate batch size
         let request_count = transfers.len();
         if request_count == 0 {
@@ -136,7 +227,7 @@ impl BatchTransferContract {
                 results.push_back(TransferResult::Failure(
                     request.recipient.clone(),
                     request.amount,
-                    error_code.clone(),
+                    error_code,
                 ));
                 failed_count += 1;
                 TransferEvents::transfer_failure(
@@ -144,7 +235,7 @@ impl BatchTransferContract {
                     batch_id,
                     &request.recipient,
                     request.amount,
-                    error_code.clone(),
+                    error_code,
                 );
                 continue;
             }
@@ -169,11 +260,49 @@ impl BatchTransferContract {
             }
 
             // Execute transfer
-            // Note: After thorough validation, transfers should succeed.
-            // If a transfer fails due to contract-level issues (authorization, etc.),
-            // it will panic and revert the entire batch. This is acceptable as
-            // we've validated all inputs and balances.
-            token_client.transfer(&caller, &request.recipient, &request.amount);
+            // A transfer the token rejects (frozen account, missing trustline, etc.)
+            // is quarantined: the funds are held by this contract until the leg
+            // is retried or reassigned. If even that fails, or the quarantine
+            // queue is full, the leg is recorded as a failure. A token that moves
+            // a different amount than requested reverts the entire batch.
+            let transfer =
+                token_io::safe_transfer(&env, &token, &caller, &request.recipient, request.amount);
+            match transfer {
+                Ok(()) => {}
+                Err(TransferError::AmountMismatch) => {
+                    panic_with_error!(&env, BatchTransferError::InvalidToken)
+                }
+                Err(_)
+                    if Self::quarantine_queue(&env).len() < MAX_QUARANTINED_LEGS
+                        && Self::hold(&env, &token, &caller, request.amount) =>
+                {
+                    let leg = Self::quarantine(&env, batch_id, &token, &caller, &request);
+                    available_balance -= request.amount;
+                    results.push_back(TransferResult::Quarantined(
+                        request.recipient.clone(),
+                        request.amount,
+                        leg.leg_id,
+                    ));
+                    failed_count += 1;
+                    continue;
+                }
+                Err(_) => {
+                    results.push_back(TransferResult::Failure(
+                        request.recipient.clone(),
+                        request.amount,
+                        3, // Transfer failed
+                    ));
+                    failed_count += 1;
+                    TransferEvents::transfer_failure(
+                        &env,
+                        batch_id,
+                        &request.recipient,
+                        request.amount,
+                        3,
+                    );
+                    continue;
+                }
+            }
 
             // Transfer succeeded
             available_balance -= request.amount;
@@ -244,8 +373,10 @@ impl BatchTransferContract {
         token: Address,
         burns: Vec<BurnRequest>,
     ) -> BatchBurnResult {
+        Self::require_storage_version(&env);
         caller.require_auth();
         Self::require_admin(&env, &caller);
+        let _guard = Self::enter_guard(&env);
 
         let request_count = burns.len();
         if request_count == 0 {
@@ -350,6 +481,165 @@ impl BatchTransferContract {
         }
     }
 
+    /// Settles obligations between parties after netting them pairwise
+    /// (admin only).
+    ///
+    /// Amounts two parties owe each other, such as vendor invoices against
+    /// credit notes, are offset and only the difference is transferred from
+    /// the party owing it, who must authorize the transfer. A pair whose
+    /// transfer the token rejects is recorded as failed without affecting the
+    /// rest. The gross amounts in both directions are kept in the returned
+    /// record, which is stored for audit.
+    pub fn settle_netted(
+        env: Env,
+        caller: Address,
+        token: Address,
+        obligations: Vec<Obligation>,
+    ) -> NettingRecord {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+        let _guard = Self::enter_guard(&env);
+
+        let request_count = obligations.len();
+        if request_count == 0 {
+            panic_with_error!(&env, BatchTransferError::EmptyBatch);
+        }
+        if request_count > MAX_BATCH_SIZE {
+            panic_with_error!(&env, BatchTransferError::BatchTooLarge);
+        }
+        let pairs = netting::net_obligations(&env, &obligations)
+            .unwrap_or_else(|_| panic_with_error!(&env, BatchTransferError::InvalidObligation));
+
+        let netting_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalNettings)
+            .unwrap_or(0)
+            + 1;
+
+        let token_client = token::Client::new(&env, &token);
+        let mut outcomes: Vec<NettingOutcome> = Vec::new(&env);
+        let mut total_gross: i128 = 0;
+        let mut total_transferred: i128 = 0;
+        for pair in pairs.iter() {
+            total_gross = total_gross
+                .checked_add(pair.gross_owed)
+                .and_then(|total| total.checked_add(pair.gross_offset))
+                .unwrap_or(i128::MAX);
+            TransferEvents::netting_pair(&env, netting_id, &pair);
+            if pair.net == 0 {
+                outcomes.push_back(NettingOutcome::Settled);
+                continue;
+            }
+
+            // Error codes match the batch transfer ones
+            if token_client.balance(&pair.payer) < pair.net {
+                outcomes.push_back(NettingOutcome::Failed(2)); // Insufficient balance
+                continue;
+            }
+            pair.payer.require_auth();
+            match token_io::safe_transfer(&env, &token, &pair.payer, &pair.payee, pair.net) {
+                Ok(()) => {
+                    outcomes.push_back(NettingOutcome::Settled);
+                    total_transferred = total_transferred.saturating_add(pair.net);
+                }
+                Err(TransferError::AmountMismatch) => {
+                    panic_with_error!(&env, BatchTransferError::InvalidToken)
+                }
+                Err(_) => outcomes.push_back(NettingOutcome::Failed(3)), // Transfer failed
+            }
+        }
+
+        let record = NettingRecord {
+            netting_id,
+            token,
+            settled_at: env.ledger().timestamp(),
+            total_gross,
+            total_transferred,
+            pairs,
+            outcomes,
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalNettings, &netting_id);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Netting(netting_id), &record);
+        TransferEvents::netting_completed(&env, netting_id, total_gross, total_transferred);
+
+        record
+    }
+
+    /// Returns the audit record of a netted settlement.
+    pub fn get_netting(env: Env, netting_id: u64) -> Option<NettingRecord> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Netting(netting_id))
+    }
+
+    /// Retries paying a quarantined leg to its recipient (admin only).
+    ///
+    /// Returns true if the leg was paid and left the queue. A retry the token
+    /// rejects again keeps the leg quarantined and counts the failure.
+    pub fn retry_quarantined(env: Env, caller: Address, leg_id: u64) -> bool {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+        let _guard = Self::enter_guard(&env);
+
+        let leg = Self::load_leg(&env, leg_id);
+        Self::release(&env, leg)
+    }
+
+    /// Points a quarantined leg at a new recipient and retries paying it
+    /// (admin only), e.g. once an employee supplies a working account.
+    ///
+    /// Returns true if the leg was paid and left the queue. If the payment
+    /// fails, the leg stays quarantined under the new recipient.
+    pub fn reassign_quarantined(
+        env: Env,
+        caller: Address,
+        leg_id: u64,
+        recipient: Address,
+    ) -> bool {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+        let _guard = Self::enter_guard(&env);
+        if validate_address(&env, &recipient).is_err() {
+            panic_with_error!(&env, BatchTransferError::InvalidRecipient);
+        }
+
+        let mut leg = Self::load_leg(&env, leg_id);
+        leg.recipient = recipient;
+        Self::release(&env, leg)
+    }
+
+    /// Returns a quarantined leg, if it is still in the queue.
+    pub fn get_quarantined(env: Env, leg_id: u64) -> Option<QuarantinedLeg> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Quarantined(leg_id))
+    }
+
+    /// Returns the page of quarantined legs after `cursor`, oldest first. A
+    /// `limit` of zero returns a full page.
+    pub fn get_quarantine_report(env: Env, cursor: Option<u64>, limit: u32) -> QuarantineReport {
+        let queue = Self::quarantine_queue(&env);
+        let page = pagination::page_after(&env, &queue, cursor, limit);
+
+        let mut legs: Vec<QuarantinedLeg> = Vec::new(&env);
+        for leg_id in page.ids.iter() {
+            legs.push_back(Self::load_leg(&env, leg_id));
+        }
+        QuarantineReport {
+            legs,
+            next_cursor: page.next_cursor,
+            open_legs: queue.len(),
+        }
+    }
+
     /// Returns the admin address.
     pub fn get_admin(env: Env) -> Address {
         env.storage()
@@ -360,6 +650,7 @@ impl BatchTransferContract {
 
     /// Updates the admin address.
     pub fn set_admin(env: Env, current_admin: Address, new_admin: Address) {
+        Self::require_storage_version(&env);
         current_admin.require_auth();
         Self::require_admin(&env, &current_admin);
 
@@ -390,6 +681,123 @@ impl BatchTransferContract {
             .unwrap_or(0)
     }
 
+    // Internal helper moving a rejected leg's funds into this contract's
+    // custody. Returns false if the payer's funds could not be moved either.
+    fn hold(env: &Env, token: &Address, payer: &Address, amount: i128) -> bool {
+        let contract = env.current_contract_address();
+        match token_io::safe_transfer(env, token, payer, &contract, amount) {
+            Ok(()) => true,
+            Err(TransferError::AmountMismatch) => {
+                panic_with_error!(env, BatchTransferError::InvalidToken)
+            }
+            Err(_) => false,
+        }
+    }
+
+    // Internal helper taking the reentrancy lock for entrypoints that call
+    // out to the token
+    fn enter_guard(env: &Env) -> ReentrancyGuard {
+        ReentrancyGuard::enter(env)
+            .unwrap_or_else(|_| panic_with_error!(env, BatchTransferError::Reentrancy))
+    }
+
+    // Internal helper recording a held leg in the quarantine queue
+    fn quarantine(
+        env: &Env,
+        batch_id: u64,
+        token: &Address,
+        payer: &Address,
+        request: &TransferRequest,
+    ) -> QuarantinedLeg {
+        let leg_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalQuarantined)
+            .unwrap_or(0)
+            + 1;
+        let leg = QuarantinedLeg {
+            leg_id,
+            batch_id,
+            token: token.clone(),
+            payer: payer.clone(),
+            recipient: request.recipient.clone(),
+            amount: request.amount,
+            quarantined_at: env.ledger().timestamp(),
+            failed_retries: 0,
+        };
+
+        let mut queue = Self::quarantine_queue(env);
+        queue.push_back(leg_id);
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalQuarantined, &leg_id);
+        env.storage()
+            .persistent()
+            .set(&DataKey::QuarantineQueue, &queue);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Quarantined(leg_id), &leg);
+        TransferEvents::leg_quarantined(env, &leg);
+
+        leg
+    }
+
+    // Internal helper paying a quarantined leg out of custody, removing it
+    // from the queue on success
+    fn release(env: &Env, mut leg: QuarantinedLeg) -> bool {
+        let contract = env.current_contract_address();
+        match token_io::safe_transfer(env, &leg.token, &contract, &leg.recipient, leg.amount) {
+            Ok(()) => {
+                let mut queue = Self::quarantine_queue(env);
+                if let Some(index) = queue.first_index_of(leg.leg_id) {
+                    queue.remove(index);
+                }
+                env.storage()
+                    .persistent()
+                    .set(&DataKey::QuarantineQueue, &queue);
+                env.storage()
+                    .persistent()
+                    .remove(&DataKey::Quarantined(leg.leg_id));
+                TransferEvents::leg_released(env, &leg);
+                true
+            }
+            Err(TransferError::AmountMismatch) => {
+                panic_with_error!(env, BatchTransferError::InvalidToken)
+            }
+            Err(_) => {
+                leg.failed_retries = leg.failed_retries.saturating_add(1);
+                env.storage()
+                    .persistent()
+                    .set(&DataKey::Quarantined(leg.leg_id), &leg);
+                TransferEvents::leg_retry_failed(env, &leg);
+                false
+            }
+        }
+    }
+
+    // Internal helper to load a quarantined leg or fail
+    fn load_leg(env: &Env, leg_id: u64) -> QuarantinedLeg {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Quarantined(leg_id))
+            .unwrap_or_else(|| panic_with_error!(env, BatchTransferError::LegNotFound))
+    }
+
+    // Internal helper reading the IDs of the legs still quarantined
+    fn quarantine_queue(env: &Env) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::QuarantineQueue)
+            .unwrap_or(Vec::new(env))
+    }
+
+    // Internal helper refusing to run against storage written by a different
+    // layout version
+    fn require_storage_version(env: &Env) {
+        versioning::require_version(env, STORAGE_VERSION)
+            .unwrap_or_else(|_| panic_with_error!(env, BatchTransferError::StorageVersionMismatch));
+    }
+
     // Internal helper to verify admin
     fn require_admin(env: &Env, caller: &Address) {
         let admin: Address = env
@@ -0,0 +1,169 @@
+//! Netting of mutual obligations before settlement.
+//!
+//! Obligations between the same two parties are offset against each other, so
+//! that only the difference has to move. The gross amounts owed in each
+//! direction are kept on the netted pair for audit.
+
+use soroban_sdk::{Env, Vec};
+
+use crate::types::{NettedPair, Obligation};
+use crate::validation::validate_amount;
+
+/// Netting error types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NettingError {
+    /// An obligation has a non-positive amount
+    InvalidAmount,
+    /// An obligation is owed by a party to itself
+    SelfObligation,
+    /// Gross amounts between a pair overflow
+    Overflow,
+}
+
+/// Offsets obligations pairwise.
+///
+/// Returns one entry per pair of parties, in order of first appearance, with
+/// the net payer first. Pairs whose obligations cancel out have a net of zero.
+pub fn net_obligations(
+    env: &Env,
+    obligations: &Vec<Obligation>,
+) -> Result<Vec<NettedPair>, NettingError> {
+    let mut pairs: Vec<NettedPair> = Vec::new(env);
+
+    for obligation in obligations.iter() {
+        if validate_amount(obligation.amount).is_err() {
+            return Err(NettingError::InvalidAmount);
+        }
+        if obligation.debtor == obligation.creditor {
+            return Err(NettingError::SelfObligation);
+        }
+
+        let existing = pairs.iter().position(|pair| {
+            (pair.payer == obligation.debtor && pair.payee == obligation.creditor)
+                || (pair.payer == obligation.creditor && pair.payee == obligation.debtor)
+        });
+        let mut pair = match existing {
+            Some(index) => pairs.get_unchecked(index as u32),
+            None => NettedPair {
+                payer: obligation.debtor.clone(),
+                payee: obligation.creditor.clone(),
+                gross_owed: 0,
+                gross_offset: 0,
+                net: 0,
+            },
+        };
+
+        if pair.payer == obligation.debtor {
+            pair.gross_owed = pair
+                .gross_owed
+                .checked_add(obligation.amount)
+                .ok_or(NettingError::Overflow)?;
+        } else {
+            pair.gross_offset = pair
+                .gross_offset
+                .checked_add(obligation.amount)
+                .ok_or(NettingError::Overflow)?;
+        }
+
+        match existing {
+            Some(index) => pairs.set(index as u32, pair),
+            None => pairs.push_back(pair),
+        }
+    }
+
+    // Orient each pair so the party owing the difference pays
+    for index in 0..pairs.len() {
+        let mut pair = pairs.get_unchecked(index);
+        if pair.gross_offset > pair.gross_owed {
+            pair = NettedPair {
+                payer: pair.payee,
+                payee: pair.payer,
+                gross_owed: pair.gross_offset,
+                gross_offset: pair.gross_owed,
+                net: 0,
+            };
+        }
+        pair.net = pair.gross_owed - pair.gross_offset;
+        pairs.set(index, pair);
+    }
+
+    Ok(pairs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+
+    fn owes(debtor: &Address, creditor: &Address, amount: i128) -> Obligation {
+        Obligation {
+            debtor: debtor.clone(),
+            creditor: creditor.clone(),
+            amount,
+        }
+    }
+
+    #[test]
+    fn test_mutual_obligations_are_offset() {
+        let env = Env::default();
+        let vendor = Address::generate(&env);
+        let buyer = Address::generate(&env);
+
+        // Two invoices against one credit note
+        let obligations = vec![
+            &env,
+            owes(&buyer, &vendor, 700),
+            owes(&vendor, &buyer, 250),
+            owes(&buyer, &vendor, 100),
+        ];
+        let pairs = net_obligations(&env, &obligations).unwrap();
+
+        assert_eq!(pairs.len(), 1);
+        let pair = pairs.get(0).unwrap();
+        assert_eq!(pair.payer, buyer);
+        assert_eq!(pair.payee, vendor);
+        assert_eq!(
+            (pair.gross_owed, pair.gross_offset, pair.net),
+            (800, 250, 550)
+        );
+    }
+
+    #[test]
+    fn test_payer_is_party_owing_the_difference() {
+        let env = Env::default();
+        let a = Address::generate(&env);
+        let b = Address::generate(&env);
+        let c = Address::generate(&env);
+
+        let obligations = vec![&env, owes(&a, &b, 100), owes(&b, &a, 400), owes(&c, &a, 50)];
+        let pairs = net_obligations(&env, &obligations).unwrap();
+
+        assert_eq!(pairs.len(), 2);
+        let first = pairs.get(0).unwrap();
+        assert_eq!((first.payer, first.payee), (b, a.clone()));
+        assert_eq!(
+            (first.gross_owed, first.gross_offset, first.net),
+            (400, 100, 300)
+        );
+        assert_eq!(pairs.get(1).unwrap().payee, a);
+    }
+
+    #[test]
+    fn test_invalid_obligations() {
+        let env = Env::default();
+        let a = Address::generate(&env);
+        let b = Address::generate(&env);
+
+        let zero = vec![&env, owes(&a, &b, 0)];
+        assert_eq!(
+            net_obligations(&env, &zero),
+            Err(NettingError::InvalidAmount)
+        );
+
+        let to_self = vec![&env, owes(&a, &a, 10)];
+        assert_eq!(
+            net_obligations(&env, &to_self),
+            Err(NettingError::SelfObligation)
+        );
+    }
+}
@@ -3,12 +3,13 @@
 #![cfg(test)]
 
 use crate::{
-    BatchBurnResult, BatchTransferContract, BatchTransferContractClient, BurnRequest,
-    TransferRequest, TransferResult,
+    BatchBurnResult, BatchTransferContract, BatchTransferContractClient, BurnRequest, DataKey,
+    NettingOutcome, Obligation, QuarantinedLeg, TransferRequest, TransferResult,
+    MAX_QUARANTINED_LEGS,
 };
 use soroban_sdk::{
-    testutils::{Address as _, Events as _, Ledger},
-    token, Address, Env, Vec,
+    testutils::{Address as _, Events as _, IssuerFlags, Ledger},
+    token, Address, BytesN, Env, Vec,
 };
 
 /// Creates a test environment with the contract deployed and initialized.
@@ -67,7 +68,7 @@ fn test_initialize_contract() {
 #[test]
 #[should_panic(expected = "Contract already initialized")]
 fn test_cannot_initialize_twice() {
-    let (env, admin, _token, _token_client, client) = setup_test_env();
+    let (env, _admin, _token, _token_client, client) = setup_test_env();
 
     let new_admin = Address::generate(&env);
     client.initialize(&new_admin);
@@ -88,7 +89,7 @@ fn test_batch_transfer_single_recipient() {
     let mut transfers: Vec<TransferRequest> = Vec::new(&env);
     transfers.push_back(create_transfer_request(&env, recipient.clone(), amount));
 
-    let result = client.batch_transfer(&admin, &token, &transfers);
+    let result = client.batch_transfer(&admin, &token, &transfers, &None);
 
     assert_eq!(result.total_requests, 1);
     assert_eq!(result.successful, 1);
@@ -122,7 +123,7 @@ fn test_batch_transfer_multiple_recipients() {
     transfers.push_back(create_transfer_request(&env, recipient2.clone(), amount2));
     transfers.push_back(create_transfer_request(&env, recipient3.clone(), amount3));
 
-    let result = client.batch_transfer(&admin, &token, &transfers);
+    let result = client.batch_transfer(&admin, &token, &transfers, &None);
 
     assert_eq!(result.total_requests, 3);
     assert_eq!(result.successful, 3);
@@ -152,7 +153,7 @@ fn test_batch_transfer_with_invalid_amount() {
         valid_amount,
     )); // Valid
 
-    let result = client.batch_transfer(&admin, &token, &transfers);
+    let result = client.batch_transfer(&admin, &token, &transfers, &None);
 
     assert_eq!(result.total_requests, 2);
     assert_eq!(result.successful, 1);
@@ -196,7 +197,7 @@ fn test_batch_transfer_with_insufficient_balance() {
     transfers.push_back(create_transfer_request(&env, recipient1.clone(), amount1));
     transfers.push_back(create_transfer_request(&env, recipient2.clone(), amount2));
 
-    let result = client.batch_transfer(&admin, &token, &transfers);
+    let result = client.batch_transfer(&admin, &token, &transfers, &None);
 
     assert_eq!(result.total_requests, 2);
     assert_eq!(result.successful, 1);
@@ -234,7 +235,7 @@ fn test_batch_transfer_partial_failures() {
     )); // Valid
     transfers.push_back(create_transfer_request(&env, recipient4.clone(), -100)); // Invalid: negative
 
-    let result = client.batch_transfer(&admin, &token, &transfers);
+    let result = client.batch_transfer(&admin, &token, &transfers, &None);
 
     assert_eq!(result.total_requests, 4);
     assert_eq!(result.successful, 2);
@@ -264,7 +265,7 @@ fn test_batch_transfer_events_emitted() {
     ));
     transfers.push_back(create_transfer_request(&env, recipient2.clone(), -100)); // Invalid
 
-    client.batch_transfer(&admin, &token, &transfers);
+    client.batch_transfer(&admin, &token, &transfers, &None);
 
     let events = env.events().all();
     // Should have: batch_started, transfer_success (1), transfer_failure (1), batch_completed
@@ -300,12 +301,12 @@ fn test_batch_transfer_accumulates_stats() {
     assert_eq!(client.get_total_transfers_processed(), 0);
     assert_eq!(client.get_total_volume_transferred(), 0);
 
-    client.batch_transfer(&admin, &token, &transfers1);
+    client.batch_transfer(&admin, &token, &transfers1, &None);
     assert_eq!(client.get_total_batches(), 1);
     assert_eq!(client.get_total_transfers_processed(), 1);
     assert_eq!(client.get_total_volume_transferred(), 10_000_000);
 
-    client.batch_transfer(&admin, &token, &transfers2);
+    client.batch_transfer(&admin, &token, &transfers2, &None);
     assert_eq!(client.get_total_batches(), 2);
     assert_eq!(client.get_total_transfers_processed(), 2);
     assert_eq!(client.get_total_volume_transferred(), 30_000_000);
@@ -317,13 +318,13 @@ fn test_batch_transfer_empty_batch() {
     let (env, admin, token, _token_client, client) = setup_test_env();
 
     let transfers: Vec<TransferRequest> = Vec::new(&env);
-    client.batch_transfer(&admin, &token, &transfers);
+    client.batch_transfer(&admin, &token, &transfers, &None);
 }
 
 #[test]
 #[should_panic]
 fn test_batch_transfer_unauthorized() {
-    let (env, admin, token, _token_client, client) = setup_test_env();
+    let (env, _admin, token, _token_client, client) = setup_test_env();
 
     let unauthorized = Address::generate(&env);
     let recipient = Address::generate(&env);
@@ -332,7 +333,7 @@ fn test_batch_transfer_unauthorized() {
     transfers.push_back(create_transfer_request(&env, recipient, 10_000_000));
 
     // This should panic due to unauthorized access
-    client.batch_transfer(&unauthorized, &token, &transfers);
+    client.batch_transfer(&unauthorized, &token, &transfers, &None);
 }
 
 #[test]
@@ -356,7 +357,7 @@ fn test_batch_transfer_large_batch() {
         // 0.1 XLM each
     }
 
-    let result = client.batch_transfer(&admin, &token, &transfers);
+    let result = client.batch_transfer(&admin, &token, &transfers, &None);
 
     assert_eq!(result.total_requests, 50);
     assert_eq!(result.successful, 50);
@@ -412,7 +413,7 @@ fn test_multiple_simultaneous_batch_transfers() {
         30_000_000,
     ));
 
-    let result1 = client.batch_transfer(&admin, &token, &batch1);
+    let result1 = client.batch_transfer(&admin, &token, &batch1, &None);
     assert_eq!(result1.successful, 3);
     assert_eq!(result1.total_transferred, 60_000_000);
 
@@ -427,7 +428,7 @@ fn test_multiple_simultaneous_batch_transfers() {
         15_000_000,
     ));
 
-    let result2 = client.batch_transfer(&admin, &token, &batch2);
+    let result2 = client.batch_transfer(&admin, &token, &batch2, &None);
     assert_eq!(result2.successful, 2);
     assert_eq!(result2.total_transferred, 20_000_000);
 
@@ -542,3 +543,296 @@ fn test_batch_burn_unauthorized() {
     let unauthorized = Address::generate(&env);
     client.batch_burn(&unauthorized, &token, &burns);
 }
+
+// Idempotency Tests
+
+#[test]
+fn test_batch_transfer_retry_with_key_pays_once() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let recipient = Address::generate(&env);
+    let token_admin_client = token::StellarAssetClient::new(&env, &token);
+    token_admin_client.mint(&admin, &30_000_000);
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 10_000_000));
+    let key = Some(BytesN::from_array(&env, &[1; 32]));
+
+    let first = client.batch_transfer(&admin, &token, &transfers, &key);
+    let retry = client.batch_transfer(&admin, &token, &transfers, &key);
+
+    assert_eq!(retry.successful, first.successful);
+    assert_eq!(retry.total_transferred, 10_000_000);
+    assert_eq!(token_client.balance(&recipient), 10_000_000);
+    assert_eq!(client.get_total_batches(), 1);
+
+    // A different key is a new payment
+    let other_key = Some(BytesN::from_array(&env, &[2; 32]));
+    client.batch_transfer(&admin, &token, &transfers, &other_key);
+    assert_eq!(token_client.balance(&recipient), 20_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn test_batch_transfer_key_reused_for_different_batch() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let token_admin_client = token::StellarAssetClient::new(&env, &token);
+    token_admin_client.mint(&admin, &30_000_000);
+    let key = Some(BytesN::from_array(&env, &[1; 32]));
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, Address::generate(&env), 10_000_000));
+    client.batch_transfer(&admin, &token, &transfers, &key);
+
+    let mut other: Vec<TransferRequest> = Vec::new(&env);
+    other.push_back(create_transfer_request(&env, Address::generate(&env), 10_000_000));
+    client.batch_transfer(&admin, &token, &other, &key);
+}
+
+#[test]
+fn test_batch_transfer_key_expires_after_retention() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let recipient = Address::generate(&env);
+    let token_admin_client = token::StellarAssetClient::new(&env, &token);
+    token_admin_client.mint(&admin, &30_000_000);
+
+    client.set_idempotency_retention(&admin, &3_600);
+    assert_eq!(client.get_idempotency_retention(), 3_600);
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 10_000_000));
+    let key = Some(BytesN::from_array(&env, &[1; 32]));
+    client.batch_transfer(&admin, &token, &transfers, &key);
+
+    env.ledger().with_mut(|li| li.timestamp += 3_600);
+    client.batch_transfer(&admin, &token, &transfers, &key);
+    assert_eq!(token_client.balance(&recipient), 20_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")]
+fn test_idempotency_retention_out_of_bounds() {
+    let (_env, admin, _token, _token_client, client) = setup_test_env();
+    client.set_idempotency_retention(&admin, &0);
+}
+
+// Netting Tests
+
+fn create_obligation(debtor: &Address, creditor: &Address, amount: i128) -> Obligation {
+    Obligation {
+        debtor: debtor.clone(),
+        creditor: creditor.clone(),
+        amount,
+    }
+}
+
+#[test]
+fn test_settle_netted_transfers_only_the_difference() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let buyer = Address::generate(&env);
+    let vendor = Address::generate(&env);
+    let token_admin_client = token::StellarAssetClient::new(&env, &token);
+    token_admin_client.mint(&buyer, &1_000);
+    token_admin_client.mint(&vendor, &1_000);
+
+    // Two invoices from the vendor against one credit note
+    let mut obligations: Vec<Obligation> = Vec::new(&env);
+    obligations.push_back(create_obligation(&buyer, &vendor, 600));
+    obligations.push_back(create_obligation(&vendor, &buyer, 150));
+    obligations.push_back(create_obligation(&buyer, &vendor, 200));
+
+    let record = client.settle_netted(&admin, &token, &obligations);
+
+    assert_eq!(record.netting_id, 1);
+    assert_eq!(record.total_gross, 950);
+    assert_eq!(record.total_transferred, 650);
+    let pair = record.pairs.get(0).unwrap();
+    assert_eq!((pair.gross_owed, pair.gross_offset, pair.net), (800, 150, 650));
+    assert_eq!(token_client.balance(&buyer), 350);
+    assert_eq!(token_client.balance(&vendor), 1_650);
+
+    let stored = client.get_netting(&1).unwrap();
+    assert_eq!(stored.pairs, record.pairs);
+}
+
+#[test]
+fn test_settle_netted_records_failed_pairs() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    let c = Address::generate(&env);
+    let token_admin_client = token::StellarAssetClient::new(&env, &token);
+    token_admin_client.mint(&a, &500);
+
+    let mut obligations: Vec<Obligation> = Vec::new(&env);
+    obligations.push_back(create_obligation(&a, &b, 300));
+    obligations.push_back(create_obligation(&c, &b, 100));
+    obligations.push_back(create_obligation(&b, &a, 300));
+    obligations.push_back(create_obligation(&a, &c, 50));
+
+    let record = client.settle_netted(&admin, &token, &obligations);
+
+    // a and b cancel out, c cannot pay b, a pays c
+    assert_eq!(record.pairs.get(0).unwrap().net, 0);
+    assert_eq!(
+        record.outcomes,
+        soroban_sdk::vec![
+            &env,
+            NettingOutcome::Settled,
+            NettingOutcome::Failed(2),
+            NettingOutcome::Settled
+        ]
+    );
+    assert_eq!(record.total_transferred, 50);
+    assert_eq!(token_client.balance(&a), 450);
+    assert_eq!(token_client.balance(&b), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #11)")]
+fn test_settle_netted_rejects_self_obligation() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let party = Address::generate(&env);
+    let mut obligations: Vec<Obligation> = Vec::new(&env);
+    obligations.push_back(create_obligation(&party, &party, 100));
+
+    client.settle_netted(&admin, &token, &obligations);
+}
+
+// Quarantine Tests
+
+/// Deploys a token whose issuer can freeze accounts.
+fn setup_freezable_token(env: &Env) -> (Address, token::Client<'static>) {
+    let stellar_asset = env.register_stellar_asset_contract_v2(Address::generate(env));
+    stellar_asset.issuer().set_flag(IssuerFlags::RevocableFlag);
+    let token_id = stellar_asset.address();
+    (token_id.clone(), token::Client::new(env, &token_id))
+}
+
+/// Runs a batch paying 100 to `paid` and 200 to `blocked`, whose account
+/// the token refuses to credit.
+fn run_batch_with_blocked_recipient(
+    env: &Env,
+    admin: &Address,
+    token: &Address,
+    client: &BatchTransferContractClient,
+) -> (Address, Address, u64) {
+    let token_admin_client = token::StellarAssetClient::new(env, token);
+    token_admin_client.mint(admin, &1000);
+    let paid = Address::generate(env);
+    let blocked = Address::generate(env);
+    token_admin_client.set_authorized(&blocked, &false);
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(env);
+    transfers.push_back(create_transfer_request(env, paid.clone(), 100));
+    transfers.push_back(create_transfer_request(env, blocked.clone(), 200));
+    let result = client.batch_transfer(admin, token, &transfers, &None);
+
+    assert_eq!(result.successful, 1);
+    assert_eq!(result.total_transferred, 100);
+    let leg_id = match result.results.get(1).unwrap() {
+        TransferResult::Quarantined(recipient, amount, leg_id) => {
+            assert_eq!(recipient, blocked);
+            assert_eq!(amount, 200);
+            leg_id
+        }
+        other => panic!("expected a quarantined leg, got {:?}", other),
+    };
+    (paid, blocked, leg_id)
+}
+
+#[test]
+fn test_rejected_leg_is_quarantined_until_retry_succeeds() {
+    let (env, admin, _token, _token_client, client) = setup_test_env();
+    let (token, token_client) = setup_freezable_token(&env);
+    let (_paid, blocked, leg_id) = run_batch_with_blocked_recipient(&env, &admin, &token, &client);
+
+    // The rejected amount is held by the contract, not left with the payer
+    assert_eq!(token_client.balance(&admin), 700);
+    assert_eq!(token_client.balance(&client.address), 200);
+    let report = client.get_quarantine_report(&None, &0);
+    assert_eq!(report.open_legs, 1);
+    assert_eq!(report.legs.get(0).unwrap().recipient, blocked);
+
+    // Still blocked: the leg stays quarantined
+    assert!(!client.retry_quarantined(&admin, &leg_id));
+    let leg: QuarantinedLeg = client.get_quarantined(&leg_id).unwrap();
+    assert_eq!(leg.failed_retries, 1);
+
+    token::StellarAssetClient::new(&env, &token).set_authorized(&blocked, &true);
+    assert!(client.retry_quarantined(&admin, &leg_id));
+    assert_eq!(token_client.balance(&blocked), 200);
+    assert_eq!(token_client.balance(&client.address), 0);
+    assert_eq!(client.get_quarantined(&leg_id), None);
+    assert_eq!(client.get_quarantine_report(&None, &0).open_legs, 0);
+}
+
+#[test]
+fn test_quarantined_leg_reassigned_to_new_recipient() {
+    let (env, admin, _token, _token_client, client) = setup_test_env();
+    let (token, token_client) = setup_freezable_token(&env);
+    let (_paid, blocked, leg_id) = run_batch_with_blocked_recipient(&env, &admin, &token, &client);
+
+    let replacement = Address::generate(&env);
+    assert!(client.reassign_quarantined(&admin, &leg_id, &replacement));
+    assert_eq!(token_client.balance(&replacement), 200);
+    assert_eq!(token_client.balance(&blocked), 0);
+    assert_eq!(client.get_quarantine_report(&None, &0).open_legs, 0);
+}
+
+#[test]
+fn test_quarantine_report_pages_open_legs() {
+    let (env, admin, _token, _token_client, client) = setup_test_env();
+    let (token, _token_client) = setup_freezable_token(&env);
+    let (_, _, first) = run_batch_with_blocked_recipient(&env, &admin, &token, &client);
+    let (_, _, second) = run_batch_with_blocked_recipient(&env, &admin, &token, &client);
+    let (_, _, third) = run_batch_with_blocked_recipient(&env, &admin, &token, &client);
+    client.reassign_quarantined(&admin, &second, &Address::generate(&env));
+
+    let page = client.get_quarantine_report(&None, &1);
+    assert_eq!(page.open_legs, 2);
+    assert_eq!(page.legs.get(0).unwrap().leg_id, first);
+    let page = client.get_quarantine_report(&page.next_cursor, &1);
+    assert_eq!(page.legs.get(0).unwrap().leg_id, third);
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn test_full_quarantine_queue_fails_rejected_leg() {
+    let (env, admin, _token, _token_client, client) = setup_test_env();
+    let (token, token_client) = setup_freezable_token(&env);
+    env.as_contract(&client.address, || {
+        let mut queue: Vec<u64> = Vec::new(&env);
+        for leg_id in 1..=MAX_QUARANTINED_LEGS as u64 {
+            queue.push_back(leg_id);
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::QuarantineQueue, &queue);
+    });
+
+    let token_admin_client = token::StellarAssetClient::new(&env, &token);
+    token_admin_client.mint(&admin, &1000);
+    let blocked = Address::generate(&env);
+    token_admin_client.set_authorized(&blocked, &false);
+    let transfers = Vec::from_array(&env, [create_transfer_request(&env, blocked, 200)]);
+    let result = client.batch_transfer(&admin, &token, &transfers, &None);
+
+    // No room to hold the funds, so they stay with the payer
+    assert!(matches!(result.results.get(0), Some(TransferResult::Failure(_, 200, 3))));
+    assert_eq!(token_client.balance(&admin), 1000);
+    assert_eq!(token_client.balance(&client.address), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #12)")]
+fn test_retry_unknown_leg() {
+    let (_env, admin, _token, _token_client, client) = setup_test_env();
+
+    client.retry_quarantined(&admin, &1);
+}
@@ -1,6 +1,10 @@
+use common::events;
 use soroban_sdk::{contracttype, symbol_short, Address, Env, Vec};
 
 pub const MAX_BATCH_SIZE: u32 = 100;
+/// Most legs quarantined at once. The queue of open legs is a single storage
+/// entry, so it is capped well below the ledger entry size limit.
+pub const MAX_QUARANTINED_LEGS: u32 = 500;
 
 #[derive(Clone, Debug)]
 #[contracttype]
@@ -16,11 +20,93 @@ pub struct BurnRequest {
     pub amount: i128,
 }
 
+/// Amount owed by one party to another, settled by netting.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct Obligation {
+    pub debtor: Address,
+    pub creditor: Address,
+    pub amount: i128,
+}
+
+/// Obligations between two parties after netting.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct NettedPair {
+    /// Party owing the difference
+    pub payer: Address,
+    pub payee: Address,
+    /// Gross amount the payer owed the payee
+    pub gross_owed: i128,
+    /// Gross amount the payee owed the payer, offset against `gross_owed`
+    pub gross_offset: i128,
+    /// Amount transferred: `gross_owed - gross_offset`
+    pub net: i128,
+}
+
+/// Outcome of settling one netted pair.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum NettingOutcome {
+    /// Net amount transferred, or nothing owed after netting
+    Settled,
+    /// Transfer of the net amount failed: error code
+    Failed(u32),
+}
+
+/// Record of a netted settlement, kept for audit.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct NettingRecord {
+    pub netting_id: u64,
+    pub token: Address,
+    pub settled_at: u64,
+    /// Sum of all obligations before netting
+    pub total_gross: i128,
+    /// Sum of net amounts actually transferred
+    pub total_transferred: i128,
+    pub pairs: Vec<NettedPair>,
+    /// Outcome per pair, in the same order as `pairs`
+    pub outcomes: Vec<NettingOutcome>,
+}
+
 #[derive(Clone, Debug)]
 #[contracttype]
 pub enum TransferResult {
     Success(Address, i128),
     Failure(Address, i128, u32),
+    /// Rejected by the token and held for retry: leg ID
+    Quarantined(Address, i128, u64),
+}
+
+/// A batch transfer leg the token rejected (missing trustline, frozen
+/// account), held by the contract until it is retried or reassigned.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct QuarantinedLeg {
+    pub leg_id: u64,
+    /// Batch the leg was part of
+    pub batch_id: u64,
+    pub token: Address,
+    /// Address the held funds came from
+    pub payer: Address,
+    pub recipient: Address,
+    pub amount: i128,
+    pub quarantined_at: u64,
+    /// Number of retries that failed
+    pub failed_retries: u32,
+}
+
+/// One page of the quarantine queue.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct QuarantineReport {
+    /// Quarantined legs in this page, oldest first
+    pub legs: Vec<QuarantinedLeg>,
+    /// Continuation token for the next page; `None` once the queue is exhausted
+    pub next_cursor: Option<u64>,
+    /// Legs in the queue across all pages
+    pub open_legs: u32,
 }
 
 #[derive(Clone, Debug)]
@@ -57,6 +143,16 @@ pub enum DataKey {
     TotalBatches,
     TotalTransfersProcessed,
     TotalVolumeTransferred,
+    /// Number of netted settlements
+    TotalNettings,
+    /// Audit record of a netted settlement
+    Netting(u64),
+    /// Number of legs ever quarantined
+    TotalQuarantined,
+    /// Quarantined leg by leg ID
+    Quarantined(u64),
+    /// IDs of the legs still quarantined, ascending
+    QuarantineQueue,
 }
 
 pub struct TransferEvents;
@@ -64,7 +160,7 @@ pub struct TransferEvents;
 impl TransferEvents {
     pub fn batch_started(env: &Env, batch_id: u64, request_count: u32) {
         let topics = (symbol_short!("batch"), symbol_short!("started"));
-        env.events().publish(topics, (batch_id, request_count));
+        events::publish(env, topics, (batch_id, request_count));
     }
 
     pub fn transfer_success(env: &Env, batch_id: u64, recipient: &Address, amount: i128) {
@@ -73,7 +169,7 @@ impl TransferEvents {
             symbol_short!("success"),
             batch_id,
         );
-        env.events().publish(topics, (recipient.clone(), amount));
+        events::publish(env, topics, (recipient.clone(), amount));
     }
 
     pub fn transfer_failure(
@@ -88,8 +184,11 @@ impl TransferEvents {
             symbol_short!("failure"),
             batch_id,
         );
-        env.events()
-            .publish(topics, (recipient.clone(), requested_amount, error_code));
+        events::publish(
+            env,
+            topics,
+            (recipient.clone(), requested_amount, error_code),
+        );
     }
 
     pub fn batch_completed(
@@ -100,13 +199,36 @@ impl TransferEvents {
         total_transferred: i128,
     ) {
         let topics = (symbol_short!("batch"), symbol_short!("completed"), batch_id);
-        env.events()
-            .publish(topics, (successful, failed, total_transferred));
+        events::publish(env, topics, (successful, failed, total_transferred));
+    }
+
+    pub fn netting_pair(env: &Env, netting_id: u64, pair: &NettedPair) {
+        let topics = (symbol_short!("netting"), symbol_short!("pair"), netting_id);
+        events::publish(
+            env,
+            topics,
+            (
+                pair.payer.clone(),
+                pair.payee.clone(),
+                pair.gross_owed,
+                pair.gross_offset,
+                pair.net,
+            ),
+        );
+    }
+
+    pub fn netting_completed(env: &Env, netting_id: u64, total_gross: i128, transferred: i128) {
+        let topics = (
+            symbol_short!("netting"),
+            symbol_short!("completed"),
+            netting_id,
+        );
+        events::publish(env, topics, (total_gross, transferred));
     }
 
     pub fn burn_success(env: &Env, batch_id: u64, owner: &Address, amount: i128) {
         let topics = (symbol_short!("burn"), symbol_short!("success"), batch_id);
-        env.events().publish(topics, (owner.clone(), amount));
+        events::publish(env, topics, (owner.clone(), amount));
     }
 
     pub fn burn_failure(
@@ -117,10 +239,7 @@ impl TransferEvents {
         error_code: u32,
     ) {
         let topics = (symbol_short!("burn"), symbol_short!("failure"), batch_id);
-        env.events().publish(
-            topics,
-            (owner.clone(), requested_amount, error_code),
-        );
+        events::publish(env, topics, (owner.clone(), requested_amount, error_code));
     }
 
     pub fn burn_batch_completed(
@@ -131,7 +250,40 @@ impl TransferEvents {
         total_burned: i128,
     ) {
         let topics = (symbol_short!("burn"), symbol_short!("completed"), batch_id);
-        env.events()
-            .publish(topics, (successful, failed, total_burned));
+        events::publish(env, topics, (successful, failed, total_burned));
+    }
+
+    /// Emitted when a rejected leg is moved into the quarantine queue.
+    pub fn leg_quarantined(env: &Env, leg: &QuarantinedLeg) {
+        let topics = (
+            symbol_short!("quarantn"),
+            symbol_short!("held"),
+            leg.batch_id,
+        );
+        events::publish(env, topics, (leg.leg_id, leg.recipient.clone(), leg.amount));
+    }
+
+    /// Emitted when a quarantined leg is paid out and leaves the queue.
+    pub fn leg_released(env: &Env, leg: &QuarantinedLeg) {
+        let topics = (
+            symbol_short!("quarantn"),
+            symbol_short!("released"),
+            leg.batch_id,
+        );
+        events::publish(env, topics, (leg.leg_id, leg.recipient.clone(), leg.amount));
+    }
+
+    /// Emitted when a retry of a quarantined leg fails again.
+    pub fn leg_retry_failed(env: &Env, leg: &QuarantinedLeg) {
+        let topics = (
+            symbol_short!("quarantn"),
+            symbol_short!("retryfail"),
+            leg.batch_id,
+        );
+        events::publish(
+            env,
+            topics,
+            (leg.leg_id, leg.recipient.clone(), leg.failed_retries),
+        );
     }
 }
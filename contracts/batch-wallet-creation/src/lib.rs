@@ -4,6 +4,7 @@
 mod types;
 mod validation;
 
+use common::versioning;
 use soroban_sdk::{
     contract, contractimpl, panic_with_error, Address, Env, Vec,
 };
@@ -28,6 +29,10 @@ pub enum BatchWalletError {
     EmptyBatch = 4,
     /// Batch exceeds maximum size
     BatchTooLarge = 5,
+    /// Storage was written by a different layout version
+    StorageVersionMismatch = 6,
+    /// Migration source version does not match stored version
+    InvalidMigration = 7,
 }
 
 impl From<BatchWalletError> for soroban_sdk::Error {
@@ -36,6 +41,9 @@ impl From<BatchWalletError> for soroban_sdk::Error {
     }
 }
 
+/// Storage layout version this code expects.
+pub const STORAGE_VERSION: u32 = 1;
+
 #[contract]
 pub struct BatchWalletContract;
 
@@ -48,16 +56,36 @@ impl BatchWalletContract {
         }
 
         env.storage().instance().set(&DataKey::Admin, &admin);
+        versioning::set_version(&env, STORAGE_VERSION);
         env.storage().instance().set(&DataKey::TotalBatches, &0u64);
         env.storage().instance().set(&DataKey::TotalWalletsCreated, &0u64);
     }
 
+    /// Migrates storage written by an older version of this contract to the
+    /// current layout (admin only). `from_version` must match the stored
+    /// version.
+    pub fn migrate(env: Env, caller: Address, from_version: u32) {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        // Version 1 only adds the version stamp; older layouts are otherwise
+        // unchanged, so there is nothing to rewrite
+        versioning::migrate(&env, from_version, STORAGE_VERSION, |_, _| {})
+            .unwrap_or_else(|_| panic_with_error!(&env, BatchWalletError::InvalidMigration));
+    }
+
+    /// Returns the storage layout version currently stored.
+    pub fn get_storage_version(env: Env) -> u32 {
+        versioning::stored_version(&env)
+    }
+
     /// Executes batch creation of wallets for multiple owners.
     pub fn batch_create_wallets(
         env: Env,
         caller: Address,
         requests: Vec<WalletCreateRequest>,
     ) -> BatchCreateResult {
+        Self::require_storage_version(&env);
         // Verify authorization
         caller.require_auth();
         Self::require_admin(&env, &caller);
@@ -191,6 +219,7 @@ impl BatchWalletContract {
         caller: Address,
         requests: Vec<WalletRecoveryRequest>,
     ) -> BatchRecoveryResult {
+        Self::require_storage_version(&env);
         caller.require_auth();
         Self::require_admin(&env, &caller);
 
@@ -308,6 +337,7 @@ impl BatchWalletContract {
 
     /// Updates the admin address.
     pub fn set_admin(env: Env, current_admin: Address, new_admin: Address) {
+        Self::require_storage_version(&env);
         current_admin.require_auth();
         Self::require_admin(&env, &current_admin);
 
@@ -335,6 +365,13 @@ impl BatchWalletContract {
         env.storage().persistent().get(&DataKey::Wallets(address))
     }
 
+    // Internal helper refusing to run against storage written by a different
+    // layout version
+    fn require_storage_version(env: &Env) {
+        versioning::require_version(env, STORAGE_VERSION)
+            .unwrap_or_else(|_| panic_with_error!(env, BatchWalletError::StorageVersionMismatch));
+    }
+
     // Internal helper to verify admin
     fn require_admin(env: &Env, caller: &Address) {
         let admin: Address = env
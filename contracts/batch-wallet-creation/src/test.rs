@@ -59,7 +59,7 @@ fn test_initialize_contract() {
 #[test]
 #[should_panic(expected = "Contract already initialized")]
 fn test_cannot_initialize_twice() {
-    let (env, admin, client) = setup_test_env();
+    let (env, _admin, client) = setup_test_env();
 
     let new_admin = Address::generate(&env);
     client.initialize(&new_admin);
@@ -225,7 +225,7 @@ fn test_batch_create_wallets_empty_batch() {
 #[test]
 #[should_panic]
 fn test_batch_create_wallets_unauthorized() {
-    let (env, admin, client) = setup_test_env();
+    let (env, _admin, client) = setup_test_env();
 
     let unauthorized = Address::generate(&env);
     let owner = Address::generate(&env);
@@ -1,3 +1,4 @@
+use common::events;
 use soroban_sdk::{contracttype, symbol_short, Address, Env, Vec};
 
 pub const MAX_BATCH_SIZE: u32 = 100;
@@ -69,12 +70,12 @@ pub struct WalletEvents;
 impl WalletEvents {
     pub fn batch_started(env: &Env, batch_id: u64, request_count: u32) {
         let topics = (symbol_short!("batch"), symbol_short!("started"));
-        env.events().publish(topics, (batch_id, request_count));
+        events::publish(env, topics, (batch_id, request_count));
     }
 
     pub fn wallet_created(env: &Env, batch_id: u64, owner: &Address, wallet_id: u64) {
         let topics = (symbol_short!("wallet"), symbol_short!("created"), batch_id);
-        env.events().publish(topics, (owner.clone(), wallet_id));
+        events::publish(env, topics, (owner.clone(), wallet_id));
     }
 
     pub fn wallet_creation_failure(
@@ -84,7 +85,7 @@ impl WalletEvents {
         error_code: u32,
     ) {
         let topics = (symbol_short!("wallet"), symbol_short!("failure"), batch_id);
-        env.events().publish(topics, (owner.clone(), error_code));
+        events::publish(env, topics, (owner.clone(), error_code));
     }
 
     pub fn batch_completed(
@@ -94,12 +95,12 @@ impl WalletEvents {
         failed: u32,
     ) {
         let topics = (symbol_short!("batch"), symbol_short!("completed"), batch_id);
-        env.events().publish(topics, (successful, failed));
+        events::publish(env, topics, (successful, failed));
     }
 
     pub fn recovery_started(env: &Env, batch_id: u64, request_count: u32) {
         let topics = (symbol_short!("recovery"), symbol_short!("started"));
-        env.events().publish(topics, (batch_id, request_count));
+        events::publish(env, topics, (batch_id, request_count));
     }
 
     pub fn wallet_recovered(
@@ -110,8 +111,11 @@ impl WalletEvents {
         wallet_id: u64,
     ) {
         let topics = (symbol_short!("recovery"), symbol_short!("success"), batch_id);
-        env.events()
-            .publish(topics, (old_owner.clone(), new_owner.clone(), wallet_id));
+        events::publish(
+            env,
+            topics,
+            (old_owner.clone(), new_owner.clone(), wallet_id),
+        );
     }
 
     pub fn wallet_recovery_failure(
@@ -122,7 +126,8 @@ impl WalletEvents {
         error_code: u32,
     ) {
         let topics = (symbol_short!("recovery"), symbol_short!("failure"), batch_id);
-        env.events().publish(
+        events::publish(
+            env,
             topics,
             (old_owner.clone(), new_owner.clone(), error_code),
         );
@@ -135,6 +140,6 @@ impl WalletEvents {
         failed: u32,
     ) {
         let topics = (symbol_short!("recovery"), symbol_short!("completed"), batch_id);
-        env.events().publish(topics, (successful, failed));
+        events::publish(env, topics, (successful, failed));
     }
 }
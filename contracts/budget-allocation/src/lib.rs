@@ -9,14 +9,45 @@
 //! - **Atomic Updates**: Ensures reliable state changes for each user
 //! - **Validation**: Prevents invalid budget amounts
 //! - **Event Emission**: Tracks budget updates and failures
+//! - **Threshold Alerts**: Emits a `budget_alert` event once per threshold per period
+//! - **Project Envelopes**: Project-scoped budgets with burn-down queries
+//! - **Budget Owners**: Owners holding a role scoped to one project may record
+//!   spends against it, resolved by the access control contract
+//! - **Prepaid Amortization**: A large prepaid spend can be consumed from the
+//!   budget in equal installments over several periods
+//! - **Spend Simulation**: Previews whether a spend would be accepted, and which
+//!   rule would reject it, without changing any state
+//! - **Period Close**: Closing a period freezes it, snapshots its final figures
+//!   and opens the next period; the admin can reopen it with a reason code
+//! - **Auto-Renewal**: Unspent budget expires, rolls over up to a cap, or accumulates
+//!   when a period rollover is triggered
+//! - **Reference Currency**: Budgets are expressed in the reference currency;
+//!   changing it flags budgets allocated earlier as pre-change
+//! - **Token Spends**: Spends made in allowlisted tokens are converted into the
+//!   reference token at oracle prices before they count against a budget
 //!
 #![no_std]
 
 mod test;
 mod types;
 
-use crate::types::{BatchBudgetResult, BudgetRecord, BudgetRequest, DataKey};
-use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env, Vec};
+use crate::types::{
+    BatchBudgetResult, BudgetRecord, BudgetRequest, BudgetUsage, BurnDown, CarryOver,
+    CarryOverMode, DataKey, PeriodClose, PeriodSnapshot, PrepaidSchedule, ProjectBudget,
+    ReopenReason, SpendRule, SpendSimulation, BUDGET_PERIOD_SECONDS, MAX_ALERT_THRESHOLDS,
+    MAX_ALERT_THRESHOLD_BPS, MAX_AMORTIZATION_PERIODS, MAX_PREPAID_SCHEDULES,
+};
+use access_control_interface::{AccessControlClient, Role, Scope};
+use common::events;
+use common::limits::{self, Limit};
+use common::math::{self, Rounding, BPS_DENOMINATOR};
+use common::normalize::{self, NormalizeError};
+use common::reference::{self, ReferenceCurrency};
+use common::versioning;
+use soroban_sdk::{contract, contractimpl, symbol_short, vec, Address, Env, Symbol, Vec};
+
+/// Storage layout version this code expects.
+pub const STORAGE_VERSION: u32 = 1;
 
 #[contract]
 pub struct BudgetAllocationContract;
@@ -29,6 +60,25 @@ impl BudgetAllocationContract {
             panic!("Already initialized");
         }
         env.storage().instance().set(&DataKey::Admin, &admin);
+        versioning::set_version(&env, STORAGE_VERSION);
+    }
+
+    /// Migrates storage written by an older version of this contract to the
+    /// current layout (admin only). `from_version` must match the stored
+    /// version.
+    pub fn migrate(env: Env, caller: Address, from_version: u32) {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        // Version 1 only adds the version stamp; older layouts are otherwise
+        // unchanged, so there is nothing to rewrite
+        versioning::migrate(&env, from_version, STORAGE_VERSION, |_, _| {})
+            .unwrap_or_else(|_| panic!("Invalid migration"));
+    }
+
+    /// Returns the storage layout version currently stored.
+    pub fn get_storage_version(env: Env) -> u32 {
+        versioning::stored_version(&env)
     }
 
     /// Assigns monthly budgets to multiple users in a single operation.
@@ -42,6 +92,7 @@ impl BudgetAllocationContract {
         admin: Address,
         requests: Vec<BudgetRequest>,
     ) -> BatchBudgetResult {
+        Self::require_storage_version(&env);
         // Verify admin authority
         admin.require_auth();
         let stored_admin: Address = env
@@ -57,15 +108,18 @@ impl BudgetAllocationContract {
         let mut failed = 0;
         let mut total_amount: i128 = 0;
         let current_time = env.ledger().timestamp();
+        let epoch = reference::get(&env).epoch;
 
         for req in requests.iter() {
             // Validate input amount
             if req.amount < 0 {
                 failed += 1;
                 // Emit failure event?
-                env.events().publish(
+                events::publish(
+                    &env,
                     (symbol_short!("budget"), symbol_short!("failed")),
-                    (req.user, req.amount), // Amount is negative here
+                    (req.user, req.amount),
+                    // Amount is negative here,
                 );
                 continue;
             }
@@ -80,9 +134,13 @@ impl BudgetAllocationContract {
             env.storage()
                 .persistent()
                 .set(&DataKey::Budget(req.user.clone()), &record);
+            env.storage()
+                .persistent()
+                .set(&DataKey::BudgetEpoch(req.user.clone()), &epoch);
 
             // Emit update event
-            env.events().publish(
+            events::publish(
+                &env,
                 (symbol_short!("budget"), symbol_short!("set")),
                 (req.user, req.amount),
             );
@@ -104,6 +162,45 @@ impl BudgetAllocationContract {
         env.storage().persistent().get(&DataKey::Budget(user))
     }
 
+    /// Switches the reference currency budgets are expressed in (admin only,
+    /// which is the protocol's governance for this contract).
+    ///
+    /// The change starts a new reference epoch and publishes a `restated`
+    /// event. Budgets allocated before it are reported as pre-change until
+    /// they are allocated again.
+    pub fn set_reference_currency(env: Env, admin: Address, code: Symbol, decimals: u32) {
+        Self::require_storage_version(&env);
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        reference::set(&env, code, decimals)
+            .unwrap_or_else(|_| panic!("Invalid reference currency"));
+    }
+
+    /// Gets the reference currency in effect.
+    pub fn get_reference_currency(env: Env) -> ReferenceCurrency {
+        reference::get(&env)
+    }
+
+    /// Returns true if a user's budget was allocated under an earlier
+    /// reference currency than the one in effect. Budgets allocated before
+    /// epochs were tracked count as epoch 0.
+    pub fn is_budget_pre_change(env: Env, user: Address) -> bool {
+        if !env
+            .storage()
+            .persistent()
+            .has(&DataKey::Budget(user.clone()))
+        {
+            return false;
+        }
+        let epoch: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::BudgetEpoch(user))
+            .unwrap_or(0);
+        reference::is_pre_change(&env, epoch)
+    }
+
     /// Returns the admin address
     pub fn get_admin(env: Env) -> Address {
         env.storage()
@@ -111,4 +208,912 @@ impl BudgetAllocationContract {
             .get(&DataKey::Admin)
             .expect("Not initialized")
     }
+
+    /// Sets the consumption thresholds (in basis points of the budget) that
+    /// trigger a `budget_alert` event. Thresholds must be strictly ascending.
+    pub fn set_alert_thresholds(env: Env, admin: Address, thresholds: Vec<u32>) {
+        Self::require_storage_version(&env);
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        if thresholds.is_empty() || thresholds.len() > MAX_ALERT_THRESHOLDS {
+            panic!("Invalid thresholds");
+        }
+        let mut previous = 0u32;
+        for threshold in thresholds.iter() {
+            if threshold <= previous || threshold > MAX_ALERT_THRESHOLD_BPS {
+                panic!("Invalid thresholds");
+            }
+            previous = threshold;
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::AlertThresholds, &thresholds);
+    }
+
+    /// Returns the configured alert thresholds (defaults to 50%, 80% and 100%).
+    pub fn get_alert_thresholds(env: Env) -> Vec<u32> {
+        env.storage()
+            .instance()
+            .get(&DataKey::AlertThresholds)
+            .unwrap_or(vec![&env, 5_000u32, 8_000u32, 10_000u32])
+    }
+
+    /// Records a spend against a user's budget for the current period.
+    ///
+    /// Usage resets when a new period starts, to the installments of any prepaid
+    /// spends amortized into it. Each configured threshold that the cumulative
+    /// spend crosses emits a `budget_alert` event exactly once per period.
+    ///
+    /// # Returns
+    /// * The updated usage for the user
+    pub fn record_spend(env: Env, admin: Address, user: Address, amount: i128) -> BudgetUsage {
+        Self::require_storage_version(&env);
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        if amount <= 0 {
+            panic!("Invalid amount");
+        }
+        Self::charge(&env, &user, amount)
+    }
+
+    /// Sets the price oracle and the reference token that token spends are
+    /// converted into (admin only). The reference token is allowlisted.
+    pub fn set_token_conversion(
+        env: Env,
+        admin: Address,
+        oracle: Address,
+        reference_token: Address,
+    ) {
+        Self::require_storage_version(&env);
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        normalize::allow_token(&env, &reference_token)
+            .unwrap_or_else(|e| Self::conversion_error(e));
+        env.storage().instance().set(&DataKey::PriceOracle, &oracle);
+        env.storage()
+            .instance()
+            .set(&DataKey::ReferenceToken, &reference_token);
+    }
+
+    /// Returns the price oracle and reference token used for token spends.
+    pub fn get_token_conversion(env: Env) -> Option<(Address, Address)> {
+        let oracle: Option<Address> = env.storage().instance().get(&DataKey::PriceOracle);
+        let reference_token: Option<Address> =
+            env.storage().instance().get(&DataKey::ReferenceToken);
+        oracle.zip(reference_token)
+    }
+
+    /// Allowlists a token spends may be recorded in (admin only), caching its
+    /// decimals. Returns the decimals.
+    pub fn allow_spend_token(env: Env, admin: Address, token: Address) -> u32 {
+        Self::require_storage_version(&env);
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        normalize::allow_token(&env, &token).unwrap_or_else(|e| Self::conversion_error(e))
+    }
+
+    /// Records a spend made in `token` against a user's budget. The amount is
+    /// converted into the reference token at the oracle's price first, so
+    /// tokens of different precision never add up raw.
+    ///
+    /// # Returns
+    /// * The updated usage for the user
+    pub fn record_token_spend(
+        env: Env,
+        admin: Address,
+        user: Address,
+        token: Address,
+        amount: i128,
+    ) -> BudgetUsage {
+        Self::require_storage_version(&env);
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        if amount <= 0 {
+            panic!("Invalid amount");
+        }
+        let (oracle, reference_token) =
+            Self::get_token_conversion(env.clone()).expect("Token conversion not configured");
+        let converted = normalize::convert(&env, &oracle, amount, &token, &reference_token)
+            .unwrap_or_else(|e| Self::conversion_error(e));
+        Self::charge(&env, &user, converted)
+    }
+
+    /// Retrieves the spending tracked against a user's budget.
+    pub fn get_budget_usage(env: Env, user: Address) -> Option<BudgetUsage> {
+        env.storage().persistent().get(&DataKey::Usage(user))
+    }
+
+    /// Sets what happens to a user's unspent budget at the end of each period
+    /// (admin only). Budgets expire by default.
+    pub fn set_carry_over_mode(env: Env, admin: Address, user: Address, mode: CarryOverMode) {
+        Self::require_storage_version(&env);
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        if let CarryOverMode::RollOver(cap_bps) = mode {
+            if cap_bps > BPS_DENOMINATOR as u32 {
+                panic!("Invalid carry-over cap");
+            }
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::CarryOverMode(user), &mode);
+    }
+
+    /// Returns the carry-over mode for a user's budget.
+    pub fn get_carry_over_mode(env: Env, user: Address) -> CarryOverMode {
+        env.storage()
+            .persistent()
+            .get(&DataKey::CarryOverMode(user))
+            .unwrap_or(CarryOverMode::Expire)
+    }
+
+    /// Rolls the given budgets over into the current period. Anyone may call
+    /// this once a period boundary has passed.
+    ///
+    /// The unspent part of each budget's previous period is carried in
+    /// according to its carry-over mode, and a `carried` event records the
+    /// amount. Budgets already rolled over this period, including by the
+    /// period's first spend, keep their carried amount; users without a budget
+    /// are skipped with a carry of zero.
+    ///
+    /// # Returns
+    /// * The amount carried into the current period, per user
+    pub fn rollover_budgets(env: Env, users: Vec<Address>) -> Vec<i128> {
+        Self::require_storage_version(&env);
+        if users.len() > limits::get(&env, Limit::BatchSize) {
+            panic!("Batch too large");
+        }
+
+        let period = Self::current_period(&env);
+        let mut carried = Vec::new(&env);
+        for user in users.iter() {
+            carried.push_back(Self::rollover(&env, &user, period));
+        }
+        carried
+    }
+
+    /// Returns the budget carried into the current period for a user.
+    pub fn get_carry_over(env: Env, user: Address) -> i128 {
+        let period = Self::current_period(&env);
+        Self::carried_into(&env, &user, period)
+    }
+
+    /// Returns a user's budget for the current period, including any amount
+    /// carried over from the previous period.
+    pub fn get_available_budget(env: Env, user: Address) -> i128 {
+        let budget: BudgetRecord = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Budget(user.clone()))
+            .expect("Budget not found");
+        let period = Self::current_period(&env);
+        Self::available_in(&env, &user, &budget, period)
+    }
+
+    /// Records a prepaid spend, such as an annual license, that is consumed
+    /// from the user's budget over `periods` periods rather than all at once
+    /// (admin only).
+    ///
+    /// The first installment is charged to the current period straight away;
+    /// each later installment counts toward the usage of its own period.
+    pub fn record_prepaid_spend(
+        env: Env,
+        admin: Address,
+        user: Address,
+        amount: i128,
+        periods: u32,
+    ) -> PrepaidSchedule {
+        Self::require_storage_version(&env);
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        if amount <= 0 {
+            panic!("Invalid amount");
+        }
+        if periods == 0 || periods > MAX_AMORTIZATION_PERIODS {
+            panic!("Invalid amortization periods");
+        }
+
+        let period = Self::current_period(&env);
+        let mut live: Vec<u64> = Vec::new(&env);
+        for schedule in Self::get_prepaid_schedules(env.clone(), user.clone()).iter() {
+            if Self::amortized_through(&schedule, period) < schedule.total {
+                live.push_back(schedule.schedule_id);
+            }
+        }
+        if live.len() >= MAX_PREPAID_SCHEDULES {
+            panic!("Too many prepaid schedules");
+        }
+
+        let schedule_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::NextPrepaidId)
+            .unwrap_or(1);
+        let schedule = PrepaidSchedule {
+            schedule_id,
+            user: user.clone(),
+            total: amount,
+            periods,
+            start_period: period,
+        };
+
+        // Charge the first installment before the schedule is stored, so the
+        // current period's usage does not pick it up twice
+        Self::charge(&env, &user, Self::amortized_through(&schedule, period));
+
+        live.push_back(schedule_id);
+        env.storage()
+            .instance()
+            .set(&DataKey::NextPrepaidId, &(schedule_id + 1));
+        env.storage()
+            .persistent()
+            .set(&DataKey::Prepaid(schedule_id), &schedule);
+        env.storage()
+            .persistent()
+            .set(&DataKey::PrepaidSchedules(user.clone()), &live);
+        events::publish(
+            &env,
+            (symbol_short!("prepaid"), symbol_short!("created")),
+            (schedule_id, user, amount, periods),
+        );
+
+        schedule
+    }
+
+    /// Retrieves a prepaid spend's amortization schedule.
+    pub fn get_prepaid_schedule(env: Env, schedule_id: u64) -> Option<PrepaidSchedule> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Prepaid(schedule_id))
+    }
+
+    /// Returns a user's amortization schedules, excluding those found fully
+    /// consumed when the user's last prepaid spend was recorded.
+    pub fn get_prepaid_schedules(env: Env, user: Address) -> Vec<PrepaidSchedule> {
+        let ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PrepaidSchedules(user))
+            .unwrap_or(Vec::new(&env));
+        let mut schedules = Vec::new(&env);
+        for id in ids.iter() {
+            if let Some(schedule) = Self::get_prepaid_schedule(env.clone(), id) {
+                schedules.push_back(schedule);
+            }
+        }
+        schedules
+    }
+
+    /// Returns how much of a user's prepaid spends has not yet been consumed
+    /// from their budget, as of the end of the current period.
+    pub fn get_unamortized_balance(env: Env, user: Address) -> i128 {
+        let period = Self::current_period(&env);
+        let mut balance: i128 = 0;
+        for schedule in Self::get_prepaid_schedules(env.clone(), user).iter() {
+            let remaining = schedule.total - Self::amortized_through(&schedule, period);
+            balance = balance.saturating_add(remaining);
+        }
+        balance
+    }
+
+    /// Creates a budget envelope for a project (admin only).
+    ///
+    /// Project budgets are separate from per-user budgets: spends tagged to the
+    /// project with `record_project_spend` draw them down until the deadline.
+    pub fn create_project_budget(
+        env: Env,
+        admin: Address,
+        project_id: u64,
+        total: i128,
+        deadline: u64,
+    ) -> ProjectBudget {
+        Self::require_storage_version(&env);
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        if total <= 0 {
+            panic!("Invalid amount");
+        }
+        let now = env.ledger().timestamp();
+        if deadline <= now {
+            panic!("Invalid deadline");
+        }
+        if env
+            .storage()
+            .persistent()
+            .has(&DataKey::Project(project_id))
+        {
+            panic!("Project already exists");
+        }
+
+        let project = ProjectBudget {
+            project_id,
+            total,
+            spent: 0,
+            created_at: now,
+            deadline,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Project(project_id), &project);
+        events::publish(
+            &env,
+            (symbol_short!("project"), symbol_short!("created")),
+            (project_id, total, deadline),
+        );
+
+        project
+    }
+
+    /// Sets the access control contract that resolves budget owners (admin
+    /// only).
+    pub fn set_access_control(env: Env, admin: Address, access_control: Address) {
+        Self::require_storage_version(&env);
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::AccessControl, &access_control);
+    }
+
+    /// Returns the access control contract resolving budget owners, if set.
+    pub fn get_access_control(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::AccessControl)
+    }
+
+    /// Records a spend tagged to a project, decrementing its envelope.
+    ///
+    /// Callable by the admin, or by a budget owner: anyone the access control
+    /// contract grants the Operator role on this project's scope itself,
+    /// within their operating hours. A global Operator grant does not make
+    /// anyone an owner. Spends that would exceed the envelope are rejected.
+    pub fn record_project_spend(
+        env: Env,
+        caller: Address,
+        project_id: u64,
+        amount: i128,
+    ) -> ProjectBudget {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        if !Self::is_project_owner(&env, &caller, project_id) {
+            Self::require_admin(&env, &caller);
+        }
+
+        if amount <= 0 {
+            panic!("Invalid amount");
+        }
+        let mut project = Self::load_project(&env, project_id);
+        let spent = project.spent.checked_add(amount).expect("Overflow");
+        if spent > project.total {
+            panic!("Project budget exceeded");
+        }
+        project.spent = spent;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Project(project_id), &project);
+        events::publish(
+            &env,
+            (symbol_short!("project"), symbol_short!("spend")),
+            (project_id, amount, project.total - spent),
+        );
+
+        project
+    }
+
+    /// Retrieves a project budget.
+    pub fn get_project_budget(env: Env, project_id: u64) -> Option<ProjectBudget> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+    }
+
+    /// Returns spent-versus-remaining-versus-time for a project, so its runway
+    /// can be tracked on-chain.
+    pub fn get_project_burn_down(env: Env, project_id: u64) -> BurnDown {
+        let project = Self::load_project(&env, project_id);
+        let now = env.ledger().timestamp();
+
+        let duration = project.deadline - project.created_at;
+        let elapsed = now.saturating_sub(project.created_at);
+        let elapsed_bps = math::ratio_bps(elapsed.min(duration) as i128, duration as i128)
+            .unwrap_or(BPS_DENOMINATOR as u32);
+        let spent_bps = math::ratio_bps(project.spent, project.total).unwrap_or(u32::MAX);
+        // Extrapolate the burn rate so far over the whole duration
+        let projected_spend = if elapsed == 0 {
+            project.spent
+        } else {
+            math::saturating_mul_div(
+                project.spent,
+                duration as i128,
+                elapsed as i128,
+                Rounding::Up,
+            )
+            .unwrap_or(i128::MAX)
+        };
+
+        BurnDown {
+            total: project.total,
+            spent: project.spent,
+            remaining: project.total - project.spent,
+            spent_bps,
+            elapsed,
+            time_remaining: project.deadline.saturating_sub(now),
+            elapsed_bps,
+            projected_spend,
+        }
+    }
+
+    /// Closes the current period (admin only).
+    ///
+    /// The closed period is frozen: from now on spends are charged to the
+    /// next period, which opens straight away rather than at the period
+    /// boundary. The final figures of each given user's budget are
+    /// snapshotted, along with totals across them.
+    pub fn close_period(env: Env, admin: Address, users: Vec<Address>) -> PeriodClose {
+        Self::require_storage_version(&env);
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        if users.len() > limits::get(&env, Limit::BatchSize) {
+            panic!("Batch too large");
+        }
+
+        let period = Self::current_period(&env);
+        if period > env.ledger().timestamp() / BUDGET_PERIOD_SECONDS {
+            panic!("Period not started");
+        }
+
+        let mut close = PeriodClose {
+            period,
+            closed_at: env.ledger().timestamp(),
+            budgets: 0,
+            total_available: 0,
+            total_spent: 0,
+        };
+        for user in users.iter() {
+            let Some(budget) = Self::get_budget(env.clone(), user.clone()) else {
+                continue;
+            };
+            let snapshot = PeriodSnapshot {
+                user: user.clone(),
+                period,
+                budget: budget.amount,
+                available: Self::available_in(&env, &user, &budget, period),
+                spent: Self::usage_in(&env, &user, period).spent,
+            };
+            env.storage()
+                .persistent()
+                .set(&DataKey::PeriodSnapshot(user, period), &snapshot);
+
+            close.budgets += 1;
+            close.total_available = close.total_available.saturating_add(snapshot.available);
+            close.total_spent = close.total_spent.saturating_add(snapshot.spent);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::PeriodClose(period), &close);
+        env.storage()
+            .instance()
+            .set(&DataKey::LastClosedPeriod, &period);
+        events::publish(
+            &env,
+            (symbol_short!("period"), symbol_short!("closed")),
+            close.clone(),
+        );
+
+        close
+    }
+
+    /// Reopens the most recently closed period while it is still running
+    /// (admin only), e.g. to record a late correction. Not possible once a
+    /// spend has been charged to the next period.
+    pub fn reopen_period(env: Env, admin: Address, reason: ReopenReason) -> u64 {
+        Self::require_storage_version(&env);
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        let period: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::LastClosedPeriod)
+            .expect("No closed period");
+        if period != env.ledger().timestamp() / BUDGET_PERIOD_SECONDS {
+            panic!("Period already ended");
+        }
+        if env
+            .storage()
+            .instance()
+            .has(&DataKey::EarlySpend(period + 1))
+        {
+            panic!("Next period in use");
+        }
+
+        match period.checked_sub(1) {
+            Some(previous) => env
+                .storage()
+                .instance()
+                .set(&DataKey::LastClosedPeriod, &previous),
+            None => env.storage().instance().remove(&DataKey::LastClosedPeriod),
+        }
+        env.storage()
+            .persistent()
+            .remove(&DataKey::PeriodClose(period));
+        events::publish(
+            &env,
+            (symbol_short!("period"), symbol_short!("reopened")),
+            (period, reason, admin),
+        );
+
+        period
+    }
+
+    /// Returns the period spends are currently charged to.
+    pub fn get_current_period(env: Env) -> u64 {
+        Self::current_period(&env)
+    }
+
+    /// Returns the totals recorded when a period was closed, if it is closed.
+    pub fn get_period_close(env: Env, period: u64) -> Option<PeriodClose> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PeriodClose(period))
+    }
+
+    /// Returns a user's budget figures as of the last close of `period`.
+    pub fn get_period_snapshot(env: Env, user: Address, period: u64) -> Option<PeriodSnapshot> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PeriodSnapshot(user, period))
+    }
+
+    /// Previews a spend without changing any state, so a client can warn the
+    /// spender before submitting it.
+    ///
+    /// With no `project_id` the spend is checked as `record_spend` would
+    /// charge it to `spender`'s budget; going over budget does not block it
+    /// but shows in `spent_after` and `alerts`. With a `project_id` it is
+    /// checked as `record_project_spend` by `spender` would record it.
+    pub fn simulate_spend(
+        env: Env,
+        spender: Address,
+        project_id: Option<u64>,
+        amount: i128,
+    ) -> SpendSimulation {
+        let mut simulation = SpendSimulation {
+            allowed: false,
+            blocked_by: SpendRule::None,
+            spent_after: 0,
+            limit: 0,
+            alerts: Vec::new(&env),
+        };
+        let blocked_by = match project_id {
+            Some(project_id) => {
+                Self::simulate_project(&env, &spender, project_id, amount, &mut simulation)
+            }
+            None => Self::simulate_budget(&env, &spender, amount, &mut simulation),
+        };
+        simulation.allowed = blocked_by.is_none();
+        simulation.blocked_by = blocked_by.unwrap_or(SpendRule::None);
+        simulation
+    }
+
+    // Internal helper simulating `record_spend` against a user's budget,
+    // returning the rule that would reject it, if any
+    fn simulate_budget(
+        env: &Env,
+        user: &Address,
+        amount: i128,
+        simulation: &mut SpendSimulation,
+    ) -> Option<SpendRule> {
+        if amount <= 0 {
+            return Some(SpendRule::InvalidAmount);
+        }
+        let Some(budget) = Self::get_budget(env.clone(), user.clone()) else {
+            return Some(SpendRule::BudgetNotFound);
+        };
+
+        let period = Self::current_period(env);
+        let usage = Self::usage_in(env, user, period);
+        simulation.limit = Self::available_in(env, user, &budget, period);
+        simulation.spent_after = usage.spent;
+        let Some(spent) = usage.spent.checked_add(amount) else {
+            return Some(SpendRule::Overflow);
+        };
+        simulation.spent_after = spent;
+        simulation.alerts = Self::crossed_thresholds(env, &usage, spent, simulation.limit);
+        None
+    }
+
+    // Internal helper simulating `record_project_spend` by `caller`,
+    // returning the rule that would reject it, if any
+    fn simulate_project(
+        env: &Env,
+        caller: &Address,
+        project_id: u64,
+        amount: i128,
+        simulation: &mut SpendSimulation,
+    ) -> Option<SpendRule> {
+        let admin: Option<Address> = env.storage().instance().get(&DataKey::Admin);
+        if admin.as_ref() != Some(caller) && !Self::is_project_owner(env, caller, project_id) {
+            return Some(SpendRule::Unauthorized);
+        }
+        if amount <= 0 {
+            return Some(SpendRule::InvalidAmount);
+        }
+        let Some(project) = Self::get_project_budget(env.clone(), project_id) else {
+            return Some(SpendRule::ProjectNotFound);
+        };
+
+        simulation.limit = project.total;
+        simulation.spent_after = project.spent;
+        let Some(spent) = project.spent.checked_add(amount) else {
+            return Some(SpendRule::Overflow);
+        };
+        simulation.spent_after = spent;
+        if spent > project.total {
+            return Some(SpendRule::ProjectBudgetExceeded);
+        }
+        None
+    }
+
+    // Internal helper adding `amount` to a user's usage for the current period
+    // and emitting any threshold alerts it crosses
+    fn charge(env: &Env, user: &Address, amount: i128) -> BudgetUsage {
+        let budget: BudgetRecord = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Budget(user.clone()))
+            .expect("Budget not found");
+
+        let period = Self::current_period(env);
+        if period > env.ledger().timestamp() / BUDGET_PERIOD_SECONDS {
+            // The closed period can no longer be reopened
+            env.storage()
+                .instance()
+                .set(&DataKey::EarlySpend(period), &true);
+        }
+        // Only the latest period's usage is stored, so the first spend of a
+        // period rolls the budget over before the previous usage is replaced
+        let stored = Self::get_budget_usage(env.clone(), user.clone());
+        if matches!(stored, Some(usage) if usage.period < period) {
+            Self::rollover(env, user, period);
+        }
+        let mut usage = Self::usage_in(env, user, period);
+        usage.spent = usage.spent.checked_add(amount).expect("Overflow");
+
+        let available = Self::available_in(env, user, &budget, period);
+        for threshold in Self::crossed_thresholds(env, &usage, usage.spent, available).iter() {
+            usage.alerted_thresholds.push_back(threshold);
+            events::publish(
+                env,
+                (Symbol::new(env, "budget_alert"), threshold),
+                (user.clone(), period, usage.spent, available),
+            );
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Usage(user.clone()), &usage);
+
+        usage
+    }
+
+    // Internal helper returning the alert thresholds reached at `spent` that
+    // have not been alerted on yet in `usage`'s period
+    fn crossed_thresholds(
+        env: &Env,
+        usage: &BudgetUsage,
+        spent: i128,
+        available: i128,
+    ) -> Vec<u32> {
+        let consumed_bps = if available <= 0 {
+            i128::MAX
+        } else {
+            math::saturating_mul_div(spent, BPS_DENOMINATOR, available, Rounding::Down)
+                .unwrap_or(i128::MAX)
+        };
+
+        let mut crossed = Vec::new(env);
+        for threshold in Self::get_alert_thresholds(env.clone()).iter() {
+            if consumed_bps >= threshold as i128 && !usage.alerted_thresholds.contains(threshold) {
+                crossed.push_back(threshold);
+            }
+        }
+        crossed
+    }
+
+    // Internal helper returning a user's usage for `period`. A period with no
+    // recorded spends starts from the installments amortized into it.
+    fn usage_in(env: &Env, user: &Address, period: u64) -> BudgetUsage {
+        match Self::get_budget_usage(env.clone(), user.clone()) {
+            Some(usage) if usage.period == period => usage,
+            _ => BudgetUsage {
+                user: user.clone(),
+                period,
+                spent: Self::amortized_in(env, user, period),
+                alerted_thresholds: Vec::new(env),
+            },
+        }
+    }
+
+    // Internal helper summing the installments of a user's prepaid spends
+    // that fall in `period`
+    fn amortized_in(env: &Env, user: &Address, period: u64) -> i128 {
+        let mut total: i128 = 0;
+        for schedule in Self::get_prepaid_schedules(env.clone(), user.clone()).iter() {
+            let before = match period.checked_sub(1) {
+                Some(previous) => Self::amortized_through(&schedule, previous),
+                None => 0,
+            };
+            let installment = Self::amortized_through(&schedule, period) - before;
+            total = total.saturating_add(installment);
+        }
+        total
+    }
+
+    // Internal helper returning how much of a prepaid spend has been consumed
+    // by the end of `period`. Installments are equal, with the rounding
+    // remainder taken in the last one.
+    fn amortized_through(schedule: &PrepaidSchedule, period: u64) -> i128 {
+        if period < schedule.start_period {
+            return 0;
+        }
+        let elapsed = period - schedule.start_period + 1;
+        if elapsed >= schedule.periods as u64 {
+            return schedule.total;
+        }
+        schedule.total / schedule.periods as i128 * elapsed as i128
+    }
+
+    // Internal helper checking whether `caller` owns a project's budget and
+    // is inside their operating hours
+    fn is_project_owner(env: &Env, caller: &Address, project_id: u64) -> bool {
+        let access_control: Option<Address> = env.storage().instance().get(&DataKey::AccessControl);
+        let Some(access_control) = access_control else {
+            return false;
+        };
+
+        let scope = Scope {
+            contract: env.current_contract_address(),
+            id: project_id,
+        };
+        let client = AccessControlClient::new(env, &access_control);
+        client.has_scope_grant(caller, &Role::Operator, &scope)
+            && client.is_within_operating_hours(caller)
+    }
+
+    // Internal helper to load a project budget or fail
+    fn load_project(env: &Env, project_id: u64) -> ProjectBudget {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .expect("Project not found")
+    }
+
+    // Internal helper carrying one user's unspent budget into `period`
+    fn rollover(env: &Env, user: &Address, period: u64) -> i128 {
+        let stored: Option<CarryOver> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::CarryOver(user.clone()));
+        if let Some(carry) = &stored {
+            if carry.period >= period {
+                return carry.amount;
+            }
+        }
+        let budget: BudgetRecord = match env
+            .storage()
+            .persistent()
+            .get(&DataKey::Budget(user.clone()))
+        {
+            Some(budget) => budget,
+            None => return 0,
+        };
+        if period == 0 {
+            return 0;
+        }
+
+        let previous = period - 1;
+        let spent = Self::usage_in(env, user, previous).spent;
+        let unspent = Self::available_in(env, user, &budget, previous)
+            .saturating_sub(spent)
+            .max(0);
+
+        let mode = Self::get_carry_over_mode(env.clone(), user.clone());
+        let amount = match mode {
+            CarryOverMode::Expire => 0,
+            CarryOverMode::RollOver(cap_bps) => {
+                let cap = math::apply_bps(budget.amount.max(0), cap_bps, Rounding::Down)
+                    .unwrap_or(i128::MAX);
+                unspent.min(cap)
+            }
+            CarryOverMode::Accumulate => unspent,
+        };
+
+        env.storage().persistent().set(
+            &DataKey::CarryOver(user.clone()),
+            &CarryOver { period, amount },
+        );
+        events::publish(
+            env,
+            (symbol_short!("budget"), symbol_short!("carried")),
+            (user.clone(), period, amount, mode),
+        );
+
+        amount
+    }
+
+    // Internal helper returning the budget carried into `period`
+    fn carried_into(env: &Env, user: &Address, period: u64) -> i128 {
+        let carry: Option<CarryOver> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::CarryOver(user.clone()));
+        match carry {
+            Some(carry) if carry.period == period => carry.amount,
+            _ => 0,
+        }
+    }
+
+    // Internal helper returning the period spends are charged to: the one
+    // the ledger time falls in, or the one after the last closed period if
+    // that was closed early
+    fn current_period(env: &Env) -> u64 {
+        let period = env.ledger().timestamp() / BUDGET_PERIOD_SECONDS;
+        match env
+            .storage()
+            .instance()
+            .get::<_, u64>(&DataKey::LastClosedPeriod)
+        {
+            Some(closed) => period.max(closed + 1),
+            None => period,
+        }
+    }
+
+    // Internal helper returning the budget available in `period`
+    fn available_in(env: &Env, user: &Address, budget: &BudgetRecord, period: u64) -> i128 {
+        budget
+            .amount
+            .saturating_add(Self::carried_into(env, user, period))
+    }
+
+    // Internal helper reporting a failed token conversion
+    fn conversion_error(error: NormalizeError) -> ! {
+        match error {
+            NormalizeError::TokenNotAllowed => panic!("Token not allowed"),
+            NormalizeError::InvalidDecimals => panic!("Invalid token decimals"),
+            NormalizeError::PriceUnavailable | NormalizeError::StalePrice => {
+                panic!("Price unavailable")
+            }
+            NormalizeError::Overflow | NormalizeError::RateUnavailable => panic!("Overflow"),
+        }
+    }
+
+    // Internal helper refusing to run against storage written by a different
+    // layout version
+    fn require_storage_version(env: &Env) {
+        versioning::require_version(env, STORAGE_VERSION)
+            .unwrap_or_else(|_| panic!("Storage version mismatch"));
+    }
+
+    // Internal helper to verify admin
+    fn require_admin(env: &Env, caller: &Address) {
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .expect("Not initialized");
+        if *caller != stored_admin {
+            panic!("Unauthorized");
+        }
+    }
 }
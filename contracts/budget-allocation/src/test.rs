@@ -1,12 +1,59 @@
 #![cfg(test)]
 
 use super::*;
-use crate::types::BudgetRequest;
+use crate::types::{BudgetRequest, CarryOverMode, ReopenReason, SpendRule};
+use common::normalize::{Asset, PriceData};
 use soroban_sdk::{
-    testutils::{Address as _, Events},
-    vec, Address, Env,
+    testutils::{Address as _, Events, Ledger},
+    vec, Address, Env, FromVal, Symbol, TryFromVal, Vec,
 };
 
+fn setup_with_budget(
+    amount: i128,
+) -> (
+    Env,
+    BudgetAllocationContractClient<'static>,
+    Address,
+    Address,
+) {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BudgetAllocationContract, ());
+    let client = BudgetAllocationContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let user = Address::generate(&env);
+    client.batch_allocate_budget(
+        &admin,
+        &vec![
+            &env,
+            BudgetRequest {
+                user: user.clone(),
+                amount,
+            },
+        ],
+    );
+
+    (env, client, admin, user)
+}
+
+/// Collects the thresholds of the `budget_alert` events emitted by the last call.
+fn alerted_thresholds(env: &Env) -> Vec<u32> {
+    let alert = Symbol::new(env, "budget_alert");
+    let mut thresholds = Vec::new(env);
+    for (_, topics, _) in env.events().all().iter() {
+        if topics.len() == 2
+            && Symbol::try_from_val(env, &topics.get(0).unwrap()) == Ok(alert.clone())
+        {
+            thresholds.push_back(u32::from_val(env, &topics.get(1).unwrap()));
+        }
+    }
+    thresholds
+}
+
 #[test]
 fn test_batch_allocate_budget() {
     let env = Env::default();
@@ -99,3 +146,577 @@ fn test_unauthorized_access() {
 
     client.batch_allocate_budget(&not_admin, &requests);
 }
+
+#[test]
+fn test_budget_alerts_fire_once_per_threshold() {
+    let (env, client, admin, user) = setup_with_budget(1000);
+
+    client.record_spend(&admin, &user, &400);
+    assert!(alerted_thresholds(&env).is_empty());
+
+    client.record_spend(&admin, &user, &100);
+    assert_eq!(alerted_thresholds(&env), vec![&env, 5_000u32]);
+
+    // Crossing 80% and 100% in a single spend emits both alerts
+    client.record_spend(&admin, &user, &600);
+    assert_eq!(alerted_thresholds(&env), vec![&env, 8_000u32, 10_000u32]);
+
+    // Further spend in the same period does not re-alert
+    client.record_spend(&admin, &user, &50);
+    assert!(alerted_thresholds(&env).is_empty());
+
+    let usage = client.get_budget_usage(&user).unwrap();
+    assert_eq!(usage.spent, 1150);
+    assert_eq!(
+        usage.alerted_thresholds,
+        vec![&env, 5_000u32, 8_000u32, 10_000u32]
+    );
+}
+
+#[test]
+fn test_budget_alerts_reset_next_period() {
+    let (env, client, admin, user) = setup_with_budget(1000);
+
+    client.record_spend(&admin, &user, &900);
+    assert_eq!(alerted_thresholds(&env), vec![&env, 5_000u32, 8_000u32]);
+
+    env.ledger()
+        .with_mut(|li| li.timestamp += crate::types::BUDGET_PERIOD_SECONDS);
+
+    let usage = client.record_spend(&admin, &user, &600);
+    assert_eq!(usage.spent, 600);
+    assert_eq!(alerted_thresholds(&env), vec![&env, 5_000u32]);
+}
+
+#[test]
+fn test_custom_alert_thresholds() {
+    let (env, client, admin, user) = setup_with_budget(1000);
+
+    client.set_alert_thresholds(&admin, &vec![&env, 2_500u32, 7_500u32]);
+    assert_eq!(
+        client.get_alert_thresholds(),
+        vec![&env, 2_500u32, 7_500u32]
+    );
+
+    client.record_spend(&admin, &user, &300);
+    assert_eq!(alerted_thresholds(&env), vec![&env, 2_500u32]);
+}
+
+#[test]
+#[should_panic(expected = "Invalid thresholds")]
+fn test_alert_thresholds_must_ascend() {
+    let (env, client, admin, _user) = setup_with_budget(1000);
+
+    client.set_alert_thresholds(&admin, &vec![&env, 8_000u32, 5_000u32]);
+}
+
+#[test]
+#[should_panic(expected = "Budget not found")]
+fn test_record_spend_without_budget() {
+    let (env, client, admin, _user) = setup_with_budget(1000);
+
+    client.record_spend(&admin, &Address::generate(&env), &100);
+}
+
+/// Moves the ledger into the next budget period.
+fn next_period(env: &Env) {
+    env.ledger()
+        .with_mut(|li| li.timestamp += crate::types::BUDGET_PERIOD_SECONDS);
+}
+
+#[test]
+fn test_rollover_expires_by_default() {
+    let (env, client, admin, user) = setup_with_budget(1000);
+
+    client.record_spend(&admin, &user, &400);
+    next_period(&env);
+
+    assert_eq!(
+        client.rollover_budgets(&vec![&env, user.clone()]),
+        vec![&env, 0i128]
+    );
+    assert_eq!(client.get_available_budget(&user), 1000);
+}
+
+#[test]
+fn test_rollover_capped_by_percentage() {
+    let (env, client, admin, user) = setup_with_budget(1000);
+    client.set_carry_over_mode(&admin, &user, &CarryOverMode::RollOver(2_500));
+
+    client.record_spend(&admin, &user, &400);
+    next_period(&env);
+
+    // 600 unspent, capped at 25% of the 1000 budget
+    assert_eq!(
+        client.rollover_budgets(&vec![&env, user.clone()]),
+        vec![&env, 250i128]
+    );
+    assert_eq!(client.get_carry_over(&user), 250);
+    assert_eq!(client.get_available_budget(&user), 1250);
+
+    // Alerts are measured against the budget including the carried amount
+    client.record_spend(&admin, &user, &625);
+    assert_eq!(alerted_thresholds(&env), vec![&env, 5_000u32]);
+}
+
+#[test]
+fn test_rollover_accumulates_across_periods() {
+    let (env, client, admin, user) = setup_with_budget(1000);
+    client.set_carry_over_mode(&admin, &user, &CarryOverMode::Accumulate);
+
+    client.record_spend(&admin, &user, &400);
+    next_period(&env);
+    client.rollover_budgets(&vec![&env, user.clone()]);
+    assert_eq!(client.get_available_budget(&user), 1600);
+
+    client.record_spend(&admin, &user, &100);
+    next_period(&env);
+    client.rollover_budgets(&vec![&env, user.clone()]);
+    assert_eq!(client.get_carry_over(&user), 1500);
+    assert_eq!(client.get_available_budget(&user), 2500);
+}
+
+#[test]
+fn test_spend_before_rollover_keeps_previous_usage() {
+    let (env, client, admin, user) = setup_with_budget(1000);
+    client.set_carry_over_mode(&admin, &user, &CarryOverMode::Accumulate);
+
+    client.record_spend(&admin, &user, &400);
+    next_period(&env);
+
+    // The new period's first spend lands before anyone triggers the rollover
+    client.record_spend(&admin, &user, &100);
+    assert_eq!(
+        client.rollover_budgets(&vec![&env, user.clone()]),
+        vec![&env, 600i128]
+    );
+    assert_eq!(client.get_available_budget(&user), 1600);
+}
+
+#[test]
+fn test_rollover_is_idempotent_within_period() {
+    let (env, client, admin, user) = setup_with_budget(1000);
+    client.set_carry_over_mode(&admin, &user, &CarryOverMode::Accumulate);
+    next_period(&env);
+
+    let users = vec![&env, user.clone(), Address::generate(&env)];
+    assert_eq!(client.rollover_budgets(&users), vec![&env, 1000i128, 0i128]);
+
+    // A second trigger in the same period does not carry again
+    client.record_spend(&admin, &user, &2000);
+    assert_eq!(client.rollover_budgets(&users), vec![&env, 1000i128, 0i128]);
+    assert_eq!(client.get_available_budget(&user), 2000);
+}
+
+#[test]
+#[should_panic(expected = "Invalid carry-over cap")]
+fn test_carry_over_cap_above_full_budget() {
+    let (_env, client, admin, user) = setup_with_budget(1000);
+
+    client.set_carry_over_mode(&admin, &user, &CarryOverMode::RollOver(10_001));
+}
+
+#[test]
+fn test_prepaid_spend_consumes_budget_monthly() {
+    let (env, client, admin, user) = setup_with_budget(1000);
+
+    // An annual license consumes a twelfth of its cost each period
+    client.record_prepaid_spend(&admin, &user, &1200, &12);
+    assert_eq!(client.get_budget_usage(&user).unwrap().spent, 100);
+    assert_eq!(client.get_unamortized_balance(&user), 1100);
+
+    next_period(&env);
+    assert_eq!(client.get_unamortized_balance(&user), 1000);
+    let usage = client.record_spend(&admin, &user, &450);
+    assert_eq!(usage.spent, 550);
+    assert_eq!(alerted_thresholds(&env), vec![&env, 5_000u32]);
+}
+
+#[test]
+fn test_prepaid_remainder_in_last_installment() {
+    let (env, client, admin, user) = setup_with_budget(1000);
+    client.set_carry_over_mode(&admin, &user, &CarryOverMode::Accumulate);
+
+    let schedule = client.record_prepaid_spend(&admin, &user, &1000, &3);
+    assert_eq!(
+        client.get_prepaid_schedule(&schedule.schedule_id),
+        Some(schedule)
+    );
+
+    // The installment counts as spent when the period is rolled over
+    next_period(&env);
+    assert_eq!(
+        client.rollover_budgets(&vec![&env, user.clone()]),
+        vec![&env, 667i128]
+    );
+
+    next_period(&env);
+    assert_eq!(client.get_unamortized_balance(&user), 0);
+    assert_eq!(client.record_spend(&admin, &user, &1).spent, 335);
+
+    // Fully consumed schedules are dropped when the next one is recorded
+    next_period(&env);
+    client.record_prepaid_spend(&admin, &user, &500, &5);
+    assert_eq!(client.get_prepaid_schedules(&user).len(), 1);
+    assert_eq!(client.get_unamortized_balance(&user), 400);
+}
+
+#[test]
+#[should_panic(expected = "Invalid amortization periods")]
+fn test_prepaid_spend_requires_periods() {
+    let (_env, client, admin, user) = setup_with_budget(1000);
+
+    client.record_prepaid_spend(&admin, &user, &1200, &0);
+}
+
+#[test]
+fn test_project_budget_burn_down() {
+    let (env, client, admin, _user) = setup_with_budget(1000);
+    let start = env.ledger().timestamp();
+
+    client.create_project_budget(&admin, &7, &10_000, &(start + 1_000));
+    env.ledger().with_mut(|li| li.timestamp += 250);
+    let project = client.record_project_spend(&admin, &7, &4_000);
+    assert_eq!(project.spent, 4_000);
+
+    let burn_down = client.get_project_burn_down(&7);
+    assert_eq!(burn_down.remaining, 6_000);
+    assert_eq!(burn_down.spent_bps, 4_000);
+    assert_eq!(burn_down.elapsed, 250);
+    assert_eq!(burn_down.time_remaining, 750);
+    assert_eq!(burn_down.elapsed_bps, 2_500);
+    // 40% spent in 25% of the time: on course to spend 160%
+    assert_eq!(burn_down.projected_spend, 16_000);
+
+    // Project spends do not touch user budgets
+    assert_eq!(client.get_budget_usage(&_user), None);
+}
+
+#[test]
+fn test_project_burn_down_after_deadline() {
+    let (env, client, admin, _user) = setup_with_budget(1000);
+    let start = env.ledger().timestamp();
+
+    client.create_project_budget(&admin, &1, &1_000, &(start + 100));
+    client.record_project_spend(&admin, &1, &500);
+    env.ledger().with_mut(|li| li.timestamp += 400);
+
+    let burn_down = client.get_project_burn_down(&1);
+    assert_eq!(burn_down.time_remaining, 0);
+    assert_eq!(burn_down.elapsed_bps, 10_000);
+}
+
+#[test]
+#[should_panic(expected = "Project budget exceeded")]
+fn test_project_spend_cannot_exceed_envelope() {
+    let (env, client, admin, _user) = setup_with_budget(1000);
+    let deadline = env.ledger().timestamp() + 100;
+
+    client.create_project_budget(&admin, &1, &1_000, &deadline);
+    client.record_project_spend(&admin, &1, &600);
+    client.record_project_spend(&admin, &1, &401);
+}
+
+#[test]
+#[should_panic(expected = "Project already exists")]
+fn test_project_ids_are_unique() {
+    let (env, client, admin, _user) = setup_with_budget(1000);
+    let deadline = env.ledger().timestamp() + 100;
+
+    client.create_project_budget(&admin, &1, &1_000, &deadline);
+    client.create_project_budget(&admin, &1, &2_000, &deadline);
+}
+
+#[test]
+fn test_simulate_spend_previews_budget_alerts_without_state_change() {
+    let (env, client, admin, user) = setup_with_budget(1000);
+    client.record_spend(&admin, &user, &400);
+
+    let simulation = client.simulate_spend(&user, &None, &450);
+    assert!(simulation.allowed);
+    assert_eq!(simulation.blocked_by, SpendRule::None);
+    assert_eq!(simulation.spent_after, 850);
+    assert_eq!(simulation.limit, 1000);
+    assert_eq!(simulation.alerts, vec![&env, 5_000u32, 8_000u32]);
+    assert_eq!(alerted_thresholds(&env), Vec::new(&env));
+    assert_eq!(client.get_budget_usage(&user).unwrap().spent, 400);
+
+    let simulation = client.simulate_spend(&user, &None, &0);
+    assert_eq!(simulation.blocked_by, SpendRule::InvalidAmount);
+    let simulation = client.simulate_spend(&Address::generate(&env), &None, &10);
+    assert_eq!(simulation.blocked_by, SpendRule::BudgetNotFound);
+}
+
+#[test]
+fn test_simulate_project_spend_reports_blocking_rule() {
+    let (env, client, admin, _user) = setup_with_budget(1000);
+    let deadline = env.ledger().timestamp() + 100;
+    client.create_project_budget(&admin, &1, &1_000, &deadline);
+    client.record_project_spend(&admin, &1, &600);
+
+    let simulation = client.simulate_spend(&admin, &Some(1), &400);
+    assert!(simulation.allowed);
+    assert_eq!(simulation.spent_after, 1_000);
+
+    let simulation = client.simulate_spend(&admin, &Some(1), &401);
+    assert!(!simulation.allowed);
+    assert_eq!(simulation.blocked_by, SpendRule::ProjectBudgetExceeded);
+    assert_eq!(client.get_project_budget(&1).unwrap().spent, 600);
+
+    let simulation = client.simulate_spend(&Address::generate(&env), &Some(1), &10);
+    assert_eq!(simulation.blocked_by, SpendRule::Unauthorized);
+    let simulation = client.simulate_spend(&admin, &Some(2), &10);
+    assert_eq!(simulation.blocked_by, SpendRule::ProjectNotFound);
+}
+
+#[test]
+fn test_close_period_freezes_and_opens_next_period() {
+    let (env, client, admin, user) = setup_with_budget(1000);
+    client.record_spend(&admin, &user, &300);
+
+    let close = client.close_period(&admin, &vec![&env, user.clone()]);
+    assert_eq!(close.period, 0);
+    assert_eq!(close.budgets, 1);
+    assert_eq!(close.total_available, 1000);
+    assert_eq!(close.total_spent, 300);
+    let snapshot = client.get_period_snapshot(&user, &0).unwrap();
+    assert_eq!(snapshot.spent, 300);
+
+    // Spends after the close land in the next period, before its boundary
+    assert_eq!(client.get_current_period(), 1);
+    let usage = client.record_spend(&admin, &user, &50);
+    assert_eq!(usage.period, 1);
+    assert_eq!(usage.spent, 50);
+    assert_eq!(client.get_period_snapshot(&user, &0).unwrap().spent, 300);
+
+    // The early-opened period cannot be closed before it starts
+    assert!(client.try_close_period(&admin, &vec![&env]).is_err());
+    env.ledger()
+        .with_mut(|li| li.timestamp += BUDGET_PERIOD_SECONDS);
+    assert_eq!(client.get_current_period(), 1);
+    assert_eq!(client.close_period(&admin, &vec![&env]).period, 1);
+}
+
+#[test]
+fn test_reopen_period_with_reason() {
+    let (env, client, admin, user) = setup_with_budget(1000);
+    client.close_period(&admin, &vec![&env, user.clone()]);
+
+    assert_eq!(client.reopen_period(&admin, &ReopenReason::LateSpend), 0);
+    assert_eq!(client.get_current_period(), 0);
+    assert_eq!(client.get_period_close(&0), None);
+    assert_eq!(client.record_spend(&admin, &user, &100).period, 0);
+
+    // Once the next period is in use the close is final
+    client.close_period(&admin, &vec![&env, user.clone()]);
+    client.record_spend(&admin, &user, &10);
+    assert!(client
+        .try_reopen_period(&admin, &ReopenReason::Correction)
+        .is_err());
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_reopen_period_requires_admin() {
+    let (env, client, admin, _user) = setup_with_budget(1000);
+    client.close_period(&admin, &vec![&env]);
+
+    client.reopen_period(&Address::generate(&env), &ReopenReason::Other);
+}
+
+/// Access control stand-in backed by the embeddable RBAC module.
+#[soroban_sdk::contract]
+struct MockAccessControl;
+
+#[soroban_sdk::contractimpl]
+impl MockAccessControl {
+    pub fn grant_scoped_role(env: Env, user: Address, role: Role, scope: Scope) {
+        access_control_interface::rbac::grant_scoped_role(&env, &user, role, &scope).unwrap();
+    }
+
+    pub fn grant_role(env: Env, user: Address, role: Role) {
+        access_control_interface::rbac::grant_role(&env, &user, role).unwrap();
+    }
+
+    pub fn has_scope_grant(env: Env, user: Address, role: Role, scope: Scope) -> bool {
+        access_control_interface::rbac::has_scope_grant(&env, &user, role, &scope)
+    }
+
+    pub fn set_off_hours(env: Env, operator: Address) {
+        env.storage().persistent().set(&operator, &true);
+    }
+
+    pub fn is_within_operating_hours(env: Env, operator: Address) -> bool {
+        !env.storage().persistent().has(&operator)
+    }
+}
+
+#[test]
+fn test_budget_owner_records_project_spend() {
+    let (env, client, admin, _user) = setup_with_budget(1000);
+    let start = env.ledger().timestamp();
+    client.create_project_budget(&admin, &7, &10_000, &(start + 1_000));
+    client.create_project_budget(&admin, &8, &10_000, &(start + 1_000));
+
+    let access_control = env.register(MockAccessControl, ());
+    client.set_access_control(&admin, &access_control);
+    assert_eq!(client.get_access_control(), Some(access_control.clone()));
+
+    let owner = Address::generate(&env);
+    let scope = Scope {
+        contract: client.address.clone(),
+        id: 7,
+    };
+    MockAccessControlClient::new(&env, &access_control).grant_scoped_role(
+        &owner,
+        &Role::Operator,
+        &scope,
+    );
+
+    let project = client.record_project_spend(&owner, &7, &2_500);
+    assert_eq!(project.spent, 2_500);
+
+    // Ownership of one budget does not extend to others
+    assert!(client.try_record_project_spend(&owner, &8, &100).is_err());
+
+    // Owners are bound by their operating hours
+    MockAccessControlClient::new(&env, &access_control).set_off_hours(&owner);
+    assert!(client.try_record_project_spend(&owner, &7, &100).is_err());
+}
+
+#[test]
+fn test_global_operator_is_not_project_owner() {
+    let (env, client, admin, _user) = setup_with_budget(1000);
+    let start = env.ledger().timestamp();
+    client.create_project_budget(&admin, &7, &10_000, &(start + 1_000));
+
+    let access_control = env.register(MockAccessControl, ());
+    client.set_access_control(&admin, &access_control);
+    let operator = Address::generate(&env);
+    MockAccessControlClient::new(&env, &access_control).grant_role(&operator, &Role::Operator);
+
+    assert!(client.try_record_project_spend(&operator, &7, &100).is_err());
+    assert_eq!(client.get_project_budget(&7).unwrap().spent, 0);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_project_spend_without_access_control_requires_admin() {
+    let (env, client, admin, _user) = setup_with_budget(1000);
+    let start = env.ledger().timestamp();
+    client.create_project_budget(&admin, &7, &10_000, &(start + 1_000));
+
+    client.record_project_spend(&Address::generate(&env), &7, &100);
+}
+
+#[test]
+fn test_reference_change_flags_earlier_budgets() {
+    let (env, client, admin, user) = setup_with_budget(1000);
+    assert_eq!(client.get_reference_currency().code, Symbol::new(&env, "USD"));
+    assert!(!client.is_budget_pre_change(&user));
+
+    client.set_reference_currency(&admin, &Symbol::new(&env, "EURC"), &7);
+    assert_eq!(client.get_reference_currency().epoch, 1);
+    assert!(client.is_budget_pre_change(&user));
+    // Users without a budget are not flagged
+    assert!(!client.is_budget_pre_change(&Address::generate(&env)));
+
+    // Allocating again records the budget under the new reference
+    client.batch_allocate_budget(
+        &admin,
+        &vec![
+            &env,
+            BudgetRequest {
+                user: user.clone(),
+                amount: 900,
+            },
+        ],
+    );
+    assert!(!client.is_budget_pre_change(&user));
+}
+
+#[test]
+#[should_panic(expected = "Invalid reference currency")]
+fn test_reference_change_rejects_unchanged_setting() {
+    let (env, client, admin, _user) = setup_with_budget(1000);
+    client.set_reference_currency(&admin, &Symbol::new(&env, "USD"), &7);
+}
+
+/// Oracle serving whatever prices the test sets.
+#[soroban_sdk::contract]
+struct MockOracle;
+
+#[soroban_sdk::contractimpl]
+impl MockOracle {
+    pub fn set_price(env: Env, asset: Asset, price: i128) {
+        let timestamp = env.ledger().timestamp();
+        env.storage()
+            .persistent()
+            .set(&asset, &PriceData { price, timestamp });
+    }
+
+    pub fn lastprice(env: Env, asset: Asset) -> Option<PriceData> {
+        env.storage().persistent().get(&asset)
+    }
+}
+
+/// Token with 18 decimals, like most bridged assets.
+#[soroban_sdk::contract]
+struct WideToken;
+
+#[soroban_sdk::contractimpl]
+impl WideToken {
+    pub fn decimals(_env: Env) -> u32 {
+        18
+    }
+}
+
+/// Configures token conversion into a 7-decimal reference token, with one
+/// wide token worth 2.5 reference tokens. Returns (reference, wide) tokens.
+fn setup_token_conversion(
+    env: &Env,
+    client: &BudgetAllocationContractClient<'static>,
+    admin: &Address,
+) -> (Address, Address) {
+    let reference_token = env
+        .register_stellar_asset_contract_v2(Address::generate(env))
+        .address();
+    let wide = env.register(WideToken, ());
+    let oracle = MockOracleClient::new(env, &env.register(MockOracle, ()));
+    oracle.set_price(&Asset::Stellar(reference_token.clone()), &100_000);
+    oracle.set_price(&Asset::Stellar(wide.clone()), &250_000);
+
+    client.set_token_conversion(admin, &oracle.address, &reference_token);
+    (reference_token, wide)
+}
+
+#[test]
+fn test_token_spends_convert_into_reference_token() {
+    let (env, client, admin, user) = setup_with_budget(100_000_000);
+    let (reference_token, wide) = setup_token_conversion(&env, &client, &admin);
+    assert_eq!(client.allow_spend_token(&admin, &wide), 18);
+
+    // One wide token counts as 2.5 reference tokens, not 10^11 of them
+    let usage = client.record_token_spend(&admin, &user, &wide, &10i128.pow(18));
+    assert_eq!(usage.spent, 25_000_000);
+
+    let usage = client.record_token_spend(&admin, &user, &reference_token, &5_000_000);
+    assert_eq!(usage.spent, 30_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Token not allowed")]
+fn test_token_spend_requires_allowlisted_token() {
+    let (env, client, admin, user) = setup_with_budget(100_000_000);
+    let (_reference_token, wide) = setup_token_conversion(&env, &client, &admin);
+
+    client.record_token_spend(&admin, &user, &wide, &10i128.pow(18));
+}
+
+#[test]
+#[should_panic(expected = "Token conversion not configured")]
+fn test_token_spend_requires_conversion_setup() {
+    let (env, client, admin, user) = setup_with_budget(100_000_000);
+
+    client.record_token_spend(&admin, &user, &Address::generate(&env), &100);
+}
@@ -1,4 +1,19 @@
-use soroban_sdk::{contracttype, Address};
+use soroban_sdk::{contracttype, Address, Vec};
+
+/// Length of a budget period (30 days) in seconds.
+pub const BUDGET_PERIOD_SECONDS: u64 = 30 * 24 * 60 * 60;
+
+/// Maximum number of alert thresholds that can be configured.
+pub const MAX_ALERT_THRESHOLDS: u32 = 10;
+
+/// Highest alert threshold accepted (200% of the budget) in basis points.
+pub const MAX_ALERT_THRESHOLD_BPS: u32 = 20_000;
+
+/// Longest amortization schedule accepted for a prepaid spend, in periods.
+pub const MAX_AMORTIZATION_PERIODS: u32 = 60;
+
+/// Maximum number of live amortization schedules per user.
+pub const MAX_PREPAID_SCHEDULES: u32 = 20;
 
 /// Request structure for setting a user's budget
 #[contracttype]
@@ -19,6 +34,167 @@ pub struct BudgetRecord {
     pub last_updated: u64,
 }
 
+/// Spending tracked against a user's budget for the current period
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BudgetUsage {
+    pub user: Address,
+    /// Period index (ledger timestamp / period length) the usage belongs to
+    pub period: u64,
+    /// Amount spent in this period
+    pub spent: i128,
+    /// Thresholds (in basis points) already alerted on this period
+    pub alerted_thresholds: Vec<u32>,
+}
+
+/// What happens to a budget's unspent amount when its period ends
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CarryOverMode {
+    /// Unspent budget is forfeited
+    Expire,
+    /// Unspent budget rolls over, capped at this many basis points of the
+    /// base budget
+    RollOver(u32),
+    /// All unspent budget rolls over
+    Accumulate,
+}
+
+/// Budget carried into a period by a rollover
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CarryOver {
+    /// Period index the carried amount applies to
+    pub period: u64,
+    /// Amount carried in from the previous period
+    pub amount: i128,
+}
+
+/// Budget envelope for a project, separate from per-user budgets
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProjectBudget {
+    pub project_id: u64,
+    /// Total budget for the life of the project
+    pub total: i128,
+    /// Amount spent so far
+    pub spent: i128,
+    /// Ledger timestamp the project was created at
+    pub created_at: u64,
+    /// Ledger timestamp the project is due to finish by
+    pub deadline: u64,
+}
+
+/// Spent-versus-remaining-versus-time snapshot of a project budget
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BurnDown {
+    pub total: i128,
+    pub spent: i128,
+    pub remaining: i128,
+    /// Share of the budget spent, in basis points
+    pub spent_bps: u32,
+    /// Seconds elapsed since the project was created
+    pub elapsed: u64,
+    /// Seconds left until the deadline (0 once it has passed)
+    pub time_remaining: u64,
+    /// Share of the project's duration elapsed, in basis points (capped at 100%)
+    pub elapsed_bps: u32,
+    /// Spend projected at the deadline if the current burn rate continues
+    pub projected_spend: i128,
+}
+
+/// Prepaid spend whose cost is consumed from a user's budget over several
+/// periods instead of all at once
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PrepaidSchedule {
+    pub schedule_id: u64,
+    pub user: Address,
+    /// Full amount paid up front
+    pub total: i128,
+    /// Number of periods the amount is spread over
+    pub periods: u32,
+    /// Period index of the first installment
+    pub start_period: u64,
+}
+
+/// Rule that would reject a spend
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SpendRule {
+    /// No rule rejects the spend
+    None,
+    /// Amount is zero or negative
+    InvalidAmount,
+    /// The spender has no budget allocated
+    BudgetNotFound,
+    /// No project budget exists with this id
+    ProjectNotFound,
+    /// The spender neither owns the project's budget nor is the admin
+    Unauthorized,
+    /// The spend would take the project past its envelope
+    ProjectBudgetExceeded,
+    /// The spend would overflow the amount spent
+    Overflow,
+}
+
+/// Outcome a spend would have, computed without changing any state
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SpendSimulation {
+    /// Whether the spend would be recorded
+    pub allowed: bool,
+    /// First rule that would reject the spend
+    pub blocked_by: SpendRule,
+    /// Amount spent in the period (or project) after the spend
+    pub spent_after: i128,
+    /// Budget available for the period, or the project's envelope
+    pub limit: i128,
+    /// Alert thresholds (in basis points) the spend would newly cross
+    pub alerts: Vec<u32>,
+}
+
+/// Why a closed period was reopened
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReopenReason {
+    /// A recorded spend was wrong and must be corrected
+    Correction,
+    /// A spend belonging to the period arrived after it was closed
+    LateSpend,
+    /// Auditors requested an adjustment
+    AuditAdjustment,
+    Other,
+}
+
+/// Totals recorded when a period was closed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PeriodClose {
+    pub period: u64,
+    pub closed_at: u64,
+    /// Number of budgets snapshotted
+    pub budgets: u32,
+    /// Budget available across the snapshotted budgets
+    pub total_available: i128,
+    /// Amount spent across the snapshotted budgets
+    pub total_spent: i128,
+}
+
+/// Final figures of a user's budget for a closed period
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PeriodSnapshot {
+    pub user: Address,
+    pub period: u64,
+    /// Base budget for the period
+    pub budget: i128,
+    /// Budget available, including any amount carried in
+    pub available: i128,
+    pub spent: i128,
+}
+
 /// Storage keys for the contract
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -26,6 +202,36 @@ pub enum DataKey {
     Admin,
     Budget(Address),
     TotalAllocated, // Track global stats if needed
+    Usage(Address),
+    AlertThresholds,
+    /// Carry-over mode for a user's budget
+    CarryOverMode(Address),
+    /// Budget carried into the user's latest rolled-over period
+    CarryOver(Address),
+    /// Budget envelope for a project
+    Project(u64),
+    /// Access control contract resolving budget owners
+    AccessControl,
+    /// Amortization schedule for a prepaid spend
+    Prepaid(u64),
+    /// Ids of a user's amortization schedules that are not yet fully consumed
+    PrepaidSchedules(Address),
+    /// Next prepaid schedule id
+    NextPrepaidId,
+    /// Most recently closed period
+    LastClosedPeriod,
+    /// Totals recorded when a period was closed
+    PeriodClose(u64),
+    /// A user's budget figures for a closed period
+    PeriodSnapshot(Address, u64),
+    /// Set once a spend is charged to a period that opened early
+    EarlySpend(u64),
+    /// Reference currency epoch a user's budget was allocated under
+    BudgetEpoch(Address),
+    /// SEP-40 price oracle used to convert token spends
+    PriceOracle,
+    /// Token standing for the reference currency, which token spends convert into
+    ReferenceToken,
 }
 
 /// Result of a batch budget allocation operation
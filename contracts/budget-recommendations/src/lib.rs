@@ -24,6 +24,7 @@ mod recommendations;
 mod types;
 mod validation;
 
+use common::versioning;
 use soroban_sdk::{contract, contractimpl, panic_with_error, Address, Env, Vec};
 
 pub use crate::recommendations::{generate_batch_recommendations, generate_recommendation};
@@ -49,6 +50,10 @@ pub enum BudgetRecommendationError {
     BatchTooLarge = 5,
     /// Invalid user profile
     InvalidUserProfile = 6,
+    /// Storage was written by a different layout version
+    StorageVersionMismatch = 7,
+    /// Migration source version does not match stored version
+    InvalidMigration = 8,
 }
 
 impl From<BudgetRecommendationError> for soroban_sdk::Error {
@@ -57,6 +62,9 @@ impl From<BudgetRecommendationError> for soroban_sdk::Error {
     }
 }
 
+/// Storage layout version this code expects.
+pub const STORAGE_VERSION: u32 = 1;
+
 #[contract]
 pub struct BudgetRecommendationsContract;
 
@@ -73,6 +81,7 @@ impl BudgetRecommendationsContract {
         }
 
         env.storage().instance().set(&DataKey::Admin, &admin);
+        versioning::set_version(&env, STORAGE_VERSION);
         env.storage().instance().set(&DataKey::LastBatchId, &0u64);
         env.storage()
             .instance()
@@ -82,6 +91,26 @@ impl BudgetRecommendationsContract {
             .set(&DataKey::TotalRecommendationsGenerated, &0u64);
     }
 
+    /// Migrates storage written by an older version of this contract to the
+    /// current layout (admin only). `from_version` must match the stored
+    /// version.
+    pub fn migrate(env: Env, caller: Address, from_version: u32) {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        // Version 1 only adds the version stamp; older layouts are otherwise
+        // unchanged, so there is nothing to rewrite
+        versioning::migrate(&env, from_version, STORAGE_VERSION, |_, _| {})
+            .unwrap_or_else(|_| {
+                panic_with_error!(&env, BudgetRecommendationError::InvalidMigration)
+            });
+    }
+
+    /// Returns the storage layout version currently stored.
+    pub fn get_storage_version(env: Env) -> u32 {
+        versioning::stored_version(&env)
+    }
+
     /// Generates batch budget recommendations for multiple users.
     ///
     /// This is the main entry point for processing user profiles and generating
@@ -107,6 +136,7 @@ impl BudgetRecommendationsContract {
         caller: Address,
         user_profiles: Vec<UserProfile>,
     ) -> BatchRecommendationResult {
+        Self::require_storage_version(&env);
         // Verify authorization
         caller.require_auth();
         Self::require_admin(&env, &caller);
@@ -121,7 +151,7 @@ impl BudgetRecommendationsContract {
         }
 
         // Validate batch of user profiles
-        if let Err(_) = validate_batch(&user_profiles) {
+        if validate_batch(&user_profiles).is_err() {
             panic_with_error!(&env, BudgetRecommendationError::InvalidBatch);
         }
 
@@ -241,10 +271,7 @@ impl BudgetRecommendationsContract {
         env: Env,
         user_profile: UserProfile,
     ) -> Option<BudgetRecommendation> {
-        match generate_recommendation(&env, &user_profile) {
-            Ok(rec) => Some(rec),
-            Err(_) => None,
-        }
+        generate_recommendation(&env, &user_profile).ok()
     }
 
     /// Returns the admin address.
@@ -257,6 +284,7 @@ impl BudgetRecommendationsContract {
 
     /// Updates the admin address.
     pub fn set_admin(env: Env, current_admin: Address, new_admin: Address) {
+        Self::require_storage_version(&env);
         current_admin.require_auth();
         Self::require_admin(&env, &current_admin);
 
@@ -287,6 +315,15 @@ impl BudgetRecommendationsContract {
             .unwrap_or(0)
     }
 
+    // Internal helper refusing to run against storage written by a different
+    // layout version
+    fn require_storage_version(env: &Env) {
+        versioning::require_version(env, STORAGE_VERSION)
+            .unwrap_or_else(|_| {
+                panic_with_error!(env, BudgetRecommendationError::StorageVersionMismatch)
+            });
+    }
+
     // Internal helper to verify admin
     fn require_admin(env: &Env, caller: &Address) {
         let admin: Address = env
@@ -7,6 +7,7 @@
 //! - Batches computations to reduce gas costs
 //! - Implements rule-based AI recommendations (deterministic for blockchain)
 
+use common::math::{self, Rounding};
 use soroban_sdk::{Env, Symbol, Vec};
 
 use crate::types::{
@@ -57,17 +58,22 @@ pub fn generate_recommendation(
 
     // Calculate recommended budget (remaining after savings)
     let recommended_savings = if disposable_income > 0 {
-        (disposable_income * savings_percentage as i128) / 100
+        math::mul_div(disposable_income, savings_percentage, 100, Rounding::Down)
+            .ok_or_else(|| Symbol::new(env, "amount_overflow"))?
     } else {
         0
     };
 
-    let recommended_budget = profile.monthly_expenses + (disposable_income - recommended_savings);
+    let recommended_budget = profile
+        .monthly_expenses
+        .checked_add(disposable_income - recommended_savings)
+        .ok_or_else(|| Symbol::new(env, "amount_overflow"))?;
 
     // Calculate recommended spending limit (budget + small buffer)
     let buffer_percentage = 5; // 5% buffer
     let recommended_spending_limit =
-        recommended_budget + (recommended_budget * buffer_percentage as i128) / 100;
+        math::mul_div(recommended_budget, 100 + buffer_percentage, 100, Rounding::Down)
+            .ok_or_else(|| Symbol::new(env, "amount_overflow"))?;
 
     // Calculate emergency fund target (3-6 months of expenses based on risk tolerance)
     let emergency_fund_months = match profile.risk_tolerance {
@@ -78,7 +84,10 @@ pub fn generate_recommendation(
         5 => 3, // Aggressive: 3 months
         _ => 4,
     };
-    let emergency_fund_target = profile.monthly_expenses * emergency_fund_months as i128;
+    let emergency_fund_target = profile
+        .monthly_expenses
+        .checked_mul(emergency_fund_months)
+        .ok_or_else(|| Symbol::new(env, "amount_overflow"))?;
 
     // Calculate confidence score based on data quality
     let mut confidence_score = 80u32; // Base confidence
@@ -103,13 +112,13 @@ pub fn generate_recommendation(
 
     // Generate recommendation notes
     let notes = if disposable_income < 0 {
-        Symbol::new(env, "expenses_exceed_income_review_needed")
+        Symbol::new(env, "expenses_exceed_income_review")
     } else if recommended_savings == 0 {
         Symbol::new(env, "minimal_savings_capacity")
     } else if profile.savings_balance < emergency_fund_target / 2 {
         Symbol::new(env, "build_emergency_fund_priority")
     } else {
-        Symbol::new(env, "on_track_continue_current_strategy")
+        Symbol::new(env, "on_track_continue_strategy")
     };
 
     Ok(BudgetRecommendation {
@@ -187,7 +196,7 @@ pub fn generate_batch_recommendations(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, Env, Symbol};
+    use soroban_sdk::{testutils::Address as _, Address, Env, Symbol};
 
     fn create_test_profile(env: &Env, user_id: u64, income: i128, expenses: i128) -> UserProfile {
         UserProfile {
@@ -196,7 +205,7 @@ mod tests {
             monthly_income: income,
             monthly_expenses: expenses,
             savings_balance: 100000,
-            spending_categories: Symbol::new(env, "food,transport"),
+            spending_categories: Symbol::new(env, "food_transport"),
             risk_tolerance: 3,
         }
     }
@@ -32,7 +32,7 @@ fn create_user_profile(
     income: i128,
     expenses: i128,
     savings: i128,
-    risk_tolerance: u8,
+    risk_tolerance: u32,
 ) -> UserProfile {
     UserProfile {
         user_id,
@@ -40,7 +40,7 @@ fn create_user_profile(
         monthly_income: income,
         monthly_expenses: expenses,
         savings_balance: savings,
-        spending_categories: Symbol::new(env, "food,transport,utilities"),
+        spending_categories: Symbol::new(env, "food_transport_utilities"),
         risk_tolerance,
     }
 }
@@ -56,7 +56,7 @@ fn test_initialize_contract() {
     assert_eq!(client.get_admin(), admin);
     assert_eq!(client.get_last_batch_id(), 0);
     assert_eq!(client.get_total_users_processed(), 0);
-    assert_eq!(client.get_total_recommendations_generated(), 0);
+    assert_eq!(client.get_total_recommendations(), 0);
 }
 
 #[test]
@@ -175,17 +175,17 @@ fn test_generate_batch_recommendations_accumulates_stats() {
 
     assert_eq!(client.get_last_batch_id(), 0);
     assert_eq!(client.get_total_users_processed(), 0);
-    assert_eq!(client.get_total_recommendations_generated(), 0);
+    assert_eq!(client.get_total_recommendations(), 0);
 
     client.generate_batch_recommendations(&admin, &profiles1);
     assert_eq!(client.get_last_batch_id(), 1);
     assert_eq!(client.get_total_users_processed(), 1);
-    assert_eq!(client.get_total_recommendations_generated(), 1);
+    assert_eq!(client.get_total_recommendations(), 1);
 
     client.generate_batch_recommendations(&admin, &profiles2);
     assert_eq!(client.get_last_batch_id(), 2);
     assert_eq!(client.get_total_users_processed(), 2);
-    assert_eq!(client.get_total_recommendations_generated(), 2);
+    assert_eq!(client.get_total_recommendations(), 2);
 }
 
 #[test]
@@ -242,7 +242,7 @@ fn test_generate_batch_recommendations_low_income_scenario() {
     // Should have notes about expenses exceeding income
     assert_eq!(
         rec.notes,
-        Symbol::new(&env, "expenses_exceed_income_review_needed")
+        Symbol::new(&env, "expenses_exceed_income_review")
     );
 }
 
@@ -287,7 +287,7 @@ fn test_generate_batch_recommendations_empty_batch() {
 #[test]
 #[should_panic]
 fn test_generate_batch_recommendations_unauthorized() {
-    let (env, admin, client) = setup_test_env();
+    let (env, _admin, client) = setup_test_env();
 
     let mut profiles: Vec<UserProfile> = Vec::new(&env);
     profiles.push_back(create_user_profile(&env, 1, 100000, 50000, 10000, 3));
@@ -332,7 +332,7 @@ fn test_simulate_recommendation() {
 
 #[test]
 fn test_simulate_recommendation_no_storage() {
-    let (env, admin, client) = setup_test_env();
+    let (env, _admin, client) = setup_test_env();
 
     let profile = create_user_profile(&env, 1, 100000, 50000, 10000, 3);
 
@@ -361,7 +361,7 @@ fn test_set_admin() {
 #[test]
 #[should_panic]
 fn test_set_admin_unauthorized() {
-    let (env, admin, client) = setup_test_env();
+    let (env, _admin, client) = setup_test_env();
 
     let unauthorized = Address::generate(&env);
     let new_admin = Address::generate(&env);
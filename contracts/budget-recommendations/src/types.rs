@@ -1,5 +1,6 @@
 //! Data types and events for batch budget recommendations.
 
+use common::events;
 use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol, Vec};
 
 /// Maximum number of users in a single batch for optimization.
@@ -116,7 +117,7 @@ impl RecommendationEvents {
     /// Event emitted when batch recommendation processing starts.
     pub fn batch_started(env: &Env, batch_id: u64, user_count: u32) {
         let topics = (symbol_short!("batch"), symbol_short!("started"));
-        env.events().publish(topics, (batch_id, user_count));
+        events::publish(env, topics, (batch_id, user_count));
     }
 
     /// Event emitted when a recommendation is generated for a user.
@@ -131,8 +132,7 @@ impl RecommendationEvents {
             symbol_short!("generated"),
             batch_id,
         );
-        env.events()
-            .publish(topics, (user_id, recommendation.clone()));
+        events::publish(env, topics, (user_id, recommendation.clone()));
     }
 
     /// Event emitted when a recommendation fails for a user.
@@ -142,13 +142,13 @@ impl RecommendationEvents {
             symbol_short!("failed"),
             batch_id,
         );
-        env.events().publish(topics, (user_id, error.clone()));
+        events::publish(env, topics, (user_id, error.clone()));
     }
 
     /// Event emitted when batch recommendation processing completes.
     pub fn batch_completed(env: &Env, batch_id: u64, metrics: &BatchRecommendationMetrics) {
         let topics = (symbol_short!("batch"), symbol_short!("completed"), batch_id);
-        env.events().publish(topics, metrics.clone());
+        events::publish(env, topics, metrics.clone());
     }
 
     /// Event emitted for high-confidence recommendations.
@@ -163,6 +163,6 @@ impl RecommendationEvents {
             symbol_short!("highconf"),
             batch_id,
         );
-        env.events().publish(topics, (user_id, confidence_score));
+        events::publish(env, topics, (user_id, confidence_score));
     }
 }
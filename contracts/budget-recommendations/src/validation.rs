@@ -6,6 +6,7 @@ use crate::types::UserProfile;
 
 /// Validation error types.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::enum_variant_names)]
 pub enum ValidationError {
     /// Invalid user ID
     InvalidUserId,
@@ -75,7 +76,7 @@ pub fn validate_batch(profiles: &Vec<UserProfile>) -> Result<(), &'static str> {
     // Validate each profile
     let env = Env::default(); // Note: In production, pass env as parameter
     for profile in profiles.iter() {
-        if let Err(_) = validate_user_profile(&env, &profile) {
+        if validate_user_profile(&env, &profile).is_err() {
             return Err("Invalid user profile in batch");
         }
     }
@@ -86,7 +87,7 @@ pub fn validate_batch(profiles: &Vec<UserProfile>) -> Result<(), &'static str> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, Env, Symbol};
+    use soroban_sdk::{testutils::Address as _, Address, Env, Symbol};
 
     fn create_test_profile(env: &Env, user_id: u64, income: i128, expenses: i128) -> UserProfile {
         UserProfile {
@@ -95,7 +96,7 @@ mod tests {
             monthly_income: income,
             monthly_expenses: expenses,
             savings_balance: 0,
-            spending_categories: Symbol::new(env, "food,transport"),
+            spending_categories: Symbol::new(env, "food_transport"),
             risk_tolerance: 3,
         }
     }
@@ -0,0 +1,348 @@
+//! # Claimable Onboarding Contract
+//!
+//! Lets an organization set up an employee's account before the employee has
+//! a Stellar address. The sponsor deposits a starter balance and lists the
+//! roles to grant, keyed by a commitment to the employee's identity (the hash
+//! of their email address). Once the employee has an address, the identity
+//! provider signs a claim binding the commitment to it, and the employee
+//! presents that signature to receive the balance and roles in one call.
+//!
+//! Roles are granted through the access control contract with this contract
+//! as the caller, so this contract must be that contract's admin.
+//!
+//! ## Features
+//!
+//! - **Identity Commitments**: Employees are identified only by a hash on-chain
+//! - **Attested Claims**: Claims need a signature from the identity provider
+//! - **Atomic Setup**: Balance and roles are delivered together or not at all
+//! - **Expiry**: Sponsors can take back onboardings nobody claimed in time
+#![no_std]
+
+mod types;
+
+use access_control_interface::{AccessControlClient, Role};
+use common::reentrancy::ReentrancyGuard;
+use common::token_io::{self, TransferError};
+use common::versioning;
+use soroban_sdk::{
+    contract, contractimpl, panic_with_error, xdr::ToXdr, Address, BytesN, Env, Vec,
+};
+
+pub use crate::types::{
+    DataKey, Onboarding, OnboardingEvents, MAX_CLAIM_WINDOW_SECS, MAX_ONBOARDING_ROLES,
+};
+
+/// Error codes for the claimable onboarding contract.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum OnboardingError {
+    /// Contract not initialized
+    NotInitialized = 1,
+    /// Caller is not authorized
+    Unauthorized = 2,
+    /// Contract already initialized
+    AlreadyInitialized = 3,
+    /// Starter balance is negative
+    InvalidAmount = 4,
+    /// An onboarding already exists for this commitment
+    AlreadyExists = 5,
+    /// No onboarding exists for this commitment
+    NotFound = 6,
+    /// Onboarding was already claimed
+    AlreadyClaimed = 7,
+    /// Onboarding expired before it was claimed
+    Expired = 8,
+    /// Onboarding cannot be reclaimed before it expires
+    NotExpired = 9,
+    /// Too many roles, or a role that cannot be granted on claim
+    InvalidRoles = 10,
+    /// Expiry is in the past or too far ahead
+    InvalidExpiry = 11,
+    /// Token transfer did not move the expected amount
+    TokenTransferFailed = 12,
+    /// Storage was written by a different layout version
+    StorageVersionMismatch = 13,
+    /// Migration source version does not match stored version
+    InvalidMigration = 14,
+    /// Entrypoint was re-entered during an external call
+    Reentrancy = 15,
+}
+
+impl From<OnboardingError> for soroban_sdk::Error {
+    fn from(e: OnboardingError) -> Self {
+        soroban_sdk::Error::from_contract_error(e as u32)
+    }
+}
+
+/// Storage layout version this code expects.
+pub const STORAGE_VERSION: u32 = 1;
+
+#[contract]
+pub struct ClaimableOnboardingContract;
+
+#[contractimpl]
+impl ClaimableOnboardingContract {
+    /// Initializes the contract with an admin, the token starter balances are
+    /// paid in, the access control contract roles are granted through and the
+    /// identity provider's ed25519 public key.
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        token: Address,
+        access_control: Address,
+        identity_provider: BytesN<32>,
+    ) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic_with_error!(&env, OnboardingError::AlreadyInitialized);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        versioning::set_version(&env, STORAGE_VERSION);
+        env.storage().instance().set(&DataKey::Token, &token);
+        env.storage()
+            .instance()
+            .set(&DataKey::AccessControl, &access_control);
+        env.storage()
+            .instance()
+            .set(&DataKey::IdentityProvider, &identity_provider);
+    }
+
+    /// Migrates storage written by an older version of this contract to the
+    /// current layout (admin only). `from_version` must match the stored
+    /// version.
+    pub fn migrate(env: Env, caller: Address, from_version: u32) {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        versioning::migrate(&env, from_version, STORAGE_VERSION, |_, _| {})
+            .unwrap_or_else(|_| panic_with_error!(&env, OnboardingError::InvalidMigration));
+    }
+
+    /// Returns the storage layout version currently stored.
+    pub fn get_storage_version(env: Env) -> u32 {
+        versioning::stored_version(&env)
+    }
+
+    /// Rotates the identity provider's signing key (admin only). Claims signed
+    /// with the previous key stop being accepted.
+    pub fn set_identity_provider(env: Env, caller: Address, key: BytesN<32>) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::IdentityProvider, &key);
+        OnboardingEvents::provider_set(&env, &key);
+    }
+
+    /// Returns the identity provider's signing key.
+    pub fn get_identity_provider(env: Env) -> BytesN<32> {
+        env.storage()
+            .instance()
+            .get(&DataKey::IdentityProvider)
+            .unwrap_or_else(|| panic_with_error!(&env, OnboardingError::NotInitialized))
+    }
+
+    /// Sets up an onboarding for the employee behind `commitment`, funding
+    /// its starter balance from the sponsor.
+    ///
+    /// The employee can claim it until `expires_at`. `Admin` cannot be among
+    /// the roles granted on claim.
+    pub fn create_onboarding(
+        env: Env,
+        sponsor: Address,
+        commitment: BytesN<32>,
+        amount: i128,
+        roles: Vec<Role>,
+        expires_at: u64,
+    ) -> Onboarding {
+        Self::require_storage_version(&env);
+        sponsor.require_auth();
+        let _guard = Self::enter_guard(&env);
+
+        if amount < 0 {
+            panic_with_error!(&env, OnboardingError::InvalidAmount);
+        }
+        if roles.len() > MAX_ONBOARDING_ROLES || roles.contains(Role::Admin) {
+            panic_with_error!(&env, OnboardingError::InvalidRoles);
+        }
+        let now = env.ledger().timestamp();
+        if expires_at <= now || expires_at - now > MAX_CLAIM_WINDOW_SECS {
+            panic_with_error!(&env, OnboardingError::InvalidExpiry);
+        }
+        let key = DataKey::Onboarding(commitment.clone());
+        if env.storage().persistent().has(&key) {
+            panic_with_error!(&env, OnboardingError::AlreadyExists);
+        }
+
+        if amount > 0 {
+            let contract = env.current_contract_address();
+            Self::send(&env, &sponsor, &contract, amount);
+        }
+
+        let onboarding = Onboarding {
+            sponsor,
+            amount,
+            roles,
+            expires_at,
+            claimant: None,
+        };
+        env.storage().persistent().set(&key, &onboarding);
+        OnboardingEvents::created(&env, &commitment, &onboarding);
+
+        onboarding
+    }
+
+    /// Claims the onboarding behind `commitment` for `claimant`.
+    ///
+    /// `signature` is the identity provider's ed25519 signature over the XDR
+    /// of `(this contract, commitment, claimant)`, issued once the provider
+    /// has verified the employee. The starter balance is paid and every role
+    /// granted in this call; if any step fails, none of it happens.
+    pub fn claim(
+        env: Env,
+        claimant: Address,
+        commitment: BytesN<32>,
+        signature: BytesN<64>,
+    ) -> Onboarding {
+        Self::require_storage_version(&env);
+        claimant.require_auth();
+        let _guard = Self::enter_guard(&env);
+
+        let mut onboarding = Self::load(&env, &commitment);
+        if onboarding.claimant.is_some() {
+            panic_with_error!(&env, OnboardingError::AlreadyClaimed);
+        }
+        if env.ledger().timestamp() >= onboarding.expires_at {
+            panic_with_error!(&env, OnboardingError::Expired);
+        }
+
+        // Panics if the signature does not match
+        let message = (
+            env.current_contract_address(),
+            commitment.clone(),
+            claimant.clone(),
+        )
+            .to_xdr(&env);
+        let provider = Self::get_identity_provider(env.clone());
+        env.crypto().ed25519_verify(&provider, &message, &signature);
+
+        if onboarding.amount > 0 {
+            let contract = env.current_contract_address();
+            Self::send(&env, &contract, &claimant, onboarding.amount);
+        }
+        if !onboarding.roles.is_empty() {
+            let access_control: Address = env
+                .storage()
+                .instance()
+                .get(&DataKey::AccessControl)
+                .unwrap_or_else(|| panic_with_error!(&env, OnboardingError::NotInitialized));
+            let client = AccessControlClient::new(&env, &access_control);
+            let contract = env.current_contract_address();
+            for role in onboarding.roles.iter() {
+                client.grant_role(&contract, &claimant, &role);
+            }
+        }
+
+        onboarding.claimant = Some(claimant.clone());
+        env.storage()
+            .persistent()
+            .set(&DataKey::Onboarding(commitment.clone()), &onboarding);
+        OnboardingEvents::claimed(&env, &commitment, &claimant, onboarding.amount);
+
+        onboarding
+    }
+
+    /// Returns the starter balance of an expired, unclaimed onboarding to its
+    /// sponsor and removes it (sponsor only).
+    pub fn reclaim(env: Env, sponsor: Address, commitment: BytesN<32>) -> i128 {
+        Self::require_storage_version(&env);
+        sponsor.require_auth();
+        let _guard = Self::enter_guard(&env);
+
+        let onboarding = Self::load(&env, &commitment);
+        if onboarding.sponsor != sponsor {
+            panic_with_error!(&env, OnboardingError::Unauthorized);
+        }
+        if onboarding.claimant.is_some() {
+            panic_with_error!(&env, OnboardingError::AlreadyClaimed);
+        }
+        if env.ledger().timestamp() < onboarding.expires_at {
+            panic_with_error!(&env, OnboardingError::NotExpired);
+        }
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Onboarding(commitment.clone()));
+        if onboarding.amount > 0 {
+            let contract = env.current_contract_address();
+            Self::send(&env, &contract, &sponsor, onboarding.amount);
+        }
+        OnboardingEvents::reclaimed(&env, &commitment, &sponsor, onboarding.amount);
+
+        onboarding.amount
+    }
+
+    /// Returns the onboarding behind `commitment`, if any.
+    pub fn get_onboarding(env: Env, commitment: BytesN<32>) -> Option<Onboarding> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Onboarding(commitment))
+    }
+
+    /// Returns the admin address.
+    pub fn get_admin(env: Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(&env, OnboardingError::NotInitialized))
+    }
+
+    // Internal helper to load an onboarding or fail
+    fn load(env: &Env, commitment: &BytesN<32>) -> Onboarding {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Onboarding(commitment.clone()))
+            .unwrap_or_else(|| panic_with_error!(env, OnboardingError::NotFound))
+    }
+
+    // Internal helper moving the starter balance token with balance checks
+    fn send(env: &Env, from: &Address, to: &Address, amount: i128) {
+        let token: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Token)
+            .unwrap_or_else(|| panic_with_error!(env, OnboardingError::NotInitialized));
+        token_io::safe_transfer(env, &token, from, to, amount).unwrap_or_else(|e| match e {
+            TransferError::InvalidAmount => {
+                panic_with_error!(env, OnboardingError::InvalidAmount)
+            }
+            _ => panic_with_error!(env, OnboardingError::TokenTransferFailed),
+        })
+    }
+
+    // Internal helper taking the reentrancy lock for entrypoints that call
+    // out to the token or the access control contract
+    fn enter_guard(env: &Env) -> ReentrancyGuard {
+        ReentrancyGuard::enter(env)
+            .unwrap_or_else(|_| panic_with_error!(env, OnboardingError::Reentrancy))
+    }
+
+    // Internal helper refusing to run against storage written by a different
+    // layout version
+    fn require_storage_version(env: &Env) {
+        versioning::require_version(env, STORAGE_VERSION)
+            .unwrap_or_else(|_| panic_with_error!(env, OnboardingError::StorageVersionMismatch));
+    }
+
+    // Internal helper to verify admin
+    fn require_admin(env: &Env, caller: &Address) {
+        if *caller != Self::get_admin(env.clone()) {
+            panic_with_error!(env, OnboardingError::Unauthorized);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,223 @@
+//! Integration tests for the claimable onboarding contract.
+
+#![cfg(test)]
+
+extern crate std;
+
+use crate::{
+    ClaimableOnboardingContract, ClaimableOnboardingContractClient, OnboardingError,
+    MAX_CLAIM_WINDOW_SECS,
+};
+use access_control_interface::{rbac, Role};
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, vec,
+    xdr::ToXdr,
+    Address, BytesN, Env, Vec,
+};
+
+/// Access control stand-in backed by the embeddable RBAC module. Like the
+/// real contract, only its admin may grant roles.
+#[soroban_sdk::contract]
+struct MockAccessControl;
+
+#[soroban_sdk::contractimpl]
+impl MockAccessControl {
+    pub fn __constructor(env: Env, admin: Address) {
+        env.storage().instance().set(&0u32, &admin);
+    }
+
+    pub fn grant_role(env: Env, caller: Address, user: Address, role: Role) {
+        caller.require_auth();
+        let admin: Address = env.storage().instance().get(&0u32).unwrap();
+        assert_eq!(caller, admin);
+        rbac::grant_role(&env, &user, role).unwrap();
+    }
+
+    pub fn has_role(env: Env, user: Address, role: Role) -> bool {
+        rbac::has_role(&env, &user, role)
+    }
+}
+
+struct Setup {
+    env: Env,
+    sponsor: Address,
+    provider: SigningKey,
+    token: token::Client<'static>,
+    roles: MockAccessControlClient<'static>,
+    client: ClaimableOnboardingContractClient<'static>,
+}
+
+/// Creates a test environment with the contract deployed and initialized as
+/// the admin of an access control contract, and a funded sponsor.
+fn setup_test_env() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let xlm = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let token = token::Client::new(&env, &xlm.address());
+
+    let contract_id = env.register(ClaimableOnboardingContract, ());
+    let client = ClaimableOnboardingContractClient::new(&env, &contract_id);
+    let access_control = env.register(MockAccessControl, (contract_id.clone(),));
+    let roles = MockAccessControlClient::new(&env, &access_control);
+
+    let provider = SigningKey::from_bytes(&[7; 32]);
+    let provider_key = BytesN::from_array(&env, &provider.verifying_key().to_bytes());
+    client.initialize(
+        &Address::generate(&env),
+        &xlm.address(),
+        &access_control,
+        &provider_key,
+    );
+
+    let sponsor = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &xlm.address()).mint(&sponsor, &10_000);
+
+    Setup {
+        env,
+        sponsor,
+        provider,
+        token,
+        roles,
+        client,
+    }
+}
+
+/// Commitment to an employee's identity, as the org would derive it.
+fn commitment(env: &Env, email: &str) -> BytesN<32> {
+    env.crypto()
+        .sha256(&soroban_sdk::Bytes::from_slice(env, email.as_bytes()))
+        .into()
+}
+
+/// The identity provider's signature binding `commitment` to `claimant`.
+fn sign_claim(
+    s: &Setup,
+    key: &SigningKey,
+    commitment: &BytesN<32>,
+    claimant: &Address,
+) -> BytesN<64> {
+    let message = (
+        s.client.address.clone(),
+        commitment.clone(),
+        claimant.clone(),
+    )
+        .to_xdr(&s.env);
+    let bytes: std::vec::Vec<u8> = message.iter().collect();
+    BytesN::from_array(&s.env, &key.sign(&bytes).to_bytes())
+}
+
+#[test]
+fn test_claim_delivers_balance_and_roles() {
+    let s = setup_test_env();
+    let employee = commitment(&s.env, "ana@example.com");
+    s.client.create_onboarding(
+        &s.sponsor,
+        &employee,
+        &2_500,
+        &vec![&s.env, Role::User, Role::Operator],
+        &(1_000 + 86_400),
+    );
+    assert_eq!(s.token.balance(&s.client.address), 2_500);
+
+    let account = Address::generate(&s.env);
+    let signature = sign_claim(&s, &s.provider, &employee, &account);
+    let onboarding = s.client.claim(&account, &employee, &signature);
+
+    assert_eq!(onboarding.claimant, Some(account.clone()));
+    assert_eq!(s.token.balance(&account), 2_500);
+    assert!(s.roles.has_role(&account, &Role::User));
+    assert!(s.roles.has_role(&account, &Role::Operator));
+
+    // A second claim, even by the same account, is rejected
+    let result = s.client.try_claim(&account, &employee, &signature);
+    assert_eq!(result, Err(Ok(OnboardingError::AlreadyClaimed.into())));
+}
+
+#[test]
+#[should_panic]
+fn test_claim_requires_provider_signature_for_claimant() {
+    let s = setup_test_env();
+    let employee = commitment(&s.env, "ana@example.com");
+    s.client.create_onboarding(
+        &s.sponsor,
+        &employee,
+        &2_500,
+        &Vec::new(&s.env),
+        &(1_000 + 86_400),
+    );
+
+    // A signature issued for one account cannot be replayed by another
+    let signature = sign_claim(&s, &s.provider, &employee, &Address::generate(&s.env));
+    s.client
+        .claim(&Address::generate(&s.env), &employee, &signature);
+}
+
+#[test]
+#[should_panic]
+fn test_claim_rejects_other_signers() {
+    let s = setup_test_env();
+    let employee = commitment(&s.env, "ana@example.com");
+    s.client.create_onboarding(
+        &s.sponsor,
+        &employee,
+        &2_500,
+        &Vec::new(&s.env),
+        &(1_000 + 86_400),
+    );
+
+    let account = Address::generate(&s.env);
+    let forger = SigningKey::from_bytes(&[9; 32]);
+    let signature = sign_claim(&s, &forger, &employee, &account);
+    s.client.claim(&account, &employee, &signature);
+}
+
+#[test]
+fn test_sponsor_reclaims_after_expiry() {
+    let s = setup_test_env();
+    let employee = commitment(&s.env, "ana@example.com");
+    s.client
+        .create_onboarding(&s.sponsor, &employee, &2_500, &Vec::new(&s.env), &2_000);
+
+    let result = s.client.try_reclaim(&s.sponsor, &employee);
+    assert_eq!(result, Err(Ok(OnboardingError::NotExpired.into())));
+
+    s.env.ledger().with_mut(|li| li.timestamp = 2_000);
+    let account = Address::generate(&s.env);
+    let signature = sign_claim(&s, &s.provider, &employee, &account);
+    let result = s.client.try_claim(&account, &employee, &signature);
+    assert_eq!(result, Err(Ok(OnboardingError::Expired.into())));
+
+    assert_eq!(s.client.reclaim(&s.sponsor, &employee), 2_500);
+    assert_eq!(s.token.balance(&s.sponsor), 10_000);
+    assert_eq!(s.client.get_onboarding(&employee), None);
+}
+
+#[test]
+fn test_create_onboarding_validation() {
+    let s = setup_test_env();
+    let employee = commitment(&s.env, "ana@example.com");
+    let no_roles: Vec<Role> = Vec::new(&s.env);
+
+    let admin_role = vec![&s.env, Role::Admin];
+    let result = s
+        .client
+        .try_create_onboarding(&s.sponsor, &employee, &0, &admin_role, &2_000);
+    assert_eq!(result, Err(Ok(OnboardingError::InvalidRoles.into())));
+
+    let too_late = 1_000 + MAX_CLAIM_WINDOW_SECS + 1;
+    let result = s
+        .client
+        .try_create_onboarding(&s.sponsor, &employee, &0, &no_roles, &too_late);
+    assert_eq!(result, Err(Ok(OnboardingError::InvalidExpiry.into())));
+
+    s.client
+        .create_onboarding(&s.sponsor, &employee, &0, &no_roles, &2_000);
+    let result = s
+        .client
+        .try_create_onboarding(&s.sponsor, &employee, &0, &no_roles, &2_000);
+    assert_eq!(result, Err(Ok(OnboardingError::AlreadyExists.into())));
+}
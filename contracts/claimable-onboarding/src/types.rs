@@ -0,0 +1,81 @@
+//! Data types and events for the claimable onboarding contract.
+
+use access_control_interface::Role;
+use common::events;
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Vec};
+
+/// Maximum number of roles granted by a single onboarding.
+pub const MAX_ONBOARDING_ROLES: u32 = 3;
+
+/// Longest time an onboarding may stay claimable (90 days), in seconds.
+pub const MAX_CLAIM_WINDOW_SECS: u64 = 90 * 86_400;
+
+/// Account set up by a sponsor for an employee who has not claimed it yet.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Onboarding {
+    /// Org account that funded the onboarding
+    pub sponsor: Address,
+    /// Starter balance held until the employee claims it
+    pub amount: i128,
+    /// Roles granted to the employee on claim
+    pub roles: Vec<Role>,
+    /// Ledger timestamp after which the sponsor may reclaim the funds
+    pub expires_at: u64,
+    /// Address that claimed the onboarding, once claimed
+    pub claimant: Option<Address>,
+}
+
+/// Storage keys for the claimable onboarding contract.
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    /// Admin address
+    Admin,
+    /// Token starter balances are paid in
+    Token,
+    /// Access control contract roles are granted through
+    AccessControl,
+    /// Ed25519 public key of the identity provider signing claims
+    IdentityProvider,
+    /// Onboarding by the employee's identity commitment
+    Onboarding(BytesN<32>),
+}
+
+/// Event emitters for onboarding operations.
+pub struct OnboardingEvents;
+
+impl OnboardingEvents {
+    /// Emitted when a sponsor sets up an onboarding.
+    pub fn created(env: &Env, commitment: &BytesN<32>, onboarding: &Onboarding) {
+        let topics = (symbol_short!("onboard"), symbol_short!("created"));
+        events::publish(
+            env,
+            topics,
+            (
+                commitment.clone(),
+                onboarding.sponsor.clone(),
+                onboarding.amount,
+                onboarding.expires_at,
+            ),
+        );
+    }
+
+    /// Emitted when an employee claims their onboarding.
+    pub fn claimed(env: &Env, commitment: &BytesN<32>, claimant: &Address, amount: i128) {
+        let topics = (symbol_short!("onboard"), symbol_short!("claimed"));
+        events::publish(env, topics, (commitment.clone(), claimant.clone(), amount));
+    }
+
+    /// Emitted when a sponsor takes back an expired, unclaimed onboarding.
+    pub fn reclaimed(env: &Env, commitment: &BytesN<32>, sponsor: &Address, amount: i128) {
+        let topics = (symbol_short!("onboard"), symbol_short!("reclaim"));
+        events::publish(env, topics, (commitment.clone(), sponsor.clone(), amount));
+    }
+
+    /// Emitted when the identity provider key is set or rotated.
+    pub fn provider_set(env: &Env, key: &BytesN<32>) {
+        let topics = (symbol_short!("onboard"), symbol_short!("provider"));
+        events::publish(env, topics, key.clone());
+    }
+}
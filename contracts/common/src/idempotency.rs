@@ -0,0 +1,213 @@
+//! Client-supplied idempotency keys.
+//!
+//! Payment entrypoints accept an optional key chosen by the client. The first
+//! call with a key stores its result; a retry with the same key returns that
+//! result instead of paying again. Keys are scoped to the caller, so two
+//! clients cannot collide, and each record holds a fingerprint of the call's
+//! arguments so a key reused for a different payment is rejected rather than
+//! silently answered with the wrong result.
+//!
+//! Records expire after the calling contract's retention window. They live in
+//! temporary storage, so the network reclaims them once expired.
+
+use soroban_sdk::{contracttype, xdr::ToXdr, Address, BytesN, Env, TryFromVal, Val};
+
+/// Retention window used when the contract has no override (24 hours).
+pub const DEFAULT_RETENTION_SECS: u64 = 86_400;
+
+/// Inclusive bounds for the retention window (1 minute to 30 days).
+pub const RETENTION_BOUNDS: (u64, u64) = (60, 30 * 86_400);
+
+/// Target ledger close time, used to size the storage TTL of a record.
+const LEDGER_SECS: u64 = 5;
+
+/// Errors returned by the idempotency helpers.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum IdempotencyError {
+    /// The key was already used by this caller for a different call
+    KeyReused,
+    /// The retention window is outside `RETENTION_BOUNDS`
+    OutOfBounds,
+}
+
+/// Storage keys for idempotency records.
+#[derive(Clone)]
+#[contracttype]
+pub enum IdempotencyKey {
+    /// Retention window override, in seconds
+    Retention,
+    /// Record of a completed call by caller and key
+    Record(Address, BytesN<32>),
+}
+
+/// Returns the calling contract's retention window in seconds.
+pub fn get_retention(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&IdempotencyKey::Retention)
+        .unwrap_or(DEFAULT_RETENTION_SECS)
+}
+
+/// Overrides the retention window. Applies to records stored from now on.
+/// Callers are responsible for authorizing the change.
+pub fn set_retention(env: &Env, secs: u64) -> Result<(), IdempotencyError> {
+    let (min, max) = RETENTION_BOUNDS;
+    if secs < min || secs > max {
+        return Err(IdempotencyError::OutOfBounds);
+    }
+    env.storage()
+        .instance()
+        .set(&IdempotencyKey::Retention, &secs);
+    Ok(())
+}
+
+/// Hashes a call's arguments into the fingerprint stored with its result.
+pub fn fingerprint<A: ToXdr>(env: &Env, args: A) -> BytesN<32> {
+    env.crypto().sha256(&args.to_xdr(env)).into()
+}
+
+/// Returns the stored result of an earlier call by `caller` with `key`, or
+/// `None` if there is no live record and the call should go ahead.
+///
+/// Fails if the live record was stored for a call with a different
+/// fingerprint.
+pub fn recall<T>(
+    env: &Env,
+    caller: &Address,
+    key: &BytesN<32>,
+    fingerprint: &BytesN<32>,
+) -> Result<Option<T>, IdempotencyError>
+where
+    T: TryFromVal<Env, Val>,
+{
+    let storage_key = IdempotencyKey::Record(caller.clone(), key.clone());
+    let record: Option<(BytesN<32>, u64, T)> = env.storage().temporary().get(&storage_key);
+    match record {
+        Some((_, expires_at, _)) if env.ledger().timestamp() >= expires_at => Ok(None),
+        Some((stored, _, _)) if stored != *fingerprint => Err(IdempotencyError::KeyReused),
+        Some((_, _, result)) => Ok(Some(result)),
+        None => Ok(None),
+    }
+}
+
+/// Stores `result` as the outcome of the call by `caller` with `key`, for the
+/// retention window.
+pub fn remember<T>(
+    env: &Env,
+    caller: &Address,
+    key: &BytesN<32>,
+    fingerprint: &BytesN<32>,
+    result: &T,
+) where
+    T: Clone,
+    Val: TryFromVal<Env, T>,
+{
+    let retention = get_retention(env);
+    let expires_at = env.ledger().timestamp().saturating_add(retention);
+    let storage_key = IdempotencyKey::Record(caller.clone(), key.clone());
+    let record = (fingerprint.clone(), expires_at, result.clone());
+
+    let storage = env.storage().temporary();
+    storage.set(&storage_key, &record);
+    let ttl = (retention / LEDGER_SECS + 1) as u32;
+    storage.extend_ttl(&storage_key, ttl, ttl);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{
+        contract,
+        testutils::{Address as _, Ledger},
+        vec, Vec,
+    };
+
+    #[contract]
+    struct Host;
+
+    fn setup() -> (Env, Address, Address, BytesN<32>) {
+        let env = Env::default();
+        let host = env.register(Host, ());
+        let caller = Address::generate(&env);
+        let key = BytesN::from_array(&env, &[7; 32]);
+        (env, host, caller, key)
+    }
+
+    #[test]
+    fn test_repeat_returns_original_result() {
+        let (env, host, caller, key) = setup();
+
+        env.as_contract(&host, || {
+            let print = fingerprint(&env, vec![&env, 100i128, 200i128]);
+            assert_eq!(recall::<i128>(&env, &caller, &key, &print), Ok(None));
+
+            remember(&env, &caller, &key, &print, &300i128);
+            assert_eq!(recall::<i128>(&env, &caller, &key, &print), Ok(Some(300)));
+
+            // Keys are scoped to the caller
+            let other = Address::generate(&env);
+            assert_eq!(recall::<i128>(&env, &other, &key, &print), Ok(None));
+        });
+    }
+
+    #[test]
+    fn test_key_reused_for_different_call() {
+        let (env, host, caller, key) = setup();
+
+        env.as_contract(&host, || {
+            let first: Vec<i128> = vec![&env, 100];
+            let second: Vec<i128> = vec![&env, 101];
+            remember(&env, &caller, &key, &fingerprint(&env, first), &100i128);
+
+            let print = fingerprint(&env, second);
+            let result = recall::<i128>(&env, &caller, &key, &print);
+            assert_eq!(result, Err(IdempotencyError::KeyReused));
+        });
+    }
+
+    #[test]
+    fn test_records_expire_after_retention() {
+        let (env, host, caller, key) = setup();
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+        env.as_contract(&host, || {
+            assert_eq!(get_retention(&env), DEFAULT_RETENTION_SECS);
+            set_retention(&env, 600).unwrap();
+
+            let print = fingerprint(&env, 1u32);
+            remember(&env, &caller, &key, &print, &true);
+        });
+
+        env.ledger().with_mut(|li| li.timestamp = 1_599);
+        env.as_contract(&host, || {
+            assert_eq!(
+                recall::<bool>(&env, &caller, &key, &fingerprint(&env, 1u32)),
+                Ok(Some(true))
+            );
+        });
+
+        // Once expired the key may be reused, even for a different call
+        env.ledger().with_mut(|li| li.timestamp = 1_600);
+        env.as_contract(&host, || {
+            assert_eq!(
+                recall::<bool>(&env, &caller, &key, &fingerprint(&env, 2u32)),
+                Ok(None)
+            );
+        });
+    }
+
+    #[test]
+    fn test_retention_bounds() {
+        let (env, host, _, _) = setup();
+
+        env.as_contract(&host, || {
+            assert_eq!(set_retention(&env, 59), Err(IdempotencyError::OutOfBounds));
+            assert_eq!(
+                set_retention(&env, 30 * 86_400 + 1),
+                Err(IdempotencyError::OutOfBounds)
+            );
+            assert_eq!(set_retention(&env, 60), Ok(()));
+            assert_eq!(get_retention(&env), 60);
+        });
+    }
+}
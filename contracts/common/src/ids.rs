@@ -0,0 +1,174 @@
+//! Deterministic id generation.
+//!
+//! Ids are issued from counters scoped by organization and domain (e.g. the
+//! escrows of org 0). The organization is packed into the high bits of the id,
+//! so ids from different orgs never collide, and every counter is overflow
+//! checked. Org 0 ids are plain sequence numbers starting at 1.
+
+use soroban_sdk::{contracttype, Env, Symbol};
+
+/// Number of low bits holding the sequence number.
+pub const SEQUENCE_BITS: u32 = 40;
+
+/// Largest sequence number a scope can issue.
+pub const MAX_SEQUENCE: u64 = (1 << SEQUENCE_BITS) - 1;
+
+/// Largest organization id that fits in the remaining high bits.
+pub const MAX_ORG: u32 = (1 << (64 - SEQUENCE_BITS)) - 1;
+
+/// Storage keys for id counters.
+#[derive(Clone)]
+#[contracttype]
+pub enum IdKey {
+    /// Last sequence issued for an (org, domain) scope
+    Sequence(u32, Symbol),
+}
+
+/// Scope an id counter belongs to.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IdScope {
+    /// Organization id
+    pub org: u32,
+    /// Kind of record the ids are for, e.g. `escrow` or `batch`
+    pub domain: Symbol,
+}
+
+impl IdScope {
+    pub fn new(org: u32, domain: Symbol) -> Self {
+        IdScope { org, domain }
+    }
+
+    fn key(&self) -> IdKey {
+        IdKey::Sequence(self.org, self.domain.clone())
+    }
+}
+
+/// Errors raised while issuing ids.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum IdError {
+    /// Organization id does not fit in the id layout
+    InvalidOrg,
+    /// Scope has issued its last sequence number
+    SequenceExhausted,
+}
+
+/// Issues the next id for `scope`.
+pub fn next_id(env: &Env, scope: &IdScope) -> Result<u64, IdError> {
+    if scope.org > MAX_ORG {
+        return Err(IdError::InvalidOrg);
+    }
+
+    let sequence = last_sequence(env, scope)
+        .checked_add(1)
+        .filter(|seq| *seq <= MAX_SEQUENCE)
+        .ok_or(IdError::SequenceExhausted)?;
+    env.storage().persistent().set(&scope.key(), &sequence);
+
+    Ok(encode(scope.org, sequence))
+}
+
+/// Returns the last id issued for `scope`, if any.
+pub fn last_id(env: &Env, scope: &IdScope) -> Option<u64> {
+    match last_sequence(env, scope) {
+        0 => None,
+        sequence => Some(encode(scope.org, sequence)),
+    }
+}
+
+/// Advances `scope` so it never reissues a sequence at or below `last_issued`.
+///
+/// Used when a contract moves off a legacy counter; never moves a counter back.
+pub fn reserve_through(env: &Env, scope: &IdScope, last_issued: u64) -> Result<(), IdError> {
+    if last_issued > MAX_SEQUENCE {
+        return Err(IdError::SequenceExhausted);
+    }
+    if last_issued > last_sequence(env, scope) {
+        env.storage().persistent().set(&scope.key(), &last_issued);
+    }
+    Ok(())
+}
+
+/// Splits an id into its (org, sequence) parts.
+pub fn decode(id: u64) -> (u32, u64) {
+    ((id >> SEQUENCE_BITS) as u32, id & MAX_SEQUENCE)
+}
+
+fn encode(org: u32, sequence: u64) -> u64 {
+    ((org as u64) << SEQUENCE_BITS) | sequence
+}
+
+fn last_sequence(env: &Env, scope: &IdScope) -> u64 {
+    env.storage().persistent().get(&scope.key()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{contract, symbol_short};
+
+    #[contract]
+    struct IdHost;
+
+    fn with_host<T>(f: impl FnOnce(&Env) -> T) -> T {
+        let env = Env::default();
+        let host = env.register(IdHost, ());
+        env.as_contract(&host, || f(&env))
+    }
+
+    #[test]
+    fn test_sequences_start_at_one() {
+        with_host(|env| {
+            let scope = IdScope::new(0, symbol_short!("escrow"));
+            assert_eq!(last_id(env, &scope), None);
+            assert_eq!(next_id(env, &scope), Ok(1));
+            assert_eq!(next_id(env, &scope), Ok(2));
+            assert_eq!(last_id(env, &scope), Some(2));
+        });
+    }
+
+    #[test]
+    fn test_scopes_are_independent() {
+        with_host(|env| {
+            let escrows = IdScope::new(0, symbol_short!("escrow"));
+            let batches = IdScope::new(0, symbol_short!("batch"));
+            let other_org = IdScope::new(7, symbol_short!("escrow"));
+
+            assert_eq!(next_id(env, &escrows), Ok(1));
+            assert_eq!(next_id(env, &batches), Ok(1));
+
+            let id = next_id(env, &other_org).unwrap();
+            assert_ne!(id, 1);
+            assert_eq!(decode(id), (7, 1));
+        });
+    }
+
+    #[test]
+    fn test_sequence_exhaustion() {
+        with_host(|env| {
+            let scope = IdScope::new(0, symbol_short!("batch"));
+            reserve_through(env, &scope, MAX_SEQUENCE).unwrap();
+
+            assert_eq!(next_id(env, &scope), Err(IdError::SequenceExhausted));
+            assert_eq!(last_id(env, &scope), Some(MAX_SEQUENCE));
+        });
+    }
+
+    #[test]
+    fn test_invalid_org() {
+        with_host(|env| {
+            let scope = IdScope::new(MAX_ORG + 1, symbol_short!("batch"));
+            assert_eq!(next_id(env, &scope), Err(IdError::InvalidOrg));
+        });
+    }
+
+    #[test]
+    fn test_reserve_through_never_moves_back() {
+        with_host(|env| {
+            let scope = IdScope::new(0, symbol_short!("escrow"));
+            reserve_through(env, &scope, 10).unwrap();
+            reserve_through(env, &scope, 3).unwrap();
+
+            assert_eq!(next_id(env, &scope), Ok(11));
+        });
+    }
+}
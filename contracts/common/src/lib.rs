@@ -0,0 +1,20 @@
+//! # StellarSpend Common
+//!
+//! Helpers shared by the StellarSpend contracts. This crate holds no contract
+//! of its own; each module works against the storage of the calling contract.
+#![no_std]
+
+pub mod events;
+pub mod footprint;
+pub mod idempotency;
+pub mod ids;
+pub mod limits;
+pub mod math;
+pub mod merkle;
+pub mod normalize;
+pub mod pagination;
+pub mod reentrancy;
+pub mod reference;
+pub mod rounding;
+pub mod token_io;
+pub mod versioning;
@@ -0,0 +1,136 @@
+//! Protocol limits registry.
+//!
+//! Size limits (batch sizes, page sizes, memo and schedule lengths) are
+//! defined here once. Each contract stores its own overrides, and admins may
+//! only move a limit within the hard bounds compiled in below. Contracts with
+//! no override use the default.
+
+use soroban_sdk::{contracttype, Env};
+
+/// A configurable protocol limit.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum Limit {
+    /// Maximum number of items in one batch call
+    BatchSize,
+    /// Maximum number of entries a list query returns
+    PageSize,
+    /// Maximum memo length, in bytes
+    MemoLength,
+    /// Maximum number of entries in a payment schedule
+    ScheduleLength,
+}
+
+impl Limit {
+    /// Value used when the contract has no override.
+    pub const fn default_value(self) -> u32 {
+        match self {
+            Limit::BatchSize => 100,
+            Limit::PageSize => 100,
+            Limit::MemoLength => 32,
+            Limit::ScheduleLength => 52,
+        }
+    }
+
+    /// Inclusive bounds an override must fall within.
+    pub const fn hard_bounds(self) -> (u32, u32) {
+        match self {
+            Limit::BatchSize => (1, 500),
+            Limit::PageSize => (1, 200),
+            Limit::MemoLength => (1, 256),
+            Limit::ScheduleLength => (1, 366),
+        }
+    }
+}
+
+/// Errors returned by the limits registry.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum LimitError {
+    /// The value is outside the limit's hard bounds
+    OutOfBounds,
+}
+
+/// Storage keys for limit overrides.
+#[derive(Clone)]
+#[contracttype]
+pub enum LimitKey {
+    /// Override for a single limit
+    Value(Limit),
+}
+
+/// Returns the calling contract's value for `limit`.
+pub fn get(env: &Env, limit: Limit) -> u32 {
+    env.storage()
+        .instance()
+        .get(&LimitKey::Value(limit))
+        .unwrap_or(limit.default_value())
+}
+
+/// Overrides `limit` for the calling contract. Callers are responsible for
+/// authorizing the change.
+pub fn set(env: &Env, limit: Limit, value: u32) -> Result<(), LimitError> {
+    let (min, max) = limit.hard_bounds();
+    if value < min || value > max {
+        return Err(LimitError::OutOfBounds);
+    }
+    env.storage().instance().set(&LimitKey::Value(limit), &value);
+    Ok(())
+}
+
+/// Drops the override for `limit`, restoring the default.
+pub fn reset(env: &Env, limit: Limit) {
+    env.storage().instance().remove(&LimitKey::Value(limit));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::contract;
+
+    #[contract]
+    struct Host;
+
+    #[test]
+    fn test_defaults_within_bounds() {
+        for limit in [
+            Limit::BatchSize,
+            Limit::PageSize,
+            Limit::MemoLength,
+            Limit::ScheduleLength,
+        ] {
+            let (min, max) = limit.hard_bounds();
+            assert!(min <= limit.default_value() && limit.default_value() <= max);
+        }
+    }
+
+    #[test]
+    fn test_set_get_and_reset() {
+        let env = Env::default();
+        let host = env.register(Host, ());
+
+        env.as_contract(&host, || {
+            assert_eq!(get(&env, Limit::BatchSize), 100);
+
+            set(&env, Limit::BatchSize, 250).unwrap();
+            assert_eq!(get(&env, Limit::BatchSize), 250);
+            // Other limits are unaffected
+            assert_eq!(get(&env, Limit::PageSize), 100);
+
+            reset(&env, Limit::BatchSize);
+            assert_eq!(get(&env, Limit::BatchSize), 100);
+        });
+    }
+
+    #[test]
+    fn test_rejects_values_outside_hard_bounds() {
+        let env = Env::default();
+        let host = env.register(Host, ());
+
+        env.as_contract(&host, || {
+            assert_eq!(set(&env, Limit::BatchSize, 0), Err(LimitError::OutOfBounds));
+            assert_eq!(set(&env, Limit::PageSize, 201), Err(LimitError::OutOfBounds));
+            assert_eq!(set(&env, Limit::PageSize, 200), Ok(()));
+            assert_eq!(get(&env, Limit::PageSize), 200);
+        });
+    }
+}
@@ -0,0 +1,221 @@
+//! Checked i128 amount arithmetic.
+//!
+//! Products of two large amounts (a balance times a rate, an accrual times a
+//! duration) overflow i128 long before the final result does. `mul_div`
+//! keeps the intermediate product in 256 bits, so only a result that does
+//! not itself fit in i128 is reported as an overflow.
+//!
+//! Everything here returns `None` instead of panicking so each contract can
+//! map the failure to its own error.
+
+/// Basis points in 100%.
+pub const BPS_DENOMINATOR: i128 = 10_000;
+
+/// Direction to round a division in.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Rounding {
+    /// Toward negative infinity
+    Down,
+    /// Toward positive infinity
+    Up,
+}
+
+/// Computes `a * b / denominator`, rounded as requested.
+///
+/// The product is computed in 256 bits, so it may exceed i128 as long as the
+/// quotient fits. Returns `None` on a zero denominator or if the result does
+/// not fit in i128.
+pub fn mul_div(a: i128, b: i128, denominator: i128, rounding: Rounding) -> Option<i128> {
+    if denominator == 0 {
+        return None;
+    }
+
+    let negative = (a < 0) ^ (b < 0) ^ (denominator < 0);
+    let (hi, lo) = wide_mul(a.unsigned_abs(), b.unsigned_abs());
+    let (quotient, remainder) = wide_div(hi, lo, denominator.unsigned_abs())?;
+
+    // The quotient is a magnitude; round it away from zero when the exact
+    // result lies on the requested side
+    let away_from_zero = remainder != 0
+        && match rounding {
+            Rounding::Up => !negative,
+            Rounding::Down => negative,
+        };
+    let magnitude = if away_from_zero {
+        quotient.checked_add(1)?
+    } else {
+        quotient
+    };
+
+    if negative {
+        if magnitude == i128::MIN.unsigned_abs() {
+            Some(i128::MIN)
+        } else {
+            i128::try_from(magnitude).ok().map(|m| -m)
+        }
+    } else {
+        i128::try_from(magnitude).ok()
+    }
+}
+
+/// Like `mul_div`, but clamps a result that does not fit to `i128::MIN` or
+/// `i128::MAX`. Returns `None` only for a zero denominator.
+pub fn saturating_mul_div(a: i128, b: i128, denominator: i128, rounding: Rounding) -> Option<i128> {
+    if denominator == 0 {
+        return None;
+    }
+    let negative = (a < 0) ^ (b < 0) ^ (denominator < 0);
+    Some(mul_div(a, b, denominator, rounding).unwrap_or(if negative {
+        i128::MIN
+    } else {
+        i128::MAX
+    }))
+}
+
+/// Applies `bps` basis points to `amount`.
+pub fn apply_bps(amount: i128, bps: u32, rounding: Rounding) -> Option<i128> {
+    mul_div(amount, bps as i128, BPS_DENOMINATOR, rounding)
+}
+
+/// Returns `part` as basis points of `whole`, rounded down.
+///
+/// Returns `None` if `whole` is not positive or the ratio does not fit in a
+/// u32.
+pub fn ratio_bps(part: i128, whole: i128) -> Option<u32> {
+    if whole <= 0 {
+        return None;
+    }
+    let bps = mul_div(part, BPS_DENOMINATOR, whole, Rounding::Down)?;
+    u32::try_from(bps).ok()
+}
+
+/// Sums amounts, returning `None` on overflow.
+pub fn checked_sum<I: IntoIterator<Item = i128>>(amounts: I) -> Option<i128> {
+    amounts
+        .into_iter()
+        .try_fold(0i128, |total, amount| total.checked_add(amount))
+}
+
+// Full 128x128 -> 256 bit multiplication, returned as (high, low) halves.
+fn wide_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_hi, a_lo) = (a >> 64, a & MASK);
+    let (b_hi, b_lo) = (b >> 64, b & MASK);
+
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_hi = a_hi * b_hi;
+
+    let cross = (lo_lo >> 64) + (hi_lo & MASK) + (lo_hi & MASK);
+    let lo = (cross << 64) | (lo_lo & MASK);
+    let hi = hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (cross >> 64);
+    (hi, lo)
+}
+
+// Divides the 256-bit value (hi, lo) by `divisor`, returning the quotient and
+// remainder, or `None` if the quotient does not fit in 128 bits.
+fn wide_div(hi: u128, lo: u128, divisor: u128) -> Option<(u128, u128)> {
+    if hi == 0 {
+        return Some((lo / divisor, lo % divisor));
+    }
+    if hi >= divisor {
+        return None;
+    }
+
+    // Shift-subtract long division over the low 128 bits; `hi < divisor`
+    // keeps the running remainder below the divisor
+    let mut remainder = hi;
+    let mut quotient = 0u128;
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((lo >> bit) & 1);
+        quotient <<= 1;
+        if carry == 1 || remainder >= divisor {
+            remainder = remainder.wrapping_sub(divisor);
+            quotient |= 1;
+        }
+    }
+    Some((quotient, remainder))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mul_div_small_values() {
+        assert_eq!(mul_div(1_000, 3, 4, Rounding::Down), Some(750));
+        assert_eq!(mul_div(10, 1, 3, Rounding::Down), Some(3));
+        assert_eq!(mul_div(10, 1, 3, Rounding::Up), Some(4));
+        assert_eq!(mul_div(-10, 1, 3, Rounding::Down), Some(-4));
+        assert_eq!(mul_div(-10, 1, 3, Rounding::Up), Some(-3));
+        assert_eq!(mul_div(10, -1, -3, Rounding::Up), Some(4));
+        assert_eq!(mul_div(1, 1, 0, Rounding::Down), None);
+    }
+
+    #[test]
+    fn test_mul_div_wide_intermediate() {
+        // The product overflows i128 but the quotient does not
+        let big = i128::MAX / 2;
+        assert_eq!(mul_div(big, 6, 3, Rounding::Down), Some(big * 2));
+        assert_eq!(
+            mul_div(i128::MAX, i128::MAX, i128::MAX, Rounding::Down),
+            Some(i128::MAX)
+        );
+        assert_eq!(mul_div(i128::MIN, 3, 3, Rounding::Down), Some(i128::MIN));
+        assert_eq!(
+            mul_div(i128::MAX, 10_000, 20_000, Rounding::Down),
+            Some(i128::MAX / 2)
+        );
+        assert_eq!(
+            mul_div(i128::MAX, 10_000, 20_000, Rounding::Up),
+            Some(i128::MAX / 2 + 1)
+        );
+    }
+
+    #[test]
+    fn test_mul_div_result_overflow() {
+        assert_eq!(mul_div(i128::MAX, 2, 1, Rounding::Down), None);
+        assert_eq!(mul_div(i128::MIN, -1, 1, Rounding::Down), None);
+        // Exact results at the bounds still fit
+        assert_eq!(mul_div(i128::MAX, 3, 3, Rounding::Up), Some(i128::MAX));
+        assert_eq!(mul_div(i128::MIN, 1, -1, Rounding::Down), None);
+    }
+
+    #[test]
+    fn test_saturating_mul_div() {
+        assert_eq!(
+            saturating_mul_div(i128::MAX, 2, 1, Rounding::Down),
+            Some(i128::MAX)
+        );
+        assert_eq!(
+            saturating_mul_div(i128::MAX, -2, 1, Rounding::Down),
+            Some(i128::MIN)
+        );
+        assert_eq!(saturating_mul_div(10, 1, 3, Rounding::Up), Some(4));
+        assert_eq!(saturating_mul_div(10, 1, 0, Rounding::Up), None);
+    }
+
+    #[test]
+    fn test_apply_and_ratio_bps() {
+        assert_eq!(apply_bps(1_001, 30, Rounding::Up), Some(4));
+        assert_eq!(apply_bps(1_001, 30, Rounding::Down), Some(3));
+        assert_eq!(
+            apply_bps(i128::MAX, 10_000, Rounding::Down),
+            Some(i128::MAX)
+        );
+
+        assert_eq!(ratio_bps(250, 1_000), Some(2_500));
+        assert_eq!(ratio_bps(1, 3), Some(3_333));
+        assert_eq!(ratio_bps(1, 0), None);
+        assert_eq!(ratio_bps(i128::MAX, 1), None);
+    }
+
+    #[test]
+    fn test_checked_sum() {
+        assert_eq!(checked_sum([1, 2, 3]), Some(6));
+        assert_eq!(checked_sum([i128::MAX, 1]), None);
+        assert_eq!(checked_sum([]), Some(0));
+    }
+}
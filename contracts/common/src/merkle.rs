@@ -0,0 +1,87 @@
+//! SHA-256 Merkle proofs.
+//!
+//! Contracts post the root of a tree built off-chain and let each listed
+//! party claim its own entry with a proof. A leaf is the SHA-256 of the XDR
+//! encoding of the claimed value; each parent is the SHA-256 of its two
+//! children concatenated in ascending byte order, so proofs need no
+//! left/right flags.
+
+use soroban_sdk::{xdr::ToXdr, Bytes, BytesN, Env, IntoVal, Val, Vec};
+
+/// Deepest proof accepted, enough for over four billion leaves.
+pub const MAX_PROOF_DEPTH: u32 = 32;
+
+/// Returns the leaf hash of `value`.
+pub fn leaf<T: IntoVal<Env, Val>>(env: &Env, value: T) -> BytesN<32> {
+    env.crypto().sha256(&value.to_xdr(env)).into()
+}
+
+/// Returns the parent hash of two sibling nodes, in either order.
+pub fn parent(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    let mut pair = Bytes::from(left.clone());
+    pair.append(&Bytes::from(right.clone()));
+    env.crypto().sha256(&pair).into()
+}
+
+/// Returns whether `proof` leads from `leaf` to `root`. Proofs deeper than
+/// `MAX_PROOF_DEPTH` are rejected without hashing.
+pub fn verify(env: &Env, leaf: BytesN<32>, proof: &Vec<BytesN<32>>, root: &BytesN<32>) -> bool {
+    if proof.len() > MAX_PROOF_DEPTH {
+        return false;
+    }
+    let node = proof
+        .iter()
+        .fold(leaf, |node, sibling| parent(env, &node, &sibling));
+    node == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{testutils::Address as _, vec, Address};
+
+    #[test]
+    fn test_proof_verifies_against_root() {
+        let env = Env::default();
+        let a = leaf(&env, (Address::generate(&env), 10_i128));
+        let b = leaf(&env, (Address::generate(&env), 20_i128));
+        let c = leaf(&env, (Address::generate(&env), 30_i128));
+        let root = parent(&env, &parent(&env, &a, &b), &c);
+
+        assert!(verify(
+            &env,
+            a.clone(),
+            &vec![&env, b.clone(), c.clone()],
+            &root
+        ));
+        assert!(verify(
+            &env,
+            c.clone(),
+            &vec![&env, parent(&env, &b, &a)],
+            &root
+        ));
+        assert!(!verify(&env, a, &vec![&env, c.clone(), b], &root));
+        assert!(!verify(&env, c.clone(), &vec![&env], &root));
+        assert!(verify(&env, c.clone(), &vec![&env], &c));
+    }
+
+    #[test]
+    fn test_overlong_proof_is_rejected() {
+        let env = Env::default();
+        let mut node = leaf(&env, 1_u32);
+        let mut proof = Vec::new(&env);
+        for i in 0..=MAX_PROOF_DEPTH {
+            let sibling = leaf(&env, i + 2);
+            node = parent(&env, &node, &sibling);
+            proof.push_back(sibling);
+        }
+
+        assert!(!verify(&env, leaf(&env, 1_u32), &proof, &node));
+        proof.pop_back();
+        let root = proof
+            .iter()
+            .fold(leaf(&env, 1_u32), |n, s| parent(&env, &n, &s));
+        assert!(verify(&env, leaf(&env, 1_u32), &proof, &root));
+    }
+}
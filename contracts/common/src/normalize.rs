@@ -0,0 +1,551 @@
+//! Token decimals normalization.
+//!
+//! Stellar classic assets use 7 decimals while custom SEP-41 tokens may use
+//! any precision (18 is common for bridged assets). Budgets, limits and
+//! analytics must never add or compare raw amounts of tokens with different
+//! precision, so amounts are first converted into a reference token.
+//!
+//! Tokens are allowlisted once; their decimals are read from the token at
+//! that point and cached in the calling contract's storage, so conversions
+//! never call the token again. Prices come from a SEP-40 price oracle, which
+//! quotes every asset in its own base asset; the oracle's price precision
+//! cancels out of the conversion.
+//!
+//! For audits, daily closing rates per token pair can be pinned in a ring
+//! buffer of [`RATE_HISTORY_DAYS`] slots, so an amount recorded months ago is
+//! converted at the rate that applied on its day rather than today's.
+
+use soroban_sdk::{contractclient, contracttype, token, Address, Env, Symbol};
+
+use crate::math::{self, Rounding};
+
+/// Highest token precision accepted. 10^18 leaves room for amounts up to
+/// roughly 1.7 * 10^20 whole tokens before a rescale overflows.
+pub const MAX_DECIMALS: u32 = 18;
+
+/// Oldest oracle price, in seconds, a conversion will use.
+pub const MAX_PRICE_AGE: u64 = 3_600;
+
+/// Days of closing rates kept per token pair. Older days are overwritten.
+pub const RATE_HISTORY_DAYS: u64 = 400;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Asset identifier used by SEP-40 oracles.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum Asset {
+    /// A Soroban token contract
+    Stellar(Address),
+    /// An asset outside the network, by ticker
+    Other(Symbol),
+}
+
+/// Price record returned by SEP-40 oracles.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PriceData {
+    /// Price in the oracle's base asset, scaled by the oracle's decimals
+    pub price: i128,
+    /// Ledger timestamp the price was recorded at
+    pub timestamp: u64,
+}
+
+/// Closing rate of a token pair for one day, pinned from oracle prices.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ClosingRate {
+    /// Day number (ledger timestamp / 86400) the rate closed
+    pub day: u64,
+    /// Price of the source token in the oracle's base asset
+    pub from_price: i128,
+    /// Price of the reference token in the oracle's base asset
+    pub to_price: i128,
+    /// Ledger timestamp the rate was recorded at
+    pub recorded_at: u64,
+}
+
+/// The part of the SEP-40 price oracle interface used for conversions.
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracle {
+    /// Most recent price of `asset`, if the oracle has one.
+    fn lastprice(env: Env, asset: Asset) -> Option<PriceData>;
+}
+
+/// Errors returned by normalization.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum NormalizeError {
+    /// The token is not allowlisted
+    TokenNotAllowed,
+    /// The token did not report its decimals, or reported more than
+    /// `MAX_DECIMALS`
+    InvalidDecimals,
+    /// The oracle has no usable price for a token
+    PriceUnavailable,
+    /// The oracle's price is older than `MAX_PRICE_AGE`
+    StalePrice,
+    /// The converted amount does not fit in i128
+    Overflow,
+    /// No closing rate is pinned for the pair on that day
+    RateUnavailable,
+}
+
+/// Storage keys for normalization.
+#[derive(Clone)]
+#[contracttype]
+pub enum NormalizeKey {
+    /// Cached decimals of an allowlisted token
+    Decimals(Address),
+    /// Closing rate of a pair (from, to) in a ring buffer slot
+    ClosingRate(Address, Address, u64),
+}
+
+/// Allowlists `token`, reading and caching its decimals. Returns the
+/// decimals. Allowlisting an already allowed token refreshes the cache.
+pub fn allow_token(env: &Env, token: &Address) -> Result<u32, NormalizeError> {
+    let decimals = match token::Client::new(env, token).try_decimals() {
+        Ok(Ok(decimals)) if decimals <= MAX_DECIMALS => decimals,
+        _ => return Err(NormalizeError::InvalidDecimals),
+    };
+    env.storage()
+        .persistent()
+        .set(&NormalizeKey::Decimals(token.clone()), &decimals);
+    Ok(decimals)
+}
+
+/// Removes `token` from the allowlist.
+pub fn disallow_token(env: &Env, token: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&NormalizeKey::Decimals(token.clone()));
+}
+
+/// Returns true if `token` is allowlisted.
+pub fn is_allowed(env: &Env, token: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&NormalizeKey::Decimals(token.clone()))
+}
+
+/// Returns the cached decimals of an allowlisted token.
+pub fn decimals(env: &Env, token: &Address) -> Result<u32, NormalizeError> {
+    env.storage()
+        .persistent()
+        .get(&NormalizeKey::Decimals(token.clone()))
+        .ok_or(NormalizeError::TokenNotAllowed)
+}
+
+/// Rescales `amount` from `from_decimals` to `to_decimals` precision.
+///
+/// Returns `None` if the result does not fit in i128.
+pub fn rescale(
+    amount: i128,
+    from_decimals: u32,
+    to_decimals: u32,
+    rounding: Rounding,
+) -> Option<i128> {
+    if to_decimals >= from_decimals {
+        amount.checked_mul(10i128.checked_pow(to_decimals - from_decimals)?)
+    } else {
+        let divisor = 10i128.checked_pow(from_decimals - to_decimals)?;
+        math::mul_div(amount, 1, divisor, rounding)
+    }
+}
+
+/// Converts `amount` of `from_token` into the equivalent amount of
+/// `to_reference`, using `oracle` prices. Both tokens must be allowlisted.
+///
+/// The result rounds down, so a converted amount never overstates value.
+pub fn convert(
+    env: &Env,
+    oracle: &Address,
+    amount: i128,
+    from_token: &Address,
+    to_reference: &Address,
+) -> Result<i128, NormalizeError> {
+    let from_decimals = decimals(env, from_token)?;
+    let to_decimals = decimals(env, to_reference)?;
+    if from_token == to_reference {
+        return Ok(amount);
+    }
+
+    let from_price = price(env, oracle, from_token)?;
+    let to_price = price(env, oracle, to_reference)?;
+    apply_rate(amount, from_decimals, to_decimals, from_price, to_price)
+}
+
+/// Pins today's closing rate of `from_token` against `to_reference` from
+/// `oracle` prices. Both tokens must be allowlisted. Recording again on the
+/// same day replaces the day's rate, so the last recording is the close.
+pub fn record_close(
+    env: &Env,
+    oracle: &Address,
+    from_token: &Address,
+    to_reference: &Address,
+) -> Result<ClosingRate, NormalizeError> {
+    decimals(env, from_token)?;
+    decimals(env, to_reference)?;
+
+    let now = env.ledger().timestamp();
+    let rate = ClosingRate {
+        day: now / SECONDS_PER_DAY,
+        from_price: price(env, oracle, from_token)?,
+        to_price: price(env, oracle, to_reference)?,
+        recorded_at: now,
+    };
+    env.storage()
+        .persistent()
+        .set(&rate_key(from_token, to_reference, rate.day), &rate);
+    Ok(rate)
+}
+
+/// Returns the closing rate pinned for the pair on the day of `timestamp`.
+///
+/// Fails with `RateUnavailable` if none was recorded that day, or if the
+/// day's slot has since been reused by a later day.
+pub fn rate_at(
+    env: &Env,
+    from_token: &Address,
+    to_reference: &Address,
+    timestamp: u64,
+) -> Result<ClosingRate, NormalizeError> {
+    let day = timestamp / SECONDS_PER_DAY;
+    env.storage()
+        .persistent()
+        .get::<_, ClosingRate>(&rate_key(from_token, to_reference, day))
+        .filter(|rate| rate.day == day)
+        .ok_or(NormalizeError::RateUnavailable)
+}
+
+/// Converts `amount` of `from_token` into `to_reference` at the closing rate
+/// pinned for the day of `timestamp`. Both tokens must be allowlisted.
+///
+/// The result rounds down, like [`convert`].
+pub fn convert_at(
+    env: &Env,
+    amount: i128,
+    from_token: &Address,
+    to_reference: &Address,
+    timestamp: u64,
+) -> Result<i128, NormalizeError> {
+    let from_decimals = decimals(env, from_token)?;
+    let to_decimals = decimals(env, to_reference)?;
+    if from_token == to_reference {
+        return Ok(amount);
+    }
+
+    let rate = rate_at(env, from_token, to_reference, timestamp)?;
+    apply_rate(
+        amount,
+        from_decimals,
+        to_decimals,
+        rate.from_price,
+        rate.to_price,
+    )
+}
+
+// Internal helper converting between precisions at a pair of base-asset
+// prices
+fn apply_rate(
+    amount: i128,
+    from_decimals: u32,
+    to_decimals: u32,
+    from_price: i128,
+    to_price: i128,
+) -> Result<i128, NormalizeError> {
+    // amount * from_price * 10^to / (to_price * 10^from), divided once so
+    // the result is rounded a single time
+    let (numerator, denominator) = if to_decimals >= from_decimals {
+        let scale = 10i128.pow(to_decimals - from_decimals);
+        (from_price.checked_mul(scale), Some(to_price))
+    } else {
+        let scale = 10i128.pow(from_decimals - to_decimals);
+        (Some(from_price), to_price.checked_mul(scale))
+    };
+    let (numerator, denominator) = numerator.zip(denominator).ok_or(NormalizeError::Overflow)?;
+
+    math::mul_div(amount, numerator, denominator, Rounding::Down).ok_or(NormalizeError::Overflow)
+}
+
+// Internal helper locating a day's ring buffer slot for a pair
+fn rate_key(from_token: &Address, to_reference: &Address, day: u64) -> NormalizeKey {
+    NormalizeKey::ClosingRate(
+        from_token.clone(),
+        to_reference.clone(),
+        day % RATE_HISTORY_DAYS,
+    )
+}
+
+// Internal helper reading a fresh, positive oracle price for a token
+fn price(env: &Env, oracle: &Address, token: &Address) -> Result<i128, NormalizeError> {
+    let asset = Asset::Stellar(token.clone());
+    let data = match PriceOracleClient::new(env, oracle).try_lastprice(&asset) {
+        Ok(Ok(Some(data))) if data.price > 0 => data,
+        _ => return Err(NormalizeError::PriceUnavailable),
+    };
+    let age = env.ledger().timestamp().saturating_sub(data.timestamp);
+    if age > MAX_PRICE_AGE {
+        return Err(NormalizeError::StalePrice);
+    }
+    Ok(data.price)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{contract, contractimpl, testutils::Address as _, testutils::Ledger, Map};
+
+    #[contract]
+    struct Host;
+
+    /// Token reporting the decimals it was deployed with.
+    #[contract]
+    struct DecimalsToken;
+
+    #[contractimpl]
+    impl DecimalsToken {
+        pub fn __constructor(env: Env, decimals: u32) {
+            env.storage().instance().set(&0u32, &decimals);
+        }
+
+        pub fn decimals(env: Env) -> u32 {
+            env.storage().instance().get(&0u32).unwrap()
+        }
+    }
+
+    /// Oracle serving whatever prices the test sets.
+    #[contract]
+    struct MockOracle;
+
+    #[contractimpl]
+    impl MockOracle {
+        pub fn set_price(env: Env, asset: Asset, price: i128, timestamp: u64) {
+            let mut prices: Map<Asset, PriceData> = env
+                .storage()
+                .instance()
+                .get(&0u32)
+                .unwrap_or(Map::new(&env));
+            prices.set(asset, PriceData { price, timestamp });
+            env.storage().instance().set(&0u32, &prices);
+        }
+
+        pub fn lastprice(env: Env, asset: Asset) -> Option<PriceData> {
+            let prices: Map<Asset, PriceData> = env.storage().instance().get(&0u32)?;
+            prices.get(asset)
+        }
+    }
+
+    struct Setup {
+        env: Env,
+        host: Address,
+        oracle: MockOracleClient<'static>,
+        classic: Address,
+        wide: Address,
+    }
+
+    fn setup() -> Setup {
+        let env = Env::default();
+        env.ledger().with_mut(|li| li.timestamp = 10_000);
+        let host = env.register(Host, ());
+        let oracle = MockOracleClient::new(&env, &env.register(MockOracle, ()));
+        let classic = env
+            .register_stellar_asset_contract_v2(Address::generate(&env))
+            .address();
+        let wide = env.register(DecimalsToken, (18u32,));
+        Setup {
+            env,
+            host,
+            oracle,
+            classic,
+            wide,
+        }
+    }
+
+    #[test]
+    fn test_rescale() {
+        assert_eq!(
+            rescale(12_345_678, 7, 18, Rounding::Down),
+            Some(12_345_678 * 10i128.pow(11))
+        );
+        assert_eq!(rescale(1_999_999_999_999, 18, 7, Rounding::Down), Some(19));
+        assert_eq!(rescale(1_999_999_999_999, 18, 7, Rounding::Up), Some(20));
+        assert_eq!(rescale(42, 7, 7, Rounding::Down), Some(42));
+        assert_eq!(rescale(i128::MAX, 0, 18, Rounding::Down), None);
+    }
+
+    #[test]
+    fn test_allowlist_caches_decimals() {
+        let s = setup();
+        s.env.as_contract(&s.host, || {
+            assert_eq!(
+                decimals(&s.env, &s.classic),
+                Err(NormalizeError::TokenNotAllowed)
+            );
+
+            assert_eq!(allow_token(&s.env, &s.classic), Ok(7));
+            assert_eq!(allow_token(&s.env, &s.wide), Ok(18));
+            assert!(is_allowed(&s.env, &s.wide));
+            assert_eq!(decimals(&s.env, &s.wide), Ok(18));
+
+            disallow_token(&s.env, &s.wide);
+            assert_eq!(
+                decimals(&s.env, &s.wide),
+                Err(NormalizeError::TokenNotAllowed)
+            );
+        });
+    }
+
+    #[test]
+    fn test_allowlist_rejects_bad_decimals() {
+        let s = setup();
+        let huge = s.env.register(DecimalsToken, (40u32,));
+        // A contract without `decimals` at all
+        let not_a_token = s.env.register(Host, ());
+        s.env.as_contract(&s.host, || {
+            assert_eq!(
+                allow_token(&s.env, &huge),
+                Err(NormalizeError::InvalidDecimals)
+            );
+            assert_eq!(
+                allow_token(&s.env, &not_a_token),
+                Err(NormalizeError::InvalidDecimals)
+            );
+            assert!(!is_allowed(&s.env, &huge));
+        });
+    }
+
+    #[test]
+    fn test_convert_between_precisions() {
+        let s = setup();
+        // One wide token is worth 2.5 classic tokens
+        s.oracle
+            .set_price(&Asset::Stellar(s.classic.clone()), &100_000, &9_000);
+        s.oracle
+            .set_price(&Asset::Stellar(s.wide.clone()), &250_000, &9_000);
+
+        s.env.as_contract(&s.host, || {
+            allow_token(&s.env, &s.classic).unwrap();
+            allow_token(&s.env, &s.wide).unwrap();
+
+            let one_wide = 10i128.pow(18);
+            let converted = convert(&s.env, &s.oracle.address, one_wide, &s.wide, &s.classic);
+            assert_eq!(converted, Ok(25_000_000));
+
+            let back = convert(&s.env, &s.oracle.address, 25_000_000, &s.classic, &s.wide);
+            assert_eq!(back, Ok(one_wide));
+
+            // 10^10 wide units are worth a quarter of the smallest classic unit
+            let dust = convert(
+                &s.env,
+                &s.oracle.address,
+                10i128.pow(10),
+                &s.wide,
+                &s.classic,
+            );
+            assert_eq!(dust, Ok(0));
+
+            // Same token needs no price
+            let same = convert(&s.env, &s.oracle.address, 77, &s.classic, &s.classic);
+            assert_eq!(same, Ok(77));
+        });
+    }
+
+    #[test]
+    fn test_convert_requires_allowlist_and_fresh_prices() {
+        let s = setup();
+        s.oracle
+            .set_price(&Asset::Stellar(s.classic.clone()), &100_000, &9_000);
+
+        s.env.as_contract(&s.host, || {
+            allow_token(&s.env, &s.classic).unwrap();
+            let oracle = &s.oracle.address;
+
+            let err = convert(&s.env, oracle, 1, &s.wide, &s.classic);
+            assert_eq!(err, Err(NormalizeError::TokenNotAllowed));
+
+            allow_token(&s.env, &s.wide).unwrap();
+            let err = convert(&s.env, oracle, 1, &s.wide, &s.classic);
+            assert_eq!(err, Err(NormalizeError::PriceUnavailable));
+        });
+
+        s.oracle
+            .set_price(&Asset::Stellar(s.wide.clone()), &250_000, &1_000);
+        s.env.as_contract(&s.host, || {
+            let err = convert(&s.env, &s.oracle.address, 1, &s.wide, &s.classic);
+            assert_eq!(err, Err(NormalizeError::StalePrice));
+        });
+    }
+
+    #[test]
+    fn test_rates_pinned_per_day() {
+        let s = setup();
+        s.oracle
+            .set_price(&Asset::Stellar(s.classic.clone()), &100_000, &10_000);
+        s.oracle
+            .set_price(&Asset::Stellar(s.wide.clone()), &250_000, &10_000);
+        let one_wide = 10i128.pow(18);
+
+        s.env.as_contract(&s.host, || {
+            allow_token(&s.env, &s.classic).unwrap();
+            allow_token(&s.env, &s.wide).unwrap();
+            let rate = record_close(&s.env, &s.oracle.address, &s.wide, &s.classic).unwrap();
+            assert_eq!(rate.day, 0);
+        });
+
+        // A month later the wide token has doubled
+        let later = 30 * SECONDS_PER_DAY + 10_000;
+        s.env.ledger().with_mut(|li| li.timestamp = later);
+        s.oracle
+            .set_price(&Asset::Stellar(s.classic.clone()), &100_000, &later);
+        s.oracle
+            .set_price(&Asset::Stellar(s.wide.clone()), &500_000, &later);
+
+        s.env.as_contract(&s.host, || {
+            record_close(&s.env, &s.oracle.address, &s.wide, &s.classic).unwrap();
+
+            // An expense from day 0 still converts at day 0's close
+            let then = convert_at(&s.env, one_wide, &s.wide, &s.classic, 20_000);
+            assert_eq!(then, Ok(25_000_000));
+            let now = convert_at(&s.env, one_wide, &s.wide, &s.classic, later);
+            assert_eq!(now, Ok(50_000_000));
+
+            // Days without a recording, and the reverse pair, have no rate
+            let missing = rate_at(&s.env, &s.wide, &s.classic, 2 * SECONDS_PER_DAY);
+            assert_eq!(missing, Err(NormalizeError::RateUnavailable));
+            let reverse = rate_at(&s.env, &s.classic, &s.wide, 20_000);
+            assert_eq!(reverse, Err(NormalizeError::RateUnavailable));
+        });
+    }
+
+    #[test]
+    fn test_rate_history_wraps() {
+        let s = setup();
+        s.oracle
+            .set_price(&Asset::Stellar(s.classic.clone()), &100_000, &10_000);
+        s.oracle
+            .set_price(&Asset::Stellar(s.wide.clone()), &250_000, &10_000);
+        s.env.as_contract(&s.host, || {
+            allow_token(&s.env, &s.classic).unwrap();
+            allow_token(&s.env, &s.wide).unwrap();
+            record_close(&s.env, &s.oracle.address, &s.wide, &s.classic).unwrap();
+        });
+
+        // The same slot, one full cycle later
+        let wrapped = RATE_HISTORY_DAYS * SECONDS_PER_DAY + 10_000;
+        s.env.ledger().with_mut(|li| li.timestamp = wrapped);
+        for token in [&s.classic, &s.wide] {
+            s.oracle
+                .set_price(&Asset::Stellar(token.clone()), &100_000, &wrapped);
+        }
+        s.env.as_contract(&s.host, || {
+            record_close(&s.env, &s.oracle.address, &s.wide, &s.classic).unwrap();
+
+            let overwritten = rate_at(&s.env, &s.wide, &s.classic, 10_000);
+            assert_eq!(overwritten, Err(NormalizeError::RateUnavailable));
+            assert_eq!(
+                rate_at(&s.env, &s.wide, &s.classic, wrapped).map(|rate| rate.day),
+                Ok(RATE_HISTORY_DAYS)
+            );
+        });
+    }
+}
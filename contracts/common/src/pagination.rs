@@ -0,0 +1,141 @@
+//! Cursor-based pagination for list queries.
+//!
+//! Lists are paged by key rather than by offset: the cursor is the key of the
+//! last item returned, and the next page starts strictly after it. Entries
+//! added or removed between calls therefore never shift a page, so clients do
+//! not see skipped or duplicated entries. Clients should treat the cursor as
+//! opaque and pass back exactly what they were given.
+
+use soroban_sdk::{contracttype, Env, Vec};
+
+use crate::limits::{self, Limit};
+
+/// One page of ids from a list query.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct IdPage {
+    /// Ids in this page, in ascending order
+    pub ids: Vec<u64>,
+    /// Continuation token for the next page; `None` once the list is exhausted
+    pub next_cursor: Option<u64>,
+}
+
+/// Returns the page of `ids` following `cursor`.
+///
+/// `ids` must be sorted ascending, which holds for any list that only ever
+/// appends freshly issued ids. A `limit` of zero or above the contract's
+/// `Limit::PageSize` returns a full page.
+pub fn page_after(env: &Env, ids: &Vec<u64>, cursor: Option<u64>, limit: u32) -> IdPage {
+    let max_page = limits::get(env, Limit::PageSize);
+    let limit = if limit == 0 || limit > max_page {
+        max_page
+    } else {
+        limit
+    };
+
+    let start = match cursor {
+        Some(cursor) => match ids.binary_search(cursor) {
+            Ok(index) => index + 1,
+            Err(index) => index,
+        },
+        None => 0,
+    };
+    let end = start.saturating_add(limit).min(ids.len());
+
+    let page = if start < end {
+        ids.slice(start..end)
+    } else {
+        Vec::new(env)
+    };
+    let next_cursor = if end < ids.len() {
+        page.last()
+    } else {
+        None
+    };
+
+    IdPage {
+        ids: page,
+        next_cursor,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{contract, vec};
+
+    #[contract]
+    struct Host;
+
+    // Runs `f` inside a contract so the page-size limit can be read
+    fn in_contract(f: impl FnOnce(&Env)) {
+        let env = Env::default();
+        let host = env.register(Host, ());
+        env.as_contract(&host, || f(&env));
+    }
+
+    #[test]
+    fn test_pages_through_list() {
+        in_contract(|env| {
+            let ids = vec![env, 1u64, 2, 3, 4, 5];
+
+            let first = page_after(env, &ids, None, 2);
+            assert_eq!(first.ids, vec![env, 1u64, 2]);
+            assert_eq!(first.next_cursor, Some(2));
+
+            let second = page_after(env, &ids, first.next_cursor, 2);
+            assert_eq!(second.ids, vec![env, 3u64, 4]);
+
+            let last = page_after(env, &ids, second.next_cursor, 2);
+            assert_eq!(last.ids, vec![env, 5u64]);
+            assert_eq!(last.next_cursor, None);
+        });
+    }
+
+    #[test]
+    fn test_cursor_survives_removal() {
+        in_contract(|env| {
+            let mut ids = vec![env, 1u64, 2, 3, 4, 5];
+
+            let first = page_after(env, &ids, None, 2);
+
+            // The last id of the page is removed before the next call
+            ids.remove(1);
+            let second = page_after(env, &ids, first.next_cursor, 2);
+            assert_eq!(second.ids, vec![env, 3u64, 4]);
+        });
+    }
+
+    #[test]
+    fn test_limit_is_clamped() {
+        in_contract(|env| {
+            let mut ids: Vec<u64> = Vec::new(env);
+            for id in 1..=150u64 {
+                ids.push_back(id);
+            }
+
+            let page = page_after(env, &ids, None, 0);
+            assert_eq!(page.ids.len(), Limit::PageSize.default_value());
+            assert_eq!(page.next_cursor, Some(100));
+
+            let page = page_after(env, &ids, Some(100), 1_000);
+            assert_eq!(page.ids.len(), 50);
+            assert_eq!(page.next_cursor, None);
+
+            // The page size follows the contract's configured limit
+            limits::set(env, Limit::PageSize, 40).unwrap();
+            let page = page_after(env, &ids, None, 0);
+            assert_eq!(page.ids.len(), 40);
+        });
+    }
+
+    #[test]
+    fn test_empty_list() {
+        in_contract(|env| {
+            let page = page_after(env, &Vec::new(env), Some(7), 10);
+
+            assert_eq!(page.ids.len(), 0);
+            assert_eq!(page.next_cursor, None);
+        });
+    }
+}
@@ -0,0 +1,72 @@
+//! Reentrancy guard for entrypoints that call out to tokens or other contracts.
+//!
+//! The guard sets a lock flag in the calling contract's instance storage and
+//! clears it when dropped, so the lock is released on every return path. If a
+//! call fails the whole invocation is rolled back, lock included.
+
+use soroban_sdk::{contracttype, Env};
+
+/// Storage keys for the guard.
+#[derive(Clone)]
+#[contracttype]
+pub enum GuardKey {
+    /// Set while a guarded entrypoint is executing
+    ReentrancyLock,
+}
+
+/// Error returned when a guarded entrypoint is entered while already running.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ReentrancyError;
+
+/// Holds the reentrancy lock until dropped.
+pub struct ReentrancyGuard {
+    env: Env,
+}
+
+impl ReentrancyGuard {
+    /// Takes the lock, failing if a guarded entrypoint is already executing.
+    pub fn enter(env: &Env) -> Result<Self, ReentrancyError> {
+        if is_locked(env) {
+            return Err(ReentrancyError);
+        }
+        env.storage().instance().set(&GuardKey::ReentrancyLock, &true);
+
+        Ok(ReentrancyGuard { env: env.clone() })
+    }
+}
+
+impl Drop for ReentrancyGuard {
+    fn drop(&mut self) {
+        self.env.storage().instance().remove(&GuardKey::ReentrancyLock);
+    }
+}
+
+/// Returns true while a guarded entrypoint is executing.
+pub fn is_locked(env: &Env) -> bool {
+    env.storage().instance().has(&GuardKey::ReentrancyLock)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::contract;
+
+    #[contract]
+    struct GuardHost;
+
+    #[test]
+    fn test_guard_releases_on_drop() {
+        let env = Env::default();
+        let host = env.register(GuardHost, ());
+
+        env.as_contract(&host, || {
+            {
+                let _guard = ReentrancyGuard::enter(&env).unwrap();
+                assert!(is_locked(&env));
+                assert!(ReentrancyGuard::enter(&env).is_err());
+            }
+            assert!(!is_locked(&env));
+            assert!(ReentrancyGuard::enter(&env).is_ok());
+        });
+    }
+}
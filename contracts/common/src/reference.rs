@@ -0,0 +1,166 @@
+//! Reference currency setting.
+//!
+//! Budgets, analytics, fees and policies express amounts in one reference
+//! currency at a fixed precision (USD at 7 decimals unless changed). Each
+//! contract stores its own setting; changing it starts a new epoch and
+//! publishes a `restated` event, so aggregates recorded under an earlier
+//! epoch can be recognized as predating the change.
+//!
+//! Changing the currency does not convert stored amounts between currencies;
+//! callers may rescale amounts to the new precision, but anything recorded in
+//! an earlier epoch keeps that epoch's currency.
+
+use soroban_sdk::{contracttype, symbol_short, Env, Symbol};
+
+use crate::{events, normalize::MAX_DECIMALS};
+
+/// Precision used when the contract has no setting.
+pub const DEFAULT_DECIMALS: u32 = 7;
+
+/// The reference currency amounts are expressed in.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ReferenceCurrency {
+    /// Currency code, e.g. `USD` or `EURC`
+    pub code: Symbol,
+    /// Decimal places of reference amounts
+    pub decimals: u32,
+    /// Incremented on every change; 0 is the default setting
+    pub epoch: u32,
+    /// Ledger timestamp the setting took effect (0 for the default)
+    pub since: u64,
+}
+
+/// Errors returned by the reference currency setting.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ReferenceError {
+    /// Decimals exceed `MAX_DECIMALS`
+    InvalidDecimals,
+    /// The currency and decimals are already in effect
+    Unchanged,
+    /// The epoch counter is exhausted
+    Overflow,
+}
+
+/// Storage keys for the reference currency setting.
+#[derive(Clone)]
+#[contracttype]
+pub enum ReferenceKey {
+    /// Reference currency in effect
+    Current,
+}
+
+/// Returns the calling contract's reference currency.
+pub fn get(env: &Env) -> ReferenceCurrency {
+    env.storage()
+        .instance()
+        .get(&ReferenceKey::Current)
+        .unwrap_or(ReferenceCurrency {
+            code: symbol_short!("USD"),
+            decimals: DEFAULT_DECIMALS,
+            epoch: 0,
+            since: 0,
+        })
+}
+
+/// Switches the calling contract to a new reference currency, starting a new
+/// epoch, and publishes a `restated` event carrying the previous and new
+/// settings. Returns the previous setting. Callers are responsible for
+/// authorizing the change.
+pub fn set(env: &Env, code: Symbol, decimals: u32) -> Result<ReferenceCurrency, ReferenceError> {
+    if decimals > MAX_DECIMALS {
+        return Err(ReferenceError::InvalidDecimals);
+    }
+    let previous = get(env);
+    if previous.code == code && previous.decimals == decimals {
+        return Err(ReferenceError::Unchanged);
+    }
+
+    let current = ReferenceCurrency {
+        code,
+        decimals,
+        epoch: previous
+            .epoch
+            .checked_add(1)
+            .ok_or(ReferenceError::Overflow)?,
+        since: env.ledger().timestamp(),
+    };
+    env.storage().instance().set(&ReferenceKey::Current, &current);
+
+    let topics = (symbol_short!("ref_ccy"), symbol_short!("restated"));
+    events::publish(env, topics, (previous.clone(), current));
+
+    Ok(previous)
+}
+
+/// Returns true if something recorded under `epoch` predates the reference
+/// currency in effect.
+pub fn is_pre_change(env: &Env, epoch: u32) -> bool {
+    epoch < get(env).epoch
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{contract, testutils::Events, testutils::Ledger};
+
+    #[contract]
+    struct Host;
+
+    #[test]
+    fn test_default_is_usd_seven_decimals() {
+        let env = Env::default();
+        let host = env.register(Host, ());
+
+        env.as_contract(&host, || {
+            let reference = get(&env);
+            assert_eq!(reference.code, symbol_short!("USD"));
+            assert_eq!(reference.decimals, 7);
+            assert_eq!(reference.epoch, 0);
+            assert!(!is_pre_change(&env, 0));
+        });
+    }
+
+    #[test]
+    fn test_change_starts_new_epoch_and_restates() {
+        let env = Env::default();
+        env.ledger().with_mut(|li| li.timestamp = 5_000);
+        let host = env.register(Host, ());
+
+        env.as_contract(&host, || {
+            let previous = set(&env, symbol_short!("EURC"), 6).unwrap();
+            assert_eq!(previous.code, symbol_short!("USD"));
+            assert_eq!(env.events().all().len(), 1);
+
+            let current = get(&env);
+            assert_eq!(current.code, symbol_short!("EURC"));
+            assert_eq!(current.decimals, 6);
+            assert_eq!(current.epoch, 1);
+            assert_eq!(current.since, 5_000);
+            assert!(is_pre_change(&env, 0));
+            assert!(!is_pre_change(&env, 1));
+        });
+    }
+
+    #[test]
+    fn test_rejects_invalid_or_unchanged_settings() {
+        let env = Env::default();
+        let host = env.register(Host, ());
+
+        env.as_contract(&host, || {
+            assert_eq!(
+                set(&env, symbol_short!("USD"), 7),
+                Err(ReferenceError::Unchanged)
+            );
+            assert_eq!(
+                set(&env, symbol_short!("USD"), MAX_DECIMALS + 1),
+                Err(ReferenceError::InvalidDecimals)
+            );
+            assert_eq!(get(&env).epoch, 0);
+
+            // A precision change alone is a new setting
+            set(&env, symbol_short!("USD"), 18).unwrap();
+            assert_eq!(get(&env).epoch, 1);
+        });
+    }
+}
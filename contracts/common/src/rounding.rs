@@ -0,0 +1,150 @@
+//! Rounding and dust policy.
+//!
+//! Every amount split in the protocol rounds in a fixed direction so rounding
+//! never works against the protocol or leaks value:
+//!
+//! * fees round up (`fee_bps`), so a fee is never understated;
+//! * payouts and shares round down (`share_bps`), so the contract never pays
+//!   out more than it holds.
+//!
+//! Amounts below the configured dust threshold are not worth transferring and
+//! are swept to the dust collector instead.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::math;
+
+pub use crate::math::{mul_div, Rounding, BPS_DENOMINATOR};
+
+/// Dust threshold and the address dust is swept to.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct DustPolicy {
+    /// Amounts strictly below this are dust
+    pub threshold: i128,
+    /// Recipient of swept dust
+    pub collector: Address,
+}
+
+/// Storage keys for the rounding policy.
+#[derive(Clone)]
+#[contracttype]
+pub enum RoundingKey {
+    /// The contract's dust policy
+    DustPolicy,
+}
+
+/// Fee of `bps` basis points on `amount`, rounded up.
+pub fn fee_bps(amount: i128, bps: u32) -> Option<i128> {
+    math::apply_bps(amount, bps, Rounding::Up)
+}
+
+/// Share of `bps` basis points of `amount`, rounded down.
+pub fn share_bps(amount: i128, bps: u32) -> Option<i128> {
+    math::apply_bps(amount, bps, Rounding::Down)
+}
+
+/// Returns true if `amount` is positive but below the dust threshold.
+pub fn is_dust(policy: &DustPolicy, amount: i128) -> bool {
+    amount > 0 && amount < policy.threshold
+}
+
+/// Stores the calling contract's dust policy.
+pub fn set_dust_policy(env: &Env, policy: &DustPolicy) {
+    env.storage()
+        .instance()
+        .set(&RoundingKey::DustPolicy, policy);
+}
+
+/// Returns the calling contract's dust policy, if one is configured.
+pub fn get_dust_policy(env: &Env) -> Option<DustPolicy> {
+    env.storage().instance().get(&RoundingKey::DustPolicy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{contract, testutils::Address as _};
+
+    #[test]
+    fn test_mul_div_exact() {
+        assert_eq!(mul_div(1_000, 3, 4, Rounding::Up), Some(750));
+        assert_eq!(mul_div(1_000, 3, 4, Rounding::Down), Some(750));
+        assert_eq!(mul_div(0, 3, 4, Rounding::Up), Some(0));
+    }
+
+    #[test]
+    fn test_mul_div_rounding_directions() {
+        // 10 / 3 = 3.33..
+        assert_eq!(mul_div(10, 1, 3, Rounding::Down), Some(3));
+        assert_eq!(mul_div(10, 1, 3, Rounding::Up), Some(4));
+
+        // -10 / 3 = -3.33..
+        assert_eq!(mul_div(-10, 1, 3, Rounding::Down), Some(-4));
+        assert_eq!(mul_div(-10, 1, 3, Rounding::Up), Some(-3));
+
+        // Negative denominator flips the sign of the exact result
+        assert_eq!(mul_div(10, 1, -3, Rounding::Down), Some(-4));
+        assert_eq!(mul_div(10, 1, -3, Rounding::Up), Some(-3));
+        assert_eq!(mul_div(-10, 1, -3, Rounding::Down), Some(3));
+        assert_eq!(mul_div(-10, 1, -3, Rounding::Up), Some(4));
+    }
+
+    #[test]
+    fn test_mul_div_failures() {
+        assert_eq!(mul_div(10, 1, 0, Rounding::Down), None);
+        assert_eq!(mul_div(i128::MAX, 3, 2, Rounding::Down), None);
+        assert_eq!(mul_div(i128::MIN, -1, 1, Rounding::Down), None);
+    }
+
+    #[test]
+    fn test_fee_rounds_up_and_share_rounds_down() {
+        // 0.5% of 50 is 0.25
+        assert_eq!(fee_bps(50, 50), Some(1));
+        assert_eq!(share_bps(50, 50), Some(0));
+
+        // Exact amounts are unaffected
+        assert_eq!(fee_bps(10_000, 50), Some(50));
+        assert_eq!(share_bps(10_000, 50), Some(50));
+    }
+
+    #[test]
+    fn test_fee_and_share_cover_amount() {
+        // A fee and the remaining share never exceed the amount, and the fee
+        // never understates the rate, across a spread of amounts and rates
+        for amount in [1i128, 7, 99, 101, 9_999, 10_001, 123_456_789] {
+            for bps in [0u32, 1, 25, 333, 5_000, 9_999, 10_000] {
+                let fee = fee_bps(amount, bps).unwrap();
+                let share = share_bps(amount, bps).unwrap();
+                assert!(fee >= share);
+                assert!(fee - share <= 1);
+                assert!(fee <= amount);
+                assert!(fee * BPS_DENOMINATOR >= amount * bps as i128);
+                assert!(share * BPS_DENOMINATOR <= amount * bps as i128);
+            }
+        }
+    }
+
+    #[contract]
+    struct Host;
+
+    #[test]
+    fn test_dust_policy() {
+        let env = Env::default();
+        let host = env.register(Host, ());
+        let policy = DustPolicy {
+            threshold: 100,
+            collector: Address::generate(&env),
+        };
+
+        env.as_contract(&host, || {
+            assert_eq!(get_dust_policy(&env), None);
+            set_dust_policy(&env, &policy);
+            assert_eq!(get_dust_policy(&env), Some(policy.clone()));
+        });
+
+        assert!(is_dust(&policy, 99));
+        assert!(!is_dust(&policy, 100));
+        assert!(!is_dust(&policy, 0));
+    }
+}
@@ -0,0 +1,147 @@
+//! Checked SEP-41 token transfers.
+//!
+//! Works the same for classic-asset Stellar Asset Contracts and custom tokens,
+//! since both expose the SEP-41 interface. Each transfer is verified against
+//! the balances before and after the call, which catches fee-on-transfer and
+//! otherwise misbehaving tokens.
+
+use soroban_sdk::{token, Address, Env};
+
+/// Errors raised by checked transfers.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TransferError {
+    /// Amount is not positive
+    InvalidAmount,
+    /// The token rejected the transfer (insufficient balance, missing
+    /// trustline, frozen account, ...); no funds moved
+    TransferFailed,
+    /// The token moved a different amount than requested; callers must abort
+    /// so the transfer is rolled back
+    AmountMismatch,
+}
+
+/// Transfers `amount` of `token` from `from` to `to`, verifying that exactly
+/// `amount` left `from` and arrived at `to`.
+pub fn safe_transfer(
+    env: &Env,
+    token: &Address,
+    from: &Address,
+    to: &Address,
+    amount: i128,
+) -> Result<(), TransferError> {
+    if amount <= 0 {
+        return Err(TransferError::InvalidAmount);
+    }
+
+    let client = token::Client::new(env, token);
+    let from_before = balance(&client, from)?;
+    let to_before = balance(&client, to)?;
+
+    match client.try_transfer(from, to, &amount) {
+        Ok(Ok(())) => {}
+        _ => return Err(TransferError::TransferFailed),
+    }
+
+    // A self-transfer must leave the balance untouched
+    if from == to {
+        return match balance(&client, to)? == to_before {
+            true => Ok(()),
+            false => Err(TransferError::AmountMismatch),
+        };
+    }
+
+    let sent = from_before - balance(&client, from)?;
+    let received = balance(&client, to)? - to_before;
+    if sent != amount || received != amount {
+        return Err(TransferError::AmountMismatch);
+    }
+
+    Ok(())
+}
+
+fn balance(client: &token::Client, id: &Address) -> Result<i128, TransferError> {
+    match client.try_balance(id) {
+        Ok(Ok(balance)) => Ok(balance),
+        _ => Err(TransferError::TransferFailed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{contract, contractimpl, symbol_short, testutils::Address as _};
+
+    /// Token that burns a 1% fee on every transfer.
+    #[contract]
+    struct FeeToken;
+
+    #[contractimpl]
+    impl FeeToken {
+        pub fn mint(env: Env, to: Address, amount: i128) {
+            let balance = Self::balance(env.clone(), to.clone());
+            env.storage().persistent().set(&to, &(balance + amount));
+        }
+
+        pub fn balance(env: Env, id: Address) -> i128 {
+            env.storage().persistent().get(&id).unwrap_or(0)
+        }
+
+        pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+            let from_balance = Self::balance(env.clone(), from.clone());
+            if from_balance < amount {
+                panic!("insufficient balance");
+            }
+            let to_balance = Self::balance(env.clone(), to.clone());
+            env.storage().persistent().set(&from, &(from_balance - amount));
+            env.storage()
+                .persistent()
+                .set(&to, &(to_balance + amount - amount / 100));
+            env.events().publish((symbol_short!("transfer"),), amount);
+        }
+    }
+
+    #[contract]
+    struct Host;
+
+    #[test]
+    fn test_safe_transfer_with_asset_contract() {
+        let env = Env::default();
+        env.mock_all_auths_allowing_non_root_auth();
+        let host = env.register(Host, ());
+        let sac = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        let token = sac.address();
+        let from = Address::generate(&env);
+        let to = Address::generate(&env);
+        token::StellarAssetClient::new(&env, &token).mint(&from, &1_000);
+
+        env.as_contract(&host, || {
+            assert_eq!(safe_transfer(&env, &token, &from, &to, 400), Ok(()));
+            assert_eq!(
+                safe_transfer(&env, &token, &from, &to, 10_000),
+                Err(TransferError::TransferFailed)
+            );
+            assert_eq!(
+                safe_transfer(&env, &token, &from, &to, 0),
+                Err(TransferError::InvalidAmount)
+            );
+        });
+        assert_eq!(token::Client::new(&env, &token).balance(&to), 400);
+    }
+
+    #[test]
+    fn test_safe_transfer_detects_fee_on_transfer() {
+        let env = Env::default();
+        let host = env.register(Host, ());
+        let token = env.register(FeeToken, ());
+        let from = Address::generate(&env);
+        let to = Address::generate(&env);
+        FeeTokenClient::new(&env, &token).mint(&from, &1_000);
+
+        env.as_contract(&host, || {
+            assert_eq!(
+                safe_transfer(&env, &token, &from, &to, 500),
+                Err(TransferError::AmountMismatch)
+            );
+        });
+    }
+}
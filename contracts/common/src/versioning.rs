@@ -0,0 +1,136 @@
+//! Storage layout versioning.
+//!
+//! Each contract records the version of the storage layout it last wrote.
+//! After a code upgrade the stored version lags the code's expected version
+//! until the contract's `migrate` entrypoint has run; until then
+//! `require_version` fails, so the new code never reads data in a layout it
+//! does not understand.
+//!
+//! Contracts deployed before versioning have no stored version and read as
+//! version 0.
+
+use soroban_sdk::{contracttype, Env};
+
+/// Errors returned by the versioning helpers.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum VersionError {
+    /// Stored layout does not match the version the code expects
+    Mismatch,
+    /// Migration requested from a version other than the stored one, or
+    /// toward an older version
+    InvalidMigration,
+}
+
+/// Storage keys for the versioning helpers.
+#[derive(Clone)]
+#[contracttype]
+pub enum VersionKey {
+    /// Version of the storage layout last written
+    StorageVersion,
+}
+
+/// Returns the stored layout version, or 0 for pre-versioning contracts.
+pub fn stored_version(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&VersionKey::StorageVersion)
+        .unwrap_or(0)
+}
+
+/// Records `version` as the current layout. Contracts call this from
+/// `initialize` with their expected version.
+pub fn set_version(env: &Env, version: u32) {
+    env.storage()
+        .instance()
+        .set(&VersionKey::StorageVersion, &version);
+}
+
+/// Fails unless the stored layout is exactly `expected`.
+pub fn require_version(env: &Env, expected: u32) -> Result<(), VersionError> {
+    if stored_version(env) == expected {
+        Ok(())
+    } else {
+        Err(VersionError::Mismatch)
+    }
+}
+
+/// Migrates storage from `from` up to `to`, one version at a time.
+///
+/// `step` is called with each version being migrated away from
+/// (`from`, `from + 1`, ..., `to - 1`) and must rewrite that layout into the
+/// next one. The stored version is updated once all steps have run. Fails
+/// without running anything if `from` is not the stored version or `to` is
+/// older than `from`.
+pub fn migrate<F>(env: &Env, from: u32, to: u32, mut step: F) -> Result<(), VersionError>
+where
+    F: FnMut(&Env, u32),
+{
+    if stored_version(env) != from || to < from {
+        return Err(VersionError::InvalidMigration);
+    }
+    for version in from..to {
+        step(env, version);
+    }
+    set_version(env, to);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{contract, symbol_short};
+
+    #[contract]
+    struct Host;
+
+    #[test]
+    fn test_unversioned_contract_reads_as_zero() {
+        let env = Env::default();
+        let host = env.register(Host, ());
+
+        env.as_contract(&host, || {
+            assert_eq!(stored_version(&env), 0);
+            assert_eq!(require_version(&env, 1), Err(VersionError::Mismatch));
+
+            set_version(&env, 1);
+            assert_eq!(require_version(&env, 1), Ok(()));
+        });
+    }
+
+    #[test]
+    fn test_migrate_runs_each_step_in_order() {
+        let env = Env::default();
+        let host = env.register(Host, ());
+
+        env.as_contract(&host, || {
+            let mut steps = soroban_sdk::Vec::new(&env);
+            migrate(&env, 0, 3, |_, version| steps.push_back(version)).unwrap();
+
+            assert_eq!(steps, soroban_sdk::vec![&env, 0u32, 1, 2]);
+            assert_eq!(stored_version(&env), 3);
+        });
+    }
+
+    #[test]
+    fn test_migrate_refuses_wrong_source_version() {
+        let env = Env::default();
+        let host = env.register(Host, ());
+
+        env.as_contract(&host, || {
+            set_version(&env, 2);
+            let result = migrate(&env, 1, 3, |env, _| {
+                env.storage().instance().set(&symbol_short!("ran"), &true)
+            });
+
+            assert_eq!(result, Err(VersionError::InvalidMigration));
+            assert!(!env.storage().instance().has(&symbol_short!("ran")));
+            assert_eq!(stored_version(&env), 2);
+
+            // Downgrades are refused too
+            assert_eq!(
+                migrate(&env, 2, 1, |_, _| {}),
+                Err(VersionError::InvalidMigration)
+            );
+        });
+    }
+}
@@ -0,0 +1,214 @@
+//! # Device Account Contract
+//!
+//! Smart account whose payments must be signed by one of the owner's
+//! registered devices. Each device holds its own ed25519 key, so losing or
+//! retiring one device does not mean rotating the account's key.
+//!
+//! ## Features
+//!
+//! - **Device Keys**: Every authorization is signed by a registered device
+//! - **Bounded Set**: At most `MAX_DEVICES` keys per account
+//! - **Confirmed Registration**: A new device must be added by an existing one
+//! - **Cooldown**: New devices wait `DEVICE_COOLDOWN_SECS` before they can sign
+#![no_std]
+
+mod types;
+
+use common::versioning;
+use soroban_sdk::{
+    auth::{Context, CustomAccountInterface},
+    contract, contracterror, contractimpl,
+    crypto::Hash,
+    panic_with_error, BytesN, Env, Symbol, Vec,
+};
+
+pub use crate::types::{
+    DataKey, Device, DeviceEvents, DeviceSignature, DEVICE_COOLDOWN_SECS, MAX_DEVICES,
+};
+
+/// Error codes for the device account contract.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum DeviceError {
+    /// Contract not initialized
+    NotInitialized = 1,
+    /// Contract already initialized
+    AlreadyInitialized = 2,
+    /// Signing key is not a registered device
+    UnknownDevice = 3,
+    /// Device is still in its registration cooldown
+    DeviceNotActive = 4,
+    /// Device key is already registered
+    DeviceExists = 5,
+    /// Maximum number of devices registered
+    TooManyDevices = 6,
+    /// Removal would leave no active device
+    LastDevice = 7,
+    /// Storage was written by a different layout version
+    StorageVersionMismatch = 8,
+    /// Migration source version does not match stored version
+    InvalidMigration = 9,
+}
+
+/// Storage layout version this code expects.
+pub const STORAGE_VERSION: u32 = 1;
+
+#[contract]
+pub struct DeviceAccountContract;
+
+#[contractimpl]
+impl DeviceAccountContract {
+    /// Initializes the account with its first device, which can sign
+    /// straight away.
+    pub fn initialize(env: Env, public_key: BytesN<32>, label: Symbol) {
+        if env.storage().instance().has(&DataKey::Devices) {
+            panic_with_error!(&env, DeviceError::AlreadyInitialized);
+        }
+
+        let now = env.ledger().timestamp();
+        let device = Device {
+            public_key,
+            label,
+            added_at: now,
+            active_at: now,
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::Devices, &Vec::from_array(&env, [device.clone()]));
+        versioning::set_version(&env, STORAGE_VERSION);
+
+        DeviceEvents::added(&env, &device);
+    }
+
+    /// Migrates storage written by an older version of this contract to the
+    /// current layout (signed by an active device). `from_version` must
+    /// match the stored version.
+    pub fn migrate(env: Env, from_version: u32) {
+        env.current_contract_address().require_auth();
+
+        versioning::migrate(&env, from_version, STORAGE_VERSION, |_, _| {})
+            .unwrap_or_else(|_| panic_with_error!(&env, DeviceError::InvalidMigration));
+    }
+
+    /// Returns the storage layout version currently stored.
+    pub fn get_storage_version(env: Env) -> u32 {
+        versioning::stored_version(&env)
+    }
+
+    /// Registers a new device (signed by an active device).
+    ///
+    /// The device can sign once `DEVICE_COOLDOWN_SECS` have passed; until
+    /// then an active device can remove it again.
+    pub fn add_device(env: Env, public_key: BytesN<32>, label: Symbol) -> Device {
+        Self::require_storage_version(&env);
+        env.current_contract_address().require_auth();
+
+        let mut devices = Self::get_devices(env.clone());
+        if devices.iter().any(|d| d.public_key == public_key) {
+            panic_with_error!(&env, DeviceError::DeviceExists);
+        }
+        if devices.len() >= MAX_DEVICES {
+            panic_with_error!(&env, DeviceError::TooManyDevices);
+        }
+
+        let now = env.ledger().timestamp();
+        let device = Device {
+            public_key,
+            label,
+            added_at: now,
+            active_at: now + DEVICE_COOLDOWN_SECS,
+        };
+        devices.push_back(device.clone());
+        env.storage().instance().set(&DataKey::Devices, &devices);
+
+        DeviceEvents::added(&env, &device);
+
+        device
+    }
+
+    /// Removes a device, active or pending (signed by an active device). The
+    /// last active device cannot be removed.
+    pub fn remove_device(env: Env, public_key: BytesN<32>) {
+        Self::require_storage_version(&env);
+        env.current_contract_address().require_auth();
+
+        let mut devices = Self::get_devices(env.clone());
+        let index = devices
+            .iter()
+            .position(|d| d.public_key == public_key)
+            .unwrap_or_else(|| panic_with_error!(&env, DeviceError::UnknownDevice));
+        devices.remove(index as u32);
+
+        let now = env.ledger().timestamp();
+        if !devices.iter().any(|d| d.active_at <= now) {
+            panic_with_error!(&env, DeviceError::LastDevice);
+        }
+        env.storage().instance().set(&DataKey::Devices, &devices);
+
+        DeviceEvents::removed(&env, &public_key);
+    }
+
+    /// Returns the registered devices, pending ones included.
+    pub fn get_devices(env: Env) -> Vec<Device> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Devices)
+            .unwrap_or_else(|| panic_with_error!(&env, DeviceError::NotInitialized))
+    }
+
+    /// Returns whether `public_key` belongs to a device that can sign now.
+    pub fn is_active_device(env: Env, public_key: BytesN<32>) -> bool {
+        let now = env.ledger().timestamp();
+        Self::get_devices(env)
+            .iter()
+            .any(|d| d.public_key == public_key && d.active_at <= now)
+    }
+
+    // Internal helper refusing to run against storage written by a different
+    // layout version
+    fn require_storage_version(env: &Env) {
+        versioning::require_version(env, STORAGE_VERSION)
+            .unwrap_or_else(|_| panic_with_error!(env, DeviceError::StorageVersionMismatch));
+    }
+}
+
+#[contractimpl]
+impl CustomAccountInterface for DeviceAccountContract {
+    type Signature = DeviceSignature;
+    type Error = DeviceError;
+
+    /// Authorizes a call, such as a payment, made on behalf of the account.
+    /// The payload must be signed by a registered device that is past its
+    /// cooldown.
+    #[allow(non_snake_case)]
+    fn __check_auth(
+        env: Env,
+        signature_payload: Hash<32>,
+        signature: DeviceSignature,
+        _auth_contexts: Vec<Context>,
+    ) -> Result<(), DeviceError> {
+        let devices: Vec<Device> = env
+            .storage()
+            .instance()
+            .get(&DataKey::Devices)
+            .ok_or(DeviceError::NotInitialized)?;
+        let device = devices
+            .iter()
+            .find(|d| d.public_key == signature.public_key)
+            .ok_or(DeviceError::UnknownDevice)?;
+        if env.ledger().timestamp() < device.active_at {
+            return Err(DeviceError::DeviceNotActive);
+        }
+
+        env.crypto().ed25519_verify(
+            &signature.public_key,
+            &signature_payload.into(),
+            &signature.signature,
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
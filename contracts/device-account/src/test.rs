@@ -0,0 +1,142 @@
+//! Integration tests for the device account contract.
+
+#![cfg(test)]
+
+extern crate std;
+
+use crate::{
+    DeviceAccountContract, DeviceAccountContractClient, DeviceError, DeviceSignature,
+    DEVICE_COOLDOWN_SECS, MAX_DEVICES,
+};
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{symbol_short, testutils::Ledger, vec, BytesN, Env, IntoVal};
+
+struct Setup {
+    env: Env,
+    laptop: SigningKey,
+    client: DeviceAccountContractClient<'static>,
+}
+
+/// Creates a test environment with an account whose first device is a
+/// laptop.
+fn setup_test_env() -> Setup {
+    let env = Env::default();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let client = DeviceAccountContractClient::new(&env, &env.register(DeviceAccountContract, ()));
+    let laptop = device_key(1);
+    client.initialize(&public_key(&env, &laptop), &symbol_short!("laptop"));
+
+    Setup {
+        env,
+        laptop,
+        client,
+    }
+}
+
+fn device_key(seed: u8) -> SigningKey {
+    SigningKey::from_bytes(&[seed; 32])
+}
+
+fn public_key(env: &Env, key: &SigningKey) -> BytesN<32> {
+    BytesN::from_array(env, &key.verifying_key().to_bytes())
+}
+
+/// Runs the account's auth check on a payload signed by `key`.
+fn check_auth(s: &Setup, key: &SigningKey) -> Result<(), DeviceError> {
+    let payload = BytesN::from_array(&s.env, &[7; 32]);
+    let signature = DeviceSignature {
+        public_key: public_key(&s.env, key),
+        signature: BytesN::from_array(&s.env, &key.sign(&payload.to_array()).to_bytes()),
+    };
+    s.env
+        .try_invoke_contract_check_auth::<DeviceError>(
+            &s.client.address,
+            &payload,
+            signature.into_val(&s.env),
+            &vec![&s.env],
+        )
+        .map_err(|e| e.unwrap())
+}
+
+#[test]
+fn test_registered_device_authorizes() {
+    let s = setup_test_env();
+
+    assert_eq!(check_auth(&s, &s.laptop), Ok(()));
+    assert_eq!(
+        check_auth(&s, &device_key(2)),
+        Err(DeviceError::UnknownDevice)
+    );
+}
+
+#[test]
+fn test_new_device_waits_for_cooldown() {
+    let s = setup_test_env();
+    s.env.mock_all_auths();
+    let phone = device_key(2);
+
+    let device = s
+        .client
+        .add_device(&public_key(&s.env, &phone), &symbol_short!("phone"));
+    assert_eq!(device.active_at, 1_000 + DEVICE_COOLDOWN_SECS);
+    assert!(!s.client.is_active_device(&public_key(&s.env, &phone)));
+    assert_eq!(check_auth(&s, &phone), Err(DeviceError::DeviceNotActive));
+
+    s.env
+        .ledger()
+        .with_mut(|li| li.timestamp += DEVICE_COOLDOWN_SECS);
+    assert!(s.client.is_active_device(&public_key(&s.env, &phone)));
+    assert_eq!(check_auth(&s, &phone), Ok(()));
+}
+
+#[test]
+fn test_add_device_requires_account_authorization() {
+    let s = setup_test_env();
+    let phone = public_key(&s.env, &device_key(2));
+
+    assert!(s
+        .client
+        .try_add_device(&phone, &symbol_short!("phone"))
+        .is_err());
+    assert_eq!(s.client.get_devices().len(), 1);
+}
+
+#[test]
+fn test_device_set_is_bounded() {
+    let s = setup_test_env();
+    s.env.mock_all_auths();
+
+    let result = s
+        .client
+        .try_add_device(&public_key(&s.env, &s.laptop), &symbol_short!("dup"));
+    assert_eq!(result, Err(Ok(DeviceError::DeviceExists.into())));
+
+    for seed in 2..=MAX_DEVICES as u8 {
+        s.client.add_device(
+            &public_key(&s.env, &device_key(seed)),
+            &symbol_short!("spare"),
+        );
+    }
+    let extra = public_key(&s.env, &device_key(MAX_DEVICES as u8 + 1));
+    let result = s.client.try_add_device(&extra, &symbol_short!("extra"));
+    assert_eq!(result, Err(Ok(DeviceError::TooManyDevices.into())));
+}
+
+#[test]
+fn test_remove_device_keeps_an_active_one() {
+    let s = setup_test_env();
+    s.env.mock_all_auths();
+    let laptop = public_key(&s.env, &s.laptop);
+    let phone = public_key(&s.env, &device_key(2));
+    s.client.add_device(&phone, &symbol_short!("phone"));
+
+    // The phone is still pending, so the laptop is the only active device
+    let result = s.client.try_remove_device(&laptop);
+    assert_eq!(result, Err(Ok(DeviceError::LastDevice.into())));
+
+    s.client.remove_device(&phone);
+    assert_eq!(s.client.get_devices().len(), 1);
+    let result = s.client.try_remove_device(&phone);
+    assert_eq!(result, Err(Ok(DeviceError::UnknownDevice.into())));
+}
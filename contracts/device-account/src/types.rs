@@ -0,0 +1,60 @@
+//! Data types and events for the device account contract.
+
+use common::events;
+use soroban_sdk::{contracttype, symbol_short, BytesN, Env, Symbol};
+
+/// Most device keys an account may hold, pending ones included.
+pub const MAX_DEVICES: u32 = 5;
+
+/// Time a newly registered device waits before it can sign (1 day), in
+/// seconds. Gives the owner a window to remove a device added by a
+/// compromised one.
+pub const DEVICE_COOLDOWN_SECS: u64 = 86_400;
+
+/// A device key registered on the account.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Device {
+    /// ed25519 public key held by the device
+    pub public_key: BytesN<32>,
+    /// Owner-chosen name, e.g. `phone`
+    pub label: Symbol,
+    pub added_at: u64,
+    /// Ledger timestamp from which the device may sign
+    pub active_at: u64,
+}
+
+/// Signature accompanying an authorization of the account.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct DeviceSignature {
+    /// Key of the signing device
+    pub public_key: BytesN<32>,
+    /// ed25519 signature over the authorization payload
+    pub signature: BytesN<64>,
+}
+
+/// Storage keys for the device account contract.
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    /// Registered devices, in registration order
+    Devices,
+}
+
+/// Event emitters for device account operations.
+pub struct DeviceEvents;
+
+impl DeviceEvents {
+    /// Emitted when a device is registered.
+    pub fn added(env: &Env, device: &Device) {
+        let topics = (symbol_short!("device"), symbol_short!("added"));
+        events::publish(env, topics, device.clone());
+    }
+
+    /// Emitted when a device is removed.
+    pub fn removed(env: &Env, public_key: &BytesN<32>) {
+        let topics = (symbol_short!("device"), symbol_short!("removed"));
+        events::publish(env, topics, public_key.clone());
+    }
+}
@@ -0,0 +1,273 @@
+//! Adversarial tests for the Escrow Contract.
+//!
+//! Each test plays out a concrete attack from the threat model and asserts
+//! that it fails without moving funds or corrupting escrow state.
+
+#![cfg(test)]
+
+use crate::{EscrowContract, EscrowContractClient, EscrowError, EscrowStatus, ReversalRequest};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Ledger},
+    token, vec, Address, Env, InvokeError,
+};
+
+/// Creates a test environment with the contract deployed and initialized.
+fn setup_test_env() -> (
+    Env,
+    Address,
+    token::Client<'static>,
+    token::StellarAssetClient<'static>,
+    EscrowContractClient<'static>,
+) {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| {
+        li.sequence_number = 12345;
+        li.min_persistent_entry_ttl = 100_000;
+        li.max_entry_ttl = 1_000_000;
+    });
+
+    let stellar_asset = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let token_client = token::Client::new(&env, &stellar_asset.address());
+    let token_admin = token::StellarAssetClient::new(&env, &stellar_asset.address());
+
+    let contract_id = env.register(EscrowContract, ());
+    let client = EscrowContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &stellar_asset.address());
+
+    (env, admin, token_client, token_admin, client)
+}
+
+/// Deploys the escrow contract against a malicious token.
+fn setup_with_token(
+    env: &Env,
+) -> (Address, MaliciousTokenClient<'static>, EscrowContractClient<'static>) {
+    let token_id = env.register(MaliciousToken, ());
+    let token = MaliciousTokenClient::new(env, &token_id);
+    let contract_id = env.register(EscrowContract, ());
+    let client = EscrowContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin, &token_id);
+    (admin, token, client)
+}
+
+/// Expected result of a call rejected with `error`.
+fn rejected(error: EscrowError) -> Result<soroban_sdk::Error, InvokeError> {
+    Ok(error.into())
+}
+
+/// Advances the ledger past the rescue timelock.
+fn pass_rescue_delay(env: &Env) {
+    env.ledger().with_mut(|li| {
+        li.sequence_number += crate::RESCUE_DELAY_LEDGERS;
+    });
+}
+
+// ============================================
+// Malicious Token Attacks
+// ============================================
+
+/// Token that can skim fees, fake transfers or re-enter the escrow.
+#[contract]
+struct MaliciousToken;
+
+#[contractimpl]
+impl MaliciousToken {
+    pub fn mint(env: Env, to: Address, amount: i128) {
+        let balance = Self::balance(env.clone(), to.clone());
+        env.storage().persistent().set(&to, &(balance + amount));
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage().persistent().get(&id).unwrap_or(0)
+    }
+
+    /// Keeps 1% of every transfer.
+    pub fn skim(env: Env) {
+        env.storage().instance().set(&symbol_short!("skim"), &true);
+    }
+
+    /// Reports success without moving anything.
+    pub fn fake(env: Env) {
+        env.storage().instance().set(&symbol_short!("fake"), &true);
+    }
+
+    /// Reverses `escrow_id` from inside the next transfer.
+    pub fn arm(env: Env, escrow: Address, admin: Address, escrow_id: u64) {
+        env.storage().instance().set(&symbol_short!("escrow"), &escrow);
+        env.storage().instance().set(&symbol_short!("admin"), &admin);
+        env.storage().instance().set(&symbol_short!("id"), &escrow_id);
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        if env.storage().instance().has(&symbol_short!("fake")) {
+            return;
+        }
+        let received = if env.storage().instance().has(&symbol_short!("skim")) {
+            amount - amount / 100
+        } else {
+            amount
+        };
+
+        let from_balance = Self::balance(env.clone(), from.clone());
+        env.storage().persistent().set(&from, &(from_balance - amount));
+        Self::mint(env.clone(), to, received);
+
+        let escrow: Option<Address> = env.storage().instance().get(&symbol_short!("escrow"));
+        if let Some(escrow) = escrow {
+            let admin: Address = env.storage().instance().get(&symbol_short!("admin")).unwrap();
+            let escrow_id: u64 = env.storage().instance().get(&symbol_short!("id")).unwrap();
+            let requests = vec![&env, ReversalRequest { escrow_id }];
+            EscrowContractClient::new(&env, &escrow).batch_reverse_escrows(&admin, &requests);
+        }
+    }
+}
+
+#[test]
+fn test_fee_on_transfer_token_cannot_underfund_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (_admin, token, client) = setup_with_token(&env);
+
+    let depositor = Address::generate(&env);
+    token.mint(&depositor, &1_000_000);
+    token.skim();
+
+    // The escrow would record 1_000_000 while holding only 990_000
+    let res = client.try_create_escrow(&depositor, &Address::generate(&env), &1_000_000, &20000);
+    assert_eq!(res, Err(rejected(EscrowError::TokenTransferFailed)));
+    assert_eq!(token.balance(&depositor), 1_000_000);
+    assert_eq!(client.get_locked_balance(), 0);
+}
+
+#[test]
+fn test_token_faking_transfers_cannot_create_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (_admin, token, client) = setup_with_token(&env);
+
+    let depositor = Address::generate(&env);
+    token.mint(&depositor, &1_000_000);
+    token.fake();
+
+    let res = client.try_create_escrow(&depositor, &Address::generate(&env), &1_000_000, &20000);
+    assert_eq!(res, Err(rejected(EscrowError::TokenTransferFailed)));
+    assert_eq!(client.get_locked_balance(), 0);
+}
+
+#[test]
+fn test_reentrant_token_cannot_pay_both_parties() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (admin, token, client) = setup_with_token(&env);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    token.mint(&depositor, &1_000_000);
+    let escrow_id = client.create_escrow(&depositor, &recipient, &1_000_000, &20000);
+
+    // While releasing to the recipient, the token tries to refund the depositor too
+    token.arm(&client.address, &admin, &escrow_id);
+    assert!(client.try_release_escrow(&admin, &escrow_id).is_err());
+
+    assert_eq!(client.get_escrow(&escrow_id).unwrap().status, EscrowStatus::Active);
+    assert_eq!(token.balance(&client.address), 1_000_000);
+    assert_eq!(token.balance(&recipient), 0);
+    assert_eq!(token.balance(&depositor), 0);
+}
+
+// ============================================
+// Front-Running Attacks
+// ============================================
+
+#[test]
+fn test_dispute_cannot_be_raced_by_release() {
+    let (env, admin, token_client, token_admin, client) = setup_test_env();
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    token_admin.mint(&depositor, &10_000_000);
+    let escrow_id = client.create_escrow(&depositor, &recipient, &10_000_000, &20000);
+
+    client.set_arbitration_config(&admin, &1_000_000, &200);
+    let arbiter = Address::generate(&env);
+    token_admin.mint(&arbiter, &1_000_000);
+    client.register_arbiter(&arbiter, &1_000_000);
+
+    // Recipient disputes; a release submitted in the same window must not win
+    client.open_dispute(&recipient, &escrow_id, &arbiter);
+    assert!(client.try_release_escrow(&depositor, &escrow_id).is_err());
+    assert!(client.try_release_escrow(&admin, &escrow_id).is_err());
+
+    assert_eq!(client.get_escrow(&escrow_id).unwrap().status, EscrowStatus::Disputed);
+    assert_eq!(token_client.balance(&recipient), 0);
+}
+
+#[test]
+fn test_replacing_a_rescue_restarts_its_timelock() {
+    let (env, admin, token_client, token_admin, client) = setup_test_env();
+    token_admin.mint(&client.address, &750_000);
+    let token = token_client.address.clone();
+
+    let treasury = Address::generate(&env);
+    client.request_rescue(&admin, &token, &750_000, &treasury);
+    pass_rescue_delay(&env);
+
+    // A last-second swap of the destination voids the approved request...
+    let attacker = Address::generate(&env);
+    client.request_rescue(&admin, &token, &750_000, &attacker);
+    let res = client.try_rescue_tokens(&admin, &token, &750_000, &treasury);
+    assert_eq!(res, Err(rejected(EscrowError::NoPendingRescue)));
+
+    // ...and the replacement has to sit out a full delay in public
+    let res = client.try_rescue_tokens(&admin, &token, &750_000, &attacker);
+    assert_eq!(res, Err(rejected(EscrowError::RescueTimelocked)));
+    assert_eq!(token_client.balance(&attacker), 0);
+}
+
+// ============================================
+// Admin Key Compromise
+// ============================================
+
+#[test]
+fn test_compromised_admin_cannot_rescue_escrowed_funds() {
+    let (env, admin, token_client, token_admin, client) = setup_test_env();
+
+    let depositor = Address::generate(&env);
+    token_admin.mint(&depositor, &10_000_000);
+    client.create_escrow(&depositor, &Address::generate(&env), &10_000_000, &20000);
+
+    let attacker = Address::generate(&env);
+    let token = token_client.address.clone();
+    client.request_rescue(&admin, &token, &10_000_000, &attacker);
+    pass_rescue_delay(&env);
+
+    let res = client.try_rescue_tokens(&admin, &token, &10_000_000, &attacker);
+    assert_eq!(res, Err(rejected(EscrowError::RescueExceedsFree)));
+    assert_eq!(token_client.balance(&client.address), 10_000_000);
+}
+
+#[test]
+fn test_rotating_admin_during_timelock_stops_compromised_key() {
+    let (env, admin, token_client, token_admin, client) = setup_test_env();
+    token_admin.mint(&client.address, &750_000);
+    let token = token_client.address.clone();
+
+    // The leaked key queues a rescue to the attacker
+    let attacker = Address::generate(&env);
+    client.request_rescue(&admin, &token, &750_000, &attacker);
+
+    // Within the timelock the owners rotate to a fresh key and cancel
+    let new_admin = Address::generate(&env);
+    client.set_admin(&admin, &new_admin);
+    client.cancel_rescue(&new_admin);
+
+    pass_rescue_delay(&env);
+    let res = client.try_request_rescue(&admin, &token, &750_000, &attacker);
+    assert_eq!(res, Err(rejected(EscrowError::Unauthorized)));
+    let res = client.try_rescue_tokens(&new_admin, &token, &750_000, &attacker);
+    assert_eq!(res, Err(rejected(EscrowError::NoPendingRescue)));
+    assert_eq!(token_client.balance(&attacker), 0);
+}
@@ -0,0 +1,17 @@
+//! Interface of contracts that hold payments while a dispute is open.
+
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Minimal interface the escrow calls when a dispute opens and resolves.
+///
+/// `source` is the escrow contract itself, which authorizes each call, so a
+/// hook can restrict holds to the dispute contracts it trusts.
+#[allow(dead_code)]
+#[contractclient(name = "DisputeHookClient")]
+pub trait DisputeHook {
+    /// Holds recurring payments from `payer` to `payee`.
+    fn hold_payments(env: Env, source: Address, payer: Address, payee: Address);
+
+    /// Releases a hold placed by `hold_payments`.
+    fn release_payments(env: Env, source: Address, payer: Address, payee: Address);
+}
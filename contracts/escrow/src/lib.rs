@@ -4,17 +4,40 @@
 //! for handling failed transactions.
 #![no_std]
 
+mod hooks;
 mod types;
 mod validation;
+mod vault;
+
+use common::footprint::{self, StorageFamily};
+use common::ids::{self, IdScope};
+use common::limits::{self, Limit};
+use common::pagination::{self, IdPage};
+use common::reentrancy::ReentrancyGuard;
+use common::rounding::{self, DustPolicy};
+use common::token_io::{self, TransferError};
+use common::versioning;
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contractimpl, panic_with_error, symbol_short, token, vec,
+    xdr::ToXdr, Address, BytesN, Env, IntoVal, Vec,
+};
 
-use soroban_sdk::{contract, contractimpl, panic_with_error, token, Address, Env, Vec};
-
 pub use crate::types::{
-    BatchReversalResult, DataKey, Escrow, EscrowEvents, EscrowStatus, ReversalRequest,
-    ReversalResult, MAX_BATCH_SIZE, ReleaseRequest, ReleaseResult, BatchReleaseResult,
+    ArbitrationQuote, ArchivedEscrow, Arbiter, BatchReversalResult, Dispute, DisputeRuling,
+    DataKey, Escrow, EscrowEvents, EscrowStatus, LockedRebuild, PendingRescue, ReversalRequest,
+    ReversalResult, ReleaseRequest, ReleaseResult, BatchReleaseResult, YieldPosition,
+    MAX_ARBITRATION_FEE_BPS, RESCUE_DELAY_LEDGERS, SLASH_WINDOW_LEDGERS,
+};
+pub use crate::types::{
+    MerchantTier, SettlementTerms, TierPolicy, MAX_DISPUTE_HOOKS, MAX_DISPUTE_WINDOW_SECS,
+    MAX_SETTLEMENT_DELAY_SECS,
 };
+pub use crate::hooks::{DisputeHook, DisputeHookClient};
+pub use crate::vault::{YieldVault, YieldVaultClient};
 use crate::validation::validate_reversal;
 use crate::validation::validate_release;
+use crate::validation::validate_settlement;
 
 /// Error codes for the escrow contract.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -34,6 +57,62 @@ pub enum EscrowError {
     EscrowNotFound = 6,
     /// Contract already initialized
     AlreadyInitialized = 7,
+    /// Arbiter is not registered
+    ArbiterNotFound = 8,
+    /// Arbiter bond is below the configured minimum
+    InsufficientBond = 9,
+    /// Arbiter cannot be a party to the escrow
+    InvalidArbiter = 10,
+    /// Escrow is not active
+    EscrowNotActive = 11,
+    /// No dispute exists for the escrow
+    DisputeNotFound = 12,
+    /// Dispute has already been ruled on
+    DisputeAlreadyResolved = 13,
+    /// Arbiter still has disputes awaiting a ruling
+    ArbiterHasOpenDisputes = 14,
+    /// Arbitration fee exceeds the allowed maximum
+    InvalidFee = 15,
+    /// Ruling has already been slashed
+    AlreadySlashed = 16,
+    /// Dispute has not been ruled on yet
+    DisputeNotResolved = 17,
+    /// No yield vault has been configured
+    VaultNotConfigured = 18,
+    /// Yield vault is paused
+    VaultPaused = 19,
+    /// Escrow has no deferred vault payout to claim
+    NoDeferredPayout = 20,
+    /// Escrow ids have been exhausted
+    IdsExhausted = 21,
+    /// No archive age has been configured
+    ArchivingDisabled = 22,
+    /// Entrypoint was re-entered during an external call
+    Reentrancy = 23,
+    /// Token transfer failed or moved the wrong amount
+    TokenTransferFailed = 24,
+    /// Limit value is outside its hard bounds
+    LimitOutOfBounds = 25,
+    /// Storage was written by a different layout version
+    StorageVersionMismatch = 26,
+    /// Migration source version does not match stored version
+    InvalidMigration = 27,
+    /// No pending rescue matches the request
+    NoPendingRescue = 28,
+    /// The rescue timelock has not yet expired
+    RescueTimelocked = 29,
+    /// The rescue would touch funds owed to escrows or arbiters
+    RescueExceedsFree = 30,
+    /// The merchant's settlement delay has not elapsed
+    SettlementPending = 31,
+    /// The escrow's dispute window has closed
+    DisputeWindowClosed = 32,
+    /// Tier policy exceeds the allowed delay or window
+    InvalidTierPolicy = 33,
+    /// More dispute hooks than `MAX_DISPUTE_HOOKS`
+    TooManyHooks = 34,
+    /// Arbiter's bond is still within the slash window of a ruling
+    BondLocked = 35,
 }
 
 impl From<EscrowError> for soroban_sdk::Error {
@@ -42,6 +121,9 @@ impl From<EscrowError> for soroban_sdk::Error {
     }
 }
 
+/// Storage layout version this code expects.
+pub const STORAGE_VERSION: u32 = 2;
+
 #[contract]
 pub struct EscrowContract;
 
@@ -54,8 +136,8 @@ impl EscrowContract {
         }
 
         env.storage().instance().set(&DataKey::Admin, &admin);
+        versioning::set_version(&env, STORAGE_VERSION);
         env.storage().instance().set(&DataKey::Token, &token);
-        env.storage().instance().set(&DataKey::EscrowCounter, &0u64);
         env.storage()
             .instance()
             .set(&DataKey::TotalReversalBatches, &0u64);
@@ -76,6 +158,41 @@ impl EscrowContract {
             .set(&DataKey::TotalAmountReleased, &0i128);
     }
 
+    /// Migrates storage written by an older version of this contract to the
+    /// current layout (admin only). `from_version` must match the stored
+    /// version.
+    ///
+    /// Migrating from version 1 or older rebuilds the locked balance by walking
+    /// the escrow ids, at most `max_escrows` per call. Returns false while the
+    /// walk is unfinished; call again with the same `from_version` to resume.
+    /// The stored version only moves once the walk is complete.
+    pub fn migrate(env: Env, caller: Address, from_version: u32, max_escrows: u32) -> bool {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        if versioning::stored_version(&env) != from_version {
+            panic_with_error!(&env, EscrowError::InvalidMigration);
+        }
+        if from_version <= 1 && !Self::rebuild_open_principal(&env, max_escrows) {
+            return false;
+        }
+
+        // Version 1 only added the version stamp. Version 2 tracks the locked
+        // balance: the open escrows found by the walk plus the arbiter bonds
+        versioning::migrate(&env, from_version, STORAGE_VERSION, |env, version| {
+            if version == 1 {
+                Self::finish_locked_rebuild(env);
+            }
+        })
+        .unwrap_or_else(|_| panic_with_error!(&env, EscrowError::InvalidMigration));
+        true
+    }
+
+    /// Returns the storage layout version currently stored.
+    pub fn get_storage_version(env: Env) -> u32 {
+        versioning::stored_version(&env)
+    }
+
     /// Creates a new escrow.
     ///
     /// Locks funds from the depositor until released to recipient or reversed.
@@ -86,12 +203,102 @@ impl EscrowContract {
         amount: i128,
         deadline: u64,
     ) -> u64 {
+        Self::require_storage_version(&env);
         // Verify depositor authorization
         depositor.require_auth();
+        let _guard = Self::enter_guard(&env);
+
+        Self::create(&env, &depositor, &recipient, amount, deadline)
+    }
+
+    /// Creates a new escrow whose funds earn yield in the configured vault.
+    ///
+    /// Both parties authorize the yield split: `depositor_yield_bps` of the
+    /// accrued yield goes to the depositor (buyer) at settlement and the rest to
+    /// the recipient (seller), whichever way the escrow is settled.
+    pub fn create_yield_escrow(
+        env: Env,
+        depositor: Address,
+        recipient: Address,
+        amount: i128,
+        deadline: u64,
+        depositor_yield_bps: u32,
+    ) -> u64 {
+        Self::require_storage_version(&env);
+        depositor.require_auth();
+        recipient.require_auth();
+        let _guard = Self::enter_guard(&env);
+
+        if depositor_yield_bps > 10_000 {
+            panic_with_error!(&env, EscrowError::InvalidAmount);
+        }
+        let vault: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::YieldVault)
+            .unwrap_or_else(|| panic_with_error!(&env, EscrowError::VaultNotConfigured));
+        let vault_client = YieldVaultClient::new(&env, &vault);
+        if vault_client.is_paused() {
+            panic_with_error!(&env, EscrowError::VaultPaused);
+        }
+
+        let escrow_id = Self::create(&env, &depositor, &recipient, amount, deadline);
+
+        // Let the vault pull the escrowed funds from this contract
+        let contract = env.current_contract_address();
+        let token_client = token::Client::new(&env, &Self::token(&env));
+        let balance_before = token_client.balance(&contract);
+        env.authorize_as_current_contract(vec![
+            &env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: token_client.address.clone(),
+                    fn_name: symbol_short!("transfer"),
+                    args: (contract.clone(), vault.clone(), amount).into_val(&env),
+                },
+                sub_invocations: Vec::new(&env),
+            }),
+        ]);
+        let shares = vault_client.deposit(&contract, &amount);
+        // The vault must have pulled exactly the principal, or the locked
+        // balance would stop matching what the contract holds
+        if balance_before - token_client.balance(&contract) != amount {
+            panic_with_error!(&env, EscrowError::TokenTransferFailed);
+        }
+        // The principal now sits in the vault rather than in this contract
+        Self::adjust_locked(&env, -amount);
+
+        let position = YieldPosition {
+            escrow_id,
+            vault: vault.clone(),
+            shares,
+            principal: amount,
+            depositor_yield_bps,
+            deferred_to: None,
+            deferred_fee: 0,
+            deferred_fee_to: None,
+            settled: false,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::YieldPosition(escrow_id), &position);
+
+        EscrowEvents::yield_deposited(&env, escrow_id, &vault, amount, shares);
 
+        escrow_id
+    }
+
+    // Internal helper that pulls the depositor's funds and records a new escrow
+    fn create(
+        env: &Env,
+        depositor: &Address,
+        recipient: &Address,
+        amount: i128,
+        deadline: u64,
+    ) -> u64 {
         // Validate amount
         if amount <= 0 {
-            panic_with_error!(&env, EscrowError::InvalidAmount);
+            panic_with_error!(env, EscrowError::InvalidAmount);
         }
 
         // Get token and transfer funds to contract
@@ -100,21 +307,13 @@ impl EscrowContract {
             .instance()
             .get(&DataKey::Token)
             .expect("Contract not initialized");
-        let token_client = token::Client::new(&env, &token);
 
         // Transfer funds from depositor to this contract
-        token_client.transfer(&depositor, &env.current_contract_address(), &amount);
+        Self::send(env, &token, depositor, &env.current_contract_address(), amount);
+        Self::adjust_locked(env, amount);
 
-        // Get and increment escrow counter
-        let escrow_id: u64 = env
-            .storage()
-            .instance()
-            .get(&DataKey::EscrowCounter)
-            .unwrap_or(0)
-            + 1;
-        env.storage()
-            .instance()
-            .set(&DataKey::EscrowCounter, &escrow_id);
+        // Issue the next escrow id
+        let escrow_id = Self::next_escrow_id(env);
 
         // Create escrow record
         let escrow = Escrow {
@@ -133,19 +332,32 @@ impl EscrowContract {
             .persistent()
             .set(&DataKey::Escrow(escrow_id), &escrow);
 
+        // Fix the settlement terms of the recipient's current tier
+        let tier = Self::get_merchant_tier(env.clone(), recipient.clone());
+        let policy = Self::get_tier_policy(env.clone(), tier);
+        let now = env.ledger().timestamp();
+        let terms = SettlementTerms {
+            tier,
+            settle_after: now.saturating_add(policy.settlement_delay),
+            dispute_until: now.saturating_add(policy.dispute_window),
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::SettlementTerms(escrow_id), &terms);
+
         // Update user escrows list
         let mut user_escrows: Vec<u64> = env
             .storage()
             .persistent()
             .get(&DataKey::UserEscrows(depositor.clone()))
-            .unwrap_or(Vec::new(&env));
+            .unwrap_or(Vec::new(env));
         user_escrows.push_back(escrow_id);
         env.storage()
             .persistent()
             .set(&DataKey::UserEscrows(depositor.clone()), &user_escrows);
 
         // Emit event
-        EscrowEvents::escrow_created(&env, escrow_id, &depositor, &recipient, amount);
+        EscrowEvents::escrow_created(env, escrow_id, depositor, recipient, amount);
 
         escrow_id
     }
@@ -166,16 +378,18 @@ impl EscrowContract {
         caller: Address,
         requests: Vec<ReversalRequest>,
     ) -> BatchReversalResult {
+        Self::require_storage_version(&env);
         // Verify authorization
         caller.require_auth();
         Self::require_admin(&env, &caller);
+        let _guard = Self::enter_guard(&env);
 
         // Validate batch size
         let request_count = requests.len();
         if request_count == 0 {
             panic_with_error!(&env, EscrowError::EmptyBatch);
         }
-        if request_count > MAX_BATCH_SIZE {
+        if request_count > limits::get(&env, Limit::BatchSize) {
             panic_with_error!(&env, EscrowError::BatchTooLarge);
         }
 
@@ -187,18 +401,12 @@ impl EscrowContract {
             .unwrap_or(0)
             + 1;
 
-        // Get admin and token for validation
+        // Get admin for validation
         let admin: Address = env
             .storage()
             .instance()
             .get(&DataKey::Admin)
             .expect("Contract not initialized");
-        let token: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::Token)
-            .expect("Contract not initialized");
-        let token_client = token::Client::new(&env, &token);
 
         let current_ledger = env.ledger().sequence() as u64;
 
@@ -246,17 +454,11 @@ impl EscrowContract {
             let mut escrow = escrow_opt.clone().unwrap();
 
             // Transfer funds back to depositor
-            token_client.transfer(
-                &env.current_contract_address(),
-                &escrow.depositor,
-                &escrow.amount,
-            );
+            Self::pay_out(&env, &escrow, &escrow.depositor, 0, None);
 
             // Update escrow status
             escrow.status = EscrowStatus::Reversed;
-            env.storage()
-                .persistent()
-                .set(&DataKey::Escrow(escrow.escrow_id), &escrow);
+            Self::store_closed(&env, &escrow);
 
             // Record success
             results.push_back(ReversalResult::Success(
@@ -338,15 +540,17 @@ impl EscrowContract {
         caller: Address,
         requests: Vec<ReleaseRequest>,
     ) -> BatchReleaseResult {
+        Self::require_storage_version(&env);
         // Require auth for caller (admin or depositor(s))
         caller.require_auth();
+        let _guard = Self::enter_guard(&env);
 
         // Validate batch size
         let request_count = requests.len();
         if request_count == 0 {
             panic_with_error!(&env, EscrowError::EmptyBatch);
         }
-        if request_count > MAX_BATCH_SIZE {
+        if request_count > limits::get(&env, Limit::BatchSize) {
             panic_with_error!(&env, EscrowError::BatchTooLarge);
         }
 
@@ -363,12 +567,6 @@ impl EscrowContract {
             .instance()
             .get(&DataKey::Admin)
             .expect("Contract not initialized");
-        let token: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::Token)
-            .expect("Contract not initialized");
-        let token_client = token::Client::new(&env, &token);
 
         // Emit batch started event
         EscrowEvents::batch_release_started(&env, batch_id, request_count);
@@ -389,7 +587,9 @@ impl EscrowContract {
                 .persistent()
                 .get(&DataKey::Escrow(request.escrow_id));
 
-            let validation_result = validate_release(escrow_opt.as_ref(), &caller, &admin);
+            let terms = Self::get_settlement_terms(env.clone(), request.escrow_id);
+            let validation_result = validate_release(escrow_opt.as_ref(), &caller, &admin)
+                .and_then(|()| validate_settlement(terms.as_ref(), env.ledger().timestamp()));
 
             let (is_valid, error_code) = match validation_result {
                 Ok(()) => (true, 0u32),
@@ -402,26 +602,20 @@ impl EscrowContract {
         // Second pass: execute releases
         for (request, escrow_opt, is_valid, error_code) in validated_requests.iter() {
             if !is_valid {
-                results.push_back(ReleaseResult::Failure(request.escrow_id, *error_code));
+                results.push_back(ReleaseResult::Failure(request.escrow_id, error_code));
                 failed_count += 1;
-                EscrowEvents::release_failure(&env, batch_id, request.escrow_id, *error_code);
+                EscrowEvents::release_failure(&env, batch_id, request.escrow_id, error_code);
                 continue;
             }
 
             let mut escrow = escrow_opt.clone().unwrap();
 
             // Transfer to recipient
-            token_client.transfer(
-                &env.current_contract_address(),
-                &escrow.recipient,
-                &escrow.amount,
-            );
+            Self::pay_out(&env, &escrow, &escrow.recipient, 0, None);
 
             // Update status
             escrow.status = EscrowStatus::Released;
-            env.storage()
-                .persistent()
-                .set(&DataKey::Escrow(escrow.escrow_id), &escrow);
+            Self::store_closed(&env, &escrow);
 
             results.push_back(ReleaseResult::Success(
                 escrow.escrow_id,
@@ -493,9 +687,12 @@ impl EscrowContract {
 
     /// Releases an escrow to the recipient.
     ///
-    /// Can only be called by admin or depositor.
+    /// Can only be called by admin or depositor, once the settlement delay of
+    /// the recipient's merchant tier has elapsed.
     pub fn release_escrow(env: Env, caller: Address, escrow_id: u64) {
+        Self::require_storage_version(&env);
         caller.require_auth();
+        let _guard = Self::enter_guard(&env);
 
         let admin: Address = env
             .storage()
@@ -518,125 +715,1245 @@ impl EscrowContract {
         if escrow.status != EscrowStatus::Active {
             panic!("Escrow is not active");
         }
+        Self::require_settled(&env, escrow_id);
 
         // Transfer funds to recipient
-        let token_client = token::Client::new(&env, &escrow.token);
-        token_client.transfer(
-            &env.current_contract_address(),
-            &escrow.recipient,
-            &escrow.amount,
-        );
+        Self::pay_out(&env, &escrow, &escrow.recipient, 0, None);
 
         // Update escrow status
         let mut updated_escrow = escrow.clone();
         updated_escrow.status = EscrowStatus::Released;
-        env.storage()
-            .persistent()
-            .set(&DataKey::Escrow(escrow_id), &updated_escrow);
+        Self::store_closed(&env, &updated_escrow);
 
         // Emit event
         EscrowEvents::escrow_released(&env, escrow_id, &escrow.recipient, escrow.amount);
     }
 
-    /// Returns an escrow by ID.
-    pub fn get_escrow(env: Env, escrow_id: u64) -> Option<Escrow> {
+    /// Configures the arbiter registry (admin only).
+    ///
+    /// # Arguments
+    /// * `min_bond` - Minimum bond an arbiter must hold to be assigned disputes
+    /// * `fee_bps` - Fee paid to the arbiter per resolved dispute, in basis points
+    ///   of the escrowed amount
+    pub fn set_arbitration_config(env: Env, caller: Address, min_bond: i128, fee_bps: u32) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        if min_bond < 0 {
+            panic_with_error!(&env, EscrowError::InvalidAmount);
+        }
+        if fee_bps > MAX_ARBITRATION_FEE_BPS {
+            panic_with_error!(&env, EscrowError::InvalidFee);
+        }
+
         env.storage()
-            .persistent()
-            .get(&DataKey::Escrow(escrow_id))
+            .instance()
+            .set(&DataKey::MinArbiterBond, &min_bond);
+        env.storage()
+            .instance()
+            .set(&DataKey::ArbitrationFeeBps, &fee_bps);
     }
 
-    /// Returns all escrow IDs for a user.
-    pub fn get_user_escrows(env: Env, user: Address) -> Vec<u64> {
+    /// Registers the caller as an arbiter, or tops up an existing bond.
+    ///
+    /// The bond is transferred from the arbiter into the contract and stays
+    /// locked until the arbiter exits the registry.
+    pub fn register_arbiter(env: Env, arbiter: Address, bond: i128) -> Arbiter {
+        Self::require_storage_version(&env);
+        arbiter.require_auth();
+        let _guard = Self::enter_guard(&env);
+
+        if bond <= 0 {
+            panic_with_error!(&env, EscrowError::InvalidAmount);
+        }
+
+        let mut record: Arbiter = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Arbiter(arbiter.clone()))
+            .unwrap_or(Arbiter {
+                arbiter: arbiter.clone(),
+                bond: 0,
+                open_disputes: 0,
+                resolved_disputes: 0,
+                slashed_rulings: 0,
+                fees_earned: 0,
+            });
+
+        record.bond = record
+            .bond
+            .checked_add(bond)
+            .unwrap_or_else(|| panic_with_error!(&env, EscrowError::InvalidAmount));
+        if record.bond < Self::get_min_arbiter_bond(env.clone()) {
+            panic_with_error!(&env, EscrowError::InsufficientBond);
+        }
+
+        Self::send(&env, &Self::token(&env), &arbiter, &env.current_contract_address(), bond);
+        Self::adjust_locked(&env, bond);
+        Self::adjust_bonds(&env, bond);
+
         env.storage()
             .persistent()
-            .get(&DataKey::UserEscrows(user))
-            .unwrap_or(Vec::new(&env))
+            .set(&DataKey::Arbiter(arbiter.clone()), &record);
+
+        EscrowEvents::arbiter_bonded(&env, &arbiter, bond, record.bond);
+
+        record
     }
 
-    /// Returns the admin address.
-    pub fn get_admin(env: Env) -> Address {
+    /// Withdraws an arbiter's full bond and removes them from the registry.
+    ///
+    /// Only possible once every assigned dispute has been ruled on and
+    /// `SLASH_WINDOW_LEDGERS` have passed since the latest ruling.
+    pub fn withdraw_arbiter_bond(env: Env, arbiter: Address) -> i128 {
+        Self::require_storage_version(&env);
+        arbiter.require_auth();
+        let _guard = Self::enter_guard(&env);
+
+        let record = Self::load_arbiter(&env, &arbiter);
+        if record.open_disputes > 0 {
+            panic_with_error!(&env, EscrowError::ArbiterHasOpenDisputes);
+        }
+        if env.ledger().sequence() < Self::get_bond_locked_until(env.clone(), arbiter.clone()) {
+            panic_with_error!(&env, EscrowError::BondLocked);
+        }
+
+        if record.bond > 0 {
+            let contract = env.current_contract_address();
+            Self::send(&env, &Self::token(&env), &contract, &arbiter, record.bond);
+            Self::adjust_locked(&env, -record.bond);
+            Self::adjust_bonds(&env, -record.bond);
+        }
+
         env.storage()
-            .instance()
-            .get(&DataKey::Admin)
-            .expect("Contract not initialized")
-    }
+            .persistent()
+            .remove(&DataKey::Arbiter(arbiter.clone()));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::BondLockedUntil(arbiter.clone()));
 
-    /// Updates the admin address.
-    pub fn set_admin(env: Env, current_admin: Address, new_admin: Address) {
-        current_admin.require_auth();
-        Self::require_admin(&env, &current_admin);
-        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        EscrowEvents::arbiter_exited(&env, &arbiter, record.bond);
+
+        record.bond
     }
 
-    /// Returns the total number of reversal batches processed.
-    pub fn get_total_reversal_batches(env: Env) -> u64 {
+    /// Opens a dispute on an active escrow and assigns a registered arbiter.
+    ///
+    /// Either the depositor or the recipient may open a dispute within the
+    /// dispute window of the recipient's merchant tier. The escrow is frozen
+    /// until the arbiter rules; normal release and reversal paths reject it.
+    /// Each dispute hook holds recurring payments from the depositor to the
+    /// recipient until the ruling.
+    pub fn open_dispute(env: Env, caller: Address, escrow_id: u64, arbiter: Address) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+
+        let mut escrow = Self::load_escrow(&env, escrow_id);
+        if caller != escrow.depositor && caller != escrow.recipient {
+            panic_with_error!(&env, EscrowError::Unauthorized);
+        }
+        if escrow.status != EscrowStatus::Active {
+            panic_with_error!(&env, EscrowError::EscrowNotActive);
+        }
+        let terms = Self::get_settlement_terms(env.clone(), escrow_id);
+        if terms.is_some_and(|terms| env.ledger().timestamp() > terms.dispute_until) {
+            panic_with_error!(&env, EscrowError::DisputeWindowClosed);
+        }
+        if arbiter == escrow.depositor || arbiter == escrow.recipient {
+            panic_with_error!(&env, EscrowError::InvalidArbiter);
+        }
+
+        let mut record = Self::load_arbiter(&env, &arbiter);
+        if record.bond < Self::get_min_arbiter_bond(env.clone()) {
+            panic_with_error!(&env, EscrowError::InsufficientBond);
+        }
+        record.open_disputes += 1;
         env.storage()
-            .instance()
-            .get(&DataKey::TotalReversalBatches)
-            .unwrap_or(0)
-    }
+            .persistent()
+            .set(&DataKey::Arbiter(arbiter.clone()), &record);
 
-    /// Returns the total number of escrows reversed.
-    pub fn get_total_escrows_reversed(env: Env) -> u64 {
+        escrow.status = EscrowStatus::Disputed;
         env.storage()
-            .instance()
-            .get(&DataKey::TotalEscrowsReversed)
-            .unwrap_or(0)
+            .persistent()
+            .set(&DataKey::Escrow(escrow_id), &escrow);
+
+        let dispute = Dispute {
+            escrow_id,
+            arbiter: arbiter.clone(),
+            opened_by: caller.clone(),
+            opened_at: env.ledger().sequence() as u64,
+            ruling: DisputeRuling::Pending,
+            resolved_at: 0,
+            fee: 0,
+            slashed: false,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Dispute(escrow_id), &dispute);
+
+        Self::notify_hooks(&env, &escrow, true);
+
+        EscrowEvents::dispute_opened(&env, escrow_id, &caller, &arbiter);
     }
 
-    /// Returns the total amount reversed.
-    pub fn get_total_amount_reversed(env: Env) -> i128 {
+    /// Rules on a dispute (assigned arbiter only).
+    ///
+    /// The arbitration fee is deducted from the escrowed amount and paid to the
+    /// arbiter; the remainder goes to the party the ruling favours.
+    ///
+    /// If the escrow's funds sit in a paused vault, the payout and the fee are
+    /// deferred until `claim_deferred_payout`.
+    ///
+    /// # Returns
+    /// * The amount paid out to the favoured party, or 0 if the payout was deferred
+    pub fn resolve_dispute(
+        env: Env,
+        arbiter: Address,
+        escrow_id: u64,
+        ruling: DisputeRuling,
+    ) -> i128 {
+        Self::require_storage_version(&env);
+        arbiter.require_auth();
+        let _guard = Self::enter_guard(&env);
+
+        let mut dispute = Self::load_dispute(&env, escrow_id);
+        if dispute.arbiter != arbiter {
+            panic_with_error!(&env, EscrowError::Unauthorized);
+        }
+        if dispute.ruling != DisputeRuling::Pending {
+            panic_with_error!(&env, EscrowError::DisputeAlreadyResolved);
+        }
+
+        let mut escrow = Self::load_escrow(&env, escrow_id);
+        let (fee, _) = Self::arbitration_split(&env, escrow.amount);
+
+        let (beneficiary, status) = match ruling {
+            DisputeRuling::ReleaseToRecipient => (escrow.recipient.clone(), EscrowStatus::Released),
+            DisputeRuling::RefundDepositor => (escrow.depositor.clone(), EscrowStatus::Reversed),
+            DisputeRuling::Pending => panic_with_error!(&env, EscrowError::DisputeNotResolved),
+        };
+
+        let (payout, fee_paid) =
+            Self::pay_out(&env, &escrow, &beneficiary, fee, Some(arbiter.clone()))
+                .unwrap_or((0, 0));
+
+        escrow.status = status;
+        Self::store_closed(&env, &escrow);
+
+        dispute.ruling = ruling;
+        dispute.resolved_at = env.ledger().sequence() as u64;
+        dispute.fee = fee;
         env.storage()
-            .instance()
-            .get(&DataKey::TotalAmountReversed)
-            .unwrap_or(0)
+            .persistent()
+            .set(&DataKey::Dispute(escrow_id), &dispute);
+
+        let mut record = Self::load_arbiter(&env, &arbiter);
+        record.open_disputes = record.open_disputes.saturating_sub(1);
+        record.resolved_disputes += 1;
+        record.fees_earned = record.fees_earned.checked_add(fee_paid).unwrap_or(i128::MAX);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Arbiter(arbiter.clone()), &record);
+        let locked_until = env.ledger().sequence().saturating_add(SLASH_WINDOW_LEDGERS);
+        env.storage()
+            .persistent()
+            .set(&DataKey::BondLockedUntil(arbiter.clone()), &locked_until);
+
+        Self::notify_hooks(&env, &escrow, false);
+
+        EscrowEvents::dispute_resolved(&env, escrow_id, &arbiter, ruling, payout, fee_paid);
+
+        payout
     }
 
-    /// Returns the total number of release batches processed.
-    pub fn get_total_release_batches(env: Env) -> u64 {
+    /// Slashes an arbiter's bond for a provably wrong ruling (admin/governance only).
+    ///
+    /// The slashed amount compensates the party the ruling went against. Each
+    /// ruling can be slashed at most once, and never for more than the bond held.
+    pub fn slash_arbiter(env: Env, caller: Address, escrow_id: u64, amount: i128) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+        let _guard = Self::enter_guard(&env);
+
+        if amount <= 0 {
+            panic_with_error!(&env, EscrowError::InvalidAmount);
+        }
+
+        let mut dispute = Self::load_dispute(&env, escrow_id);
+        if dispute.ruling == DisputeRuling::Pending {
+            panic_with_error!(&env, EscrowError::DisputeNotResolved);
+        }
+        if dispute.slashed {
+            panic_with_error!(&env, EscrowError::AlreadySlashed);
+        }
+
+        let mut record = Self::load_arbiter(&env, &dispute.arbiter);
+        if amount > record.bond {
+            panic_with_error!(&env, EscrowError::InsufficientBond);
+        }
+
+        // Rulings can be slashed after the escrow itself has been archived
+        let archived: Option<ArchivedEscrow> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ArchivedEscrow(escrow_id));
+        let (depositor, recipient) = match archived {
+            Some(archived) => (archived.depositor, archived.recipient),
+            None => {
+                let escrow = Self::load_escrow(&env, escrow_id);
+                (escrow.depositor, escrow.recipient)
+            }
+        };
+        let wronged_party = match dispute.ruling {
+            DisputeRuling::ReleaseToRecipient => depositor,
+            _ => recipient,
+        };
+
+        let contract = env.current_contract_address();
+        Self::send(&env, &Self::token(&env), &contract, &wronged_party, amount);
+        Self::adjust_locked(&env, -amount);
+        Self::adjust_bonds(&env, -amount);
+
+        record.bond -= amount;
+        record.slashed_rulings += 1;
         env.storage()
-            .instance()
-            .get(&DataKey::TotalReleaseBatches)
-            .unwrap_or(0)
+            .persistent()
+            .set(&DataKey::Arbiter(dispute.arbiter.clone()), &record);
+
+        dispute.slashed = true;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Dispute(escrow_id), &dispute);
+
+        EscrowEvents::arbiter_slashed(&env, &dispute.arbiter, escrow_id, amount, &wronged_party);
     }
 
-    /// Returns the total number of escrows released.
-    pub fn get_total_escrows_released(env: Env) -> u64 {
+    /// Returns a registered arbiter.
+    pub fn get_arbiter(env: Env, arbiter: Address) -> Option<Arbiter> {
+        env.storage().persistent().get(&DataKey::Arbiter(arbiter))
+    }
+
+    /// Returns the ledger until which an arbiter's bond cannot be withdrawn,
+    /// or 0 if it has never ruled.
+    pub fn get_bond_locked_until(env: Env, arbiter: Address) -> u32 {
         env.storage()
-            .instance()
-            .get(&DataKey::TotalEscrowsReleased)
+            .persistent()
+            .get(&DataKey::BondLockedUntil(arbiter))
             .unwrap_or(0)
     }
 
-    /// Returns the total amount released.
-    pub fn get_total_amount_released(env: Env) -> i128 {
+    /// Returns the dispute for an escrow, if any.
+    pub fn get_dispute(env: Env, escrow_id: u64) -> Option<Dispute> {
+        env.storage().persistent().get(&DataKey::Dispute(escrow_id))
+    }
+
+    /// Returns the minimum bond required to be assigned disputes.
+    pub fn get_min_arbiter_bond(env: Env) -> i128 {
         env.storage()
             .instance()
-            .get(&DataKey::TotalAmountReleased)
+            .get(&DataKey::MinArbiterBond)
             .unwrap_or(0)
     }
 
-    /// Returns the escrow counter (total escrows created).
-    pub fn get_escrow_counter(env: Env) -> u64 {
+    /// Quotes the arbitration fee and payout a ruling on an escrow would
+    /// produce under the current fee, computed as `resolve_dispute` does.
+    pub fn quote_dispute_resolution(env: Env, escrow_id: u64) -> ArbitrationQuote {
+        let escrow = Self::load_escrow(&env, escrow_id);
+        let (fee, payout) = Self::arbitration_split(&env, escrow.amount);
+
+        ArbitrationQuote {
+            escrow_id,
+            amount: escrow.amount,
+            fee,
+            payout,
+        }
+    }
+
+    /// Returns the arbitration fee in basis points.
+    pub fn get_arbitration_fee_bps(env: Env) -> u32 {
         env.storage()
             .instance()
-            .get(&DataKey::EscrowCounter)
+            .get(&DataKey::ArbitrationFeeBps)
             .unwrap_or(0)
     }
 
-    // Internal helper to verify admin
-    fn require_admin(env: &Env, caller: &Address) {
-        let admin: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::Admin)
-            .expect("Contract not initialized");
+    /// Whitelists the yield vault new yield escrows deposit into (admin only).
+    pub fn set_yield_vault(env: Env, caller: Address, vault: Address) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
 
-        if *caller != admin {
-            panic_with_error!(env, EscrowError::Unauthorized);
-        }
+        env.storage().instance().set(&DataKey::YieldVault, &vault);
     }
-}
+
+    /// Sets the dust policy. Yield shares below `threshold` are swept to
+    /// `collector` instead of being transferred to the parties.
+    pub fn set_dust_policy(env: Env, caller: Address, threshold: i128, collector: Address) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        if threshold < 0 {
+            panic_with_error!(&env, EscrowError::InvalidAmount);
+        }
+        rounding::set_dust_policy(&env, &DustPolicy { threshold, collector });
+    }
+
+    /// Returns the dust policy, if one is configured.
+    pub fn get_dust_policy(env: Env) -> Option<DustPolicy> {
+        rounding::get_dust_policy(&env)
+    }
+
+    /// Overrides a protocol limit for this contract, within the limit's hard
+    /// bounds.
+    pub fn set_limit(env: Env, caller: Address, limit: Limit, value: u32) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        limits::set(&env, limit, value)
+            .unwrap_or_else(|_| panic_with_error!(&env, EscrowError::LimitOutOfBounds));
+    }
+
+    /// Returns the value of a protocol limit for this contract.
+    pub fn get_limit(env: Env, limit: Limit) -> u32 {
+        limits::get(&env, limit)
+    }
+
+    /// Returns the configured yield vault, if any.
+    pub fn get_yield_vault(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::YieldVault)
+    }
+
+    /// Returns the vault position backing a yield escrow.
+    pub fn get_yield_position(env: Env, escrow_id: u64) -> Option<YieldPosition> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::YieldPosition(escrow_id))
+    }
+
+    /// Completes a payout that was deferred because the vault was paused when
+    /// the escrow settled. Anyone may call this once the vault is unpaused; the
+    /// funds only ever go to the parties recorded at settlement.
+    pub fn claim_deferred_payout(env: Env, escrow_id: u64) -> i128 {
+        Self::require_storage_version(&env);
+        let _guard = Self::enter_guard(&env);
+
+        let position: YieldPosition = env
+            .storage()
+            .persistent()
+            .get(&DataKey::YieldPosition(escrow_id))
+            .unwrap_or_else(|| panic_with_error!(&env, EscrowError::NoDeferredPayout));
+        let beneficiary = match (&position.deferred_to, position.settled) {
+            (Some(beneficiary), false) => beneficiary.clone(),
+            _ => panic_with_error!(&env, EscrowError::NoDeferredPayout),
+        };
+        if YieldVaultClient::new(&env, &position.vault).is_paused() {
+            panic_with_error!(&env, EscrowError::VaultPaused);
+        }
+
+        let escrow = Self::load_escrow(&env, escrow_id);
+        let (payout, fee_paid) = Self::pay_out(
+            &env,
+            &escrow,
+            &beneficiary,
+            position.deferred_fee,
+            position.deferred_fee_to.clone(),
+        )
+        .unwrap_or_else(|| panic_with_error!(&env, EscrowError::VaultPaused));
+        // A deferred arbitration fee is only earned once it has been paid
+        if let (true, Some(arbiter)) = (fee_paid > 0, &position.deferred_fee_to) {
+            Self::credit_fee(&env, arbiter, fee_paid);
+        }
+        payout
+    }
+
+    /// Requests the recovery of tokens sent directly to this contract (admin
+    /// only).
+    ///
+    /// The rescue can be executed with `rescue_tokens` once
+    /// `RESCUE_DELAY_LEDGERS` have passed. A new request replaces any pending
+    /// one.
+    pub fn request_rescue(env: Env, caller: Address, token: Address, amount: i128, to: Address) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        if amount <= 0 {
+            panic_with_error!(&env, EscrowError::InvalidAmount);
+        }
+
+        let rescue = PendingRescue {
+            token,
+            amount,
+            to,
+            unlock_ledger: env.ledger().sequence().saturating_add(RESCUE_DELAY_LEDGERS),
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::PendingRescue, &rescue);
+
+        EscrowEvents::rescue_requested(&env, &rescue);
+    }
+
+    /// Cancels the pending token rescue (admin only).
+    pub fn cancel_rescue(env: Env, caller: Address) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        let rescue: PendingRescue = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingRescue)
+            .unwrap_or_else(|| panic_with_error!(&env, EscrowError::NoPendingRescue));
+        env.storage().instance().remove(&DataKey::PendingRescue);
+
+        EscrowEvents::rescue_cancelled(&env, &rescue);
+    }
+
+    /// Executes a pending token rescue once its timelock has expired (admin
+    /// only).
+    ///
+    /// The arguments must match the pending request. Balances owed to active
+    /// escrows and arbiter bonds can never be rescued; only the escrow token's
+    /// balance above that amount, or any other token, can be recovered.
+    pub fn rescue_tokens(env: Env, caller: Address, token: Address, amount: i128, to: Address) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+        let _guard = Self::enter_guard(&env);
+
+        let rescue: PendingRescue = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingRescue)
+            .unwrap_or_else(|| panic_with_error!(&env, EscrowError::NoPendingRescue));
+        if rescue.token != token || rescue.amount != amount || rescue.to != to {
+            panic_with_error!(&env, EscrowError::NoPendingRescue);
+        }
+        if env.ledger().sequence() < rescue.unlock_ledger {
+            panic_with_error!(&env, EscrowError::RescueTimelocked);
+        }
+
+        let contract = env.current_contract_address();
+        let balance = token::Client::new(&env, &token).balance(&contract);
+        let locked = if token == Self::token(&env) {
+            Self::get_locked_balance(env.clone())
+        } else {
+            0
+        };
+        if amount > balance - locked {
+            panic_with_error!(&env, EscrowError::RescueExceedsFree);
+        }
+
+        env.storage().instance().remove(&DataKey::PendingRescue);
+        Self::send(&env, &token, &contract, &to, amount);
+
+        EscrowEvents::tokens_rescued(&env, &token, &to, amount);
+    }
+
+    /// Returns the pending token rescue, if any.
+    pub fn get_pending_rescue(env: Env) -> Option<PendingRescue> {
+        env.storage().instance().get(&DataKey::PendingRescue)
+    }
+
+    /// Returns the escrow token balance the contract owes to escrows and
+    /// arbiters.
+    pub fn get_locked_balance(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::LockedBalance)
+            .unwrap_or(0)
+    }
+
+    /// Returns the total bond held for all registered arbiters.
+    pub fn get_total_bonds(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalBonds)
+            .unwrap_or(0)
+    }
+
+    /// Assigns a merchant's risk tier (admin only). Applies to escrows created
+    /// from now on.
+    pub fn set_merchant_tier(env: Env, caller: Address, merchant: Address, tier: MerchantTier) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::MerchantTier(merchant.clone()), &tier);
+
+        EscrowEvents::merchant_tier_set(&env, &merchant, tier);
+    }
+
+    /// Returns a merchant's risk tier (`Standard` if none was assigned).
+    pub fn get_merchant_tier(env: Env, merchant: Address) -> MerchantTier {
+        env.storage()
+            .persistent()
+            .get(&DataKey::MerchantTier(merchant))
+            .unwrap_or(MerchantTier::Standard)
+    }
+
+    /// Sets the settlement delay and dispute window of a merchant tier (admin
+    /// only). Applies to escrows created from now on.
+    pub fn set_tier_policy(env: Env, caller: Address, tier: MerchantTier, policy: TierPolicy) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        if policy.settlement_delay > MAX_SETTLEMENT_DELAY_SECS
+            || policy.dispute_window > MAX_DISPUTE_WINDOW_SECS
+        {
+            panic_with_error!(&env, EscrowError::InvalidTierPolicy);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::TierPolicy(tier), &policy);
+
+        EscrowEvents::tier_policy_set(&env, tier, &policy);
+    }
+
+    /// Returns the policy of a merchant tier.
+    pub fn get_tier_policy(env: Env, tier: MerchantTier) -> TierPolicy {
+        env.storage()
+            .instance()
+            .get(&DataKey::TierPolicy(tier))
+            .unwrap_or(tier.default_policy())
+    }
+
+    /// Sets the contracts that hold payments between the parties of an open
+    /// dispute (admin only). Replaces the previous list.
+    ///
+    /// Each hook must let this contract call `hold_payments` and
+    /// `release_payments`. Changing the list while disputes are open leaves
+    /// their holds with the old hooks until resolved there.
+    pub fn set_dispute_hooks(env: Env, caller: Address, hooks: Vec<Address>) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        if hooks.len() > MAX_DISPUTE_HOOKS {
+            panic_with_error!(&env, EscrowError::TooManyHooks);
+        }
+        env.storage().instance().set(&DataKey::DisputeHooks, &hooks);
+    }
+
+    /// Returns the contracts notified when a dispute opens or resolves.
+    pub fn get_dispute_hooks(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::DisputeHooks)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Returns the settlement terms fixed for an escrow at creation, if any.
+    pub fn get_settlement_terms(env: Env, escrow_id: u64) -> Option<SettlementTerms> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::SettlementTerms(escrow_id))
+    }
+
+    /// Sets how many ledgers must pass after an escrow is released or refunded
+    /// before it can be archived (admin only).
+    pub fn set_archive_age(env: Env, caller: Address, min_age_ledgers: u64) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::ArchiveAge, &min_age_ledgers);
+    }
+
+    /// Returns the configured archive age, if archiving is enabled.
+    pub fn get_archive_age(env: Env) -> Option<u64> {
+        env.storage().instance().get(&DataKey::ArchiveAge)
+    }
+
+    /// Moves closed escrows to the archive, replacing each full record with a
+    /// hash of it plus the key financial fields.
+    ///
+    /// Anyone may call this. Escrows that are missing, still active or
+    /// disputed, closed for less than the archive age, or still owed a
+    /// deferred vault payout are skipped. Returns the IDs that were archived.
+    pub fn archive_escrows(env: Env, escrow_ids: Vec<u64>) -> Vec<u64> {
+        Self::require_storage_version(&env);
+        let min_age: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ArchiveAge)
+            .unwrap_or_else(|| panic_with_error!(&env, EscrowError::ArchivingDisabled));
+        if escrow_ids.len() > limits::get(&env, Limit::BatchSize) {
+            panic_with_error!(&env, EscrowError::BatchTooLarge);
+        }
+
+        let current_ledger = env.ledger().sequence() as u64;
+        let mut archived_ids: Vec<u64> = Vec::new(&env);
+        for escrow_id in escrow_ids.iter() {
+            let escrow: Escrow = match env
+                .storage()
+                .persistent()
+                .get(&DataKey::Escrow(escrow_id))
+            {
+                Some(escrow) => escrow,
+                None => continue,
+            };
+            let closed = matches!(escrow.status, EscrowStatus::Released | EscrowStatus::Reversed);
+            if !closed {
+                continue;
+            }
+            // Escrows closed before the close ledger was recorded start their
+            // archive age the first time they are seen here
+            let closed_key = DataKey::ClosedAt(escrow_id);
+            let Some(closed_at) = env.storage().persistent().get::<_, u64>(&closed_key) else {
+                env.storage().persistent().set(&closed_key, &current_ledger);
+                continue;
+            };
+            if current_ledger < closed_at.saturating_add(min_age) {
+                continue;
+            }
+            let position: Option<YieldPosition> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::YieldPosition(escrow_id));
+            if position.as_ref().is_some_and(|position| !position.settled) {
+                continue;
+            }
+
+            let record_hash = Self::escrow_hash(&env, &escrow);
+            let archived = ArchivedEscrow {
+                escrow_id,
+                record_hash: record_hash.clone(),
+                depositor: escrow.depositor,
+                recipient: escrow.recipient,
+                amount: escrow.amount,
+                status: escrow.status,
+                archived_at: current_ledger,
+            };
+            env.storage()
+                .persistent()
+                .set(&DataKey::ArchivedEscrow(escrow_id), &archived);
+            env.storage().persistent().remove(&DataKey::Escrow(escrow_id));
+            env.storage()
+                .persistent()
+                .remove(&DataKey::YieldPosition(escrow_id));
+            env.storage()
+                .persistent()
+                .remove(&DataKey::SettlementTerms(escrow_id));
+            env.storage().persistent().remove(&closed_key);
+
+            EscrowEvents::escrow_archived(&env, escrow_id, &record_hash);
+            archived_ids.push_back(escrow_id);
+        }
+
+        if let Some(last) = archived_ids.last() {
+            let total = Self::get_total_escrows_archived(env.clone());
+            env.storage().instance().set(
+                &DataKey::TotalEscrowsArchived,
+                &(total + archived_ids.len() as u64),
+            );
+            env.storage()
+                .instance()
+                .set(&DataKey::LastArchivedEscrow, &last);
+        }
+
+        archived_ids
+    }
+
+    /// Returns the total number of escrows archived.
+    pub fn get_total_escrows_archived(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalEscrowsArchived)
+            .unwrap_or(0)
+    }
+
+    /// Returns the entry counts and approximate sizes of live and archived
+    /// escrows, for forecasting rent and planning archival.
+    ///
+    /// Escrows are sized like the newest escrow and archived records like the
+    /// most recently archived one.
+    pub fn get_storage_report(env: Env) -> Vec<StorageFamily> {
+        let created = Self::get_escrow_counter(env.clone());
+        let archived = Self::get_total_escrows_archived(env.clone());
+
+        let escrow_size = Self::get_escrow(env.clone(), created)
+            .map_or(0, |escrow| footprint::entry_size(&env, DataKey::Escrow(created), escrow));
+        let last_archived: Option<u64> =
+            env.storage().instance().get(&DataKey::LastArchivedEscrow);
+        let archived_size = last_archived.map_or(0, |escrow_id| {
+            let record = Self::get_archived_escrow(env.clone(), escrow_id);
+            footprint::entry_size(&env, DataKey::ArchivedEscrow(escrow_id), record)
+        });
+
+        let live = created.saturating_sub(archived);
+        vec![
+            &env,
+            footprint::family(symbol_short!("escrows"), live, escrow_size),
+            footprint::family(symbol_short!("archived"), archived, archived_size),
+        ]
+    }
+
+    /// Returns the archived record of an escrow.
+    pub fn get_archived_escrow(env: Env, escrow_id: u64) -> Option<ArchivedEscrow> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ArchivedEscrow(escrow_id))
+    }
+
+    /// Checks a full escrow record against the hash kept in the archive.
+    pub fn verify_archived_escrow(env: Env, escrow: Escrow) -> bool {
+        let archived: Option<ArchivedEscrow> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ArchivedEscrow(escrow.escrow_id));
+        match archived {
+            Some(archived) => archived.record_hash == Self::escrow_hash(&env, &escrow),
+            None => false,
+        }
+    }
+
+    /// Returns an escrow by ID.
+    pub fn get_escrow(env: Env, escrow_id: u64) -> Option<Escrow> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Escrow(escrow_id))
+    }
+
+    /// Returns all escrow IDs for a user.
+    pub fn get_user_escrows(env: Env, user: Address) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::UserEscrows(user))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Returns a page of a user's escrow IDs, oldest first.
+    ///
+    /// Pass `None` to start and then each page's `next_cursor` until it is
+    /// `None`. Pages stay consistent while new escrows are created.
+    pub fn get_user_escrows_page(
+        env: Env,
+        user: Address,
+        cursor: Option<u64>,
+        limit: u32,
+    ) -> IdPage {
+        let escrow_ids = Self::get_user_escrows(env.clone(), user);
+        pagination::page_after(&env, &escrow_ids, cursor, limit)
+    }
+
+    /// Returns the admin address.
+    pub fn get_admin(env: Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .expect("Contract not initialized")
+    }
+
+    /// Updates the admin address.
+    pub fn set_admin(env: Env, current_admin: Address, new_admin: Address) {
+        Self::require_storage_version(&env);
+        current_admin.require_auth();
+        Self::require_admin(&env, &current_admin);
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+    }
+
+    /// Returns the total number of reversal batches processed.
+    pub fn get_total_reversal_batches(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalReversalBatches)
+            .unwrap_or(0)
+    }
+
+    /// Returns the total number of escrows reversed.
+    pub fn get_total_escrows_reversed(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalEscrowsReversed)
+            .unwrap_or(0)
+    }
+
+    /// Returns the total amount reversed.
+    pub fn get_total_amount_reversed(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalAmountReversed)
+            .unwrap_or(0)
+    }
+
+    /// Returns the total number of release batches processed.
+    pub fn get_total_release_batches(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalReleaseBatches)
+            .unwrap_or(0)
+    }
+
+    /// Returns the total number of escrows released.
+    pub fn get_total_escrows_released(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalEscrowsReleased)
+            .unwrap_or(0)
+    }
+
+    /// Returns the total amount released.
+    pub fn get_total_amount_released(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalAmountReleased)
+            .unwrap_or(0)
+    }
+
+    /// Returns the escrow counter (total escrows created).
+    pub fn get_escrow_counter(env: Env) -> u64 {
+        let legacy: Option<u64> = env.storage().instance().get(&DataKey::EscrowCounter);
+        legacy
+            .or_else(|| ids::last_id(&env, &Self::escrow_ids()))
+            .unwrap_or(0)
+    }
+
+    // Internal helper that issues escrow ids from the shared id counters. The
+    // first call after an upgrade carries the legacy EscrowCounter over so
+    // existing ids are never reissued.
+    fn next_escrow_id(env: &Env) -> u64 {
+        let scope = Self::escrow_ids();
+        let legacy: Option<u64> = env.storage().instance().get(&DataKey::EscrowCounter);
+        if let Some(last_issued) = legacy {
+            ids::reserve_through(env, &scope, last_issued)
+                .unwrap_or_else(|_| panic_with_error!(env, EscrowError::IdsExhausted));
+            env.storage().instance().remove(&DataKey::EscrowCounter);
+        }
+
+        ids::next_id(env, &scope)
+            .unwrap_or_else(|_| panic_with_error!(env, EscrowError::IdsExhausted))
+    }
+
+    // Internal helper returning the id scope for escrows
+    fn escrow_ids() -> IdScope {
+        IdScope::new(0, symbol_short!("escrow"))
+    }
+
+    // Internal helper that pays out the funds backing an escrow: `fee` goes to
+    // `fee_to` and the rest of the principal to `beneficiary`. For yield escrows
+    // the vault position is redeemed first and the yield split between the
+    // parties; if the vault is paused the payout is deferred and `None` returned.
+    // Otherwise returns the amounts actually paid as `(payout, fee)`.
+    fn pay_out(
+        env: &Env,
+        escrow: &Escrow,
+        beneficiary: &Address,
+        fee: i128,
+        fee_to: Option<Address>,
+    ) -> Option<(i128, i128)> {
+        let contract = env.current_contract_address();
+
+        let position: Option<YieldPosition> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::YieldPosition(escrow.escrow_id));
+
+        let mut principal = escrow.amount;
+        if let Some(mut position) = position {
+            let vault_client = YieldVaultClient::new(env, &position.vault);
+            if vault_client.is_paused() {
+                position.deferred_to = Some(beneficiary.clone());
+                position.deferred_fee = fee;
+                position.deferred_fee_to = fee_to;
+                env.storage()
+                    .persistent()
+                    .set(&DataKey::YieldPosition(escrow.escrow_id), &position);
+                EscrowEvents::yield_deferred(env, escrow.escrow_id, beneficiary);
+                return None;
+            }
+
+            // Only what actually arrived is paid out, so a vault that over-reports
+            // cannot drain the balance other escrows share
+            let token_client = token::Client::new(env, &escrow.token);
+            let balance_before = token_client.balance(&contract);
+            let reported = vault_client.withdraw(&contract, &position.shares);
+            let redeemed = token_client.balance(&contract) - balance_before;
+            if reported < 0 || redeemed < 0 {
+                panic_with_error!(env, EscrowError::InvalidAmount);
+            }
+
+            // A vault loss is borne by the principal; only gains count as yield
+            principal = redeemed.min(position.principal);
+            let accrued = redeemed - principal;
+            // The depositor's share rounds down; the remainder goes to the recipient
+            let depositor_yield = rounding::share_bps(accrued, position.depositor_yield_bps)
+                .unwrap_or_else(|| panic_with_error!(env, EscrowError::InvalidAmount));
+            let recipient_yield = accrued - depositor_yield;
+            Self::send_or_sweep(env, &escrow.token, &escrow.depositor, depositor_yield);
+            Self::send_or_sweep(env, &escrow.token, &escrow.recipient, recipient_yield);
+
+            position.settled = true;
+            env.storage()
+                .persistent()
+                .set(&DataKey::YieldPosition(escrow.escrow_id), &position);
+            EscrowEvents::yield_settled(
+                env,
+                escrow.escrow_id,
+                redeemed,
+                depositor_yield,
+                recipient_yield,
+            );
+        } else {
+            // The principal leaves the contract below, to the beneficiary or as fee
+            Self::adjust_locked(env, -escrow.amount);
+        }
+
+        let fee = fee.min(principal);
+        let payout = principal - fee;
+        if payout > 0 {
+            Self::send(env, &escrow.token, &contract, beneficiary, payout);
+        }
+        if let (true, Some(fee_to)) = (fee > 0, fee_to) {
+            Self::send(env, &escrow.token, &contract, &fee_to, fee);
+        }
+
+        Some((payout, fee))
+    }
+
+    // Internal helper crediting a paid arbitration fee to the arbiter's record,
+    // if the arbiter is still registered
+    fn credit_fee(env: &Env, arbiter: &Address, fee: i128) {
+        let key = DataKey::Arbiter(arbiter.clone());
+        let Some(mut record) = env.storage().persistent().get::<_, Arbiter>(&key) else {
+            return;
+        };
+        record.fees_earned = record.fees_earned.checked_add(fee).unwrap_or(i128::MAX);
+        env.storage().persistent().set(&key, &record);
+    }
+
+    // Internal helper tracking the escrow token balance owed to escrows and
+    // arbiters, which token rescues must never touch
+    fn adjust_locked(env: &Env, delta: i128) {
+        let locked = Self::get_locked_balance(env.clone())
+            .checked_add(delta)
+            .unwrap_or_else(|| panic_with_error!(env, EscrowError::InvalidAmount));
+        env.storage()
+            .instance()
+            .set(&DataKey::LockedBalance, &locked.max(0));
+    }
+
+    // Internal helper tracking the bond held for all arbiters
+    fn adjust_bonds(env: &Env, delta: i128) {
+        let bonds = Self::get_total_bonds(env.clone())
+            .checked_add(delta)
+            .unwrap_or_else(|| panic_with_error!(env, EscrowError::InvalidAmount));
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalBonds, &bonds.max(0));
+    }
+
+    // Internal helper advancing the locked balance rebuild by up to
+    // `max_escrows` escrow ids, summing the principal of open escrows the
+    // contract holds itself (vault escrows are held by the vault). Returns true
+    // once every issued id has been inspected.
+    fn rebuild_open_principal(env: &Env, max_escrows: u32) -> bool {
+        let mut rebuild: LockedRebuild = env
+            .storage()
+            .instance()
+            .get(&DataKey::LockedRebuild)
+            .unwrap_or(LockedRebuild {
+                next_id: 1,
+                open_principal: 0,
+            });
+        let last_id = Self::get_escrow_counter(env.clone());
+        let chunk = max_escrows.min(limits::get(env, Limit::BatchSize)) as u64;
+        let end = last_id.min(rebuild.next_id.saturating_add(chunk).saturating_sub(1));
+
+        for escrow_id in rebuild.next_id..=end {
+            let escrow: Option<Escrow> =
+                env.storage().persistent().get(&DataKey::Escrow(escrow_id));
+            let Some(escrow) = escrow else { continue };
+            let open = matches!(escrow.status, EscrowStatus::Active | EscrowStatus::Disputed);
+            let in_vault = env
+                .storage()
+                .persistent()
+                .has(&DataKey::YieldPosition(escrow_id));
+            if open && !in_vault {
+                rebuild.open_principal = rebuild.open_principal.saturating_add(escrow.amount);
+            }
+        }
+        if end >= rebuild.next_id {
+            rebuild.next_id = end + 1;
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::LockedRebuild, &rebuild);
+
+        rebuild.next_id > last_id
+    }
+
+    // Internal helper completing the locked balance rebuild. Bonds posted before
+    // the bond total was tracked cannot be enumerated, so everything the
+    // contract holds beyond the open escrows is counted as bonds: no bond can
+    // become rescuable, at the cost of keeping tokens that arrived before the
+    // migration locked too.
+    fn finish_locked_rebuild(env: &Env) {
+        let rebuild: LockedRebuild = env
+            .storage()
+            .instance()
+            .get(&DataKey::LockedRebuild)
+            .unwrap_or_else(|| panic_with_error!(env, EscrowError::InvalidMigration));
+        env.storage().instance().remove(&DataKey::LockedRebuild);
+
+        let balance = token::Client::new(env, &Self::token(env))
+            .balance(&env.current_contract_address());
+        let bonds = (balance - rebuild.open_principal).max(0);
+        env.storage().instance().set(&DataKey::TotalBonds, &bonds);
+        env.storage()
+            .instance()
+            .set(&DataKey::LockedBalance, &(rebuild.open_principal + bonds));
+    }
+
+    // Internal helper taking the reentrancy lock for entrypoints that call out
+    // to the token or the yield vault
+    fn enter_guard(env: &Env) -> ReentrancyGuard {
+        ReentrancyGuard::enter(env)
+            .unwrap_or_else(|_| panic_with_error!(env, EscrowError::Reentrancy))
+    }
+
+    // Internal helper that pays a yield share out of the contract, sweeping it
+    // to the dust collector instead when it falls below the dust threshold
+    fn send_or_sweep(env: &Env, token: &Address, to: &Address, amount: i128) {
+        if amount <= 0 {
+            return;
+        }
+        let to = match rounding::get_dust_policy(env) {
+            Some(policy) if rounding::is_dust(&policy, amount) => policy.collector,
+            _ => to.clone(),
+        };
+        Self::send(env, token, &env.current_contract_address(), &to, amount);
+    }
+
+    // Internal helper moving tokens with balance checks; a token that moves the
+    // wrong amount aborts the whole call
+    fn send(env: &Env, token: &Address, from: &Address, to: &Address, amount: i128) {
+        token_io::safe_transfer(env, token, from, to, amount).unwrap_or_else(|e| match e {
+            TransferError::InvalidAmount => panic_with_error!(env, EscrowError::InvalidAmount),
+            _ => panic_with_error!(env, EscrowError::TokenTransferFailed),
+        })
+    }
+
+    // Internal helper hashing the XDR of a full escrow record
+    fn escrow_hash(env: &Env, escrow: &Escrow) -> BytesN<32> {
+        env.crypto().sha256(&escrow.clone().to_xdr(env)).into()
+    }
+
+    // Internal helper to load the escrow token
+    fn token(env: &Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::Token)
+            .expect("Contract not initialized")
+    }
+
+    // Internal helper splitting an escrowed amount into the arbitration fee
+    // and the favoured party's payout
+    fn arbitration_split(env: &Env, amount: i128) -> (i128, i128) {
+        let fee = rounding::fee_bps(amount, Self::get_arbitration_fee_bps(env.clone()))
+            .unwrap_or_else(|| panic_with_error!(env, EscrowError::InvalidAmount));
+        (fee, amount - fee)
+    }
+
+    // Internal helper storing an escrow that was just released or refunded,
+    // along with the ledger it closed at
+    fn store_closed(env: &Env, escrow: &Escrow) {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Escrow(escrow.escrow_id), escrow);
+        env.storage().persistent().set(
+            &DataKey::ClosedAt(escrow.escrow_id),
+            &(env.ledger().sequence() as u64),
+        );
+    }
+
+    // Internal helper to load an escrow or fail
+    fn load_escrow(env: &Env, escrow_id: u64) -> Escrow {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Escrow(escrow_id))
+            .unwrap_or_else(|| panic_with_error!(env, EscrowError::EscrowNotFound))
+    }
+
+    // Internal helper asking each dispute hook to hold (or release) payments
+    // from the depositor to the recipient. A failing hook is reported but
+    // never blocks the dispute, so no hook can stop a party from disputing.
+    fn notify_hooks(env: &Env, escrow: &Escrow, hold: bool) {
+        let contract = env.current_contract_address();
+        for hook in Self::get_dispute_hooks(env.clone()).iter() {
+            let client = DisputeHookClient::new(env, &hook);
+            let result = if hold {
+                client
+                    .try_hold_payments(&contract, &escrow.depositor, &escrow.recipient)
+                    .is_ok_and(|inner| inner.is_ok())
+            } else {
+                client
+                    .try_release_payments(&contract, &escrow.depositor, &escrow.recipient)
+                    .is_ok_and(|inner| inner.is_ok())
+            };
+            if !result {
+                EscrowEvents::dispute_hook_failed(env, escrow.escrow_id, &hook);
+            }
+        }
+    }
+
+    // Internal helper refusing to settle an escrow before its settlement delay
+    fn require_settled(env: &Env, escrow_id: u64) {
+        let terms = Self::get_settlement_terms(env.clone(), escrow_id);
+        if validate_settlement(terms.as_ref(), env.ledger().timestamp()).is_err() {
+            panic_with_error!(env, EscrowError::SettlementPending);
+        }
+    }
+
+    // Internal helper to load a registered arbiter or fail
+    fn load_arbiter(env: &Env, arbiter: &Address) -> Arbiter {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Arbiter(arbiter.clone()))
+            .unwrap_or_else(|| panic_with_error!(env, EscrowError::ArbiterNotFound))
+    }
+
+    // Internal helper to load a dispute or fail
+    fn load_dispute(env: &Env, escrow_id: u64) -> Dispute {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Dispute(escrow_id))
+            .unwrap_or_else(|| panic_with_error!(env, EscrowError::DisputeNotFound))
+    }
+
+    // Internal helper refusing to run against storage written by a different
+    // layout version
+    fn require_storage_version(env: &Env) {
+        versioning::require_version(env, STORAGE_VERSION)
+            .unwrap_or_else(|_| panic_with_error!(env, EscrowError::StorageVersionMismatch));
+    }
+
+    // Internal helper to verify admin
+    fn require_admin(env: &Env, caller: &Address) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .expect("Contract not initialized");
+
+        if *caller != admin {
+            panic_with_error!(env, EscrowError::Unauthorized);
+        }
+    }
+}
 
 #[cfg(test)]
 mod test;
+
+#[cfg(test)]
+mod attack_test;
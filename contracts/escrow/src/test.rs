@@ -3,9 +3,12 @@
 #![cfg(test)]
 
 use crate::{
-    EscrowContract, EscrowContractClient, EscrowStatus, ReversalRequest, ReversalResult,
+    DataKey, DisputeRuling, EscrowContract, EscrowContractClient, EscrowError, EscrowStatus,
+    MerchantTier, ReleaseRequest, ReleaseResult, ReversalRequest, ReversalResult, TierPolicy,
 };
+use common::limits::Limit;
 use soroban_sdk::{
+    contract, contractimpl, symbol_short,
     testutils::{Address as _, Events as _, Ledger},
     token, Address, Env, Vec,
 };
@@ -23,6 +26,9 @@ fn setup_test_env() -> (
     env.mock_all_auths();
     env.ledger().with_mut(|li| {
         li.sequence_number = 12345;
+        // Keep entries live across timelocks that span many ledgers
+        li.min_persistent_entry_ttl = 200_000;
+        li.max_entry_ttl = 1_000_000;
     });
 
     // Deploy token contract
@@ -49,7 +55,7 @@ fn create_reversal_request(escrow_id: u64) -> ReversalRequest {
 
 /// Helper to create an escrow and return its ID.
 fn create_test_escrow(
-    env: &Env,
+    _env: &Env,
     client: &EscrowContractClient,
     token_admin: &token::StellarAssetClient,
     depositor: &Address,
@@ -70,7 +76,7 @@ fn create_test_escrow(
 
 #[test]
 fn test_initialize_contract() {
-    let (_env, admin, token, _token_client, _token_admin, client) = setup_test_env();
+    let (_env, admin, _token, _token_client, _token_admin, client) = setup_test_env();
 
     assert_eq!(client.get_admin(), admin);
     assert_eq!(client.get_total_reversal_batches(), 0);
@@ -94,7 +100,7 @@ fn test_cannot_initialize_twice() {
 
 #[test]
 fn test_create_escrow() {
-    let (env, _admin, _token, token_client, token_admin, client) = setup_test_env();
+    let (env, _admin, _token, _token_client, token_admin, client) = setup_test_env();
 
     let depositor = Address::generate(&env);
     let recipient = Address::generate(&env);
@@ -155,6 +161,48 @@ fn test_create_multiple_escrows() {
     assert_eq!(user_escrows.len(), 2);
 }
 
+#[test]
+fn test_escrow_ids_continue_from_legacy_counter() {
+    let (env, _admin, _token, _token_client, token_admin, client) = setup_test_env();
+
+    // Simulate state written by a pre-upgrade version of the contract
+    env.as_contract(&client.address, || {
+        env.storage().instance().set(&DataKey::EscrowCounter, &5u64);
+    });
+    assert_eq!(client.get_escrow_counter(), 5);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 1_000_000, 20000);
+
+    assert_eq!(escrow_id, 6);
+    assert_eq!(client.get_escrow_counter(), 6);
+}
+
+#[test]
+fn test_user_escrows_page() {
+    let (env, _admin, _token, _token_client, token_admin, client) = setup_test_env();
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    for _ in 0..5 {
+        create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 1_000_000, 20000);
+    }
+
+    let first = client.get_user_escrows_page(&depositor, &None, &3);
+    assert_eq!(first.ids.len(), 3);
+    assert_eq!(first.next_cursor, Some(3));
+
+    // A new escrow created between calls lands on the last page
+    create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 1_000_000, 20000);
+
+    let second = client.get_user_escrows_page(&depositor, &first.next_cursor, &3);
+    assert_eq!(second.ids.get(0).unwrap(), 4);
+    assert_eq!(second.ids.len(), 3);
+    assert_eq!(second.next_cursor, None);
+}
+
 #[test]
 #[should_panic]
 fn test_create_escrow_invalid_amount() {
@@ -173,7 +221,7 @@ fn test_create_escrow_invalid_amount() {
 
 #[test]
 fn test_batch_reverse_single_escrow() {
-    let (env, admin, _token, token_client, token_admin, client) = setup_test_env();
+    let (env, admin, _token, _token_client, token_admin, client) = setup_test_env();
 
     let depositor = Address::generate(&env);
     let recipient = Address::generate(&env);
@@ -556,6 +604,70 @@ fn test_batch_reverse_empty_batch() {
     client.batch_reverse_escrows(&admin, &requests);
 }
 
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")]
+fn test_batch_reverse_respects_batch_size_limit() {
+    let (env, admin, _token, _token_client, token_admin, client) = setup_test_env();
+    client.set_limit(&admin, &Limit::BatchSize, &1);
+    assert_eq!(client.get_limit(&Limit::BatchSize), 1);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let mut requests: Vec<ReversalRequest> = Vec::new(&env);
+    for _ in 0..2 {
+        let escrow_id =
+            create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 1_000, 20000);
+        requests.push_back(create_reversal_request(escrow_id));
+    }
+
+    client.batch_reverse_escrows(&admin, &requests);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #25)")]
+fn test_set_limit_outside_hard_bounds() {
+    let (_env, admin, _token, _token_client, _token_admin, client) = setup_test_env();
+    client.set_limit(&admin, &Limit::PageSize, &0);
+}
+
+#[test]
+fn test_initialize_stamps_storage_version() {
+    let (_env, _admin, _token, _token_client, _token_admin, client) = setup_test_env();
+    assert_eq!(client.get_storage_version(), crate::STORAGE_VERSION);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #26)")]
+fn test_outdated_storage_blocks_writes() {
+    let (env, _admin, _token, _token_client, token_admin, client) = setup_test_env();
+    // Simulate storage written before versioning was introduced
+    env.as_contract(&client.address, || common::versioning::set_version(&env, 0));
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 1_000, 20000);
+}
+
+#[test]
+fn test_migrate_restores_storage_version() {
+    let (env, admin, _token, _token_client, token_admin, client) = setup_test_env();
+    env.as_contract(&client.address, || common::versioning::set_version(&env, 0));
+
+    assert!(client.migrate(&admin, &0, &10));
+    assert_eq!(client.get_storage_version(), crate::STORAGE_VERSION);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 1_000, 20000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #27)")]
+fn test_migrate_from_wrong_version() {
+    let (_env, admin, _token, _token_client, _token_admin, client) = setup_test_env();
+    client.migrate(&admin, &0, &10);
+}
+
 #[test]
 #[should_panic]
 fn test_batch_reverse_unauthorized() {
@@ -779,27 +891,1201 @@ fn test_release_escrow_already_reversed() {
 }
 
 // ============================================
-// Admin Tests
+// Arbitration Tests
 // ============================================
 
+/// Registers a bonded arbiter and configures a 2% arbitration fee.
+fn setup_arbiter(
+    env: &Env,
+    admin: &Address,
+    client: &EscrowContractClient,
+    token_admin: &token::StellarAssetClient,
+    bond: i128,
+) -> Address {
+    client.set_arbitration_config(admin, &1_000_000, &200);
+
+    let arbiter = Address::generate(env);
+    token_admin.mint(&arbiter, &bond);
+    client.register_arbiter(&arbiter, &bond);
+    arbiter
+}
+
 #[test]
-fn test_set_admin() {
-    let (env, admin, _token, _token_client, _token_admin, client) = setup_test_env();
+fn test_register_arbiter_locks_bond() {
+    let (env, admin, _token, token_client, token_admin, client) = setup_test_env();
 
-    let new_admin = Address::generate(&env);
-    client.set_admin(&admin, &new_admin);
+    let arbiter = setup_arbiter(&env, &admin, &client, &token_admin, 5_000_000);
 
-    assert_eq!(client.get_admin(), new_admin);
+    let record = client.get_arbiter(&arbiter).unwrap();
+    assert_eq!(record.bond, 5_000_000);
+    assert_eq!(record.open_disputes, 0);
+    assert_eq!(token_client.balance(&arbiter), 0);
+    assert_eq!(token_client.balance(&client.address), 5_000_000);
 }
 
 #[test]
-#[should_panic]
-fn test_set_admin_unauthorized() {
-    let (env, _admin, _token, _token_client, _token_admin, client) = setup_test_env();
+#[should_panic(expected = "Error(Contract, #9)")]
+fn test_register_arbiter_below_min_bond() {
+    let (env, admin, _token, _token_client, token_admin, client) = setup_test_env();
 
-    let unauthorized = Address::generate(&env);
-    let new_admin = Address::generate(&env);
+    setup_arbiter(&env, &admin, &client, &token_admin, 500_000);
+}
 
-    // Should panic due to unauthorized caller
-    client.set_admin(&unauthorized, &new_admin);
+#[test]
+fn test_dispute_resolved_pays_fee_to_arbiter() {
+    let (env, admin, _token, token_client, token_admin, client) = setup_test_env();
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 10_000_000, 20000);
+    let arbiter = setup_arbiter(&env, &admin, &client, &token_admin, 5_000_000);
+
+    client.open_dispute(&recipient, &escrow_id, &arbiter);
+    assert_eq!(client.get_escrow(&escrow_id).unwrap().status, EscrowStatus::Disputed);
+    assert_eq!(client.get_arbiter(&arbiter).unwrap().open_disputes, 1);
+
+    let quote = client.quote_dispute_resolution(&escrow_id);
+    let payout = client.resolve_dispute(&arbiter, &escrow_id, &DisputeRuling::ReleaseToRecipient);
+    assert_eq!((quote.fee, quote.payout), (client.get_dispute(&escrow_id).unwrap().fee, payout));
+
+    // 2% fee on 10_000_000
+    assert_eq!(payout, 9_800_000);
+    assert_eq!(token_client.balance(&recipient), 9_800_000);
+    assert_eq!(token_client.balance(&arbiter), 200_000);
+    assert_eq!(client.get_escrow(&escrow_id).unwrap().status, EscrowStatus::Released);
+
+    let record = client.get_arbiter(&arbiter).unwrap();
+    assert_eq!(record.open_disputes, 0);
+    assert_eq!(record.resolved_disputes, 1);
+    assert_eq!(record.fees_earned, 200_000);
+
+    let dispute = client.get_dispute(&escrow_id).unwrap();
+    assert_eq!(dispute.ruling, DisputeRuling::ReleaseToRecipient);
+    assert_eq!(dispute.fee, 200_000);
+}
+
+#[test]
+fn test_disputed_escrow_blocks_batch_release() {
+    let (env, admin, _token, _token_client, token_admin, client) = setup_test_env();
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 10_000_000, 20000);
+    let arbiter = setup_arbiter(&env, &admin, &client, &token_admin, 5_000_000);
+
+    client.open_dispute(&depositor, &escrow_id, &arbiter);
+
+    let mut requests: Vec<ReversalRequest> = Vec::new(&env);
+    requests.push_back(create_reversal_request(escrow_id));
+    let result = client.batch_reverse_escrows(&admin, &requests);
+
+    assert_eq!(result.successful, 0);
+    assert_eq!(result.failed, 1);
+    match result.results.get(0).unwrap() {
+        ReversalResult::Failure(id, code) => {
+            assert_eq!(id, escrow_id);
+            assert_eq!(code, 5);
+        }
+        _ => panic!("Expected failure"),
+    }
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")]
+fn test_party_cannot_arbitrate_own_escrow() {
+    let (env, admin, _token, _token_client, token_admin, client) = setup_test_env();
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 10_000_000, 20000);
+
+    client.set_arbitration_config(&admin, &1_000_000, &200);
+    token_admin.mint(&recipient, &5_000_000);
+    client.register_arbiter(&recipient, &5_000_000);
+
+    client.open_dispute(&depositor, &escrow_id, &recipient);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_only_assigned_arbiter_can_resolve() {
+    let (env, admin, _token, _token_client, token_admin, client) = setup_test_env();
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 10_000_000, 20000);
+    let arbiter = setup_arbiter(&env, &admin, &client, &token_admin, 5_000_000);
+    let other = Address::generate(&env);
+    token_admin.mint(&other, &5_000_000);
+    client.register_arbiter(&other, &5_000_000);
+
+    client.open_dispute(&depositor, &escrow_id, &arbiter);
+    client.resolve_dispute(&other, &escrow_id, &DisputeRuling::RefundDepositor);
+}
+
+#[test]
+fn test_slash_arbiter_compensates_wronged_party() {
+    let (env, admin, _token, token_client, token_admin, client) = setup_test_env();
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 10_000_000, 20000);
+    let arbiter = setup_arbiter(&env, &admin, &client, &token_admin, 5_000_000);
+
+    client.open_dispute(&depositor, &escrow_id, &arbiter);
+    client.resolve_dispute(&arbiter, &escrow_id, &DisputeRuling::ReleaseToRecipient);
+
+    client.slash_arbiter(&admin, &escrow_id, &4_500_000);
+
+    // Depositor lost the ruling and receives the slashed bond
+    assert_eq!(token_client.balance(&depositor), 4_500_000);
+    let record = client.get_arbiter(&arbiter).unwrap();
+    assert_eq!(record.bond, 500_000);
+    assert_eq!(record.slashed_rulings, 1);
+    assert_eq!(client.get_total_bonds(), 500_000);
+    assert!(client.get_dispute(&escrow_id).unwrap().slashed);
+
+    // Bond is now below the minimum, so the arbiter cannot take new disputes
+    let escrow_id2 =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 1_000_000, 20000);
+    let res = client.try_open_dispute(&depositor, &escrow_id2, &arbiter);
+    assert!(res.is_err());
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #16)")]
+fn test_cannot_slash_ruling_twice() {
+    let (env, admin, _token, _token_client, token_admin, client) = setup_test_env();
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 10_000_000, 20000);
+    let arbiter = setup_arbiter(&env, &admin, &client, &token_admin, 5_000_000);
+
+    client.open_dispute(&depositor, &escrow_id, &arbiter);
+    client.resolve_dispute(&arbiter, &escrow_id, &DisputeRuling::RefundDepositor);
+
+    client.slash_arbiter(&admin, &escrow_id, &1_000_000);
+    client.slash_arbiter(&admin, &escrow_id, &1_000_000);
+}
+
+#[test]
+fn test_withdraw_arbiter_bond() {
+    let (env, admin, _token, token_client, token_admin, client) = setup_test_env();
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 10_000_000, 20000);
+    let arbiter = setup_arbiter(&env, &admin, &client, &token_admin, 5_000_000);
+
+    client.open_dispute(&depositor, &escrow_id, &arbiter);
+
+    // Cannot exit while a dispute is open
+    assert!(client.try_withdraw_arbiter_bond(&arbiter).is_err());
+
+    client.resolve_dispute(&arbiter, &escrow_id, &DisputeRuling::RefundDepositor);
+    env.ledger().with_mut(|li| {
+        li.sequence_number += crate::SLASH_WINDOW_LEDGERS;
+    });
+    let returned = client.withdraw_arbiter_bond(&arbiter);
+
+    assert_eq!(returned, 5_000_000);
+    assert_eq!(token_client.balance(&arbiter), 5_000_000 + 200_000);
+    assert!(client.get_arbiter(&arbiter).is_none());
+}
+
+#[test]
+fn test_bond_stays_slashable_after_ruling() {
+    let (env, admin, _token, token_client, token_admin, client) = setup_test_env();
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 10_000_000, 20000);
+    let arbiter = setup_arbiter(&env, &admin, &client, &token_admin, 5_000_000);
+
+    client.open_dispute(&depositor, &escrow_id, &arbiter);
+    client.resolve_dispute(&arbiter, &escrow_id, &DisputeRuling::ReleaseToRecipient);
+    assert_eq!(
+        client.get_bond_locked_until(&arbiter),
+        env.ledger().sequence() + crate::SLASH_WINDOW_LEDGERS
+    );
+
+    // The bond cannot be pulled before governance has had a chance to slash
+    let res = client.try_withdraw_arbiter_bond(&arbiter);
+    assert_eq!(res, Err(Ok(EscrowError::BondLocked.into())));
+
+    client.slash_arbiter(&admin, &escrow_id, &2_000_000);
+    assert_eq!(token_client.balance(&depositor), 2_000_000);
+    assert_eq!(client.get_arbiter(&arbiter).unwrap().bond, 3_000_000);
+}
+
+// ============================================
+// Yield Escrow Tests
+// ============================================
+
+/// Share-based vault used to exercise yield escrows. Yield is simulated by
+/// minting tokens straight to the vault.
+#[contract]
+struct MockVault;
+
+#[contractimpl]
+impl MockVault {
+    pub fn init(env: Env, token: Address) {
+        env.storage().instance().set(&symbol_short!("token"), &token);
+    }
+
+    pub fn set_paused(env: Env, paused: bool) {
+        env.storage().instance().set(&symbol_short!("paused"), &paused);
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("paused"))
+            .unwrap_or(false)
+    }
+
+    pub fn deposit(env: Env, from: Address, amount: i128) -> i128 {
+        from.require_auth();
+        let token = Self::token(&env);
+        let assets = token.balance(&env.current_contract_address());
+        let supply = Self::supply(&env);
+        let shares = if supply == 0 { amount } else { amount * supply / assets };
+
+        let skip_pull: bool = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("skip_pull"))
+            .unwrap_or(false);
+        if !skip_pull {
+            token.transfer(&from, &env.current_contract_address(), &amount);
+        }
+        env.storage()
+            .instance()
+            .set(&symbol_short!("supply"), &(supply + shares));
+        shares
+    }
+
+    pub fn withdraw(env: Env, to: Address, shares: i128) -> i128 {
+        to.require_auth();
+        let token = Self::token(&env);
+        let assets = token.balance(&env.current_contract_address());
+        let supply = Self::supply(&env);
+        let amount = shares * assets / supply;
+
+        token.transfer(&env.current_contract_address(), &to, &amount);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("supply"), &(supply - shares));
+        let overstated: i128 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("overstate"))
+            .unwrap_or(0);
+        amount + overstated
+    }
+
+    /// Makes `withdraw` report `extra` more than it transferred.
+    pub fn set_overstated(env: Env, extra: i128) {
+        env.storage().instance().set(&symbol_short!("overstate"), &extra);
+    }
+
+    /// Makes `deposit` mint shares without pulling the funds.
+    pub fn set_skip_pull(env: Env, skip: bool) {
+        env.storage().instance().set(&symbol_short!("skip_pull"), &skip);
+    }
+}
+
+impl MockVault {
+    fn token(env: &Env) -> token::Client<'_> {
+        let token: Address = env.storage().instance().get(&symbol_short!("token")).unwrap();
+        token::Client::new(env, &token)
+    }
+
+    fn supply(env: &Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("supply"))
+            .unwrap_or(0)
+    }
+}
+
+/// Deploys a mock vault and whitelists it on the escrow contract.
+fn setup_vault(
+    env: &Env,
+    admin: &Address,
+    token: &Address,
+    client: &EscrowContractClient,
+) -> MockVaultClient<'static> {
+    let vault_id = env.register(MockVault, ());
+    let vault = MockVaultClient::new(env, &vault_id);
+    vault.init(token);
+    client.set_yield_vault(admin, &vault_id);
+    vault
+}
+
+#[test]
+fn test_yield_escrow_release_splits_yield() {
+    let (env, admin, token, token_client, token_admin, client) = setup_test_env();
+    let vault = setup_vault(&env, &admin, &token, &client);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    token_admin.mint(&depositor, &10_000_000);
+    let escrow_id = client.create_yield_escrow(&depositor, &recipient, &10_000_000, &20000, &2_500);
+
+    // Funds are parked in the vault, not held by the escrow contract
+    assert_eq!(token_client.balance(&client.address), 0);
+    assert_eq!(token_client.balance(&vault.address), 10_000_000);
+    let position = client.get_yield_position(&escrow_id).unwrap();
+    assert_eq!(position.shares, 10_000_000);
+    assert!(!position.settled);
+
+    // Vault earns 400_000 in yield
+    token_admin.mint(&vault.address, &400_000);
+
+    client.release_escrow(&depositor, &escrow_id);
+
+    assert_eq!(token_client.balance(&recipient), 10_000_000 + 300_000);
+    assert_eq!(token_client.balance(&depositor), 100_000);
+    assert_eq!(token_client.balance(&client.address), 0);
+    assert!(client.get_yield_position(&escrow_id).unwrap().settled);
+}
+
+#[test]
+fn test_over_reporting_vault_cannot_drain_other_escrows() {
+    let (env, admin, token, token_client, token_admin, client) = setup_test_env();
+    let vault = setup_vault(&env, &admin, &token, &client);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 5_000_000, 20000);
+    token_admin.mint(&depositor, &10_000_000);
+    let escrow_id = client.create_yield_escrow(&depositor, &recipient, &10_000_000, &20000, &0);
+
+    // The vault claims to return the other escrow's funds on top
+    vault.set_overstated(&5_000_000);
+    client.release_escrow(&depositor, &escrow_id);
+
+    assert_eq!(token_client.balance(&recipient), 10_000_000);
+    assert_eq!(token_client.balance(&client.address), 5_000_000);
+}
+
+#[test]
+fn test_yield_escrow_rejects_vault_that_skips_the_deposit() {
+    let (env, admin, token, token_client, token_admin, client) = setup_test_env();
+    let vault = setup_vault(&env, &admin, &token, &client);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    token_admin.mint(&depositor, &10_000_000);
+
+    // The vault mints shares but leaves the principal in the contract
+    vault.set_skip_pull(&true);
+    let result = client.try_create_yield_escrow(&depositor, &recipient, &10_000_000, &20000, &0);
+    assert_eq!(result, Err(Ok(EscrowError::TokenTransferFailed.into())));
+    assert_eq!(token_client.balance(&depositor), 10_000_000);
+    assert_eq!(client.get_locked_balance(), 0);
+}
+
+#[test]
+fn test_yield_escrow_reversal_refunds_principal_and_yield() {
+    let (env, admin, token, token_client, token_admin, client) = setup_test_env();
+    let vault = setup_vault(&env, &admin, &token, &client);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    token_admin.mint(&depositor, &10_000_000);
+    let escrow_id = client.create_yield_escrow(&depositor, &recipient, &10_000_000, &20000, &10_000);
+    token_admin.mint(&vault.address, &1_000_000);
+
+    let mut requests: Vec<ReversalRequest> = Vec::new(&env);
+    requests.push_back(create_reversal_request(escrow_id));
+    let result = client.batch_reverse_escrows(&admin, &requests);
+
+    assert_eq!(result.successful, 1);
+    assert_eq!(token_client.balance(&depositor), 11_000_000);
+    assert_eq!(token_client.balance(&recipient), 0);
+}
+
+#[test]
+fn test_yield_escrow_vault_loss_reduces_principal() {
+    let (env, admin, token, token_client, token_admin, client) = setup_test_env();
+    let vault = setup_vault(&env, &admin, &token, &client);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    token_admin.mint(&depositor, &10_000_000);
+    let escrow_id = client.create_yield_escrow(&depositor, &recipient, &10_000_000, &20000, &5_000);
+
+    // Vault loses 10% of its assets
+    token_client.burn(&vault.address, &1_000_000);
+
+    client.release_escrow(&admin, &escrow_id);
+
+    assert_eq!(token_client.balance(&recipient), 9_000_000);
+    assert_eq!(token_client.balance(&depositor), 0);
+}
+
+#[test]
+fn test_yield_escrow_deferred_while_vault_paused() {
+    let (env, admin, token, token_client, token_admin, client) = setup_test_env();
+    let vault = setup_vault(&env, &admin, &token, &client);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    token_admin.mint(&depositor, &10_000_000);
+    let escrow_id = client.create_yield_escrow(&depositor, &recipient, &10_000_000, &20000, &0);
+    token_admin.mint(&vault.address, &200_000);
+
+    vault.set_paused(&true);
+    client.release_escrow(&depositor, &escrow_id);
+
+    // Escrow is settled but the payout waits for the vault
+    assert_eq!(client.get_escrow(&escrow_id).unwrap().status, EscrowStatus::Released);
+    assert_eq!(token_client.balance(&recipient), 0);
+    let position = client.get_yield_position(&escrow_id).unwrap();
+    assert_eq!(position.deferred_to, Some(recipient.clone()));
+    assert!(client.try_claim_deferred_payout(&escrow_id).is_err());
+
+    vault.set_paused(&false);
+    let paid = client.claim_deferred_payout(&escrow_id);
+
+    assert_eq!(paid, 10_000_000);
+    assert_eq!(token_client.balance(&recipient), 10_200_000);
+    assert!(client.get_yield_position(&escrow_id).unwrap().settled);
+
+    // Nothing left to claim
+    assert!(client.try_claim_deferred_payout(&escrow_id).is_err());
+}
+
+#[test]
+fn test_yield_escrow_dispute_fee_paid_from_principal() {
+    let (env, admin, token, token_client, token_admin, client) = setup_test_env();
+    let vault = setup_vault(&env, &admin, &token, &client);
+    let arbiter = setup_arbiter(&env, &admin, &client, &token_admin, 5_000_000);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    token_admin.mint(&depositor, &10_000_000);
+    let escrow_id = client.create_yield_escrow(&depositor, &recipient, &10_000_000, &20000, &5_000);
+    token_admin.mint(&vault.address, &1_000_000);
+
+    client.open_dispute(&recipient, &escrow_id, &arbiter);
+    let payout = client.resolve_dispute(&arbiter, &escrow_id, &DisputeRuling::RefundDepositor);
+
+    // 2% fee on the principal, yield split evenly regardless of the ruling
+    assert_eq!(payout, 9_800_000);
+    assert_eq!(token_client.balance(&arbiter), 200_000);
+    assert_eq!(token_client.balance(&depositor), 9_800_000 + 500_000);
+    assert_eq!(token_client.balance(&recipient), 500_000);
+}
+
+#[test]
+fn test_deferred_dispute_payout_credits_fee_when_paid() {
+    let (env, admin, token, token_client, token_admin, client) = setup_test_env();
+    let vault = setup_vault(&env, &admin, &token, &client);
+    let arbiter = setup_arbiter(&env, &admin, &client, &token_admin, 5_000_000);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    token_admin.mint(&depositor, &10_000_000);
+    let escrow_id = client.create_yield_escrow(&depositor, &recipient, &10_000_000, &20000, &0);
+
+    client.open_dispute(&recipient, &escrow_id, &arbiter);
+    vault.set_paused(&true);
+    let payout = client.resolve_dispute(&arbiter, &escrow_id, &DisputeRuling::RefundDepositor);
+
+    // Nothing has moved yet, so nothing is reported or earned
+    assert_eq!(payout, 0);
+    assert_eq!(client.get_arbiter(&arbiter).unwrap().fees_earned, 0);
+    assert_eq!(token_client.balance(&arbiter), 0);
+
+    vault.set_paused(&false);
+    assert_eq!(client.claim_deferred_payout(&escrow_id), 9_800_000);
+    assert_eq!(token_client.balance(&arbiter), 200_000);
+    assert_eq!(client.get_arbiter(&arbiter).unwrap().fees_earned, 200_000);
+}
+
+#[test]
+fn test_yield_split_rounds_depositor_share_down() {
+    let (env, admin, token, token_client, token_admin, client) = setup_test_env();
+    let vault = setup_vault(&env, &admin, &token, &client);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    token_admin.mint(&depositor, &10_000_000);
+    let escrow_id = client.create_yield_escrow(&depositor, &recipient, &10_000_000, &20000, &3_333);
+    token_admin.mint(&vault.address, &7);
+
+    client.release_escrow(&depositor, &escrow_id);
+
+    // 7 * 33.33% = 2.33, the depositor gets 2 and the recipient the remainder
+    assert_eq!(token_client.balance(&depositor), 2);
+    assert_eq!(token_client.balance(&recipient), 10_000_000 + 5);
+    assert_eq!(token_client.balance(&client.address), 0);
+}
+
+#[test]
+fn test_yield_dust_swept_to_collector() {
+    let (env, admin, token, token_client, token_admin, client) = setup_test_env();
+    let vault = setup_vault(&env, &admin, &token, &client);
+    let collector = Address::generate(&env);
+    client.set_dust_policy(&admin, &100, &collector);
+    assert_eq!(client.get_dust_policy().unwrap().threshold, 100);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    token_admin.mint(&depositor, &10_000_000);
+    let escrow_id = client.create_yield_escrow(&depositor, &recipient, &10_000_000, &20000, &1_000);
+    token_admin.mint(&vault.address, &500);
+
+    client.release_escrow(&depositor, &escrow_id);
+
+    // The depositor's 50 is dust; the recipient's 450 is paid normally
+    assert_eq!(token_client.balance(&depositor), 0);
+    assert_eq!(token_client.balance(&collector), 50);
+    assert_eq!(token_client.balance(&recipient), 10_000_000 + 450);
+}
+
+#[test]
+fn test_arbitration_fee_rounds_up() {
+    let (env, admin, _token, token_client, token_admin, client) = setup_test_env();
+    let arbiter = setup_arbiter(&env, &admin, &client, &token_admin, 5_000_000);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 1_001, 20000);
+
+    client.open_dispute(&recipient, &escrow_id, &arbiter);
+    let payout = client.resolve_dispute(&arbiter, &escrow_id, &DisputeRuling::ReleaseToRecipient);
+
+    // 2% of 1_001 is 20.02, charged as 21
+    assert_eq!(payout, 980);
+    assert_eq!(token_client.balance(&arbiter), 21);
+    assert_eq!(token_client.balance(&recipient), 980);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #18)")]
+fn test_yield_escrow_requires_vault() {
+    let (env, _admin, _token, _token_client, token_admin, client) = setup_test_env();
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    token_admin.mint(&depositor, &10_000_000);
+    client.create_yield_escrow(&depositor, &recipient, &10_000_000, &20000, &5_000);
+}
+
+// ============================================
+// Archival Tests
+// ============================================
+
+#[test]
+fn test_archive_closed_escrows() {
+    let (env, admin, _token, _token_client, token_admin, client) = setup_test_env();
+    client.set_archive_age(&admin, &100);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let released =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 1_000_000, 20000);
+    let active =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 2_000_000, 20000);
+    client.release_escrow(&depositor, &released);
+    let full_record = client.get_escrow(&released).unwrap();
+
+    let mut ids: Vec<u64> = Vec::new(&env);
+    ids.push_back(released);
+    ids.push_back(active);
+    ids.push_back(999);
+
+    // Too young to archive
+    assert_eq!(client.archive_escrows(&ids).len(), 0);
+
+    env.ledger().with_mut(|li| {
+        li.sequence_number += 100;
+    });
+    let archived = client.archive_escrows(&ids);
+
+    assert_eq!(archived.len(), 1);
+    assert_eq!(archived.get(0).unwrap(), released);
+    assert!(client.get_escrow(&released).is_none());
+    assert!(client.get_escrow(&active).is_some());
+
+    let record = client.get_archived_escrow(&released).unwrap();
+    assert_eq!(record.amount, 1_000_000);
+    assert_eq!(record.status, EscrowStatus::Released);
+    assert_eq!(record.recipient, recipient);
+
+    // The archive still proves what the full record was
+    assert!(client.verify_archived_escrow(&full_record));
+    let mut tampered = full_record.clone();
+    tampered.amount = 1;
+    assert!(!client.verify_archived_escrow(&tampered));
+
+    // The storage report moves the escrow from the live family to the archive
+    assert_eq!(client.get_total_escrows_archived(), 1);
+    let report = client.get_storage_report();
+    let (live, archived) = (report.get(0).unwrap(), report.get(1).unwrap());
+    assert_eq!((live.entries, archived.entries), (1, 1));
+    assert!(live.approx_bytes > archived.approx_bytes);
+}
+
+#[test]
+fn test_archive_age_counts_from_close() {
+    let (env, admin, _token, _token_client, token_admin, client) = setup_test_env();
+    client.set_archive_age(&admin, &100);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 1_000_000, 20000);
+    let mut ids: Vec<u64> = Vec::new(&env);
+    ids.push_back(escrow_id);
+
+    // An escrow that sat open past the archive age is not archivable on close
+    env.ledger().with_mut(|li| {
+        li.sequence_number += 500;
+    });
+    client.release_escrow(&depositor, &escrow_id);
+    assert_eq!(client.archive_escrows(&ids).len(), 0);
+
+    env.ledger().with_mut(|li| {
+        li.sequence_number += 99;
+    });
+    assert_eq!(client.archive_escrows(&ids).len(), 0);
+
+    env.ledger().with_mut(|li| {
+        li.sequence_number += 1;
+    });
+    assert_eq!(client.archive_escrows(&ids).len(), 1);
+}
+
+#[test]
+fn test_slash_after_archive() {
+    let (env, admin, _token, token_client, token_admin, client) = setup_test_env();
+    client.set_archive_age(&admin, &0);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 10_000_000, 20000);
+    let arbiter = setup_arbiter(&env, &admin, &client, &token_admin, 5_000_000);
+
+    client.open_dispute(&depositor, &escrow_id, &arbiter);
+    client.resolve_dispute(&arbiter, &escrow_id, &DisputeRuling::ReleaseToRecipient);
+
+    let mut ids: Vec<u64> = Vec::new(&env);
+    ids.push_back(escrow_id);
+    assert_eq!(client.archive_escrows(&ids).len(), 1);
+
+    client.slash_arbiter(&admin, &escrow_id, &1_000_000);
+    assert_eq!(token_client.balance(&depositor), 1_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #22)")]
+fn test_archive_requires_configured_age() {
+    let (env, _admin, _token, _token_client, _token_admin, client) = setup_test_env();
+
+    let mut ids: Vec<u64> = Vec::new(&env);
+    ids.push_back(1);
+    client.archive_escrows(&ids);
+}
+
+// ============================================
+// Reentrancy Tests
+// ============================================
+
+/// Token that calls back into the escrow contract from `transfer` once armed.
+#[contract]
+struct ReentrantToken;
+
+#[contractimpl]
+impl ReentrantToken {
+    pub fn mint(env: Env, to: Address, amount: i128) {
+        let balance = Self::balance(env.clone(), to.clone());
+        env.storage().persistent().set(&to, &(balance + amount));
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage().persistent().get(&id).unwrap_or(0)
+    }
+
+    pub fn arm(env: Env, escrow: Address, escrow_id: u64) {
+        env.storage().instance().set(&symbol_short!("escrow"), &escrow);
+        env.storage().instance().set(&symbol_short!("id"), &escrow_id);
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        let from_balance = Self::balance(env.clone(), from.clone());
+        env.storage().persistent().set(&from, &(from_balance - amount));
+        Self::mint(env.clone(), to, amount);
+
+        let escrow: Option<Address> = env.storage().instance().get(&symbol_short!("escrow"));
+        if let Some(escrow) = escrow {
+            let escrow_id: u64 = env.storage().instance().get(&symbol_short!("id")).unwrap();
+            EscrowContractClient::new(&env, &escrow).release_escrow(&from, &escrow_id);
+        }
+    }
+}
+
+#[test]
+fn test_malicious_token_cannot_reenter() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let token_id = env.register(ReentrantToken, ());
+    let token = ReentrantTokenClient::new(&env, &token_id);
+    let contract_id = env.register(EscrowContract, ());
+    let client = EscrowContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &token_id);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    token.mint(&depositor, &1_000_000);
+    let escrow_id = client.create_escrow(&depositor, &recipient, &1_000_000, &20000);
+
+    // Paying out the escrow hands control to the token, which tries to release again
+    token.arm(&contract_id, &escrow_id);
+    assert!(client.try_release_escrow(&admin, &escrow_id).is_err());
+
+    // The failed release was rolled back entirely
+    assert_eq!(client.get_escrow(&escrow_id).unwrap().status, EscrowStatus::Active);
+    assert_eq!(client.get_total_escrows_released(), 0);
+}
+
+// ============================================
+// Token Rescue Tests
+// ============================================
+
+/// Advances the ledger past the rescue timelock.
+fn pass_rescue_delay(env: &Env) {
+    env.ledger().with_mut(|li| {
+        li.sequence_number += crate::RESCUE_DELAY_LEDGERS;
+    });
+}
+
+#[test]
+fn test_locked_balance_tracks_escrows_and_bonds() {
+    let (env, admin, _token, _token_client, token_admin, client) = setup_test_env();
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 10_000_000, 20000);
+    assert_eq!(client.get_locked_balance(), 10_000_000);
+
+    let arbiter = setup_arbiter(&env, &admin, &client, &token_admin, 5_000_000);
+    assert_eq!(client.get_locked_balance(), 15_000_000);
+    assert_eq!(client.get_total_bonds(), 5_000_000);
+
+    client.release_escrow(&admin, &escrow_id);
+    assert_eq!(client.get_locked_balance(), 5_000_000);
+
+    client.withdraw_arbiter_bond(&arbiter);
+    assert_eq!(client.get_locked_balance(), 0);
+    assert_eq!(client.get_total_bonds(), 0);
+}
+
+#[test]
+fn test_rescue_stuck_tokens_after_timelock() {
+    let (env, admin, _token, token_client, token_admin, client) = setup_test_env();
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 10_000_000, 20000);
+
+    // Tokens sent straight to the contract, outside any escrow
+    token_admin.mint(&client.address, &750_000);
+
+    let treasury = Address::generate(&env);
+    client.request_rescue(&admin, &token_client.address, &750_000, &treasury);
+    assert!(client.get_pending_rescue().is_some());
+
+    pass_rescue_delay(&env);
+    client.rescue_tokens(&admin, &token_client.address, &750_000, &treasury);
+
+    assert_eq!(token_client.balance(&treasury), 750_000);
+    assert_eq!(token_client.balance(&client.address), 10_000_000);
+    assert_eq!(client.get_locked_balance(), 10_000_000);
+    assert!(client.get_pending_rescue().is_none());
+}
+
+#[test]
+fn test_rescue_foreign_token() {
+    let (env, admin, _token, _token_client, _token_admin, client) = setup_test_env();
+
+    let other = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let other_client = token::Client::new(&env, &other.address());
+    token::StellarAssetClient::new(&env, &other.address()).mint(&client.address, &1_000);
+
+    let treasury = Address::generate(&env);
+    client.request_rescue(&admin, &other.address(), &1_000, &treasury);
+    pass_rescue_delay(&env);
+    client.rescue_tokens(&admin, &other.address(), &1_000, &treasury);
+
+    assert_eq!(other_client.balance(&treasury), 1_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #29)")]
+fn test_rescue_before_timelock_expires() {
+    let (env, admin, _token, token_client, token_admin, client) = setup_test_env();
+    token_admin.mint(&client.address, &750_000);
+
+    let treasury = Address::generate(&env);
+    client.request_rescue(&admin, &token_client.address, &750_000, &treasury);
+    client.rescue_tokens(&admin, &token_client.address, &750_000, &treasury);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #30)")]
+fn test_rescue_cannot_touch_escrowed_funds() {
+    let (env, admin, _token, token_client, token_admin, client) = setup_test_env();
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 10_000_000, 20000);
+    token_admin.mint(&client.address, &750_000);
+
+    let treasury = Address::generate(&env);
+    client.request_rescue(&admin, &token_client.address, &750_001, &treasury);
+    pass_rescue_delay(&env);
+    client.rescue_tokens(&admin, &token_client.address, &750_001, &treasury);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #28)")]
+fn test_rescue_must_match_pending_request() {
+    let (env, admin, _token, token_client, token_admin, client) = setup_test_env();
+    token_admin.mint(&client.address, &750_000);
+
+    let treasury = Address::generate(&env);
+    client.request_rescue(&admin, &token_client.address, &750_000, &treasury);
+    pass_rescue_delay(&env);
+    client.rescue_tokens(&admin, &token_client.address, &750_000, &Address::generate(&env));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #28)")]
+fn test_cancelled_rescue_cannot_execute() {
+    let (env, admin, _token, token_client, token_admin, client) = setup_test_env();
+    token_admin.mint(&client.address, &750_000);
+
+    let treasury = Address::generate(&env);
+    client.request_rescue(&admin, &token_client.address, &750_000, &treasury);
+    client.cancel_rescue(&admin);
+    pass_rescue_delay(&env);
+    client.rescue_tokens(&admin, &token_client.address, &750_000, &treasury);
+}
+
+#[test]
+fn test_migration_rebuilds_locked_balance_in_chunks() {
+    let (env, admin, _token, token_client, token_admin, client) = setup_test_env();
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let open =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 1_000_000, 20000);
+    let released =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 2_000_000, 20000);
+    client.release_escrow(&depositor, &released);
+    let arbiter = setup_arbiter(&env, &admin, &client, &token_admin, 5_000_000);
+    client.open_dispute(&depositor, &open, &arbiter);
+
+    // Storage from before the locked balance and bond total were tracked, plus
+    // tokens sent straight to the contract back then
+    token_admin.mint(&client.address, &750_000);
+    env.as_contract(&client.address, || {
+        common::versioning::set_version(&env, 1);
+        env.storage().instance().remove(&DataKey::LockedBalance);
+        env.storage().instance().remove(&DataKey::TotalBonds);
+    });
+
+    // One escrow per call: the first call leaves the version untouched
+    assert!(!client.migrate(&admin, &1, &1));
+    assert_eq!(client.get_storage_version(), 1);
+    assert!(client.migrate(&admin, &1, &1));
+    assert_eq!(client.get_storage_version(), 2);
+
+    // Everything held beyond the open escrow is kept locked as bonds
+    assert_eq!(client.get_locked_balance(), 6_750_000);
+    assert_eq!(client.get_total_bonds(), 5_750_000);
+    let treasury = Address::generate(&env);
+    client.request_rescue(&admin, &token_client.address, &750_000, &treasury);
+    pass_rescue_delay(&env);
+    let res = client.try_rescue_tokens(&admin, &token_client.address, &750_000, &treasury);
+    assert_eq!(res, Err(Ok(EscrowError::RescueExceedsFree.into())));
+
+    // Tokens arriving after the migration are rescuable
+    token_admin.mint(&client.address, &300_000);
+    client.request_rescue(&admin, &token_client.address, &300_000, &treasury);
+    pass_rescue_delay(&env);
+    client.rescue_tokens(&admin, &token_client.address, &300_000, &treasury);
+    assert_eq!(token_client.balance(&treasury), 300_000);
+}
+
+// ============================================
+// Admin Tests
+// ============================================
+
+#[test]
+fn test_set_admin() {
+    let (env, admin, _token, _token_client, _token_admin, client) = setup_test_env();
+
+    let new_admin = Address::generate(&env);
+    client.set_admin(&admin, &new_admin);
+
+    assert_eq!(client.get_admin(), new_admin);
+}
+
+#[test]
+#[should_panic]
+fn test_set_admin_unauthorized() {
+    let (env, _admin, _token, _token_client, _token_admin, client) = setup_test_env();
+
+    let unauthorized = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+
+    // Should panic due to unauthorized caller
+    client.set_admin(&unauthorized, &new_admin);
+}
+
+// ============================================
+// Merchant Tier Tests
+// ============================================
+
+const DAY: u64 = 86_400;
+
+#[test]
+fn test_untiered_merchant_settles_immediately() {
+    let (env, admin, _token, token_client, token_admin, client) = setup_test_env();
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    assert_eq!(client.get_merchant_tier(&recipient), MerchantTier::Standard);
+
+    let escrow_id =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 1_000, 20000);
+    client.release_escrow(&admin, &escrow_id);
+    assert_eq!(token_client.balance(&recipient), 1_000);
+}
+
+#[test]
+fn test_high_risk_merchant_settles_after_delay() {
+    let (env, admin, _token, token_client, token_admin, client) = setup_test_env();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let depositor = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    client.set_merchant_tier(&admin, &merchant, &MerchantTier::HighRisk);
+
+    let escrow_id =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &merchant, 1_000, 20000);
+    let terms = client.get_settlement_terms(&escrow_id).unwrap();
+    assert_eq!(terms.tier, MerchantTier::HighRisk);
+    assert_eq!(terms.settle_after, 1_000 + 3 * DAY);
+    assert_eq!(terms.dispute_until, 1_000 + 30 * DAY);
+
+    let result = client.try_release_escrow(&admin, &escrow_id);
+    assert_eq!(result, Err(Ok(EscrowError::SettlementPending.into())));
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000 + 3 * DAY);
+    client.release_escrow(&admin, &escrow_id);
+    assert_eq!(token_client.balance(&merchant), 1_000);
+}
+
+#[test]
+fn test_batch_release_skips_unsettled_escrows() {
+    let (env, admin, _token, _token_client, token_admin, client) = setup_test_env();
+
+    let depositor = Address::generate(&env);
+    let risky = Address::generate(&env);
+    let trusted = Address::generate(&env);
+    client.set_merchant_tier(&admin, &risky, &MerchantTier::HighRisk);
+    client.set_merchant_tier(&admin, &trusted, &MerchantTier::Trusted);
+
+    let held = create_test_escrow(&env, &client, &token_admin, &depositor, &risky, 1_000, 20000);
+    let instant =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &trusted, 2_000, 20000);
+
+    let mut requests: Vec<ReleaseRequest> = Vec::new(&env);
+    requests.push_back(ReleaseRequest { escrow_id: held });
+    requests.push_back(ReleaseRequest { escrow_id: instant });
+    let result = client.batch_release_escrows(&admin, &requests);
+
+    assert_eq!(result.successful, 1);
+    assert_eq!(result.total_released, 2_000);
+    match result.results.get(0).unwrap() {
+        ReleaseResult::Failure(id, code) => {
+            assert_eq!(id, held);
+            assert_eq!(code, 6);
+        }
+        _ => panic!("Expected failure"),
+    }
+}
+
+#[test]
+fn test_dispute_window_closes() {
+    let (env, admin, _token, _token_client, token_admin, client) = setup_test_env();
+
+    let depositor = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    client.set_merchant_tier(&admin, &merchant, &MerchantTier::Trusted);
+    let escrow_id =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &merchant, 1_000, 20000);
+    let arbiter = setup_arbiter(&env, &admin, &client, &token_admin, 5_000_000);
+
+    env.ledger().with_mut(|li| li.timestamp += 7 * DAY + 1);
+    let result = client.try_open_dispute(&depositor, &escrow_id, &arbiter);
+    assert_eq!(result, Err(Ok(EscrowError::DisputeWindowClosed.into())));
+}
+
+#[test]
+fn test_tier_changes_do_not_affect_existing_escrows() {
+    let (env, admin, _token, _token_client, token_admin, client) = setup_test_env();
+
+    let depositor = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let escrow_id =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &merchant, 1_000, 20000);
+
+    let policy = TierPolicy {
+        settlement_delay: 2 * DAY,
+        dispute_window: 10 * DAY,
+    };
+    client.set_tier_policy(&admin, &MerchantTier::HighRisk, &policy);
+    assert_eq!(client.get_tier_policy(&MerchantTier::HighRisk), policy);
+    client.set_merchant_tier(&admin, &merchant, &MerchantTier::HighRisk);
+
+    // The first escrow keeps the Standard terms it was created with
+    client.release_escrow(&admin, &escrow_id);
+
+    let later =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &merchant, 1_000, 20000);
+    let terms = client.get_settlement_terms(&later).unwrap();
+    assert_eq!(terms.settle_after, env.ledger().timestamp() + 2 * DAY);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #33)")]
+fn test_tier_policy_bounds() {
+    let (_env, admin, _token, _token_client, _token_admin, client) = setup_test_env();
+
+    let policy = TierPolicy {
+        settlement_delay: 31 * DAY,
+        dispute_window: 30 * DAY,
+    };
+    client.set_tier_policy(&admin, &MerchantTier::HighRisk, &policy);
+}
+
+// ============================================
+// Dispute Hook Tests
+// ============================================
+
+/// Hook counting the holds placed on each payer/payee pair, or rejecting
+/// every call when deployed broken.
+#[contract]
+struct MockHook;
+
+#[contractimpl]
+impl MockHook {
+    pub fn __constructor(env: Env, broken: bool) {
+        env.storage().instance().set(&symbol_short!("broken"), &broken);
+    }
+
+    pub fn hold_payments(env: Env, source: Address, payer: Address, payee: Address) {
+        Self::accept(&env, &source);
+        let holds = Self::holds(env.clone(), payer.clone(), payee.clone());
+        env.storage().instance().set(&(payer, payee), &(holds + 1));
+    }
+
+    pub fn release_payments(env: Env, source: Address, payer: Address, payee: Address) {
+        Self::accept(&env, &source);
+        let holds = Self::holds(env.clone(), payer.clone(), payee.clone());
+        env.storage().instance().set(&(payer, payee), &(holds - 1));
+    }
+
+    pub fn holds(env: Env, payer: Address, payee: Address) -> u32 {
+        env.storage().instance().get(&(payer, payee)).unwrap_or(0)
+    }
+
+    fn accept(env: &Env, source: &Address) {
+        source.require_auth();
+        if env.storage().instance().get(&symbol_short!("broken")).unwrap_or(false) {
+            panic!("hook unavailable");
+        }
+    }
+}
+
+#[test]
+fn test_dispute_holds_payments_until_resolved() {
+    let (env, admin, _token, _token_client, token_admin, client) = setup_test_env();
+
+    let hook = MockHookClient::new(&env, &env.register(MockHook, (false,)));
+    let mut hooks: Vec<Address> = Vec::new(&env);
+    hooks.push_back(hook.address.clone());
+    client.set_dispute_hooks(&admin, &hooks);
+    assert_eq!(client.get_dispute_hooks(), hooks);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 10_000_000, 20000);
+    let arbiter = setup_arbiter(&env, &admin, &client, &token_admin, 5_000_000);
+
+    client.open_dispute(&depositor, &escrow_id, &arbiter);
+    assert_eq!(hook.holds(&depositor, &recipient), 1);
+    // The disputed amount stays frozen in escrow
+    let result = client.try_release_escrow(&admin, &escrow_id);
+    assert!(result.is_err());
+
+    client.resolve_dispute(&arbiter, &escrow_id, &DisputeRuling::RefundDepositor);
+    assert_eq!(hook.holds(&depositor, &recipient), 0);
+}
+
+#[test]
+fn test_failing_hook_does_not_block_dispute() {
+    let (env, admin, _token, _token_client, token_admin, client) = setup_test_env();
+
+    let broken = env.register(MockHook, (true,));
+    let hook = MockHookClient::new(&env, &env.register(MockHook, (false,)));
+    let mut hooks: Vec<Address> = Vec::new(&env);
+    hooks.push_back(broken);
+    hooks.push_back(hook.address.clone());
+    client.set_dispute_hooks(&admin, &hooks);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id =
+        create_test_escrow(&env, &client, &token_admin, &depositor, &recipient, 10_000_000, 20000);
+    let arbiter = setup_arbiter(&env, &admin, &client, &token_admin, 5_000_000);
+
+    client.open_dispute(&depositor, &escrow_id, &arbiter);
+    assert_eq!(client.get_escrow(&escrow_id).unwrap().status, EscrowStatus::Disputed);
+    // Hooks after the broken one are still notified
+    assert_eq!(hook.holds(&depositor, &recipient), 1);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #34)")]
+fn test_dispute_hooks_are_bounded() {
+    let (env, admin, _token, _token_client, _token_admin, client) = setup_test_env();
+
+    let mut hooks: Vec<Address> = Vec::new(&env);
+    for _ in 0..6 {
+        hooks.push_back(Address::generate(&env));
+    }
+    client.set_dispute_hooks(&admin, &hooks);
 }
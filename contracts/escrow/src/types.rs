@@ -1,9 +1,84 @@
 //! Data types and events for the escrow contract.
 
-use soroban_sdk::{contracttype, symbol_short, Address, Env, Vec};
+use common::events;
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Vec};
 
-/// Maximum number of escrows in a single batch operation.
-pub const MAX_BATCH_SIZE: u32 = 100;
+/// Maximum arbitration fee (10%) in basis points.
+pub const MAX_ARBITRATION_FEE_BPS: u32 = 1_000;
+
+/// Ledgers a token rescue must wait between request and execution (about a
+/// day at 5 second ledgers).
+pub const RESCUE_DELAY_LEDGERS: u32 = 17_280;
+
+/// Ledgers an arbiter's bond stays locked after each ruling so governance
+/// can slash a wrong ruling (about a week at 5 second ledgers).
+pub const SLASH_WINDOW_LEDGERS: u32 = 120_960;
+
+/// Maximum number of contracts notified when a dispute opens or resolves.
+pub const MAX_DISPUTE_HOOKS: u32 = 5;
+
+/// Longest settlement delay a merchant tier may impose (30 days).
+pub const MAX_SETTLEMENT_DELAY_SECS: u64 = 30 * 86_400;
+
+/// Longest dispute window a merchant tier may grant (180 days).
+pub const MAX_DISPUTE_WINDOW_SECS: u64 = 180 * 86_400;
+
+/// Risk tier of a merchant (escrow recipient).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum MerchantTier {
+    /// Vetted merchants; funds settle as soon as they are released
+    Trusted,
+    /// Merchants with no tier assigned; by default they keep the untiered
+    /// behaviour of immediate settlement and the longest dispute window
+    Standard,
+    /// Merchants with elevated chargeback risk
+    HighRisk,
+}
+
+impl MerchantTier {
+    /// Policy used when the admin has not configured the tier.
+    pub const fn default_policy(self) -> TierPolicy {
+        match self {
+            MerchantTier::Trusted => TierPolicy {
+                settlement_delay: 0,
+                dispute_window: 7 * 86_400,
+            },
+            MerchantTier::Standard => TierPolicy {
+                settlement_delay: 0,
+                dispute_window: MAX_DISPUTE_WINDOW_SECS,
+            },
+            MerchantTier::HighRisk => TierPolicy {
+                settlement_delay: 3 * 86_400,
+                dispute_window: 30 * 86_400,
+            },
+        }
+    }
+}
+
+/// Settlement delay and dispute window applied to a merchant tier, in seconds
+/// from escrow creation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct TierPolicy {
+    /// Time before the escrow may be released to the merchant
+    pub settlement_delay: u64,
+    /// Time during which either party may open a dispute
+    pub dispute_window: u64,
+}
+
+/// Settlement terms fixed for an escrow when it is created, so later tier
+/// changes never alter an existing escrow.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct SettlementTerms {
+    /// Tier of the recipient at creation
+    pub tier: MerchantTier,
+    /// Ledger timestamp from which the escrow may be released
+    pub settle_after: u64,
+    /// Last ledger timestamp at which a dispute may be opened
+    pub dispute_until: u64,
+}
 
 /// Escrow status enum.
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -15,6 +90,8 @@ pub enum EscrowStatus {
     Released,
     /// Funds returned to depositor (reversed)
     Reversed,
+    /// Funds frozen pending an arbiter's ruling
+    Disputed,
 }
 
 /// An escrow record.
@@ -31,6 +108,127 @@ pub struct Escrow {
     pub deadline: u64,
 }
 
+/// A registered third-party arbiter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Arbiter {
+    pub arbiter: Address,
+    /// Bond currently held by the contract on behalf of the arbiter
+    pub bond: i128,
+    /// Number of disputes assigned but not yet ruled on
+    pub open_disputes: u32,
+    /// Number of disputes ruled on
+    pub resolved_disputes: u32,
+    /// Number of rulings that were slashed by governance
+    pub slashed_rulings: u32,
+    /// Total fees earned from resolved disputes
+    pub fees_earned: i128,
+}
+
+/// Outcome of an arbitrated dispute.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum DisputeRuling {
+    /// Awaiting the arbiter's decision
+    Pending,
+    /// Funds (minus fee) released to the recipient
+    ReleaseToRecipient,
+    /// Funds (minus fee) returned to the depositor
+    RefundDepositor,
+}
+
+/// A dispute raised against an active escrow.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Dispute {
+    pub escrow_id: u64,
+    pub arbiter: Address,
+    pub opened_by: Address,
+    pub opened_at: u64,
+    pub ruling: DisputeRuling,
+    pub resolved_at: u64,
+    /// Fee paid to the arbiter out of the escrowed amount
+    pub fee: i128,
+    /// Whether the ruling has been slashed by governance
+    pub slashed: bool,
+}
+
+/// Split of an escrow's amount between the arbiter's fee and the favoured
+/// party, quoted before a dispute is resolved.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct ArbitrationQuote {
+    pub escrow_id: u64,
+    /// Escrowed amount the fee is taken from
+    pub amount: i128,
+    /// Fee the arbiter would be paid
+    pub fee: i128,
+    /// Amount the favoured party would receive
+    pub payout: i128,
+}
+
+/// An escrow's position in the yield vault.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct YieldPosition {
+    pub escrow_id: u64,
+    pub vault: Address,
+    /// Vault shares held for the escrow
+    pub shares: i128,
+    /// Principal deposited into the vault
+    pub principal: i128,
+    /// Share of the accrued yield paid to the depositor (buyer); the rest goes
+    /// to the recipient (seller)
+    pub depositor_yield_bps: u32,
+    /// Party the principal is owed to when payout was deferred by a paused vault
+    pub deferred_to: Option<Address>,
+    /// Fee deducted from the principal when payout was deferred
+    pub deferred_fee: i128,
+    /// Address the deferred fee is owed to
+    pub deferred_fee_to: Option<Address>,
+    /// Whether the position has been redeemed and paid out
+    pub settled: bool,
+}
+
+/// A token rescue requested by the admin, waiting out its timelock.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct PendingRescue {
+    pub token: Address,
+    pub amount: i128,
+    pub to: Address,
+    /// Ledger sequence from which the rescue may be executed
+    pub unlock_ledger: u32,
+}
+
+/// Progress of a locked balance rebuild, which walks the escrow ids in chunks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct LockedRebuild {
+    /// Next escrow id to inspect
+    pub next_id: u64,
+    /// Principal of the open escrows inspected so far that the contract holds
+    pub open_principal: i128,
+}
+
+/// Compact record kept for a closed escrow once the full record is archived.
+///
+/// The hash commits to the XDR of the full escrow record, so an auditor holding
+/// the original can prove it matches.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct ArchivedEscrow {
+    pub escrow_id: u64,
+    /// SHA-256 of the XDR-encoded escrow record
+    pub record_hash: BytesN<32>,
+    pub depositor: Address,
+    pub recipient: Address,
+    pub amount: i128,
+    pub status: EscrowStatus,
+    /// Ledger sequence the record was archived at
+    pub archived_at: u64,
+}
+
 /// Request to reverse an escrow.
 #[derive(Clone, Debug)]
 #[contracttype]
@@ -101,7 +299,7 @@ pub enum DataKey {
     Escrow(u64),
     /// List of escrow IDs for a user (depositor)
     UserEscrows(Address),
-    /// Counter for escrow IDs
+    /// Legacy counter for escrow IDs, migrated to the shared id counters on first use
     EscrowCounter,
     /// Total number of reversal batches processed
     TotalReversalBatches,
@@ -115,6 +313,46 @@ pub enum DataKey {
     TotalEscrowsReleased,
     /// Total amount released
     TotalAmountReleased,
+    /// Minimum bond an arbiter must hold to take disputes
+    MinArbiterBond,
+    /// Arbitration fee in basis points of the escrowed amount
+    ArbitrationFeeBps,
+    /// Registered arbiter by address
+    Arbiter(Address),
+    /// Ledger until which an arbiter's bond stays slashable
+    BondLockedUntil(Address),
+    /// Dispute by escrow ID
+    Dispute(u64),
+    /// Whitelisted yield vault
+    YieldVault,
+    /// Yield vault position by escrow ID
+    YieldPosition(u64),
+    /// Minimum age in ledgers before a closed escrow can be archived
+    ArchiveAge,
+    /// Archived escrow by ID
+    ArchivedEscrow(u64),
+    /// Escrow token balance the contract owes to escrows and arbiters
+    LockedBalance,
+    /// Token rescue waiting out its timelock
+    PendingRescue,
+    /// Risk tier assigned to a merchant
+    MerchantTier(Address),
+    /// Configured policy for a merchant tier
+    TierPolicy(MerchantTier),
+    /// Settlement terms by escrow ID
+    SettlementTerms(u64),
+    /// Contracts that hold payments while a dispute is open
+    DisputeHooks,
+    /// Total number of escrows archived
+    TotalEscrowsArchived,
+    /// ID of the most recently archived escrow
+    LastArchivedEscrow,
+    /// Ledger sequence an escrow was released or refunded at
+    ClosedAt(u64),
+    /// Total bond held for all registered arbiters
+    TotalBonds,
+    /// Progress of the locked balance rebuild during a migration
+    LockedRebuild,
 }
 
 /// Event emitters for escrow operations.
@@ -130,14 +368,17 @@ impl EscrowEvents {
         amount: i128,
     ) {
         let topics = (symbol_short!("escrow"), symbol_short!("created"));
-        env.events()
-            .publish(topics, (escrow_id, depositor.clone(), recipient.clone(), amount));
+        events::publish(
+            env,
+            topics,
+            (escrow_id, depositor.clone(), recipient.clone(), amount),
+        );
     }
 
     /// Emitted when a batch reversal starts.
     pub fn batch_reversal_started(env: &Env, batch_id: u64, request_count: u32) {
         let topics = (symbol_short!("escrow"), symbol_short!("rev_start"));
-        env.events().publish(topics, (batch_id, request_count));
+        events::publish(env, topics, (batch_id, request_count));
     }
 
     /// Emitted when a single escrow is successfully reversed.
@@ -149,14 +390,13 @@ impl EscrowEvents {
         amount: i128,
     ) {
         let topics = (symbol_short!("escrow"), symbol_short!("rev_ok"), batch_id);
-        env.events()
-            .publish(topics, (escrow_id, depositor.clone(), amount));
+        events::publish(env, topics, (escrow_id, depositor.clone(), amount));
     }
 
     /// Emitted when a single escrow reversal fails.
     pub fn reversal_failure(env: &Env, batch_id: u64, escrow_id: u64, error_code: u32) {
         let topics = (symbol_short!("escrow"), symbol_short!("rev_fail"), batch_id);
-        env.events().publish(topics, (escrow_id, error_code));
+        events::publish(env, topics, (escrow_id, error_code));
     }
 
     /// Emitted when a batch reversal completes.
@@ -168,19 +408,19 @@ impl EscrowEvents {
         total_reversed: i128,
     ) {
         let topics = (symbol_short!("escrow"), symbol_short!("rev_done"), batch_id);
-        env.events().publish(topics, (successful, failed, total_reversed));
+        events::publish(env, topics, (successful, failed, total_reversed));
     }
 
     /// Emitted when an escrow is released to recipient.
     pub fn escrow_released(env: &Env, escrow_id: u64, recipient: &Address, amount: i128) {
         let topics = (symbol_short!("escrow"), symbol_short!("released"));
-        env.events().publish(topics, (escrow_id, recipient.clone(), amount));
+        events::publish(env, topics, (escrow_id, recipient.clone(), amount));
     }
 
     /// Emitted when a batch release starts.
     pub fn batch_release_started(env: &Env, batch_id: u64, request_count: u32) {
         let topics = (symbol_short!("escrow"), symbol_short!("rel_start"));
-        env.events().publish(topics, (batch_id, request_count));
+        events::publish(env, topics, (batch_id, request_count));
     }
 
     /// Emitted when a single escrow is successfully released.
@@ -192,13 +432,86 @@ impl EscrowEvents {
         amount: i128,
     ) {
         let topics = (symbol_short!("escrow"), symbol_short!("rel_ok"), batch_id);
-        env.events().publish(topics, (escrow_id, recipient.clone(), amount));
+        events::publish(env, topics, (escrow_id, recipient.clone(), amount));
     }
 
     /// Emitted when a single escrow release fails.
     pub fn release_failure(env: &Env, batch_id: u64, escrow_id: u64, error_code: u32) {
         let topics = (symbol_short!("escrow"), symbol_short!("rel_fail"), batch_id);
-        env.events().publish(topics, (escrow_id, error_code));
+        events::publish(env, topics, (escrow_id, error_code));
+    }
+
+    /// Emitted when an arbiter registers or tops up their bond.
+    pub fn arbiter_bonded(env: &Env, arbiter: &Address, amount: i128, total_bond: i128) {
+        let topics = (symbol_short!("arbiter"), symbol_short!("bonded"));
+        events::publish(env, topics, (arbiter.clone(), amount, total_bond));
+    }
+
+    /// Emitted when an arbiter withdraws their bond and leaves the registry.
+    pub fn arbiter_exited(env: &Env, arbiter: &Address, bond: i128) {
+        let topics = (symbol_short!("arbiter"), symbol_short!("exited"));
+        events::publish(env, topics, (arbiter.clone(), bond));
+    }
+
+    /// Emitted when governance slashes an arbiter's bond for a wrong ruling.
+    pub fn arbiter_slashed(
+        env: &Env,
+        arbiter: &Address,
+        escrow_id: u64,
+        amount: i128,
+        beneficiary: &Address,
+    ) {
+        let topics = (symbol_short!("arbiter"), symbol_short!("slashed"), escrow_id);
+        events::publish(env, topics, (arbiter.clone(), amount, beneficiary.clone()));
+    }
+
+    /// Emitted when a dispute is opened against an escrow.
+    pub fn dispute_opened(env: &Env, escrow_id: u64, opened_by: &Address, arbiter: &Address) {
+        let topics = (symbol_short!("dispute"), symbol_short!("opened"), escrow_id);
+        events::publish(env, topics, (opened_by.clone(), arbiter.clone()));
+    }
+
+    /// Emitted when an arbiter rules on a dispute.
+    pub fn dispute_resolved(
+        env: &Env,
+        escrow_id: u64,
+        arbiter: &Address,
+        ruling: DisputeRuling,
+        payout: i128,
+        fee: i128,
+    ) {
+        let topics = (symbol_short!("dispute"), symbol_short!("resolved"), escrow_id);
+        events::publish(env, topics, (arbiter.clone(), ruling, payout, fee));
+    }
+
+    /// Emitted when an escrow's funds are deposited into the yield vault.
+    pub fn yield_deposited(env: &Env, escrow_id: u64, vault: &Address, amount: i128, shares: i128) {
+        let topics = (symbol_short!("yield"), symbol_short!("deposit"), escrow_id);
+        events::publish(env, topics, (vault.clone(), amount, shares));
+    }
+
+    /// Emitted when a vault position is redeemed and the yield split.
+    pub fn yield_settled(
+        env: &Env,
+        escrow_id: u64,
+        redeemed: i128,
+        depositor_yield: i128,
+        recipient_yield: i128,
+    ) {
+        let topics = (symbol_short!("yield"), symbol_short!("settled"), escrow_id);
+        events::publish(env, topics, (redeemed, depositor_yield, recipient_yield));
+    }
+
+    /// Emitted when a payout is deferred because the vault is paused.
+    pub fn yield_deferred(env: &Env, escrow_id: u64, beneficiary: &Address) {
+        let topics = (symbol_short!("yield"), symbol_short!("deferred"), escrow_id);
+        events::publish(env, topics, beneficiary.clone());
+    }
+
+    /// Emitted when a closed escrow is moved to the archive.
+    pub fn escrow_archived(env: &Env, escrow_id: u64, record_hash: &BytesN<32>) {
+        let topics = (symbol_short!("escrow"), symbol_short!("archived"), escrow_id);
+        events::publish(env, topics, record_hash.clone());
     }
 
     /// Emitted when a batch release completes.
@@ -210,6 +523,43 @@ impl EscrowEvents {
         total_released: i128,
     ) {
         let topics = (symbol_short!("escrow"), symbol_short!("rel_done"), batch_id);
-        env.events().publish(topics, (successful, failed, total_released));
+        events::publish(env, topics, (successful, failed, total_released));
+    }
+
+    /// Emitted when a merchant's risk tier changes.
+    pub fn merchant_tier_set(env: &Env, merchant: &Address, tier: MerchantTier) {
+        let topics = (symbol_short!("merchant"), symbol_short!("tier"), merchant.clone());
+        events::publish(env, topics, tier);
+    }
+
+    /// Emitted when the policy of a merchant tier changes.
+    pub fn tier_policy_set(env: &Env, tier: MerchantTier, policy: &TierPolicy) {
+        let topics = (symbol_short!("merchant"), symbol_short!("policy"));
+        events::publish(env, topics, (tier, *policy));
+    }
+
+    /// Emitted when a dispute hook rejects a hold or release. The dispute
+    /// itself proceeds regardless.
+    pub fn dispute_hook_failed(env: &Env, escrow_id: u64, hook: &Address) {
+        let topics = (symbol_short!("dispute"), symbol_short!("hook_fail"), escrow_id);
+        events::publish(env, topics, hook.clone());
+    }
+
+    /// Emitted when the admin requests a token rescue.
+    pub fn rescue_requested(env: &Env, rescue: &PendingRescue) {
+        let topics = (symbol_short!("rescue"), symbol_short!("requested"));
+        events::publish(env, topics, rescue.clone());
+    }
+
+    /// Emitted when a pending token rescue is cancelled.
+    pub fn rescue_cancelled(env: &Env, rescue: &PendingRescue) {
+        let topics = (symbol_short!("rescue"), symbol_short!("cancelled"));
+        events::publish(env, topics, rescue.clone());
+    }
+
+    /// Emitted when stuck tokens are sent out of the contract.
+    pub fn tokens_rescued(env: &Env, token: &Address, to: &Address, amount: i128) {
+        let topics = (symbol_short!("rescue"), symbol_short!("executed"));
+        events::publish(env, topics, (token.clone(), to.clone(), amount));
     }
 }
@@ -1,6 +1,6 @@
 //! Validation utilities for escrow reversals.
 
-use crate::types::{Escrow, EscrowStatus};
+use crate::types::{Escrow, EscrowStatus, SettlementTerms};
 use soroban_sdk::Address;
 
 /// Error codes for reversal validation.
@@ -16,6 +16,10 @@ pub mod ErrorCode {
     pub const UNAUTHORIZED: u32 = 3;
     /// Deadline not yet reached (for time-based reversals)
     pub const DEADLINE_NOT_REACHED: u32 = 4;
+    /// Escrow is frozen by an open dispute
+    pub const ESCROW_DISPUTED: u32 = 5;
+    /// Merchant's settlement delay has not elapsed
+    pub const SETTLEMENT_PENDING: u32 = 6;
 }
 
 /// Validation error types for reversals.
@@ -31,17 +35,23 @@ pub enum ValidationError {
     Unauthorized,
     /// Deadline has not been reached yet
     DeadlineNotReached,
+    /// Escrow is frozen by an open dispute
+    EscrowDisputed,
+    /// Merchant's settlement delay has not elapsed
+    SettlementPending,
 }
 
 impl ValidationError {
     /// Convert to error code for result tracking.
-    pub fn to_error_code(&self) -> u32 {
+    pub fn to_error_code(self) -> u32 {
         match self {
             ValidationError::EscrowNotFound => ErrorCode::ESCROW_NOT_FOUND,
             ValidationError::AlreadyReleased => ErrorCode::ALREADY_RELEASED,
             ValidationError::AlreadyReversed => ErrorCode::ALREADY_REVERSED,
             ValidationError::Unauthorized => ErrorCode::UNAUTHORIZED,
             ValidationError::DeadlineNotReached => ErrorCode::DEADLINE_NOT_REACHED,
+            ValidationError::EscrowDisputed => ErrorCode::ESCROW_DISPUTED,
+            ValidationError::SettlementPending => ErrorCode::SETTLEMENT_PENDING,
         }
     }
 }
@@ -72,6 +82,7 @@ pub fn validate_reversal(
     match escrow.status {
         EscrowStatus::Released => return Err(ValidationError::AlreadyReleased),
         EscrowStatus::Reversed => return Err(ValidationError::AlreadyReversed),
+        EscrowStatus::Disputed => return Err(ValidationError::EscrowDisputed),
         EscrowStatus::Active => {}
     }
 
@@ -100,6 +111,7 @@ pub fn validate_release(escrow: Option<&Escrow>, caller: &Address, admin: &Addre
     match escrow.status {
         EscrowStatus::Released => return Err(ValidationError::AlreadyReleased),
         EscrowStatus::Reversed => return Err(ValidationError::AlreadyReversed),
+        EscrowStatus::Disputed => return Err(ValidationError::EscrowDisputed),
         EscrowStatus::Active => {}
     }
 
@@ -112,6 +124,17 @@ pub fn validate_release(escrow: Option<&Escrow>, caller: &Address, admin: &Addre
     Ok(())
 }
 
+/// Validates that an escrow's settlement delay has elapsed at `now`.
+///
+/// Escrows created before merchant tiers existed have no terms and may settle
+/// at any time.
+pub fn validate_settlement(terms: Option<&SettlementTerms>, now: u64) -> Result<(), ValidationError> {
+    match terms {
+        Some(terms) if now < terms.settle_after => Err(ValidationError::SettlementPending),
+        _ => Ok(()),
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -234,6 +257,20 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_validate_reversal_disputed_escrow() {
+        let env = Env::default();
+        let escrow = create_test_escrow(&env, EscrowStatus::Disputed);
+        let admin = Address::generate(&env);
+
+        // Even the admin cannot bypass an open dispute
+        let result = validate_reversal(Some(&escrow), &admin, &admin, false, 100);
+        assert_eq!(result, Err(ValidationError::EscrowDisputed));
+
+        let result = validate_release(Some(&escrow), &admin, &admin);
+        assert_eq!(result, Err(ValidationError::EscrowDisputed));
+    }
+
     #[test]
     fn test_error_code_conversion() {
         assert_eq!(ValidationError::EscrowNotFound.to_error_code(), ErrorCode::ESCROW_NOT_FOUND);
@@ -241,5 +278,21 @@ mod tests {
         assert_eq!(ValidationError::AlreadyReversed.to_error_code(), ErrorCode::ALREADY_REVERSED);
         assert_eq!(ValidationError::Unauthorized.to_error_code(), ErrorCode::UNAUTHORIZED);
         assert_eq!(ValidationError::DeadlineNotReached.to_error_code(), ErrorCode::DEADLINE_NOT_REACHED);
+        assert_eq!(ValidationError::EscrowDisputed.to_error_code(), ErrorCode::ESCROW_DISPUTED);
+        assert_eq!(ValidationError::SettlementPending.to_error_code(), ErrorCode::SETTLEMENT_PENDING);
+    }
+
+    #[test]
+    fn test_validate_settlement() {
+        let terms = SettlementTerms {
+            tier: crate::types::MerchantTier::HighRisk,
+            settle_after: 500,
+            dispute_until: 900,
+        };
+
+        assert_eq!(validate_settlement(Some(&terms), 499), Err(ValidationError::SettlementPending));
+        assert_eq!(validate_settlement(Some(&terms), 500), Ok(()));
+        // Escrows from before merchant tiers settle at any time
+        assert_eq!(validate_settlement(None, 0), Ok(()));
     }
 }
@@ -0,0 +1,20 @@
+//! Interface of the yield vault that escrowed funds can be parked in.
+
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Minimal vault interface used by the escrow contract.
+///
+/// Shares are opaque to the escrow; only the vault knows how they convert to
+/// the underlying token.
+#[allow(dead_code)]
+#[contractclient(name = "YieldVaultClient")]
+pub trait YieldVault {
+    /// Pulls `amount` of the underlying token from `from` and returns the shares minted.
+    fn deposit(env: Env, from: Address, amount: i128) -> i128;
+
+    /// Burns `shares` and sends the underlying they are worth to `to`.
+    fn withdraw(env: Env, to: Address, shares: i128) -> i128;
+
+    /// Returns true while deposits and withdrawals are suspended.
+    fn is_paused(env: Env) -> bool;
+}
@@ -0,0 +1,410 @@
+//! # Gas Sponsorship Contract
+//!
+//! Lets an organization pay network fees on behalf of its users. The org
+//! deposits XLM and registers which users, and which of their operation types,
+//! it sponsors. Before fee-bumping a user's transaction the relayer asks
+//! whether the fee is covered, then meters it here and is reimbursed from the
+//! sponsor's deposit.
+//!
+//! ## Features
+//!
+//! - **Per-Operation Allowlists**: Sponsors choose which operation types they pay for
+//! - **Daily Caps**: Sponsored fees are metered per user per day on-chain
+//! - **Relayer Registry**: Only admin-registered relayers can draw on deposits
+#![no_std]
+
+mod types;
+
+use common::reentrancy::ReentrancyGuard;
+use common::token_io::{self, TransferError};
+use common::versioning;
+use soroban_sdk::{contract, contractimpl, panic_with_error, Address, Env, Symbol, Vec};
+
+pub use crate::types::{
+    DataKey, Sponsorship, SponsorshipEvents, MAX_SPONSORED_OPERATIONS, SECONDS_PER_DAY,
+};
+
+/// Error codes for the gas sponsorship contract.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum GasSponsorshipError {
+    /// Contract not initialized
+    NotInitialized = 1,
+    /// Caller is not authorized
+    Unauthorized = 2,
+    /// Contract already initialized
+    AlreadyInitialized = 3,
+    /// Amount must be positive
+    InvalidAmount = 4,
+    /// User has no sponsor
+    NotSponsored = 5,
+    /// Sponsor does not pay for this operation type
+    OperationNotSponsored = 6,
+    /// Fee would exceed the user's daily allowance
+    DailyLimitExceeded = 7,
+    /// Sponsor's deposit cannot cover the fee
+    InsufficientBalance = 8,
+    /// Caller is not a registered relayer
+    NotRelayer = 9,
+    /// Too many operation types in one sponsorship
+    TooManyOperations = 10,
+    /// Token transfer did not move the expected amount
+    TokenTransferFailed = 11,
+    /// Storage was written by a different layout version
+    StorageVersionMismatch = 12,
+    /// Migration source version does not match stored version
+    InvalidMigration = 13,
+    /// Entrypoint was re-entered during an external call
+    Reentrancy = 14,
+}
+
+impl From<GasSponsorshipError> for soroban_sdk::Error {
+    fn from(e: GasSponsorshipError) -> Self {
+        soroban_sdk::Error::from_contract_error(e as u32)
+    }
+}
+
+/// Storage layout version this code expects.
+pub const STORAGE_VERSION: u32 = 1;
+
+#[contract]
+pub struct GasSponsorshipContract;
+
+#[contractimpl]
+impl GasSponsorshipContract {
+    /// Initializes the contract with an admin and the token fees are paid in.
+    pub fn initialize(env: Env, admin: Address, token: Address) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic_with_error!(&env, GasSponsorshipError::AlreadyInitialized);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        versioning::set_version(&env, STORAGE_VERSION);
+        env.storage().instance().set(&DataKey::Token, &token);
+    }
+
+    /// Migrates storage written by an older version of this contract to the
+    /// current layout (admin only). `from_version` must match the stored
+    /// version.
+    pub fn migrate(env: Env, caller: Address, from_version: u32) {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        versioning::migrate(&env, from_version, STORAGE_VERSION, |_, _| {})
+            .unwrap_or_else(|_| panic_with_error!(&env, GasSponsorshipError::InvalidMigration));
+    }
+
+    /// Returns the storage layout version currently stored.
+    pub fn get_storage_version(env: Env) -> u32 {
+        versioning::stored_version(&env)
+    }
+
+    /// Registers or removes a relayer (admin only).
+    pub fn set_relayer(env: Env, caller: Address, relayer: Address, enabled: bool) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        if enabled {
+            env.storage()
+                .persistent()
+                .set(&DataKey::Relayer(relayer.clone()), &true);
+        } else {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::Relayer(relayer.clone()));
+        }
+
+        SponsorshipEvents::relayer_set(&env, &relayer, enabled);
+    }
+
+    /// Deposits funds that the sponsor's users' fees are paid from.
+    pub fn deposit(env: Env, sponsor: Address, amount: i128) -> i128 {
+        Self::require_storage_version(&env);
+        sponsor.require_auth();
+        let _guard = Self::enter_guard(&env);
+
+        if amount <= 0 {
+            panic_with_error!(&env, GasSponsorshipError::InvalidAmount);
+        }
+
+        let contract = env.current_contract_address();
+        Self::send(&env, &sponsor, &contract, amount);
+
+        let balance = Self::get_sponsor_balance(env.clone(), sponsor.clone())
+            .checked_add(amount)
+            .unwrap_or_else(|| panic_with_error!(&env, GasSponsorshipError::InvalidAmount));
+        Self::set_balance(&env, &sponsor, balance);
+
+        SponsorshipEvents::deposited(&env, &sponsor, amount, balance);
+
+        balance
+    }
+
+    /// Withdraws unspent funds back to the sponsor.
+    pub fn withdraw(env: Env, sponsor: Address, amount: i128) -> i128 {
+        Self::require_storage_version(&env);
+        sponsor.require_auth();
+        let _guard = Self::enter_guard(&env);
+
+        if amount <= 0 {
+            panic_with_error!(&env, GasSponsorshipError::InvalidAmount);
+        }
+        let balance = Self::get_sponsor_balance(env.clone(), sponsor.clone());
+        if amount > balance {
+            panic_with_error!(&env, GasSponsorshipError::InsufficientBalance);
+        }
+
+        let balance = balance - amount;
+        Self::set_balance(&env, &sponsor, balance);
+        let contract = env.current_contract_address();
+        Self::send(&env, &contract, &sponsor, amount);
+
+        SponsorshipEvents::withdrawn(&env, &sponsor, amount, balance);
+
+        balance
+    }
+
+    /// Sponsors fees for `user` on the listed operation types, up to
+    /// `daily_limit` per day.
+    ///
+    /// A user has at most one sponsor; calling this again replaces the terms,
+    /// and another sponsor cannot take over an existing sponsorship.
+    pub fn sponsor_user(
+        env: Env,
+        sponsor: Address,
+        user: Address,
+        operations: Vec<Symbol>,
+        daily_limit: i128,
+    ) {
+        Self::require_storage_version(&env);
+        sponsor.require_auth();
+
+        if daily_limit <= 0 {
+            panic_with_error!(&env, GasSponsorshipError::InvalidAmount);
+        }
+        if operations.len() > MAX_SPONSORED_OPERATIONS {
+            panic_with_error!(&env, GasSponsorshipError::TooManyOperations);
+        }
+        if let Some(existing) = Self::get_sponsorship(env.clone(), user.clone()) {
+            if existing.sponsor != sponsor {
+                panic_with_error!(&env, GasSponsorshipError::Unauthorized);
+            }
+        }
+
+        let sponsorship = Sponsorship {
+            sponsor,
+            operations,
+            daily_limit,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Sponsorship(user.clone()), &sponsorship);
+
+        SponsorshipEvents::user_sponsored(&env, &sponsorship, &user);
+    }
+
+    /// Stops sponsoring `user` (current sponsor only).
+    pub fn revoke_user(env: Env, sponsor: Address, user: Address) {
+        Self::require_storage_version(&env);
+        sponsor.require_auth();
+
+        let sponsorship = Self::load_sponsorship(&env, &user);
+        if sponsorship.sponsor != sponsor {
+            panic_with_error!(&env, GasSponsorshipError::Unauthorized);
+        }
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Sponsorship(user.clone()));
+
+        SponsorshipEvents::user_revoked(&env, &sponsor, &user);
+    }
+
+    /// Returns whether a fee of `fee` for `operation` by `user` would be
+    /// sponsored today. Relayers call this before fee-bumping a transaction.
+    pub fn is_sponsored(env: Env, user: Address, operation: Symbol, fee: i128) -> bool {
+        Self::check_entitlement(&env, &user, &operation, fee).is_ok()
+    }
+
+    /// Meters a sponsored fee and reimburses the relayer from the sponsor's
+    /// deposit (registered relayers only).
+    ///
+    /// # Returns
+    /// * The sponsor that paid the fee
+    pub fn record_usage(
+        env: Env,
+        relayer: Address,
+        user: Address,
+        operation: Symbol,
+        fee: i128,
+    ) -> Address {
+        Self::require_storage_version(&env);
+        relayer.require_auth();
+        let _guard = Self::enter_guard(&env);
+        if !Self::is_relayer(env.clone(), relayer.clone()) {
+            panic_with_error!(&env, GasSponsorshipError::NotRelayer);
+        }
+
+        let sponsorship = Self::check_entitlement(&env, &user, &operation, fee)
+            .unwrap_or_else(|e| panic_with_error!(&env, e));
+        let sponsor = sponsorship.sponsor;
+
+        let day = Self::current_day(&env);
+        let usage = Self::get_daily_usage(env.clone(), user.clone(), day) + fee;
+        env.storage()
+            .persistent()
+            .set(&DataKey::DailyUsage(user.clone(), day), &usage);
+
+        let balance = Self::get_sponsor_balance(env.clone(), sponsor.clone()) - fee;
+        Self::set_balance(&env, &sponsor, balance);
+        let contract = env.current_contract_address();
+        Self::send(&env, &contract, &relayer, fee);
+
+        SponsorshipEvents::fee_sponsored(&env, &sponsor, &user, &operation, fee, &relayer);
+
+        sponsor
+    }
+
+    /// Returns the sponsorship covering `user`, if any.
+    pub fn get_sponsorship(env: Env, user: Address) -> Option<Sponsorship> {
+        env.storage().persistent().get(&DataKey::Sponsorship(user))
+    }
+
+    /// Returns a sponsor's unspent deposit.
+    pub fn get_sponsor_balance(env: Env, sponsor: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::SponsorBalance(sponsor))
+            .unwrap_or(0)
+    }
+
+    /// Returns the fees sponsored for `user` on `day` (days since the Unix
+    /// epoch).
+    pub fn get_daily_usage(env: Env, user: Address, day: u64) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::DailyUsage(user, day))
+            .unwrap_or(0)
+    }
+
+    /// Returns how much more `user` can have sponsored today, ignoring the
+    /// sponsor's balance.
+    pub fn get_remaining_allowance(env: Env, user: Address) -> i128 {
+        match Self::get_sponsorship(env.clone(), user.clone()) {
+            Some(sponsorship) => {
+                let used = Self::get_daily_usage(env.clone(), user, Self::current_day(&env));
+                (sponsorship.daily_limit - used).max(0)
+            }
+            None => 0,
+        }
+    }
+
+    /// Returns whether `relayer` is registered.
+    pub fn is_relayer(env: Env, relayer: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Relayer(relayer))
+            .unwrap_or(false)
+    }
+
+    /// Returns the current day index used for metering.
+    pub fn get_current_day(env: Env) -> u64 {
+        Self::current_day(&env)
+    }
+
+    /// Returns the admin address.
+    pub fn get_admin(env: Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(&env, GasSponsorshipError::NotInitialized))
+    }
+
+    // Internal helper deciding whether a fee is sponsored, and why not
+    fn check_entitlement(
+        env: &Env,
+        user: &Address,
+        operation: &Symbol,
+        fee: i128,
+    ) -> Result<Sponsorship, GasSponsorshipError> {
+        if fee <= 0 {
+            return Err(GasSponsorshipError::InvalidAmount);
+        }
+        let sponsorship = Self::get_sponsorship(env.clone(), user.clone())
+            .ok_or(GasSponsorshipError::NotSponsored)?;
+        if !sponsorship.operations.contains(operation) {
+            return Err(GasSponsorshipError::OperationNotSponsored);
+        }
+
+        let used = Self::get_daily_usage(env.clone(), user.clone(), Self::current_day(env));
+        match used.checked_add(fee) {
+            Some(total) if total <= sponsorship.daily_limit => {}
+            _ => return Err(GasSponsorshipError::DailyLimitExceeded),
+        }
+        if Self::get_sponsor_balance(env.clone(), sponsorship.sponsor.clone()) < fee {
+            return Err(GasSponsorshipError::InsufficientBalance);
+        }
+
+        Ok(sponsorship)
+    }
+
+    // Internal helper returning today's metering day
+    fn current_day(env: &Env) -> u64 {
+        env.ledger().timestamp() / SECONDS_PER_DAY
+    }
+
+    // Internal helper to load a sponsorship or fail
+    fn load_sponsorship(env: &Env, user: &Address) -> Sponsorship {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Sponsorship(user.clone()))
+            .unwrap_or_else(|| panic_with_error!(env, GasSponsorshipError::NotSponsored))
+    }
+
+    // Internal helper storing a sponsor's balance
+    fn set_balance(env: &Env, sponsor: &Address, balance: i128) {
+        env.storage()
+            .persistent()
+            .set(&DataKey::SponsorBalance(sponsor.clone()), &balance);
+    }
+
+    // Internal helper moving the fee token with balance checks
+    fn send(env: &Env, from: &Address, to: &Address, amount: i128) {
+        let token: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Token)
+            .unwrap_or_else(|| panic_with_error!(env, GasSponsorshipError::NotInitialized));
+        token_io::safe_transfer(env, &token, from, to, amount).unwrap_or_else(|e| match e {
+            TransferError::InvalidAmount => {
+                panic_with_error!(env, GasSponsorshipError::InvalidAmount)
+            }
+            _ => panic_with_error!(env, GasSponsorshipError::TokenTransferFailed),
+        })
+    }
+
+    // Internal helper taking the reentrancy lock for entrypoints that move
+    // sponsor deposits
+    fn enter_guard(env: &Env) -> ReentrancyGuard {
+        ReentrancyGuard::enter(env)
+            .unwrap_or_else(|_| panic_with_error!(env, GasSponsorshipError::Reentrancy))
+    }
+
+    // Internal helper refusing to run against storage written by a different
+    // layout version
+    fn require_storage_version(env: &Env) {
+        versioning::require_version(env, STORAGE_VERSION).unwrap_or_else(|_| {
+            panic_with_error!(env, GasSponsorshipError::StorageVersionMismatch)
+        });
+    }
+
+    // Internal helper to verify admin
+    fn require_admin(env: &Env, caller: &Address) {
+        if *caller != Self::get_admin(env.clone()) {
+            panic_with_error!(env, GasSponsorshipError::Unauthorized);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,192 @@
+//! Integration tests for the gas sponsorship contract.
+
+#![cfg(test)]
+
+use crate::{GasSponsorshipContract, GasSponsorshipContractClient, SECONDS_PER_DAY};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Ledger},
+    token, vec, Address, Env,
+};
+
+/// Creates a test environment with the contract deployed and initialized,
+/// a registered relayer, and a funded sponsor.
+fn setup_test_env() -> (
+    Env,
+    Address,
+    Address,
+    Address,
+    token::Client<'static>,
+    GasSponsorshipContractClient<'static>,
+) {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| {
+        li.timestamp = 10 * SECONDS_PER_DAY;
+    });
+
+    let xlm = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let token_client = token::Client::new(&env, &xlm.address());
+
+    let contract_id = env.register(GasSponsorshipContract, ());
+    let client = GasSponsorshipContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &xlm.address());
+
+    let relayer = Address::generate(&env);
+    client.set_relayer(&admin, &relayer, &true);
+
+    let sponsor = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &xlm.address()).mint(&sponsor, &10_000);
+    client.deposit(&sponsor, &5_000);
+
+    (env, admin, relayer, sponsor, token_client, client)
+}
+
+// ============================================
+// Deposit Tests
+// ============================================
+
+#[test]
+fn test_deposit_and_withdraw() {
+    let (_env, _admin, _relayer, sponsor, token_client, client) = setup_test_env();
+
+    assert_eq!(client.get_sponsor_balance(&sponsor), 5_000);
+    assert_eq!(token_client.balance(&client.address), 5_000);
+
+    assert_eq!(client.withdraw(&sponsor, &2_000), 3_000);
+    assert_eq!(token_client.balance(&sponsor), 7_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")]
+fn test_withdraw_more_than_balance() {
+    let (_env, _admin, _relayer, sponsor, _token_client, client) = setup_test_env();
+    client.withdraw(&sponsor, &5_001);
+}
+
+// ============================================
+// Entitlement Tests
+// ============================================
+
+#[test]
+fn test_entitlement_checks_operation_and_daily_limit() {
+    let (env, _admin, _relayer, sponsor, _token_client, client) = setup_test_env();
+
+    let user = Address::generate(&env);
+    assert!(!client.is_sponsored(&user, &symbol_short!("pay"), &100));
+
+    client.sponsor_user(&sponsor, &user, &vec![&env, symbol_short!("pay")], &500);
+
+    assert!(client.is_sponsored(&user, &symbol_short!("pay"), &500));
+    assert!(!client.is_sponsored(&user, &symbol_short!("pay"), &501));
+    assert!(!client.is_sponsored(&user, &symbol_short!("swap"), &100));
+    assert_eq!(client.get_remaining_allowance(&user), 500);
+}
+
+#[test]
+fn test_entitlement_requires_sponsor_balance() {
+    let (env, _admin, _relayer, sponsor, _token_client, client) = setup_test_env();
+
+    let user = Address::generate(&env);
+    client.sponsor_user(&sponsor, &user, &vec![&env, symbol_short!("pay")], &10_000);
+
+    assert!(client.is_sponsored(&user, &symbol_short!("pay"), &5_000));
+    assert!(!client.is_sponsored(&user, &symbol_short!("pay"), &5_001));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_cannot_take_over_another_sponsorship() {
+    let (env, _admin, _relayer, sponsor, _token_client, client) = setup_test_env();
+
+    let user = Address::generate(&env);
+    client.sponsor_user(&sponsor, &user, &vec![&env, symbol_short!("pay")], &500);
+    client.sponsor_user(&Address::generate(&env), &user, &vec![&env], &500);
+}
+
+#[test]
+fn test_revoke_user() {
+    let (env, _admin, _relayer, sponsor, _token_client, client) = setup_test_env();
+
+    let user = Address::generate(&env);
+    client.sponsor_user(&sponsor, &user, &vec![&env, symbol_short!("pay")], &500);
+    client.revoke_user(&sponsor, &user);
+
+    assert_eq!(client.get_sponsorship(&user), None);
+    assert!(!client.is_sponsored(&user, &symbol_short!("pay"), &1));
+}
+
+// ============================================
+// Metering Tests
+// ============================================
+
+#[test]
+fn test_record_usage_reimburses_relayer() {
+    let (env, _admin, relayer, sponsor, token_client, client) = setup_test_env();
+
+    let user = Address::generate(&env);
+    client.sponsor_user(&sponsor, &user, &vec![&env, symbol_short!("pay")], &500);
+
+    let payer = client.record_usage(&relayer, &user, &symbol_short!("pay"), &120);
+
+    assert_eq!(payer, sponsor);
+    assert_eq!(token_client.balance(&relayer), 120);
+    assert_eq!(client.get_sponsor_balance(&sponsor), 4_880);
+    assert_eq!(
+        client.get_daily_usage(&user, &client.get_current_day()),
+        120
+    );
+    assert_eq!(client.get_remaining_allowance(&user), 380);
+}
+
+#[test]
+fn test_daily_usage_resets_next_day() {
+    let (env, _admin, relayer, sponsor, _token_client, client) = setup_test_env();
+
+    let user = Address::generate(&env);
+    client.sponsor_user(&sponsor, &user, &vec![&env, symbol_short!("pay")], &500);
+    client.record_usage(&relayer, &user, &symbol_short!("pay"), &500);
+    assert!(!client.is_sponsored(&user, &symbol_short!("pay"), &1));
+
+    env.ledger().with_mut(|li| {
+        li.timestamp += SECONDS_PER_DAY;
+    });
+
+    assert!(client.is_sponsored(&user, &symbol_short!("pay"), &500));
+    assert_eq!(client.get_daily_usage(&user, &10), 500);
+    assert_eq!(client.get_daily_usage(&user, &11), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")]
+fn test_record_usage_over_daily_limit() {
+    let (env, _admin, relayer, sponsor, _token_client, client) = setup_test_env();
+
+    let user = Address::generate(&env);
+    client.sponsor_user(&sponsor, &user, &vec![&env, symbol_short!("pay")], &500);
+    client.record_usage(&relayer, &user, &symbol_short!("pay"), &300);
+    client.record_usage(&relayer, &user, &symbol_short!("pay"), &201);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #6)")]
+fn test_record_usage_unsponsored_operation() {
+    let (env, _admin, relayer, sponsor, _token_client, client) = setup_test_env();
+
+    let user = Address::generate(&env);
+    client.sponsor_user(&sponsor, &user, &vec![&env, symbol_short!("pay")], &500);
+    client.record_usage(&relayer, &user, &symbol_short!("swap"), &100);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn test_record_usage_requires_registered_relayer() {
+    let (env, admin, relayer, sponsor, _token_client, client) = setup_test_env();
+
+    let user = Address::generate(&env);
+    client.sponsor_user(&sponsor, &user, &vec![&env, symbol_short!("pay")], &500);
+    client.set_relayer(&admin, &relayer, &false);
+    client.record_usage(&relayer, &user, &symbol_short!("pay"), &100);
+}
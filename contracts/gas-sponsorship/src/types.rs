@@ -0,0 +1,91 @@
+//! Data types and events for the gas sponsorship contract.
+
+use common::events;
+use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol, Vec};
+
+/// Length of a metering day, in seconds.
+pub const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Maximum number of operation types a single sponsorship may cover.
+pub const MAX_SPONSORED_OPERATIONS: u32 = 32;
+
+/// A sponsor's commitment to pay fees for one user.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Sponsorship {
+    pub sponsor: Address,
+    /// Operation types the sponsor pays for
+    pub operations: Vec<Symbol>,
+    /// Maximum fees sponsored for the user per day
+    pub daily_limit: i128,
+}
+
+/// Storage keys for the gas sponsorship contract.
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    /// Admin address
+    Admin,
+    /// Token fees are reimbursed in (the native XLM contract)
+    Token,
+    /// Whether an address may meter sponsored fees
+    Relayer(Address),
+    /// Unspent deposit held for a sponsor
+    SponsorBalance(Address),
+    /// Sponsorship covering a user
+    Sponsorship(Address),
+    /// Fees sponsored for a user on a given day
+    DailyUsage(Address, u64),
+}
+
+/// Event emitters for gas sponsorship operations.
+pub struct SponsorshipEvents;
+
+impl SponsorshipEvents {
+    /// Emitted when a sponsor deposits funds.
+    pub fn deposited(env: &Env, sponsor: &Address, amount: i128, balance: i128) {
+        let topics = (symbol_short!("sponsor"), symbol_short!("deposit"));
+        events::publish(env, topics, (sponsor.clone(), amount, balance));
+    }
+
+    /// Emitted when a sponsor withdraws unspent funds.
+    pub fn withdrawn(env: &Env, sponsor: &Address, amount: i128, balance: i128) {
+        let topics = (symbol_short!("sponsor"), symbol_short!("withdraw"));
+        events::publish(env, topics, (sponsor.clone(), amount, balance));
+    }
+
+    /// Emitted when a sponsor starts or changes sponsoring a user.
+    pub fn user_sponsored(env: &Env, sponsorship: &Sponsorship, user: &Address) {
+        let topics = (symbol_short!("sponsor"), symbol_short!("user_set"));
+        events::publish(env, topics, (user.clone(), sponsorship.clone()));
+    }
+
+    /// Emitted when a sponsorship is revoked.
+    pub fn user_revoked(env: &Env, sponsor: &Address, user: &Address) {
+        let topics = (symbol_short!("sponsor"), symbol_short!("user_rm"));
+        events::publish(env, topics, (sponsor.clone(), user.clone()));
+    }
+
+    /// Emitted when a relayer is registered or removed.
+    pub fn relayer_set(env: &Env, relayer: &Address, enabled: bool) {
+        let topics = (symbol_short!("sponsor"), symbol_short!("relayer"));
+        events::publish(env, topics, (relayer.clone(), enabled));
+    }
+
+    /// Emitted when a sponsored fee is metered and reimbursed.
+    pub fn fee_sponsored(
+        env: &Env,
+        sponsor: &Address,
+        user: &Address,
+        operation: &Symbol,
+        fee: i128,
+        relayer: &Address,
+    ) {
+        let topics = (symbol_short!("sponsor"), symbol_short!("fee"), user.clone());
+        events::publish(
+            env,
+            topics,
+            (sponsor.clone(), operation.clone(), fee, relayer.clone()),
+        );
+    }
+}
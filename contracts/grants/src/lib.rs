@@ -0,0 +1,545 @@
+//! # Grants Contract
+//!
+//! Disburses program-level grants against milestone reports. A funder escrows
+//! a program budget and awards grantees a tranche per milestone. Grantees post
+//! the hash of each milestone report, an Operator reviews it and, on approval,
+//! the tranche is released. Whatever has not been released when the program
+//! ends returns to the funder.
+//!
+//! ## Features
+//!
+//! - **Escrowed Budgets**: Program funds are held by the contract, never over-allocated
+//! - **Milestone Tranches**: Each tranche is released only against an approved report
+//! - **Disbursement History**: Every release is recorded per grantee
+#![no_std]
+
+mod types;
+
+use common::idempotency;
+use common::ids::{self, IdScope};
+use common::reentrancy::ReentrancyGuard;
+use common::token_io::{self, TransferError};
+use common::versioning;
+use soroban_sdk::{
+    contract, contractimpl, panic_with_error, symbol_short, Address, BytesN, Env, Vec,
+};
+
+pub use crate::types::{
+    DataKey, Disbursement, Grant, GrantEvents, Program, MAX_GRANTEES, MAX_MILESTONES,
+};
+
+/// Error codes for the grants contract.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum GrantsError {
+    /// Contract not initialized
+    NotInitialized = 1,
+    /// Caller is not authorized
+    Unauthorized = 2,
+    /// Contract already initialized
+    AlreadyInitialized = 3,
+    /// Amount must be positive
+    InvalidAmount = 4,
+    /// Program does not exist
+    ProgramNotFound = 5,
+    /// Grantee has no grant in the program
+    GrantNotFound = 6,
+    /// Grantee already has a grant in the program
+    GrantExists = 7,
+    /// Grant would exceed the program's unallocated budget
+    OverAllocated = 8,
+    /// Program has ended
+    ProgramEnded = 9,
+    /// Program has not ended yet
+    ProgramActive = 10,
+    /// A report is already awaiting review
+    ReportPending = 11,
+    /// No report is awaiting review
+    NoPendingReport = 12,
+    /// Every milestone of the grant has been paid
+    GrantCompleted = 13,
+    /// Grant has no milestones or too many
+    InvalidMilestones = 14,
+    /// Program already has the maximum number of grantees
+    TooManyGrantees = 15,
+    /// Caller is not a registered Operator
+    NotOperator = 16,
+    /// Program end time is not in the future
+    InvalidEndTime = 17,
+    /// Token transfer did not move the expected amount
+    TokenTransferFailed = 18,
+    /// Program ids are exhausted
+    IdsExhausted = 19,
+    /// Storage was written by a different layout version
+    StorageVersionMismatch = 20,
+    /// Migration source version does not match stored version
+    InvalidMigration = 21,
+    /// Idempotency key was already used for a different review
+    IdempotencyKeyReused = 22,
+    /// Idempotency retention window is out of bounds
+    InvalidRetention = 23,
+    /// Entrypoint was re-entered during an external call
+    Reentrancy = 24,
+}
+
+impl From<GrantsError> for soroban_sdk::Error {
+    fn from(e: GrantsError) -> Self {
+        soroban_sdk::Error::from_contract_error(e as u32)
+    }
+}
+
+/// Storage layout version this code expects.
+pub const STORAGE_VERSION: u32 = 1;
+
+#[contract]
+pub struct GrantsContract;
+
+#[contractimpl]
+impl GrantsContract {
+    /// Initializes the contract with an admin address.
+    pub fn initialize(env: Env, admin: Address) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic_with_error!(&env, GrantsError::AlreadyInitialized);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        versioning::set_version(&env, STORAGE_VERSION);
+    }
+
+    /// Migrates storage written by an older version of this contract to the
+    /// current layout (admin only). `from_version` must match the stored
+    /// version.
+    pub fn migrate(env: Env, caller: Address, from_version: u32) {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        versioning::migrate(&env, from_version, STORAGE_VERSION, |_, _| {})
+            .unwrap_or_else(|_| panic_with_error!(&env, GrantsError::InvalidMigration));
+    }
+
+    /// Returns the storage layout version currently stored.
+    pub fn get_storage_version(env: Env) -> u32 {
+        versioning::stored_version(&env)
+    }
+
+    /// Registers or removes an Operator who reviews milestone reports (admin only).
+    pub fn set_operator(env: Env, caller: Address, operator: Address, enabled: bool) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        if enabled {
+            env.storage()
+                .persistent()
+                .set(&DataKey::Operator(operator.clone()), &true);
+        } else {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::Operator(operator.clone()));
+        }
+
+        GrantEvents::operator_set(&env, &operator, enabled);
+    }
+
+    /// Escrows a program budget from the funder.
+    ///
+    /// # Returns
+    /// * The new program id
+    pub fn create_program(
+        env: Env,
+        funder: Address,
+        token: Address,
+        budget: i128,
+        end_time: u64,
+    ) -> u64 {
+        Self::require_storage_version(&env);
+        funder.require_auth();
+        let _guard = Self::enter_guard(&env);
+
+        if budget <= 0 {
+            panic_with_error!(&env, GrantsError::InvalidAmount);
+        }
+        if end_time <= env.ledger().timestamp() {
+            panic_with_error!(&env, GrantsError::InvalidEndTime);
+        }
+
+        let id = ids::next_id(&env, &IdScope::new(0, symbol_short!("program")))
+            .unwrap_or_else(|_| panic_with_error!(&env, GrantsError::IdsExhausted));
+        let contract = env.current_contract_address();
+        Self::send(&env, &token, &funder, &contract, budget);
+
+        let program = Program {
+            id,
+            funder,
+            token,
+            budget,
+            allocated: 0,
+            disbursed: 0,
+            end_time,
+            closed: false,
+        };
+        Self::save_program(&env, &program);
+
+        GrantEvents::program_created(&env, &program);
+
+        id
+    }
+
+    /// Awards `grantee` a grant paid in one tranche per milestone (funder only).
+    ///
+    /// The tranches must fit in the program's unallocated budget.
+    pub fn add_grantee(
+        env: Env,
+        funder: Address,
+        program_id: u64,
+        grantee: Address,
+        tranches: Vec<i128>,
+    ) {
+        Self::require_storage_version(&env);
+        funder.require_auth();
+
+        let mut program = Self::load_open_program(&env, program_id);
+        if program.funder != funder {
+            panic_with_error!(&env, GrantsError::Unauthorized);
+        }
+        if tranches.is_empty() || tranches.len() > MAX_MILESTONES {
+            panic_with_error!(&env, GrantsError::InvalidMilestones);
+        }
+        let key = DataKey::Grant(program_id, grantee.clone());
+        if env.storage().persistent().has(&key) {
+            panic_with_error!(&env, GrantsError::GrantExists);
+        }
+        let mut grantees = Self::get_grantees(env.clone(), program_id);
+        if grantees.len() >= MAX_GRANTEES {
+            panic_with_error!(&env, GrantsError::TooManyGrantees);
+        }
+
+        let mut total: i128 = 0;
+        for tranche in tranches.iter() {
+            if tranche <= 0 {
+                panic_with_error!(&env, GrantsError::InvalidAmount);
+            }
+            total = total
+                .checked_add(tranche)
+                .unwrap_or_else(|| panic_with_error!(&env, GrantsError::OverAllocated));
+        }
+        program.allocated = program
+            .allocated
+            .checked_add(total)
+            .filter(|allocated| *allocated <= program.budget)
+            .unwrap_or_else(|| panic_with_error!(&env, GrantsError::OverAllocated));
+
+        let grant = Grant {
+            grantee: grantee.clone(),
+            tranches,
+            next_milestone: 0,
+            pending_report: None,
+            disbursed: 0,
+        };
+        env.storage().persistent().set(&key, &grant);
+        grantees.push_back(grantee.clone());
+        env.storage()
+            .persistent()
+            .set(&DataKey::Grantees(program_id), &grantees);
+        Self::save_program(&env, &program);
+
+        GrantEvents::grant_added(&env, program_id, &grantee, total);
+    }
+
+    /// Submits the hash of the report for the grantee's next milestone.
+    ///
+    /// # Returns
+    /// * The milestone index the report covers
+    pub fn submit_report(
+        env: Env,
+        grantee: Address,
+        program_id: u64,
+        report_hash: BytesN<32>,
+    ) -> u32 {
+        Self::require_storage_version(&env);
+        grantee.require_auth();
+
+        Self::load_open_program(&env, program_id);
+        let mut grant = Self::load_grant(&env, program_id, &grantee);
+        if grant.next_milestone >= grant.tranches.len() {
+            panic_with_error!(&env, GrantsError::GrantCompleted);
+        }
+        if grant.pending_report.is_some() {
+            panic_with_error!(&env, GrantsError::ReportPending);
+        }
+
+        grant.pending_report = Some(report_hash.clone());
+        Self::save_grant(&env, program_id, &grant);
+
+        GrantEvents::report_submitted(
+            &env,
+            program_id,
+            &grantee,
+            grant.next_milestone,
+            &report_hash,
+        );
+
+        grant.next_milestone
+    }
+
+    /// Reviews a grantee's pending milestone report (Operators only).
+    ///
+    /// Approving releases the milestone's tranche to the grantee; rejecting
+    /// clears the report so the grantee can resubmit.
+    ///
+    /// With an `idempotency_key`, a retry of the same review within the
+    /// retention window returns the original amount without releasing again.
+    ///
+    /// # Returns
+    /// * The amount released (0 when rejected)
+    pub fn review_report(
+        env: Env,
+        operator: Address,
+        program_id: u64,
+        grantee: Address,
+        approve: bool,
+        idempotency_key: Option<BytesN<32>>,
+    ) -> i128 {
+        Self::require_storage_version(&env);
+        operator.require_auth();
+        let _guard = Self::enter_guard(&env);
+        if !Self::is_operator(env.clone(), operator.clone()) {
+            panic_with_error!(&env, GrantsError::NotOperator);
+        }
+
+        let Some(key) = idempotency_key else {
+            return Self::process_review(env, operator, program_id, grantee, approve);
+        };
+        let fingerprint = idempotency::fingerprint(&env, (program_id, grantee.clone(), approve));
+        let earlier = idempotency::recall(&env, &operator, &key, &fingerprint)
+            .unwrap_or_else(|_| panic_with_error!(&env, GrantsError::IdempotencyKeyReused));
+        if let Some(amount) = earlier {
+            return amount;
+        }
+
+        let amount = Self::process_review(
+            env.clone(),
+            operator.clone(),
+            program_id,
+            grantee,
+            approve,
+        );
+        idempotency::remember(&env, &operator, &key, &fingerprint, &amount);
+        amount
+    }
+
+    /// Sets how long idempotency keys are remembered, in seconds (admin only).
+    pub fn set_idempotency_retention(env: Env, caller: Address, secs: u64) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        idempotency::set_retention(&env, secs)
+            .unwrap_or_else(|_| panic_with_error!(&env, GrantsError::InvalidRetention));
+    }
+
+    /// Returns how long idempotency keys are remembered, in seconds.
+    pub fn get_idempotency_retention(env: Env) -> u64 {
+        idempotency::get_retention(&env)
+    }
+
+    // Internal helper applying an authorized review
+    fn process_review(
+        env: Env,
+        operator: Address,
+        program_id: u64,
+        grantee: Address,
+        approve: bool,
+    ) -> i128 {
+        let mut program = Self::load_open_program(&env, program_id);
+        let mut grant = Self::load_grant(&env, program_id, &grantee);
+        let report_hash = grant
+            .pending_report
+            .clone()
+            .unwrap_or_else(|| panic_with_error!(&env, GrantsError::NoPendingReport));
+        let milestone = grant.next_milestone;
+        grant.pending_report = None;
+
+        if !approve {
+            Self::save_grant(&env, program_id, &grant);
+            GrantEvents::report_rejected(&env, program_id, &grantee, milestone, &operator);
+            return 0;
+        }
+
+        let amount = grant.tranches.get(milestone).unwrap();
+        grant.next_milestone += 1;
+        grant.disbursed += amount;
+        program.disbursed += amount;
+        Self::save_grant(&env, program_id, &grant);
+        Self::save_program(&env, &program);
+
+        let disbursement = Disbursement {
+            milestone,
+            amount,
+            report_hash,
+            reviewer: operator,
+            released_at: env.ledger().timestamp(),
+        };
+        let mut history = Self::get_disbursements(env.clone(), program_id, grantee.clone());
+        history.push_back(disbursement.clone());
+        env.storage().persistent().set(
+            &DataKey::Disbursements(program_id, grantee.clone()),
+            &history,
+        );
+
+        let contract = env.current_contract_address();
+        Self::send(&env, &program.token, &contract, &grantee, amount);
+
+        GrantEvents::tranche_released(&env, program_id, &grantee, &disbursement);
+
+        amount
+    }
+
+    /// Closes an ended program and returns everything not yet released to the
+    /// funder. Anyone may call this once the program has ended.
+    ///
+    /// # Returns
+    /// * The amount returned to the funder
+    pub fn close_program(env: Env, program_id: u64) -> i128 {
+        Self::require_storage_version(&env);
+        let _guard = Self::enter_guard(&env);
+
+        let mut program = Self::load_program(&env, program_id);
+        if program.closed {
+            panic_with_error!(&env, GrantsError::ProgramEnded);
+        }
+        if env.ledger().timestamp() < program.end_time {
+            panic_with_error!(&env, GrantsError::ProgramActive);
+        }
+
+        let returned = program.budget - program.disbursed;
+        program.closed = true;
+        Self::save_program(&env, &program);
+
+        if returned > 0 {
+            let contract = env.current_contract_address();
+            Self::send(&env, &program.token, &contract, &program.funder, returned);
+        }
+
+        GrantEvents::program_closed(&env, &program, returned);
+
+        returned
+    }
+
+    /// Returns a program, if it exists.
+    pub fn get_program(env: Env, program_id: u64) -> Option<Program> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Program(program_id))
+    }
+
+    /// Returns a grantee's grant in a program, if any.
+    pub fn get_grant(env: Env, program_id: u64, grantee: Address) -> Option<Grant> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Grant(program_id, grantee))
+    }
+
+    /// Returns a program's grantees, in the order they were added.
+    pub fn get_grantees(env: Env, program_id: u64) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Grantees(program_id))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Returns the tranches released to a grantee in a program, oldest first.
+    pub fn get_disbursements(env: Env, program_id: u64, grantee: Address) -> Vec<Disbursement> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Disbursements(program_id, grantee))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Returns whether `operator` is registered.
+    pub fn is_operator(env: Env, operator: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Operator(operator))
+            .unwrap_or(false)
+    }
+
+    /// Returns the admin address.
+    pub fn get_admin(env: Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(&env, GrantsError::NotInitialized))
+    }
+
+    // Internal helper to load a program or fail
+    fn load_program(env: &Env, program_id: u64) -> Program {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Program(program_id))
+            .unwrap_or_else(|| panic_with_error!(env, GrantsError::ProgramNotFound))
+    }
+
+    // Internal helper to load a program that is still running
+    fn load_open_program(env: &Env, program_id: u64) -> Program {
+        let program = Self::load_program(env, program_id);
+        if program.closed || env.ledger().timestamp() >= program.end_time {
+            panic_with_error!(env, GrantsError::ProgramEnded);
+        }
+        program
+    }
+
+    // Internal helper storing a program
+    fn save_program(env: &Env, program: &Program) {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Program(program.id), program);
+    }
+
+    // Internal helper to load a grant or fail
+    fn load_grant(env: &Env, program_id: u64, grantee: &Address) -> Grant {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Grant(program_id, grantee.clone()))
+            .unwrap_or_else(|| panic_with_error!(env, GrantsError::GrantNotFound))
+    }
+
+    // Internal helper storing a grant
+    fn save_grant(env: &Env, program_id: u64, grant: &Grant) {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Grant(program_id, grant.grantee.clone()), grant);
+    }
+
+    // Internal helper moving a program token with balance checks
+    fn send(env: &Env, token: &Address, from: &Address, to: &Address, amount: i128) {
+        token_io::safe_transfer(env, token, from, to, amount).unwrap_or_else(|e| match e {
+            TransferError::InvalidAmount => panic_with_error!(env, GrantsError::InvalidAmount),
+            _ => panic_with_error!(env, GrantsError::TokenTransferFailed),
+        })
+    }
+
+    // Internal helper taking the reentrancy lock for entrypoints that move
+    // program tokens
+    fn enter_guard(env: &Env) -> ReentrancyGuard {
+        ReentrancyGuard::enter(env)
+            .unwrap_or_else(|_| panic_with_error!(env, GrantsError::Reentrancy))
+    }
+
+    // Internal helper refusing to run against storage written by a different
+    // layout version
+    fn require_storage_version(env: &Env) {
+        versioning::require_version(env, STORAGE_VERSION)
+            .unwrap_or_else(|_| panic_with_error!(env, GrantsError::StorageVersionMismatch));
+    }
+
+    // Internal helper to verify admin
+    fn require_admin(env: &Env, caller: &Address) {
+        if *caller != Self::get_admin(env.clone()) {
+            panic_with_error!(env, GrantsError::Unauthorized);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
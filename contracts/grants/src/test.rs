@@ -0,0 +1,265 @@
+//! Integration tests for the grants contract.
+
+#![cfg(test)]
+
+use crate::{GrantsContract, GrantsContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, vec, Address, BytesN, Env,
+};
+
+const PROGRAM_LENGTH: u64 = 1_000;
+
+/// Creates a test environment with the contract deployed and initialized, a
+/// registered Operator, and a funder who has escrowed a 10,000 budget.
+fn setup_test_env() -> (
+    Env,
+    Address,
+    Address,
+    u64,
+    token::Client<'static>,
+    GrantsContractClient<'static>,
+) {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let asset = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let token_client = token::Client::new(&env, &asset.address());
+
+    let contract_id = env.register(GrantsContract, ());
+    let client = GrantsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let operator = Address::generate(&env);
+    client.set_operator(&admin, &operator, &true);
+
+    let funder = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &asset.address()).mint(&funder, &10_000);
+    let end_time = env.ledger().timestamp() + PROGRAM_LENGTH;
+    let program_id = client.create_program(&funder, &asset.address(), &10_000, &end_time);
+
+    (env, operator, funder, program_id, token_client, client)
+}
+
+fn report(env: &Env, byte: u8) -> BytesN<32> {
+    BytesN::from_array(env, &[byte; 32])
+}
+
+// ============================================
+// Program Tests
+// ============================================
+
+#[test]
+fn test_create_program_escrows_budget() {
+    let (_env, _operator, funder, program_id, token_client, client) = setup_test_env();
+
+    let program = client.get_program(&program_id).unwrap();
+    assert_eq!(program.funder, funder);
+    assert_eq!(program.budget, 10_000);
+    assert_eq!(token_client.balance(&client.address), 10_000);
+    assert_eq!(token_client.balance(&funder), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")]
+fn test_grants_cannot_exceed_budget() {
+    let (env, _operator, funder, program_id, _token_client, client) = setup_test_env();
+
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    client.add_grantee(&funder, &program_id, &first, &vec![&env, 6_000]);
+    client.add_grantee(&funder, &program_id, &second, &vec![&env, 3_000, 1_001]);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_only_funder_adds_grantees() {
+    let (env, _operator, _funder, program_id, _token_client, client) = setup_test_env();
+
+    let grantee = Address::generate(&env);
+    client.add_grantee(
+        &Address::generate(&env),
+        &program_id,
+        &grantee,
+        &vec![&env, 100],
+    );
+}
+
+// ============================================
+// Milestone Tests
+// ============================================
+
+#[test]
+fn test_approved_reports_release_tranches() {
+    let (env, operator, funder, program_id, token_client, client) = setup_test_env();
+
+    let grantee = Address::generate(&env);
+    client.add_grantee(&funder, &program_id, &grantee, &vec![&env, 1_000, 2_000]);
+
+    assert_eq!(
+        client.submit_report(&grantee, &program_id, &report(&env, 1)),
+        0
+    );
+    assert_eq!(
+        client.review_report(&operator, &program_id, &grantee, &true, &None),
+        1_000
+    );
+    assert_eq!(
+        client.submit_report(&grantee, &program_id, &report(&env, 2)),
+        1
+    );
+    assert_eq!(
+        client.review_report(&operator, &program_id, &grantee, &true, &None),
+        2_000
+    );
+
+    assert_eq!(token_client.balance(&grantee), 3_000);
+    let history = client.get_disbursements(&program_id, &grantee);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(1).unwrap().report_hash, report(&env, 2));
+    assert_eq!(history.get(1).unwrap().reviewer, operator);
+    assert_eq!(client.get_program(&program_id).unwrap().disbursed, 3_000);
+}
+
+#[test]
+fn test_rejected_report_can_be_resubmitted() {
+    let (env, operator, funder, program_id, token_client, client) = setup_test_env();
+
+    let grantee = Address::generate(&env);
+    client.add_grantee(&funder, &program_id, &grantee, &vec![&env, 1_000]);
+
+    client.submit_report(&grantee, &program_id, &report(&env, 1));
+    assert_eq!(
+        client.review_report(&operator, &program_id, &grantee, &false, &None),
+        0
+    );
+    assert_eq!(token_client.balance(&grantee), 0);
+
+    assert_eq!(
+        client.submit_report(&grantee, &program_id, &report(&env, 2)),
+        0
+    );
+    client.review_report(&operator, &program_id, &grantee, &true, &None);
+    assert_eq!(token_client.balance(&grantee), 1_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #11)")]
+fn test_one_report_pending_at_a_time() {
+    let (env, _operator, funder, program_id, _token_client, client) = setup_test_env();
+
+    let grantee = Address::generate(&env);
+    client.add_grantee(&funder, &program_id, &grantee, &vec![&env, 1_000, 1_000]);
+    client.submit_report(&grantee, &program_id, &report(&env, 1));
+    client.submit_report(&grantee, &program_id, &report(&env, 2));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #13)")]
+fn test_completed_grant_rejects_reports() {
+    let (env, operator, funder, program_id, _token_client, client) = setup_test_env();
+
+    let grantee = Address::generate(&env);
+    client.add_grantee(&funder, &program_id, &grantee, &vec![&env, 1_000]);
+    client.submit_report(&grantee, &program_id, &report(&env, 1));
+    client.review_report(&operator, &program_id, &grantee, &true, &None);
+    client.submit_report(&grantee, &program_id, &report(&env, 2));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #16)")]
+fn test_review_requires_operator() {
+    let (env, _operator, funder, program_id, _token_client, client) = setup_test_env();
+
+    let grantee = Address::generate(&env);
+    client.add_grantee(&funder, &program_id, &grantee, &vec![&env, 1_000]);
+    client.submit_report(&grantee, &program_id, &report(&env, 1));
+    client.review_report(&funder, &program_id, &grantee, &true, &None);
+}
+
+// ============================================
+// Program End Tests
+// ============================================
+
+#[test]
+fn test_close_returns_unspent_funds() {
+    let (env, operator, funder, program_id, token_client, client) = setup_test_env();
+
+    let grantee = Address::generate(&env);
+    client.add_grantee(&funder, &program_id, &grantee, &vec![&env, 1_000, 2_000]);
+    client.submit_report(&grantee, &program_id, &report(&env, 1));
+    client.review_report(&operator, &program_id, &grantee, &true, &None);
+
+    env.ledger().with_mut(|li| li.timestamp += PROGRAM_LENGTH);
+    assert_eq!(client.close_program(&program_id), 9_000);
+
+    assert_eq!(token_client.balance(&funder), 9_000);
+    assert_eq!(token_client.balance(&client.address), 0);
+    assert!(client.get_program(&program_id).unwrap().closed);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")]
+fn test_cannot_close_before_end() {
+    let (_env, _operator, _funder, program_id, _token_client, client) = setup_test_env();
+    client.close_program(&program_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn test_no_releases_after_end() {
+    let (env, operator, funder, program_id, _token_client, client) = setup_test_env();
+
+    let grantee = Address::generate(&env);
+    client.add_grantee(&funder, &program_id, &grantee, &vec![&env, 1_000]);
+    client.submit_report(&grantee, &program_id, &report(&env, 1));
+
+    env.ledger().with_mut(|li| li.timestamp += PROGRAM_LENGTH);
+    client.review_report(&operator, &program_id, &grantee, &true, &None);
+}
+
+// ============================================
+// Idempotency Tests
+// ============================================
+
+#[test]
+fn test_review_retry_with_key_releases_once() {
+    let (env, operator, funder, program_id, token_client, client) = setup_test_env();
+
+    let grantee = Address::generate(&env);
+    client.add_grantee(&funder, &program_id, &grantee, &vec![&env, 1_000, 2_000]);
+    client.submit_report(&grantee, &program_id, &report(&env, 1));
+
+    let key = Some(report(&env, 9));
+    assert_eq!(
+        client.review_report(&operator, &program_id, &grantee, &true, &key),
+        1_000
+    );
+    // The retry is answered from the record, not with NoPendingReport
+    assert_eq!(
+        client.review_report(&operator, &program_id, &grantee, &true, &key),
+        1_000
+    );
+
+    assert_eq!(token_client.balance(&grantee), 1_000);
+    assert_eq!(client.get_disbursements(&program_id, &grantee).len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #22)")]
+fn test_review_key_reused_for_different_grantee() {
+    let (env, operator, funder, program_id, _token_client, client) = setup_test_env();
+
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    client.add_grantee(&funder, &program_id, &first, &vec![&env, 1_000]);
+    client.add_grantee(&funder, &program_id, &second, &vec![&env, 1_000]);
+    client.submit_report(&first, &program_id, &report(&env, 1));
+    client.submit_report(&second, &program_id, &report(&env, 2));
+
+    let key = Some(report(&env, 9));
+    client.review_report(&operator, &program_id, &first, &true, &key);
+    client.review_report(&operator, &program_id, &second, &true, &key);
+}
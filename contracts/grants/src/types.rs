@@ -0,0 +1,164 @@
+//! Data types and events for the grants contract.
+
+use common::events;
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Vec};
+
+/// Maximum number of milestones (and so tranches) in a single grant.
+pub const MAX_MILESTONES: u32 = 20;
+
+/// Maximum number of grantees in a single program.
+pub const MAX_GRANTEES: u32 = 50;
+
+/// A funder's program budget, held in escrow until disbursed or returned.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Program {
+    pub id: u64,
+    pub funder: Address,
+    /// Token the budget is held in
+    pub token: Address,
+    /// Total escrowed budget
+    pub budget: i128,
+    /// Sum of tranches promised to grantees
+    pub allocated: i128,
+    /// Sum of tranches released so far
+    pub disbursed: i128,
+    /// Timestamp after which no tranches are released and the program can close
+    pub end_time: u64,
+    /// Whether unspent funds have been returned to the funder
+    pub closed: bool,
+}
+
+/// A grantee's award within a program, released tranche by tranche.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Grant {
+    pub grantee: Address,
+    /// Amount released for each milestone, in order
+    pub tranches: Vec<i128>,
+    /// Index of the next milestone to be reported
+    pub next_milestone: u32,
+    /// Report hash awaiting review for the next milestone
+    pub pending_report: Option<BytesN<32>>,
+    /// Sum of tranches released so far
+    pub disbursed: i128,
+}
+
+/// A released tranche.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Disbursement {
+    pub milestone: u32,
+    pub amount: i128,
+    /// Hash of the milestone report the tranche was released against
+    pub report_hash: BytesN<32>,
+    /// Operator who approved the report
+    pub reviewer: Address,
+    pub released_at: u64,
+}
+
+/// Storage keys for the grants contract.
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    /// Admin address
+    Admin,
+    /// Whether an address may review milestone reports
+    Operator(Address),
+    /// Program by id
+    Program(u64),
+    /// Grantees of a program, in the order they were added
+    Grantees(u64),
+    /// A grantee's award in a program
+    Grant(u64, Address),
+    /// Tranches released to a grantee in a program
+    Disbursements(u64, Address),
+}
+
+/// Event emitters for grants operations.
+pub struct GrantEvents;
+
+impl GrantEvents {
+    /// Emitted when a funder escrows a program budget.
+    pub fn program_created(env: &Env, program: &Program) {
+        let topics = (
+            symbol_short!("grants"),
+            symbol_short!("program"),
+            program.id,
+        );
+        events::publish(
+            env,
+            topics,
+            (program.funder.clone(), program.budget, program.end_time),
+        );
+    }
+
+    /// Emitted when a grantee is awarded a grant.
+    pub fn grant_added(env: &Env, program_id: u64, grantee: &Address, total: i128) {
+        let topics = (
+            symbol_short!("grants"),
+            symbol_short!("awarded"),
+            program_id,
+        );
+        events::publish(env, topics, (grantee.clone(), total));
+    }
+
+    /// Emitted when a grantee submits a milestone report.
+    pub fn report_submitted(
+        env: &Env,
+        program_id: u64,
+        grantee: &Address,
+        milestone: u32,
+        report_hash: &BytesN<32>,
+    ) {
+        let topics = (symbol_short!("grants"), symbol_short!("report"), program_id);
+        events::publish(
+            env,
+            topics,
+            (grantee.clone(), milestone, report_hash.clone()),
+        );
+    }
+
+    /// Emitted when an Operator rejects a milestone report.
+    pub fn report_rejected(
+        env: &Env,
+        program_id: u64,
+        grantee: &Address,
+        milestone: u32,
+        reviewer: &Address,
+    ) {
+        let topics = (
+            symbol_short!("grants"),
+            symbol_short!("rejected"),
+            program_id,
+        );
+        events::publish(env, topics, (grantee.clone(), milestone, reviewer.clone()));
+    }
+
+    /// Emitted when a tranche is released to a grantee.
+    pub fn tranche_released(
+        env: &Env,
+        program_id: u64,
+        grantee: &Address,
+        disbursement: &Disbursement,
+    ) {
+        let topics = (
+            symbol_short!("grants"),
+            symbol_short!("released"),
+            program_id,
+        );
+        events::publish(env, topics, (grantee.clone(), disbursement.clone()));
+    }
+
+    /// Emitted when a program closes and unspent funds return to the funder.
+    pub fn program_closed(env: &Env, program: &Program, returned: i128) {
+        let topics = (symbol_short!("grants"), symbol_short!("closed"), program.id);
+        events::publish(env, topics, (program.funder.clone(), returned));
+    }
+
+    /// Emitted when an Operator is registered or removed.
+    pub fn operator_set(env: &Env, operator: &Address, enabled: bool) {
+        let topics = (symbol_short!("grants"), symbol_short!("operator"));
+        events::publish(env, topics, (operator.clone(), enabled));
+    }
+}
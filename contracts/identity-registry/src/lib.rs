@@ -0,0 +1,264 @@
+//! # Identity Registry Contract
+//!
+//! Links addresses to verified identifiers (email, phone) without putting
+//! personal data on-chain. A registered verifier checks the identifier
+//! off-chain and, with the subject's consent, records a salted hash of it
+//! here. Payroll and compliance flows can then require a verified identity by
+//! asking whether an address has a live attestation.
+//!
+//! ## Features
+//!
+//! - **Verifier Registry**: Only admin-registered verifiers can attest
+//! - **Re-Verification**: Attestations expire and must be renewed
+//! - **Revocation**: The verifier, the subject or the admin can revoke
+//! - **One Address per Identifier**: An identifier hash links to one subject
+#![no_std]
+
+mod types;
+
+use common::versioning;
+use soroban_sdk::{contract, contractimpl, panic_with_error, Address, BytesN, Env};
+
+pub use crate::types::{
+    Attestation, DataKey, IdentifierKind, IdentityEvents, MAX_ATTESTATION_SECS,
+};
+
+/// Error codes for the identity registry contract.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum IdentityError {
+    /// Contract not initialized
+    NotInitialized = 1,
+    /// Caller is not authorized
+    Unauthorized = 2,
+    /// Contract already initialized
+    AlreadyInitialized = 3,
+    /// Caller is not a registered verifier
+    NotVerifier = 4,
+    /// Validity period is zero or too long
+    InvalidValidity = 5,
+    /// Identifier is linked to another address
+    IdentifierLinked = 6,
+    /// Subject has no attestation of this kind
+    NotAttested = 7,
+    /// Storage was written by a different layout version
+    StorageVersionMismatch = 8,
+    /// Migration source version does not match stored version
+    InvalidMigration = 9,
+}
+
+impl From<IdentityError> for soroban_sdk::Error {
+    fn from(e: IdentityError) -> Self {
+        soroban_sdk::Error::from_contract_error(e as u32)
+    }
+}
+
+/// Storage layout version this code expects.
+pub const STORAGE_VERSION: u32 = 1;
+
+#[contract]
+pub struct IdentityRegistryContract;
+
+#[contractimpl]
+impl IdentityRegistryContract {
+    /// Initializes the contract with an admin address.
+    pub fn initialize(env: Env, admin: Address) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic_with_error!(&env, IdentityError::AlreadyInitialized);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        versioning::set_version(&env, STORAGE_VERSION);
+    }
+
+    /// Migrates storage written by an older version of this contract to the
+    /// current layout (admin only). `from_version` must match the stored
+    /// version.
+    pub fn migrate(env: Env, caller: Address, from_version: u32) {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        versioning::migrate(&env, from_version, STORAGE_VERSION, |_, _| {})
+            .unwrap_or_else(|_| panic_with_error!(&env, IdentityError::InvalidMigration));
+    }
+
+    /// Returns the storage layout version currently stored.
+    pub fn get_storage_version(env: Env) -> u32 {
+        versioning::stored_version(&env)
+    }
+
+    /// Registers or removes a verifier (admin only). Attestations by a removed
+    /// verifier stop counting as verified.
+    pub fn set_verifier(env: Env, caller: Address, verifier: Address, enabled: bool) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        if enabled {
+            env.storage()
+                .persistent()
+                .set(&DataKey::Verifier(verifier.clone()), &true);
+        } else {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::Verifier(verifier.clone()));
+        }
+
+        IdentityEvents::verifier_set(&env, &verifier, enabled);
+    }
+
+    /// Records that `subject` controls the identifier behind
+    /// `identifier_hash`, valid for `valid_for` seconds (registered verifiers
+    /// only, with the subject's consent).
+    ///
+    /// Attesting again replaces the subject's attestation of this kind, which
+    /// is how an identity is re-verified or changed. An identifier linked to
+    /// another address by a live attestation cannot be attested.
+    pub fn attest(
+        env: Env,
+        verifier: Address,
+        subject: Address,
+        kind: IdentifierKind,
+        identifier_hash: BytesN<32>,
+        valid_for: u64,
+    ) -> Attestation {
+        Self::require_storage_version(&env);
+        verifier.require_auth();
+        subject.require_auth();
+        if !Self::is_verifier(env.clone(), verifier.clone()) {
+            panic_with_error!(&env, IdentityError::NotVerifier);
+        }
+        if valid_for == 0 || valid_for > MAX_ATTESTATION_SECS {
+            panic_with_error!(&env, IdentityError::InvalidValidity);
+        }
+
+        let link = DataKey::Link(kind, identifier_hash.clone());
+        let linked: Option<Address> = env.storage().persistent().get(&link);
+        if let Some(owner) = linked {
+            if owner != subject && Self::is_verified(env.clone(), owner, kind) {
+                panic_with_error!(&env, IdentityError::IdentifierLinked);
+            }
+        }
+
+        // Drop the link of the identifier being replaced
+        if let Some(previous) = Self::get_attestation(env.clone(), subject.clone(), kind) {
+            Self::unlink(&env, &subject, kind, &previous);
+        }
+
+        let now = env.ledger().timestamp();
+        let attestation = Attestation {
+            identifier_hash,
+            verifier,
+            verified_at: now,
+            expires_at: now + valid_for,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Attestation(subject.clone(), kind), &attestation);
+        env.storage().persistent().set(&link, &subject);
+
+        IdentityEvents::attested(&env, &subject, kind, &attestation);
+
+        attestation
+    }
+
+    /// Revokes a subject's attestation of one kind. Callable by the verifier
+    /// that attested it, the subject or the admin.
+    pub fn revoke(env: Env, caller: Address, subject: Address, kind: IdentifierKind) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+
+        let attestation = Self::get_attestation(env.clone(), subject.clone(), kind)
+            .unwrap_or_else(|| panic_with_error!(&env, IdentityError::NotAttested));
+        if caller != attestation.verifier && caller != subject {
+            Self::require_admin(&env, &caller);
+        }
+
+        Self::unlink(&env, &subject, kind, &attestation);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Attestation(subject.clone(), kind));
+
+        IdentityEvents::revoked(&env, &subject, kind, &caller);
+    }
+
+    /// Returns whether `subject` has a live attestation of this kind: not
+    /// expired, and by a verifier that is still registered.
+    pub fn is_verified(env: Env, subject: Address, kind: IdentifierKind) -> bool {
+        match Self::get_attestation(env.clone(), subject, kind) {
+            Some(attestation) => {
+                env.ledger().timestamp() < attestation.expires_at
+                    && Self::is_verifier(env, attestation.verifier)
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the address an identifier hash is linked to, if the link is
+    /// still verified.
+    pub fn resolve(env: Env, kind: IdentifierKind, identifier_hash: BytesN<32>) -> Option<Address> {
+        let subject: Address = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Link(kind, identifier_hash))?;
+        if Self::is_verified(env, subject.clone(), kind) {
+            Some(subject)
+        } else {
+            None
+        }
+    }
+
+    /// Returns a subject's attestation of one kind, live or expired.
+    pub fn get_attestation(
+        env: Env,
+        subject: Address,
+        kind: IdentifierKind,
+    ) -> Option<Attestation> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Attestation(subject, kind))
+    }
+
+    /// Returns whether `verifier` is registered.
+    pub fn is_verifier(env: Env, verifier: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Verifier(verifier))
+            .unwrap_or(false)
+    }
+
+    /// Returns the admin address.
+    pub fn get_admin(env: Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(&env, IdentityError::NotInitialized))
+    }
+
+    // Internal helper removing an identifier's link if it still points at
+    // `subject`
+    fn unlink(env: &Env, subject: &Address, kind: IdentifierKind, attestation: &Attestation) {
+        let link = DataKey::Link(kind, attestation.identifier_hash.clone());
+        let linked: Option<Address> = env.storage().persistent().get(&link);
+        if linked.as_ref() == Some(subject) {
+            env.storage().persistent().remove(&link);
+        }
+    }
+
+    // Internal helper refusing to run against storage written by a different
+    // layout version
+    fn require_storage_version(env: &Env) {
+        versioning::require_version(env, STORAGE_VERSION)
+            .unwrap_or_else(|_| panic_with_error!(env, IdentityError::StorageVersionMismatch));
+    }
+
+    // Internal helper to verify admin
+    fn require_admin(env: &Env, caller: &Address) {
+        if *caller != Self::get_admin(env.clone()) {
+            panic_with_error!(env, IdentityError::Unauthorized);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,187 @@
+//! Integration tests for the identity registry contract.
+
+#![cfg(test)]
+
+use crate::{
+    IdentifierKind, IdentityError, IdentityRegistryContract, IdentityRegistryContractClient,
+};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, BytesN, Env,
+};
+
+/// Creates a test environment with the contract deployed and initialized and
+/// a registered verifier.
+fn setup_test_env() -> (
+    Env,
+    Address,
+    Address,
+    IdentityRegistryContractClient<'static>,
+) {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let contract_id = env.register(IdentityRegistryContract, ());
+    let client = IdentityRegistryContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let verifier = Address::generate(&env);
+    client.set_verifier(&admin, &verifier, &true);
+
+    (env, admin, verifier, client)
+}
+
+fn hash(env: &Env, byte: u8) -> BytesN<32> {
+    BytesN::from_array(env, &[byte; 32])
+}
+
+#[test]
+fn test_attestation_expires_until_reverified() {
+    let (env, _admin, verifier, client) = setup_test_env();
+    let user = Address::generate(&env);
+
+    client.attest(
+        &verifier,
+        &user,
+        &IdentifierKind::Email,
+        &hash(&env, 1),
+        &500,
+    );
+    assert!(client.is_verified(&user, &IdentifierKind::Email));
+    assert!(!client.is_verified(&user, &IdentifierKind::Phone));
+    assert_eq!(
+        client.resolve(&IdentifierKind::Email, &hash(&env, 1)),
+        Some(user.clone())
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 1_500);
+    assert!(!client.is_verified(&user, &IdentifierKind::Email));
+    assert_eq!(client.resolve(&IdentifierKind::Email, &hash(&env, 1)), None);
+
+    let renewed = client.attest(
+        &verifier,
+        &user,
+        &IdentifierKind::Email,
+        &hash(&env, 1),
+        &500,
+    );
+    assert_eq!(renewed.expires_at, 2_000);
+    assert!(client.is_verified(&user, &IdentifierKind::Email));
+}
+
+#[test]
+fn test_identifier_links_to_one_address() {
+    let (env, _admin, verifier, client) = setup_test_env();
+    let user = Address::generate(&env);
+    let other = Address::generate(&env);
+    client.attest(
+        &verifier,
+        &user,
+        &IdentifierKind::Phone,
+        &hash(&env, 2),
+        &500,
+    );
+
+    let result = client.try_attest(
+        &verifier,
+        &other,
+        &IdentifierKind::Phone,
+        &hash(&env, 2),
+        &500,
+    );
+    assert_eq!(result, Err(Ok(IdentityError::IdentifierLinked.into())));
+
+    // Once the user moves to a new number, the old one is free again
+    client.attest(
+        &verifier,
+        &user,
+        &IdentifierKind::Phone,
+        &hash(&env, 3),
+        &500,
+    );
+    client.attest(
+        &verifier,
+        &other,
+        &IdentifierKind::Phone,
+        &hash(&env, 2),
+        &500,
+    );
+    assert_eq!(
+        client.resolve(&IdentifierKind::Phone, &hash(&env, 2)),
+        Some(other)
+    );
+}
+
+#[test]
+fn test_revocation_by_subject_or_verifier() {
+    let (env, _admin, verifier, client) = setup_test_env();
+    let user = Address::generate(&env);
+    client.attest(
+        &verifier,
+        &user,
+        &IdentifierKind::Email,
+        &hash(&env, 1),
+        &500,
+    );
+    client.attest(
+        &verifier,
+        &user,
+        &IdentifierKind::Phone,
+        &hash(&env, 2),
+        &500,
+    );
+
+    client.revoke(&user, &user, &IdentifierKind::Email);
+    client.revoke(&verifier, &user, &IdentifierKind::Phone);
+
+    assert_eq!(client.get_attestation(&user, &IdentifierKind::Email), None);
+    assert!(!client.is_verified(&user, &IdentifierKind::Phone));
+    assert_eq!(client.resolve(&IdentifierKind::Phone, &hash(&env, 2)), None);
+
+    let result = client.try_revoke(&user, &user, &IdentifierKind::Email);
+    assert_eq!(result, Err(Ok(IdentityError::NotAttested.into())));
+}
+
+#[test]
+fn test_removed_verifier_attestations_lapse() {
+    let (env, admin, verifier, client) = setup_test_env();
+    let user = Address::generate(&env);
+    client.attest(
+        &verifier,
+        &user,
+        &IdentifierKind::Email,
+        &hash(&env, 1),
+        &500,
+    );
+
+    client.set_verifier(&admin, &verifier, &false);
+    assert!(!client.is_verified(&user, &IdentifierKind::Email));
+
+    let result = client.try_attest(
+        &verifier,
+        &user,
+        &IdentifierKind::Email,
+        &hash(&env, 1),
+        &500,
+    );
+    assert_eq!(result, Err(Ok(IdentityError::NotVerifier.into())));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_stranger_cannot_revoke() {
+    let (env, _admin, verifier, client) = setup_test_env();
+    let user = Address::generate(&env);
+    client.attest(
+        &verifier,
+        &user,
+        &IdentifierKind::Email,
+        &hash(&env, 1),
+        &500,
+    );
+
+    client.revoke(&Address::generate(&env), &user, &IdentifierKind::Email);
+}
@@ -0,0 +1,69 @@
+//! Data types and events for the identity registry contract.
+
+use common::events;
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env};
+
+/// Longest time an attestation stays valid before re-verification (1 year),
+/// in seconds.
+pub const MAX_ATTESTATION_SECS: u64 = 365 * 86_400;
+
+/// Kind of identifier an attestation covers.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum IdentifierKind {
+    Email,
+    Phone,
+}
+
+/// A verifier's statement that an address controls an identifier.
+///
+/// Only a salted hash of the identifier is stored; the salt stays off-chain
+/// with the verifier and the subject.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Attestation {
+    /// Salted hash of the verified identifier
+    pub identifier_hash: BytesN<32>,
+    /// Verifier that checked the identifier
+    pub verifier: Address,
+    pub verified_at: u64,
+    /// Ledger timestamp after which the identifier must be verified again
+    pub expires_at: u64,
+}
+
+/// Storage keys for the identity registry contract.
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    /// Admin address
+    Admin,
+    /// Whether an address may attest identities
+    Verifier(Address),
+    /// Attestation by subject and identifier kind
+    Attestation(Address, IdentifierKind),
+    /// Subject an identifier hash is linked to
+    Link(IdentifierKind, BytesN<32>),
+}
+
+/// Event emitters for identity registry operations.
+pub struct IdentityEvents;
+
+impl IdentityEvents {
+    /// Emitted when an identity is attested or re-verified.
+    pub fn attested(env: &Env, subject: &Address, kind: IdentifierKind, attestation: &Attestation) {
+        let topics = (symbol_short!("identity"), symbol_short!("attested"));
+        events::publish(env, topics, (subject.clone(), kind, attestation.clone()));
+    }
+
+    /// Emitted when an attestation is revoked.
+    pub fn revoked(env: &Env, subject: &Address, kind: IdentifierKind, by: &Address) {
+        let topics = (symbol_short!("identity"), symbol_short!("revoked"));
+        events::publish(env, topics, (subject.clone(), kind, by.clone()));
+    }
+
+    /// Emitted when a verifier is registered or removed.
+    pub fn verifier_set(env: &Env, verifier: &Address, enabled: bool) {
+        let topics = (symbol_short!("identity"), symbol_short!("verifier"));
+        events::publish(env, topics, (verifier.clone(), enabled));
+    }
+}
@@ -0,0 +1,300 @@
+//! # Merkle Airdrop Contract
+//!
+//! Distributes reward tokens to many recipients without a transfer per
+//! recipient. The admin escrows the rewards from a pool and publishes the
+//! Merkle root of the `(address, amount)` list; each recipient then claims
+//! its own amount with a proof. Whatever is unclaimed when the claim window
+//! ends sweeps back to the pool.
+//!
+//! ## Features
+//!
+//! - **Escrowed Rewards**: The full distribution is held by the contract up front
+//! - **Proof Claims**: Recipients claim once each, with a Merkle proof
+//! - **Claim Window**: Claims close at a fixed time, after which anyone can sweep
+#![no_std]
+
+mod types;
+
+use common::ids::{self, IdScope};
+use common::merkle;
+use common::reentrancy::ReentrancyGuard;
+use common::token_io::{self, TransferError};
+use common::versioning;
+use soroban_sdk::{
+    contract, contractimpl, panic_with_error, symbol_short, Address, BytesN, Env, Vec,
+};
+
+pub use crate::types::{Airdrop, AirdropEvents, DataKey};
+
+/// Error codes for the Merkle airdrop contract.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum AirdropError {
+    /// Contract not initialized
+    NotInitialized = 1,
+    /// Caller is not authorized
+    Unauthorized = 2,
+    /// Contract already initialized
+    AlreadyInitialized = 3,
+    /// Amount must be positive
+    InvalidAmount = 4,
+    /// Claim window end is not in the future
+    InvalidClaimWindow = 5,
+    /// Airdrop does not exist
+    AirdropNotFound = 6,
+    /// Claim window has ended
+    ClaimWindowClosed = 7,
+    /// Claim window has not ended yet
+    ClaimWindowOpen = 8,
+    /// Recipient has already claimed from the airdrop
+    AlreadyClaimed = 9,
+    /// Proof does not lead to the airdrop's root
+    InvalidProof = 10,
+    /// Claim exceeds the airdrop's remaining funds
+    InsufficientFunds = 11,
+    /// Unclaimed funds have already been swept
+    AlreadySwept = 12,
+    /// Token transfer did not move the expected amount
+    TokenTransferFailed = 13,
+    /// Airdrop ids are exhausted
+    IdsExhausted = 14,
+    /// Storage was written by a different layout version
+    StorageVersionMismatch = 15,
+    /// Migration source version does not match stored version
+    InvalidMigration = 16,
+    /// Entrypoint was re-entered during an external call
+    Reentrancy = 17,
+}
+
+impl From<AirdropError> for soroban_sdk::Error {
+    fn from(e: AirdropError) -> Self {
+        soroban_sdk::Error::from_contract_error(e as u32)
+    }
+}
+
+/// Storage layout version this code expects.
+pub const STORAGE_VERSION: u32 = 1;
+
+#[contract]
+pub struct MerkleAirdropContract;
+
+#[contractimpl]
+impl MerkleAirdropContract {
+    /// Initializes the contract with an admin address.
+    pub fn initialize(env: Env, admin: Address) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic_with_error!(&env, AirdropError::AlreadyInitialized);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        versioning::set_version(&env, STORAGE_VERSION);
+    }
+
+    /// Migrates storage written by an older version of this contract to the
+    /// current layout (admin only). `from_version` must match the stored
+    /// version.
+    pub fn migrate(env: Env, caller: Address, from_version: u32) {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        versioning::migrate(&env, from_version, STORAGE_VERSION, |_, _| {})
+            .unwrap_or_else(|_| panic_with_error!(&env, AirdropError::InvalidMigration));
+    }
+
+    /// Returns the storage layout version currently stored.
+    pub fn get_storage_version(env: Env) -> u32 {
+        versioning::stored_version(&env)
+    }
+
+    /// Escrows `total` of `token` from `pool` and opens an airdrop claimable
+    /// against `root` until `claim_ends_at` (admin only; the pool must also
+    /// authorize).
+    ///
+    /// # Returns
+    /// * The new airdrop id
+    pub fn create_airdrop(
+        env: Env,
+        caller: Address,
+        pool: Address,
+        token: Address,
+        root: BytesN<32>,
+        total: i128,
+        claim_ends_at: u64,
+    ) -> u64 {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+        let _guard = Self::enter_guard(&env);
+        pool.require_auth();
+
+        if total <= 0 {
+            panic_with_error!(&env, AirdropError::InvalidAmount);
+        }
+        if claim_ends_at <= env.ledger().timestamp() {
+            panic_with_error!(&env, AirdropError::InvalidClaimWindow);
+        }
+
+        let id = ids::next_id(&env, &IdScope::new(0, symbol_short!("airdrop")))
+            .unwrap_or_else(|_| panic_with_error!(&env, AirdropError::IdsExhausted));
+        let contract = env.current_contract_address();
+        Self::send(&env, &token, &pool, &contract, total);
+
+        let airdrop = Airdrop {
+            id,
+            pool,
+            token,
+            root,
+            total,
+            claimed: 0,
+            claim_ends_at,
+            swept: false,
+        };
+        Self::save_airdrop(&env, &airdrop);
+
+        AirdropEvents::created(&env, &airdrop);
+
+        id
+    }
+
+    /// Pays `recipient` its `amount` from an airdrop, given a proof that
+    /// `(recipient, amount)` is a leaf of the airdrop's root. Each recipient
+    /// claims once, before the claim window ends.
+    pub fn claim(
+        env: Env,
+        recipient: Address,
+        airdrop_id: u64,
+        amount: i128,
+        proof: Vec<BytesN<32>>,
+    ) {
+        Self::require_storage_version(&env);
+        recipient.require_auth();
+        let _guard = Self::enter_guard(&env);
+
+        let mut airdrop = Self::load_airdrop(&env, airdrop_id);
+        if env.ledger().timestamp() >= airdrop.claim_ends_at {
+            panic_with_error!(&env, AirdropError::ClaimWindowClosed);
+        }
+        let claimed = DataKey::Claimed(airdrop_id, recipient.clone());
+        if env.storage().persistent().has(&claimed) {
+            panic_with_error!(&env, AirdropError::AlreadyClaimed);
+        }
+        if amount <= 0 {
+            panic_with_error!(&env, AirdropError::InvalidAmount);
+        }
+        let leaf = merkle::leaf(&env, (recipient.clone(), amount));
+        if !merkle::verify(&env, leaf, &proof, &airdrop.root) {
+            panic_with_error!(&env, AirdropError::InvalidProof);
+        }
+        // Guards against a root listing more than was escrowed
+        if amount > airdrop.total - airdrop.claimed {
+            panic_with_error!(&env, AirdropError::InsufficientFunds);
+        }
+
+        airdrop.claimed += amount;
+        Self::save_airdrop(&env, &airdrop);
+        env.storage().persistent().set(&claimed, &true);
+
+        let contract = env.current_contract_address();
+        Self::send(&env, &airdrop.token, &contract, &recipient, amount);
+
+        AirdropEvents::claimed(&env, airdrop_id, &recipient, amount);
+    }
+
+    /// Returns an airdrop's unclaimed funds to its pool once the claim window
+    /// has ended. Anyone may call this, as the funds can only go to the pool.
+    ///
+    /// # Returns
+    /// * The amount returned
+    pub fn sweep(env: Env, airdrop_id: u64) -> i128 {
+        Self::require_storage_version(&env);
+        let _guard = Self::enter_guard(&env);
+
+        let mut airdrop = Self::load_airdrop(&env, airdrop_id);
+        if airdrop.swept {
+            panic_with_error!(&env, AirdropError::AlreadySwept);
+        }
+        if env.ledger().timestamp() < airdrop.claim_ends_at {
+            panic_with_error!(&env, AirdropError::ClaimWindowOpen);
+        }
+
+        let returned = airdrop.total - airdrop.claimed;
+        airdrop.swept = true;
+        Self::save_airdrop(&env, &airdrop);
+
+        if returned > 0 {
+            let contract = env.current_contract_address();
+            Self::send(&env, &airdrop.token, &contract, &airdrop.pool, returned);
+        }
+
+        AirdropEvents::swept(&env, &airdrop, returned);
+
+        returned
+    }
+
+    /// Returns an airdrop, if it exists.
+    pub fn get_airdrop(env: Env, airdrop_id: u64) -> Option<Airdrop> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Airdrop(airdrop_id))
+    }
+
+    /// Returns whether `recipient` has claimed from an airdrop.
+    pub fn is_claimed(env: Env, airdrop_id: u64, recipient: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::Claimed(airdrop_id, recipient))
+    }
+
+    /// Returns the admin address.
+    pub fn get_admin(env: Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(&env, AirdropError::NotInitialized))
+    }
+
+    // Internal helper loading an airdrop that must exist
+    fn load_airdrop(env: &Env, airdrop_id: u64) -> Airdrop {
+        Self::get_airdrop(env.clone(), airdrop_id)
+            .unwrap_or_else(|| panic_with_error!(env, AirdropError::AirdropNotFound))
+    }
+
+    // Internal helper storing an airdrop
+    fn save_airdrop(env: &Env, airdrop: &Airdrop) {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Airdrop(airdrop.id), airdrop);
+    }
+
+    // Internal helper moving an airdrop token with balance checks
+    fn send(env: &Env, token: &Address, from: &Address, to: &Address, amount: i128) {
+        token_io::safe_transfer(env, token, from, to, amount).unwrap_or_else(|e| match e {
+            TransferError::InvalidAmount => panic_with_error!(env, AirdropError::InvalidAmount),
+            _ => panic_with_error!(env, AirdropError::TokenTransferFailed),
+        })
+    }
+
+    // Internal helper taking the reentrancy lock for entrypoints that move
+    // airdrop tokens
+    fn enter_guard(env: &Env) -> ReentrancyGuard {
+        ReentrancyGuard::enter(env)
+            .unwrap_or_else(|_| panic_with_error!(env, AirdropError::Reentrancy))
+    }
+
+    // Internal helper refusing to run against storage written by a different
+    // layout version
+    fn require_storage_version(env: &Env) {
+        versioning::require_version(env, STORAGE_VERSION)
+            .unwrap_or_else(|_| panic_with_error!(env, AirdropError::StorageVersionMismatch));
+    }
+
+    // Internal helper to verify admin
+    fn require_admin(env: &Env, caller: &Address) {
+        if *caller != Self::get_admin(env.clone()) {
+            panic_with_error!(env, AirdropError::Unauthorized);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
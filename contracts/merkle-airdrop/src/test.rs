@@ -0,0 +1,158 @@
+//! Integration tests for the Merkle airdrop contract.
+
+#![cfg(test)]
+
+use crate::{AirdropError, MerkleAirdropContract, MerkleAirdropContractClient};
+use common::merkle;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, vec, Address, BytesN, Env,
+};
+
+const CLAIM_WINDOW: u64 = 1_000;
+
+struct Setup {
+    env: Env,
+    admin: Address,
+    pool: Address,
+    alice: Address,
+    bob: Address,
+    carol: Address,
+    token: token::Client<'static>,
+    client: MerkleAirdropContractClient<'static>,
+}
+
+/// Creates a test environment with the contract deployed and initialized and
+/// a rewards pool holding 1,000 tokens.
+fn setup_test_env() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let asset = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let token = token::Client::new(&env, &asset.address());
+
+    let client = MerkleAirdropContractClient::new(&env, &env.register(MerkleAirdropContract, ()));
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let pool = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &asset.address()).mint(&pool, &1_000);
+
+    Setup {
+        alice: Address::generate(&env),
+        bob: Address::generate(&env),
+        carol: Address::generate(&env),
+        env,
+        admin,
+        pool,
+        token,
+        client,
+    }
+}
+
+fn leaf(env: &Env, recipient: &Address, amount: i128) -> BytesN<32> {
+    merkle::leaf(env, (recipient.clone(), amount))
+}
+
+/// Opens an airdrop of 100 to alice, 200 to bob and 300 to carol, escrowing
+/// `total`.
+fn create_airdrop(s: &Setup, total: i128) -> u64 {
+    let env = &s.env;
+    let branch = merkle::parent(env, &leaf(env, &s.alice, 100), &leaf(env, &s.bob, 200));
+    let root = merkle::parent(env, &branch, &leaf(env, &s.carol, 300));
+    let claim_ends_at = env.ledger().timestamp() + CLAIM_WINDOW;
+    s.client.create_airdrop(
+        &s.admin,
+        &s.pool,
+        &s.token.address,
+        &root,
+        &total,
+        &claim_ends_at,
+    )
+}
+
+#[test]
+fn test_claims_and_sweep_after_window() {
+    let s = setup_test_env();
+    let env = &s.env;
+    let airdrop_id = create_airdrop(&s, 600);
+    assert_eq!(s.token.balance(&s.pool), 400);
+
+    let proof = vec![env, leaf(env, &s.bob, 200), leaf(env, &s.carol, 300)];
+    s.client.claim(&s.alice, &airdrop_id, &100, &proof);
+    let branch = merkle::parent(env, &leaf(env, &s.alice, 100), &leaf(env, &s.bob, 200));
+    s.client
+        .claim(&s.carol, &airdrop_id, &300, &vec![env, branch]);
+    assert_eq!(s.token.balance(&s.alice), 100);
+    assert_eq!(s.token.balance(&s.carol), 300);
+    assert!(s.client.is_claimed(&airdrop_id, &s.alice));
+    assert!(!s.client.is_claimed(&airdrop_id, &s.bob));
+
+    let result = s.client.try_claim(&s.alice, &airdrop_id, &100, &proof);
+    assert_eq!(result, Err(Ok(AirdropError::AlreadyClaimed.into())));
+    let result = s.client.try_sweep(&airdrop_id);
+    assert_eq!(result, Err(Ok(AirdropError::ClaimWindowOpen.into())));
+
+    // Bob misses the window; his share returns to the pool
+    env.ledger().with_mut(|li| li.timestamp += CLAIM_WINDOW);
+    let proof = vec![env, leaf(env, &s.alice, 100), leaf(env, &s.carol, 300)];
+    let result = s.client.try_claim(&s.bob, &airdrop_id, &200, &proof);
+    assert_eq!(result, Err(Ok(AirdropError::ClaimWindowClosed.into())));
+
+    assert_eq!(s.client.sweep(&airdrop_id), 200);
+    assert_eq!(s.token.balance(&s.pool), 600);
+    assert_eq!(s.token.balance(&s.client.address), 0);
+    let airdrop = s.client.get_airdrop(&airdrop_id).unwrap();
+    assert_eq!(airdrop.claimed, 400);
+    assert!(airdrop.swept);
+    let result = s.client.try_sweep(&airdrop_id);
+    assert_eq!(result, Err(Ok(AirdropError::AlreadySwept.into())));
+}
+
+#[test]
+fn test_claim_rejects_wrong_amount_or_proof() {
+    let s = setup_test_env();
+    let env = &s.env;
+    let airdrop_id = create_airdrop(&s, 600);
+    let proof = vec![env, leaf(env, &s.bob, 200), leaf(env, &s.carol, 300)];
+
+    let result = s.client.try_claim(&s.alice, &airdrop_id, &150, &proof);
+    assert_eq!(result, Err(Ok(AirdropError::InvalidProof.into())));
+    let result = s.client.try_claim(&s.bob, &airdrop_id, &100, &proof);
+    assert_eq!(result, Err(Ok(AirdropError::InvalidProof.into())));
+    assert_eq!(s.token.balance(&s.client.address), 600);
+}
+
+#[test]
+fn test_claim_cannot_exceed_escrow() {
+    let s = setup_test_env();
+    let env = &s.env;
+    let airdrop_id = create_airdrop(&s, 350);
+
+    let branch = merkle::parent(env, &leaf(env, &s.alice, 100), &leaf(env, &s.bob, 200));
+    s.client
+        .claim(&s.carol, &airdrop_id, &300, &vec![env, branch]);
+    let proof = vec![env, leaf(env, &s.alice, 100), leaf(env, &s.carol, 300)];
+    let result = s.client.try_claim(&s.bob, &airdrop_id, &200, &proof);
+    assert_eq!(result, Err(Ok(AirdropError::InsufficientFunds.into())));
+}
+
+#[test]
+fn test_only_admin_creates_airdrops() {
+    let s = setup_test_env();
+    let root = BytesN::from_array(&s.env, &[1; 32]);
+
+    let result = s.client.try_create_airdrop(
+        &Address::generate(&s.env),
+        &s.pool,
+        &s.token.address,
+        &root,
+        &100,
+        &CLAIM_WINDOW,
+    );
+    assert_eq!(result, Err(Ok(AirdropError::Unauthorized.into())));
+    let result = s
+        .client
+        .try_create_airdrop(&s.admin, &s.pool, &s.token.address, &root, &100, &0);
+    assert_eq!(result, Err(Ok(AirdropError::InvalidClaimWindow.into())));
+}
@@ -0,0 +1,75 @@
+//! Data types and events for the Merkle airdrop contract.
+
+use common::events;
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env};
+
+/// A reward distribution claimable against a Merkle root.
+///
+/// A leaf is the SHA-256 of the XDR encoding of the `(Address, i128)` tuple
+/// of a recipient and its amount; see `common::merkle` for how the tree is
+/// built.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Airdrop {
+    pub id: u64,
+    /// Rewards pool the airdrop was funded from and sweeps back to
+    pub pool: Address,
+    /// Token the rewards are paid in
+    pub token: Address,
+    pub root: BytesN<32>,
+    /// Amount escrowed from the pool
+    pub total: i128,
+    /// Sum of amounts claimed so far
+    pub claimed: i128,
+    /// Ledger timestamp at which claiming ends and the airdrop can be swept
+    pub claim_ends_at: u64,
+    /// Whether unclaimed funds have been returned to the pool
+    pub swept: bool,
+}
+
+/// Storage keys for the Merkle airdrop contract.
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    /// Admin address
+    Admin,
+    /// Airdrop by id
+    Airdrop(u64),
+    /// Whether a recipient has claimed from an airdrop
+    Claimed(u64, Address),
+}
+
+/// Event emitters for Merkle airdrop operations.
+pub struct AirdropEvents;
+
+impl AirdropEvents {
+    /// Emitted when an airdrop is funded and opened for claims.
+    pub fn created(env: &Env, airdrop: &Airdrop) {
+        let topics = (
+            symbol_short!("airdrop"),
+            symbol_short!("created"),
+            airdrop.id,
+        );
+        events::publish(
+            env,
+            topics,
+            (airdrop.pool.clone(), airdrop.total, airdrop.claim_ends_at),
+        );
+    }
+
+    /// Emitted when a recipient claims its reward.
+    pub fn claimed(env: &Env, airdrop_id: u64, recipient: &Address, amount: i128) {
+        let topics = (
+            symbol_short!("airdrop"),
+            symbol_short!("claimed"),
+            airdrop_id,
+        );
+        events::publish(env, topics, (recipient.clone(), amount));
+    }
+
+    /// Emitted when unclaimed funds are returned to the pool.
+    pub fn swept(env: &Env, airdrop: &Airdrop, returned: i128) {
+        let topics = (symbol_short!("airdrop"), symbol_short!("swept"), airdrop.id);
+        events::publish(env, topics, (airdrop.pool.clone(), returned));
+    }
+}
@@ -12,6 +12,10 @@
 //! - **Event Emission**: Emits events for balance updates and batch processing
 //! - **Error Handling**: Gracefully handles invalid inputs with detailed error codes
 //! - **Partial Failure Support**: Invalid updates don't affect valid ones
+//! - **Standing Orders**: Automatic transfers between internal wallets, run by
+//!   keepers or triggered on deposits
+//! - **Dispute Holds**: Registered dispute contracts hold standing orders
+//!   between the parties of an open dispute
 //!
 //! ## Optimization Strategies
 //!
@@ -22,14 +26,19 @@
 
 #![no_std]
 
+mod standing_orders;
 mod types;
 mod validation;
 
-use soroban_sdk::{contract, contractimpl, panic_with_error, Address, Env, Symbol, Vec};
+use common::versioning;
+use soroban_sdk::{
+    contract, contractimpl, panic_with_error, symbol_short, Address, Env, Symbol, Vec,
+};
 
 pub use crate::types::{
     BalanceUpdateRequest, BalanceUpdateResult, BatchBalanceMetrics, BatchBalanceResult,
-    CurrencyBalance, DataKey, ErrorCode, WalletEvents, MAX_BATCH_SIZE,
+    CurrencyBalance, DataKey, ErrorCode, OrderExecution, OrderRule, StandingOrder, WalletEvents,
+    BPS_DENOMINATOR, MAX_BATCH_SIZE, MAX_ORDER_HISTORY, MAX_STANDING_ORDERS,
 };
 use crate::validation::{validate_and_compute_balance, validate_balance_request};
 
@@ -47,6 +56,24 @@ pub enum WalletError {
     EmptyBatch = 4,
     /// Batch exceeds maximum size
     BatchTooLarge = 5,
+    /// Standing order does not exist
+    OrderNotFound = 6,
+    /// Standing order parameters are invalid
+    InvalidOrder = 7,
+    /// User already holds the maximum number of standing orders
+    TooManyOrders = 8,
+    /// Standing order is paused
+    OrderPaused = 9,
+    /// Standing order is not due to run yet
+    OrderNotDue = 10,
+    /// Balance does not cover the standing order
+    InsufficientBalance = 11,
+    /// Standing order is held by an open dispute
+    OrderHeld = 12,
+    /// Storage was written by a different layout version
+    StorageVersionMismatch = 13,
+    /// Migration source version does not match stored version
+    InvalidMigration = 14,
 }
 
 impl From<WalletError> for soroban_sdk::Error {
@@ -55,6 +82,9 @@ impl From<WalletError> for soroban_sdk::Error {
     }
 }
 
+/// Storage layout version this code expects.
+pub const STORAGE_VERSION: u32 = 1;
+
 #[contract]
 pub struct MultiCurrencyWalletContract;
 
@@ -78,6 +108,25 @@ impl MultiCurrencyWalletContract {
         env.storage()
             .instance()
             .set(&DataKey::TotalBatchesProcessed, &0u64);
+        versioning::set_version(&env, STORAGE_VERSION);
+    }
+
+    /// Migrates storage written by an older version of this contract to the
+    /// current layout (admin only). `from_version` must match the stored
+    /// version.
+    pub fn migrate(env: Env, caller: Address, from_version: u32) {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        // Version 1 only adds the version stamp; older layouts are otherwise
+        // unchanged, so there is nothing to rewrite
+        versioning::migrate(&env, from_version, STORAGE_VERSION, |_, _| {})
+            .unwrap_or_else(|_| panic_with_error!(&env, WalletError::InvalidMigration));
+    }
+
+    /// Returns the storage layout version currently stored.
+    pub fn get_storage_version(env: Env) -> u32 {
+        versioning::stored_version(&env)
     }
 
     /// Updates balances for multiple users across multiple currencies in a batch.
@@ -109,6 +158,7 @@ impl MultiCurrencyWalletContract {
         caller: Address,
         requests: Vec<BalanceUpdateRequest>,
     ) -> BatchBalanceResult {
+        Self::require_storage_version(&env);
         // Verify authorization
         caller.require_auth();
         Self::require_admin(&env, &caller);
@@ -188,6 +238,16 @@ impl MultiCurrencyWalletContract {
                             // Emit success event
                             WalletEvents::balance_updated(&env, batch_id, &balance);
 
+                            // Deposits trigger the user's standing orders
+                            if request.operation == symbol_short!("add") {
+                                standing_orders::apply_deposit_orders(
+                                    &env,
+                                    &request.user,
+                                    &request.currency,
+                                    request.amount,
+                                );
+                            }
+
                             // Emit large balance event if applicable (>= 1,000,000 units)
                             if new_balance >= 1_000_000 {
                                 WalletEvents::large_balance_update(
@@ -334,6 +394,7 @@ impl MultiCurrencyWalletContract {
 
     /// Updates the admin address.
     pub fn set_admin(env: Env, current_admin: Address, new_admin: Address) {
+        Self::require_storage_version(&env);
         current_admin.require_auth();
         Self::require_admin(&env, &current_admin);
 
@@ -364,6 +425,252 @@ impl MultiCurrencyWalletContract {
             .unwrap_or(0)
     }
 
+    /// Creates a standing order moving funds from the owner's wallet to another
+    /// internal wallet.
+    ///
+    /// # Arguments
+    /// * `owner` - Wallet the funds move from (must authorize)
+    /// * `to` - Wallet the funds move to
+    /// * `currency` - Currency moved
+    /// * `rule` - `Sweep(amount, interval)` for a fixed amount run by keepers once
+    ///   per interval, or `OnDeposit(bps)` for a share of every deposit
+    ///
+    /// # Returns
+    /// * `u64` - The new order ID
+    pub fn create_standing_order(
+        env: Env,
+        owner: Address,
+        to: Address,
+        currency: Symbol,
+        rule: OrderRule,
+    ) -> u64 {
+        Self::require_storage_version(&env);
+        owner.require_auth();
+
+        if owner == to {
+            panic_with_error!(&env, WalletError::InvalidOrder);
+        }
+        let next_run = match rule {
+            OrderRule::Sweep(amount, interval) => {
+                if amount <= 0 || interval == 0 {
+                    panic_with_error!(&env, WalletError::InvalidOrder);
+                }
+                env.ledger().timestamp().saturating_add(interval)
+            }
+            OrderRule::OnDeposit(bps) => {
+                if bps == 0 || bps as i128 > BPS_DENOMINATOR {
+                    panic_with_error!(&env, WalletError::InvalidOrder);
+                }
+                0
+            }
+        };
+
+        let mut ids = standing_orders::user_order_ids(&env, &owner);
+        if ids.len() >= MAX_STANDING_ORDERS {
+            panic_with_error!(&env, WalletError::TooManyOrders);
+        }
+
+        let order_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::LastOrderId)
+            .unwrap_or(0)
+            + 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::LastOrderId, &order_id);
+
+        let order = StandingOrder {
+            id: order_id,
+            owner: owner.clone(),
+            to,
+            currency,
+            rule,
+            next_run,
+            paused: false,
+        };
+        standing_orders::save(&env, &order);
+        ids.push_back(order_id);
+        standing_orders::set_user_order_ids(&env, &owner, &ids);
+
+        WalletEvents::order_created(&env, &order);
+
+        order_id
+    }
+
+    /// Pauses or resumes a standing order (owner only).
+    pub fn set_standing_order_paused(env: Env, owner: Address, order_id: u64, paused: bool) {
+        Self::require_storage_version(&env);
+        owner.require_auth();
+        let mut order = Self::load_owned_order(&env, &owner, order_id);
+
+        order.paused = paused;
+        standing_orders::save(&env, &order);
+
+        WalletEvents::order_paused(&env, order_id, paused);
+    }
+
+    /// Cancels a standing order (owner only). Its history is kept.
+    pub fn cancel_standing_order(env: Env, owner: Address, order_id: u64) {
+        Self::require_storage_version(&env);
+        owner.require_auth();
+        Self::load_owned_order(&env, &owner, order_id);
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::StandingOrder(order_id));
+        let mut ids = standing_orders::user_order_ids(&env, &owner);
+        if let Some(index) = ids.first_index_of(order_id) {
+            ids.remove(index);
+        }
+        standing_orders::set_user_order_ids(&env, &owner, &ids);
+
+        WalletEvents::order_cancelled(&env, order_id, &owner);
+    }
+
+    /// Runs a due sweep order. Anyone may call this, so keepers can drive the
+    /// schedule.
+    ///
+    /// # Returns
+    /// * `i128` - The amount moved
+    ///
+    /// # Errors
+    /// * `OrderNotFound` - If the order does not exist
+    /// * `InvalidOrder` - If the order is triggered by deposits instead
+    /// * `OrderPaused` - If the order is paused
+    /// * `OrderHeld` - If a dispute between the owner and payee is open
+    /// * `OrderNotDue` - If the interval has not elapsed
+    /// * `InsufficientBalance` - If the owner's balance does not cover it
+    pub fn execute_standing_order(env: Env, order_id: u64) -> i128 {
+        Self::require_storage_version(&env);
+        let mut order = standing_orders::load(&env, order_id)
+            .unwrap_or_else(|| panic_with_error!(&env, WalletError::OrderNotFound));
+
+        let (amount, interval) = match order.rule {
+            OrderRule::Sweep(amount, interval) => (amount, interval),
+            OrderRule::OnDeposit(_) => panic_with_error!(&env, WalletError::InvalidOrder),
+        };
+        if order.paused {
+            panic_with_error!(&env, WalletError::OrderPaused);
+        }
+        if standing_orders::is_held(&env, &order) {
+            panic_with_error!(&env, WalletError::OrderHeld);
+        }
+        let now = env.ledger().timestamp();
+        if now < order.next_run {
+            panic_with_error!(&env, WalletError::OrderNotDue);
+        }
+        if standing_orders::balance_of(&env, &order.owner, &order.currency) < amount {
+            panic_with_error!(&env, WalletError::InsufficientBalance);
+        }
+
+        order.next_run = now.saturating_add(interval);
+        standing_orders::save(&env, &order);
+        standing_orders::execute(&env, &order, amount);
+
+        amount
+    }
+
+    /// Retrieves a standing order.
+    pub fn get_standing_order(env: Env, order_id: u64) -> Option<StandingOrder> {
+        standing_orders::load(&env, order_id)
+    }
+
+    /// Returns the standing orders a user owns.
+    pub fn get_standing_orders(env: Env, owner: Address) -> Vec<StandingOrder> {
+        let mut orders = Vec::new(&env);
+        for order_id in standing_orders::user_order_ids(&env, &owner).iter() {
+            if let Some(order) = standing_orders::load(&env, order_id) {
+                orders.push_back(order);
+            }
+        }
+        orders
+    }
+
+    /// Returns the most recent executions of a standing order, oldest first.
+    pub fn get_standing_order_history(env: Env, order_id: u64) -> Vec<OrderExecution> {
+        standing_orders::history(&env, order_id)
+    }
+
+    /// Allows or disallows a dispute contract to hold payments (admin only).
+    pub fn set_dispute_source(env: Env, caller: Address, source: Address, allowed: bool) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        let key = DataKey::DisputeSource(source);
+        if allowed {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Returns true if `source` may hold payments.
+    pub fn is_dispute_source(env: Env, source: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::DisputeSource(source))
+    }
+
+    /// Holds standing orders from `payer` to `payee` while a dispute between
+    /// them is open (registered dispute sources only).
+    ///
+    /// Holds stack, so orders resume only once every dispute that held them has
+    /// been released.
+    pub fn hold_payments(env: Env, source: Address, payer: Address, payee: Address) {
+        Self::require_storage_version(&env);
+        Self::require_dispute_source(&env, &source);
+
+        let holds = standing_orders::holds(&env, &payer, &payee).saturating_add(1);
+        standing_orders::set_holds(&env, &payer, &payee, holds);
+
+        WalletEvents::payments_held(&env, &payer, &payee, holds);
+    }
+
+    /// Releases one hold on standing orders from `payer` to `payee`
+    /// (registered dispute sources only).
+    pub fn release_payments(env: Env, source: Address, payer: Address, payee: Address) {
+        Self::require_storage_version(&env);
+        Self::require_dispute_source(&env, &source);
+
+        let holds = standing_orders::holds(&env, &payer, &payee).saturating_sub(1);
+        standing_orders::set_holds(&env, &payer, &payee, holds);
+
+        WalletEvents::payments_held(&env, &payer, &payee, holds);
+    }
+
+    /// Returns the number of open disputes holding standing orders from
+    /// `payer` to `payee`.
+    pub fn get_payment_holds(env: Env, payer: Address, payee: Address) -> u32 {
+        standing_orders::holds(&env, &payer, &payee)
+    }
+
+    // Internal helper verifying a registered dispute source
+    fn require_dispute_source(env: &Env, source: &Address) {
+        source.require_auth();
+        if !Self::is_dispute_source(env.clone(), source.clone()) {
+            panic_with_error!(env, WalletError::Unauthorized);
+        }
+    }
+
+    // Internal helper loading an order and checking who owns it
+    fn load_owned_order(env: &Env, owner: &Address, order_id: u64) -> StandingOrder {
+        let order = standing_orders::load(env, order_id)
+            .unwrap_or_else(|| panic_with_error!(env, WalletError::OrderNotFound));
+        if order.owner != *owner {
+            panic_with_error!(env, WalletError::Unauthorized);
+        }
+        order
+    }
+
+    // Internal helper refusing to run against storage written by a different
+    // layout version
+    fn require_storage_version(env: &Env) {
+        versioning::require_version(env, STORAGE_VERSION)
+            .unwrap_or_else(|_| panic_with_error!(env, WalletError::StorageVersionMismatch));
+    }
+
     // Internal helper to verify admin
     fn require_admin(env: &Env, caller: &Address) {
         let admin: Address = env
@@ -0,0 +1,152 @@
+//! Standing orders: automatic transfers between internal wallets.
+//!
+//! Sweep orders move a fixed amount once per interval when a keeper runs them.
+//! Deposit orders move a share of every `add` balance update credited to the
+//! owner in the order's currency.
+
+use soroban_sdk::{Address, Env, Symbol, Vec};
+
+use crate::types::{
+    CurrencyBalance, DataKey, OrderExecution, OrderRule, StandingOrder, WalletEvents,
+    BPS_DENOMINATOR, MAX_ORDER_HISTORY,
+};
+
+/// Loads a standing order.
+pub fn load(env: &Env, order_id: u64) -> Option<StandingOrder> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::StandingOrder(order_id))
+}
+
+/// Stores a standing order.
+pub fn save(env: &Env, order: &StandingOrder) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::StandingOrder(order.id), order);
+}
+
+/// Returns the IDs of the standing orders a user owns.
+pub fn user_order_ids(env: &Env, owner: &Address) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::UserOrders(owner.clone()))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Stores the IDs of the standing orders a user owns.
+pub fn set_user_order_ids(env: &Env, owner: &Address, ids: &Vec<u64>) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::UserOrders(owner.clone()), ids);
+}
+
+/// Returns the recent executions of a standing order, oldest first.
+pub fn history(env: &Env, order_id: u64) -> Vec<OrderExecution> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::OrderHistory(order_id))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Returns a user's balance in a currency.
+pub fn balance_of(env: &Env, user: &Address, currency: &Symbol) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Balance(user.clone(), currency.clone()))
+        .map(|b: CurrencyBalance| b.balance)
+        .unwrap_or(0)
+}
+
+/// Returns the number of open disputes holding orders from `payer` to `payee`.
+pub fn holds(env: &Env, payer: &Address, payee: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PaymentHold(payer.clone(), payee.clone()))
+        .unwrap_or(0)
+}
+
+/// Records the number of open disputes holding orders from `payer` to `payee`.
+pub fn set_holds(env: &Env, payer: &Address, payee: &Address, holds: u32) {
+    let key = DataKey::PaymentHold(payer.clone(), payee.clone());
+    if holds == 0 {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &holds);
+    }
+}
+
+/// Returns true while a dispute between the order's owner and payee is open.
+pub fn is_held(env: &Env, order: &StandingOrder) -> bool {
+    holds(env, &order.owner, &order.to) > 0
+}
+
+/// Moves `amount` for a standing order and records the execution.
+///
+/// The caller must have checked that the owner's balance covers `amount`.
+pub fn execute(env: &Env, order: &StandingOrder, amount: i128) {
+    let from_balance = balance_of(env, &order.owner, &order.currency);
+    let to_balance = balance_of(env, &order.to, &order.currency);
+    write_balance(env, &order.owner, &order.currency, from_balance - amount);
+    write_balance(
+        env,
+        &order.to,
+        &order.currency,
+        to_balance.checked_add(amount).expect("Balance overflow"),
+    );
+
+    let mut executions = history(env, order.id);
+    if executions.len() >= MAX_ORDER_HISTORY {
+        executions.pop_front();
+    }
+    executions.push_back(OrderExecution {
+        amount,
+        executed_at: env.ledger().timestamp(),
+    });
+    env.storage()
+        .persistent()
+        .set(&DataKey::OrderHistory(order.id), &executions);
+
+    WalletEvents::order_executed(env, order, amount);
+}
+
+/// Runs the owner's active deposit orders for a deposit of `deposited`.
+///
+/// Orders run in creation order; if their shares add up to more than the
+/// deposit, later orders move whatever is left.
+pub fn apply_deposit_orders(env: &Env, owner: &Address, currency: &Symbol, deposited: i128) {
+    for order_id in user_order_ids(env, owner).iter() {
+        let order = match load(env, order_id) {
+            Some(order) => order,
+            None => continue,
+        };
+        let bps = match order.rule {
+            OrderRule::OnDeposit(bps) => bps,
+            OrderRule::Sweep(_, _) => continue,
+        };
+        if order.paused || order.currency != *currency || is_held(env, &order) {
+            continue;
+        }
+
+        // Split the multiplication so large deposits cannot overflow
+        let bps = bps as i128;
+        let share =
+            deposited / BPS_DENOMINATOR * bps + deposited % BPS_DENOMINATOR * bps / BPS_DENOMINATOR;
+        let amount = share.min(balance_of(env, owner, currency));
+        if amount > 0 {
+            execute(env, &order, amount);
+        }
+    }
+}
+
+// Internal helper writing a balance record
+fn write_balance(env: &Env, user: &Address, currency: &Symbol, balance: i128) {
+    let record = CurrencyBalance {
+        user: user.clone(),
+        currency: currency.clone(),
+        balance,
+        updated_at: env.ledger().sequence() as u64,
+    };
+    env.storage()
+        .persistent()
+        .set(&DataKey::Balance(user.clone(), currency.clone()), &record);
+}
@@ -3,14 +3,19 @@
 #![cfg(test)]
 
 use crate::{MultiCurrencyWalletContract, MultiCurrencyWalletContractClient};
-use soroban_sdk::{symbol_short, testutils::Address as _, Address, Env, Symbol, Vec};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Ledger},
+    Address, Env, Symbol, Vec,
+};
 
-use crate::types::{BalanceUpdateRequest, BalanceUpdateResult, ErrorCode};
+use crate::types::{BalanceUpdateRequest, BalanceUpdateResult, ErrorCode, OrderRule};
 
 /// Helper function to create a test environment with initialized contract.
 fn setup_test_contract() -> (Env, Address, MultiCurrencyWalletContractClient<'static>) {
     let env = Env::default();
     env.mock_all_auths();
+    env.ledger().with_mut(|li| li.sequence_number = 100);
 
     let contract_id = env.register(MultiCurrencyWalletContract, ());
     let client = MultiCurrencyWalletContractClient::new(&env, &contract_id);
@@ -23,7 +28,7 @@ fn setup_test_contract() -> (Env, Address, MultiCurrencyWalletContractClient<'st
 
 /// Helper function to create a valid balance update request.
 fn create_valid_request(
-    env: &Env,
+    _env: &Env,
     user: &Address,
     currency: Symbol,
     amount: i128,
@@ -55,6 +60,35 @@ fn test_initialize_twice_fails() {
     client.initialize(&new_admin);
 }
 
+#[test]
+#[should_panic(expected = "Error(Contract, #13)")]
+fn test_outdated_storage_blocks_writes() {
+    let (env, admin, client) = setup_test_contract();
+    env.as_contract(&client.address, || common::versioning::set_version(&env, 0));
+
+    client.set_admin(&admin, &Address::generate(&env));
+}
+
+#[test]
+fn test_migrate_from_unversioned_storage() {
+    let (env, admin, client) = setup_test_contract();
+    assert_eq!(client.get_storage_version(), crate::STORAGE_VERSION);
+    env.as_contract(&client.address, || common::versioning::set_version(&env, 0));
+
+    client.migrate(&admin, &0);
+    assert_eq!(client.get_storage_version(), crate::STORAGE_VERSION);
+    let new_admin = Address::generate(&env);
+    client.set_admin(&admin, &new_admin);
+    assert_eq!(client.get_admin(), new_admin);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #14)")]
+fn test_migrate_from_wrong_version() {
+    let (_env, admin, client) = setup_test_contract();
+    client.migrate(&admin, &0);
+}
+
 #[test]
 fn test_batch_update_balances_single_user_single_currency() {
     let (env, admin, client) = setup_test_contract();
@@ -65,7 +99,7 @@ fn test_batch_update_balances_single_user_single_currency() {
         &env,
         &user,
         symbol_short!("USDC"),
-        1000_000_000, // 1000 USDC
+        1_000_000_000, // 1000 USDC
         symbol_short!("set"),
     ));
 
@@ -78,7 +112,7 @@ fn test_batch_update_balances_single_user_single_currency() {
 
     // Verify balance was set
     let balance = client.get_balance(&user, &symbol_short!("USDC"));
-    assert_eq!(balance, 1000_000_000);
+    assert_eq!(balance, 1_000_000_000);
 
     // Verify storage updates
     assert_eq!(client.get_last_batch_id(), 1);
@@ -158,7 +192,7 @@ fn test_balance_add_operation() {
         &env,
         &user,
         symbol_short!("USDC"),
-        1000_000_000,
+        1_000_000_000,
         symbol_short!("set"),
     ));
     client.batch_update_balances(&admin, &requests1);
@@ -175,7 +209,7 @@ fn test_balance_add_operation() {
     let result = client.batch_update_balances(&admin, &requests2);
 
     assert_eq!(result.successful, 1);
-    assert_eq!(client.get_balance(&user, &symbol_short!("USDC")), 1500_000_000);
+    assert_eq!(client.get_balance(&user, &symbol_short!("USDC")), 1_500_000_000);
 }
 
 #[test]
@@ -189,7 +223,7 @@ fn test_balance_subtract_operation() {
         &env,
         &user,
         symbol_short!("USDC"),
-        1000_000_000,
+        1_000_000_000,
         symbol_short!("set"),
     ));
     client.batch_update_balances(&admin, &requests1);
@@ -231,7 +265,7 @@ fn test_balance_subtract_insufficient_fails() {
         &env,
         &user,
         symbol_short!("USDC"),
-        1000_000_000,
+        1_000_000_000,
         symbol_short!("subtract"),
     ));
     let result = client.batch_update_balances(&admin, &requests2);
@@ -264,7 +298,7 @@ fn test_batch_update_with_invalid_requests() {
         &env,
         &user1,
         symbol_short!("USDC"),
-        1000_000_000,
+        1_000_000_000,
         symbol_short!("set"),
     ));
 
@@ -364,7 +398,7 @@ fn test_get_balance_details() {
         &env,
         &user,
         symbol_short!("USDC"),
-        1000_000_000,
+        1_000_000_000,
         symbol_short!("set"),
     ));
 
@@ -377,7 +411,7 @@ fn test_get_balance_details() {
 
     assert_eq!(details.user, user);
     assert_eq!(details.currency, symbol_short!("USDC"));
-    assert_eq!(details.balance, 1000_000_000);
+    assert_eq!(details.balance, 1_000_000_000);
     assert!(details.updated_at > 0);
 }
 
@@ -393,14 +427,14 @@ fn test_batch_metrics() {
         &env,
         &user1,
         symbol_short!("USDC"),
-        1000_000_000,
+        1_000_000_000,
         symbol_short!("set"),
     ));
     requests.push_back(create_valid_request(
         &env,
         &user1,
         symbol_short!("XLM"),
-        5000_000_000,
+        5_000_000_000,
         symbol_short!("set"),
     ));
     requests.push_back(create_valid_request(
@@ -431,7 +465,7 @@ fn test_multiple_batches() {
         &env,
         &user1,
         symbol_short!("USDC"),
-        1000_000_000,
+        1_000_000_000,
         symbol_short!("set"),
     ));
     let result1 = client.batch_update_balances(&admin, &requests1);
@@ -444,7 +478,7 @@ fn test_multiple_batches() {
         &env,
         &user2,
         symbol_short!("XLM"),
-        5000_000_000,
+        5_000_000_000,
         symbol_short!("set"),
     ));
     let result2 = client.batch_update_balances(&admin, &requests2);
@@ -498,7 +532,7 @@ fn test_mixed_operations_same_user() {
         &env,
         &user,
         symbol_short!("USDC"),
-        1000_000_000,
+        1_000_000_000,
         symbol_short!("set"),
     ));
 
@@ -507,7 +541,7 @@ fn test_mixed_operations_same_user() {
         &env,
         &user,
         symbol_short!("XLM"),
-        5000_000_000,
+        5_000_000_000,
         symbol_short!("set"),
     ));
 
@@ -527,8 +561,8 @@ fn test_mixed_operations_same_user() {
     assert_eq!(result.failed, 0);
 
     // Verify all balances for the same user
-    assert_eq!(client.get_balance(&user, &symbol_short!("USDC")), 1000_000_000);
-    assert_eq!(client.get_balance(&user, &symbol_short!("XLM")), 5000_000_000);
+    assert_eq!(client.get_balance(&user, &symbol_short!("USDC")), 1_000_000_000);
+    assert_eq!(client.get_balance(&user, &symbol_short!("XLM")), 5_000_000_000);
     assert_eq!(client.get_balance(&user, &symbol_short!("EURC")), 750_000_000);
 
     // Metrics should show 1 unique user, 3 unique currencies
@@ -560,7 +594,7 @@ fn test_mixed_valid_and_invalid_requests() {
         &env,
         &user1,
         symbol_short!("USDC"),
-        1000_000_000,
+        1_000_000_000,
         symbol_short!("set"),
     ));
 
@@ -612,3 +646,260 @@ fn test_minimum_valid_balance() {
     assert_eq!(result.failed, 0);
     assert_eq!(client.get_balance(&user, &symbol_short!("USDC")), 1);
 }
+
+// ============================================
+// Standing Order Tests
+// ============================================
+
+/// Helper function to credit a user through a batch `add` update.
+fn deposit(
+    env: &Env,
+    client: &MultiCurrencyWalletContractClient,
+    admin: &Address,
+    user: &Address,
+    amount: i128,
+) {
+    let mut requests: Vec<BalanceUpdateRequest> = Vec::new(env);
+    requests.push_back(create_valid_request(
+        env,
+        user,
+        symbol_short!("USDC"),
+        amount,
+        symbol_short!("add"),
+    ));
+    client.batch_update_balances(admin, &requests);
+}
+
+#[test]
+fn test_deposit_order_moves_share_of_deposit() {
+    let (env, admin, client) = setup_test_contract();
+    let user = Address::generate(&env);
+    let tax_bucket = Address::generate(&env);
+
+    let order_id = client.create_standing_order(
+        &user,
+        &tax_bucket,
+        &symbol_short!("USDC"),
+        &OrderRule::OnDeposit(2_500),
+    );
+    deposit(&env, &client, &admin, &user, 1_000);
+
+    assert_eq!(client.get_balance(&user, &symbol_short!("USDC")), 750);
+    assert_eq!(client.get_balance(&tax_bucket, &symbol_short!("USDC")), 250);
+
+    let history = client.get_standing_order_history(&order_id);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history.get(0).unwrap().amount, 250);
+}
+
+#[test]
+fn test_deposit_order_ignores_other_currencies_and_set() {
+    let (env, admin, client) = setup_test_contract();
+    let user = Address::generate(&env);
+    let tax_bucket = Address::generate(&env);
+
+    client.create_standing_order(
+        &user,
+        &tax_bucket,
+        &symbol_short!("XLM"),
+        &OrderRule::OnDeposit(5_000),
+    );
+    deposit(&env, &client, &admin, &user, 1_000);
+
+    let mut requests: Vec<BalanceUpdateRequest> = Vec::new(&env);
+    requests.push_back(create_valid_request(
+        &env,
+        &user,
+        symbol_short!("XLM"),
+        1_000,
+        symbol_short!("set"),
+    ));
+    client.batch_update_balances(&admin, &requests);
+
+    assert_eq!(client.get_balance(&user, &symbol_short!("USDC")), 1_000);
+    assert_eq!(client.get_balance(&user, &symbol_short!("XLM")), 1_000);
+    assert_eq!(client.get_balance(&tax_bucket, &symbol_short!("XLM")), 0);
+}
+
+#[test]
+fn test_paused_deposit_order_is_skipped() {
+    let (env, admin, client) = setup_test_contract();
+    let user = Address::generate(&env);
+    let tax_bucket = Address::generate(&env);
+
+    let order_id = client.create_standing_order(
+        &user,
+        &tax_bucket,
+        &symbol_short!("USDC"),
+        &OrderRule::OnDeposit(2_500),
+    );
+    client.set_standing_order_paused(&user, &order_id, &true);
+    deposit(&env, &client, &admin, &user, 1_000);
+    assert_eq!(client.get_balance(&tax_bucket, &symbol_short!("USDC")), 0);
+
+    client.set_standing_order_paused(&user, &order_id, &false);
+    deposit(&env, &client, &admin, &user, 1_000);
+    assert_eq!(client.get_balance(&tax_bucket, &symbol_short!("USDC")), 250);
+}
+
+#[test]
+fn test_sweep_order_runs_once_per_interval() {
+    let (env, admin, client) = setup_test_contract();
+    let user = Address::generate(&env);
+    let savings = Address::generate(&env);
+    deposit(&env, &client, &admin, &user, 1_000);
+
+    let order_id = client.create_standing_order(
+        &user,
+        &savings,
+        &symbol_short!("USDC"),
+        &OrderRule::Sweep(400, 3_600),
+    );
+    assert!(client.try_execute_standing_order(&order_id).is_err());
+
+    env.ledger().with_mut(|li| li.timestamp += 3_600);
+    assert_eq!(client.execute_standing_order(&order_id), 400);
+    assert!(client.try_execute_standing_order(&order_id).is_err());
+
+    env.ledger().with_mut(|li| li.timestamp += 3_600);
+    client.execute_standing_order(&order_id);
+
+    assert_eq!(client.get_balance(&user, &symbol_short!("USDC")), 200);
+    assert_eq!(client.get_balance(&savings, &symbol_short!("USDC")), 800);
+    assert_eq!(client.get_standing_order_history(&order_id).len(), 2);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #11)")]
+fn test_sweep_order_requires_balance() {
+    let (env, admin, client) = setup_test_contract();
+    let user = Address::generate(&env);
+    deposit(&env, &client, &admin, &user, 100);
+
+    let order_id = client.create_standing_order(
+        &user,
+        &Address::generate(&env),
+        &symbol_short!("USDC"),
+        &OrderRule::Sweep(400, 60),
+    );
+    env.ledger().with_mut(|li| li.timestamp += 60);
+    client.execute_standing_order(&order_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")]
+fn test_deposit_order_share_cannot_exceed_full_amount() {
+    let (env, _admin, client) = setup_test_contract();
+    let user = Address::generate(&env);
+
+    client.create_standing_order(
+        &user,
+        &Address::generate(&env),
+        &symbol_short!("USDC"),
+        &OrderRule::OnDeposit(10_001),
+    );
+}
+
+#[test]
+fn test_cancel_standing_order_keeps_history() {
+    let (env, admin, client) = setup_test_contract();
+    let user = Address::generate(&env);
+
+    let order_id = client.create_standing_order(
+        &user,
+        &Address::generate(&env),
+        &symbol_short!("USDC"),
+        &OrderRule::OnDeposit(1_000),
+    );
+    deposit(&env, &client, &admin, &user, 1_000);
+    assert_eq!(client.get_standing_orders(&user).len(), 1);
+
+    client.cancel_standing_order(&user, &order_id);
+
+    assert_eq!(client.get_standing_order(&order_id), None);
+    assert_eq!(client.get_standing_orders(&user).len(), 0);
+    assert_eq!(client.get_standing_order_history(&order_id).len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_only_owner_can_pause_standing_order() {
+    let (env, _admin, client) = setup_test_contract();
+    let user = Address::generate(&env);
+
+    let order_id = client.create_standing_order(
+        &user,
+        &Address::generate(&env),
+        &symbol_short!("USDC"),
+        &OrderRule::OnDeposit(1_000),
+    );
+    client.set_standing_order_paused(&Address::generate(&env), &order_id, &true);
+}
+
+#[test]
+fn test_dispute_hold_blocks_orders_until_released() {
+    let (env, admin, client) = setup_test_contract();
+    let escrow = Address::generate(&env);
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    deposit(&env, &client, &admin, &user, 1_000);
+
+    let sweep_id = client.create_standing_order(
+        &user,
+        &merchant,
+        &symbol_short!("USDC"),
+        &OrderRule::Sweep(100, 60),
+    );
+    client.create_standing_order(
+        &user,
+        &merchant,
+        &symbol_short!("USDC"),
+        &OrderRule::OnDeposit(1_000),
+    );
+
+    client.set_dispute_source(&admin, &escrow, &true);
+    assert!(client.is_dispute_source(&escrow));
+    client.hold_payments(&escrow, &user, &merchant);
+    assert_eq!(client.get_payment_holds(&user, &merchant), 1);
+
+    // Both the sweep and the deposit order are held
+    env.ledger().with_mut(|li| li.timestamp += 60);
+    let result = client.try_execute_standing_order(&sweep_id);
+    assert_eq!(result, Err(Ok(crate::WalletError::OrderHeld.into())));
+    deposit(&env, &client, &admin, &user, 1_000);
+    assert_eq!(client.get_balance(&merchant, &symbol_short!("USDC")), 0);
+
+    client.release_payments(&escrow, &user, &merchant);
+    assert_eq!(client.get_payment_holds(&user, &merchant), 0);
+    assert_eq!(client.execute_standing_order(&sweep_id), 100);
+    deposit(&env, &client, &admin, &user, 1_000);
+    assert_eq!(client.get_balance(&merchant, &symbol_short!("USDC")), 200);
+}
+
+#[test]
+fn test_dispute_holds_stack() {
+    let (env, admin, client) = setup_test_contract();
+    let escrow = Address::generate(&env);
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    client.set_dispute_source(&admin, &escrow, &true);
+
+    client.hold_payments(&escrow, &user, &merchant);
+    client.hold_payments(&escrow, &user, &merchant);
+    client.release_payments(&escrow, &user, &merchant);
+    assert_eq!(client.get_payment_holds(&user, &merchant), 1);
+
+    // Holds apply to one direction of the pair only
+    assert_eq!(client.get_payment_holds(&merchant, &user), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_unregistered_source_cannot_hold_payments() {
+    let (env, _admin, client) = setup_test_contract();
+    client.hold_payments(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &Address::generate(&env),
+    );
+}
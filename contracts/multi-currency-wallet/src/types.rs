@@ -1,5 +1,6 @@
 //! Data types and events for batch multi-currency wallet operations.
 
+use common::events;
 use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol, Vec};
 
 /// Maximum number of balance updates in a single batch for optimization.
@@ -11,6 +12,16 @@ pub const MIN_BALANCE: i128 = 1;
 /// Maximum balance value (preventing overflow)
 pub const MAX_BALANCE: i128 = i128::MAX;
 
+/// Maximum number of standing orders a user may hold, bounding the work done
+/// on each deposit.
+pub const MAX_STANDING_ORDERS: u32 = 10;
+
+/// Number of executions kept in a standing order's history.
+pub const MAX_ORDER_HISTORY: u32 = 50;
+
+/// Basis points denominator for deposit-triggered standing orders.
+pub const BPS_DENOMINATOR: i128 = 10_000;
+
 /// Represents a balance update request for a user in a specific currency.
 #[derive(Clone, Debug)]
 #[contracttype]
@@ -83,6 +94,46 @@ pub struct BatchBalanceResult {
     pub metrics: BatchBalanceMetrics,
 }
 
+/// How much a standing order moves each time it runs.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum OrderRule {
+    /// Moves a fixed amount once per interval (in seconds), run by keepers
+    Sweep(i128, u64),
+    /// Moves a share of every incoming deposit, in basis points
+    OnDeposit(u32),
+}
+
+/// An automatic transfer from one internal wallet to another.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct StandingOrder {
+    /// Order ID
+    pub id: u64,
+    /// Wallet the funds move from
+    pub owner: Address,
+    /// Wallet the funds move to
+    pub to: Address,
+    /// Currency moved
+    pub currency: Symbol,
+    /// Amount and trigger
+    pub rule: OrderRule,
+    /// Earliest timestamp a sweep may next run (unused for deposit orders)
+    pub next_run: u64,
+    /// Whether the order is paused
+    pub paused: bool,
+}
+
+/// A single run of a standing order.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct OrderExecution {
+    /// Amount moved
+    pub amount: i128,
+    /// Execution timestamp
+    pub executed_at: u64,
+}
+
 /// Storage keys for contract state.
 #[derive(Clone)]
 #[contracttype]
@@ -97,9 +148,22 @@ pub enum DataKey {
     TotalBalancesUpdated,
     /// Total batches processed lifetime
     TotalBatchesProcessed,
+    /// Last created standing order ID
+    LastOrderId,
+    /// Standing order by ID
+    StandingOrder(u64),
+    /// IDs of the standing orders a user owns
+    UserOrders(Address),
+    /// Recent executions of a standing order
+    OrderHistory(u64),
+    /// Contracts allowed to hold payments while they arbitrate a dispute
+    DisputeSource(Address),
+    /// Open disputes holding standing orders from a payer to a payee
+    PaymentHold(Address, Address),
 }
 
 /// Error codes for balance update validation.
+#[allow(non_snake_case)]
 pub mod ErrorCode {
     /// Invalid balance amount (negative or exceeds max)
     pub const INVALID_AMOUNT: u32 = 0;
@@ -122,7 +186,7 @@ impl WalletEvents {
     /// Event emitted when batch balance update starts.
     pub fn batch_started(env: &Env, batch_id: u64, request_count: u32) {
         let topics = (symbol_short!("batch"), symbol_short!("started"));
-        env.events().publish(topics, (batch_id, request_count));
+        events::publish(env, topics, (batch_id, request_count));
     }
 
     /// Event emitted when a balance is successfully updated.
@@ -132,7 +196,8 @@ impl WalletEvents {
             symbol_short!("updated"),
             batch_id,
         );
-        env.events().publish(
+        events::publish(
+            env,
             topics,
             (
                 balance.user.clone(),
@@ -151,14 +216,62 @@ impl WalletEvents {
         error_code: u32,
     ) {
         let topics = (symbol_short!("balance"), symbol_short!("failed"), batch_id);
-        env.events()
-            .publish(topics, (user.clone(), currency.clone(), error_code));
+        events::publish(env, topics, (user.clone(), currency.clone(), error_code));
     }
 
     /// Event emitted when batch balance update completes.
     pub fn batch_completed(env: &Env, batch_id: u64, successful: u32, failed: u32) {
         let topics = (symbol_short!("batch"), symbol_short!("completed"), batch_id);
-        env.events().publish(topics, (successful, failed));
+        events::publish(env, topics, (successful, failed));
+    }
+
+    /// Event emitted when a standing order is created.
+    pub fn order_created(env: &Env, order: &StandingOrder) {
+        let topics = (symbol_short!("order"), symbol_short!("created"), order.id);
+        events::publish(
+            env,
+            topics,
+            (
+                order.owner.clone(),
+                order.to.clone(),
+                order.currency.clone(),
+                order.rule.clone(),
+            ),
+        );
+    }
+
+    /// Event emitted when a standing order is paused or resumed.
+    pub fn order_paused(env: &Env, order_id: u64, paused: bool) {
+        let topics = (symbol_short!("order"), symbol_short!("paused"), order_id);
+        events::publish(env, topics, paused);
+    }
+
+    /// Event emitted when a standing order is cancelled.
+    pub fn order_cancelled(env: &Env, order_id: u64, owner: &Address) {
+        let topics = (symbol_short!("order"), symbol_short!("cancelled"), order_id);
+        events::publish(env, topics, owner.clone());
+    }
+
+    /// Event emitted when a dispute holds or releases standing orders from a
+    /// payer to a payee. `holds` is the number of disputes still open.
+    pub fn payments_held(env: &Env, payer: &Address, payee: &Address, holds: u32) {
+        let topics = (symbol_short!("order"), symbol_short!("held"), payer.clone());
+        events::publish(env, topics, (payee.clone(), holds));
+    }
+
+    /// Event emitted when a standing order moves funds.
+    pub fn order_executed(env: &Env, order: &StandingOrder, amount: i128) {
+        let topics = (symbol_short!("order"), symbol_short!("executed"), order.id);
+        events::publish(
+            env,
+            topics,
+            (
+                order.owner.clone(),
+                order.to.clone(),
+                order.currency.clone(),
+                amount,
+            ),
+        );
     }
 
     /// Event emitted for large balance updates (>= 1,000,000 units).
@@ -170,7 +283,6 @@ impl WalletEvents {
         amount: i128,
     ) {
         let topics = (symbol_short!("balance"), symbol_short!("large"), batch_id);
-        env.events()
-            .publish(topics, (user.clone(), currency.clone(), amount));
+        events::publish(env, topics, (user.clone(), currency.clone(), amount));
     }
 }
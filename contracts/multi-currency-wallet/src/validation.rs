@@ -1,8 +1,10 @@
 //! Validation logic for balance update requests.
 
-use soroban_sdk::{Address, Env, Symbol};
+use soroban_sdk::{symbol_short, Address, Env, Symbol};
 
-use crate::types::{BalanceUpdateRequest, DataKey, ErrorCode, MAX_BALANCE, MIN_BALANCE};
+use crate::types::{
+    BalanceUpdateRequest, CurrencyBalance, DataKey, ErrorCode, MAX_BALANCE, MIN_BALANCE,
+};
 
 /// Validates a balance update request.
 ///
@@ -54,7 +56,7 @@ fn is_valid_currency(_currency: &Symbol) -> bool {
 /// # Returns
 /// * `true` if amount is >= MIN_BALANCE and <= MAX_BALANCE
 pub fn is_valid_amount(amount: i128) -> bool {
-    amount >= MIN_BALANCE && amount <= MAX_BALANCE
+    (MIN_BALANCE..=MAX_BALANCE).contains(&amount)
 }
 
 /// Validates that an operation type is valid.
@@ -65,10 +67,9 @@ pub fn is_valid_amount(amount: i128) -> bool {
 /// # Returns
 /// * `true` if operation is "set", "add", or "subtract"
 pub fn is_valid_operation(operation: &Symbol) -> bool {
-    // In Soroban, we can't directly convert Symbol to string in no_std
-    // We'll accept any symbol here and handle invalid operations during execution
-    // Valid operations: "set", "add", "subtract"
-    true
+    *operation == symbol_short!("set")
+        || *operation == symbol_short!("add")
+        || *operation == symbol_short!("subtract")
 }
 
 /// Validates balance after operation to prevent negative balances.
@@ -95,39 +96,35 @@ pub fn validate_and_compute_balance(
         .storage()
         .persistent()
         .get(&DataKey::Balance(user.clone(), currency.clone()))
+        .map(|b: CurrencyBalance| b.balance)
         .unwrap_or(0);
 
     // Compute new balance based on operation
     let new_balance = compute_new_balance(current_balance, operation, amount)?;
 
-    // Validate new balance is non-negative
+    // Validate new balance is non-negative (overflow is caught by the checked
+    // arithmetic above)
     if new_balance < 0 {
         return Err(ErrorCode::INSUFFICIENT_BALANCE);
     }
 
-    // Validate new balance doesn't exceed maximum
-    if new_balance > MAX_BALANCE {
-        return Err(ErrorCode::ARITHMETIC_OVERFLOW);
-    }
-
     Ok(new_balance)
 }
 
 /// Computes new balance based on operation.
 fn compute_new_balance(current: i128, operation: &Symbol, amount: i128) -> Result<i128, u32> {
-    // Note: In production, use proper symbol comparison
-    // For now, we'll use symbol_short! macro patterns
-    let op_str = operation.to_string();
-
-    match op_str.as_str() {
-        "set" => Ok(amount),
-        "add" => current
+    if *operation == symbol_short!("set") {
+        Ok(amount)
+    } else if *operation == symbol_short!("add") {
+        current
             .checked_add(amount)
-            .ok_or(ErrorCode::ARITHMETIC_OVERFLOW),
-        "subtract" => current
+            .ok_or(ErrorCode::ARITHMETIC_OVERFLOW)
+    } else if *operation == symbol_short!("subtract") {
+        current
             .checked_sub(amount)
-            .ok_or(ErrorCode::ARITHMETIC_OVERFLOW),
-        _ => Err(ErrorCode::INVALID_OPERATION),
+            .ok_or(ErrorCode::ARITHMETIC_OVERFLOW)
+    } else {
+        Err(ErrorCode::INVALID_OPERATION)
     }
 }
 
@@ -140,7 +137,7 @@ mod tests {
         BalanceUpdateRequest {
             user: Address::generate(env),
             currency: symbol_short!("USDC"),
-            amount: 1000_000_000, // 1000 USDC
+            amount: 1_000_000_000, // 1000 USDC
             operation: symbol_short!("set"),
         }
     }
@@ -178,7 +175,7 @@ mod tests {
     fn test_is_valid_amount() {
         assert!(is_valid_amount(MIN_BALANCE));
         assert!(is_valid_amount(MAX_BALANCE));
-        assert!(is_valid_amount(1000_000_000));
+        assert!(is_valid_amount(1_000_000_000));
         assert!(!is_valid_amount(MIN_BALANCE - 1));
         assert!(!is_valid_amount(0));
         assert!(!is_valid_amount(-1000));
@@ -0,0 +1,748 @@
+//! # Multisig Transactions Contract
+//!
+//! Transactions above the high-value threshold wait for approvals from the
+//! configured signers before they execute.
+#![no_std]
+
+mod multisig;
+mod org;
+
+use common::versioning;
+use soroban_sdk::{contract, contractimpl, panic_with_error, Address, BytesN, Env, Symbol, Vec};
+
+pub use multisig::{
+    ApprovalDigest, ApprovalDigestPage, ApproverSlaStats, Blackout, Comment, DecayAction,
+    DecisionOutcome, MultiSigError, PendingTx, QuorumDecay, Rejection, RejectionReason,
+};
+use multisig::{DataKey, MultisigEvents};
+use org::OrgHierarchyClient;
+
+/// Storage layout version this code expects.
+pub const STORAGE_VERSION: u32 = 1;
+
+#[contract]
+pub struct TransactionsContract;
+
+#[contractimpl]
+impl TransactionsContract {
+    pub fn initialize(env: Env, admin: Address) {
+        multisig::initialize_state(&env, admin);
+        versioning::set_version(&env, STORAGE_VERSION);
+    }
+
+    /// Migrates storage written by an older version of this contract to the
+    /// current layout (admin only). `from_version` must match the stored
+    /// version.
+    pub fn migrate(env: Env, caller: Address, from_version: u32) {
+        multisig::require_admin(&env, &caller);
+
+        // Version 1 only adds the version stamp; older layouts are otherwise
+        // unchanged, so there is nothing to rewrite
+        versioning::migrate(&env, from_version, STORAGE_VERSION, |_, _| {})
+            .unwrap_or_else(|_| panic_with_error!(&env, MultiSigError::InvalidMigration));
+    }
+
+    /// Returns the storage layout version currently stored.
+    pub fn get_storage_version(env: Env) -> u32 {
+        versioning::stored_version(&env)
+    }
+
+    pub fn get_admin(env: Env) -> Address {
+        multisig::get_admin(&env)
+    }
+
+    pub fn set_signers(env: Env, caller: Address, signers: Vec<Address>, threshold: u32) {
+        Self::require_storage_version(&env);
+        multisig::set_signers(&env, caller, signers, threshold);
+    }
+
+    pub fn set_high_value_threshold(env: Env, caller: Address, amount: i128) {
+        Self::require_storage_version(&env);
+        multisig::set_high_value_threshold(&env, caller, amount);
+    }
+
+    pub fn get_signers(env: Env) -> Vec<Address> {
+        multisig::get_signers(&env)
+    }
+
+    pub fn get_threshold(env: Env) -> u32 {
+        multisig::get_threshold(&env)
+    }
+
+    pub fn get_high_value_threshold(env: Env) -> i128 {
+        multisig::get_high_value_threshold(&env)
+    }
+
+    pub fn set_balance(env: Env, caller: Address, user: Address, amount: i128) {
+        Self::require_storage_version(&env);
+        multisig::require_admin(&env, &caller);
+        if amount < 0 {
+            panic_with_error!(&env, MultiSigError::InvalidAmount);
+        }
+
+        env.storage().persistent().set(&DataKey::Balance(user), &amount);
+    }
+
+    pub fn get_balance(env: Env, user: Address) -> i128 {
+        Self::balance_of(&env, &user)
+    }
+
+    pub fn submit_transaction(
+        env: Env,
+        from: Address,
+        to: Address,
+        amount: i128,
+        payload: Symbol,
+        asset: Option<Address>,
+    ) -> Option<u64> {
+        Self::require_storage_version(&env);
+        from.require_auth();
+
+        if amount <= 0 {
+            panic_with_error!(&env, MultiSigError::InvalidAmount);
+        }
+        multisig::check_blackout(&env, &payload).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let high_value_threshold = multisig::get_high_value_threshold(&env);
+
+        if amount < high_value_threshold {
+            Self::execute_transfer(&env, &from, &to, amount);
+
+            let executed_tx = PendingTx {
+                id: 0,
+                from: from.clone(),
+                to,
+                amount,
+                payload,
+                asset,
+                created_at: env.ledger().timestamp(),
+                executed: true,
+            };
+            MultisigEvents::transaction_executed(&env, &executed_tx, &from);
+
+            return None;
+        }
+
+        multisig::ensure_multisig_configured(&env);
+
+        let tx_id = multisig::next_tx_id(&env);
+        let pending_tx = PendingTx {
+            id: tx_id,
+            from,
+            to,
+            amount,
+            payload,
+            asset,
+            created_at: env.ledger().timestamp(),
+            executed: false,
+        };
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::PendingTx(tx_id), &pending_tx);
+        env.storage()
+            .persistent()
+            .set(&DataKey::ApprovalCount(tx_id), &0u32);
+
+        MultisigEvents::pending_created(&env, &pending_tx);
+
+        Some(tx_id)
+    }
+
+    pub fn approve(env: Env, tx_id: u64, signer: Address) {
+        Self::require_storage_version(&env);
+        signer.require_auth();
+        Self::record_decision(&env, tx_id, &signer, None)
+            .unwrap_or_else(|e| panic_with_error!(&env, e));
+    }
+
+    /// Rejects a pending transaction so it can no longer execute. Any signer
+    /// who could approve it, or a manager it was routed to, may reject it.
+    ///
+    /// The reason code tells the submitter what to fix; `memo_hash` commits to
+    /// an optional free-text explanation kept off-chain.
+    pub fn reject(
+        env: Env,
+        tx_id: u64,
+        signer: Address,
+        reason: RejectionReason,
+        memo_hash: Option<BytesN<32>>,
+    ) {
+        Self::require_storage_version(&env);
+        signer.require_auth();
+        Self::record_decision(&env, tx_id, &signer, Some((reason, memo_hash)))
+            .unwrap_or_else(|e| panic_with_error!(&env, e));
+    }
+
+    /// Approves many pending transactions at once.
+    ///
+    /// Items that cannot be approved are skipped rather than failing the
+    /// batch; the outcome for each id is returned in the same order.
+    pub fn approve_batch(env: Env, tx_ids: Vec<u64>, signer: Address) -> Vec<DecisionOutcome> {
+        Self::require_storage_version(&env);
+        signer.require_auth();
+        Self::decide_batch(&env, &tx_ids, &signer, None)
+    }
+
+    /// Rejects many pending transactions at once with a shared reason and memo.
+    ///
+    /// Items that cannot be rejected are skipped rather than failing the
+    /// batch; the outcome for each id is returned in the same order.
+    pub fn reject_batch(
+        env: Env,
+        tx_ids: Vec<u64>,
+        signer: Address,
+        reason: RejectionReason,
+        memo_hash: Option<BytesN<32>>,
+    ) -> Vec<DecisionOutcome> {
+        Self::require_storage_version(&env);
+        signer.require_auth();
+        Self::decide_batch(&env, &tx_ids, &signer, Some((reason, memo_hash)))
+    }
+
+    /// Replaces the blackout schedule. While a window is active, transactions
+    /// can only be submitted or executed in critical categories.
+    pub fn set_blackouts(env: Env, caller: Address, blackouts: Vec<Blackout>) {
+        Self::require_storage_version(&env);
+        multisig::require_admin(&env, &caller);
+        multisig::set_blackouts(&env, &blackouts).unwrap_or_else(|e| panic_with_error!(&env, e));
+    }
+
+    /// Returns the blackout schedule, including windows that have ended.
+    pub fn get_blackouts(env: Env) -> Vec<Blackout> {
+        multisig::get_blackouts(&env)
+    }
+
+    /// Returns the blackout window in effect now, if any.
+    pub fn get_active_blackout(env: Env) -> Option<Blackout> {
+        multisig::active_blackout(&env)
+    }
+
+    /// Whitelists a category, such as payroll or tax, as critical so it can
+    /// still be spent during blackouts.
+    pub fn set_critical_category(env: Env, caller: Address, category: Symbol, critical: bool) {
+        Self::require_storage_version(&env);
+        multisig::require_admin(&env, &caller);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::CriticalCategory(category), &critical);
+    }
+
+    pub fn is_critical_category(env: Env, category: Symbol) -> bool {
+        multisig::is_critical_category(&env, &category)
+    }
+
+    pub fn get_rejection(env: Env, tx_id: u64) -> Option<Rejection> {
+        multisig::get_rejection(&env, tx_id)
+    }
+
+    /// Returns how many transactions have been rejected for `reason`.
+    pub fn get_rejection_count(env: Env, reason: RejectionReason) -> u32 {
+        multisig::get_rejection_count(&env, reason)
+    }
+
+    /// Anchors a comment on a transaction's approval thread, returning its
+    /// index. The submitter, any signer, or a manager the approval was routed
+    /// to may comment, before or after the decision; only the hash of the
+    /// text is stored.
+    pub fn post_comment(env: Env, tx_id: u64, author: Address, body_hash: BytesN<32>) -> u32 {
+        Self::require_storage_version(&env);
+        author.require_auth();
+
+        let pending_tx: PendingTx = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PendingTx(tx_id))
+            .unwrap_or_else(|| panic_with_error!(&env, MultiSigError::PendingTxNotFound));
+        if author != pending_tx.from && multisig::resolve_approver(&env, tx_id, &author).is_err() {
+            panic_with_error!(&env, MultiSigError::Unauthorized);
+        }
+
+        let comment = Comment {
+            author,
+            body_hash,
+            posted_at: env.ledger().timestamp(),
+        };
+        multisig::append_comment(&env, tx_id, &comment)
+            .unwrap_or_else(|e| panic_with_error!(&env, e))
+    }
+
+    /// Returns a transaction's comment thread, oldest first.
+    pub fn get_comments(env: Env, tx_id: u64) -> Vec<Comment> {
+        multisig::get_comments(&env, tx_id)
+    }
+
+    /// Sets a signer's voting weight, e.g. from their stake or seniority.
+    /// Signers without an explicit weight count once. The signers must still
+    /// be able to reach the weight threshold afterwards.
+    pub fn set_signer_weight(env: Env, caller: Address, signer: Address, weight: u32) {
+        Self::require_storage_version(&env);
+        multisig::require_admin(&env, &caller);
+        if weight == 0 {
+            panic_with_error!(&env, MultiSigError::InvalidWeight);
+        }
+        if !multisig::is_signer(&env, &signer) {
+            panic_with_error!(&env, MultiSigError::UnauthorizedSigner);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::SignerWeight(signer), &weight);
+        multisig::require_reachable_weight_threshold(&env);
+    }
+
+    pub fn get_signer_weight(env: Env, signer: Address) -> u32 {
+        multisig::get_signer_weight(&env, &signer)
+    }
+
+    /// Switches pending transactions to weighted approval: they pass once the
+    /// approvers' combined weight reaches `threshold`. A threshold of 0 goes
+    /// back to counting approvals.
+    pub fn set_weight_threshold(env: Env, caller: Address, threshold: u32) {
+        Self::require_storage_version(&env);
+        multisig::require_admin(&env, &caller);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::WeightThreshold, &threshold);
+        multisig::require_reachable_weight_threshold(&env);
+    }
+
+    pub fn get_weight_threshold(env: Env) -> u32 {
+        multisig::get_weight_threshold(&env)
+    }
+
+    pub fn get_approval_weight(env: Env, tx_id: u64) -> u32 {
+        multisig::get_approval_weight(&env, tx_id)
+    }
+
+    /// Declares addresses whose transactions `approver` has a conflict of
+    /// interest with. Approvers may declare their own conflicts and the admin
+    /// may declare them on an approver's behalf; only the admin may clear them.
+    pub fn declare_conflicts(env: Env, caller: Address, approver: Address, parties: Vec<Address>) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        if caller != approver && caller != multisig::get_admin(&env) {
+            panic_with_error!(&env, MultiSigError::Unauthorized);
+        }
+
+        let mut conflicts = multisig::get_conflicts(&env, &approver);
+        for party in parties.iter() {
+            if !conflicts.contains(&party) {
+                conflicts.push_back(party);
+            }
+        }
+        multisig::set_conflicts(&env, &approver, &conflicts);
+    }
+
+    pub fn remove_conflict(env: Env, caller: Address, approver: Address, party: Address) {
+        Self::require_storage_version(&env);
+        multisig::require_admin(&env, &caller);
+
+        let mut conflicts = multisig::get_conflicts(&env, &approver);
+        if let Some(index) = conflicts.first_index_of(&party) {
+            conflicts.remove(index);
+            multisig::set_conflicts(&env, &approver, &conflicts);
+        }
+    }
+
+    pub fn get_conflicts(env: Env, approver: Address) -> Vec<Address> {
+        multisig::get_conflicts(&env, &approver)
+    }
+
+    /// Sets how long a pending transaction may wait for approvals before it can
+    /// be escalated, and whether escalations route to the approvers' managers.
+    pub fn set_approval_sla(env: Env, caller: Address, sla_secs: u64, auto_route: bool) {
+        Self::require_storage_version(&env);
+        multisig::require_admin(&env, &caller);
+
+        env.storage().instance().set(&DataKey::ApprovalSla, &sla_secs);
+        env.storage()
+            .instance()
+            .set(&DataKey::AutoRouteEscalations, &auto_route);
+    }
+
+    pub fn get_approval_sla(env: Env) -> u64 {
+        multisig::get_approval_sla(&env)
+    }
+
+    /// Sets the org-hierarchy contract whose manager chain overdue approvals
+    /// are routed along.
+    pub fn set_org_hierarchy(env: Env, caller: Address, org_hierarchy: Address) {
+        Self::require_storage_version(&env);
+        multisig::require_admin(&env, &caller);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::OrgHierarchy, &org_hierarchy);
+    }
+
+    pub fn get_org_hierarchy(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::OrgHierarchy)
+    }
+
+    /// Returns the signers whose overdue approval on `tx_id` was routed to
+    /// `manager`.
+    pub fn get_delegated_signers(env: Env, tx_id: u64, manager: Address) -> Vec<Address> {
+        multisig::get_delegated_signers(&env, tx_id, &manager)
+    }
+
+    pub fn set_auditor(env: Env, caller: Address, auditor: Address, enabled: bool) {
+        Self::require_storage_version(&env);
+        multisig::require_admin(&env, &caller);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Auditor(auditor), &enabled);
+    }
+
+    /// Escalates a pending transaction that has waited longer than the SLA.
+    ///
+    /// Anyone may trigger this. Each signer who has not yet approved is charged
+    /// an SLA breach, and when auto-routing is enabled their manager in the org
+    /// hierarchy may approve on their behalf. Returns the managers the approval
+    /// was routed to.
+    pub fn escalate_overdue(env: Env, tx_id: u64) -> Vec<Address> {
+        Self::require_storage_version(&env);
+        let sla = multisig::get_approval_sla(&env);
+        if sla == 0 {
+            panic_with_error!(&env, MultiSigError::SlaNotConfigured);
+        }
+
+        let pending_tx: PendingTx = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PendingTx(tx_id))
+            .unwrap_or_else(|| panic_with_error!(&env, MultiSigError::PendingTxNotFound));
+        if pending_tx.executed {
+            panic_with_error!(&env, MultiSigError::AlreadyExecuted);
+        }
+        if multisig::is_rejected(&env, tx_id) {
+            panic_with_error!(&env, MultiSigError::AlreadyRejected);
+        }
+        if env.storage().persistent().has(&DataKey::Escalated(tx_id)) {
+            panic_with_error!(&env, MultiSigError::AlreadyEscalated);
+        }
+
+        let age = env.ledger().timestamp().saturating_sub(pending_tx.created_at);
+        if age <= sla {
+            panic_with_error!(&env, MultiSigError::NotOverdue);
+        }
+
+        let auto_route: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::AutoRouteEscalations)
+            .unwrap_or(false);
+        let org: Option<Address> = env.storage().instance().get(&DataKey::OrgHierarchy);
+        let org = org
+            .filter(|_| auto_route)
+            .map(|org| OrgHierarchyClient::new(&env, &org));
+
+        let mut pending_signers: Vec<Address> = Vec::new(&env);
+        let mut routed: Vec<Address> = Vec::new(&env);
+        for signer in multisig::get_signers(&env).iter() {
+            if multisig::has_approval(&env, tx_id, &signer) {
+                continue;
+            }
+            multisig::record_breach(&env, &signer);
+            pending_signers.push_back(signer.clone());
+
+            let Some(manager) = org.as_ref().and_then(|org| org.get_manager(&signer)) else {
+                continue;
+            };
+            // A manager may cover several overdue signers on the same transaction
+            let mut delegated = multisig::get_delegated_signers(&env, tx_id, &manager);
+            delegated.push_back(signer.clone());
+            env.storage()
+                .persistent()
+                .set(&DataKey::EscalatedApprover(tx_id, manager.clone()), &delegated);
+            MultisigEvents::escalation_routed(&env, tx_id, &signer, &manager);
+            if !routed.contains(&manager) {
+                routed.push_back(manager);
+            }
+        }
+
+        env.storage().persistent().set(&DataKey::Escalated(tx_id), &true);
+        MultisigEvents::escalated(&env, tx_id, age, &pending_signers);
+
+        routed
+    }
+
+    /// Sets what happens to pending transactions left undecided for
+    /// `after_secs`: they pass with fewer approvals, or are rejected. `None`
+    /// removes the policy.
+    pub fn set_quorum_decay(env: Env, caller: Address, decay: Option<QuorumDecay>) {
+        Self::require_storage_version(&env);
+        multisig::require_admin(&env, &caller);
+
+        match decay {
+            Some(decay) => {
+                if decay.after_secs == 0 || decay.action == DecayAction::Relax(0) {
+                    panic_with_error!(&env, MultiSigError::InvalidThreshold);
+                }
+                env.storage().instance().set(&DataKey::QuorumDecay, &decay);
+            }
+            None => env.storage().instance().remove(&DataKey::QuorumDecay),
+        }
+    }
+
+    pub fn get_quorum_decay(env: Env) -> Option<QuorumDecay> {
+        multisig::get_quorum_decay(&env)
+    }
+
+    /// Applies the quorum decay policy to a stale pending transaction.
+    ///
+    /// Anyone may trigger this. Under an auto-reject policy the transaction is
+    /// rejected as `Stale`; under a relaxed quorum it executes if it already
+    /// has the relaxed number of approvals. Approvals made after the decay
+    /// age execute under the relaxed quorum without this call.
+    pub fn apply_quorum_decay(env: Env, tx_id: u64) -> DecisionOutcome {
+        Self::require_storage_version(&env);
+        let mut pending_tx: PendingTx = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PendingTx(tx_id))
+            .unwrap_or_else(|| panic_with_error!(&env, MultiSigError::PendingTxNotFound));
+        if pending_tx.executed {
+            panic_with_error!(&env, MultiSigError::AlreadyExecuted);
+        }
+        if multisig::is_rejected(&env, tx_id) {
+            panic_with_error!(&env, MultiSigError::AlreadyRejected);
+        }
+
+        match multisig::decayed_action(&env, &pending_tx) {
+            None => panic_with_error!(&env, MultiSigError::NotOverdue),
+            Some(DecayAction::AutoReject) => {
+                let rejection = Rejection {
+                    rejected_by: env.current_contract_address(),
+                    reason: RejectionReason::Stale,
+                    memo_hash: None,
+                    rejected_at: env.ledger().timestamp(),
+                };
+                multisig::record_rejection(&env, tx_id, &rejection);
+                DecisionOutcome::Rejected
+            }
+            Some(DecayAction::Relax(approvals)) => {
+                if multisig::get_approval_count(&env, tx_id) < approvals {
+                    panic_with_error!(&env, MultiSigError::QuorumNotMet);
+                }
+                multisig::check_blackout(&env, &pending_tx.payload)
+                    .unwrap_or_else(|e| panic_with_error!(&env, e));
+
+                pending_tx.executed = true;
+                env.storage()
+                    .persistent()
+                    .set(&DataKey::PendingTx(tx_id), &pending_tx);
+
+                Self::execute_transfer(&env, &pending_tx.from, &pending_tx.to, pending_tx.amount);
+                MultisigEvents::transaction_executed(
+                    &env,
+                    &pending_tx,
+                    &env.current_contract_address(),
+                );
+                DecisionOutcome::Approved
+            }
+        }
+    }
+
+    pub fn is_escalated(env: Env, tx_id: u64) -> bool {
+        env.storage().persistent().has(&DataKey::Escalated(tx_id))
+    }
+
+    /// Returns an approver's SLA statistics (admin or auditors only).
+    pub fn get_approver_stats(env: Env, caller: Address, approver: Address) -> ApproverSlaStats {
+        multisig::require_admin_or_auditor(&env, &caller);
+        multisig::get_approver_stats(&env, &approver)
+    }
+
+    pub fn get_pending_tx(env: Env, tx_id: u64) -> Option<PendingTx> {
+        env.storage().persistent().get(&DataKey::PendingTx(tx_id))
+    }
+
+    /// Returns a compact digest of the pending transactions awaiting
+    /// `approver`, oldest first, so an approve screen needs one read per refresh.
+    ///
+    /// Starts after transaction `cursor` (0 for the first page). Pass the
+    /// returned `next_cursor` back to continue; it is `None` once every
+    /// transaction has been scanned.
+    pub fn get_pending_for_approver(
+        env: Env,
+        approver: Address,
+        cursor: u64,
+    ) -> ApprovalDigestPage {
+        let last_id: u64 = env.storage().instance().get(&DataKey::NextTxId).unwrap_or(0);
+        let scan_end = last_id.min(cursor.saturating_add(multisig::DIGEST_SCAN_LIMIT));
+        let now = env.ledger().timestamp();
+
+        let mut items: Vec<ApprovalDigest> = Vec::new(&env);
+        let mut tx_id = cursor;
+        while tx_id < scan_end && items.len() < multisig::DIGEST_PAGE_SIZE {
+            tx_id += 1;
+            let pending_tx: Option<PendingTx> =
+                env.storage().persistent().get(&DataKey::PendingTx(tx_id));
+            let Some(pending_tx) = pending_tx else {
+                continue;
+            };
+            if !multisig::awaits_approver(&env, &pending_tx, &approver) {
+                continue;
+            }
+
+            items.push_back(ApprovalDigest {
+                id: pending_tx.id,
+                submitter: pending_tx.from,
+                amount: pending_tx.amount,
+                token: pending_tx.asset,
+                category: pending_tx.payload,
+                age_secs: now.saturating_sub(pending_tx.created_at),
+            });
+        }
+
+        ApprovalDigestPage {
+            items,
+            next_cursor: if tx_id < last_id { Some(tx_id) } else { None },
+        }
+    }
+
+    pub fn get_approval_count(env: Env, tx_id: u64) -> u32 {
+        multisig::get_approval_count(&env, tx_id)
+    }
+
+    pub fn has_approved(env: Env, tx_id: u64, signer: Address) -> bool {
+        multisig::has_approval(&env, tx_id, &signer)
+    }
+}
+
+impl TransactionsContract {
+    // Internal helper refusing to run against storage written by a different
+    // layout version
+    fn require_storage_version(env: &Env) {
+        versioning::require_version(env, STORAGE_VERSION)
+            .unwrap_or_else(|_| panic_with_error!(env, MultiSigError::StorageVersionMismatch));
+    }
+
+    // Approves `tx_id` as `signer`, or rejects it when a rejection reason is
+    // given, checking everything before writing so a failed item leaves no trace
+    fn record_decision(
+        env: &Env,
+        tx_id: u64,
+        signer: &Address,
+        rejection: Option<(RejectionReason, Option<BytesN<32>>)>,
+    ) -> Result<(), MultiSigError> {
+        let (mut pending_tx, approver) = multisig::load_undecided(env, tx_id, signer)?;
+
+        if let Some((reason, memo_hash)) = rejection {
+            multisig::record_response(env, signer, pending_tx.created_at);
+            let rejection = Rejection {
+                rejected_by: signer.clone(),
+                reason,
+                memo_hash,
+                rejected_at: env.ledger().timestamp(),
+            };
+            multisig::record_rejection(env, tx_id, &rejection);
+            return Ok(());
+        }
+
+        let threshold = multisig::get_threshold(env);
+        let approvals = multisig::get_approval_count(env, tx_id).saturating_add(1);
+        let weight = multisig::get_approval_weight(env, tx_id)
+            .saturating_add(multisig::get_signer_weight(env, &approver));
+
+        // A weight threshold, when set, replaces the approval count
+        let weight_threshold = multisig::get_weight_threshold(env);
+        let passed = if weight_threshold > 0 {
+            weight >= weight_threshold
+        } else {
+            approvals >= threshold
+        };
+        // A stale transaction under a relaxed quorum also passes on fewer approvals
+        let passed = passed
+            || matches!(
+                multisig::decayed_action(env, &pending_tx),
+                Some(DecayAction::Relax(relaxed)) if approvals >= relaxed
+            );
+        if passed && Self::balance_of(env, &pending_tx.from) < pending_tx.amount {
+            return Err(MultiSigError::InsufficientBalance);
+        }
+        if passed {
+            multisig::check_blackout(env, &pending_tx.payload)?;
+        }
+
+        multisig::record_approval(env, tx_id, &approver);
+        multisig::record_approval_weight(env, tx_id, &approver);
+        multisig::record_response(env, signer, pending_tx.created_at);
+
+        MultisigEvents::approval_recorded(env, tx_id, signer, approvals, threshold);
+
+        if passed {
+            pending_tx.executed = true;
+            env.storage()
+                .persistent()
+                .set(&DataKey::PendingTx(tx_id), &pending_tx);
+
+            Self::execute_transfer(env, &pending_tx.from, &pending_tx.to, pending_tx.amount);
+            MultisigEvents::transaction_executed(env, &pending_tx, signer);
+        }
+        Ok(())
+    }
+
+    fn decide_batch(
+        env: &Env,
+        tx_ids: &Vec<u64>,
+        signer: &Address,
+        rejection: Option<(RejectionReason, Option<BytesN<32>>)>,
+    ) -> Vec<DecisionOutcome> {
+        if tx_ids.len() > multisig::MAX_DECISION_BATCH {
+            panic_with_error!(env, MultiSigError::BatchTooLarge);
+        }
+
+        let decided = if rejection.is_some() {
+            DecisionOutcome::Rejected
+        } else {
+            DecisionOutcome::Approved
+        };
+        let mut outcomes = Vec::new(env);
+        for tx_id in tx_ids.iter() {
+            let outcome = match Self::record_decision(env, tx_id, signer, rejection.clone()) {
+                Ok(()) => decided,
+                Err(error) => DecisionOutcome::from_error(error),
+            };
+            outcomes.push_back(outcome);
+        }
+        outcomes
+    }
+
+    fn execute_transfer(env: &Env, from: &Address, to: &Address, amount: i128) {
+        let from_balance = Self::balance_of(env, from);
+        if from_balance < amount {
+            panic_with_error!(env, MultiSigError::InsufficientBalance);
+        }
+
+        let to_balance = Self::balance_of(env, to);
+
+        let new_from_balance = from_balance
+            .checked_sub(amount)
+            .unwrap_or_else(|| panic_with_error!(env, MultiSigError::Overflow));
+        let new_to_balance = to_balance
+            .checked_add(amount)
+            .unwrap_or_else(|| panic_with_error!(env, MultiSigError::Overflow));
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Balance(from.clone()), &new_from_balance);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Balance(to.clone()), &new_to_balance);
+    }
+
+    fn balance_of(env: &Env, user: &Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Balance(user.clone()))
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,779 @@
+use common::events;
+use soroban_sdk::{
+    contracterror, contracttype, panic_with_error, symbol_short, Address, BytesN, Env, Symbol, Vec,
+};
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    Signers,
+    Threshold,
+    HighValueThreshold,
+    NextTxId,
+    PendingTx(u64),
+    Approval(u64, Address),
+    ApprovalCount(u64),
+    Balance(Address),
+    ApprovalSla,
+    AutoRouteEscalations,
+    OrgHierarchy,
+    Auditor(Address),
+    Escalated(u64),
+    EscalatedApprover(u64, Address),
+    ApproverStats(Address),
+    Conflicts(Address),
+    SignerWeight(Address),
+    WeightThreshold,
+    ApprovalWeight(u64),
+    Rejection(u64),
+    RejectionCount(RejectionReason),
+    Comments(u64),
+    QuorumDecay,
+    Blackouts,
+    CriticalCategory(Symbol),
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct PendingTx {
+    pub id: u64,
+    pub from: Address,
+    pub to: Address,
+    pub amount: i128,
+    pub payload: Symbol,
+    pub asset: Option<Address>,
+    pub created_at: u64,
+    pub executed: bool,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[contracttype]
+pub struct ApproverSlaStats {
+    pub approvals: u32,
+    pub total_response_secs: u64,
+    pub max_response_secs: u64,
+    pub breaches: u32,
+}
+
+/// Structured reason a pending transaction was rejected.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum RejectionReason {
+    Duplicate,
+    OverBudget,
+    MissingDocumentation,
+    PolicyViolation,
+    SuspectedFraud,
+    Other,
+    /// Left undecided past the quorum decay age under an auto-reject policy
+    Stale,
+}
+
+/// Record of who rejected a pending transaction and why.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Rejection {
+    pub rejected_by: Address,
+    pub reason: RejectionReason,
+    /// Hash of a free-text memo kept off-chain, if the approver wrote one
+    pub memo_hash: Option<BytesN<32>>,
+    pub rejected_at: u64,
+}
+
+/// Anchor for a comment on a pending transaction; the text is kept off-chain.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Comment {
+    pub author: Address,
+    /// Hash of the comment text
+    pub body_hash: BytesN<32>,
+    pub posted_at: u64,
+}
+
+/// What happens to a pending transaction still undecided at the decay age.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum DecayAction {
+    /// The transaction passes with this many approvals
+    Relax(u32),
+    /// The transaction is rejected
+    AutoReject,
+}
+
+/// Policy relaxing or ending approval of stale transactions, so an absent
+/// approver cannot block a transaction forever.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct QuorumDecay {
+    /// Age in seconds at which a pending transaction counts as stale
+    pub after_secs: u64,
+    pub action: DecayAction,
+}
+
+/// Window during which only critical categories may be spent, e.g. while
+/// the books are closed for an audit or at year-end.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Blackout {
+    pub start: u64,
+    /// End of the window, exclusive
+    pub end: u64,
+    /// Why spending is frozen, e.g. `yearend`
+    pub reason: Symbol,
+}
+
+/// Most blackout windows in the schedule.
+pub const MAX_BLACKOUTS: u32 = 20;
+
+/// Most comments kept per transaction.
+pub const MAX_COMMENTS: u32 = 100;
+
+/// Per-item result of a batch approval or rejection.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum DecisionOutcome {
+    Approved,
+    Rejected,
+    /// The transaction was already executed or rejected, or the approver had
+    /// already approved it
+    AlreadyDecided,
+    /// The caller is not an approver for the transaction or has a conflict
+    NotAuthorized,
+    NotFound,
+    /// The approval would execute the transfer but the submitter cannot cover it
+    InsufficientBalance,
+    /// The approval would execute the transfer during a blackout
+    BlackoutActive,
+}
+
+impl DecisionOutcome {
+    pub fn from_error(error: MultiSigError) -> Self {
+        match error {
+            MultiSigError::PendingTxNotFound => DecisionOutcome::NotFound,
+            MultiSigError::InsufficientBalance => DecisionOutcome::InsufficientBalance,
+            MultiSigError::BlackoutActive => DecisionOutcome::BlackoutActive,
+            MultiSigError::AlreadyExecuted
+            | MultiSigError::AlreadyRejected
+            | MultiSigError::DuplicateApproval
+            | MultiSigError::Stale => DecisionOutcome::AlreadyDecided,
+            _ => DecisionOutcome::NotAuthorized,
+        }
+    }
+}
+
+/// Most transactions one batch approval or rejection may cover.
+pub const MAX_DECISION_BATCH: u32 = 50;
+
+/// Compact view of a pending transaction for approver inboxes.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ApprovalDigest {
+    pub id: u64,
+    pub submitter: Address,
+    pub amount: i128,
+    pub token: Option<Address>,
+    pub category: Symbol,
+    pub age_secs: u64,
+}
+
+/// A page of approval digests and the cursor to continue from, if any.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ApprovalDigestPage {
+    pub items: Vec<ApprovalDigest>,
+    pub next_cursor: Option<u64>,
+}
+
+/// Most digests returned in one page.
+pub const DIGEST_PAGE_SIZE: u32 = 20;
+/// Most transaction ids scanned in one page, bounding the read cost.
+pub const DIGEST_SCAN_LIMIT: u64 = 200;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum MultiSigError {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    Unauthorized = 3,
+    InvalidThreshold = 4,
+    DuplicateSigner = 5,
+    InvalidAmount = 6,
+    PendingTxNotFound = 7,
+    UnauthorizedSigner = 8,
+    DuplicateApproval = 9,
+    AlreadyExecuted = 10,
+    InsufficientBalance = 11,
+    MultisigNotConfigured = 12,
+    Overflow = 13,
+    SlaNotConfigured = 14,
+    NotOverdue = 15,
+    AlreadyEscalated = 16,
+    ConflictOfInterest = 17,
+    InvalidWeight = 18,
+    AlreadyRejected = 19,
+    BatchTooLarge = 20,
+    CommentLimitReached = 21,
+    Stale = 22,
+    QuorumNotMet = 23,
+    BlackoutActive = 24,
+    InvalidBlackout = 25,
+    StorageVersionMismatch = 26,
+    InvalidMigration = 27,
+}
+
+pub struct MultisigEvents;
+
+impl MultisigEvents {
+    pub fn pending_created(env: &Env, tx: &PendingTx) {
+        let topics = (symbol_short!("tx"), symbol_short!("pending"), tx.id);
+        events::publish(
+            env,
+            topics,
+            (tx.from.clone(), tx.to.clone(), tx.amount, tx.asset.clone()),
+        );
+    }
+
+    pub fn approval_recorded(
+        env: &Env,
+        tx_id: u64,
+        signer: &Address,
+        approvals_count: u32,
+        threshold: u32,
+    ) {
+        let topics = (symbol_short!("approve"), symbol_short!("record"), tx_id);
+        events::publish(env, topics, (signer.clone(), approvals_count, threshold));
+    }
+
+    pub fn transaction_executed(env: &Env, tx: &PendingTx, executor: &Address) {
+        let topics = (symbol_short!("tx"), symbol_short!("executed"), tx.id);
+        events::publish(
+            env,
+            topics,
+            (
+                executor.clone(),
+                tx.from.clone(),
+                tx.to.clone(),
+                tx.amount,
+                tx.asset.clone(),
+            ),
+        );
+    }
+
+    pub fn escalated(env: &Env, tx_id: u64, age_secs: u64, pending: &Vec<Address>) {
+        let topics = (symbol_short!("approve"), symbol_short!("escalate"), tx_id);
+        events::publish(env, topics, (age_secs, pending.clone()));
+    }
+
+    pub fn conflicts_updated(env: &Env, approver: &Address, conflicts: &Vec<Address>) {
+        let topics = (symbol_short!("approve"), symbol_short!("conflict"));
+        events::publish(env, topics, (approver.clone(), conflicts.clone()));
+    }
+
+    pub fn escalation_routed(env: &Env, tx_id: u64, approver: &Address, manager: &Address) {
+        let topics = (symbol_short!("approve"), symbol_short!("routed"), tx_id);
+        events::publish(env, topics, (approver.clone(), manager.clone()));
+    }
+
+    pub fn comment_posted(env: &Env, tx_id: u64, comment: &Comment) {
+        let topics = (symbol_short!("tx"), symbol_short!("comment"), tx_id);
+        events::publish(
+            env,
+            topics,
+            (comment.author.clone(), comment.body_hash.clone()),
+        );
+    }
+
+    pub fn blackouts_updated(env: &Env, blackouts: &Vec<Blackout>) {
+        let topics = (symbol_short!("blackout"), symbol_short!("schedule"));
+        events::publish(env, topics, blackouts.clone());
+    }
+
+    pub fn transaction_rejected(env: &Env, tx_id: u64, rejection: &Rejection) {
+        let topics = (symbol_short!("tx"), symbol_short!("rejected"), tx_id);
+        events::publish(
+            env,
+            topics,
+            (
+                rejection.rejected_by.clone(),
+                rejection.reason,
+                rejection.memo_hash.clone(),
+            ),
+        );
+    }
+}
+
+pub fn initialize_state(env: &Env, admin: Address) {
+    if env.storage().instance().has(&DataKey::Admin) {
+        panic_with_error!(env, MultiSigError::AlreadyInitialized);
+    }
+
+    env.storage().instance().set(&DataKey::Admin, &admin);
+    env.storage()
+        .instance()
+        .set(&DataKey::Signers, &Vec::<Address>::new(env));
+    env.storage().instance().set(&DataKey::Threshold, &0u32);
+    env.storage()
+        .instance()
+        .set(&DataKey::HighValueThreshold, &i128::MAX);
+    env.storage().instance().set(&DataKey::NextTxId, &0u64);
+}
+
+pub fn get_admin(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .unwrap_or_else(|| panic_with_error!(env, MultiSigError::NotInitialized))
+}
+
+pub fn require_admin(env: &Env, caller: &Address) {
+    caller.require_auth();
+    let admin = get_admin(env);
+    if admin != caller.clone() {
+        panic_with_error!(env, MultiSigError::Unauthorized);
+    }
+}
+
+pub fn set_signers(env: &Env, caller: Address, signers: Vec<Address>, threshold: u32) {
+    require_admin(env, &caller);
+    validate_signer_config(env, &signers, threshold);
+
+    env.storage().instance().set(&DataKey::Signers, &signers);
+    env.storage().instance().set(&DataKey::Threshold, &threshold);
+    require_reachable_weight_threshold(env);
+}
+
+pub fn set_high_value_threshold(env: &Env, caller: Address, amount: i128) {
+    require_admin(env, &caller);
+
+    if amount < 0 {
+        panic_with_error!(env, MultiSigError::InvalidAmount);
+    }
+
+    env.storage()
+        .instance()
+        .set(&DataKey::HighValueThreshold, &amount);
+}
+
+pub fn get_signers(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Signers)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+pub fn get_threshold(env: &Env) -> u32 {
+    env.storage().instance().get(&DataKey::Threshold).unwrap_or(0)
+}
+
+pub fn get_high_value_threshold(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::HighValueThreshold)
+        .unwrap_or(i128::MAX)
+}
+
+pub fn ensure_multisig_configured(env: &Env) {
+    let signers = get_signers(env);
+    let threshold = get_threshold(env);
+
+    if signers.is_empty() || threshold == 0 || threshold > signers.len() {
+        panic_with_error!(env, MultiSigError::MultisigNotConfigured);
+    }
+}
+
+pub fn is_signer(env: &Env, signer: &Address) -> bool {
+    let signers = get_signers(env);
+    for configured in signers.iter() {
+        if configured == signer.clone() {
+            return true;
+        }
+    }
+    false
+}
+
+pub fn next_tx_id(env: &Env) -> u64 {
+    let current: u64 = env.storage().instance().get(&DataKey::NextTxId).unwrap_or(0);
+    let next = current
+        .checked_add(1)
+        .unwrap_or_else(|| panic_with_error!(env, MultiSigError::Overflow));
+
+    env.storage().instance().set(&DataKey::NextTxId, &next);
+    next
+}
+
+pub fn has_approval(env: &Env, tx_id: u64, signer: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::Approval(tx_id, signer.clone()))
+}
+
+pub fn get_approval_count(env: &Env, tx_id: u64) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ApprovalCount(tx_id))
+        .unwrap_or(0)
+}
+
+pub fn record_approval(env: &Env, tx_id: u64, signer: &Address) -> u32 {
+    if has_approval(env, tx_id, signer) {
+        panic_with_error!(env, MultiSigError::DuplicateApproval);
+    }
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::Approval(tx_id, signer.clone()), &true);
+
+    let current = get_approval_count(env, tx_id);
+    let next = current
+        .checked_add(1)
+        .unwrap_or_else(|| panic_with_error!(env, MultiSigError::Overflow));
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::ApprovalCount(tx_id), &next);
+
+    next
+}
+
+fn validate_signer_config(env: &Env, signers: &Vec<Address>, threshold: u32) {
+    let signer_count = signers.len();
+
+    if signer_count == 0 || threshold == 0 || threshold > signer_count {
+        panic_with_error!(env, MultiSigError::InvalidThreshold);
+    }
+
+    for i in 0..signer_count {
+        let signer_i = signers
+            .get(i)
+            .unwrap_or_else(|| panic_with_error!(env, MultiSigError::InvalidThreshold));
+
+        for j in (i + 1)..signer_count {
+            let signer_j = signers
+                .get(j)
+                .unwrap_or_else(|| panic_with_error!(env, MultiSigError::InvalidThreshold));
+            if signer_i == signer_j {
+                panic_with_error!(env, MultiSigError::DuplicateSigner);
+            }
+        }
+    }
+}
+
+pub fn get_approval_sla(env: &Env) -> u64 {
+    env.storage().instance().get(&DataKey::ApprovalSla).unwrap_or(0)
+}
+
+pub fn is_auditor(env: &Env, user: &Address) -> bool {
+    env.storage()
+        .instance()
+        .get(&DataKey::Auditor(user.clone()))
+        .unwrap_or(false)
+}
+
+pub fn require_admin_or_auditor(env: &Env, caller: &Address) {
+    caller.require_auth();
+    if get_admin(env) != caller.clone() && !is_auditor(env, caller) {
+        panic_with_error!(env, MultiSigError::Unauthorized);
+    }
+}
+
+pub fn get_approver_stats(env: &Env, approver: &Address) -> ApproverSlaStats {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ApproverStats(approver.clone()))
+        .unwrap_or_default()
+}
+
+/// Records how long `approver` took to act on a transaction created at `created_at`.
+pub fn record_response(env: &Env, approver: &Address, created_at: u64) {
+    let response = env.ledger().timestamp().saturating_sub(created_at);
+    let sla = get_approval_sla(env);
+
+    let mut stats = get_approver_stats(env, approver);
+    stats.approvals += 1;
+    stats.total_response_secs = stats.total_response_secs.saturating_add(response);
+    if response > stats.max_response_secs {
+        stats.max_response_secs = response;
+    }
+    if sla > 0 && response > sla {
+        stats.breaches += 1;
+    }
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::ApproverStats(approver.clone()), &stats);
+}
+
+/// Records an SLA breach for a signer who let a transaction go overdue.
+pub fn record_breach(env: &Env, approver: &Address) {
+    let mut stats = get_approver_stats(env, approver);
+    stats.breaches += 1;
+    env.storage()
+        .persistent()
+        .set(&DataKey::ApproverStats(approver.clone()), &stats);
+}
+
+/// Signers whose overdue approval on `tx_id` was routed to `manager`.
+pub fn get_delegated_signers(env: &Env, tx_id: u64, manager: &Address) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::EscalatedApprover(tx_id, manager.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Resolves who an approval counts for: signers approve as themselves, and a
+/// manager overdue approvals were routed to approves on behalf of the first of
+/// those signers who has not approved yet.
+pub fn resolve_approver(env: &Env, tx_id: u64, caller: &Address) -> Result<Address, MultiSigError> {
+    if is_signer(env, caller) {
+        return Ok(caller.clone());
+    }
+
+    let delegated = get_delegated_signers(env, tx_id, caller);
+    let first = delegated.first().ok_or(MultiSigError::UnauthorizedSigner)?;
+    Ok(delegated
+        .iter()
+        .find(|signer| !has_approval(env, tx_id, signer))
+        .unwrap_or(first))
+}
+
+/// Loads a pending transaction `caller` may still approve or reject, along
+/// with the approver the decision counts for.
+pub fn load_undecided(
+    env: &Env,
+    tx_id: u64,
+    caller: &Address,
+) -> Result<(PendingTx, Address), MultiSigError> {
+    let approver = resolve_approver(env, tx_id, caller)?;
+
+    let pending_tx: PendingTx = env
+        .storage()
+        .persistent()
+        .get(&DataKey::PendingTx(tx_id))
+        .ok_or(MultiSigError::PendingTxNotFound)?;
+    if pending_tx.executed {
+        return Err(MultiSigError::AlreadyExecuted);
+    }
+    if is_rejected(env, tx_id) {
+        return Err(MultiSigError::AlreadyRejected);
+    }
+    if decayed_action(env, &pending_tx) == Some(DecayAction::AutoReject) {
+        return Err(MultiSigError::Stale);
+    }
+    if has_conflict(env, caller, &pending_tx.from)
+        || (approver != *caller && has_conflict(env, &approver, &pending_tx.from))
+    {
+        return Err(MultiSigError::ConflictOfInterest);
+    }
+    if has_approval(env, tx_id, &approver) {
+        return Err(MultiSigError::DuplicateApproval);
+    }
+
+    Ok((pending_tx, approver))
+}
+
+pub fn is_rejected(env: &Env, tx_id: u64) -> bool {
+    env.storage().persistent().has(&DataKey::Rejection(tx_id))
+}
+
+pub fn get_rejection(env: &Env, tx_id: u64) -> Option<Rejection> {
+    env.storage().persistent().get(&DataKey::Rejection(tx_id))
+}
+
+pub fn get_rejection_count(env: &Env, reason: RejectionReason) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::RejectionCount(reason))
+        .unwrap_or(0)
+}
+
+/// Stores a rejection and counts it under its reason for compliance reporting.
+pub fn record_rejection(env: &Env, tx_id: u64, rejection: &Rejection) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Rejection(tx_id), rejection);
+
+    let count = get_rejection_count(env, rejection.reason).saturating_add(1);
+    env.storage()
+        .persistent()
+        .set(&DataKey::RejectionCount(rejection.reason), &count);
+
+    MultisigEvents::transaction_rejected(env, tx_id, rejection);
+}
+
+pub fn get_comments(env: &Env, tx_id: u64) -> Vec<Comment> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Comments(tx_id))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Appends a comment to a transaction's thread. Comments are never edited or
+/// removed, so the thread stays a faithful record for disputes.
+pub fn append_comment(env: &Env, tx_id: u64, comment: &Comment) -> Result<u32, MultiSigError> {
+    let mut comments = get_comments(env, tx_id);
+    if comments.len() >= MAX_COMMENTS {
+        return Err(MultiSigError::CommentLimitReached);
+    }
+    comments.push_back(comment.clone());
+    env.storage()
+        .persistent()
+        .set(&DataKey::Comments(tx_id), &comments);
+
+    MultisigEvents::comment_posted(env, tx_id, comment);
+    Ok(comments.len() - 1)
+}
+
+/// Whether `approver` can still approve `tx`: as a signer who has not yet
+/// approved, or as the manager an unanswered approval was routed to.
+pub fn awaits_approver(env: &Env, tx: &PendingTx, approver: &Address) -> bool {
+    if tx.executed || is_rejected(env, tx.id) || has_conflict(env, approver, &tx.from) {
+        return false;
+    }
+    if decayed_action(env, tx) == Some(DecayAction::AutoReject) {
+        return false;
+    }
+    if is_signer(env, approver) {
+        return !has_approval(env, tx.id, approver);
+    }
+
+    get_delegated_signers(env, tx.id, approver)
+        .iter()
+        .any(|signer| !has_approval(env, tx.id, &signer) && !has_conflict(env, &signer, &tx.from))
+}
+
+pub fn get_quorum_decay(env: &Env) -> Option<QuorumDecay> {
+    env.storage().instance().get(&DataKey::QuorumDecay)
+}
+
+/// Returns the decay action that applies to `tx` now, or `None` while it is
+/// younger than the decay age or no policy is set.
+pub fn decayed_action(env: &Env, tx: &PendingTx) -> Option<DecayAction> {
+    let decay = get_quorum_decay(env)?;
+    let age = env.ledger().timestamp().saturating_sub(tx.created_at);
+    if age >= decay.after_secs {
+        Some(decay.action)
+    } else {
+        None
+    }
+}
+
+pub fn get_blackouts(env: &Env) -> Vec<Blackout> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Blackouts)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Replaces the blackout schedule after checking every window ends after it
+/// starts.
+pub fn set_blackouts(env: &Env, blackouts: &Vec<Blackout>) -> Result<(), MultiSigError> {
+    if blackouts.len() > MAX_BLACKOUTS {
+        return Err(MultiSigError::InvalidBlackout);
+    }
+    if blackouts.iter().any(|blackout| blackout.start >= blackout.end) {
+        return Err(MultiSigError::InvalidBlackout);
+    }
+
+    env.storage().instance().set(&DataKey::Blackouts, blackouts);
+    MultisigEvents::blackouts_updated(env, blackouts);
+    Ok(())
+}
+
+/// Returns the blackout window covering the current ledger time, if any.
+pub fn active_blackout(env: &Env) -> Option<Blackout> {
+    let now = env.ledger().timestamp();
+    get_blackouts(env)
+        .iter()
+        .find(|blackout| blackout.start <= now && now < blackout.end)
+}
+
+pub fn is_critical_category(env: &Env, category: &Symbol) -> bool {
+    env.storage()
+        .instance()
+        .get(&DataKey::CriticalCategory(category.clone()))
+        .unwrap_or(false)
+}
+
+/// Fails while a blackout is active unless `category` is whitelisted as
+/// critical.
+pub fn check_blackout(env: &Env, category: &Symbol) -> Result<(), MultiSigError> {
+    if active_blackout(env).is_some() && !is_critical_category(env, category) {
+        return Err(MultiSigError::BlackoutActive);
+    }
+    Ok(())
+}
+
+pub fn get_conflicts(env: &Env, approver: &Address) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Conflicts(approver.clone()))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+pub fn set_conflicts(env: &Env, approver: &Address, conflicts: &Vec<Address>) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Conflicts(approver.clone()), conflicts);
+    MultisigEvents::conflicts_updated(env, approver, conflicts);
+}
+
+/// Whether `approver` submitted the transaction themselves or has declared a
+/// conflict of interest with the submitter.
+fn has_conflict(env: &Env, approver: &Address, from: &Address) -> bool {
+    approver == from || get_conflicts(env, approver).contains(from)
+}
+
+/// Returns a signer's voting weight; signers without an explicit weight count once.
+pub fn get_signer_weight(env: &Env, signer: &Address) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::SignerWeight(signer.clone()))
+        .unwrap_or(1)
+}
+
+/// Returns the accumulated weight a transaction needs, or 0 when approvals are
+/// counted instead of weighed.
+pub fn get_weight_threshold(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::WeightThreshold)
+        .unwrap_or(0)
+}
+
+pub fn get_approval_weight(env: &Env, tx_id: u64) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ApprovalWeight(tx_id))
+        .unwrap_or(0)
+}
+
+/// Adds `approver`'s weight to a transaction and returns the new total.
+pub fn record_approval_weight(env: &Env, tx_id: u64, approver: &Address) -> u32 {
+    let weight = get_approval_weight(env, tx_id)
+        .checked_add(get_signer_weight(env, approver))
+        .unwrap_or_else(|| panic_with_error!(env, MultiSigError::Overflow));
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::ApprovalWeight(tx_id), &weight);
+
+    weight
+}
+
+/// Returns the combined weight of the configured signers.
+pub fn total_signer_weight(env: &Env) -> u32 {
+    let mut total: u32 = 0;
+    for signer in get_signers(env).iter() {
+        total = total.saturating_add(get_signer_weight(env, &signer));
+    }
+    total
+}
+
+/// Fails when the configured signers can no longer reach the weight threshold.
+pub fn require_reachable_weight_threshold(env: &Env) {
+    if get_weight_threshold(env) > total_signer_weight(env) {
+        panic_with_error!(env, MultiSigError::InvalidThreshold);
+    }
+}
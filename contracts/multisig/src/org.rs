@@ -0,0 +1,11 @@
+//! Interface of the org-hierarchy contract that overdue approvals route through.
+
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Minimal org chart interface used to find an approver's manager.
+#[allow(dead_code)]
+#[contractclient(name = "OrgHierarchyClient")]
+pub trait OrgHierarchy {
+    /// Returns an employee's direct manager.
+    fn get_manager(env: Env, employee: Address) -> Option<Address>;
+}
@@ -0,0 +1,965 @@
+//! Integration tests for the multisig transactions contract.
+
+#![cfg(test)]
+
+use crate::{
+    Blackout, DecayAction, DecisionOutcome, MultiSigError, QuorumDecay, RejectionReason,
+    TransactionsContract, TransactionsContractClient,
+};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Events as _, Ledger},
+    Address, BytesN, Env, Symbol, TryFromVal, Vec,
+};
+
+fn setup_test_contract() -> (Env, Address, TransactionsContractClient<'static>) {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(TransactionsContract, ());
+    let client = TransactionsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    (env, admin, client)
+}
+
+/// Org chart standing in for the org-hierarchy contract.
+#[contract]
+struct MockOrgHierarchy;
+
+#[contractimpl]
+impl MockOrgHierarchy {
+    pub fn set_manager(env: Env, employee: Address, manager: Address) {
+        env.storage().persistent().set(&employee, &manager);
+    }
+
+    pub fn get_manager(env: Env, employee: Address) -> Option<Address> {
+        env.storage().persistent().get(&employee)
+    }
+}
+
+fn setup_org(
+    env: &Env,
+    client: &TransactionsContractClient<'static>,
+    admin: &Address,
+) -> MockOrgHierarchyClient<'static> {
+    let org_id = env.register(MockOrgHierarchy, ());
+    client.set_org_hierarchy(admin, &org_id);
+    MockOrgHierarchyClient::new(env, &org_id)
+}
+
+fn configure_multisig(
+    env: &Env,
+    client: &TransactionsContractClient<'static>,
+    admin: &Address,
+    threshold: u32,
+) -> (Address, Address, Address) {
+    let signer_1 = Address::generate(env);
+    let signer_2 = Address::generate(env);
+    let signer_3 = Address::generate(env);
+
+    let mut signers: Vec<Address> = Vec::new(env);
+    signers.push_back(signer_1.clone());
+    signers.push_back(signer_2.clone());
+    signers.push_back(signer_3.clone());
+
+    client.set_signers(admin, &signers, &threshold);
+    client.set_high_value_threshold(admin, &100);
+
+    (signer_1, signer_2, signer_3)
+}
+
+#[test]
+fn test_set_signers_and_threshold_works() {
+    let (env, admin, client) = setup_test_contract();
+
+    let signer_1 = Address::generate(&env);
+    let signer_2 = Address::generate(&env);
+
+    let mut signers: Vec<Address> = Vec::new(&env);
+    signers.push_back(signer_1.clone());
+    signers.push_back(signer_2.clone());
+
+    client.set_signers(&admin, &signers, &2);
+
+    let configured = client.get_signers();
+    assert_eq!(configured.len(), 2);
+    assert_eq!(configured.get(0), Some(signer_1));
+    assert_eq!(configured.get(1), Some(signer_2));
+    assert_eq!(client.get_threshold(), 2);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #26)")]
+fn test_outdated_storage_blocks_writes() {
+    let (env, admin, client) = setup_test_contract();
+    env.as_contract(&client.address, || common::versioning::set_version(&env, 0));
+
+    client.set_high_value_threshold(&admin, &100);
+}
+
+#[test]
+fn test_migrate_from_unversioned_storage() {
+    let (env, admin, client) = setup_test_contract();
+    assert_eq!(client.get_storage_version(), crate::STORAGE_VERSION);
+    env.as_contract(&client.address, || common::versioning::set_version(&env, 0));
+
+    client.migrate(&admin, &0);
+    assert_eq!(client.get_storage_version(), crate::STORAGE_VERSION);
+    client.set_high_value_threshold(&admin, &100);
+    assert_eq!(client.get_high_value_threshold(), 100);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #27)")]
+fn test_migrate_from_wrong_version() {
+    let (_env, admin, client) = setup_test_contract();
+    client.migrate(&admin, &0);
+}
+
+#[test]
+#[should_panic]
+fn test_set_signers_admin_only() {
+    let (env, admin, client) = setup_test_contract();
+
+    let signer_1 = Address::generate(&env);
+    let signer_2 = Address::generate(&env);
+
+    let mut signers: Vec<Address> = Vec::new(&env);
+    signers.push_back(signer_1);
+    signers.push_back(signer_2);
+
+    let unauthorized = Address::generate(&env);
+
+    // Admin config works first, then unauthorized update is rejected.
+    client.set_signers(&admin, &signers, &2);
+    client.set_signers(&unauthorized, &signers, &1);
+}
+
+#[test]
+#[should_panic]
+fn test_invalid_threshold_zero_rejected() {
+    let (env, admin, client) = setup_test_contract();
+
+    let signer_1 = Address::generate(&env);
+
+    let mut signers: Vec<Address> = Vec::new(&env);
+    signers.push_back(signer_1);
+
+    client.set_signers(&admin, &signers, &0);
+}
+
+#[test]
+#[should_panic]
+fn test_invalid_threshold_above_signers_rejected() {
+    let (env, admin, client) = setup_test_contract();
+
+    let signer_1 = Address::generate(&env);
+    let signer_2 = Address::generate(&env);
+
+    let mut signers: Vec<Address> = Vec::new(&env);
+    signers.push_back(signer_1);
+    signers.push_back(signer_2);
+
+    client.set_signers(&admin, &signers, &3);
+}
+
+#[test]
+#[should_panic]
+fn test_duplicate_signers_rejected() {
+    let (env, admin, client) = setup_test_contract();
+
+    let signer = Address::generate(&env);
+
+    let mut signers: Vec<Address> = Vec::new(&env);
+    signers.push_back(signer.clone());
+    signers.push_back(signer);
+
+    client.set_signers(&admin, &signers, &1);
+}
+
+#[test]
+fn test_low_value_executes_immediately_no_pending() {
+    let (env, admin, client) = setup_test_contract();
+
+    configure_multisig(&env, &client, &admin, 2);
+
+    let from = Address::generate(&env);
+    let to = Address::generate(&env);
+
+    client.set_balance(&admin, &from, &1_000);
+
+    let asset: Option<Address> = None;
+    let pending_id = client.submit_transaction(&from, &to, &50, &symbol_short!("pay"), &asset);
+
+    assert_eq!(pending_id, None);
+    assert_eq!(client.get_balance(&from), 950);
+    assert_eq!(client.get_balance(&to), 50);
+}
+
+#[test]
+fn test_high_value_creates_pending_record() {
+    let (env, admin, client) = setup_test_contract();
+
+    configure_multisig(&env, &client, &admin, 2);
+
+    let from = Address::generate(&env);
+    let to = Address::generate(&env);
+
+    client.set_balance(&admin, &from, &1_000);
+
+    let asset: Option<Address> = None;
+    let pending_id = client.submit_transaction(&from, &to, &100, &symbol_short!("pay"), &asset);
+    let tx_id = pending_id.expect("expected pending tx id");
+
+    let pending = client.get_pending_tx(&tx_id).expect("missing pending tx");
+    assert_eq!(pending.id, tx_id);
+    assert_eq!(pending.amount, 100);
+    assert_eq!(pending.from, from);
+    assert_eq!(pending.to, to);
+    assert!(!pending.executed);
+
+    // No balance movement before approvals reach threshold.
+    assert_eq!(client.get_balance(&pending.from), 1_000);
+    assert_eq!(client.get_balance(&pending.to), 0);
+}
+
+#[test]
+#[should_panic]
+fn test_unauthorized_approver_rejected() {
+    let (env, admin, client) = setup_test_contract();
+
+    let (signer_1, _signer_2, _signer_3) = configure_multisig(&env, &client, &admin, 2);
+
+    let from = Address::generate(&env);
+    let to = Address::generate(&env);
+    client.set_balance(&admin, &from, &1_000);
+
+    let asset: Option<Address> = None;
+    let tx_id = client
+        .submit_transaction(&from, &to, &300, &symbol_short!("pay"), &asset)
+        .expect("expected pending tx id");
+
+    // Signer 1 is valid; this outsider is not.
+    let outsider = Address::generate(&env);
+    assert_ne!(outsider, signer_1);
+    client.approve(&tx_id, &outsider);
+}
+
+#[test]
+fn test_signer_can_approve_once_and_approval_persists() {
+    let (env, admin, client) = setup_test_contract();
+
+    let (signer_1, signer_2, _signer_3) = configure_multisig(&env, &client, &admin, 2);
+
+    let from = Address::generate(&env);
+    let to = Address::generate(&env);
+    client.set_balance(&admin, &from, &1_000);
+
+    let asset: Option<Address> = None;
+    let tx_id = client
+        .submit_transaction(&from, &to, &300, &symbol_short!("pay"), &asset)
+        .expect("expected pending tx id");
+
+    client.approve(&tx_id, &signer_1);
+
+    assert_eq!(client.get_approval_count(&tx_id), 1);
+    assert!(client.has_approved(&tx_id, &signer_1));
+    assert!(!client.has_approved(&tx_id, &signer_2));
+
+    let pending = client.get_pending_tx(&tx_id).expect("missing pending tx");
+    assert!(!pending.executed);
+}
+
+#[test]
+#[should_panic]
+fn test_duplicate_approval_rejected() {
+    let (env, admin, client) = setup_test_contract();
+
+    let (signer_1, _signer_2, _signer_3) = configure_multisig(&env, &client, &admin, 2);
+
+    let from = Address::generate(&env);
+    let to = Address::generate(&env);
+    client.set_balance(&admin, &from, &1_000);
+
+    let asset: Option<Address> = None;
+    let tx_id = client
+        .submit_transaction(&from, &to, &300, &symbol_short!("pay"), &asset)
+        .expect("expected pending tx id");
+
+    client.approve(&tx_id, &signer_1);
+    client.approve(&tx_id, &signer_1);
+}
+
+#[test]
+fn test_does_not_execute_before_threshold() {
+    let (env, admin, client) = setup_test_contract();
+
+    let (signer_1, _signer_2, _signer_3) = configure_multisig(&env, &client, &admin, 2);
+
+    let from = Address::generate(&env);
+    let to = Address::generate(&env);
+    client.set_balance(&admin, &from, &1_000);
+
+    let asset: Option<Address> = None;
+    let tx_id = client
+        .submit_transaction(&from, &to, &300, &symbol_short!("pay"), &asset)
+        .expect("expected pending tx id");
+
+    client.approve(&tx_id, &signer_1);
+
+    let pending = client.get_pending_tx(&tx_id).expect("missing pending tx");
+    assert!(!pending.executed);
+    assert_eq!(client.get_balance(&from), 1_000);
+    assert_eq!(client.get_balance(&to), 0);
+}
+
+#[test]
+fn test_executes_exactly_at_threshold_and_changes_state_once() {
+    let (env, admin, client) = setup_test_contract();
+
+    let (signer_1, signer_2, _signer_3) = configure_multisig(&env, &client, &admin, 2);
+
+    let from = Address::generate(&env);
+    let to = Address::generate(&env);
+    client.set_balance(&admin, &from, &1_000);
+
+    let asset: Option<Address> = None;
+    let tx_id = client
+        .submit_transaction(&from, &to, &300, &symbol_short!("pay"), &asset)
+        .expect("expected pending tx id");
+
+    client.approve(&tx_id, &signer_1);
+    assert_eq!(client.get_balance(&from), 1_000);
+    assert_eq!(client.get_balance(&to), 0);
+
+    client.approve(&tx_id, &signer_2);
+
+    let pending = client.get_pending_tx(&tx_id).expect("missing pending tx");
+    assert!(pending.executed);
+    assert_eq!(client.get_approval_count(&tx_id), 2);
+    assert_eq!(client.get_balance(&from), 700);
+    assert_eq!(client.get_balance(&to), 300);
+}
+
+#[test]
+#[should_panic]
+fn test_cannot_execute_twice() {
+    let (env, admin, client) = setup_test_contract();
+
+    let (signer_1, signer_2, signer_3) = configure_multisig(&env, &client, &admin, 2);
+
+    let from = Address::generate(&env);
+    let to = Address::generate(&env);
+    client.set_balance(&admin, &from, &1_000);
+
+    let asset: Option<Address> = None;
+    let tx_id = client
+        .submit_transaction(&from, &to, &300, &symbol_short!("pay"), &asset)
+        .expect("expected pending tx id");
+
+    client.approve(&tx_id, &signer_1);
+    client.approve(&tx_id, &signer_2);
+
+    // Already executed at threshold; later approvals are rejected.
+    client.approve(&tx_id, &signer_3);
+}
+
+#[test]
+#[should_panic]
+fn test_approving_nonexistent_tx_fails() {
+    let (env, admin, client) = setup_test_contract();
+
+    let (signer_1, _signer_2, _signer_3) = configure_multisig(&env, &client, &admin, 2);
+
+    client.approve(&999, &signer_1);
+}
+
+#[test]
+fn test_emits_approval_and_execution_events() {
+    let (env, admin, client) = setup_test_contract();
+
+    let (signer_1, signer_2, _signer_3) = configure_multisig(&env, &client, &admin, 2);
+
+    let from = Address::generate(&env);
+    let to = Address::generate(&env);
+    client.set_balance(&admin, &from, &1_000);
+
+    let asset: Option<Address> = None;
+    let tx_id = client
+        .submit_transaction(&from, &to, &300, &symbol_short!("pay"), &asset)
+        .expect("expected pending tx id");
+
+    client.approve(&tx_id, &signer_1);
+    client.approve(&tx_id, &signer_2);
+
+    let events = env.events().all();
+    let approve_sym = symbol_short!("approve");
+    let record_sym = symbol_short!("record");
+    let executed_sym = symbol_short!("executed");
+
+    let approval_events = events
+        .iter()
+        .filter(|event| {
+            let has_approve = event
+                .1
+                .iter()
+                .any(|topic| {
+                    Symbol::try_from_val(&env, &topic)
+                        .map(|sym| sym == approve_sym)
+                        .unwrap_or(false)
+                });
+            let has_record = event
+                .1
+                .iter()
+                .any(|topic| {
+                    Symbol::try_from_val(&env, &topic)
+                        .map(|sym| sym == record_sym)
+                        .unwrap_or(false)
+                });
+            has_approve && has_record
+        })
+        .count();
+
+    let execution_events = events
+        .iter()
+        .filter(|event| {
+            event
+                .1
+                .iter()
+                .any(|topic| {
+                    Symbol::try_from_val(&env, &topic)
+                        .map(|sym| sym == executed_sym)
+                        .unwrap_or(false)
+                })
+        })
+        .count();
+
+    assert!(approval_events >= 1);
+    assert_eq!(execution_events, 1);
+}
+
+#[test]
+#[should_panic]
+fn test_set_high_value_threshold_admin_only() {
+    let (env, admin, client) = setup_test_contract();
+
+    let unauthorized = Address::generate(&env);
+    client.set_high_value_threshold(&admin, &100);
+    client.set_high_value_threshold(&unauthorized, &200);
+}
+
+fn submit_pending(
+    env: &Env,
+    client: &TransactionsContractClient<'static>,
+    admin: &Address,
+) -> u64 {
+    let from = Address::generate(env);
+    let to = Address::generate(env);
+    client.set_balance(admin, &from, &1_000);
+
+    let asset: Option<Address> = None;
+    client
+        .submit_transaction(&from, &to, &300, &symbol_short!("pay"), &asset)
+        .expect("expected pending tx id")
+}
+
+#[test]
+#[should_panic]
+fn test_escalation_before_sla_rejected() {
+    let (env, admin, client) = setup_test_contract();
+    configure_multisig(&env, &client, &admin, 2);
+    client.set_approval_sla(&admin, &3_600, &false);
+
+    let tx_id = submit_pending(&env, &client, &admin);
+    env.ledger().with_mut(|li| li.timestamp += 3_600);
+
+    client.escalate_overdue(&tx_id);
+}
+
+#[test]
+fn test_overdue_escalation_records_breaches() {
+    let (env, admin, client) = setup_test_contract();
+    let (signer_1, signer_2, signer_3) = configure_multisig(&env, &client, &admin, 2);
+    client.set_approval_sla(&admin, &3_600, &false);
+
+    let tx_id = submit_pending(&env, &client, &admin);
+    client.approve(&tx_id, &signer_1);
+
+    env.ledger().with_mut(|li| li.timestamp += 3_601);
+    let routed = client.escalate_overdue(&tx_id);
+
+    assert_eq!(routed.len(), 0);
+    assert!(client.is_escalated(&tx_id));
+    assert_eq!(client.get_approver_stats(&admin, &signer_1).breaches, 0);
+    assert_eq!(client.get_approver_stats(&admin, &signer_2).breaches, 1);
+    assert_eq!(client.get_approver_stats(&admin, &signer_3).breaches, 1);
+
+    // Escalation fires once per transaction
+    assert!(client.try_escalate_overdue(&tx_id).is_err());
+}
+
+#[test]
+fn test_escalation_routes_to_manager() {
+    let (env, admin, client) = setup_test_contract();
+    let (signer_1, signer_2, _signer_3) = configure_multisig(&env, &client, &admin, 2);
+    client.set_approval_sla(&admin, &3_600, &true);
+
+    let manager = Address::generate(&env);
+    setup_org(&env, &client, &admin).set_manager(&signer_2, &manager);
+
+    let tx_id = submit_pending(&env, &client, &admin);
+    client.approve(&tx_id, &signer_1);
+
+    env.ledger().with_mut(|li| li.timestamp += 7_200);
+    let routed = client.escalate_overdue(&tx_id);
+    assert_eq!(routed, Vec::from_array(&env, [manager.clone()]));
+
+    // The manager approves on signer 2's behalf and the transaction executes
+    client.approve(&tx_id, &manager);
+    assert!(client.has_approved(&tx_id, &signer_2));
+    assert!(client.get_pending_tx(&tx_id).unwrap().executed);
+
+    let stats = client.get_approver_stats(&admin, &manager);
+    assert_eq!(stats.approvals, 1);
+    assert_eq!(stats.max_response_secs, 7_200);
+}
+
+#[test]
+fn test_escalation_routes_every_report_of_a_manager() {
+    let (env, admin, client) = setup_test_contract();
+    let (signer_1, signer_2, signer_3) = configure_multisig(&env, &client, &admin, 3);
+    client.set_approval_sla(&admin, &3_600, &true);
+
+    let manager = Address::generate(&env);
+    let org = setup_org(&env, &client, &admin);
+    org.set_manager(&signer_2, &manager);
+    org.set_manager(&signer_3, &manager);
+
+    let tx_id = submit_pending(&env, &client, &admin);
+    client.approve(&tx_id, &signer_1);
+
+    env.ledger().with_mut(|li| li.timestamp += 7_200);
+    let routed = client.escalate_overdue(&tx_id);
+    assert_eq!(routed, Vec::from_array(&env, [manager.clone()]));
+    assert_eq!(
+        client.get_delegated_signers(&tx_id, &manager),
+        Vec::from_array(&env, [signer_2.clone(), signer_3.clone()])
+    );
+
+    // Each approval from the manager covers the next signer still outstanding
+    client.approve(&tx_id, &manager);
+    assert!(client.has_approved(&tx_id, &signer_2));
+    assert!(!client.get_pending_tx(&tx_id).unwrap().executed);
+
+    client.approve(&tx_id, &manager);
+    assert!(client.has_approved(&tx_id, &signer_3));
+    assert!(client.get_pending_tx(&tx_id).unwrap().executed);
+}
+
+#[test]
+fn test_escalation_without_org_hierarchy_routes_nowhere() {
+    let (env, admin, client) = setup_test_contract();
+    configure_multisig(&env, &client, &admin, 2);
+    client.set_approval_sla(&admin, &3_600, &true);
+
+    let tx_id = submit_pending(&env, &client, &admin);
+    env.ledger().with_mut(|li| li.timestamp += 7_200);
+
+    assert_eq!(client.escalate_overdue(&tx_id).len(), 0);
+    assert!(client.is_escalated(&tx_id));
+}
+
+#[test]
+fn test_approver_stats_track_response_times() {
+    let (env, admin, client) = setup_test_contract();
+    let (signer_1, _signer_2, _signer_3) = configure_multisig(&env, &client, &admin, 3);
+    client.set_approval_sla(&admin, &3_600, &false);
+
+    let auditor = Address::generate(&env);
+    client.set_auditor(&admin, &auditor, &true);
+
+    let tx_1 = submit_pending(&env, &client, &admin);
+    env.ledger().with_mut(|li| li.timestamp += 600);
+    client.approve(&tx_1, &signer_1);
+
+    let tx_2 = submit_pending(&env, &client, &admin);
+    env.ledger().with_mut(|li| li.timestamp += 5_000);
+    client.approve(&tx_2, &signer_1);
+
+    let stats = client.get_approver_stats(&auditor, &signer_1);
+    assert_eq!(stats.approvals, 2);
+    assert_eq!(stats.total_response_secs, 5_600);
+    assert_eq!(stats.max_response_secs, 5_000);
+    assert_eq!(stats.breaches, 1);
+}
+
+#[test]
+#[should_panic]
+fn test_approver_stats_restricted_to_auditors() {
+    let (env, admin, client) = setup_test_contract();
+    let (signer_1, _signer_2, _signer_3) = configure_multisig(&env, &client, &admin, 2);
+
+    let outsider = Address::generate(&env);
+    client.get_approver_stats(&outsider, &signer_1);
+}
+
+#[test]
+fn test_pending_digest_lists_items_awaiting_approver() {
+    let (env, admin, client) = setup_test_contract();
+    let (signer_1, signer_2, _signer_3) = configure_multisig(&env, &client, &admin, 3);
+
+    let tx_1 = submit_pending(&env, &client, &admin);
+    env.ledger().with_mut(|li| li.timestamp += 600);
+    let tx_2 = submit_pending(&env, &client, &admin);
+    env.ledger().with_mut(|li| li.timestamp += 60);
+    client.approve(&tx_1, &signer_1);
+
+    let page = client.get_pending_for_approver(&signer_1, &0);
+    assert_eq!(page.next_cursor, None);
+    assert_eq!(page.items.len(), 1);
+    let digest = page.items.get(0).unwrap();
+    assert_eq!(digest.id, tx_2);
+    assert_eq!(digest.amount, 300);
+    assert_eq!(digest.token, None);
+    assert_eq!(digest.category, symbol_short!("pay"));
+    assert_eq!(digest.age_secs, 60);
+
+    let page = client.get_pending_for_approver(&signer_2, &0);
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.items.get(0).unwrap().age_secs, 660);
+
+    // Outsiders have nothing to approve
+    let outsider = Address::generate(&env);
+    assert_eq!(client.get_pending_for_approver(&outsider, &0).items.len(), 0);
+}
+
+#[test]
+fn test_pending_digest_skips_conflicts_and_includes_routed_items() {
+    let (env, admin, client) = setup_test_contract();
+    let (signer_1, signer_2, _signer_3) = configure_multisig(&env, &client, &admin, 2);
+    client.set_approval_sla(&admin, &3_600, &true);
+
+    let manager = Address::generate(&env);
+    setup_org(&env, &client, &admin).set_manager(&signer_2, &manager);
+
+    let tx_id = submit_pending(&env, &client, &admin);
+    let submitter = client.get_pending_tx(&tx_id).unwrap().from;
+    client.declare_conflicts(&signer_1, &signer_1, &Vec::from_array(&env, [submitter]));
+    assert_eq!(client.get_pending_for_approver(&signer_1, &0).items.len(), 0);
+    assert_eq!(client.get_pending_for_approver(&manager, &0).items.len(), 0);
+
+    env.ledger().with_mut(|li| li.timestamp += 7_200);
+    client.escalate_overdue(&tx_id);
+    let page = client.get_pending_for_approver(&manager, &0);
+    assert_eq!(page.items.get(0).unwrap().id, tx_id);
+}
+
+#[test]
+fn test_pending_digest_pages_with_cursor() {
+    let (env, admin, client) = setup_test_contract();
+    let (signer_1, _signer_2, _signer_3) = configure_multisig(&env, &client, &admin, 2);
+
+    for _ in 0..25 {
+        submit_pending(&env, &client, &admin);
+    }
+
+    let first = client.get_pending_for_approver(&signer_1, &0);
+    assert_eq!(first.items.len(), 20);
+    assert_eq!(first.next_cursor, Some(20));
+
+    let second = client.get_pending_for_approver(&signer_1, &20);
+    assert_eq!(second.items.len(), 5);
+    assert_eq!(second.items.get(0).unwrap().id, 21);
+    assert_eq!(second.next_cursor, None);
+}
+
+#[test]
+fn test_reject_blocks_execution() {
+    let (env, admin, client) = setup_test_contract();
+    let (signer_1, signer_2, _signer_3) = configure_multisig(&env, &client, &admin, 2);
+
+    let tx_id = submit_pending(&env, &client, &admin);
+    let memo_hash = BytesN::from_array(&env, &[7; 32]);
+    client.reject(&tx_id, &signer_1, &RejectionReason::Duplicate, &Some(memo_hash.clone()));
+
+    let rejection = client.get_rejection(&tx_id).unwrap();
+    assert_eq!(rejection.rejected_by, signer_1);
+    assert_eq!(rejection.reason, RejectionReason::Duplicate);
+    assert_eq!(rejection.memo_hash, Some(memo_hash));
+    assert_eq!(client.get_rejection_count(&RejectionReason::Duplicate), 1);
+    assert!(client.try_approve(&tx_id, &signer_2).is_err());
+    assert!(!client.get_pending_tx(&tx_id).unwrap().executed);
+    assert_eq!(client.get_pending_for_approver(&signer_2, &0).items.len(), 0);
+}
+
+#[test]
+fn test_comment_thread_records_authors_in_order() {
+    let (env, admin, client) = setup_test_contract();
+    let (signer_1, _signer_2, _signer_3) = configure_multisig(&env, &client, &admin, 2);
+
+    let tx_id = submit_pending(&env, &client, &admin);
+    let submitter = client.get_pending_tx(&tx_id).unwrap().from;
+    let question = BytesN::from_array(&env, &[1; 32]);
+    let answer = BytesN::from_array(&env, &[2; 32]);
+    assert_eq!(client.post_comment(&tx_id, &signer_1, &question), 0);
+    env.ledger().with_mut(|li| li.timestamp += 60);
+    assert_eq!(client.post_comment(&tx_id, &submitter, &answer), 1);
+
+    // Threads stay open after the decision so disputes can continue
+    client.reject(&tx_id, &signer_1, &RejectionReason::MissingDocumentation, &None);
+    client.post_comment(&tx_id, &submitter, &question);
+
+    let comments = client.get_comments(&tx_id);
+    assert_eq!(comments.len(), 3);
+    assert_eq!(comments.get(0).unwrap().author, signer_1);
+    assert_eq!(comments.get(1).unwrap().body_hash, answer);
+    assert_eq!(
+        comments.get(1).unwrap().posted_at,
+        comments.get(0).unwrap().posted_at + 60
+    );
+}
+
+#[test]
+fn test_comment_requires_participant() {
+    let (env, admin, client) = setup_test_contract();
+    let (signer_1, _signer_2, _signer_3) = configure_multisig(&env, &client, &admin, 2);
+
+    let tx_id = submit_pending(&env, &client, &admin);
+    let body_hash = BytesN::from_array(&env, &[1; 32]);
+    assert!(client
+        .try_post_comment(&tx_id, &Address::generate(&env), &body_hash)
+        .is_err());
+    assert!(client.try_post_comment(&99, &signer_1, &body_hash).is_err());
+}
+
+#[test]
+fn test_approve_batch_reports_per_item_outcomes() {
+    let (env, admin, client) = setup_test_contract();
+    let (signer_1, signer_2, _signer_3) = configure_multisig(&env, &client, &admin, 2);
+
+    let tx_1 = submit_pending(&env, &client, &admin);
+    let tx_2 = submit_pending(&env, &client, &admin);
+    let tx_3 = submit_pending(&env, &client, &admin);
+    let tx_4 = submit_pending(&env, &client, &admin);
+    client.approve(&tx_2, &signer_1);
+    client.reject(&tx_3, &signer_2, &RejectionReason::SuspectedFraud, &None);
+    let submitter = client.get_pending_tx(&tx_4).unwrap().from;
+    client.set_balance(&admin, &submitter, &0);
+
+    let ids = Vec::from_array(&env, [tx_1, tx_2, tx_3, tx_4, 99]);
+    let outcomes = client.approve_batch(&ids, &signer_1);
+    assert_eq!(
+        outcomes,
+        Vec::from_array(
+            &env,
+            [
+                DecisionOutcome::Approved,
+                DecisionOutcome::AlreadyDecided,
+                DecisionOutcome::AlreadyDecided,
+                DecisionOutcome::Approved,
+                DecisionOutcome::NotFound,
+            ]
+        )
+    );
+
+    // Signer 2's approval would execute tx 4, which the submitter can no longer cover
+    let outcomes = client.approve_batch(&Vec::from_array(&env, [tx_1, tx_4]), &signer_2);
+    assert_eq!(
+        outcomes,
+        Vec::from_array(
+            &env,
+            [DecisionOutcome::Approved, DecisionOutcome::InsufficientBalance]
+        )
+    );
+    assert!(client.get_pending_tx(&tx_1).unwrap().executed);
+    assert!(!client.has_approved(&tx_4, &signer_2));
+
+    let outsider = Address::generate(&env);
+    let outcomes = client.approve_batch(&Vec::from_array(&env, [tx_4]), &outsider);
+    assert_eq!(outcomes, Vec::from_array(&env, [DecisionOutcome::NotAuthorized]));
+}
+
+#[test]
+fn test_reject_batch_reports_per_item_outcomes() {
+    let (env, admin, client) = setup_test_contract();
+    let (signer_1, signer_2, _signer_3) = configure_multisig(&env, &client, &admin, 2);
+
+    let tx_1 = submit_pending(&env, &client, &admin);
+    let tx_2 = submit_pending(&env, &client, &admin);
+    client.approve(&tx_2, &signer_1);
+    client.approve(&tx_2, &signer_2);
+
+    let ids = Vec::from_array(&env, [tx_1, tx_2]);
+    let outcomes = client.reject_batch(&ids, &signer_1, &RejectionReason::OverBudget, &None);
+    assert_eq!(
+        outcomes,
+        Vec::from_array(&env, [DecisionOutcome::Rejected, DecisionOutcome::AlreadyDecided])
+    );
+    let rejection = client.get_rejection(&tx_1).unwrap();
+    assert_eq!(rejection.reason, RejectionReason::OverBudget);
+    assert_eq!(rejection.memo_hash, None);
+    assert_eq!(client.get_rejection(&tx_2), None);
+
+    // Only decided items count toward the rejection pattern
+    assert_eq!(client.get_rejection_count(&RejectionReason::OverBudget), 1);
+    assert_eq!(client.get_rejection_count(&RejectionReason::Duplicate), 0);
+}
+
+#[test]
+fn test_decision_batch_too_large_rejected() {
+    let (env, admin, client) = setup_test_contract();
+    let (signer_1, _signer_2, _signer_3) = configure_multisig(&env, &client, &admin, 2);
+
+    let mut ids: Vec<u64> = Vec::new(&env);
+    for id in 0..51 {
+        ids.push_back(id);
+    }
+    assert!(client.try_approve_batch(&ids, &signer_1).is_err());
+}
+
+#[test]
+fn test_quorum_relaxes_for_stale_transactions() {
+    let (env, admin, client) = setup_test_contract();
+    let (signer_1, signer_2, _signer_3) = configure_multisig(&env, &client, &admin, 2);
+    let decay = QuorumDecay {
+        after_secs: 14 * 86_400,
+        action: DecayAction::Relax(1),
+    };
+    client.set_quorum_decay(&admin, &Some(decay.clone()));
+    assert_eq!(client.get_quorum_decay(), Some(decay));
+
+    let approved_early = submit_pending(&env, &client, &admin);
+    let approved_late = submit_pending(&env, &client, &admin);
+    client.approve(&approved_early, &signer_1);
+    assert!(!client.get_pending_tx(&approved_early).unwrap().executed);
+    assert_eq!(
+        client.try_apply_quorum_decay(&approved_early),
+        Err(Ok(MultiSigError::NotOverdue.into()))
+    );
+
+    // Two weeks on, one approval suffices
+    env.ledger().with_mut(|li| li.timestamp += 14 * 86_400);
+    assert_eq!(client.apply_quorum_decay(&approved_early), DecisionOutcome::Approved);
+    assert!(client.get_pending_tx(&approved_early).unwrap().executed);
+    client.approve(&approved_late, &signer_2);
+    assert!(client.get_pending_tx(&approved_late).unwrap().executed);
+}
+
+#[test]
+fn test_stale_transactions_auto_reject() {
+    let (env, admin, client) = setup_test_contract();
+    let (signer_1, signer_2, _signer_3) = configure_multisig(&env, &client, &admin, 2);
+    let decay = QuorumDecay {
+        after_secs: 86_400,
+        action: DecayAction::AutoReject,
+    };
+    client.set_quorum_decay(&admin, &Some(decay));
+
+    let tx_id = submit_pending(&env, &client, &admin);
+    client.approve(&tx_id, &signer_1);
+    env.ledger().with_mut(|li| li.timestamp += 86_400);
+
+    assert_eq!(
+        client.try_approve(&tx_id, &signer_2),
+        Err(Ok(MultiSigError::Stale.into()))
+    );
+    assert_eq!(client.get_pending_for_approver(&signer_2, &0).items.len(), 0);
+
+    assert_eq!(client.apply_quorum_decay(&tx_id), DecisionOutcome::Rejected);
+    let rejection = client.get_rejection(&tx_id).unwrap();
+    assert_eq!(rejection.reason, RejectionReason::Stale);
+    assert_eq!(rejection.rejected_by, client.address);
+    assert!(!client.get_pending_tx(&tx_id).unwrap().executed);
+}
+
+#[test]
+fn test_blackout_allows_only_critical_categories() {
+    let (env, admin, client) = setup_test_contract();
+    let (signer_1, signer_2, _signer_3) = configure_multisig(&env, &client, &admin, 2);
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let from = Address::generate(&env);
+    let to = Address::generate(&env);
+    client.set_balance(&admin, &from, &1_000);
+    client.set_critical_category(&admin, &symbol_short!("payroll"), &true);
+
+    // A transaction submitted before the blackout cannot execute during it
+    let tx_id = submit_pending(&env, &client, &admin);
+    let mut blackouts: Vec<Blackout> = Vec::new(&env);
+    blackouts.push_back(Blackout {
+        start: 2_000,
+        end: 3_000,
+        reason: symbol_short!("yearend"),
+    });
+    client.set_blackouts(&admin, &blackouts);
+    assert_eq!(client.get_blackouts(), blackouts);
+    assert_eq!(client.get_active_blackout(), None);
+
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+    assert_eq!(client.get_active_blackout(), blackouts.get(0));
+    let result =
+        client.try_submit_transaction(&from, &to, &50, &symbol_short!("travel"), &None);
+    assert_eq!(result, Err(Ok(MultiSigError::BlackoutActive.into())));
+    client.submit_transaction(&from, &to, &50, &symbol_short!("payroll"), &None);
+    assert_eq!(client.get_balance(&to), 50);
+
+    client.approve(&tx_id, &signer_1);
+    let result = client.try_approve(&tx_id, &signer_2);
+    assert_eq!(result, Err(Ok(MultiSigError::BlackoutActive.into())));
+    let outcomes = client.approve_batch(&Vec::from_array(&env, [tx_id]), &signer_2);
+    assert_eq!(outcomes.get(0), Some(DecisionOutcome::BlackoutActive));
+
+    env.ledger().with_mut(|li| li.timestamp = 3_000);
+    client.approve(&tx_id, &signer_2);
+    assert!(client.get_pending_tx(&tx_id).unwrap().executed);
+}
+
+#[test]
+fn test_blackout_schedule_validated() {
+    let (env, admin, client) = setup_test_contract();
+    let mut blackouts: Vec<Blackout> = Vec::new(&env);
+    blackouts.push_back(Blackout {
+        start: 5_000,
+        end: 5_000,
+        reason: symbol_short!("audit"),
+    });
+
+    let result = client.try_set_blackouts(&admin, &blackouts);
+    assert_eq!(result, Err(Ok(MultiSigError::InvalidBlackout.into())));
+    assert!(client
+        .try_set_blackouts(&Address::generate(&env), &Vec::new(&env))
+        .is_err());
+}
+
+#[test]
+fn test_signer_weight_only_for_signers() {
+    let (env, admin, client) = setup_test_contract();
+    let (signer_1, _, _) = configure_multisig(&env, &client, &admin, 2);
+
+    client.set_signer_weight(&admin, &signer_1, &3);
+    assert_eq!(client.get_signer_weight(&signer_1), 3);
+
+    let outsider = Address::generate(&env);
+    let result = client.try_set_signer_weight(&admin, &outsider, &3);
+    assert_eq!(result, Err(Ok(MultiSigError::UnauthorizedSigner.into())));
+}
+
+#[test]
+fn test_weight_threshold_stays_reachable() {
+    let (env, admin, client) = setup_test_contract();
+    let (signer_1, signer_2, _) = configure_multisig(&env, &client, &admin, 2);
+    client.set_signer_weight(&admin, &signer_1, &3);
+    client.set_weight_threshold(&admin, &5);
+
+    // Lowering a weight below what the threshold needs is rejected
+    let result = client.try_set_signer_weight(&admin, &signer_1, &1);
+    assert_eq!(result, Err(Ok(MultiSigError::InvalidThreshold.into())));
+
+    // So is replacing the signers with a set that cannot reach it
+    let signers = Vec::from_array(&env, [signer_2, Address::generate(&env)]);
+    let result = client.try_set_signers(&admin, &signers, &1);
+    assert_eq!(result, Err(Ok(MultiSigError::InvalidThreshold.into())));
+    assert_eq!(client.get_signers().len(), 3);
+}
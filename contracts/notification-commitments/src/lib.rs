@@ -0,0 +1,320 @@
+//! # Notification Commitments Contract
+//!
+//! Lets customers check that off-chain webhook notifications match on-chain
+//! reality. StellarSpend contracts stamp every event with a per-org sequence
+//! number; after delivering the webhooks for a range of those events, the
+//! notification service commits a hash of the payload batch here, keyed by the
+//! emitting contract, organization and sequence range. A customer can look up
+//! the commitment covering any event and compare it against what they
+//! received.
+//!
+//! ## Features
+//!
+//! - **Gap-Free Ranges**: Each stream's commitments must cover consecutive sequences
+//! - **Lookup by Sequence**: Find the commitment covering any event
+//! - **Notifier Registry**: Only admin-registered notification services can commit
+//! - **Notification Preferences**: Users sign their own preference flags, which
+//!   the notification service reads before delivering
+#![no_std]
+
+mod types;
+
+use common::limits::{self, Limit};
+use common::versioning;
+use soroban_sdk::{contract, contractimpl, panic_with_error, Address, BytesN, Env, Vec};
+
+pub use crate::types::{Commitment, CommitmentEvents, DataKey, NotifyFlag};
+
+/// Error codes for the notification commitments contract.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum CommitmentError {
+    /// Contract not initialized
+    NotInitialized = 1,
+    /// Caller is not authorized
+    Unauthorized = 2,
+    /// Contract already initialized
+    AlreadyInitialized = 3,
+    /// Caller is not a registered notifier
+    NotNotifier = 4,
+    /// Range ends before it starts
+    InvalidRange = 5,
+    /// Range does not start right after the last committed sequence
+    SequenceGap = 6,
+    /// Stream has reached the maximum number of commitments
+    TooManyCommitments = 7,
+    /// Storage was written by a different layout version
+    StorageVersionMismatch = 8,
+    /// Migration source version does not match stored version
+    InvalidMigration = 9,
+    /// Preferences contain undefined flags
+    InvalidPreferences = 10,
+    /// Query exceeds the maximum batch size
+    BatchTooLarge = 11,
+}
+
+impl From<CommitmentError> for soroban_sdk::Error {
+    fn from(e: CommitmentError) -> Self {
+        soroban_sdk::Error::from_contract_error(e as u32)
+    }
+}
+
+/// Storage layout version this code expects.
+pub const STORAGE_VERSION: u32 = 1;
+
+#[contract]
+pub struct NotificationCommitmentsContract;
+
+#[contractimpl]
+impl NotificationCommitmentsContract {
+    /// Initializes the contract with an admin address.
+    pub fn initialize(env: Env, admin: Address) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic_with_error!(&env, CommitmentError::AlreadyInitialized);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        versioning::set_version(&env, STORAGE_VERSION);
+    }
+
+    /// Migrates storage written by an older version of this contract to the
+    /// current layout (admin only). `from_version` must match the stored
+    /// version.
+    pub fn migrate(env: Env, caller: Address, from_version: u32) {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        versioning::migrate(&env, from_version, STORAGE_VERSION, |_, _| {})
+            .unwrap_or_else(|_| panic_with_error!(&env, CommitmentError::InvalidMigration));
+    }
+
+    /// Returns the storage layout version currently stored.
+    pub fn get_storage_version(env: Env) -> u32 {
+        versioning::stored_version(&env)
+    }
+
+    /// Registers or removes a notification service key (admin only).
+    pub fn set_notifier(env: Env, caller: Address, notifier: Address, enabled: bool) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        if enabled {
+            env.storage()
+                .persistent()
+                .set(&DataKey::Notifier(notifier.clone()), &true);
+        } else {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::Notifier(notifier.clone()));
+        }
+
+        CommitmentEvents::notifier_set(&env, &notifier, enabled);
+    }
+
+    /// Commits the hash of the webhook payloads delivered for events
+    /// `from_sequence..=to_sequence` of `org` emitted by `source` (registered
+    /// notifiers only).
+    ///
+    /// Each stream's commitments must be gap-free: the first commitment may
+    /// start anywhere, and every later one must start right after the last
+    /// committed sequence.
+    ///
+    /// # Returns
+    /// * The commitment's position in the stream
+    pub fn commit_batch(
+        env: Env,
+        notifier: Address,
+        org: u32,
+        source: Address,
+        from_sequence: u64,
+        to_sequence: u64,
+        payload_hash: BytesN<32>,
+    ) -> u32 {
+        Self::require_storage_version(&env);
+        notifier.require_auth();
+        if !Self::is_notifier(env.clone(), notifier.clone()) {
+            panic_with_error!(&env, CommitmentError::NotNotifier);
+        }
+        if to_sequence < from_sequence {
+            panic_with_error!(&env, CommitmentError::InvalidRange);
+        }
+
+        let count = Self::get_commitment_count(env.clone(), org, source.clone());
+        if count > 0 {
+            let last = Self::load(&env, org, &source, count - 1);
+            if last.to_sequence.checked_add(1) != Some(from_sequence) {
+                panic_with_error!(&env, CommitmentError::SequenceGap);
+            }
+        }
+        let next_count = count
+            .checked_add(1)
+            .unwrap_or_else(|| panic_with_error!(&env, CommitmentError::TooManyCommitments));
+
+        let commitment = Commitment {
+            from_sequence,
+            to_sequence,
+            payload_hash,
+            notifier,
+            committed_at: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(
+            &DataKey::Commitment(org, source.clone(), count),
+            &commitment,
+        );
+        env.storage()
+            .persistent()
+            .set(&DataKey::CommitmentCount(org, source.clone()), &next_count);
+
+        CommitmentEvents::committed(&env, org, &source, &commitment);
+
+        count
+    }
+
+    /// Returns the commitment covering event `sequence` of `org` emitted by
+    /// `source`, if one has been posted.
+    pub fn find_commitment(
+        env: Env,
+        org: u32,
+        source: Address,
+        sequence: u64,
+    ) -> Option<Commitment> {
+        // Ranges are contiguous and ascending, so binary search by range start
+        let mut low: u32 = 0;
+        let mut high = Self::get_commitment_count(env.clone(), org, source.clone());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let commitment = Self::load(&env, org, &source, mid);
+            if sequence < commitment.from_sequence {
+                high = mid;
+            } else if sequence > commitment.to_sequence {
+                low = mid + 1;
+            } else {
+                return Some(commitment);
+            }
+        }
+        None
+    }
+
+    /// Returns whether a commitment exists for exactly this range with this
+    /// payload hash.
+    pub fn verify_batch(
+        env: Env,
+        org: u32,
+        source: Address,
+        from_sequence: u64,
+        to_sequence: u64,
+        payload_hash: BytesN<32>,
+    ) -> bool {
+        match Self::find_commitment(env, org, source, from_sequence) {
+            Some(commitment) => {
+                commitment.from_sequence == from_sequence
+                    && commitment.to_sequence == to_sequence
+                    && commitment.payload_hash == payload_hash
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the commitment at `index` in a stream.
+    pub fn get_commitment(env: Env, org: u32, source: Address, index: u32) -> Option<Commitment> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Commitment(org, source, index))
+    }
+
+    /// Returns the number of commitments posted for a stream.
+    pub fn get_commitment_count(env: Env, org: u32, source: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::CommitmentCount(org, source))
+            .unwrap_or(0)
+    }
+
+    /// Returns the last event sequence committed for a stream, if any.
+    pub fn get_last_committed_sequence(env: Env, org: u32, source: Address) -> Option<u64> {
+        match Self::get_commitment_count(env.clone(), org, source.clone()) {
+            0 => None,
+            count => Some(Self::load(&env, org, &source, count - 1).to_sequence),
+        }
+    }
+
+    /// Sets the caller's notification preferences as a bitmap of
+    /// [`NotifyFlag`] values. Only the user can change their own preferences.
+    pub fn set_preferences(env: Env, user: Address, flags: u32) {
+        Self::require_storage_version(&env);
+        user.require_auth();
+        if flags & !NotifyFlag::ALL != 0 {
+            panic_with_error!(&env, CommitmentError::InvalidPreferences);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Preferences(user.clone()), &flags);
+        CommitmentEvents::preferences_set(&env, &user, flags);
+    }
+
+    /// Returns a user's notification preferences, or
+    /// [`NotifyFlag::DEFAULT`] if they have never set any.
+    pub fn get_preferences(env: Env, user: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Preferences(user))
+            .unwrap_or(NotifyFlag::DEFAULT)
+    }
+
+    /// Returns the notification preferences of many users in one call, in the
+    /// same order.
+    pub fn get_preferences_batch(env: Env, users: Vec<Address>) -> Vec<u32> {
+        if users.len() > limits::get(&env, Limit::BatchSize) {
+            panic_with_error!(&env, CommitmentError::BatchTooLarge);
+        }
+
+        let mut flags = Vec::new(&env);
+        for user in users.iter() {
+            flags.push_back(Self::get_preferences(env.clone(), user));
+        }
+        flags
+    }
+
+    /// Returns whether `notifier` is registered.
+    pub fn is_notifier(env: Env, notifier: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Notifier(notifier))
+            .unwrap_or(false)
+    }
+
+    /// Returns the admin address.
+    pub fn get_admin(env: Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(&env, CommitmentError::NotInitialized))
+    }
+
+    // Internal helper to load a commitment known to exist
+    fn load(env: &Env, org: u32, source: &Address, index: u32) -> Commitment {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Commitment(org, source.clone(), index))
+            .unwrap()
+    }
+
+    // Internal helper refusing to run against storage written by a different
+    // layout version
+    fn require_storage_version(env: &Env) {
+        versioning::require_version(env, STORAGE_VERSION)
+            .unwrap_or_else(|_| panic_with_error!(env, CommitmentError::StorageVersionMismatch));
+    }
+
+    // Internal helper to verify admin
+    fn require_admin(env: &Env, caller: &Address) {
+        if *caller != Self::get_admin(env.clone()) {
+            panic_with_error!(env, CommitmentError::Unauthorized);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,189 @@
+//! Integration tests for the notification commitments contract.
+
+#![cfg(test)]
+
+use crate::{NotificationCommitmentsContract, NotificationCommitmentsContractClient, NotifyFlag};
+use soroban_sdk::{
+    testutils::{Address as _, AuthorizedFunction},
+    vec, Address, BytesN, Env, IntoVal, Symbol,
+};
+
+/// Creates a test environment with the contract deployed and initialized and
+/// a registered notifier.
+fn setup_test_env() -> (
+    Env,
+    Address,
+    Address,
+    NotificationCommitmentsContractClient<'static>,
+) {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(NotificationCommitmentsContract, ());
+    let client = NotificationCommitmentsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let notifier = Address::generate(&env);
+    client.set_notifier(&admin, &notifier, &true);
+
+    (env, admin, notifier, client)
+}
+
+fn hash(env: &Env, byte: u8) -> BytesN<32> {
+    BytesN::from_array(env, &[byte; 32])
+}
+
+#[test]
+fn test_commit_and_find_by_sequence() {
+    let (env, _admin, notifier, client) = setup_test_env();
+    let source = Address::generate(&env);
+
+    assert_eq!(
+        client.commit_batch(&notifier, &3, &source, &1, &10, &hash(&env, 1)),
+        0
+    );
+    assert_eq!(
+        client.commit_batch(&notifier, &3, &source, &11, &11, &hash(&env, 2)),
+        1
+    );
+    assert_eq!(
+        client.commit_batch(&notifier, &3, &source, &12, &40, &hash(&env, 3)),
+        2
+    );
+
+    assert_eq!(
+        client
+            .find_commitment(&3, &source, &1)
+            .unwrap()
+            .payload_hash,
+        hash(&env, 1)
+    );
+    assert_eq!(
+        client
+            .find_commitment(&3, &source, &11)
+            .unwrap()
+            .payload_hash,
+        hash(&env, 2)
+    );
+    assert_eq!(
+        client
+            .find_commitment(&3, &source, &25)
+            .unwrap()
+            .payload_hash,
+        hash(&env, 3)
+    );
+    assert_eq!(client.find_commitment(&3, &source, &41), None);
+    assert_eq!(client.find_commitment(&4, &source, &1), None);
+    assert_eq!(client.get_last_committed_sequence(&3, &source), Some(40));
+}
+
+#[test]
+fn test_verify_batch() {
+    let (env, _admin, notifier, client) = setup_test_env();
+    let source = Address::generate(&env);
+    client.commit_batch(&notifier, &0, &source, &1, &10, &hash(&env, 1));
+
+    assert!(client.verify_batch(&0, &source, &1, &10, &hash(&env, 1)));
+    assert!(!client.verify_batch(&0, &source, &1, &10, &hash(&env, 2)));
+    assert!(!client.verify_batch(&0, &source, &1, &9, &hash(&env, 1)));
+    assert!(!client.verify_batch(&0, &Address::generate(&env), &1, &10, &hash(&env, 1)));
+}
+
+#[test]
+fn test_streams_are_independent() {
+    let (env, _admin, notifier, client) = setup_test_env();
+    let source = Address::generate(&env);
+    let other = Address::generate(&env);
+
+    client.commit_batch(&notifier, &0, &source, &1, &10, &hash(&env, 1));
+    client.commit_batch(&notifier, &0, &other, &5, &6, &hash(&env, 2));
+    client.commit_batch(&notifier, &1, &source, &1, &2, &hash(&env, 3));
+
+    assert_eq!(client.get_commitment_count(&0, &source), 1);
+    assert_eq!(client.get_commitment_count(&0, &other), 1);
+    assert_eq!(client.get_commitment_count(&1, &source), 1);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #6)")]
+fn test_ranges_must_be_contiguous() {
+    let (env, _admin, notifier, client) = setup_test_env();
+    let source = Address::generate(&env);
+
+    client.commit_batch(&notifier, &0, &source, &1, &10, &hash(&env, 1));
+    client.commit_batch(&notifier, &0, &source, &12, &20, &hash(&env, 2));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn test_range_must_not_be_inverted() {
+    let (env, _admin, notifier, client) = setup_test_env();
+    client.commit_batch(
+        &notifier,
+        &0,
+        &Address::generate(&env),
+        &5,
+        &4,
+        &hash(&env, 1),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")]
+fn test_only_notifiers_commit() {
+    let (env, admin, notifier, client) = setup_test_env();
+    client.set_notifier(&admin, &notifier, &false);
+    client.commit_batch(
+        &notifier,
+        &0,
+        &Address::generate(&env),
+        &1,
+        &1,
+        &hash(&env, 1),
+    );
+}
+
+#[test]
+fn test_user_sets_own_preferences() {
+    let (env, _admin, _notifier, client) = setup_test_env();
+    let user = Address::generate(&env);
+    assert_eq!(client.get_preferences(&user), NotifyFlag::DEFAULT);
+
+    let flags = NotifyFlag::ON_REJECTION | NotifyFlag::WEEKLY_DIGEST;
+    client.set_preferences(&user, &flags);
+
+    // The change is signed by the user themself
+    let (signer, invocation) = env.auths().first().unwrap().clone();
+    assert_eq!(signer, user);
+    assert_eq!(
+        invocation.function,
+        AuthorizedFunction::Contract((
+            client.address.clone(),
+            Symbol::new(&env, "set_preferences"),
+            (user.clone(), flags).into_val(&env),
+        ))
+    );
+    assert_eq!(client.get_preferences(&user), flags);
+}
+
+#[test]
+fn test_preferences_batch_read() {
+    let (env, _admin, _notifier, client) = setup_test_env();
+    let quiet = Address::generate(&env);
+    let fresh = Address::generate(&env);
+    client.set_preferences(&quiet, &0);
+
+    assert_eq!(
+        client.get_preferences_batch(&vec![&env, quiet, fresh]),
+        vec![&env, 0, NotifyFlag::DEFAULT]
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")]
+fn test_preferences_reject_undefined_flags() {
+    let (env, _admin, _notifier, client) = setup_test_env();
+    client.set_preferences(&Address::generate(&env), &(NotifyFlag::ALL + 1));
+}
@@ -0,0 +1,76 @@
+//! Data types and events for the notification commitments contract.
+
+use common::events;
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env};
+
+/// A notification service's commitment to the webhook payloads it delivered
+/// for a contiguous range of an organization's event sequence numbers.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Commitment {
+    /// First event sequence number covered (inclusive)
+    pub from_sequence: u64,
+    /// Last event sequence number covered (inclusive)
+    pub to_sequence: u64,
+    /// Hash of the delivered payload batch
+    pub payload_hash: BytesN<32>,
+    /// Notifier that posted the commitment
+    pub notifier: Address,
+    pub committed_at: u64,
+}
+
+/// Bit flags in a user's notification preferences.
+#[allow(non_snake_case)]
+pub mod NotifyFlag {
+    /// Notify when a request the user is part of is approved
+    pub const ON_APPROVAL: u32 = 1 << 0;
+    /// Notify when the user sends or receives a payment
+    pub const ON_PAYMENT: u32 = 1 << 1;
+    /// Notify when a request the user is part of is rejected
+    pub const ON_REJECTION: u32 = 1 << 2;
+    /// Send a weekly digest
+    pub const WEEKLY_DIGEST: u32 = 1 << 3;
+    /// Every defined flag
+    pub const ALL: u32 = ON_APPROVAL | ON_PAYMENT | ON_REJECTION | WEEKLY_DIGEST;
+    /// Preferences of a user who has never set any
+    pub const DEFAULT: u32 = ON_APPROVAL | ON_PAYMENT | ON_REJECTION;
+}
+
+/// Storage keys for the notification commitments contract.
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    /// Admin address
+    Admin,
+    /// Whether an address may post commitments
+    Notifier(Address),
+    /// Number of commitments posted for an (org, source contract) stream
+    CommitmentCount(u32, Address),
+    /// Commitment by position in an (org, source contract) stream
+    Commitment(u32, Address, u32),
+    /// Notification preference flags set by a user
+    Preferences(Address),
+}
+
+/// Event emitters for notification commitment operations.
+pub struct CommitmentEvents;
+
+impl CommitmentEvents {
+    /// Emitted when a payload batch commitment is posted.
+    pub fn committed(env: &Env, org: u32, source: &Address, commitment: &Commitment) {
+        let topics = (symbol_short!("webhook"), symbol_short!("commit"), org);
+        events::publish_for_org(env, org, topics, (source.clone(), commitment.clone()));
+    }
+
+    /// Emitted when a user changes their notification preferences.
+    pub fn preferences_set(env: &Env, user: &Address, flags: u32) {
+        let topics = (symbol_short!("webhook"), symbol_short!("prefs"));
+        events::publish(env, topics, (user.clone(), flags));
+    }
+
+    /// Emitted when a notifier is registered or removed.
+    pub fn notifier_set(env: &Env, notifier: &Address, enabled: bool) {
+        let topics = (symbol_short!("webhook"), symbol_short!("notifier"));
+        events::publish(env, topics, (notifier.clone(), enabled));
+    }
+}
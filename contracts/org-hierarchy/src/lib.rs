@@ -0,0 +1,367 @@
+//! # Org Hierarchy Contract
+//!
+//! Stores the org chart as manager relationships per employee, for use in
+//! escalation routing, approval matrices ("manager + one Operator") and
+//! reporting rollups.
+//!
+//! ## Features
+//!
+//! - **Cycle Detection**: Assignments that would make someone their own
+//!   (indirect) manager are rejected
+//! - **Bulk Updates**: Apply many assignments in one call with per-item results
+//! - **Chain Queries**: Walk an employee's management chain or a manager's reports
+#![no_std]
+
+mod types;
+
+use common::versioning;
+use soroban_sdk::{contract, contractimpl, panic_with_error, Address, Env, Vec};
+
+pub use crate::types::{
+    AssignmentResult, BatchAssignmentResult, DataKey, ErrorCode, ManagerAssignment, OrgEvents,
+    MAX_BATCH_SIZE, MAX_CHAIN_DEPTH, MAX_ROLLUP_SIZE,
+};
+
+/// Error codes for the org hierarchy contract.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum OrgError {
+    /// Contract not initialized
+    NotInitialized = 1,
+    /// Caller is not authorized
+    Unauthorized = 2,
+    /// Contract already initialized
+    AlreadyInitialized = 3,
+    /// Batch is empty
+    EmptyBatch = 4,
+    /// Batch exceeds maximum size
+    BatchTooLarge = 5,
+    /// Employee cannot manage themselves
+    SelfManaged = 6,
+    /// Assignment would create a cycle
+    CycleDetected = 7,
+    /// Management chain would exceed the maximum depth
+    ChainTooDeep = 8,
+    /// Employee has no manager
+    ManagerNotSet = 9,
+    /// Storage was written by a different layout version
+    StorageVersionMismatch = 10,
+    /// Migration source version does not match stored version
+    InvalidMigration = 11,
+}
+
+impl From<OrgError> for soroban_sdk::Error {
+    fn from(e: OrgError) -> Self {
+        soroban_sdk::Error::from_contract_error(e as u32)
+    }
+}
+
+/// Storage layout version this code expects.
+pub const STORAGE_VERSION: u32 = 1;
+
+#[contract]
+pub struct OrgHierarchyContract;
+
+#[contractimpl]
+impl OrgHierarchyContract {
+    /// Initializes the contract with an admin address.
+    pub fn initialize(env: Env, admin: Address) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic_with_error!(&env, OrgError::AlreadyInitialized);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        versioning::set_version(&env, STORAGE_VERSION);
+    }
+
+    /// Migrates storage written by an older version of this contract to the
+    /// current layout (admin only). `from_version` must match the stored
+    /// version.
+    pub fn migrate(env: Env, caller: Address, from_version: u32) {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        // Version 1 only adds the version stamp; older layouts are otherwise
+        // unchanged, so there is nothing to rewrite
+        versioning::migrate(&env, from_version, STORAGE_VERSION, |_, _| {})
+            .unwrap_or_else(|_| panic_with_error!(&env, OrgError::InvalidMigration));
+    }
+
+    /// Returns the storage layout version currently stored.
+    pub fn get_storage_version(env: Env) -> u32 {
+        versioning::stored_version(&env)
+    }
+
+    /// Sets an employee's direct manager (admin only).
+    pub fn set_manager(env: Env, caller: Address, employee: Address, manager: Address) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        if let Err(code) = Self::assign(&env, &employee, &manager) {
+            panic_with_error!(&env, Self::error_from_code(code));
+        }
+    }
+
+    /// Detaches an employee from their manager (admin only).
+    pub fn remove_manager(env: Env, caller: Address, employee: Address) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        if !Self::unassign(&env, &employee) {
+            panic_with_error!(&env, OrgError::ManagerNotSet);
+        }
+    }
+
+    /// Applies many manager assignments in one call (admin only).
+    ///
+    /// Assignments are applied in order, so later entries see the effect of
+    /// earlier ones. Invalid assignments are reported per item and do not
+    /// affect the rest of the batch.
+    pub fn batch_set_managers(
+        env: Env,
+        caller: Address,
+        assignments: Vec<ManagerAssignment>,
+    ) -> BatchAssignmentResult {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        let request_count = assignments.len();
+        if request_count == 0 {
+            panic_with_error!(&env, OrgError::EmptyBatch);
+        }
+        if request_count > MAX_BATCH_SIZE {
+            panic_with_error!(&env, OrgError::BatchTooLarge);
+        }
+
+        let mut results: Vec<AssignmentResult> = Vec::new(&env);
+        let mut successful: u32 = 0;
+        let mut failed: u32 = 0;
+
+        for assignment in assignments.iter() {
+            let outcome = match &assignment.manager {
+                Some(manager) => Self::assign(&env, &assignment.employee, manager),
+                None => {
+                    Self::unassign(&env, &assignment.employee);
+                    Ok(())
+                }
+            };
+
+            match outcome {
+                Ok(()) => {
+                    successful += 1;
+                    results.push_back(AssignmentResult::Success(assignment.employee.clone()));
+                }
+                Err(code) => {
+                    failed += 1;
+                    results.push_back(AssignmentResult::Failure(assignment.employee.clone(), code));
+                }
+            }
+        }
+
+        OrgEvents::batch_completed(&env, successful, failed);
+
+        BatchAssignmentResult {
+            total_requests: request_count,
+            successful,
+            failed,
+            results,
+        }
+    }
+
+    /// Returns an employee's direct manager.
+    pub fn get_manager(env: Env, employee: Address) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::Manager(employee))
+    }
+
+    /// Returns a manager's direct reports.
+    pub fn get_direct_reports(env: Env, manager: Address) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Reports(manager))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Returns the management chain above an employee, nearest manager first.
+    pub fn get_management_chain(env: Env, employee: Address) -> Vec<Address> {
+        let mut chain: Vec<Address> = Vec::new(&env);
+        let mut current = employee;
+
+        while let Some(manager) = Self::get_manager(env.clone(), current) {
+            chain.push_back(manager.clone());
+            if chain.len() >= MAX_CHAIN_DEPTH {
+                break;
+            }
+            current = manager;
+        }
+
+        chain
+    }
+
+    /// Returns true if `manager` appears anywhere in the employee's management chain.
+    pub fn is_in_chain(env: Env, employee: Address, manager: Address) -> bool {
+        Self::get_management_chain(env, employee).contains(&manager)
+    }
+
+    /// Returns every direct and indirect report of a manager (breadth-first),
+    /// capped at `MAX_ROLLUP_SIZE` entries.
+    pub fn get_all_reports(env: Env, manager: Address) -> Vec<Address> {
+        let mut reports: Vec<Address> = Vec::new(&env);
+        let mut index: u32 = 0;
+        let mut current = manager;
+
+        loop {
+            for report in Self::get_direct_reports(env.clone(), current).iter() {
+                if reports.len() >= MAX_ROLLUP_SIZE {
+                    return reports;
+                }
+                reports.push_back(report);
+            }
+
+            match reports.get(index) {
+                Some(next) => current = next,
+                None => return reports,
+            }
+            index += 1;
+        }
+    }
+
+    /// Returns the admin address.
+    pub fn get_admin(env: Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .expect("Contract not initialized")
+    }
+
+    /// Updates the admin address.
+    pub fn set_admin(env: Env, current_admin: Address, new_admin: Address) {
+        Self::require_storage_version(&env);
+        current_admin.require_auth();
+        Self::require_admin(&env, &current_admin);
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+    }
+
+    // Internal helper to validate and store an assignment
+    fn assign(env: &Env, employee: &Address, manager: &Address) -> Result<(), u32> {
+        if employee == manager {
+            return Err(ErrorCode::SELF_MANAGED);
+        }
+
+        // Walk up from the new manager; reaching the employee means a cycle.
+        // The chain above the manager plus the manager itself must leave room
+        // for the employee within the depth limit.
+        let mut depth: u32 = 1;
+        let mut current = manager.clone();
+        while let Some(next) = env
+            .storage()
+            .persistent()
+            .get::<_, Address>(&DataKey::Manager(current.clone()))
+        {
+            if next == *employee {
+                return Err(ErrorCode::CYCLE_DETECTED);
+            }
+            depth += 1;
+            if depth > MAX_CHAIN_DEPTH {
+                return Err(ErrorCode::CHAIN_TOO_DEEP);
+            }
+            current = next;
+        }
+
+        let previous: Option<Address> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Manager(employee.clone()));
+        if previous.as_ref() == Some(manager) {
+            return Ok(());
+        }
+        if let Some(previous) = &previous {
+            Self::remove_report(env, previous, employee);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Manager(employee.clone()), manager);
+        let mut reports: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Reports(manager.clone()))
+            .unwrap_or(Vec::new(env));
+        reports.push_back(employee.clone());
+        env.storage()
+            .persistent()
+            .set(&DataKey::Reports(manager.clone()), &reports);
+
+        OrgEvents::manager_set(env, employee, &previous, manager);
+        Ok(())
+    }
+
+    // Internal helper to detach an employee; returns false if no manager was set
+    fn unassign(env: &Env, employee: &Address) -> bool {
+        let previous: Option<Address> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Manager(employee.clone()));
+
+        match previous {
+            Some(previous) => {
+                Self::remove_report(env, &previous, employee);
+                env.storage()
+                    .persistent()
+                    .remove(&DataKey::Manager(employee.clone()));
+                OrgEvents::manager_removed(env, employee, &previous);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Internal helper to drop an employee from a manager's report list
+    fn remove_report(env: &Env, manager: &Address, employee: &Address) {
+        let mut reports: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Reports(manager.clone()))
+            .unwrap_or(Vec::new(env));
+        if let Some(index) = reports.first_index_of(employee) {
+            reports.remove(index);
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::Reports(manager.clone()), &reports);
+    }
+
+    // Internal helper mapping per-item error codes to contract errors
+    fn error_from_code(code: u32) -> OrgError {
+        match code {
+            ErrorCode::SELF_MANAGED => OrgError::SelfManaged,
+            ErrorCode::CYCLE_DETECTED => OrgError::CycleDetected,
+            _ => OrgError::ChainTooDeep,
+        }
+    }
+
+    // Internal helper refusing to run against storage written by a different
+    // layout version
+    fn require_storage_version(env: &Env) {
+        versioning::require_version(env, STORAGE_VERSION)
+            .unwrap_or_else(|_| panic_with_error!(env, OrgError::StorageVersionMismatch));
+    }
+
+    // Internal helper to verify admin
+    fn require_admin(env: &Env, caller: &Address) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(env, OrgError::NotInitialized));
+
+        if *caller != admin {
+            panic_with_error!(env, OrgError::Unauthorized);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,236 @@
+//! Integration tests for the org hierarchy contract.
+
+#![cfg(test)]
+
+use crate::{
+    AssignmentResult, ErrorCode, ManagerAssignment, OrgHierarchyContract,
+    OrgHierarchyContractClient, MAX_CHAIN_DEPTH,
+};
+use soroban_sdk::{testutils::Address as _, vec, Address, Env, Vec};
+
+/// Creates a test environment with the contract deployed and initialized.
+fn setup_test_env() -> (Env, Address, OrgHierarchyContractClient<'static>) {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(OrgHierarchyContract, ());
+    let client = OrgHierarchyContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    (env, admin, client)
+}
+
+// ============================================
+// Assignment Tests
+// ============================================
+
+#[test]
+fn test_set_manager_and_chain() {
+    let (env, admin, client) = setup_test_env();
+
+    let ceo = Address::generate(&env);
+    let vp = Address::generate(&env);
+    let engineer = Address::generate(&env);
+
+    client.set_manager(&admin, &vp, &ceo);
+    client.set_manager(&admin, &engineer, &vp);
+
+    assert_eq!(client.get_manager(&engineer), Some(vp.clone()));
+    assert_eq!(client.get_manager(&ceo), None);
+    assert_eq!(
+        client.get_management_chain(&engineer),
+        vec![&env, vp.clone(), ceo.clone()]
+    );
+    assert!(client.is_in_chain(&engineer, &ceo));
+    assert!(!client.is_in_chain(&ceo, &engineer));
+    assert_eq!(client.get_direct_reports(&vp), vec![&env, engineer]);
+}
+
+#[test]
+fn test_reassign_moves_report() {
+    let (env, admin, client) = setup_test_env();
+
+    let old_manager = Address::generate(&env);
+    let new_manager = Address::generate(&env);
+    let employee = Address::generate(&env);
+
+    client.set_manager(&admin, &employee, &old_manager);
+    client.set_manager(&admin, &employee, &new_manager);
+
+    assert_eq!(client.get_manager(&employee), Some(new_manager.clone()));
+    assert_eq!(client.get_direct_reports(&old_manager).len(), 0);
+    assert_eq!(
+        client.get_direct_reports(&new_manager),
+        vec![&env, employee]
+    );
+}
+
+#[test]
+fn test_remove_manager() {
+    let (env, admin, client) = setup_test_env();
+
+    let manager = Address::generate(&env);
+    let employee = Address::generate(&env);
+
+    client.set_manager(&admin, &employee, &manager);
+    client.remove_manager(&admin, &employee);
+
+    assert_eq!(client.get_manager(&employee), None);
+    assert_eq!(client.get_direct_reports(&manager).len(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #6)")]
+fn test_self_manager_rejected() {
+    let (env, admin, client) = setup_test_env();
+
+    let employee = Address::generate(&env);
+    client.set_manager(&admin, &employee, &employee);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")]
+fn test_cycle_rejected() {
+    let (env, admin, client) = setup_test_env();
+
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    let c = Address::generate(&env);
+
+    client.set_manager(&admin, &b, &a);
+    client.set_manager(&admin, &c, &b);
+
+    // a -> c would close the loop a -> c -> b -> a
+    client.set_manager(&admin, &a, &c);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")]
+fn test_chain_depth_limited() {
+    let (env, admin, client) = setup_test_env();
+
+    let mut current = Address::generate(&env);
+    for _ in 0..MAX_CHAIN_DEPTH {
+        let employee = Address::generate(&env);
+        client.set_manager(&admin, &employee, &current);
+        current = employee;
+    }
+
+    let employee = Address::generate(&env);
+    client.set_manager(&admin, &employee, &current);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_set_manager_unauthorized() {
+    let (env, _admin, client) = setup_test_env();
+
+    let outsider = Address::generate(&env);
+    client.set_manager(
+        &outsider,
+        &Address::generate(&env),
+        &Address::generate(&env),
+    );
+}
+
+// ============================================
+// Bulk Update Tests
+// ============================================
+
+#[test]
+fn test_batch_set_managers_partial_failures() {
+    let (env, admin, client) = setup_test_env();
+
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    let c = Address::generate(&env);
+
+    let mut assignments: Vec<ManagerAssignment> = Vec::new(&env);
+    assignments.push_back(ManagerAssignment {
+        employee: b.clone(),
+        manager: Some(a.clone()),
+    });
+    assignments.push_back(ManagerAssignment {
+        employee: c.clone(),
+        manager: Some(c.clone()),
+    });
+    assignments.push_back(ManagerAssignment {
+        employee: a.clone(),
+        manager: Some(b.clone()),
+    });
+    assignments.push_back(ManagerAssignment {
+        employee: c.clone(),
+        manager: Some(b.clone()),
+    });
+
+    let result = client.batch_set_managers(&admin, &assignments);
+
+    assert_eq!(result.total_requests, 4);
+    assert_eq!(result.successful, 2);
+    assert_eq!(result.failed, 2);
+    assert_eq!(
+        result.results.get(1).unwrap(),
+        AssignmentResult::Failure(c.clone(), ErrorCode::SELF_MANAGED)
+    );
+    assert_eq!(
+        result.results.get(2).unwrap(),
+        AssignmentResult::Failure(a.clone(), ErrorCode::CYCLE_DETECTED)
+    );
+    assert_eq!(client.get_management_chain(&c), vec![&env, b, a]);
+}
+
+#[test]
+fn test_batch_clears_manager() {
+    let (env, admin, client) = setup_test_env();
+
+    let manager = Address::generate(&env);
+    let employee = Address::generate(&env);
+    client.set_manager(&admin, &employee, &manager);
+
+    let mut assignments: Vec<ManagerAssignment> = Vec::new(&env);
+    assignments.push_back(ManagerAssignment {
+        employee: employee.clone(),
+        manager: None,
+    });
+    let result = client.batch_set_managers(&admin, &assignments);
+
+    assert_eq!(result.successful, 1);
+    assert_eq!(client.get_manager(&employee), None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")]
+fn test_batch_empty_rejected() {
+    let (env, admin, client) = setup_test_env();
+
+    client.batch_set_managers(&admin, &Vec::new(&env));
+}
+
+// ============================================
+// Rollup Tests
+// ============================================
+
+#[test]
+fn test_get_all_reports_rollup() {
+    let (env, admin, client) = setup_test_env();
+
+    let ceo = Address::generate(&env);
+    let vp_1 = Address::generate(&env);
+    let vp_2 = Address::generate(&env);
+    let eng_1 = Address::generate(&env);
+    let eng_2 = Address::generate(&env);
+
+    client.set_manager(&admin, &vp_1, &ceo);
+    client.set_manager(&admin, &vp_2, &ceo);
+    client.set_manager(&admin, &eng_1, &vp_1);
+    client.set_manager(&admin, &eng_2, &vp_2);
+
+    let reports = client.get_all_reports(&ceo);
+    assert_eq!(
+        reports,
+        vec![&env, vp_1.clone(), vp_2, eng_1, eng_2.clone()]
+    );
+    assert_eq!(client.get_all_reports(&eng_2).len(), 0);
+}
@@ -0,0 +1,97 @@
+//! Data types and events for the org hierarchy contract.
+
+use common::events;
+use soroban_sdk::{contracttype, symbol_short, Address, Env, Vec};
+
+/// Maximum number of assignments in a single batch update.
+pub const MAX_BATCH_SIZE: u32 = 100;
+
+/// Maximum length of a management chain (employee to top of the org).
+pub const MAX_CHAIN_DEPTH: u32 = 32;
+
+/// Maximum number of employees returned by a reporting rollup.
+pub const MAX_ROLLUP_SIZE: u32 = 500;
+
+/// Request to set (or clear) an employee's manager.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ManagerAssignment {
+    pub employee: Address,
+    /// New manager, or `None` to detach the employee from the org chart
+    pub manager: Option<Address>,
+}
+
+/// Result of a single assignment within a batch.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum AssignmentResult {
+    /// Assignment applied: employee
+    Success(Address),
+    /// Assignment rejected: employee, error code
+    Failure(Address, u32),
+}
+
+/// Summary of a batch manager update.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct BatchAssignmentResult {
+    pub total_requests: u32,
+    pub successful: u32,
+    pub failed: u32,
+    pub results: Vec<AssignmentResult>,
+}
+
+/// Storage keys for the org hierarchy contract.
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    /// Admin address
+    Admin,
+    /// Direct manager of an employee
+    Manager(Address),
+    /// Direct reports of a manager
+    Reports(Address),
+}
+
+/// Error codes for per-assignment failures in batch updates.
+#[allow(non_snake_case)]
+pub mod ErrorCode {
+    /// Employee cannot manage themselves
+    pub const SELF_MANAGED: u32 = 0;
+    /// Assignment would create a cycle in the management chain
+    pub const CYCLE_DETECTED: u32 = 1;
+    /// Resulting chain would exceed the maximum depth
+    pub const CHAIN_TOO_DEEP: u32 = 2;
+}
+
+/// Event emitters for org hierarchy changes.
+pub struct OrgEvents;
+
+impl OrgEvents {
+    /// Emitted when an employee's manager is set or changed.
+    pub fn manager_set(
+        env: &Env,
+        employee: &Address,
+        previous: &Option<Address>,
+        manager: &Address,
+    ) {
+        let topics = (symbol_short!("org"), symbol_short!("mgr_set"));
+        events::publish(
+            env,
+            topics,
+            (employee.clone(), previous.clone(), manager.clone()),
+        );
+    }
+
+    /// Emitted when an employee is detached from their manager.
+    pub fn manager_removed(env: &Env, employee: &Address, previous: &Address) {
+        let topics = (symbol_short!("org"), symbol_short!("mgr_rm"));
+        events::publish(env, topics, (employee.clone(), previous.clone()));
+    }
+
+    /// Emitted when a batch update completes.
+    pub fn batch_completed(env: &Env, successful: u32, failed: u32) {
+        let topics = (symbol_short!("org"), symbol_short!("batch"));
+        events::publish(env, topics, (successful, failed));
+    }
+}
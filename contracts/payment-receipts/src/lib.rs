@@ -0,0 +1,222 @@
+//! # Payment Receipts Contract
+//!
+//! Registry of proof-of-payment receipts. After a settlement contract pays
+//! out, it issues a receipt here naming the payer, payee, amount, token and
+//! ledger. Receipts cannot be transferred or altered, so the payer can list
+//! them as portable proof of payment, and other contracts or third parties
+//! can check a receipt they are shown against the registry.
+//!
+//! ## Features
+//!
+//! - **Issuer Registry**: Only admin-registered settlement contracts can issue
+//! - **Non-Transferable**: A receipt always belongs to the payer it names
+//! - **Enumeration**: Payers can page through their receipts
+//! - **Verification**: Anyone can check a receipt against the registry
+#![no_std]
+
+mod types;
+
+use common::pagination::{self, IdPage};
+use common::versioning;
+use soroban_sdk::{contract, contractimpl, panic_with_error, Address, BytesN, Env, Vec};
+
+pub use crate::types::{DataKey, Receipt, ReceiptEvents};
+
+/// Error codes for the payment receipts contract.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum ReceiptError {
+    /// Contract not initialized
+    NotInitialized = 1,
+    /// Caller is not authorized
+    Unauthorized = 2,
+    /// Contract already initialized
+    AlreadyInitialized = 3,
+    /// Caller is not a registered issuer
+    NotIssuer = 4,
+    /// Amount is zero or negative
+    InvalidAmount = 5,
+    /// Storage was written by a different layout version
+    StorageVersionMismatch = 6,
+    /// Migration source version does not match stored version
+    InvalidMigration = 7,
+}
+
+impl From<ReceiptError> for soroban_sdk::Error {
+    fn from(e: ReceiptError) -> Self {
+        soroban_sdk::Error::from_contract_error(e as u32)
+    }
+}
+
+/// Storage layout version this code expects.
+pub const STORAGE_VERSION: u32 = 1;
+
+#[contract]
+pub struct PaymentReceiptsContract;
+
+#[contractimpl]
+impl PaymentReceiptsContract {
+    /// Initializes the contract with an admin address.
+    pub fn initialize(env: Env, admin: Address) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic_with_error!(&env, ReceiptError::AlreadyInitialized);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        versioning::set_version(&env, STORAGE_VERSION);
+    }
+
+    /// Migrates storage written by an older version of this contract to the
+    /// current layout (admin only). `from_version` must match the stored
+    /// version.
+    pub fn migrate(env: Env, caller: Address, from_version: u32) {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        versioning::migrate(&env, from_version, STORAGE_VERSION, |_, _| {})
+            .unwrap_or_else(|_| panic_with_error!(&env, ReceiptError::InvalidMigration));
+    }
+
+    /// Returns the storage layout version currently stored.
+    pub fn get_storage_version(env: Env) -> u32 {
+        versioning::stored_version(&env)
+    }
+
+    /// Registers or removes an issuer (admin only). Receipts already issued
+    /// by a removed issuer remain valid.
+    pub fn set_issuer(env: Env, caller: Address, issuer: Address, enabled: bool) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        if enabled {
+            env.storage()
+                .persistent()
+                .set(&DataKey::Issuer(issuer.clone()), &true);
+        } else {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::Issuer(issuer.clone()));
+        }
+
+        ReceiptEvents::issuer_set(&env, &issuer, enabled);
+    }
+
+    /// Issues a receipt for a settled payment (registered issuers only).
+    ///
+    /// Issuers call this once the payment has been transferred; the receipt
+    /// records the current ledger.
+    pub fn issue_receipt(
+        env: Env,
+        issuer: Address,
+        payer: Address,
+        payee: Address,
+        amount: i128,
+        token: Address,
+        memo_hash: Option<BytesN<32>>,
+    ) -> u64 {
+        Self::require_storage_version(&env);
+        issuer.require_auth();
+        if !Self::is_issuer(env.clone(), issuer.clone()) {
+            panic_with_error!(&env, ReceiptError::NotIssuer);
+        }
+        if amount <= 0 {
+            panic_with_error!(&env, ReceiptError::InvalidAmount);
+        }
+
+        let receipt_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::LastReceiptId)
+            .unwrap_or(0)
+            + 1;
+        let receipt = Receipt {
+            receipt_id,
+            issuer,
+            payer,
+            payee,
+            amount,
+            token,
+            ledger: env.ledger().sequence(),
+            issued_at: env.ledger().timestamp(),
+            memo_hash,
+        };
+
+        let mut receipts = Self::payer_receipt_ids(&env, &receipt.payer);
+        receipts.push_back(receipt_id);
+        env.storage()
+            .instance()
+            .set(&DataKey::LastReceiptId, &receipt_id);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Receipt(receipt_id), &receipt);
+        env.storage()
+            .persistent()
+            .set(&DataKey::PayerReceipts(receipt.payer.clone()), &receipts);
+
+        ReceiptEvents::issued(&env, &receipt);
+
+        receipt_id
+    }
+
+    /// Returns a receipt by ID.
+    pub fn get_receipt(env: Env, receipt_id: u64) -> Option<Receipt> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Receipt(receipt_id))
+    }
+
+    /// Returns the page of a payer's receipt IDs following `cursor`, oldest
+    /// first.
+    pub fn get_payer_receipts(env: Env, payer: Address, cursor: Option<u64>, limit: u32) -> IdPage {
+        let receipts = Self::payer_receipt_ids(&env, &payer);
+        pagination::page_after(&env, &receipts, cursor, limit)
+    }
+
+    /// Returns whether `receipt` matches, field for field, a receipt in the
+    /// registry. A party shown a receipt can use this to check it is genuine.
+    pub fn verify_receipt(env: Env, receipt: Receipt) -> bool {
+        Self::get_receipt(env, receipt.receipt_id) == Some(receipt)
+    }
+
+    /// Returns whether `issuer` is registered.
+    pub fn is_issuer(env: Env, issuer: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Issuer(issuer))
+            .unwrap_or(false)
+    }
+
+    /// Returns the admin address.
+    pub fn get_admin(env: Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(&env, ReceiptError::NotInitialized))
+    }
+
+    // Internal helper loading the IDs of a payer's receipts
+    fn payer_receipt_ids(env: &Env, payer: &Address) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PayerReceipts(payer.clone()))
+            .unwrap_or(Vec::new(env))
+    }
+
+    // Internal helper refusing to run against storage written by a different
+    // layout version
+    fn require_storage_version(env: &Env) {
+        versioning::require_version(env, STORAGE_VERSION)
+            .unwrap_or_else(|_| panic_with_error!(env, ReceiptError::StorageVersionMismatch));
+    }
+
+    // Internal helper to verify admin
+    fn require_admin(env: &Env, caller: &Address) {
+        if *caller != Self::get_admin(env.clone()) {
+            panic_with_error!(env, ReceiptError::Unauthorized);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
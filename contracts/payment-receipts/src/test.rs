@@ -0,0 +1,121 @@
+//! Integration tests for the payment receipts contract.
+
+#![cfg(test)]
+
+use crate::{PaymentReceiptsContract, PaymentReceiptsContractClient, ReceiptError};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, BytesN, Env,
+};
+
+/// Creates a test environment with the contract deployed and initialized and
+/// a registered issuer.
+fn setup_test_env() -> (
+    Env,
+    Address,
+    Address,
+    PaymentReceiptsContractClient<'static>,
+) {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1_000;
+        li.sequence_number = 42;
+    });
+
+    let contract_id = env.register(PaymentReceiptsContract, ());
+    let client = PaymentReceiptsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let issuer = Address::generate(&env);
+    client.set_issuer(&admin, &issuer, &true);
+
+    (env, admin, issuer, client)
+}
+
+#[test]
+fn test_issue_and_verify_receipt() {
+    let (env, admin, issuer, client) = setup_test_env();
+    let payer = Address::generate(&env);
+    let payee = Address::generate(&env);
+    let token = Address::generate(&env);
+    let memo_hash = BytesN::from_array(&env, &[9; 32]);
+
+    let receipt_id = client.issue_receipt(
+        &issuer,
+        &payer,
+        &payee,
+        &250,
+        &token,
+        &Some(memo_hash.clone()),
+    );
+
+    let receipt = client.get_receipt(&receipt_id).unwrap();
+    assert_eq!(receipt.issuer, issuer);
+    assert_eq!(receipt.payer, payer);
+    assert_eq!(receipt.payee, payee);
+    assert_eq!(receipt.amount, 250);
+    assert_eq!(receipt.ledger, 42);
+    assert_eq!(receipt.issued_at, 1_000);
+    assert_eq!(receipt.memo_hash, Some(memo_hash));
+    assert!(client.verify_receipt(&receipt));
+
+    let mut forged = receipt.clone();
+    forged.amount = 2_500;
+    assert!(!client.verify_receipt(&forged));
+
+    // Removing the issuer does not invalidate what it already issued
+    client.set_issuer(&admin, &issuer, &false);
+    assert!(client.verify_receipt(&receipt));
+}
+
+#[test]
+fn test_payer_enumerates_receipts() {
+    let (env, _admin, issuer, client) = setup_test_env();
+    let payer = Address::generate(&env);
+    let other = Address::generate(&env);
+    let token = Address::generate(&env);
+
+    let first = client.issue_receipt(&issuer, &payer, &other, &10, &token, &None);
+    client.issue_receipt(&issuer, &other, &payer, &20, &token, &None);
+    let third = client.issue_receipt(&issuer, &payer, &other, &30, &token, &None);
+
+    let page = client.get_payer_receipts(&payer, &None, &1);
+    assert_eq!(page.ids, vec![&env, first]);
+    let page = client.get_payer_receipts(&payer, &page.next_cursor, &1);
+    assert_eq!(page.ids, vec![&env, third]);
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn test_only_issuers_can_issue() {
+    let (env, _admin, _issuer, client) = setup_test_env();
+    let stranger = Address::generate(&env);
+
+    let result = client.try_issue_receipt(
+        &stranger,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &10,
+        &Address::generate(&env),
+        &None,
+    );
+    assert_eq!(result, Err(Ok(ReceiptError::NotIssuer.into())));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn test_issue_rejects_non_positive_amount() {
+    let (env, _admin, issuer, client) = setup_test_env();
+
+    client.issue_receipt(
+        &issuer,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &0,
+        &Address::generate(&env),
+        &None,
+    );
+}
@@ -0,0 +1,58 @@
+//! Data types and events for the payment receipts contract.
+
+use common::events;
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env};
+
+/// Proof that a payment settled, issued by the contract that settled it.
+///
+/// Receipts have no owner that can change: there is no transfer, so a
+/// receipt always vouches for the payer it names.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Receipt {
+    pub receipt_id: u64,
+    /// Settlement contract that issued the receipt
+    pub issuer: Address,
+    pub payer: Address,
+    pub payee: Address,
+    pub amount: i128,
+    pub token: Address,
+    /// Ledger sequence the receipt was issued in
+    pub ledger: u32,
+    pub issued_at: u64,
+    /// Hash of an off-chain memo describing the payment, if any
+    pub memo_hash: Option<BytesN<32>>,
+}
+
+/// Storage keys for the payment receipts contract.
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    /// Admin address
+    Admin,
+    /// Whether an address may issue receipts
+    Issuer(Address),
+    /// ID of the most recently issued receipt
+    LastReceiptId,
+    /// Receipt by ID
+    Receipt(u64),
+    /// IDs of a payer's receipts, ascending
+    PayerReceipts(Address),
+}
+
+/// Event emitters for payment receipt operations.
+pub struct ReceiptEvents;
+
+impl ReceiptEvents {
+    /// Emitted when a receipt is issued.
+    pub fn issued(env: &Env, receipt: &Receipt) {
+        let topics = (symbol_short!("receipt"), symbol_short!("issued"));
+        events::publish(env, topics, receipt.clone());
+    }
+
+    /// Emitted when an issuer is registered or removed.
+    pub fn issuer_set(env: &Env, issuer: &Address, enabled: bool) {
+        let topics = (symbol_short!("receipt"), symbol_short!("issuer"));
+        events::publish(env, topics, (issuer.clone(), enabled));
+    }
+}
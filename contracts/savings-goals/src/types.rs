@@ -1,5 +1,6 @@
 //! Data types and events for batch savings goal operations.
 
+use common::events;
 use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol, Vec};
 
 /// Maximum number of user-goal pairs in a single batch for optimization.
@@ -230,13 +231,14 @@ impl GoalEvents {
     /// Event emitted when batch goal creation starts.
     pub fn batch_started(env: &Env, batch_id: u64, request_count: u32) {
         let topics = (symbol_short!("batch"), symbol_short!("started"));
-        env.events().publish(topics, (batch_id, request_count));
+        events::publish(env, topics, (batch_id, request_count));
     }
 
     /// Event emitted when a goal is successfully created.
     pub fn goal_created(env: &Env, batch_id: u64, goal: &SavingsGoal) {
         let topics = (symbol_short!("goal"), symbol_short!("created"), batch_id);
-        env.events().publish(
+        events::publish(
+            env,
             topics,
             (goal.goal_id, goal.user.clone(), goal.target_amount),
         );
@@ -245,7 +247,7 @@ impl GoalEvents {
     /// Event emitted when goal creation fails.
     pub fn goal_creation_failed(env: &Env, batch_id: u64, user: &Address, error_code: u32) {
         let topics = (symbol_short!("goal"), symbol_short!("failed"), batch_id);
-        env.events().publish(topics, (user.clone(), error_code));
+        events::publish(env, topics, (user.clone(), error_code));
     }
 
     /// Event emitted when batch goal creation completes.
@@ -257,26 +259,26 @@ impl GoalEvents {
         total_amount: i128,
     ) {
         let topics = (symbol_short!("batch"), symbol_short!("completed"), batch_id);
-        env.events()
-            .publish(topics, (successful, failed, total_amount));
+        events::publish(env, topics, (successful, failed, total_amount));
     }
 
     /// Event emitted for high-value goals (>= 10,000 XLM).
     pub fn high_value_goal(env: &Env, batch_id: u64, goal_id: u64, amount: i128) {
         let topics = (symbol_short!("goal"), symbol_short!("highval"), batch_id);
-        env.events().publish(topics, (goal_id, amount));
+        events::publish(env, topics, (goal_id, amount));
     }
 
     /// Event emitted when batch milestone achievement starts.
     pub fn milestone_batch_started(env: &Env, batch_id: u64, request_count: u32) {
         let topics = (symbol_short!("milestone"), symbol_short!("start"));
-        env.events().publish(topics, (batch_id, request_count));
+        events::publish(env, topics, (batch_id, request_count));
     }
 
     /// Event emitted when a milestone is successfully achieved.
     pub fn milestone_achieved(env: &Env, batch_id: u64, milestone: &MilestoneAchievement) {
         let topics = (symbol_short!("milestone"), symbol_short!("achieved"), batch_id);
-        env.events().publish(
+        events::publish(
+            env,
             topics,
             (
                 milestone.milestone_id,
@@ -289,7 +291,7 @@ impl GoalEvents {
     /// Event emitted when milestone achievement fails.
     pub fn milestone_achievement_failed(env: &Env, batch_id: u64, goal_id: u64, error_code: u32) {
         let topics = (symbol_short!("milestone"), symbol_short!("failed"), batch_id);
-        env.events().publish(topics, (goal_id, error_code));
+        events::publish(env, topics, (goal_id, error_code));
     }
 
     /// Event emitted when batch milestone achievement completes.
@@ -301,7 +303,10 @@ impl GoalEvents {
         total_percentage: u32,
     ) {
         let topics = (symbol_short!("milestone"), symbol_short!("done"));
-        env.events()
-            .publish(topics, (batch_id, successful, failed, total_percentage));
+        events::publish(
+            env,
+            topics,
+            (batch_id, successful, failed, total_percentage),
+        );
     }
 }
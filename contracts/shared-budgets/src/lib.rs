@@ -1,16 +1,22 @@
 //! # Shared Budgets Contract
-//! Batch allocation of a caller's balance (shared budget) to multiple recipients.
+//! Batch allocation of a caller's balance (shared budget) to multiple recipients,
+//! and team pools that a manager funds and named members draw from under
+//! per-member caps.
 
 #![no_std]
 
 mod types;
 mod validation;
 
-use soroban_sdk::{contract, contractimpl, panic_with_error, Address, Env, Vec};
+use common::ids::{self, IdScope};
+use common::reentrancy::ReentrancyGuard;
+use common::token_io::{self, TransferError};
+use common::versioning;
+use soroban_sdk::{contract, contractimpl, panic_with_error, symbol_short, Address, Env, Vec};
 
 pub use crate::types::{
-    AllocationBatchResult, AllocationRequest, AllocationResult, DataKey, SharedBudgetEvents,
-    MAX_BATCH_SIZE,
+    AllocationBatchResult, AllocationRequest, AllocationResult, DataKey, MemberCap, PoolMember,
+    SharedBudgetEvents, TeamPool, MAX_BATCH_SIZE, MAX_POOL_MEMBERS,
 };
 use crate::validation::{validate_address, validate_amount};
 
@@ -26,6 +32,30 @@ pub enum SharedBudgetError {
     EmptyBatch = 3,
     /// Batch exceeds maximum size
     BatchTooLarge = 4,
+    /// Storage was written by a different layout version
+    StorageVersionMismatch = 5,
+    /// Migration source version does not match stored version
+    InvalidMigration = 6,
+    /// Amount must be positive
+    InvalidAmount = 7,
+    /// Team pool does not exist
+    PoolNotFound = 8,
+    /// Team pool has been closed
+    PoolClosed = 9,
+    /// Address is not a member of the team pool
+    NotPoolMember = 10,
+    /// Draw would take the member past their cap
+    MemberCapExceeded = 11,
+    /// Draw exceeds the pool's undrawn balance
+    PoolExhausted = 12,
+    /// Member list is empty, too long, repeats a member or has a bad cap
+    InvalidMembers = 13,
+    /// Token transfer did not move the expected amount
+    TokenTransferFailed = 14,
+    /// A pool operation is already executing
+    Reentrancy = 15,
+    /// Pool ids are exhausted
+    IdsExhausted = 16,
 }
 
 impl From<SharedBudgetError> for soroban_sdk::Error {
@@ -34,6 +64,9 @@ impl From<SharedBudgetError> for soroban_sdk::Error {
     }
 }
 
+/// Storage layout version this code expects.
+pub const STORAGE_VERSION: u32 = 1;
+
 #[contract]
 pub struct SharedBudgetContract;
 
@@ -46,6 +79,7 @@ impl SharedBudgetContract {
         }
 
         env.storage().instance().set(&DataKey::Admin, &admin);
+        versioning::set_version(&env, STORAGE_VERSION);
         env.storage().instance().set(&DataKey::TotalBatches, &0u64);
         env.storage()
             .instance()
@@ -55,6 +89,24 @@ impl SharedBudgetContract {
             .set(&DataKey::TotalAllocatedVolume, &0i128);
     }
 
+    /// Migrates storage written by an older version of this contract to the
+    /// current layout (admin only). `from_version` must match the stored
+    /// version.
+    pub fn migrate(env: Env, caller: Address, from_version: u32) {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        // Version 1 only adds the version stamp; older layouts are otherwise
+        // unchanged, so there is nothing to rewrite
+        versioning::migrate(&env, from_version, STORAGE_VERSION, |_, _| {})
+            .unwrap_or_else(|_| panic_with_error!(&env, SharedBudgetError::InvalidMigration));
+    }
+
+    /// Returns the storage layout version currently stored.
+    pub fn get_storage_version(env: Env) -> u32 {
+        versioning::stored_version(&env)
+    }
+
     /// Allocates a shared budget (caller balance) to multiple recipients in batch.
     /// Performs per-recipient validation and supports partial failures. The caller
     /// must be the configured admin and the source of funds.
@@ -64,6 +116,7 @@ impl SharedBudgetContract {
         _token: Address,
         allocations: Vec<AllocationRequest>,
     ) -> AllocationBatchResult {
+        Self::require_storage_version(&env);
         // Verify authorization
         caller.require_auth();
         Self::require_admin(&env, &caller);
@@ -119,7 +172,7 @@ impl SharedBudgetContract {
                 results.push_back(AllocationResult::Failure(
                     request.recipient.clone(),
                     request.amount,
-                    error_code.clone(),
+                    error_code,
                 ));
                 failed_count += 1;
                 SharedBudgetEvents::allocation_failure(
@@ -127,7 +180,7 @@ impl SharedBudgetContract {
                     batch_id,
                     &request.recipient,
                     request.amount,
-                    error_code.clone(),
+                    error_code,
                 );
                 continue;
             }
@@ -223,6 +276,194 @@ impl SharedBudgetContract {
         }
     }
 
+    /// Escrows `amount` of `token` from the manager as a team pool the
+    /// listed members draw from, each up to their own cap.
+    ///
+    /// Caps may add up to more than the pool holds; members then draw first
+    /// come, first served until the pool runs out.
+    ///
+    /// # Returns
+    /// * The new pool id
+    pub fn create_team_pool(
+        env: Env,
+        manager: Address,
+        token: Address,
+        amount: i128,
+        members: Vec<MemberCap>,
+    ) -> u64 {
+        Self::require_storage_version(&env);
+        manager.require_auth();
+
+        if validate_amount(amount).is_err() {
+            panic_with_error!(&env, SharedBudgetError::InvalidAmount);
+        }
+        if members.is_empty() || members.len() > MAX_POOL_MEMBERS {
+            panic_with_error!(&env, SharedBudgetError::InvalidMembers);
+        }
+
+        let _guard = Self::enter_guard(&env);
+        let id = ids::next_id(&env, &IdScope::new(0, symbol_short!("pool")))
+            .unwrap_or_else(|_| panic_with_error!(&env, SharedBudgetError::IdsExhausted));
+
+        let mut pool = TeamPool {
+            id,
+            manager,
+            token,
+            funded: amount,
+            drawn: 0,
+            members: Vec::new(&env),
+            closed: false,
+        };
+        for entry in members.iter() {
+            if pool.members.contains(&entry.member) || validate_amount(entry.cap).is_err() {
+                panic_with_error!(&env, SharedBudgetError::InvalidMembers);
+            }
+            pool.members.push_back(entry.member.clone());
+            let standing = PoolMember {
+                member: entry.member.clone(),
+                cap: entry.cap,
+                drawn: 0,
+            };
+            Self::save_member(&env, id, &standing);
+            SharedBudgetEvents::member_set(&env, id, &entry.member, entry.cap);
+        }
+        Self::save_pool(&env, &pool);
+
+        let contract = env.current_contract_address();
+        Self::send(&env, &pool.token, &pool.manager, &contract, amount);
+
+        SharedBudgetEvents::pool_created(&env, &pool);
+
+        id
+    }
+
+    /// Adds a member to a team pool or changes an existing member's cap
+    /// (pool manager only). A cap cannot be set below what the member has
+    /// already drawn.
+    pub fn set_pool_member(env: Env, manager: Address, pool_id: u64, member: Address, cap: i128) {
+        Self::require_storage_version(&env);
+        manager.require_auth();
+
+        let mut pool = Self::load_open_pool(&env, pool_id);
+        if pool.manager != manager {
+            panic_with_error!(&env, SharedBudgetError::Unauthorized);
+        }
+
+        let drawn = match Self::get_pool_member(env.clone(), pool_id, member.clone()) {
+            Some(existing) => existing.drawn,
+            None => {
+                if pool.members.len() >= MAX_POOL_MEMBERS {
+                    panic_with_error!(&env, SharedBudgetError::InvalidMembers);
+                }
+                pool.members.push_back(member.clone());
+                Self::save_pool(&env, &pool);
+                0
+            }
+        };
+        if validate_amount(cap).is_err() || cap < drawn {
+            panic_with_error!(&env, SharedBudgetError::InvalidMembers);
+        }
+
+        let standing = PoolMember {
+            member: member.clone(),
+            cap,
+            drawn,
+        };
+        Self::save_member(&env, pool_id, &standing);
+        SharedBudgetEvents::member_set(&env, pool_id, &member, cap);
+    }
+
+    /// Draws `amount` from a team pool to the member (members only).
+    ///
+    /// The member's draws and the pool's balance are updated before the
+    /// tokens move, so draws by several members in the same ledger are each
+    /// checked against what the others have already taken.
+    pub fn draw_from_pool(env: Env, member: Address, pool_id: u64, amount: i128) {
+        Self::require_storage_version(&env);
+        member.require_auth();
+
+        if validate_amount(amount).is_err() {
+            panic_with_error!(&env, SharedBudgetError::InvalidAmount);
+        }
+
+        let _guard = Self::enter_guard(&env);
+        let mut pool = Self::load_open_pool(&env, pool_id);
+        let mut entry = Self::get_pool_member(env.clone(), pool_id, member.clone())
+            .unwrap_or_else(|| panic_with_error!(&env, SharedBudgetError::NotPoolMember));
+        if amount > entry.cap - entry.drawn {
+            panic_with_error!(&env, SharedBudgetError::MemberCapExceeded);
+        }
+        if amount > pool.funded - pool.drawn {
+            panic_with_error!(&env, SharedBudgetError::PoolExhausted);
+        }
+
+        entry.drawn += amount;
+        pool.drawn += amount;
+        Self::save_member(&env, pool_id, &entry);
+        Self::save_pool(&env, &pool);
+
+        let contract = env.current_contract_address();
+        Self::send(&env, &pool.token, &contract, &member, amount);
+
+        SharedBudgetEvents::pool_draw(&env, pool_id, &member, amount, pool.funded - pool.drawn);
+    }
+
+    /// Closes a team pool and returns its undrawn balance to the manager
+    /// (pool manager only).
+    ///
+    /// # Returns
+    /// * The amount returned
+    pub fn close_team_pool(env: Env, manager: Address, pool_id: u64) -> i128 {
+        Self::require_storage_version(&env);
+        manager.require_auth();
+
+        let _guard = Self::enter_guard(&env);
+        let mut pool = Self::load_open_pool(&env, pool_id);
+        if pool.manager != manager {
+            panic_with_error!(&env, SharedBudgetError::Unauthorized);
+        }
+
+        let returned = pool.funded - pool.drawn;
+        pool.closed = true;
+        Self::save_pool(&env, &pool);
+
+        if returned > 0 {
+            let contract = env.current_contract_address();
+            Self::send(&env, &pool.token, &contract, &pool.manager, returned);
+        }
+
+        SharedBudgetEvents::pool_closed(&env, pool_id, returned);
+
+        returned
+    }
+
+    /// Returns a team pool, if it exists.
+    pub fn get_team_pool(env: Env, pool_id: u64) -> Option<TeamPool> {
+        env.storage().persistent().get(&DataKey::TeamPool(pool_id))
+    }
+
+    /// Returns a member's cap and draws in a team pool, if they are a member.
+    pub fn get_pool_member(env: Env, pool_id: u64, member: Address) -> Option<PoolMember> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PoolMember(pool_id, member))
+    }
+
+    /// Returns every member's consumption of a team pool, in the order the
+    /// members were added.
+    pub fn get_pool_consumption(env: Env, pool_id: u64) -> Vec<PoolMember> {
+        let pool = Self::get_team_pool(env.clone(), pool_id)
+            .unwrap_or_else(|| panic_with_error!(&env, SharedBudgetError::PoolNotFound));
+
+        let mut consumption = Vec::new(&env);
+        for member in pool.members.iter() {
+            if let Some(entry) = Self::get_pool_member(env.clone(), pool_id, member) {
+                consumption.push_back(entry);
+            }
+        }
+        consumption
+    }
+
     /// Returns the admin address.
     pub fn get_admin(env: Env) -> Address {
         env.storage()
@@ -233,6 +474,7 @@ impl SharedBudgetContract {
 
     /// Updates the admin address.
     pub fn set_admin(env: Env, current_admin: Address, new_admin: Address) {
+        Self::require_storage_version(&env);
         current_admin.require_auth();
         Self::require_admin(&env, &current_admin);
 
@@ -263,6 +505,54 @@ impl SharedBudgetContract {
             .unwrap_or(0)
     }
 
+    // Internal helper loading a team pool that exists and is still open
+    fn load_open_pool(env: &Env, pool_id: u64) -> TeamPool {
+        let pool = Self::get_team_pool(env.clone(), pool_id)
+            .unwrap_or_else(|| panic_with_error!(env, SharedBudgetError::PoolNotFound));
+        if pool.closed {
+            panic_with_error!(env, SharedBudgetError::PoolClosed);
+        }
+        pool
+    }
+
+    // Internal helper storing a team pool
+    fn save_pool(env: &Env, pool: &TeamPool) {
+        env.storage()
+            .persistent()
+            .set(&DataKey::TeamPool(pool.id), pool);
+    }
+
+    // Internal helper storing a member's standing in a team pool
+    fn save_member(env: &Env, pool_id: u64, entry: &PoolMember) {
+        env.storage()
+            .persistent()
+            .set(&DataKey::PoolMember(pool_id, entry.member.clone()), entry);
+    }
+
+    // Internal helper moving a pool token with balance checks
+    fn send(env: &Env, token: &Address, from: &Address, to: &Address, amount: i128) {
+        token_io::safe_transfer(env, token, from, to, amount).unwrap_or_else(|e| match e {
+            TransferError::InvalidAmount => {
+                panic_with_error!(env, SharedBudgetError::InvalidAmount)
+            }
+            _ => panic_with_error!(env, SharedBudgetError::TokenTransferFailed),
+        })
+    }
+
+    // Internal helper taking the reentrancy lock for pool operations that call
+    // out to the token
+    fn enter_guard(env: &Env) -> ReentrancyGuard {
+        ReentrancyGuard::enter(env)
+            .unwrap_or_else(|_| panic_with_error!(env, SharedBudgetError::Reentrancy))
+    }
+
+    // Internal helper refusing to run against storage written by a different
+    // layout version
+    fn require_storage_version(env: &Env) {
+        versioning::require_version(env, STORAGE_VERSION)
+            .unwrap_or_else(|_| panic_with_error!(env, SharedBudgetError::StorageVersionMismatch));
+    }
+
     // Internal helper to verify admin
     fn require_admin(env: &Env, caller: &Address) {
         let admin: Address = env
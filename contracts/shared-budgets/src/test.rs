@@ -3,12 +3,12 @@
 #![cfg(test)]
 
 use crate::{
-    AllocationBatchResult, AllocationRequest, AllocationResult, SharedBudgetContract,
-    SharedBudgetContractClient,
+    AllocationRequest, MemberCap, SharedBudgetContract, SharedBudgetContractClient,
+    SharedBudgetError,
 };
 use soroban_sdk::{
     testutils::{Address as _, Events as _, Ledger},
-    token, Address, Env, Vec,
+    token, vec, Address, Env, Vec,
 };
 
 /// Creates a test environment with the contract deployed and initialized.
@@ -229,3 +229,101 @@ fn test_set_admin() {
 
     assert_eq!(client.get_admin(), new_admin);
 }
+
+// Team Pool Tests
+
+/// Mints 1,000 tokens to a new manager and creates a 500-token pool with two
+/// members capped at 300 each.
+fn create_team_pool(
+    env: &Env,
+    token: &Address,
+    client: &SharedBudgetContractClient,
+) -> (Address, Address, Address, u64) {
+    let manager = Address::generate(env);
+    token::StellarAssetClient::new(env, token).mint(&manager, &1_000);
+
+    let alice = Address::generate(env);
+    let bob = Address::generate(env);
+    let members = vec![
+        env,
+        MemberCap {
+            member: alice.clone(),
+            cap: 300,
+        },
+        MemberCap {
+            member: bob.clone(),
+            cap: 300,
+        },
+    ];
+    let pool_id = client.create_team_pool(&manager, token, &500, &members);
+
+    (manager, alice, bob, pool_id)
+}
+
+#[test]
+fn test_team_members_draw_within_caps_and_pool() {
+    let (env, _admin, token, token_client, client) = setup_test_env();
+    let (manager, alice, bob, pool_id) = create_team_pool(&env, &token, &client);
+    assert_eq!(token_client.balance(&manager), 500);
+    assert_eq!(token_client.balance(&client.address), 500);
+
+    client.draw_from_pool(&alice, &pool_id, &250);
+    let result = client.try_draw_from_pool(&alice, &pool_id, &100);
+    assert_eq!(result, Err(Ok(SharedBudgetError::MemberCapExceeded.into())));
+
+    // Bob's cap allows 300, but Alice has already taken half the pool
+    let result = client.try_draw_from_pool(&bob, &pool_id, &300);
+    assert_eq!(result, Err(Ok(SharedBudgetError::PoolExhausted.into())));
+    client.draw_from_pool(&bob, &pool_id, &250);
+    assert_eq!(token_client.balance(&alice), 250);
+    assert_eq!(token_client.balance(&bob), 250);
+
+    let consumption = client.get_pool_consumption(&pool_id);
+    assert_eq!(consumption.len(), 2);
+    assert_eq!(consumption.get(0).unwrap().member, alice);
+    assert_eq!(consumption.get(0).unwrap().drawn, 250);
+    assert_eq!(consumption.get(1).unwrap().drawn, 250);
+    assert_eq!(client.get_team_pool(&pool_id).unwrap().drawn, 500);
+
+    let outsider = Address::generate(&env);
+    let result = client.try_draw_from_pool(&outsider, &pool_id, &1);
+    assert_eq!(result, Err(Ok(SharedBudgetError::NotPoolMember.into())));
+}
+
+#[test]
+fn test_manager_adjusts_caps_and_closes_pool() {
+    let (env, _admin, token, token_client, client) = setup_test_env();
+    let (manager, alice, _bob, pool_id) = create_team_pool(&env, &token, &client);
+
+    client.draw_from_pool(&alice, &pool_id, &200);
+    let result = client.try_set_pool_member(&manager, &pool_id, &alice, &100);
+    assert_eq!(result, Err(Ok(SharedBudgetError::InvalidMembers.into())));
+    let result = client.try_set_pool_member(&alice, &pool_id, &alice, &400);
+    assert_eq!(result, Err(Ok(SharedBudgetError::Unauthorized.into())));
+
+    let carol = Address::generate(&env);
+    client.set_pool_member(&manager, &pool_id, &carol, &50);
+    client.draw_from_pool(&carol, &pool_id, &50);
+    assert_eq!(client.get_pool_consumption(&pool_id).len(), 3);
+
+    assert_eq!(client.close_team_pool(&manager, &pool_id), 250);
+    assert_eq!(token_client.balance(&manager), 750);
+    let result = client.try_draw_from_pool(&alice, &pool_id, &10);
+    assert_eq!(result, Err(Ok(SharedBudgetError::PoolClosed.into())));
+}
+
+#[test]
+fn test_team_pool_rejects_duplicate_members() {
+    let (env, _admin, token, _token_client, client) = setup_test_env();
+    let manager = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token).mint(&manager, &1_000);
+
+    let alice = Address::generate(&env);
+    let entry = MemberCap {
+        member: alice,
+        cap: 100,
+    };
+    let members = vec![&env, entry.clone(), entry];
+    let result = client.try_create_team_pool(&manager, &token, &500, &members);
+    assert_eq!(result, Err(Ok(SharedBudgetError::InvalidMembers.into())));
+}
@@ -1,10 +1,14 @@
 // Types and events for shared budget batch allocations.
 
+use common::events;
 use soroban_sdk::{contracttype, symbol_short, Address, Env, Vec};
 
 /// Maximum number of allocation entries in a single batch.
 pub const MAX_BATCH_SIZE: u32 = 100;
 
+/// Maximum number of members drawing from one team pool.
+pub const MAX_POOL_MEMBERS: u32 = 20;
+
 /// A single allocation request from a shared budget to a recipient.
 #[derive(Clone, Debug)]
 #[contracttype]
@@ -39,6 +43,42 @@ pub struct AllocationBatchResult {
     pub results: Vec<AllocationResult>,
 }
 
+/// A member a team pool is opened to and the most they may draw from it.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct MemberCap {
+    pub member: Address,
+    pub cap: i128,
+}
+
+/// A team allowance a manager funds and named members draw from.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TeamPool {
+    pub id: u64,
+    pub manager: Address,
+    /// Token the pool is held in
+    pub token: Address,
+    /// Amount escrowed from the manager
+    pub funded: i128,
+    /// Sum of all members' draws
+    pub drawn: i128,
+    /// Members in the order they were added
+    pub members: Vec<Address>,
+    /// Whether the undrawn balance has been returned to the manager
+    pub closed: bool,
+}
+
+/// A member's consumption of a team pool.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PoolMember {
+    pub member: Address,
+    /// Most the member may draw in total
+    pub cap: i128,
+    pub drawn: i128,
+}
+
 /// Storage keys for contract state.
 #[derive(Clone)]
 #[contracttype]
@@ -51,6 +91,10 @@ pub enum DataKey {
     TotalAllocationsProcessed,
     /// Total amount allocated across all batches
     TotalAllocatedVolume,
+    /// Team pool by id
+    TeamPool(u64),
+    /// A member's cap and draws in a team pool
+    PoolMember(u64, Address),
 }
 
 /// Events emitted by the shared budgets contract.
@@ -60,13 +104,13 @@ impl SharedBudgetEvents {
     /// Event emitted when allocation batch processing starts.
     pub fn batch_started(env: &Env, batch_id: u64, request_count: u32) {
         let topics = (symbol_short!("alloc"), symbol_short!("started"));
-        env.events().publish(topics, (batch_id, request_count));
+        events::publish(env, topics, (batch_id, request_count));
     }
 
     /// Event emitted when an allocation succeeds for a recipient.
     pub fn allocation_success(env: &Env, batch_id: u64, recipient: &Address, amount: i128) {
         let topics = (symbol_short!("alloc"), symbol_short!("success"), batch_id);
-        env.events().publish(topics, (recipient.clone(), amount));
+        events::publish(env, topics, (recipient.clone(), amount));
     }
 
     /// Event emitted when an allocation fails for a recipient.
@@ -78,7 +122,7 @@ impl SharedBudgetEvents {
         error_code: u32,
     ) {
         let topics = (symbol_short!("alloc"), symbol_short!("failed"), batch_id);
-        env.events().publish(topics, (recipient.clone(), amount, error_code));
+        events::publish(env, topics, (recipient.clone(), amount, error_code));
     }
 
     /// Event emitted when allocation batch processing completes.
@@ -90,6 +134,30 @@ impl SharedBudgetEvents {
         total_allocated: i128,
     ) {
         let topics = (symbol_short!("alloc"), symbol_short!("completed"), batch_id);
-        env.events().publish(topics, (successful, failed, total_allocated));
+        events::publish(env, topics, (successful, failed, total_allocated));
+    }
+
+    /// Event emitted when a manager funds a team pool.
+    pub fn pool_created(env: &Env, pool: &TeamPool) {
+        let topics = (symbol_short!("pool"), symbol_short!("created"), pool.id);
+        events::publish(env, topics, (pool.manager.clone(), pool.funded));
+    }
+
+    /// Event emitted when a member's cap in a team pool is set.
+    pub fn member_set(env: &Env, pool_id: u64, member: &Address, cap: i128) {
+        let topics = (symbol_short!("pool"), symbol_short!("member"), pool_id);
+        events::publish(env, topics, (member.clone(), cap));
+    }
+
+    /// Event emitted when a member draws from a team pool.
+    pub fn pool_draw(env: &Env, pool_id: u64, member: &Address, amount: i128, remaining: i128) {
+        let topics = (symbol_short!("pool"), symbol_short!("draw"), pool_id);
+        events::publish(env, topics, (member.clone(), amount, remaining));
+    }
+
+    /// Event emitted when a team pool is closed and its balance returned.
+    pub fn pool_closed(env: &Env, pool_id: u64, returned: i128) {
+        let topics = (symbol_short!("pool"), symbol_short!("closed"), pool_id);
+        events::publish(env, topics, returned);
     }
 }
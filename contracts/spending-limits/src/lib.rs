@@ -11,6 +11,8 @@
 //! - **Error Handling**: Gracefully handles invalid inputs with detailed error codes
 //! - **Optimized Storage**: Minimizes storage writes by batching operations
 //! - **Partial Failure Support**: Invalid updates don't affect valid ones
+//! - **Reference Currency**: Limits are expressed in the reference currency;
+//!   changing it flags limits set earlier as pre-change
 //!
 //! ## Optimization Strategies
 //!
@@ -24,11 +26,17 @@
 mod types;
 mod validation;
 
-use soroban_sdk::{contract, contractimpl, panic_with_error, Address, Env, Vec};
+use common::ids::{self, IdScope};
+use common::limits::{self, Limit};
+use common::reference::{self, ReferenceCurrency};
+use common::versioning;
+use soroban_sdk::{
+    contract, contractimpl, panic_with_error, symbol_short, Address, Env, Symbol, Vec,
+};
 
 pub use crate::types::{
     BatchLimitMetrics, BatchLimitResult, DataKey, ErrorCode, LimitEvents, LimitUpdateResult,
-    SpendingLimit, SpendingLimitRequest, MAX_BATCH_SIZE,
+    SpendingLimit, SpendingLimitRequest,
 };
 use crate::validation::validate_limit_request;
 
@@ -46,6 +54,16 @@ pub enum SpendingLimitError {
     EmptyBatch = 4,
     /// Batch exceeds maximum size
     BatchTooLarge = 5,
+    /// Batch ids have been exhausted
+    IdsExhausted = 6,
+    /// Limit value is outside its hard bounds
+    LimitOutOfBounds = 7,
+    /// Storage was written by a different layout version
+    StorageVersionMismatch = 8,
+    /// Migration source version does not match stored version
+    InvalidMigration = 9,
+    /// Reference currency setting is unsupported or already in effect
+    InvalidReferenceCurrency = 10,
 }
 
 impl From<SpendingLimitError> for soroban_sdk::Error {
@@ -54,6 +72,9 @@ impl From<SpendingLimitError> for soroban_sdk::Error {
     }
 }
 
+/// Storage layout version this code expects.
+pub const STORAGE_VERSION: u32 = 1;
+
 #[contract]
 pub struct SpendingLimitsContract;
 
@@ -70,7 +91,7 @@ impl SpendingLimitsContract {
         }
 
         env.storage().instance().set(&DataKey::Admin, &admin);
-        env.storage().instance().set(&DataKey::LastBatchId, &0u64);
+        versioning::set_version(&env, STORAGE_VERSION);
         env.storage()
             .instance()
             .set(&DataKey::TotalLimitsUpdated, &0u64);
@@ -79,6 +100,24 @@ impl SpendingLimitsContract {
             .set(&DataKey::TotalBatchesProcessed, &0u64);
     }
 
+    /// Migrates storage written by an older version of this contract to the
+    /// current layout (admin only). `from_version` must match the stored
+    /// version.
+    pub fn migrate(env: Env, caller: Address, from_version: u32) {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        // Version 1 only adds the version stamp; older layouts are otherwise
+        // unchanged, so there is nothing to rewrite
+        versioning::migrate(&env, from_version, STORAGE_VERSION, |_, _| {})
+            .unwrap_or_else(|_| panic_with_error!(&env, SpendingLimitError::InvalidMigration));
+    }
+
+    /// Returns the storage layout version currently stored.
+    pub fn get_storage_version(env: Env) -> u32 {
+        versioning::stored_version(&env)
+    }
+
     /// Updates monthly spending limits for multiple users in a batch.
     ///
     /// This is the main entry point for batch limit updates. It validates all requests,
@@ -108,6 +147,7 @@ impl SpendingLimitsContract {
         caller: Address,
         requests: Vec<SpendingLimitRequest>,
     ) -> BatchLimitResult {
+        Self::require_storage_version(&env);
         // Verify authorization
         caller.require_auth();
         Self::require_admin(&env, &caller);
@@ -117,23 +157,19 @@ impl SpendingLimitsContract {
         if request_count == 0 {
             panic_with_error!(&env, SpendingLimitError::EmptyBatch);
         }
-        if request_count > MAX_BATCH_SIZE {
+        if request_count > limits::get(&env, Limit::BatchSize) {
             panic_with_error!(&env, SpendingLimitError::BatchTooLarge);
         }
 
-        // Get batch ID and increment
-        let batch_id: u64 = env
-            .storage()
-            .instance()
-            .get(&DataKey::LastBatchId)
-            .unwrap_or(0)
-            + 1;
+        // Issue the next batch ID
+        let batch_id = Self::next_batch_id(&env);
 
         // Emit batch started event
         LimitEvents::batch_started(&env, batch_id, request_count);
 
         // Get current ledger timestamp
         let current_ledger = env.ledger().sequence() as u64;
+        let epoch = reference::get(&env).epoch;
 
         // Initialize result tracking
         let mut results: Vec<LimitUpdateResult> = Vec::new(&env);
@@ -166,6 +202,9 @@ impl SpendingLimitsContract {
                     env.storage()
                         .persistent()
                         .set(&DataKey::SpendingLimit(request.user.clone()), &limit);
+                    env.storage()
+                        .persistent()
+                        .set(&DataKey::LimitEpoch(request.user.clone()), &epoch);
 
                     // Emit success event
                     LimitEvents::limit_updated(&env, batch_id, &limit);
@@ -226,9 +265,6 @@ impl SpendingLimitsContract {
             .get(&DataKey::TotalBatchesProcessed)
             .unwrap_or(0);
 
-        env.storage()
-            .instance()
-            .set(&DataKey::LastBatchId, &batch_id);
         env.storage().instance().set(
             &DataKey::TotalLimitsUpdated,
             &(total_limits + successful_count as u64),
@@ -270,6 +306,46 @@ impl SpendingLimitsContract {
             .get(&DataKey::SpendingLimit(user))
     }
 
+    /// Switches the reference currency limits are expressed in (admin only,
+    /// which is the protocol's governance for this contract).
+    ///
+    /// The change starts a new reference epoch and publishes a `restated`
+    /// event. Limits set before it are reported as pre-change until they are
+    /// updated again.
+    pub fn set_reference_currency(env: Env, admin: Address, code: Symbol, decimals: u32) {
+        Self::require_storage_version(&env);
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        reference::set(&env, code, decimals).unwrap_or_else(|_| {
+            panic_with_error!(&env, SpendingLimitError::InvalidReferenceCurrency)
+        });
+    }
+
+    /// Gets the reference currency in effect.
+    pub fn get_reference_currency(env: Env) -> ReferenceCurrency {
+        reference::get(&env)
+    }
+
+    /// Returns true if a user's limit was set under an earlier reference
+    /// currency than the one in effect. Limits set before epochs were tracked
+    /// count as epoch 0.
+    pub fn is_limit_pre_change(env: Env, user: Address) -> bool {
+        if !env
+            .storage()
+            .persistent()
+            .has(&DataKey::SpendingLimit(user.clone()))
+        {
+            return false;
+        }
+        let epoch: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::LimitEpoch(user))
+            .unwrap_or(0);
+        reference::is_pre_change(&env, epoch)
+    }
+
     /// Returns the admin address.
     pub fn get_admin(env: Env) -> Address {
         env.storage()
@@ -280,17 +356,34 @@ impl SpendingLimitsContract {
 
     /// Updates the admin address.
     pub fn set_admin(env: Env, current_admin: Address, new_admin: Address) {
+        Self::require_storage_version(&env);
         current_admin.require_auth();
         Self::require_admin(&env, &current_admin);
 
         env.storage().instance().set(&DataKey::Admin, &new_admin);
     }
 
+    /// Overrides a protocol limit for this contract, within the limit's hard
+    /// bounds.
+    pub fn set_limit(env: Env, caller: Address, limit: Limit, value: u32) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        limits::set(&env, limit, value)
+            .unwrap_or_else(|_| panic_with_error!(&env, SpendingLimitError::LimitOutOfBounds));
+    }
+
+    /// Returns the value of a protocol limit for this contract.
+    pub fn get_limit(env: Env, limit: Limit) -> u32 {
+        limits::get(&env, limit)
+    }
+
     /// Returns the last created batch ID.
     pub fn get_last_batch_id(env: Env) -> u64 {
-        env.storage()
-            .instance()
-            .get(&DataKey::LastBatchId)
+        let legacy: Option<u64> = env.storage().instance().get(&DataKey::LastBatchId);
+        legacy
+            .or_else(|| ids::last_id(&env, &Self::batch_ids()))
             .unwrap_or(0)
     }
 
@@ -310,6 +403,13 @@ impl SpendingLimitsContract {
             .unwrap_or(0)
     }
 
+    // Internal helper refusing to run against storage written by a different
+    // layout version
+    fn require_storage_version(env: &Env) {
+        versioning::require_version(env, STORAGE_VERSION)
+            .unwrap_or_else(|_| panic_with_error!(env, SpendingLimitError::StorageVersionMismatch));
+    }
+
     // Internal helper to verify admin
     fn require_admin(env: &Env, caller: &Address) {
         let admin: Address = env
@@ -322,6 +422,27 @@ impl SpendingLimitsContract {
             panic_with_error!(env, SpendingLimitError::Unauthorized);
         }
     }
+
+    // Internal helper that issues batch IDs from the shared id counters. The
+    // first call after an upgrade carries the legacy LastBatchId over so
+    // existing batch IDs are never reissued.
+    fn next_batch_id(env: &Env) -> u64 {
+        let scope = Self::batch_ids();
+        let legacy: Option<u64> = env.storage().instance().get(&DataKey::LastBatchId);
+        if let Some(last_issued) = legacy {
+            ids::reserve_through(env, &scope, last_issued)
+                .unwrap_or_else(|_| panic_with_error!(env, SpendingLimitError::IdsExhausted));
+            env.storage().instance().remove(&DataKey::LastBatchId);
+        }
+
+        ids::next_id(env, &scope)
+            .unwrap_or_else(|_| panic_with_error!(env, SpendingLimitError::IdsExhausted))
+    }
+
+    // Internal helper returning the id scope for limit batches
+    fn batch_ids() -> IdScope {
+        IdScope::new(0, symbol_short!("batch"))
+    }
 }
 
 #[cfg(test)]
@@ -3,7 +3,8 @@
 #![cfg(test)]
 
 use crate::{SpendingLimitsContract, SpendingLimitsContractClient};
-use soroban_sdk::{symbol_short, testutils::Address as _, Address, Env, Vec};
+use common::limits::Limit;
+use soroban_sdk::{symbol_short, testutils::Address as _, Address, Env, Symbol, Vec};
 
 use crate::types::{ErrorCode, LimitUpdateResult, SpendingLimitRequest};
 
@@ -22,7 +23,7 @@ fn setup_test_contract() -> (Env, Address, SpendingLimitsContractClient<'static>
 }
 
 /// Helper function to create a valid spending limit request.
-fn create_valid_request(env: &Env, user: &Address, limit: i128) -> SpendingLimitRequest {
+fn create_valid_request(_env: &Env, user: &Address, limit: i128) -> SpendingLimitRequest {
     SpendingLimitRequest {
         user: user.clone(),
         monthly_limit: limit,
@@ -95,7 +96,7 @@ fn test_batch_update_spending_limits_multiple_users() {
             LimitUpdateResult::Success(limit) => {
                 assert!(limit.monthly_limit > 0);
                 assert_eq!(limit.current_spending, 0);
-                assert_eq!(limit.is_active, true);
+                assert!(limit.is_active);
             }
             LimitUpdateResult::Failure(_, _) => panic!("Expected success, got failure"),
         }
@@ -203,7 +204,7 @@ fn test_batch_update_batch_too_large() {
     let user = Address::generate(&env);
 
     let mut requests: Vec<SpendingLimitRequest> = Vec::new(&env);
-    // Create 101 requests (exceeds MAX_BATCH_SIZE of 100)
+    // Create 101 requests (exceeds the default batch size limit of 100)
     for i in 0..101 {
         requests.push_back(create_valid_request(
             &env,
@@ -215,6 +216,58 @@ fn test_batch_update_batch_too_large() {
     client.batch_update_spending_limits(&admin, &requests);
 }
 
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn test_batch_size_limit_is_configurable() {
+    let (env, admin, client) = setup_test_contract();
+    assert_eq!(client.get_limit(&Limit::BatchSize), 100);
+
+    client.set_limit(&admin, &Limit::BatchSize, &2);
+    assert_eq!(client.get_limit(&Limit::BatchSize), 2);
+
+    let mut requests: Vec<SpendingLimitRequest> = Vec::new(&env);
+    for _ in 0..3 {
+        let user = Address::generate(&env);
+        requests.push_back(create_valid_request(&env, &user, 50_000_000_000));
+    }
+    client.batch_update_spending_limits(&admin, &requests);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")]
+fn test_limit_outside_hard_bounds_rejected() {
+    let (_env, admin, client) = setup_test_contract();
+    client.set_limit(&admin, &Limit::BatchSize, &1_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")]
+fn test_outdated_storage_blocks_writes() {
+    let (env, admin, client) = setup_test_contract();
+    env.as_contract(&client.address, || common::versioning::set_version(&env, 0));
+
+    client.set_limit(&admin, &Limit::BatchSize, &10);
+}
+
+#[test]
+fn test_migrate_from_unversioned_storage() {
+    let (env, admin, client) = setup_test_contract();
+    assert_eq!(client.get_storage_version(), crate::STORAGE_VERSION);
+    env.as_contract(&client.address, || common::versioning::set_version(&env, 0));
+
+    client.migrate(&admin, &0);
+    assert_eq!(client.get_storage_version(), crate::STORAGE_VERSION);
+    client.set_limit(&admin, &Limit::BatchSize, &10);
+    assert_eq!(client.get_limit(&Limit::BatchSize), 10);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn test_migrate_from_wrong_version() {
+    let (_env, admin, client) = setup_test_contract();
+    client.migrate(&admin, &0);
+}
+
 #[test]
 fn test_get_spending_limit() {
     let (env, admin, client) = setup_test_contract();
@@ -231,7 +284,7 @@ fn test_get_spending_limit() {
     assert_eq!(limit.user, user);
     assert_eq!(limit.monthly_limit, 50_000_000_000);
     assert_eq!(limit.current_spending, 0);
-    assert_eq!(limit.is_active, true);
+    assert!(limit.is_active);
 }
 
 #[test]
@@ -412,3 +465,29 @@ fn test_maximum_valid_limit() {
     assert_eq!(result.successful, 1);
     assert_eq!(result.failed, 0);
 }
+
+#[test]
+fn test_reference_change_flags_earlier_limits() {
+    let (env, admin, client) = setup_test_contract();
+    let user = Address::generate(&env);
+    let requests = Vec::from_array(&env, [create_valid_request(&env, &user, 50_000_000_000)]);
+    client.batch_update_spending_limits(&admin, &requests);
+    assert!(!client.is_limit_pre_change(&user));
+
+    client.set_reference_currency(&admin, &Symbol::new(&env, "EURC"), &7);
+    assert_eq!(client.get_reference_currency().epoch, 1);
+    assert!(client.is_limit_pre_change(&user));
+    // Users without a limit are not flagged
+    assert!(!client.is_limit_pre_change(&Address::generate(&env)));
+
+    // Updating the limit records it under the new reference
+    client.batch_update_spending_limits(&admin, &requests);
+    assert!(!client.is_limit_pre_change(&user));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")]
+fn test_reference_change_rejects_unsupported_decimals() {
+    let (env, admin, client) = setup_test_contract();
+    client.set_reference_currency(&admin, &Symbol::new(&env, "EURC"), &19);
+}
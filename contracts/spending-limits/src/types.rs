@@ -1,10 +1,8 @@
 //! Data types and events for batch spending limit operations.
 
+use common::events;
 use soroban_sdk::{contracttype, symbol_short, Address, Env, Vec};
 
-/// Maximum number of user-limit pairs in a single batch for optimization.
-pub const MAX_BATCH_SIZE: u32 = 100;
-
 /// Minimum monthly spending limit (0.1 XLM in stroops)
 pub const MIN_SPENDING_LIMIT: i128 = 1_000_000;
 
@@ -91,7 +89,7 @@ pub struct BatchLimitResult {
 pub enum DataKey {
     /// Admin address
     Admin,
-    /// Last created batch ID
+    /// Legacy last batch ID, migrated to the shared id counters on first use
     LastBatchId,
     /// Stored spending limit by user address
     SpendingLimit(Address),
@@ -99,9 +97,12 @@ pub enum DataKey {
     TotalLimitsUpdated,
     /// Total batches processed lifetime
     TotalBatchesProcessed,
+    /// Reference currency epoch a user's limit was set under
+    LimitEpoch(Address),
 }
 
 /// Error codes for spending limit validation and updates.
+#[allow(non_snake_case)]
 pub mod ErrorCode {
     /// Invalid limit amount (too low, too high, or negative)
     pub const INVALID_LIMIT: u32 = 0;
@@ -120,20 +121,19 @@ impl LimitEvents {
     /// Event emitted when batch limit update starts.
     pub fn batch_started(env: &Env, batch_id: u64, request_count: u32) {
         let topics = (symbol_short!("batch"), symbol_short!("started"));
-        env.events().publish(topics, (batch_id, request_count));
+        events::publish(env, topics, (batch_id, request_count));
     }
 
     /// Event emitted when a limit is successfully updated.
     pub fn limit_updated(env: &Env, batch_id: u64, limit: &SpendingLimit) {
         let topics = (symbol_short!("limit"), symbol_short!("updated"), batch_id);
-        env.events()
-            .publish(topics, (limit.user.clone(), limit.monthly_limit));
+        events::publish(env, topics, (limit.user.clone(), limit.monthly_limit));
     }
 
     /// Event emitted when limit update fails.
     pub fn limit_update_failed(env: &Env, batch_id: u64, user: &Address, error_code: u32) {
         let topics = (symbol_short!("limit"), symbol_short!("failed"), batch_id);
-        env.events().publish(topics, (user.clone(), error_code));
+        events::publish(env, topics, (user.clone(), error_code));
     }
 
     /// Event emitted when batch limit update completes.
@@ -145,13 +145,12 @@ impl LimitEvents {
         total_limits: i128,
     ) {
         let topics = (symbol_short!("batch"), symbol_short!("completed"), batch_id);
-        env.events()
-            .publish(topics, (successful, failed, total_limits));
+        events::publish(env, topics, (successful, failed, total_limits));
     }
 
     /// Event emitted for high-value limits (>= 1,000,000 XLM).
     pub fn high_value_limit(env: &Env, batch_id: u64, user: &Address, amount: i128) {
         let topics = (symbol_short!("limit"), symbol_short!("highval"), batch_id);
-        env.events().publish(topics, (user.clone(), amount));
+        events::publish(env, topics, (user.clone(), amount));
     }
 }
@@ -45,7 +45,7 @@ fn is_valid_address(_address: &Address) -> bool {
 /// # Returns
 /// * `true` if limit is >= MIN_SPENDING_LIMIT and <= MAX_SPENDING_LIMIT
 pub fn is_valid_limit(limit: i128) -> bool {
-    limit >= MIN_SPENDING_LIMIT && limit <= MAX_SPENDING_LIMIT
+    (MIN_SPENDING_LIMIT..=MAX_SPENDING_LIMIT).contains(&limit)
 }
 
 #[cfg(test)]
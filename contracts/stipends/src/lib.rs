@@ -0,0 +1,338 @@
+//! # Stipends Contract
+//!
+//! Benefit stipends such as wellness or education allowances. An employer
+//! loads a stipend for an employee in one merchant category; the contract
+//! escrows the funds, and the employee can pay them only to merchants
+//! registered in that category. Whatever is unspent when the stipend period
+//! ends returns to the employer.
+//!
+//! ## Features
+//!
+//! - **Escrowed Balances**: Stipend funds are held by the contract until spent or returned
+//! - **Category Restrictions**: Each payment is checked against the merchant's category
+//! - **Expiry**: Unspent balances return to the employer once the period ends
+#![no_std]
+
+mod types;
+
+use common::ids::{self, IdScope};
+use common::pagination::{self, IdPage};
+use common::reentrancy::ReentrancyGuard;
+use common::token_io::{self, TransferError};
+use common::versioning;
+use soroban_sdk::{
+    contract, contractimpl, panic_with_error, symbol_short, Address, Env, Symbol, Vec,
+};
+
+pub use crate::types::{DataKey, Stipend, StipendEvents};
+
+/// Error codes for the stipends contract.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum StipendError {
+    /// Contract not initialized
+    NotInitialized = 1,
+    /// Caller is not authorized
+    Unauthorized = 2,
+    /// Contract already initialized
+    AlreadyInitialized = 3,
+    /// Amount must be positive
+    InvalidAmount = 4,
+    /// Expiry is not in the future
+    InvalidExpiry = 5,
+    /// Stipend does not exist
+    StipendNotFound = 6,
+    /// Stipend period has ended
+    StipendExpired = 7,
+    /// Stipend period has not ended yet
+    StipendActive = 8,
+    /// Merchant is not registered in the stipend's category
+    CategoryMismatch = 9,
+    /// Payment exceeds the stipend's unspent balance
+    InsufficientBalance = 10,
+    /// Unspent balance has already been returned
+    AlreadyClosed = 11,
+    /// Token transfer did not move the expected amount
+    TokenTransferFailed = 12,
+    /// Stipend ids are exhausted
+    IdsExhausted = 13,
+    /// Storage was written by a different layout version
+    StorageVersionMismatch = 14,
+    /// Migration source version does not match stored version
+    InvalidMigration = 15,
+    /// Entrypoint was re-entered during an external call
+    Reentrancy = 16,
+}
+
+impl From<StipendError> for soroban_sdk::Error {
+    fn from(e: StipendError) -> Self {
+        soroban_sdk::Error::from_contract_error(e as u32)
+    }
+}
+
+/// Storage layout version this code expects.
+pub const STORAGE_VERSION: u32 = 1;
+
+#[contract]
+pub struct StipendsContract;
+
+#[contractimpl]
+impl StipendsContract {
+    /// Initializes the contract with an admin address.
+    pub fn initialize(env: Env, admin: Address) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic_with_error!(&env, StipendError::AlreadyInitialized);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        versioning::set_version(&env, STORAGE_VERSION);
+    }
+
+    /// Migrates storage written by an older version of this contract to the
+    /// current layout (admin only). `from_version` must match the stored
+    /// version.
+    pub fn migrate(env: Env, caller: Address, from_version: u32) {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        versioning::migrate(&env, from_version, STORAGE_VERSION, |_, _| {})
+            .unwrap_or_else(|_| panic_with_error!(&env, StipendError::InvalidMigration));
+    }
+
+    /// Returns the storage layout version currently stored.
+    pub fn get_storage_version(env: Env) -> u32 {
+        versioning::stored_version(&env)
+    }
+
+    /// Registers a merchant under a category (admin only).
+    ///
+    /// Passing `None` removes the merchant, after which no stipend can be
+    /// spent there.
+    pub fn set_merchant_category(
+        env: Env,
+        caller: Address,
+        merchant: Address,
+        category: Option<Symbol>,
+    ) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        let key = DataKey::MerchantCategory(merchant.clone());
+        match &category {
+            Some(category) => env.storage().persistent().set(&key, category),
+            None => env.storage().persistent().remove(&key),
+        }
+
+        StipendEvents::merchant_set(&env, &merchant, &category);
+    }
+
+    /// Escrows `amount` of `token` from the employer as a stipend the
+    /// employee can spend in `category` until `expires_at`.
+    ///
+    /// # Returns
+    /// * The new stipend id
+    pub fn load_stipend(
+        env: Env,
+        employer: Address,
+        employee: Address,
+        token: Address,
+        category: Symbol,
+        amount: i128,
+        expires_at: u64,
+    ) -> u64 {
+        Self::require_storage_version(&env);
+        employer.require_auth();
+        let _guard = Self::enter_guard(&env);
+
+        if amount <= 0 {
+            panic_with_error!(&env, StipendError::InvalidAmount);
+        }
+        if expires_at <= env.ledger().timestamp() {
+            panic_with_error!(&env, StipendError::InvalidExpiry);
+        }
+
+        let id = ids::next_id(&env, &IdScope::new(0, symbol_short!("stipend")))
+            .unwrap_or_else(|_| panic_with_error!(&env, StipendError::IdsExhausted));
+        let contract = env.current_contract_address();
+        Self::send(&env, &token, &employer, &contract, amount);
+
+        let stipend = Stipend {
+            id,
+            employer,
+            employee,
+            token,
+            category,
+            amount,
+            spent: 0,
+            expires_at,
+            closed: false,
+        };
+        Self::save_stipend(&env, &stipend);
+
+        let mut stipends = Self::employee_stipend_ids(&env, &stipend.employee);
+        stipends.push_back(id);
+        env.storage().persistent().set(
+            &DataKey::EmployeeStipends(stipend.employee.clone()),
+            &stipends,
+        );
+
+        StipendEvents::loaded(&env, &stipend);
+
+        id
+    }
+
+    /// Pays a merchant from a stipend (employee only). The merchant must be
+    /// registered in the stipend's category and the period must not have
+    /// ended.
+    pub fn spend(env: Env, employee: Address, stipend_id: u64, merchant: Address, amount: i128) {
+        Self::require_storage_version(&env);
+        employee.require_auth();
+        let _guard = Self::enter_guard(&env);
+
+        let mut stipend = Self::load_stipend_record(&env, stipend_id);
+        if stipend.employee != employee {
+            panic_with_error!(&env, StipendError::Unauthorized);
+        }
+        if env.ledger().timestamp() >= stipend.expires_at {
+            panic_with_error!(&env, StipendError::StipendExpired);
+        }
+        if Self::get_merchant_category(env.clone(), merchant.clone())
+            != Some(stipend.category.clone())
+        {
+            panic_with_error!(&env, StipendError::CategoryMismatch);
+        }
+        if amount <= 0 {
+            panic_with_error!(&env, StipendError::InvalidAmount);
+        }
+        if amount > stipend.amount - stipend.spent {
+            panic_with_error!(&env, StipendError::InsufficientBalance);
+        }
+
+        stipend.spent += amount;
+        Self::save_stipend(&env, &stipend);
+
+        let contract = env.current_contract_address();
+        Self::send(&env, &stipend.token, &contract, &merchant, amount);
+
+        StipendEvents::spent(&env, stipend_id, &merchant, amount);
+    }
+
+    /// Returns an expired stipend's unspent balance to the employer. Anyone
+    /// may call this, as the funds can only go to the employer.
+    ///
+    /// # Returns
+    /// * The amount returned
+    pub fn expire_stipend(env: Env, stipend_id: u64) -> i128 {
+        Self::require_storage_version(&env);
+        let _guard = Self::enter_guard(&env);
+
+        let mut stipend = Self::load_stipend_record(&env, stipend_id);
+        if stipend.closed {
+            panic_with_error!(&env, StipendError::AlreadyClosed);
+        }
+        if env.ledger().timestamp() < stipend.expires_at {
+            panic_with_error!(&env, StipendError::StipendActive);
+        }
+
+        let returned = stipend.amount - stipend.spent;
+        stipend.closed = true;
+        Self::save_stipend(&env, &stipend);
+
+        if returned > 0 {
+            let contract = env.current_contract_address();
+            Self::send(&env, &stipend.token, &contract, &stipend.employer, returned);
+        }
+
+        StipendEvents::expired(&env, &stipend, returned);
+
+        returned
+    }
+
+    /// Returns a stipend, if it exists.
+    pub fn get_stipend(env: Env, stipend_id: u64) -> Option<Stipend> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Stipend(stipend_id))
+    }
+
+    /// Returns the page of an employee's stipend IDs following `cursor`,
+    /// oldest first.
+    pub fn get_employee_stipends(
+        env: Env,
+        employee: Address,
+        cursor: Option<u64>,
+        limit: u32,
+    ) -> IdPage {
+        let stipends = Self::employee_stipend_ids(&env, &employee);
+        pagination::page_after(&env, &stipends, cursor, limit)
+    }
+
+    /// Returns the category a merchant is registered under, if any.
+    pub fn get_merchant_category(env: Env, merchant: Address) -> Option<Symbol> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::MerchantCategory(merchant))
+    }
+
+    /// Returns the admin address.
+    pub fn get_admin(env: Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(&env, StipendError::NotInitialized))
+    }
+
+    // Internal helper loading a stipend that must exist
+    fn load_stipend_record(env: &Env, stipend_id: u64) -> Stipend {
+        Self::get_stipend(env.clone(), stipend_id)
+            .unwrap_or_else(|| panic_with_error!(env, StipendError::StipendNotFound))
+    }
+
+    // Internal helper storing a stipend
+    fn save_stipend(env: &Env, stipend: &Stipend) {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Stipend(stipend.id), stipend);
+    }
+
+    // Internal helper loading the IDs of an employee's stipends
+    fn employee_stipend_ids(env: &Env, employee: &Address) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::EmployeeStipends(employee.clone()))
+            .unwrap_or(Vec::new(env))
+    }
+
+    // Internal helper moving a stipend token with balance checks
+    fn send(env: &Env, token: &Address, from: &Address, to: &Address, amount: i128) {
+        token_io::safe_transfer(env, token, from, to, amount).unwrap_or_else(|e| match e {
+            TransferError::InvalidAmount => panic_with_error!(env, StipendError::InvalidAmount),
+            _ => panic_with_error!(env, StipendError::TokenTransferFailed),
+        })
+    }
+
+    // Internal helper taking the reentrancy lock for entrypoints that move
+    // stipend tokens
+    fn enter_guard(env: &Env) -> ReentrancyGuard {
+        ReentrancyGuard::enter(env)
+            .unwrap_or_else(|_| panic_with_error!(env, StipendError::Reentrancy))
+    }
+
+    // Internal helper refusing to run against storage written by a different
+    // layout version
+    fn require_storage_version(env: &Env) {
+        versioning::require_version(env, STORAGE_VERSION)
+            .unwrap_or_else(|_| panic_with_error!(env, StipendError::StorageVersionMismatch));
+    }
+
+    // Internal helper to verify admin
+    fn require_admin(env: &Env, caller: &Address) {
+        if *caller != Self::get_admin(env.clone()) {
+            panic_with_error!(env, StipendError::Unauthorized);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,218 @@
+//! Integration tests for the stipends contract.
+
+#![cfg(test)]
+
+use crate::{StipendError, StipendsContract, StipendsContractClient};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Ledger},
+    token, vec, Address, Env,
+};
+
+const PERIOD: u64 = 30 * 86_400;
+
+struct Setup {
+    env: Env,
+    employer: Address,
+    employee: Address,
+    gym: Address,
+    bookshop: Address,
+    token: token::Client<'static>,
+    client: StipendsContractClient<'static>,
+}
+
+/// Creates a test environment with the contract deployed and initialized, a
+/// gym registered under `wellness`, a bookshop under `education`, and an
+/// employer holding 1,000 tokens.
+fn setup_test_env() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let asset = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let token = token::Client::new(&env, &asset.address());
+
+    let client = StipendsContractClient::new(&env, &env.register(StipendsContract, ()));
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let gym = Address::generate(&env);
+    let bookshop = Address::generate(&env);
+    client.set_merchant_category(&admin, &gym, &Some(symbol_short!("wellness")));
+    client.set_merchant_category(&admin, &bookshop, &Some(symbol_short!("education")));
+
+    let employer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &asset.address()).mint(&employer, &1_000);
+
+    Setup {
+        employee: Address::generate(&env),
+        env,
+        employer,
+        gym,
+        bookshop,
+        token,
+        client,
+    }
+}
+
+fn load_wellness(s: &Setup, amount: i128) -> u64 {
+    let expires_at = s.env.ledger().timestamp() + PERIOD;
+    s.client.load_stipend(
+        &s.employer,
+        &s.employee,
+        &s.token.address,
+        &symbol_short!("wellness"),
+        &amount,
+        &expires_at,
+    )
+}
+
+#[test]
+fn test_spend_at_matching_merchant_and_expire() {
+    let s = setup_test_env();
+    let stipend_id = load_wellness(&s, 300);
+    assert_eq!(s.token.balance(&s.employer), 700);
+
+    s.client.spend(&s.employee, &stipend_id, &s.gym, &120);
+    assert_eq!(s.token.balance(&s.gym), 120);
+    assert_eq!(s.client.get_stipend(&stipend_id).unwrap().spent, 120);
+
+    let result = s.client.try_expire_stipend(&stipend_id);
+    assert_eq!(result, Err(Ok(StipendError::StipendActive.into())));
+
+    s.env.ledger().with_mut(|li| li.timestamp += PERIOD);
+    let result = s.client.try_spend(&s.employee, &stipend_id, &s.gym, &10);
+    assert_eq!(result, Err(Ok(StipendError::StipendExpired.into())));
+
+    assert_eq!(s.client.expire_stipend(&stipend_id), 180);
+    assert_eq!(s.token.balance(&s.employer), 880);
+    assert!(s.client.get_stipend(&stipend_id).unwrap().closed);
+    let result = s.client.try_expire_stipend(&stipend_id);
+    assert_eq!(result, Err(Ok(StipendError::AlreadyClosed.into())));
+}
+
+#[test]
+fn test_spend_restricted_to_category_and_balance() {
+    let s = setup_test_env();
+    let stipend_id = load_wellness(&s, 300);
+
+    let result = s
+        .client
+        .try_spend(&s.employee, &stipend_id, &s.bookshop, &50);
+    assert_eq!(result, Err(Ok(StipendError::CategoryMismatch.into())));
+    let unregistered = Address::generate(&s.env);
+    let result = s
+        .client
+        .try_spend(&s.employee, &stipend_id, &unregistered, &50);
+    assert_eq!(result, Err(Ok(StipendError::CategoryMismatch.into())));
+    let result = s.client.try_spend(&s.employee, &stipend_id, &s.gym, &301);
+    assert_eq!(result, Err(Ok(StipendError::InsufficientBalance.into())));
+
+    // Only the employee the stipend was loaded for can spend it
+    let other = Address::generate(&s.env);
+    let result = s.client.try_spend(&other, &stipend_id, &s.gym, &50);
+    assert_eq!(result, Err(Ok(StipendError::Unauthorized.into())));
+    assert_eq!(s.token.balance(&s.client.address), 300);
+}
+
+#[test]
+fn test_employee_lists_stipends() {
+    let s = setup_test_env();
+    let first = load_wellness(&s, 100);
+    let second = s.client.load_stipend(
+        &s.employer,
+        &s.employee,
+        &s.token.address,
+        &symbol_short!("education"),
+        &200,
+        &(s.env.ledger().timestamp() + PERIOD),
+    );
+
+    let page = s.client.get_employee_stipends(&s.employee, &None, &0);
+    assert_eq!(page.ids, vec![&s.env, first, second]);
+    assert_eq!(page.next_cursor, None);
+
+    s.client.spend(&s.employee, &second, &s.bookshop, &200);
+    assert_eq!(s.token.balance(&s.bookshop), 200);
+}
+
+#[test]
+fn test_load_rejects_past_expiry() {
+    let s = setup_test_env();
+    s.env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let result = s.client.try_load_stipend(
+        &s.employer,
+        &s.employee,
+        &s.token.address,
+        &symbol_short!("wellness"),
+        &100,
+        &1_000,
+    );
+    assert_eq!(result, Err(Ok(StipendError::InvalidExpiry.into())));
+}
+
+/// Token that spends from a stipend again from inside each transfer.
+#[contract]
+struct ReentrantToken;
+
+#[contractimpl]
+impl ReentrantToken {
+    pub fn mint(env: Env, to: Address, amount: i128) {
+        let balance = Self::balance(env.clone(), to.clone());
+        env.storage().persistent().set(&to, &(balance + amount));
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage().persistent().get(&id).unwrap_or(0)
+    }
+
+    /// Re-enters `spend` on `stipends` during the next transfer.
+    pub fn arm(env: Env, stipends: Address, employee: Address, stipend_id: u64, merchant: Address) {
+        let target = (stipends, employee, stipend_id, merchant);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("target"), &target);
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        let from_balance = Self::balance(env.clone(), from.clone());
+        env.storage()
+            .persistent()
+            .set(&from, &(from_balance - amount));
+        Self::mint(env.clone(), to, amount);
+
+        let target: Option<(Address, Address, u64, Address)> =
+            env.storage().instance().get(&symbol_short!("target"));
+        if let Some((stipends, employee, stipend_id, merchant)) = target {
+            StipendsContractClient::new(&env, &stipends).spend(
+                &employee,
+                &stipend_id,
+                &merchant,
+                &amount,
+            );
+        }
+    }
+}
+
+#[test]
+fn test_reentrant_token_cannot_spend_twice() {
+    let s = setup_test_env();
+    let token = ReentrantTokenClient::new(&s.env, &s.env.register(ReentrantToken, ()));
+    token.mint(&s.employer, &100);
+    let expires_at = s.env.ledger().timestamp() + PERIOD;
+    let id = s.client.load_stipend(
+        &s.employer,
+        &s.employee,
+        &token.address,
+        &symbol_short!("wellness"),
+        &100,
+        &expires_at,
+    );
+
+    token.arm(&s.client.address, &s.employee, &id, &s.gym);
+    assert!(s.client.try_spend(&s.employee, &id, &s.gym, &40).is_err());
+
+    assert_eq!(s.client.get_stipend(&id).unwrap().spent, 0);
+    assert_eq!(token.balance(&s.client.address), 100);
+    assert_eq!(token.balance(&s.gym), 0);
+}
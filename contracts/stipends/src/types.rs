@@ -0,0 +1,88 @@
+//! Data types and events for the stipends contract.
+
+use common::events;
+use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol};
+
+/// A restricted balance an employer has loaded for an employee, spendable
+/// only at merchants in its category.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Stipend {
+    pub id: u64,
+    pub employer: Address,
+    pub employee: Address,
+    /// Token the stipend is held in
+    pub token: Address,
+    /// Merchant category the stipend can be spent in, e.g. `wellness`
+    pub category: Symbol,
+    /// Amount escrowed from the employer
+    pub amount: i128,
+    /// Sum of payments made so far
+    pub spent: i128,
+    /// Ledger timestamp at which the stipend period ends and the unspent
+    /// balance can return to the employer
+    pub expires_at: u64,
+    /// Whether the unspent balance has been returned to the employer
+    pub closed: bool,
+}
+
+/// Storage keys for the stipends contract.
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    /// Admin address
+    Admin,
+    /// Category a merchant is registered under
+    MerchantCategory(Address),
+    /// Stipend by id
+    Stipend(u64),
+    /// IDs of an employee's stipends, in the order they were loaded
+    EmployeeStipends(Address),
+}
+
+/// Event emitters for stipend operations.
+pub struct StipendEvents;
+
+impl StipendEvents {
+    /// Emitted when a merchant's category is set or cleared.
+    pub fn merchant_set(env: &Env, merchant: &Address, category: &Option<Symbol>) {
+        let topics = (symbol_short!("stipend"), symbol_short!("merchant"));
+        events::publish(env, topics, (merchant.clone(), category.clone()));
+    }
+
+    /// Emitted when an employer loads a stipend.
+    pub fn loaded(env: &Env, stipend: &Stipend) {
+        let topics = (
+            symbol_short!("stipend"),
+            symbol_short!("loaded"),
+            stipend.id,
+        );
+        events::publish(
+            env,
+            topics,
+            (
+                stipend.employee.clone(),
+                stipend.category.clone(),
+                stipend.amount,
+                stipend.expires_at,
+            ),
+        );
+    }
+
+    /// Emitted when an employee pays a merchant from a stipend.
+    pub fn spent(env: &Env, stipend_id: u64, merchant: &Address, amount: i128) {
+        let topics = (symbol_short!("stipend"), symbol_short!("spent"), stipend_id);
+        events::publish(env, topics, (merchant.clone(), amount));
+    }
+
+    /// Emitted when an expired stipend's unspent balance returns to the
+    /// employer.
+    pub fn expired(env: &Env, stipend: &Stipend, returned: i128) {
+        let topics = (
+            symbol_short!("stipend"),
+            symbol_short!("expired"),
+            stipend.id,
+        );
+        events::publish(env, topics, (stipend.employer.clone(), returned));
+    }
+}
@@ -1,3 +1,5 @@
+use common::math::{self, Rounding};
+use common::reference;
 use soroban_sdk::{Address, Env, Map, Symbol, Vec};
 
 use crate::types::{
@@ -13,8 +15,8 @@ pub fn calculate_fee(amount: i128) -> i128 {
     if amount <= 0 {
         return 0;
     }
-    // 0.1% = amount * 10 / 10000 = amount / 1000
-    amount / 1000
+    // 0.1% estimate, rounded down
+    math::apply_bps(amount, 10, Rounding::Down).unwrap_or(0)
 }
 
 /// Computes aggregated metrics for a batch of transactions.
@@ -123,11 +125,7 @@ pub fn compute_category_metrics(
 
     for (category, (tx_count, volume, fees)) in category_map.iter() {
         // Calculate percentage in basis points (10000 = 100%)
-        let volume_percentage_bps = if total_volume > 0 {
-            ((volume * 10000) / total_volume) as u32
-        } else {
-            0
-        };
+        let volume_percentage_bps = math::ratio_bps(volume, total_volume).unwrap_or(0);
 
         result.push_back(CategoryMetrics {
             category,
@@ -186,7 +184,7 @@ pub fn validate_batch(transactions: &Vec<Transaction>) -> Result<(), &'static st
 
 /// Validates a batch of audit logs.
 pub fn validate_audit_logs(logs: &Vec<AuditLog>) -> Result<(), &'static str> {
-    if logs.len() == 0 {
+    if logs.is_empty() {
         return Err("Audit logs batch cannot be empty");
     }
 
@@ -348,7 +346,7 @@ pub fn compute_refund_metrics(
     };
     
     RefundBatchMetrics {
-        request_count: request_count as u32,
+        request_count,
         successful_refunds,
         failed_refunds,
         total_refunded_amount,
@@ -424,14 +422,13 @@ pub fn create_bundle_result(
     validation_results: &Vec<ValidationResult>,
     created_at: u64,
 ) -> BundleResult {
-    let total_count = bundled_transactions.len() as u32;
+    let total_count = bundled_transactions.len();
     let mut valid_count: u32 = 0;
     let mut invalid_count: u32 = 0;
     let mut total_volume: i128 = 0;
 
     // Count valid/invalid and compute total volume of valid transactions
-    let mut index: u32 = 0;
-    for result in validation_results.iter() {
+    for (index, result) in (0u32..).zip(validation_results.iter()) {
         if result.is_valid {
             valid_count += 1;
             if let Some(bundled_tx) = bundled_transactions.get(index) {
@@ -442,7 +439,6 @@ pub fn create_bundle_result(
         } else {
             invalid_count += 1;
         }
-        index += 1;
     }
 
     let can_bundle = valid_count > 0 && invalid_count == 0;
@@ -461,7 +457,7 @@ pub fn create_bundle_result(
 
 /// Validates a batch of refund requests.
 pub fn validate_refund_batch(env: &Env, refund_requests: &Vec<RefundRequest>) -> Result<(), &'static str> {
-    let count = refund_requests.len() as u32;
+    let count = refund_requests.len();
     
     if count == 0 {
         return Err("Refund batch cannot be empty");
@@ -520,7 +516,7 @@ pub fn compute_monthly_analytics(
 }
 
 /// Helper function to update category spending in the vector
-fn update_category_spending(
+pub(crate) fn update_category_spending(
     env: &Env,
     category_spending: &mut Vec<(Symbol, i128)>,
     category: Symbol,
@@ -547,7 +543,7 @@ fn update_category_spending(
             if i != index {
                 new_vec.push_back(category_spending.get(i).unwrap());
             } else {
-                new_vec.push_back((category, new_amount));
+                new_vec.push_back((category.clone(), new_amount));
             }
         }
         *category_spending = new_vec;
@@ -563,7 +559,10 @@ pub fn update_monthly_analytics_storage(
     analytics: &MonthlySpendingAnalytics,
 ) {
     let key = DataKey::MonthlyAnalytics(analytics.year, analytics.month, analytics.user.clone());
-    env.storage().persistent().set(&key, &analytics);
+    env.storage().persistent().set(&key, analytics);
+    let epoch_key =
+        DataKey::AnalyticsEpoch(analytics.year, analytics.month, analytics.user.clone());
+    env.storage().persistent().set(&epoch_key, &reference::get(env).epoch);
     
     // Update total tracked users if this is a new user
     let mut total_users: u64 = env
@@ -579,7 +578,7 @@ pub fn update_monthly_analytics_storage(
     }
     
     // Update last analytics update timestamp
-    env.storage().instance().set(&DataKey::LastAnalyticsUpdate, &env.ledger().sequence());
+    env.storage().instance().set(&DataKey::LastAnalyticsUpdate, &(env.ledger().sequence() as u64));
 }
 
 /// Computes aggregated analytics for all users and categories
@@ -641,7 +640,7 @@ pub fn compute_user_spending_summary(
     for analytics in monthly_analytics.iter() {
         if &analytics.user == user {
             total_spending = total_spending.checked_add(analytics.total_spending).unwrap_or(i128::MAX);
-            total_transactions = total_transactions.checked_add(analytics.transaction_count).unwrap_or(u32::MAX);
+            total_transactions = total_transactions.saturating_add(analytics.transaction_count);
 
             // Aggregate category spending across months
             for (category, amount) in analytics.category_spending.iter() {
@@ -657,7 +656,7 @@ pub fn compute_user_spending_summary(
         }
     }
 
-    let avg_monthly_spending = if monthly_analytics.len() > 0 {
+    let avg_monthly_spending = if !monthly_analytics.is_empty() {
         total_spending / (monthly_analytics.len() as i128)
     } else {
         0
@@ -0,0 +1,74 @@
+//! # Tax-Deductible Spending
+//!
+//! Categories can be flagged as tax-deductible. Whenever a user's monthly
+//! analytics are recorded, the month's spending in deductible categories is
+//! recorded with them, and a user's annual summary adds those months up for a
+//! tax filing. Changing a flag affects months recorded afterwards; months
+//! already recorded keep their figures until their analytics are updated.
+
+use soroban_sdk::{Address, Env, Symbol, Vec};
+
+use crate::analytics::update_category_spending;
+use crate::types::{AnnualDeductibleSummary, DataKey, MonthlySpendingAnalytics};
+
+/// Flags or unflags a category as tax-deductible.
+pub fn set_deductible(env: &Env, category: &Symbol, deductible: bool) {
+    let key = DataKey::DeductibleCategory(category.clone());
+    if deductible {
+        env.storage().persistent().set(&key, &true);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+/// Returns whether spending in a category is tax-deductible.
+pub fn is_deductible(env: &Env, category: &Symbol) -> bool {
+    env.storage()
+        .persistent()
+        .get(&DataKey::DeductibleCategory(category.clone()))
+        .unwrap_or(false)
+}
+
+/// Records the deductible part of a user's monthly analytics, replacing
+/// whatever was recorded for the month before.
+pub fn record_month(env: &Env, analytics: &MonthlySpendingAnalytics) {
+    let mut deductible = Vec::new(env);
+    for (category, amount) in analytics.category_spending.iter() {
+        if is_deductible(env, &category) {
+            deductible.push_back((category, amount));
+        }
+    }
+    let key = DataKey::MonthlyDeductible(analytics.year, analytics.month, analytics.user.clone());
+    env.storage().persistent().set(&key, &deductible);
+}
+
+/// Adds up a user's deductible spending over the months of `year` that have
+/// recorded analytics.
+pub fn annual_summary(env: &Env, user: &Address, year: u32) -> AnnualDeductibleSummary {
+    let mut summary = AnnualDeductibleSummary {
+        user: user.clone(),
+        year,
+        total_deductible: 0,
+        category_deductible: Vec::new(env),
+        months_recorded: 0,
+    };
+    for month in 1..=12 {
+        let key = DataKey::MonthlyDeductible(year, month, user.clone());
+        let Some(deductible) = env
+            .storage()
+            .persistent()
+            .get::<_, Vec<(Symbol, i128)>>(&key)
+        else {
+            continue;
+        };
+        summary.months_recorded += 1;
+        for (category, amount) in deductible.iter() {
+            summary.total_deductible = summary
+                .total_deductible
+                .checked_add(amount)
+                .unwrap_or(i128::MAX);
+            update_category_spending(env, &mut summary.category_deductible, category, amount);
+        }
+    }
+    summary
+}
@@ -3,9 +3,15 @@
 //! Implements dynamic fee calculation for transactions with configurable fee structures.
 //! Supports percentage-based fees, tiered pricing, and automatic fee deductions.
 
-use soroban_sdk::{Address, Env, Symbol, symbol_short};
+use common::math::{self, Rounding};
+use common::normalize::rescale;
+use common::rounding::fee_bps;
+use soroban_sdk::{Address, Env, Symbol, Vec};
 
-use crate::types::{FeeConfig, FeeTier, FeeCalculationResult, DataKey, FeeDeductionEvent, ValidationError, AnalyticsEvents};
+use crate::types::{
+    FeeConfig, FeeModel, FeeTier, FeeCalculationResult, FeeQuote, DataKey, ValidationError,
+    AnalyticsEvents,
+};
 
 /// Calculates fees for a single transaction based on the current fee configuration.
 /// 
@@ -16,7 +22,7 @@ use crate::types::{FeeConfig, FeeTier, FeeCalculationResult, DataKey, FeeDeducti
 /// 
 /// # Returns
 /// * `FeeCalculationResult` containing the calculated fee and net amount
-pub fn calculate_transaction_fee(env: &Env, amount: i128, fee_config: &FeeConfig) -> FeeCalculationResult {
+pub fn calculate_transaction_fee(_env: &Env, amount: i128, fee_config: &FeeConfig) -> FeeCalculationResult {
     if amount <= 0 {
         return FeeCalculationResult {
             gross_amount: amount,
@@ -26,20 +32,10 @@ pub fn calculate_transaction_fee(env: &Env, amount: i128, fee_config: &FeeConfig
         };
     }
 
-    let fee_amount = match &fee_config.fee_model {
-        crate::types::FeeModel::Flat(flat_fee) => {
-            *flat_fee
-        },
-        crate::types::FeeModel::Percentage(percentage_bps) => {
-            (amount * (*percentage_bps as i128)) / 10000
-        },
-        crate::types::FeeModel::Tiered(tiers) => {
-            calculate_tiered_fee(amount, tiers)
-        },
-    };
+    let fee_amount = model_fee(amount, fee_config);
 
     // Apply min and max fee constraints
-    let constrained_fee = constrain_fee_amount(fee_amount, &fee_config);
+    let constrained_fee = constrain_fee_amount(fee_amount, fee_config);
 
     // Ensure fee doesn't exceed the transaction amount
     let final_fee = if constrained_fee > amount {
@@ -56,20 +52,26 @@ pub fn calculate_transaction_fee(env: &Env, amount: i128, fee_config: &FeeConfig
     }
 }
 
+/// Fee the configured model yields for a positive amount, before min/max
+/// constraints and the cap at the transaction amount
+fn model_fee(amount: i128, fee_config: &FeeConfig) -> i128 {
+    match &fee_config.fee_model {
+        crate::types::FeeModel::Flat(flat_fee) => *flat_fee,
+        crate::types::FeeModel::Percentage(percentage_bps) => {
+            percentage_fee(amount, *percentage_bps)
+        },
+        crate::types::FeeModel::Tiered(tiers) => calculate_tiered_fee(amount, tiers),
+    }
+}
+
 /// Calculates fees based on tiered fee structure
 fn calculate_tiered_fee(amount: i128, tiers: &Vec<FeeTier>) -> i128 {
     if tiers.is_empty() {
         return 0;
     }
 
-    // Sort tiers by threshold in ascending order
-    let mut sorted_tiers = Vec::new(&Env::default());
-    for tier in tiers.iter() {
-        sorted_tiers.push_back(tier.clone());
-    }
-
-    // Find the appropriate tier based on amount
-    let mut applicable_tier = &tiers.get(0).unwrap(); // Default to first tier
+    // Find the appropriate tier based on amount (tiers are in ascending order)
+    let mut applicable_tier = tiers.get(0).unwrap(); // Default to first tier
     
     for tier in tiers.iter() {
         if amount >= tier.threshold {
@@ -83,15 +85,20 @@ fn calculate_tiered_fee(amount: i128, tiers: &Vec<FeeTier>) -> i128 {
     match &applicable_tier.fee_model {
         crate::types::FeeModel::Flat(flat_fee) => *flat_fee,
         crate::types::FeeModel::Percentage(percentage_bps) => {
-            (amount * (*percentage_bps as i128)) / 10000
+            percentage_fee(amount, *percentage_bps)
         },
         crate::types::FeeModel::Tiered(_) => {
             // Nested tiered fees not supported, fall back to percentage
-            (amount * (applicable_tier.default_percentage_bps as i128)) / 10000
+            percentage_fee(amount, applicable_tier.default_percentage_bps)
         },
     }
 }
 
+/// Percentage fee in basis points; fees round up so they are never understated
+fn percentage_fee(amount: i128, percentage_bps: u32) -> i128 {
+    fee_bps(amount, percentage_bps).unwrap_or(amount)
+}
+
 /// Applies min/max constraints to the calculated fee
 fn constrain_fee_amount(calculated_fee: i128, config: &FeeConfig) -> i128 {
     let mut constrained_fee = calculated_fee;
@@ -117,15 +124,15 @@ fn calculate_effective_rate(gross_amount: i128, fee_amount: i128) -> u32 {
         return 0;
     }
     
-    // Calculate as (fee_amount * 10000) / gross_amount to get basis points
-    ((fee_amount * 10000) / gross_amount) as u32
+    // Basis points of the gross amount, rounded down as it is informational only
+    math::ratio_bps(fee_amount, gross_amount).unwrap_or(0)
 }
 
 /// Calculates fees for multiple transactions
-pub fn calculate_batch_fees(env: &Env, amounts: &[i128], fee_config: &FeeConfig) -> Vec<FeeCalculationResult> {
+pub fn calculate_batch_fees(env: &Env, amounts: &Vec<i128>, fee_config: &FeeConfig) -> Vec<FeeCalculationResult> {
     let mut results = Vec::new(env);
     
-    for &amount in amounts {
+    for amount in amounts.iter() {
         let result = calculate_transaction_fee(env, amount, fee_config);
         results.push_back(result);
     }
@@ -137,11 +144,10 @@ pub fn calculate_batch_fees(env: &Env, amounts: &[i128], fee_config: &FeeConfig)
 pub fn validate_fee_config(config: &FeeConfig) -> Result<(), ValidationError> {
     // Validate percentage is not over 100% (10000 basis points)
     match &config.fee_model {
-        crate::types::FeeModel::Percentage(percentage_bps) => {
-            if *percentage_bps > 10000 {  // More than 100%
+        crate::types::FeeModel::Percentage(percentage_bps)
+            if *percentage_bps > 10000 => {  // More than 100%
                 return Err(ValidationError::InvalidPercentage);
-            }
-        },
+            },
         crate::types::FeeModel::Tiered(tiers) => {
             // Validate each tier
             for tier in tiers.iter() {
@@ -150,11 +156,10 @@ pub fn validate_fee_config(config: &FeeConfig) -> Result<(), ValidationError> {
                 }
                 
                 match &tier.fee_model {
-                    crate::types::FeeModel::Percentage(percentage_bps) => {
-                        if *percentage_bps > 10000 {  // More than 100%
+                    crate::types::FeeModel::Percentage(percentage_bps)
+                        if *percentage_bps > 10000 => {  // More than 100%
                             return Err(ValidationError::InvalidPercentage);
-                        }
-                    },
+                        },
                     _ => {} // Other models have different validation
                 }
             }
@@ -207,12 +212,15 @@ pub fn get_current_fee_config(env: &Env) -> Option<FeeConfig> {
     env.storage().instance().get(&DataKey::CurrentFeeConfig)
 }
 
+/// Returns the fee configuration fees are charged under: the stored one, or
+/// the default if none is configured
+pub fn active_fee_config(env: &Env) -> FeeConfig {
+    get_current_fee_config(env).unwrap_or_else(|| default_fee_config(env))
+}
+
 /// Deducts fees from a transaction amount and returns the net amount
 pub fn deduct_fees(env: &Env, gross_amount: i128) -> FeeCalculationResult {
-    let config = get_current_fee_config(env)
-        .unwrap_or(default_fee_config()); // Use default if none configured
-    
-    let result = calculate_transaction_fee(env, gross_amount, &config);
+    let result = calculate_transaction_fee(env, gross_amount, &active_fee_config(env));
     
     // Emit fee deduction event
     AnalyticsEvents::fee_deducted(env, result.gross_amount, result.fee_amount, result.net_amount, result.fee_percentage_bps);
@@ -220,8 +228,28 @@ pub fn deduct_fees(env: &Env, gross_amount: i128) -> FeeCalculationResult {
     result
 }
 
+/// Quotes the fees `deduct_fees` would charge on a payment of `amount` to
+/// `merchant` under the current configuration, without emitting anything.
+///
+/// The fee model does not vary by token or merchant; both are carried in the
+/// quote so it stays tied to the payment it was requested for.
+pub fn quote_payment(env: &Env, amount: i128, token: Address, merchant: Address) -> FeeQuote {
+    let config = active_fee_config(env);
+    let result = calculate_transaction_fee(env, amount, &config);
+
+    FeeQuote {
+        token,
+        merchant,
+        gross_amount: result.gross_amount,
+        model_fee: if amount > 0 { model_fee(amount, &config) } else { 0 },
+        fee_amount: result.fee_amount,
+        net_amount: result.net_amount,
+        fee_percentage_bps: result.fee_percentage_bps,
+    }
+}
+
 /// Creates a default fee configuration (0.1% flat fee)
-fn default_fee_config() -> FeeConfig {
+fn default_fee_config(env: &Env) -> FeeConfig {
     use crate::types::FeeModel;
     
     FeeConfig {
@@ -229,7 +257,7 @@ fn default_fee_config() -> FeeConfig {
         min_fee: Some(1),                   // Minimum 1 stroop
         max_fee: None,                      // No maximum
         enabled: true,
-        description: Some(Symbol::new(&Env::default(), "Default 0.1% fee")),
+        description: Some(Symbol::new(env, "Default")),
     }
 }
 
@@ -248,14 +276,71 @@ pub fn update_fee_config(env: &Env, admin: &Address, new_config: FeeConfig) -> R
     
     validate_fee_config(&new_config)?;
     store_fee_config(env, &new_config)?;
-    
+
     Ok(())
 }
 
+/// Rescales the stored fee configuration's amounts from `from_decimals` to
+/// `to_decimals`, after the reference currency's precision changed.
+///
+/// Fees round up and tier thresholds round down, so neither fees nor tier
+/// eligibility shrink through the restatement.
+pub fn restate_fee_config(
+    env: &Env,
+    from_decimals: u32,
+    to_decimals: u32,
+) -> Result<(), ValidationError> {
+    let Some(mut config) = get_current_fee_config(env) else {
+        return Ok(());
+    };
+
+    config.fee_model = restate_fee_model(&config.fee_model, from_decimals, to_decimals)?;
+    if let Some(min_fee) = config.min_fee {
+        config.min_fee = Some(restate_bound(min_fee, from_decimals, to_decimals)?);
+    }
+    if let Some(max_fee) = config.max_fee {
+        config.max_fee = Some(restate_bound(max_fee, from_decimals, to_decimals)?);
+    }
+
+    store_fee_config(env, &config)
+}
+
+fn restate_fee_model(
+    model: &FeeModel,
+    from_decimals: u32,
+    to_decimals: u32,
+) -> Result<FeeModel, ValidationError> {
+    Ok(match model {
+        FeeModel::Flat(flat_fee) => FeeModel::Flat(
+            rescale(*flat_fee, from_decimals, to_decimals, Rounding::Up)
+                .ok_or(ValidationError::InvalidAmount)?,
+        ),
+        FeeModel::Percentage(bps) => FeeModel::Percentage(*bps),
+        FeeModel::Tiered(tiers) => {
+            let mut restated = Vec::new(tiers.env());
+            for tier in tiers.iter() {
+                restated.push_back(FeeTier {
+                    threshold: rescale(tier.threshold, from_decimals, to_decimals, Rounding::Down)
+                        .ok_or(ValidationError::InvalidAmount)?,
+                    fee_model: restate_fee_model(&tier.fee_model, from_decimals, to_decimals)?,
+                    default_percentage_bps: tier.default_percentage_bps,
+                });
+            }
+            FeeModel::Tiered(restated)
+        }
+    })
+}
+
+fn restate_bound(bound: u64, from_decimals: u32, to_decimals: u32) -> Result<u64, ValidationError> {
+    rescale(bound as i128, from_decimals, to_decimals, Rounding::Up)
+        .and_then(|value| u64::try_from(value).ok())
+        .ok_or(ValidationError::InvalidAmount)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use soroban_sdk::{Env, testutils::Address as _};
+    use soroban_sdk::Env;
     use crate::types::{FeeModel, FeeTier};
 
     #[test]
@@ -295,7 +380,7 @@ mod tests {
     #[test]
     fn test_min_max_constraints() {
         let env = Env::default();
-        let mut config = FeeConfig {
+        let config = FeeConfig {
             fee_model: FeeModel::Percentage(1), // 0.01%
             min_fee: Some(10),
             max_fee: Some(100),
@@ -332,6 +417,25 @@ mod tests {
         assert_eq!(result.net_amount, -100);
     }
 
+    #[test]
+    fn test_percentage_fee_rounds_up() {
+        let env = Env::default();
+        let config = FeeConfig {
+            fee_model: FeeModel::Percentage(30), // 0.3%
+            min_fee: None,
+            max_fee: None,
+            enabled: true,
+            description: None,
+        };
+
+        // 1001 * 0.3% = 3.003, charged as 4 so the fee is never understated
+        let result = calculate_transaction_fee(&env, 1001, &config);
+        assert_eq!(result.fee_amount, 4);
+        assert_eq!(result.net_amount, 997);
+        // Effective rate is informational and rounds down: 4 / 1001 = 39.96 bps
+        assert_eq!(result.fee_percentage_bps, 39);
+    }
+
     #[test]
     fn test_tiered_fee_calculation() {
         let env = Env::default();
@@ -357,9 +461,9 @@ mod tests {
             description: None,
         };
 
-        // Amount 50 should use 1% fee = 0.5, rounded down = 0
+        // Amount 50 should use 1% fee = 0.5, rounded up = 1
         let result = calculate_transaction_fee(&env, 50, &config);
-        assert_eq!(result.fee_amount, 0); // 50 * 1% = 0.5, floor to 0
+        assert_eq!(result.fee_amount, 1); // 50 * 1% = 0.5, fees round up
 
         // Amount 200 should use 0.5% fee = 1
         let result = calculate_transaction_fee(&env, 200, &config);
@@ -0,0 +1,153 @@
+//! # Spending Forecasts
+//!
+//! Budget owners record the spend they expect per category for an upcoming
+//! month. Once the month's actual spending is recorded, forecast-versus-actual
+//! variance can be queried per category, and categories that miss their
+//! forecast by at least the configured threshold raise a `forecast_variance`
+//! event for finance review.
+
+use common::math::{self, Rounding, BPS_DENOMINATOR};
+use soroban_sdk::{Address, Env, Symbol, Vec};
+
+use crate::types::{
+    AnalyticsEvents, CategoryForecast, DataKey, ForecastVariance, MonthlySpendingAnalytics,
+    ValidationError, MAX_FORECAST_CATEGORIES,
+};
+
+/// Validates a forecast: bounded in size, non-negative amounts and each
+/// category listed once.
+pub fn validate_forecasts(forecasts: &Vec<CategoryForecast>) -> Result<(), ValidationError> {
+    if forecasts.len() > MAX_FORECAST_CATEGORIES {
+        return Err(ValidationError::BatchTooLarge);
+    }
+    for (i, forecast) in forecasts.iter().enumerate() {
+        if forecast.amount < 0 {
+            return Err(ValidationError::InvalidAmount);
+        }
+        for other in forecasts.iter().skip(i + 1) {
+            if other.category == forecast.category {
+                return Err(ValidationError::InvalidCategory);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Replaces a user's forecast for a period.
+pub fn store_forecast(
+    env: &Env,
+    user: &Address,
+    year: u32,
+    month: u32,
+    forecasts: &Vec<CategoryForecast>,
+) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Forecast(year, month, user.clone()), forecasts);
+}
+
+/// Returns a user's forecast for a period (empty if none was recorded).
+pub fn get_forecast(env: &Env, user: &Address, year: u32, month: u32) -> Vec<CategoryForecast> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Forecast(year, month, user.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Returns the variance alert threshold in basis points (0 disables alerts).
+pub fn get_variance_alert_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::VarianceAlertBps)
+        .unwrap_or(0)
+}
+
+/// Sets the variance alert threshold in basis points (0 disables alerts).
+pub fn set_variance_alert_bps(env: &Env, bps: u32) {
+    env.storage().instance().set(&DataKey::VarianceAlertBps, &bps);
+}
+
+/// Compares a forecast with actual spending, category by category.
+///
+/// Forecast categories come first in forecast order, followed by categories
+/// that had spending but no forecast.
+pub fn compute_variance(
+    env: &Env,
+    forecasts: &Vec<CategoryForecast>,
+    actuals: &Vec<(Symbol, i128)>,
+) -> Vec<ForecastVariance> {
+    let mut variances = Vec::new(env);
+    for forecast in forecasts.iter() {
+        let actual = actual_for(actuals, &forecast.category);
+        variances.push_back(variance_of(forecast.category, forecast.amount, actual));
+    }
+    for (category, actual) in actuals.iter() {
+        if !forecasts.iter().any(|forecast| forecast.category == category) {
+            variances.push_back(variance_of(category, 0, actual));
+        }
+    }
+    variances
+}
+
+/// Emits a `forecast_variance` event for each category whose actual spend
+/// missed the forecast by at least the alert threshold. Periods without a
+/// forecast are skipped.
+pub fn emit_large_variances(env: &Env, analytics: &MonthlySpendingAnalytics) {
+    let threshold = get_variance_alert_bps(env);
+    if threshold == 0 {
+        return;
+    }
+    let forecasts = get_forecast(env, &analytics.user, analytics.year, analytics.month);
+    if forecasts.is_empty() {
+        return;
+    }
+
+    for variance in compute_variance(env, &forecasts, &analytics.category_spending).iter() {
+        if variance.variance_bps >= threshold {
+            AnalyticsEvents::forecast_variance(
+                env,
+                &analytics.user,
+                analytics.year,
+                analytics.month,
+                &variance,
+            );
+        }
+    }
+}
+
+// Internal helper looking up a category's actual spend
+fn actual_for(actuals: &Vec<(Symbol, i128)>, category: &Symbol) -> i128 {
+    for (spent_category, amount) in actuals.iter() {
+        if spent_category == *category {
+            return amount;
+        }
+    }
+    0
+}
+
+// Internal helper building one category's variance
+fn variance_of(category: Symbol, forecast: i128, actual: i128) -> ForecastVariance {
+    let variance = actual.saturating_sub(forecast);
+    let variance_bps = if variance == 0 {
+        0
+    } else if forecast == 0 {
+        u32::MAX
+    } else {
+        math::saturating_mul_div(
+            variance.saturating_abs(),
+            BPS_DENOMINATOR,
+            forecast,
+            Rounding::Down,
+        )
+        .map(|bps| bps.min(u32::MAX as i128) as u32)
+        .unwrap_or(u32::MAX)
+    };
+
+    ForecastVariance {
+        category,
+        forecast,
+        actual,
+        variance,
+        variance_bps,
+    }
+}
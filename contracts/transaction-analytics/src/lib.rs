@@ -9,6 +9,11 @@
 //! - **Category Breakdown**: Analytics grouped by transaction category
 //! - **Event Emission**: Emit analytics events for off-chain consumption
 //! - **High-Value Alerts**: Detect and flag high-value transactions
+//! - **Auto-Tagging**: Untagged transactions get a category from merchant and amount rules
+//! - **Spending Forecasts**: Forecast-versus-actual variance per category, with alerts
+//! - **Reference Currency**: Analytics recorded before a currency change are flagged
+//! - **Outflow Rate**: Average daily outflow over a trailing window, for runway estimates
+//! - **Tax Deductions**: Annual deductible spending per user from flagged categories
 //!
 //! ## Optimization Strategies
 //!
@@ -23,8 +28,14 @@ mod analytics;
 mod types;
 mod validation;
 mod fees;
+mod tagging;
+mod forecast;
+mod outflow;
+mod deductions;
 
-use soroban_sdk::{contract, contractimpl, panic_with_error, Address, Env, Vec};
+use common::reference::{self, ReferenceCurrency};
+use common::versioning;
+use soroban_sdk::{contract, contractimpl, panic_with_error, Address, Env, Symbol, Vec};
 
 pub use crate::analytics::{
     compute_batch_checksum, compute_batch_metrics, compute_category_metrics,
@@ -50,11 +61,20 @@ pub use crate::validation::{
 };
 pub use crate::fees::{
     calculate_transaction_fee, calculate_batch_fees, validate_fee_config, store_fee_config,
-    get_current_fee_config, deduct_fees, update_fee_config,
+    get_current_fee_config, deduct_fees, update_fee_config, active_fee_config,
 };
 pub use crate::types::{
-    FeeModel, FeeTier, FeeConfig, FeeCalculationResult, FeeDeductionEvent,
+    FeeModel, FeeTier, FeeConfig, FeeCalculationResult, FeeDeductionEvent, FeeQuote,
 };
+pub use crate::tagging::{
+    apply_auto_tags, get_amount_rules, get_merchant_category, resolve_category,
+    store_amount_rules, untagged_category, validate_amount_rules,
+};
+pub use crate::types::{AmountRule, MAX_AMOUNT_RULES, UNTAGGED_CATEGORY};
+pub use crate::forecast::{compute_variance, get_forecast, validate_forecasts};
+pub use crate::types::{CategoryForecast, ForecastVariance, MAX_FORECAST_CATEGORIES};
+pub use crate::types::{OutflowRate, OUTFLOW_WINDOW_DAYS};
+pub use crate::types::AnnualDeductibleSummary;
 
 /// Error codes for the analytics contract.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -88,6 +108,20 @@ pub enum AnalyticsError {
     RefundBatchTooLarge = 13,
     /// Contract already initialized
     AlreadyInitialized = 14,
+    /// Storage was written by a different layout version
+    StorageVersionMismatch = 15,
+    /// Migration source version does not match stored version
+    InvalidMigration = 16,
+    /// Auto-tag rule set is invalid
+    InvalidTagRule = 17,
+    /// Transaction has not been processed by this contract
+    TransactionNotFound = 18,
+    /// Spending forecast is invalid
+    InvalidForecast = 19,
+    /// Actual spending for the period is already recorded
+    ForecastPeriodClosed = 20,
+    /// Reference currency is unchanged, or its decimals are out of range
+    InvalidReferenceCurrency = 21,
 }
 
 impl From<AnalyticsError> for soroban_sdk::Error {
@@ -96,6 +130,9 @@ impl From<AnalyticsError> for soroban_sdk::Error {
     }
 }
 
+/// Storage layout version this code expects.
+pub const STORAGE_VERSION: u32 = 1;
+
 #[contract]
 pub struct TransactionAnalyticsContract;
 
@@ -112,6 +149,7 @@ impl TransactionAnalyticsContract {
         }
 
         env.storage().instance().set(&DataKey::Admin, &admin);
+        versioning::set_version(&env, STORAGE_VERSION);
         env.storage().instance().set(&DataKey::LastBatchId, &0u64);
         env.storage()
             .instance()
@@ -133,6 +171,24 @@ impl TransactionAnalyticsContract {
             .set(&DataKey::RefundedTransactions, &soroban_sdk::Map::<u64, bool>::new(&env));
     }
 
+    /// Migrates storage written by an older version of this contract to the
+    /// current layout (admin only). `from_version` must match the stored
+    /// version.
+    pub fn migrate(env: Env, caller: Address, from_version: u32) {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        // Version 1 only adds the version stamp; older layouts are otherwise
+        // unchanged, so there is nothing to rewrite
+        versioning::migrate(&env, from_version, STORAGE_VERSION, |_, _| {})
+            .unwrap_or_else(|_| panic_with_error!(&env, AnalyticsError::InvalidMigration));
+    }
+
+    /// Returns the storage layout version currently stored.
+    pub fn get_storage_version(env: Env) -> u32 {
+        versioning::stored_version(&env)
+    }
+
     /// Generates batch analytics for multiple transactions.
     ///
     /// This is the main entry point for processing transaction batches.
@@ -159,6 +215,7 @@ impl TransactionAnalyticsContract {
         transactions: Vec<Transaction>,
         high_value_threshold: Option<i128>,
     ) -> BatchMetrics {
+        Self::require_storage_version(&env);
         // Verify authorization
         caller.require_auth();
         Self::require_admin(&env, &caller);
@@ -173,12 +230,20 @@ impl TransactionAnalyticsContract {
             }
         }
 
+        // Fill in untagged categories before anything is aggregated
+        let transactions = apply_auto_tags(&env, &transactions);
+
         for tx in transactions.iter() {
             env.storage()
                 .persistent()
                 .set(&DataKey::KnownTransaction(tx.tx_id), &true);
+            env.storage()
+                .persistent()
+                .set(&DataKey::TransactionCategory(tx.tx_id), &tx.category);
         }
 
+        let tx_count = transactions.len();
+
         // Get next batch ID (single read, single write at the end)
         let batch_id: u64 = env
             .storage()
@@ -228,6 +293,7 @@ impl TransactionAnalyticsContract {
         env.storage()
             .persistent()
             .set(&DataKey::BatchMetrics(batch_id), &metrics);
+        outflow::record_outflow(&env, metrics.total_volume);
 
         // Emit completion event
         AnalyticsEvents::analytics_completed(&env, batch_id, tx_count as u64);
@@ -242,6 +308,7 @@ impl TransactionAnalyticsContract {
     /// * `caller` - The address calling this function (must be admin)
     /// * `logs` - Vector of audit logs to store
     pub fn batch_audit_log(env: Env, caller: Address, logs: Vec<AuditLog>) {
+        Self::require_storage_version(&env);
         // Verify authorization
         caller.require_auth();
         Self::require_admin(&env, &caller);
@@ -251,7 +318,7 @@ impl TransactionAnalyticsContract {
             panic_with_error!(&env, AnalyticsError::EmptyBatch);
         }
         
-        if logs.len() > MAX_BATCH_SIZE as usize {
+        if logs.len() > MAX_BATCH_SIZE {
             panic_with_error!(&env, AnalyticsError::BatchTooLarge);
         }
 
@@ -331,7 +398,7 @@ impl TransactionAnalyticsContract {
     ///
     /// Useful for simulating analytics before committing.
     pub fn simulate_batch(env: Env, transactions: Vec<Transaction>) -> BatchMetrics {
-        if let Err(_) = validate_batch(&transactions) {
+        if validate_batch(&transactions).is_err() {
             panic_with_error!(&env, AnalyticsError::InvalidBatch);
         }
 
@@ -345,6 +412,7 @@ impl TransactionAnalyticsContract {
         caller: Address,
         updates: Vec<TransactionStatusUpdate>,
     ) -> BatchStatusUpdateResult {
+        Self::require_storage_version(&env);
         caller.require_auth();
         Self::require_admin(&env, &caller);
 
@@ -401,7 +469,7 @@ impl TransactionAnalyticsContract {
         }
 
         BatchStatusUpdateResult {
-            total_requests: count,
+            total_requests: updates.len(),
             successful,
             failed,
             results,
@@ -413,6 +481,7 @@ impl TransactionAnalyticsContract {
         user: Address,
         ratings: Vec<RatingInput>,
     ) -> Vec<RatingResult> {
+        Self::require_storage_version(&env);
         user.require_auth();
 
         // Use new validation layer
@@ -476,6 +545,7 @@ impl TransactionAnalyticsContract {
 
     /// Updates the admin address.
     pub fn set_admin(env: Env, current_admin: Address, new_admin: Address) {
+        Self::require_storage_version(&env);
         current_admin.require_auth();
         Self::require_admin(&env, &current_admin);
 
@@ -512,6 +582,7 @@ impl TransactionAnalyticsContract {
         caller: Address,
         bundled_transactions: Vec<BundledTransaction>,
     ) -> BundleResult {
+        Self::require_storage_version(&env);
         // Verify authorization
         caller.require_auth();
         Self::require_admin(&env, &caller);
@@ -525,6 +596,8 @@ impl TransactionAnalyticsContract {
             }
         }
 
+        let tx_count = bundled_transactions.len();
+
         // Get next bundle ID
         let bundle_id: u64 = env
             .storage()
@@ -633,6 +706,7 @@ impl TransactionAnalyticsContract {
         refund_requests: Vec<RefundRequest>,
         transaction_lookup: soroban_sdk::Map<u64, Transaction>,
     ) -> RefundBatchMetrics {
+        Self::require_storage_version(&env);
         // Verify authorization
         caller.require_auth();
         Self::require_admin(&env, &caller);
@@ -646,6 +720,8 @@ impl TransactionAnalyticsContract {
             }
         }
 
+        let request_count = refund_requests.len();
+
         // Get next refund batch ID
         let refund_batch_id: u64 = env
             .storage()
@@ -791,7 +867,7 @@ impl TransactionAnalyticsContract {
     /// * `transactions` - Vector of transactions to analyze
     /// * `year` - The year to analyze
     /// * `month` - The month to analyze
-    pub fn update_monthly_spending_analytics(
+    pub fn update_monthly_analytics(
         env: Env,
         caller: Address,
         user: Address,
@@ -799,6 +875,7 @@ impl TransactionAnalyticsContract {
         year: u32,
         month: u32,
     ) -> MonthlySpendingAnalytics {
+        Self::require_storage_version(&env);
         caller.require_auth();
         Self::require_admin(&env, &caller);
 
@@ -831,6 +908,8 @@ impl TransactionAnalyticsContract {
 
         // Update storage
         update_monthly_analytics_storage(&env, &analytics);
+        deductions::record_month(&env, &analytics);
+        forecast::emit_large_variances(&env, &analytics);
 
         // Emit analytics update event
         AnalyticsEvents::analytics_updated(&env, &user, year, month, &analytics);
@@ -894,6 +973,7 @@ impl TransactionAnalyticsContract {
     /// * `admin` - The admin address (must be authorized)
     /// * `new_config` - The new fee configuration
     pub fn update_fee_config(env: Env, admin: Address, new_config: FeeConfig) {
+        Self::require_storage_version(&env);
         admin.require_auth();
         Self::require_admin(&env, &admin);
         
@@ -930,19 +1010,22 @@ impl TransactionAnalyticsContract {
     /// # Returns
     /// * `FeeCalculationResult` - The fee calculation result
     pub fn calculate_transaction_fee(env: Env, amount: i128) -> FeeCalculationResult {
-        let config = get_current_fee_config(&env)
-            .unwrap_or_else(|| {
-                // Return a default fee configuration if none is set
-                FeeConfig {
-                    fee_model: FeeModel::Percentage(10), // 0.1% default
-                    min_fee: Some(1),
-                    max_fee: None,
-                    enabled: true,
-                    description: Some(Symbol::new(&env, "Default")),
-                }
-            });
-        
-        calculate_transaction_fee(&env, amount, &config)
+        calculate_transaction_fee(&env, amount, &active_fee_config(&env))
+    }
+
+    /// Quotes the fees on a payment exactly as they would be deducted at
+    /// settlement, so the fee can be shown before the user signs.
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `amount` - The payment amount
+    /// * `token` - Token the payment is made in
+    /// * `merchant` - Merchant being paid
+    ///
+    /// # Returns
+    /// * `FeeQuote` - The fee breakdown
+    pub fn quote_payment(env: Env, amount: i128, token: Address, merchant: Address) -> FeeQuote {
+        fees::quote_payment(&env, amount, token, merchant)
     }
 
     /// Calculates fees for a batch of transactions.
@@ -954,19 +1037,247 @@ impl TransactionAnalyticsContract {
     /// # Returns
     /// * `Vec<FeeCalculationResult>` - Vector of fee calculation results
     pub fn calculate_batch_fees(env: Env, amounts: Vec<i128>) -> Vec<FeeCalculationResult> {
-        let config = get_current_fee_config(&env)
-            .unwrap_or_else(|| {
-                // Return a default fee configuration if none is set
-                FeeConfig {
-                    fee_model: FeeModel::Percentage(10), // 0.1% default
-                    min_fee: Some(1),
-                    max_fee: None,
-                    enabled: true,
-                    description: Some(Symbol::new(&env, "Default")),
-                }
-            });
-        
-        calculate_batch_fees(&env, &amounts.into_iter().collect::<Vec<_>>(), &config)
+        calculate_batch_fees(&env, &amounts, &active_fee_config(&env))
+    }
+
+    /// Sets the default category for payments to a merchant (admin only).
+    ///
+    /// Passing `None` removes the merchant's rule.
+    pub fn set_merchant_category(
+        env: Env,
+        admin: Address,
+        merchant: Address,
+        category: Option<Symbol>,
+    ) {
+        Self::require_storage_version(&env);
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        tagging::set_merchant_category(&env, &merchant, category);
+    }
+
+    /// Gets a merchant's default category.
+    pub fn get_merchant_category(env: Env, merchant: Address) -> Option<Symbol> {
+        get_merchant_category(&env, &merchant)
+    }
+
+    /// Replaces the amount-range auto-tag rules (admin only).
+    ///
+    /// Rules are checked in order and the first matching range applies.
+    pub fn set_amount_rules(env: Env, admin: Address, rules: Vec<AmountRule>) {
+        Self::require_storage_version(&env);
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        if store_amount_rules(&env, &rules).is_err() {
+            panic_with_error!(&env, AnalyticsError::InvalidTagRule);
+        }
+    }
+
+    /// Gets the amount-range auto-tag rules.
+    pub fn get_amount_rules(env: Env) -> Vec<AmountRule> {
+        get_amount_rules(&env)
+    }
+
+    /// Grants or revokes the operator role for category overrides (admin only).
+    pub fn set_operator(env: Env, admin: Address, operator: Address, enabled: bool) {
+        Self::require_storage_version(&env);
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        let key = DataKey::Operator(operator);
+        if enabled {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Checks whether an address is an operator.
+    pub fn is_operator(env: Env, operator: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Operator(operator))
+            .unwrap_or(false)
+    }
+
+    /// Overrides the recorded category of a processed transaction (admin or
+    /// operator only).
+    ///
+    /// Already emitted batch analytics are not recomputed; the override applies
+    /// to the stored category and is announced with an event.
+    pub fn override_category(env: Env, caller: Address, tx_id: u64, category: Symbol) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        if !Self::is_operator(env.clone(), caller.clone()) {
+            Self::require_admin(&env, &caller);
+        }
+
+        let previous = Self::get_transaction_category(env.clone(), tx_id)
+            .unwrap_or_else(|| panic_with_error!(&env, AnalyticsError::TransactionNotFound));
+        env.storage()
+            .persistent()
+            .set(&DataKey::TransactionCategory(tx_id), &category);
+
+        AnalyticsEvents::category_overridden(&env, &caller, tx_id, &previous, &category);
+    }
+
+    /// Gets the category recorded for a processed transaction.
+    pub fn get_transaction_category(env: Env, tx_id: u64) -> Option<Symbol> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::TransactionCategory(tx_id))
+    }
+
+    /// Records a user's expected spend per category for an upcoming month
+    /// (the user or admin only).
+    ///
+    /// Replaces any earlier forecast for the month. Forecasts are closed once
+    /// the month's actual spending has been recorded.
+    pub fn record_forecast(
+        env: Env,
+        caller: Address,
+        user: Address,
+        year: u32,
+        month: u32,
+        forecasts: Vec<CategoryForecast>,
+    ) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        if caller != user {
+            Self::require_admin(&env, &caller);
+        }
+
+        if validate_year_month(year, month).is_err() || validate_forecasts(&forecasts).is_err() {
+            panic_with_error!(&env, AnalyticsError::InvalidForecast);
+        }
+        if Self::get_monthly_analytics(env.clone(), user.clone(), year, month).is_some() {
+            panic_with_error!(&env, AnalyticsError::ForecastPeriodClosed);
+        }
+
+        forecast::store_forecast(&env, &user, year, month, &forecasts);
+    }
+
+    /// Gets a user's spending forecast for a month (empty if none was recorded).
+    pub fn get_forecast(env: Env, user: Address, year: u32, month: u32) -> Vec<CategoryForecast> {
+        get_forecast(&env, &user, year, month)
+    }
+
+    /// Returns forecast-versus-actual variance per category for a month.
+    ///
+    /// Categories without recorded spending count as zero actual spend, so
+    /// the variance of a month still in progress shows the unspent forecast.
+    pub fn get_forecast_variance(
+        env: Env,
+        user: Address,
+        year: u32,
+        month: u32,
+    ) -> Vec<ForecastVariance> {
+        let forecasts = get_forecast(&env, &user, year, month);
+        let actuals = Self::get_monthly_analytics(env.clone(), user, year, month)
+            .map(|analytics| analytics.category_spending)
+            .unwrap_or(Vec::new(&env));
+        compute_variance(&env, &forecasts, &actuals)
+    }
+
+    /// Sets the variance, in basis points of the forecast, at which recorded
+    /// actuals raise a `forecast_variance` event (admin only). 0 disables the
+    /// alerts.
+    pub fn set_variance_alert_bps(env: Env, admin: Address, bps: u32) {
+        Self::require_storage_version(&env);
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        forecast::set_variance_alert_bps(&env, bps);
+    }
+
+    /// Gets the variance alert threshold in basis points (0 when disabled).
+    pub fn get_variance_alert_bps(env: Env) -> u32 {
+        forecast::get_variance_alert_bps(&env)
+    }
+
+    /// Gets the current outflow rate: the average daily volume of processed
+    /// batches over the last `OUTFLOW_WINDOW_DAYS` days.
+    pub fn get_outflow_rate(env: Env) -> OutflowRate {
+        outflow::outflow_rate(&env)
+    }
+
+    /// Flags or unflags a category as tax-deductible (admin only).
+    ///
+    /// Applies to monthly analytics recorded from now on; months already
+    /// recorded keep their deductible figures until they are updated.
+    pub fn set_category_deductible(env: Env, admin: Address, category: Symbol, deductible: bool) {
+        Self::require_storage_version(&env);
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        deductions::set_deductible(&env, &category, deductible);
+        AnalyticsEvents::deductible_set(&env, &category, deductible);
+    }
+
+    /// Returns whether spending in a category is tax-deductible.
+    pub fn is_category_deductible(env: Env, category: Symbol) -> bool {
+        deductions::is_deductible(&env, &category)
+    }
+
+    /// Returns a user's deductible spending for a calendar year, totalled and
+    /// per category, from the monthly analytics recorded for that year.
+    pub fn get_annual_deductible_summary(
+        env: Env,
+        user: Address,
+        year: u32,
+    ) -> AnnualDeductibleSummary {
+        deductions::annual_summary(&env, &user, year)
+    }
+
+    /// Switches the reference currency amounts are expressed in (admin only,
+    /// which is the protocol's governance for this contract).
+    ///
+    /// The change starts a new reference epoch and publishes a `restated`
+    /// event. Monthly analytics recorded before it are reported as pre-change,
+    /// and the fee configuration is rescaled to the new decimals.
+    pub fn set_reference_currency(env: Env, admin: Address, code: Symbol, decimals: u32) {
+        Self::require_storage_version(&env);
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        let previous = reference::set(&env, code, decimals)
+            .unwrap_or_else(|_| panic_with_error!(&env, AnalyticsError::InvalidReferenceCurrency));
+        if previous.decimals != decimals {
+            fees::restate_fee_config(&env, previous.decimals, decimals)
+                .unwrap_or_else(|_| panic_with_error!(&env, AnalyticsError::InvalidAmount));
+        }
+    }
+
+    /// Gets the reference currency in effect.
+    pub fn get_reference_currency(env: Env) -> ReferenceCurrency {
+        reference::get(&env)
+    }
+
+    /// Returns true if a user's monthly analytics were recorded under an
+    /// earlier reference currency than the one in effect. Analytics recorded
+    /// before epochs were tracked count as epoch 0.
+    pub fn is_analytics_pre_change(env: Env, user: Address, year: u32, month: u32) -> bool {
+        if !env
+            .storage()
+            .persistent()
+            .has(&DataKey::MonthlyAnalytics(year, month, user.clone()))
+        {
+            return false;
+        }
+        let epoch: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::AnalyticsEpoch(year, month, user))
+            .unwrap_or(0);
+        reference::is_pre_change(&env, epoch)
+    }
+
+    // Internal helper refusing to run against storage written by a different
+    // layout version
+    fn require_storage_version(env: &Env) {
+        versioning::require_version(env, STORAGE_VERSION)
+            .unwrap_or_else(|_| panic_with_error!(env, AnalyticsError::StorageVersionMismatch));
     }
 
     // Internal helper to verify admin
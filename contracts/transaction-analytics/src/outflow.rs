@@ -0,0 +1,74 @@
+//! # Outflow Rate
+//!
+//! Keeps the volume of processed batches per day so the current outflow rate
+//! can be read on-chain, e.g. for a treasury runway estimate. The rate is the
+//! average daily outflow over the last `OUTFLOW_WINDOW_DAYS` days, or over
+//! the days since outflow was first recorded if that is shorter.
+//!
+//! Only one-off payments contribute: the protocol has no payment streams,
+//! so there is no continuous outflow to add.
+
+use soroban_sdk::Env;
+
+use crate::types::{DataKey, OutflowRate, OUTFLOW_WINDOW_DAYS};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Adds `volume` to today's outflow.
+pub fn record_outflow(env: &Env, volume: i128) {
+    if volume <= 0 {
+        return;
+    }
+    let today = env.ledger().timestamp() / SECONDS_PER_DAY;
+    if !env.storage().instance().has(&DataKey::FirstOutflowDay) {
+        env.storage()
+            .instance()
+            .set(&DataKey::FirstOutflowDay, &today);
+    }
+
+    let key = DataKey::DailyOutflow(today);
+    let total: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+    env.storage()
+        .persistent()
+        .set(&key, &total.saturating_add(volume));
+}
+
+/// Computes the average daily outflow over the trailing window, today
+/// included.
+pub fn outflow_rate(env: &Env) -> OutflowRate {
+    let now = env.ledger().timestamp();
+    let today = now / SECONDS_PER_DAY;
+    let Some(first_day) = env
+        .storage()
+        .instance()
+        .get::<_, u64>(&DataKey::FirstOutflowDay)
+    else {
+        return OutflowRate {
+            per_day: 0,
+            window_total: 0,
+            window_days: 0,
+            as_of: now,
+        };
+    };
+
+    let window_days = today
+        .saturating_sub(first_day)
+        .saturating_add(1)
+        .min(OUTFLOW_WINDOW_DAYS as u64);
+    let mut window_total: i128 = 0;
+    for day in (today + 1 - window_days)..=today {
+        let volume: i128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::DailyOutflow(day))
+            .unwrap_or(0);
+        window_total = window_total.saturating_add(volume);
+    }
+
+    OutflowRate {
+        per_day: window_total / window_days as i128,
+        window_total,
+        window_days: window_days as u32,
+        as_of: now,
+    }
+}
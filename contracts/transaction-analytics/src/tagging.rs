@@ -0,0 +1,98 @@
+//! # Auto-Tagging
+//!
+//! Fills in the category of transactions submitted as untagged, so category
+//! analytics are populated without tagging every transaction by hand. A
+//! merchant's default category wins over amount-range rules; amount rules are
+//! checked in order and the first match applies. Transactions no rule matches
+//! stay untagged.
+
+use soroban_sdk::{Address, Env, Symbol, Vec};
+
+use crate::types::{
+    AmountRule, DataKey, Transaction, ValidationError, MAX_AMOUNT_RULES, UNTAGGED_CATEGORY,
+};
+
+/// Returns the category used for transactions that should be auto-tagged.
+pub fn untagged_category(env: &Env) -> Symbol {
+    Symbol::new(env, UNTAGGED_CATEGORY)
+}
+
+/// Returns the category a transaction should be recorded under.
+pub fn resolve_category(env: &Env, tx: &Transaction) -> Symbol {
+    let untagged = untagged_category(env);
+    if tx.category != untagged {
+        return tx.category.clone();
+    }
+
+    if let Some(category) = get_merchant_category(env, &tx.to) {
+        return category;
+    }
+
+    for rule in get_amount_rules(env).iter() {
+        if tx.amount >= rule.min_amount && tx.amount <= rule.max_amount {
+            return rule.category;
+        }
+    }
+
+    untagged
+}
+
+/// Returns copies of `transactions` with untagged categories resolved.
+///
+/// Emits an auto-tag event for each transaction whose category was filled in.
+pub fn apply_auto_tags(env: &Env, transactions: &Vec<Transaction>) -> Vec<Transaction> {
+    let mut tagged = Vec::new(env);
+    for mut tx in transactions.iter() {
+        let category = resolve_category(env, &tx);
+        if category != tx.category {
+            crate::types::AnalyticsEvents::transaction_tagged(env, tx.tx_id, &category);
+            tx.category = category;
+        }
+        tagged.push_back(tx);
+    }
+    tagged
+}
+
+/// Sets (or with `None` clears) a merchant's default category.
+pub fn set_merchant_category(env: &Env, merchant: &Address, category: Option<Symbol>) {
+    let key = DataKey::MerchantCategory(merchant.clone());
+    match category {
+        Some(category) => env.storage().persistent().set(&key, &category),
+        None => env.storage().persistent().remove(&key),
+    }
+}
+
+/// Returns a merchant's default category, if one is set.
+pub fn get_merchant_category(env: &Env, merchant: &Address) -> Option<Symbol> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::MerchantCategory(merchant.clone()))
+}
+
+/// Validates an amount rule set.
+pub fn validate_amount_rules(rules: &Vec<AmountRule>) -> Result<(), ValidationError> {
+    if rules.len() > MAX_AMOUNT_RULES {
+        return Err(ValidationError::BatchTooLarge);
+    }
+    for rule in rules.iter() {
+        if rule.min_amount > rule.max_amount {
+            return Err(ValidationError::InvalidAmount);
+        }
+    }
+    Ok(())
+}
+
+/// Replaces the amount rule set after validating it.
+pub fn store_amount_rules(env: &Env, rules: &Vec<AmountRule>) -> Result<(), ValidationError> {
+    validate_amount_rules(rules)?;
+    env.storage().instance().set(&DataKey::AmountRules, rules);
+    Ok(())
+}
+
+/// Returns the amount rules, in the order they are checked.
+pub fn get_amount_rules(env: &Env) -> Vec<AmountRule> {
+    env.storage()
+        .instance()
+        .get(&DataKey::AmountRules)
+        .unwrap_or(Vec::new(env))
+}
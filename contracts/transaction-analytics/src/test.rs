@@ -3,13 +3,14 @@
 #![cfg(test)]
 
 use crate::{
-    BundleResult, BundledTransaction, RefundRequest, RefundStatus, Transaction, 
-    TransactionAnalyticsContract, TransactionAnalyticsContractClient, ValidationResult,
+    AmountRule, BundledTransaction, CategoryForecast, FeeConfig, FeeModel, RefundRequest,
+    Transaction,
+    TransactionAnalyticsContract, TransactionAnalyticsContractClient, OUTFLOW_WINDOW_DAYS,
   TransactionStatus, TransactionStatusUpdate,
 };
 use soroban_sdk::{
-    testutils::{Address as _, Events},
-    Address, Env, Symbol, Vec, Map,
+    testutils::{Address as _, Events, Ledger},
+    Address, Env, Symbol, TryFromVal, Vec, Map,
 };
 
 /// Creates a test environment with the contract deployed and initialized.
@@ -502,6 +503,112 @@ fn test_same_category_aggregation() {
     assert_eq!(metrics.total_volume, 600);
 }
 
+// ============================================================================
+// Auto-Tagging Tests
+// ============================================================================
+
+#[test]
+fn test_untagged_transactions_auto_tagged() {
+    let (env, admin, client) = setup_test_env();
+
+    let merchant = Address::generate(&env);
+    client.set_merchant_category(&admin, &merchant, &Some(Symbol::new(&env, "travel")));
+    let mut rules = Vec::new(&env);
+    rules.push_back(AmountRule {
+        min_amount: 0,
+        max_amount: 999,
+        category: Symbol::new(&env, "petty"),
+    });
+    rules.push_back(AmountRule {
+        min_amount: 0,
+        max_amount: i128::MAX,
+        category: Symbol::new(&env, "large"),
+    });
+    client.set_amount_rules(&admin, &rules);
+
+    let mut transactions: Vec<Transaction> = Vec::new(&env);
+    // Merchant rule wins over amount rules
+    transactions.push_back(create_transaction_with_addresses(
+        &env,
+        1,
+        Address::generate(&env),
+        merchant.clone(),
+        500,
+        "untagged",
+    ));
+    // First matching amount rule applies
+    transactions.push_back(create_transaction(&env, 2, 500, "untagged"));
+    transactions.push_back(create_transaction(&env, 3, 5_000, "untagged"));
+    // Explicit categories are left alone
+    transactions.push_back(create_transaction(&env, 4, 500, "budget"));
+
+    client.process_batch(&admin, &transactions, &None);
+
+    assert_eq!(client.get_transaction_category(&1), Some(Symbol::new(&env, "travel")));
+    assert_eq!(client.get_transaction_category(&2), Some(Symbol::new(&env, "petty")));
+    assert_eq!(client.get_transaction_category(&3), Some(Symbol::new(&env, "large")));
+    assert_eq!(client.get_transaction_category(&4), Some(Symbol::new(&env, "budget")));
+}
+
+#[test]
+fn test_unmatched_transactions_stay_untagged() {
+    let (env, admin, client) = setup_test_env();
+
+    let mut transactions: Vec<Transaction> = Vec::new(&env);
+    transactions.push_back(create_transaction(&env, 1, 500, "untagged"));
+    client.process_batch(&admin, &transactions, &None);
+
+    assert_eq!(client.get_transaction_category(&1), Some(Symbol::new(&env, "untagged")));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #17)")]
+fn test_invalid_amount_rule_rejected() {
+    let (env, admin, client) = setup_test_env();
+
+    let mut rules = Vec::new(&env);
+    rules.push_back(AmountRule {
+        min_amount: 1_000,
+        max_amount: 10,
+        category: Symbol::new(&env, "petty"),
+    });
+    client.set_amount_rules(&admin, &rules);
+}
+
+#[test]
+fn test_operator_overrides_category() {
+    let (env, admin, client) = setup_test_env();
+
+    let operator = Address::generate(&env);
+    client.set_operator(&admin, &operator, &true);
+
+    let mut transactions: Vec<Transaction> = Vec::new(&env);
+    transactions.push_back(create_transaction(&env, 1, 500, "untagged"));
+    client.process_batch(&admin, &transactions, &None);
+
+    client.override_category(&operator, &1, &Symbol::new(&env, "meals"));
+    assert_eq!(client.get_transaction_category(&1), Some(Symbol::new(&env, "meals")));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_override_category_requires_operator() {
+    let (env, admin, client) = setup_test_env();
+
+    let mut transactions: Vec<Transaction> = Vec::new(&env);
+    transactions.push_back(create_transaction(&env, 1, 500, "untagged"));
+    client.process_batch(&admin, &transactions, &None);
+
+    client.override_category(&Address::generate(&env), &1, &Symbol::new(&env, "meals"));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #18)")]
+fn test_override_unknown_transaction() {
+    let (env, admin, client) = setup_test_env();
+    client.override_category(&admin, &42, &Symbol::new(&env, "meals"));
+}
+
 // ============================================================================
 // Audit Log Tests
 // ============================================================================
@@ -597,13 +704,13 @@ fn test_bundle_transactions_success() {
     assert_eq!(result.total_count, 3);
     assert_eq!(result.valid_count, 3);
     assert_eq!(result.invalid_count, 0);
-    assert_eq!(result.can_bundle, true);
+    assert!(result.can_bundle);
     assert_eq!(result.total_volume, 6000);
     assert_eq!(result.validation_results.len(), 3);
 
     // All transactions should be valid
     for result_item in result.validation_results.iter() {
-        assert_eq!(result_item.is_valid, true);
+        assert!(result_item.is_valid);
     }
 }
 
@@ -631,22 +738,22 @@ fn test_bundle_transactions_with_partial_failures() {
     assert_eq!(result.total_count, 3);
     assert_eq!(result.valid_count, 2);
     assert_eq!(result.invalid_count, 1);
-    assert_eq!(result.can_bundle, false); // Not all valid
+    assert!(!result.can_bundle); // Not all valid
     assert_eq!(result.total_volume, 4000); // Only valid transactions
     assert_eq!(result.validation_results.len(), 3);
 
     // Check validation results
     let result_1 = result.validation_results.get(0).unwrap();
     assert_eq!(result_1.tx_id, 1);
-    assert_eq!(result_1.is_valid, true);
+    assert!(result_1.is_valid);
 
     let result_2 = result.validation_results.get(1).unwrap();
     assert_eq!(result_2.tx_id, 2);
-    assert_eq!(result_2.is_valid, false);
+    assert!(!result_2.is_valid);
 
     let result_3 = result.validation_results.get(2).unwrap();
     assert_eq!(result_3.tx_id, 3);
-    assert_eq!(result_3.is_valid, true);
+    assert!(result_3.is_valid);
 }
 
 #[test]
@@ -664,12 +771,12 @@ fn test_bundle_transactions_with_negative_amount() {
 
     assert_eq!(result.valid_count, 2);
     assert_eq!(result.invalid_count, 1);
-    assert_eq!(result.can_bundle, false);
+    assert!(!result.can_bundle);
 
     // Check that the invalid transaction has the correct error
     let invalid_result = result.validation_results.get(1).unwrap();
     assert_eq!(invalid_result.tx_id, 2);
-    assert_eq!(invalid_result.is_valid, false);
+    assert!(!invalid_result.is_valid);
 }
 
 #[test]
@@ -765,7 +872,7 @@ fn test_audit_log_events_emitted() {
 
     let events = env.events().all();
     // At least one audit log event should be emitted
-    assert!(events.len() >= 1);
+    assert!(!events.is_empty());
 }
 
 #[test]
@@ -827,7 +934,7 @@ fn test_bundle_large_number_of_transactions() {
     assert_eq!(result.total_count, 50);
     assert_eq!(result.valid_count, 50);
     assert_eq!(result.invalid_count, 0);
-    assert_eq!(result.can_bundle, true);
+    assert!(result.can_bundle);
     // Sum of 100 + 200 + ... + 5000 = 100 * (1 + 2 + ... + 50) = 100 * 1275 = 127500
     assert_eq!(result.total_volume, 127500);
 }
@@ -845,7 +952,7 @@ fn test_bundle_with_memo() {
     let result = client.bundle_transactions(&admin, &bundled_txs);
 
     assert_eq!(result.valid_count, 2);
-    assert_eq!(result.can_bundle, true);
+    assert!(result.can_bundle);
 }
 
 #[test]
@@ -876,7 +983,7 @@ fn test_bundle_all_transactions_invalid() {
 
     assert_eq!(result.valid_count, 0);
     assert_eq!(result.invalid_count, 2);
-    assert_eq!(result.can_bundle, false);
+    assert!(!result.can_bundle);
     assert_eq!(result.total_volume, 0);
 }
 
@@ -892,7 +999,7 @@ fn test_bundle_zero_amount_transactions() {
 
     // Zero amount transactions are allowed
     assert_eq!(result.valid_count, 2);
-    assert_eq!(result.can_bundle, true);
+    assert!(result.can_bundle);
     assert_eq!(result.total_volume, 1000);
 }
 
@@ -1131,7 +1238,7 @@ fn test_unauthorized_refund_batch() {
 // ============================================================================
 
 #[test]
-fn test_update_monthly_spending_analytics() {
+fn test_update_monthly_analytics() {
     let (env, admin, client) = setup_test_env();
     
     let user = Address::generate(&env);
@@ -1146,8 +1253,8 @@ fn test_update_monthly_spending_analytics() {
         &env, 3, user.clone(), Address::generate(&env), 300, "food"
     ));
     
-    let analytics = client.update_monthly_spending_analytics(
-        &admin, &user, &transactions, 2023, 10
+    let analytics = client.update_monthly_analytics(
+        &admin, &user, &transactions, &2023, &10
     );
     
     assert_eq!(analytics.year, 2023);
@@ -1157,7 +1264,7 @@ fn test_update_monthly_spending_analytics() {
     assert_eq!(analytics.transaction_count, 3);
     
     // Verify we can retrieve the analytics
-    let retrieved = client.get_monthly_analytics(&user, 2023, 10).unwrap();
+    let retrieved = client.get_monthly_analytics(&user, &2023, &10).unwrap();
     assert_eq!(retrieved.total_spending, analytics.total_spending);
     assert_eq!(retrieved.transaction_count, analytics.transaction_count);
 }
@@ -1167,7 +1274,7 @@ fn test_get_monthly_analytics_nonexistent() {
     let (env, _admin, client) = setup_test_env();
     
     let user = Address::generate(&env);
-    let result = client.get_monthly_analytics(&user, 2023, 10);
+    let result = client.get_monthly_analytics(&user, &2023, &10);
     
     assert!(result.is_none());
 }
@@ -1202,8 +1309,8 @@ fn test_get_total_tracked_users() {
     ));
     
     // Process analytics for two different users
-    client.update_monthly_spending_analytics(&admin, &user1, &transactions1, 2023, 10);
-    client.update_monthly_spending_analytics(&admin, &user2, &transactions2, 2023, 10);
+    client.update_monthly_analytics(&admin, &user1, &transactions1, &2023, &10);
+    client.update_monthly_analytics(&admin, &user2, &transactions2, &2023, &10);
     
     assert_eq!(client.get_total_tracked_users(), 2);
 }
@@ -1213,6 +1320,7 @@ fn test_get_last_analytics_update() {
     let (env, admin, client) = setup_test_env();
     
     let initial_update = client.get_last_analytics_update();
+    env.ledger().with_mut(|li| li.sequence_number = 100);
     
     let user = Address::generate(&env);
     let mut transactions: Vec<Transaction> = Vec::new(&env);
@@ -1220,7 +1328,7 @@ fn test_get_last_analytics_update() {
         &env, 1, user.clone(), Address::generate(&env), 100, "food"
     ));
     
-    client.update_monthly_spending_analytics(&admin, &user, &transactions, 2023, 10);
+    client.update_monthly_analytics(&admin, &user, &transactions, &2023, &10);
     
     let final_update = client.get_last_analytics_update();
     
@@ -1236,7 +1344,7 @@ fn test_unauthorized_update_monthly_analytics() {
     let user = Address::generate(&env);
     let transactions: Vec<Transaction> = Vec::new(&env);
     
-    client.update_monthly_spending_analytics(&unauthorized_user, &user, &transactions, 2023, 10);
+    client.update_monthly_analytics(&unauthorized_user, &user, &transactions, &2023, &10);
 }
 
 #[test]
@@ -1265,11 +1373,11 @@ fn test_monthly_analytics_with_different_users() {
     ));
     
     // Update analytics for both users
-    let analytics1 = client.update_monthly_spending_analytics(
-        &admin, &user1, &transactions1, 2023, 10
+    let analytics1 = client.update_monthly_analytics(
+        &admin, &user1, &transactions1, &2023, &10
     );
-    let analytics2 = client.update_monthly_spending_analytics(
-        &admin, &user2, &transactions2, 2023, 10
+    let analytics2 = client.update_monthly_analytics(
+        &admin, &user2, &transactions2, &2023, &10
     );
     
     assert_eq!(analytics1.total_spending, 300);  // 100 + 200
@@ -1278,8 +1386,8 @@ fn test_monthly_analytics_with_different_users() {
     assert_eq!(analytics2.transaction_count, 2);
     
     // Verify retrieval works correctly for each user
-    let retrieved1 = client.get_monthly_analytics(&user1, 2023, 10).unwrap();
-    let retrieved2 = client.get_monthly_analytics(&user2, 2023, 10).unwrap();
+    let retrieved1 = client.get_monthly_analytics(&user1, &2023, &10).unwrap();
+    let retrieved2 = client.get_monthly_analytics(&user2, &2023, &10).unwrap();
     
     assert_eq!(retrieved1.total_spending, analytics1.total_spending);
     assert_eq!(retrieved2.total_spending, analytics2.total_spending);
@@ -1301,8 +1409,8 @@ fn test_monthly_analytics_category_tracking() {
         &env, 3, user.clone(), Address::generate(&env), 300, "transport"
     ));
     
-    let analytics = client.update_monthly_spending_analytics(
-        &admin, &user, &transactions, 2023, 10
+    let analytics = client.update_monthly_analytics(
+        &admin, &user, &transactions, &2023, &10
     );
     
     // Verify total spending calculation
@@ -1314,3 +1422,414 @@ fn test_monthly_analytics_category_tracking() {
     // We can't easily test the category_spending vector content since it's a Vec<(Symbol, i128)>
     // but we know it was calculated properly based on our algorithm
 }
+
+// ============================================================================
+// Spending Forecast Tests
+// ============================================================================
+
+/// Helper to build a forecast from (category, amount) pairs.
+fn create_forecast(env: &Env, entries: &[(&str, i128)]) -> Vec<CategoryForecast> {
+    let mut forecasts = Vec::new(env);
+    for (category, amount) in entries {
+        forecasts.push_back(CategoryForecast {
+            category: Symbol::new(env, category),
+            amount: *amount,
+        });
+    }
+    forecasts
+}
+
+/// Helper counting `forecast_variance` events.
+fn count_variance_events(env: &Env) -> u32 {
+    let forecast = Symbol::new(env, "forecast");
+    env.events()
+        .all()
+        .iter()
+        .filter(|(_, topics, _)| {
+            topics
+                .get(0)
+                .and_then(|topic| Symbol::try_from_val(env, &topic).ok())
+                .is_some_and(|topic| topic == forecast)
+        })
+        .count() as u32
+}
+
+#[test]
+fn test_record_and_get_forecast() {
+    let (env, _, client) = setup_test_env();
+    let user = Address::generate(&env);
+
+    let forecasts = create_forecast(&env, &[("food", 500), ("transport", 200)]);
+    client.record_forecast(&user, &user, &2024, &3, &forecasts);
+    assert_eq!(client.get_forecast(&user, &2024, &3), forecasts);
+
+    // A new forecast for the same month replaces the old one
+    let revised = create_forecast(&env, &[("food", 400)]);
+    client.record_forecast(&user, &user, &2024, &3, &revised);
+    assert_eq!(client.get_forecast(&user, &2024, &3), revised);
+    assert!(client.get_forecast(&user, &2024, &4).is_empty());
+}
+
+#[test]
+fn test_forecast_variance_against_actuals() {
+    let (env, admin, client) = setup_test_env();
+    let user = Address::generate(&env);
+
+    let forecasts = create_forecast(&env, &[("food", 500), ("transport", 200)]);
+    client.record_forecast(&user, &user, &2024, &3, &forecasts);
+
+    let mut transactions: Vec<Transaction> = Vec::new(&env);
+    transactions.push_back(create_transaction_with_addresses(
+        &env, 1, user.clone(), Address::generate(&env), 600, "food",
+    ));
+    transactions.push_back(create_transaction_with_addresses(
+        &env, 2, user.clone(), Address::generate(&env), 50, "rent",
+    ));
+    client.update_monthly_analytics(&admin, &user, &transactions, &2024, &3);
+
+    let variances = client.get_forecast_variance(&user, &2024, &3);
+    assert_eq!(variances.len(), 3);
+
+    let food = variances.get(0).unwrap();
+    assert_eq!(food.category, Symbol::new(&env, "food"));
+    assert_eq!((food.forecast, food.actual, food.variance), (500, 600, 100));
+    assert_eq!(food.variance_bps, 2_000);
+
+    // Nothing was spent on transport
+    let transport = variances.get(1).unwrap();
+    assert_eq!((transport.actual, transport.variance), (0, -200));
+    assert_eq!(transport.variance_bps, 10_000);
+
+    // Unforecast spending shows up after the forecast categories
+    let rent = variances.get(2).unwrap();
+    assert_eq!(rent.category, Symbol::new(&env, "rent"));
+    assert_eq!((rent.forecast, rent.actual), (0, 50));
+    assert_eq!(rent.variance_bps, u32::MAX);
+}
+
+#[test]
+fn test_large_variance_emits_event() {
+    let (env, admin, client) = setup_test_env();
+    let user = Address::generate(&env);
+
+    client.set_variance_alert_bps(&admin, &2_500);
+    assert_eq!(client.get_variance_alert_bps(), 2_500);
+
+    let forecasts = create_forecast(&env, &[("food", 500), ("transport", 200)]);
+    client.record_forecast(&user, &user, &2024, &3, &forecasts);
+
+    // food is 20% over (below threshold), transport is 50% over
+    let mut transactions: Vec<Transaction> = Vec::new(&env);
+    transactions.push_back(create_transaction_with_addresses(
+        &env, 1, user.clone(), Address::generate(&env), 600, "food",
+    ));
+    transactions.push_back(create_transaction_with_addresses(
+        &env, 2, user.clone(), Address::generate(&env), 300, "transport",
+    ));
+    client.update_monthly_analytics(&admin, &user, &transactions, &2024, &3);
+
+    assert_eq!(count_variance_events(&env), 1);
+}
+
+#[test]
+fn test_no_variance_events_when_alerts_disabled() {
+    let (env, admin, client) = setup_test_env();
+    let user = Address::generate(&env);
+
+    let forecasts = create_forecast(&env, &[("food", 100)]);
+    client.record_forecast(&user, &user, &2024, &3, &forecasts);
+
+    let mut transactions: Vec<Transaction> = Vec::new(&env);
+    transactions.push_back(create_transaction_with_addresses(
+        &env, 1, user.clone(), Address::generate(&env), 900, "food",
+    ));
+    client.update_monthly_analytics(&admin, &user, &transactions, &2024, &3);
+
+    assert_eq!(count_variance_events(&env), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #20)")]
+fn test_forecast_rejected_after_actuals_recorded() {
+    let (env, admin, client) = setup_test_env();
+    let user = Address::generate(&env);
+
+    let mut transactions: Vec<Transaction> = Vec::new(&env);
+    transactions.push_back(create_transaction_with_addresses(
+        &env, 1, user.clone(), Address::generate(&env), 100, "food",
+    ));
+    client.update_monthly_analytics(&admin, &user, &transactions, &2024, &3);
+
+    let forecasts = create_forecast(&env, &[("food", 100)]);
+    client.record_forecast(&user, &user, &2024, &3, &forecasts);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #19)")]
+fn test_forecast_rejects_duplicate_categories() {
+    let (env, _, client) = setup_test_env();
+    let user = Address::generate(&env);
+
+    let forecasts = create_forecast(&env, &[("food", 100), ("food", 200)]);
+    client.record_forecast(&user, &user, &2024, &3, &forecasts);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_forecast_for_other_user_requires_admin() {
+    let (env, _, client) = setup_test_env();
+    let user = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    let forecasts = create_forecast(&env, &[("food", 100)]);
+    client.record_forecast(&stranger, &user, &2024, &3, &forecasts);
+}
+
+// ============================================================================
+// Reference Currency Tests
+// ============================================================================
+
+#[test]
+fn test_reference_change_flags_earlier_analytics() {
+    let (env, admin, client) = setup_test_env();
+    let user = Address::generate(&env);
+    let reference = client.get_reference_currency();
+    assert_eq!(reference.code, Symbol::new(&env, "USD"));
+    assert_eq!(reference.decimals, 7);
+
+    let mut transactions: Vec<Transaction> = Vec::new(&env);
+    transactions.push_back(create_transaction_with_addresses(
+        &env, 1, user.clone(), Address::generate(&env), 100, "food",
+    ));
+    client.update_monthly_analytics(&admin, &user, &transactions, &2024, &3);
+    assert!(!client.is_analytics_pre_change(&user, &2024, &3));
+
+    client.set_reference_currency(&admin, &Symbol::new(&env, "EURC"), &7);
+    assert_eq!(client.get_reference_currency().epoch, 1);
+    assert!(client.is_analytics_pre_change(&user, &2024, &3));
+    // Periods with no analytics are not flagged
+    assert!(!client.is_analytics_pre_change(&user, &2024, &4));
+
+    // Recomputing the period records it under the new reference
+    client.update_monthly_analytics(&admin, &user, &transactions, &2024, &3);
+    assert!(!client.is_analytics_pre_change(&user, &2024, &3));
+}
+
+#[test]
+fn test_reference_precision_change_restates_fees() {
+    let (env, admin, client) = setup_test_env();
+    client.update_fee_config(
+        &admin,
+        &FeeConfig {
+            fee_model: FeeModel::Flat(1_000_000),
+            min_fee: Some(15),
+            max_fee: Some(50_000_000),
+            enabled: true,
+            description: None,
+        },
+    );
+
+    client.set_reference_currency(&admin, &Symbol::new(&env, "USD"), &6);
+
+    let config = client.get_current_fee_config().unwrap();
+    assert_eq!(config.fee_model, FeeModel::Flat(100_000));
+    // Bounds round up so fees never shrink
+    assert_eq!(config.min_fee, Some(2));
+    assert_eq!(config.max_fee, Some(5_000_000));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #21)")]
+fn test_reference_change_rejects_unsupported_decimals() {
+    let (env, admin, client) = setup_test_env();
+
+    client.set_reference_currency(&admin, &Symbol::new(&env, "EURC"), &19);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_reference_change_requires_admin() {
+    let (env, _admin, client) = setup_test_env();
+    let stranger = Address::generate(&env);
+
+    client.set_reference_currency(&stranger, &Symbol::new(&env, "EURC"), &7);
+}
+
+// ============================================================================
+// Outflow Rate Tests
+// ============================================================================
+
+fn process_volume(
+    env: &Env,
+    admin: &Address,
+    client: &TransactionAnalyticsContractClient,
+    tx_id: u64,
+    amount: i128,
+) {
+    let mut transactions: Vec<Transaction> = Vec::new(env);
+    transactions.push_back(create_transaction(env, tx_id, amount, "payroll"));
+    client.process_batch(admin, &transactions, &None);
+}
+
+#[test]
+fn test_outflow_rate_averages_recorded_days() {
+    let (env, admin, client) = setup_test_env();
+    env.ledger().with_mut(|li| li.timestamp = 10 * 86_400);
+
+    let rate = client.get_outflow_rate();
+    assert_eq!(rate.per_day, 0);
+    assert_eq!(rate.window_days, 0);
+
+    process_volume(&env, &admin, &client, 1, 3_000);
+    process_volume(&env, &admin, &client, 2, 1_000);
+    env.ledger().with_mut(|li| li.timestamp = 11 * 86_400 + 500);
+    process_volume(&env, &admin, &client, 3, 2_000);
+
+    // Two days of history, so the average is over two days
+    let rate = client.get_outflow_rate();
+    assert_eq!(rate.window_total, 6_000);
+    assert_eq!(rate.window_days, 2);
+    assert_eq!(rate.per_day, 3_000);
+
+    // Quiet days still count once they are inside the window
+    env.ledger().with_mut(|li| li.timestamp = 13 * 86_400);
+    assert_eq!(client.get_outflow_rate().per_day, 1_500);
+}
+
+#[test]
+fn test_outflow_rate_drops_days_outside_window() {
+    let (env, admin, client) = setup_test_env();
+    env.ledger().with_mut(|li| li.timestamp = 86_400);
+    process_volume(&env, &admin, &client, 1, 90_000);
+
+    let later = (1 + OUTFLOW_WINDOW_DAYS as u64) * 86_400;
+    env.ledger().with_mut(|li| li.timestamp = later);
+    process_volume(&env, &admin, &client, 2, 30_000);
+
+    let rate = client.get_outflow_rate();
+    assert_eq!(rate.window_days, OUTFLOW_WINDOW_DAYS);
+    assert_eq!(rate.window_total, 30_000);
+    assert_eq!(rate.per_day, 1_000);
+}
+
+// ============================================
+// Tax Deduction Tests
+// ============================================
+
+fn record_month(
+    env: &Env,
+    admin: &Address,
+    client: &TransactionAnalyticsContractClient,
+    user: &Address,
+    month: u32,
+    spending: &[(&str, i128)],
+) {
+    let mut transactions: Vec<Transaction> = Vec::new(env);
+    for (i, (category, amount)) in spending.iter().enumerate() {
+        transactions.push_back(create_transaction_with_addresses(
+            env,
+            month as u64 * 100 + i as u64,
+            user.clone(),
+            Address::generate(env),
+            *amount,
+            category,
+        ));
+    }
+    client.update_monthly_analytics(admin, user, &transactions, &2024, &month);
+}
+
+#[test]
+fn test_annual_deductible_summary() {
+    let (env, admin, client) = setup_test_env();
+    let user = Address::generate(&env);
+    let charity = Symbol::new(&env, "charity");
+    let medical = Symbol::new(&env, "medical");
+    client.set_category_deductible(&admin, &charity, &true);
+    client.set_category_deductible(&admin, &medical, &true);
+    assert!(client.is_category_deductible(&charity));
+    assert!(!client.is_category_deductible(&Symbol::new(&env, "food")));
+
+    record_month(&env, &admin, &client, &user, 1, &[("charity", 100), ("food", 400)]);
+    record_month(&env, &admin, &client, &user, 2, &[("medical", 250), ("charity", 50)]);
+    record_month(&env, &admin, &client, &user, 3, &[("food", 300)]);
+
+    let summary = client.get_annual_deductible_summary(&user, &2024);
+    assert_eq!(summary.total_deductible, 400);
+    assert_eq!(summary.months_recorded, 3);
+    assert_eq!(summary.category_deductible.len(), 2);
+    assert_eq!(summary.category_deductible.get(0).unwrap(), (charity.clone(), 150));
+    assert_eq!(summary.category_deductible.get(1).unwrap(), (medical, 250));
+
+    // Other years and users are unaffected
+    assert_eq!(client.get_annual_deductible_summary(&user, &2023).months_recorded, 0);
+    let other = client.get_annual_deductible_summary(&Address::generate(&env), &2024);
+    assert_eq!(other.total_deductible, 0);
+}
+
+#[test]
+fn test_deductible_flag_applies_when_month_is_recorded() {
+    let (env, admin, client) = setup_test_env();
+    let user = Address::generate(&env);
+    let charity = Symbol::new(&env, "charity");
+
+    record_month(&env, &admin, &client, &user, 1, &[("charity", 100)]);
+    client.set_category_deductible(&admin, &charity, &true);
+    assert_eq!(client.get_annual_deductible_summary(&user, &2024).total_deductible, 0);
+
+    // Updating the month picks up the flag and replaces its earlier figures
+    record_month(&env, &admin, &client, &user, 1, &[("charity", 120)]);
+    record_month(&env, &admin, &client, &user, 1, &[("charity", 80)]);
+    assert_eq!(client.get_annual_deductible_summary(&user, &2024).total_deductible, 80);
+
+    let result = client.try_set_category_deductible(&user, &charity, &false);
+    assert!(result.is_err());
+}
+
+// ============================================================================
+// Fee Quote Tests
+// ============================================================================
+
+#[test]
+fn test_fee_quote_matches_deducted_fee() {
+    let (env, admin, client) = setup_test_env();
+    let token = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    client.update_fee_config(
+        &admin,
+        &FeeConfig {
+            fee_model: FeeModel::Percentage(30),
+            min_fee: Some(5),
+            max_fee: Some(100),
+            enabled: true,
+            description: None,
+        },
+    );
+
+    for amount in [1_001, 100, 3, 1_000_000, 0] {
+        let quote = client.quote_payment(&amount, &token, &merchant);
+        let deducted = env.as_contract(&client.address, || crate::deduct_fees(&env, amount));
+        assert_eq!(quote.gross_amount, deducted.gross_amount);
+        assert_eq!(quote.fee_amount, deducted.fee_amount);
+        assert_eq!(quote.net_amount, deducted.net_amount);
+        assert_eq!(quote.fee_percentage_bps, deducted.fee_percentage_bps);
+        assert_eq!((quote.token, quote.merchant), (token.clone(), merchant.clone()));
+    }
+
+    // The breakdown shows how limits moved the model's fee
+    let quote = client.quote_payment(&100, &token, &merchant);
+    assert_eq!((quote.model_fee, quote.fee_amount), (1, 5));
+    let quote = client.quote_payment(&3, &token, &merchant);
+    assert_eq!((quote.model_fee, quote.fee_amount, quote.net_amount), (1, 3, 0));
+    let quote = client.quote_payment(&1_000_000, &token, &merchant);
+    assert_eq!((quote.model_fee, quote.fee_amount), (3_000, 100));
+}
+
+#[test]
+fn test_fee_quote_uses_default_config() {
+    let (env, _admin, client) = setup_test_env();
+    let quote = client.quote_payment(&10_000, &Address::generate(&env), &Address::generate(&env));
+
+    assert_eq!(quote.fee_amount, client.calculate_transaction_fee(&10_000).fee_amount);
+    assert_eq!(quote.fee_amount, 10);
+    assert_eq!(quote.net_amount, 9_990);
+}
@@ -1,10 +1,23 @@
 //! Data types and events for batch transaction analytics.
 
+use common::events;
 use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol, Vec};
 
 /// Maximum number of transactions in a single batch for optimization.
 pub const MAX_BATCH_SIZE: u32 = 100;
 
+/// Maximum number of amount-range auto-tag rules.
+pub const MAX_AMOUNT_RULES: u32 = 20;
+
+/// Category transactions are submitted with when they should be auto-tagged.
+pub const UNTAGGED_CATEGORY: &str = "untagged";
+
+/// Maximum number of categories in one period's spending forecast.
+pub const MAX_FORECAST_CATEGORIES: u32 = 20;
+
+/// Number of trailing days the outflow rate is averaged over.
+pub const OUTFLOW_WINDOW_DAYS: u32 = 30;
+
 /// Represents a single transaction record for analytics.
 #[derive(Clone, Debug)]
 #[contracttype]
@@ -23,6 +36,67 @@ pub struct Transaction {
     pub category: Symbol,
 }
 
+/// Auto-tag rule assigning a category to untagged transactions whose amount
+/// falls within `[min_amount, max_amount]`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct AmountRule {
+    pub min_amount: i128,
+    pub max_amount: i128,
+    pub category: Symbol,
+}
+
+/// Expected spend in one category for an upcoming period.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct CategoryForecast {
+    pub category: Symbol,
+    pub amount: i128,
+}
+
+/// Forecast-versus-actual spend in one category for a period.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ForecastVariance {
+    pub category: Symbol,
+    pub forecast: i128,
+    pub actual: i128,
+    /// Actual minus forecast; positive when spend ran over
+    pub variance: i128,
+    /// Absolute variance in basis points of the forecast (`u32::MAX` when
+    /// nothing was forecast but money was spent)
+    pub variance_bps: u32,
+}
+
+/// A user's tax-deductible spending over a calendar year.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct AnnualDeductibleSummary {
+    pub user: Address,
+    pub year: u32,
+    /// Deductible spending across all categories
+    pub total_deductible: i128,
+    /// Deductible spending per category, in order of first appearance
+    pub category_deductible: Vec<(Symbol, i128)>,
+    /// Months of the year with recorded analytics
+    pub months_recorded: u32,
+}
+
+/// Average daily outflow of processed batches over a trailing window.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct OutflowRate {
+    /// Average outflow per day, rounded down
+    pub per_day: i128,
+    /// Total outflow within the window
+    pub window_total: i128,
+    /// Days averaged over: `OUTFLOW_WINDOW_DAYS`, or fewer while history is
+    /// shorter (0 before any outflow)
+    pub window_days: u32,
+    /// Ledger timestamp the rate was computed at
+    pub as_of: u64,
+}
+
 /// Represents a single audit log entry.
 #[derive(Clone, Debug)]
 #[contracttype]
@@ -227,6 +301,30 @@ pub enum DataKey {
     LastAnalyticsUpdate,
     /// Current fee configuration
     CurrentFeeConfig,
+    /// Default category for payments to a merchant
+    MerchantCategory(Address),
+    /// Amount-range auto-tag rules, checked in order
+    AmountRules,
+    /// Category recorded for a processed transaction
+    TransactionCategory(u64),
+    /// Operators allowed to override transaction categories
+    Operator(Address),
+    /// Spending forecast for a user (year, month, user)
+    Forecast(u32, u32, Address),
+    /// Variance in basis points at which a forecast miss raises an event
+    VarianceAlertBps,
+    /// Reference currency epoch monthly analytics were recorded under
+    /// (year, month, user)
+    AnalyticsEpoch(u32, u32, Address),
+    /// Volume of batches processed on a day (ledger timestamp / 86400)
+    DailyOutflow(u64),
+    /// First day any outflow was recorded
+    FirstOutflowDay,
+    /// Whether spending in a category is tax-deductible
+    DeductibleCategory(Symbol),
+    /// Deductible spending per category recorded with a user's monthly
+    /// analytics (year, month, user)
+    MonthlyDeductible(u32, u32, Address),
 }
 
 /// Status indicating refund eligibility for a transaction.
@@ -294,7 +392,7 @@ pub enum FeeModel {
 }
 
 /// Fee tier definition
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 #[contracttype]
 pub struct FeeTier {
     /// Amount threshold for this tier
@@ -306,7 +404,7 @@ pub struct FeeTier {
 }
 
 /// Fee configuration structure
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 #[contracttype]
 pub struct FeeConfig {
     /// The fee model to use
@@ -335,6 +433,26 @@ pub struct FeeCalculationResult {
     pub fee_percentage_bps: u32,
 }
 
+/// Fee breakdown quoted for a payment before the user signs it
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct FeeQuote {
+    /// Token the payment and its fee are denominated in
+    pub token: Address,
+    /// Merchant being paid
+    pub merchant: Address,
+    /// Original payment amount
+    pub gross_amount: i128,
+    /// Fee the fee model yields before min/max limits and the payment cap
+    pub model_fee: i128,
+    /// Fee charged after limits and the cap
+    pub fee_amount: i128,
+    /// Amount the merchant receives
+    pub net_amount: i128,
+    /// Effective fee rate in basis points
+    pub fee_percentage_bps: u32,
+}
+
 /// Event structure for fee deductions
 #[derive(Clone, Debug, Default)]
 #[contracttype]
@@ -396,7 +514,7 @@ pub struct RefundBatchMetrics {
 }
 
 /// Structure to hold monthly spending analytics for a user
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 #[contracttype]
 pub struct MonthlySpendingAnalytics {
     /// Year of the analytics
@@ -414,7 +532,7 @@ pub struct MonthlySpendingAnalytics {
 }
 
 /// Structure for aggregated user spending across multiple months
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 #[contracttype]
 pub struct UserSpendingSummary {
     /// User address
@@ -436,7 +554,7 @@ impl AnalyticsEvents {
     /// Event emitted when a batch is processed.
     pub fn batch_processed(env: &Env, batch_id: u64, metrics: &BatchMetrics) {
         let topics = (symbol_short!("batch"), symbol_short!("processed"), batch_id);
-        env.events().publish(topics, metrics.clone());
+        events::publish(env, topics, metrics.clone());
     }
 
     /// Event emitted for each category in a batch.
@@ -445,31 +563,39 @@ impl AnalyticsEvents {
             symbol_short!("category"),
             batch_id,
         );
-        env.events().publish(topics, (category_metrics.category.clone(), category_metrics.clone()));
+        events::publish(
+            env,
+            topics,
+            (category_metrics.category.clone(), category_metrics.clone()),
+        );
     }
 
     /// Event emitted when analytics computation starts.
     pub fn analytics_started(env: &Env, batch_id: u64, tx_count: u32) {
         let topics = (symbol_short!("analytics"), symbol_short!("started"));
-        env.events().publish(topics, (batch_id, tx_count));
+        events::publish(env, topics, (batch_id, tx_count));
     }
 
     /// Event emitted when analytics computation completes.
     pub fn analytics_completed(env: &Env, batch_id: u64, processing_cost: u64) {
         let topics = (symbol_short!("analytics"), symbol_short!("complete"));
-        env.events().publish(topics, (batch_id, processing_cost));
+        events::publish(env, topics, (batch_id, processing_cost));
     }
 
     /// Event emitted for high-value transaction alerts.
     pub fn high_value_alert(env: &Env, batch_id: u64, tx_id: u64, amount: i128) {
         let topics = (symbol_short!("alert"), symbol_short!("highval"));
-        env.events().publish(topics, (batch_id, tx_id, amount));
+        events::publish(env, topics, (batch_id, tx_id, amount));
     }
 
     /// Event emitted when an audit log is created.
     pub fn audit_logged(env: &Env, actor: &Address, operation: &Symbol, status: &Symbol) {
         let topics = (symbol_short!("audit"), symbol_short!("log"));
-        env.events().publish(topics, (actor.clone(), operation.clone(), status.clone()));
+        events::publish(
+            env,
+            topics,
+            (actor.clone(), operation.clone(), status.clone()),
+        );
     }
 
     /// Event emitted when a rating is submitted.
@@ -481,7 +607,7 @@ impl AnalyticsEvents {
         status: RatingStatus,
     ) {
         let topics = (symbol_short!("rating"), symbol_short!("submit"), user);
-        env.events().publish(topics, (tx_id, score, status));
+        events::publish(env, topics, (tx_id, score, status));
     }
 
     pub fn transaction_status_updated(
@@ -491,18 +617,18 @@ impl AnalyticsEvents {
         new_status: TransactionStatus,
     ) {
         let topics = (symbol_short!("status"), symbol_short!("updated"));
-        env.events().publish(topics, (tx_id, previous_status, new_status));
+        events::publish(env, topics, (tx_id, previous_status, new_status));
     }
 
     pub fn transaction_status_update_failed(env: &Env, tx_id: u64) {
         let topics = (symbol_short!("status"), symbol_short!("failed"));
-        env.events().publish(topics, tx_id);
+        events::publish(env, topics, tx_id);
     }
 
     /// Event emitted when a transaction bundle is created.
     pub fn bundle_created(env: &Env, bundle_id: u64, result: &BundleResult) {
         let topics = (symbol_short!("bundle"), symbol_short!("created"), bundle_id);
-        env.events().publish(topics, result.clone());
+        events::publish(env, topics, result.clone());
     }
 
     /// Event emitted when a transaction in a bundle is validated.
@@ -512,49 +638,49 @@ impl AnalyticsEvents {
             symbol_short!("validated"),
             bundle_id,
         );
-        env.events().publish(topics, validation_result.clone());
+        events::publish(env, topics, validation_result.clone());
     }
 
     /// Event emitted when bundling starts.
     pub fn bundling_started(env: &Env, bundle_id: u64, tx_count: u32) {
         let topics = (symbol_short!("bundle"), symbol_short!("started"));
-        env.events().publish(topics, (bundle_id, tx_count));
+        events::publish(env, topics, (bundle_id, tx_count));
     }
 
     /// Event emitted when bundling completes.
     pub fn bundling_completed(env: &Env, bundle_id: u64, can_bundle: bool) {
         let topics = (symbol_short!("bundle"), symbol_short!("completed"));
-        env.events().publish(topics, (bundle_id, can_bundle));
+        events::publish(env, topics, (bundle_id, can_bundle));
     }
 
     /// Event emitted when a transaction fails validation in a bundle.
     pub fn transaction_validation_failed(env: &Env, bundle_id: u64, tx_id: u64, error: &Symbol) {
         let topics = (symbol_short!("bundle"), symbol_short!("failed"));
-        env.events().publish(topics, (bundle_id, tx_id, error.clone()));
+        events::publish(env, topics, (bundle_id, tx_id, error.clone()));
     }
 
     /// Event emitted when a refund batch processing starts.
     pub fn refund_batch_started(env: &Env, batch_id: u64, request_count: u32) {
         let topics = (symbol_short!("refund"), symbol_short!("started"));
-        env.events().publish(topics, (batch_id, request_count));
+        events::publish(env, topics, (batch_id, request_count));
     }
 
     /// Event emitted for each individual refund result.
     pub fn refund_processed(env: &Env, batch_id: u64, refund_result: &RefundResult) {
         let topics = (symbol_short!("refund"), symbol_short!("processed"), batch_id);
-        env.events().publish(topics, refund_result.clone());
+        events::publish(env, topics, refund_result.clone());
     }
 
     /// Event emitted when a refund batch completes.
     pub fn refund_batch_completed(env: &Env, batch_id: u64, metrics: &RefundBatchMetrics) {
         let topics = (symbol_short!("refund"), symbol_short!("completed"), batch_id);
-        env.events().publish(topics, metrics.clone());
+        events::publish(env, topics, metrics.clone());
     }
 
     /// Event emitted for refund errors or warnings.
     pub fn refund_error(env: &Env, batch_id: u64, tx_id: u64, error_msg: Symbol) {
         let topics = (symbol_short!("refund"), symbol_short!("error"));
-        env.events().publish(topics, (batch_id, tx_id, error_msg));
+        events::publish(env, topics, (batch_id, tx_id, error_msg));
     }
     
     /// Event emitted when analytics are updated for a user.
@@ -566,7 +692,11 @@ impl AnalyticsEvents {
         analytics: &MonthlySpendingAnalytics,
     ) {
         let topics = (symbol_short!("analytics"), symbol_short!("updated"), user);
-        env.events().publish(topics, (year, month, analytics.total_spending, analytics.transaction_count));
+        events::publish(
+            env,
+            topics,
+            (year, month, analytics.total_spending, analytics.transaction_count),
+        );
     }
     
     /// Event emitted when a fee is deducted from a transaction.
@@ -578,6 +708,51 @@ impl AnalyticsEvents {
         fee_percentage_bps: u32,
     ) {
         let topics = (symbol_short!("fee"), symbol_short!("deducted"));
-        env.events().publish(topics, (gross_amount, fee_amount, net_amount, fee_percentage_bps));
+        events::publish(
+            env,
+            topics,
+            (gross_amount, fee_amount, net_amount, fee_percentage_bps),
+        );
+    }
+
+    /// Event emitted when an untagged transaction is auto-tagged.
+    pub fn transaction_tagged(env: &Env, tx_id: u64, category: &Symbol) {
+        let topics = (symbol_short!("category"), symbol_short!("autotag"));
+        events::publish(env, topics, (tx_id, category.clone()));
+    }
+
+    /// Event emitted when a period's actual spend misses its forecast by at
+    /// least the variance alert threshold, for finance review.
+    pub fn forecast_variance(
+        env: &Env,
+        user: &Address,
+        year: u32,
+        month: u32,
+        variance: &ForecastVariance,
+    ) {
+        let topics = (symbol_short!("forecast"), symbol_short!("variance"), user);
+        events::publish(env, topics, (year, month, variance.clone()));
+    }
+
+    /// Event emitted when an operator overrides a transaction's category.
+    pub fn category_overridden(
+        env: &Env,
+        operator: &Address,
+        tx_id: u64,
+        previous: &Symbol,
+        category: &Symbol,
+    ) {
+        let topics = (symbol_short!("category"), symbol_short!("override"));
+        events::publish(
+            env,
+            topics,
+            (operator.clone(), tx_id, previous.clone(), category.clone()),
+        );
+    }
+
+    /// Event emitted when a category is flagged or unflagged as tax-deductible.
+    pub fn deductible_set(env: &Env, category: &Symbol, deductible: bool) {
+        let topics = (symbol_short!("category"), symbol_short!("deduct"));
+        events::publish(env, topics, (category.clone(), deductible));
     }
 }
@@ -3,7 +3,7 @@
 //! Implements strict validation for all public inputs in the transaction analytics contract.
 //! Provides standardized validation functions for addresses, amounts, assets, and other inputs.
 
-use soroban_sdk::{Address, Env, String, symbol_short, Symbol};
+use soroban_sdk::{symbol_short, Address, Env, Map, Vec};
 
 use crate::types::{Transaction, RefundRequest, RatingInput, TransactionStatusUpdate, BundledTransaction, ValidationError};
 
@@ -15,13 +15,8 @@ use crate::types::{Transaction, RefundRequest, RatingInput, TransactionStatusUpd
 /// 
 /// # Returns
 /// * `Ok(())` if valid, `Err(ValidationError)` if invalid
-pub fn validate_address(env: &Env, address: &Address) -> Result<(), ValidationError> {
+pub fn validate_address(_env: &Env, _address: &Address) -> Result<(), ValidationError> {
     // Soroban SDK addresses are guaranteed to be valid by construction
-    // However, we can perform additional checks if needed
-    if address.is_none() {
-        return Err(ValidationError::InvalidAddress);
-    }
-    
     Ok(())
 }
 
@@ -50,9 +45,10 @@ pub fn validate_amounts(amounts: &[i128]) -> Result<(), ValidationError> {
 
 /// Validates a transaction struct
 pub fn validate_transaction(transaction: &Transaction) -> Result<(), ValidationError> {
-    validate_address(&transaction.from)?;
-    validate_address(&transaction.to)?;
-    validate_amount(transaction.amount)?;
+    // Zero-amount transactions are recorded; only negative amounts are rejected
+    if transaction.amount < 0 {
+        return Err(ValidationError::InvalidAmount);
+    }
     
     // Validate timestamp is not in the future (within reasonable tolerance)
     let current_ledger = transaction.timestamp; // This would be env.ledger().sequence() in real usage
@@ -61,7 +57,7 @@ pub fn validate_transaction(transaction: &Transaction) -> Result<(), ValidationE
     }
     
     // Validate category is not empty
-    if transaction.category.to_str().len() == 0 {
+    if transaction.category == symbol_short!("") {
         return Err(ValidationError::InvalidCategory);
     }
     
@@ -80,7 +76,7 @@ pub fn validate_transactions(transactions: &Vec<Transaction>) -> Result<(), Vali
     }
     
     for transaction in transactions.iter() {
-        validate_transaction(transaction)?;
+        validate_transaction(&transaction)?;
     }
     
     Ok(())
@@ -95,10 +91,7 @@ pub fn validate_refund_request(request: &RefundRequest) -> Result<(), Validation
     
     // Validate reason if provided
     if let Some(reason) = &request.reason {
-        if reason.to_str().len() == 0 {
-            return Err(ValidationError::InvalidReason);
-        }
-        if reason.to_str().len() > 255 { // Arbitrary limit for reason length
+        if *reason == symbol_short!("") {
             return Err(ValidationError::InvalidReason);
         }
     }
@@ -118,13 +111,13 @@ pub fn validate_refund_requests(requests: &Vec<RefundRequest>) -> Result<(), Val
     }
     
     // Check for duplicate transaction IDs
-    let mut seen_tx_ids = std::collections::HashSet::new();
+    let mut seen_tx_ids: Map<u64, bool> = Map::new(requests.env());
     for request in requests.iter() {
-        if seen_tx_ids.contains(&request.tx_id) {
+        if seen_tx_ids.contains_key(request.tx_id) {
             return Err(ValidationError::DuplicateTransactionId);
         }
-        seen_tx_ids.insert(request.tx_id);
-        validate_refund_request(request)?;
+        seen_tx_ids.set(request.tx_id, true);
+        validate_refund_request(&request)?;
     }
     
     Ok(())
@@ -157,7 +150,7 @@ pub fn validate_rating_inputs(inputs: &Vec<RatingInput>) -> Result<(), Validatio
     }
     
     for input in inputs.iter() {
-        validate_rating_input(input)?;
+        validate_rating_input(&input)?;
     }
     
     Ok(())
@@ -185,13 +178,13 @@ pub fn validate_transaction_status_updates(updates: &Vec<TransactionStatusUpdate
     }
     
     // Check for duplicate transaction IDs
-    let mut seen_tx_ids = std::collections::HashSet::new();
+    let mut seen_tx_ids: Map<u64, bool> = Map::new(updates.env());
     for update in updates.iter() {
-        if seen_tx_ids.contains(&update.tx_id) {
+        if seen_tx_ids.contains_key(update.tx_id) {
             return Err(ValidationError::DuplicateTransactionId);
         }
-        seen_tx_ids.insert(update.tx_id);
-        validate_transaction_status_update(update)?;
+        seen_tx_ids.set(update.tx_id, true);
+        validate_transaction_status_update(&update)?;
     }
     
     Ok(())
@@ -208,10 +201,7 @@ pub fn validate_bundled_transaction(bundled_tx: &BundledTransaction) -> Result<(
     
     // Validate memo if provided
     if let Some(memo) = &bundled_tx.memo {
-        if memo.to_str().len() == 0 {
-            return Err(ValidationError::InvalidMemo);
-        }
-        if memo.to_str().len() > 255 { // Arbitrary limit for memo length
+        if *memo == symbol_short!("") {
             return Err(ValidationError::InvalidMemo);
         }
     }
@@ -230,10 +220,8 @@ pub fn validate_bundled_transactions(bundled_txs: &Vec<BundledTransaction>) -> R
         return Err(ValidationError::BatchTooLarge);
     }
     
-    for bundled_tx in bundled_txs.iter() {
-        validate_bundled_transaction(bundled_tx)?;
-    }
-    
+    // Individual transactions are validated during bundling so that invalid
+    // entries are reported as partial failures instead of rejecting the bundle
     Ok(())
 }
 
@@ -244,11 +232,11 @@ pub fn validate_user_address(env: &Env, user: &Address) -> Result<(), Validation
 
 /// Validates year and month for analytics functions
 pub fn validate_year_month(year: u32, month: u32) -> Result<(), ValidationError> {
-    if year < 2000 || year > 2100 {
+    if !(2000..=2100).contains(&year) {
         return Err(ValidationError::InvalidYear);
     }
     
-    if month < 1 || month > 12 {
+    if !(1..=12).contains(&month) {
         return Err(ValidationError::InvalidMonth);
     }
     
@@ -313,8 +301,8 @@ pub fn validate_asset_amounts(asset_identifier: &str, amounts: &[i128]) -> Resul
 #[cfg(test)]
 mod tests {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, Env};
-    use crate::types::{TransactionStatus, RatingInput, TransactionStatusUpdate, RefundRequest, BundledTransaction, Transaction};
+    use soroban_sdk::{testutils::Address as _, Env, Symbol};
+    use crate::types::{RatingInput, RefundRequest, Transaction};
 
     fn create_test_transaction(env: &Env, tx_id: u64, amount: i128, category: &str) -> Transaction {
         Transaction {
@@ -323,7 +311,7 @@ mod tests {
             to: Address::generate(env),
             amount,
             timestamp: 12345,
-            category: symbol_short!(category),
+            category: Symbol::new(env, category),
         }
     }
 
@@ -350,13 +338,12 @@ mod tests {
     #[test]
     fn test_validate_transaction_invalid_amount() {
         let env = Env::default();
-        let mut transaction = create_test_transaction(&env, 1, -100, "transfer");
+        let transaction = create_test_transaction(&env, 1, -100, "transfer");
         assert!(validate_transaction(&transaction).is_err());
     }
 
     #[test]
     fn test_validate_refund_request_valid() {
-        let env = Env::default();
         let request = RefundRequest {
             tx_id: 1,
             reason: Some(symbol_short!("test")),
@@ -366,7 +353,6 @@ mod tests {
 
     #[test]
     fn test_validate_refund_request_invalid_tx_id() {
-        let env = Env::default();
         let request = RefundRequest {
             tx_id: 0,
             reason: Some(symbol_short!("test")),
@@ -438,10 +424,10 @@ mod tests {
 
     #[test]
     fn test_validate_asset_amounts() {
-        let amounts = vec![100, 200, 300];
+        let amounts = [100, 200, 300];
         assert!(validate_asset_amounts("USDC", &amounts).is_ok());
         
-        let invalid_amounts = vec![100, -1, 300]; // Contains negative
+        let invalid_amounts = [100, -1, 300]; // Contains negative
         assert!(validate_asset_amounts("USDC", &invalid_amounts).is_err());
     }
 
@@ -0,0 +1,22 @@
+//! Interfaces of the contracts the watchdog reads from and trips.
+
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Contract reporting what it still owes, such as an escrow or payroll queue.
+#[allow(dead_code)]
+#[contractclient(name = "ObligationSourceClient")]
+pub trait ObligationSource {
+    /// Total amount of `token` owed but not yet paid out.
+    fn pending_obligations(env: Env, token: Address) -> i128;
+}
+
+/// Circuit breaker the watchdog trips when an invariant is violated.
+///
+/// `source` is the watchdog itself, which authorizes the call, so a breaker
+/// can restrict tripping to the watchdogs it trusts.
+#[allow(dead_code)]
+#[contractclient(name = "CircuitBreakerClient")]
+pub trait CircuitBreaker {
+    /// Halts the guarded contracts because check `check_id` failed.
+    fn trip(env: Env, source: Address, check_id: u64);
+}
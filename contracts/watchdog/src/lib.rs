@@ -0,0 +1,279 @@
+//! # Watchdog Contract
+//!
+//! Monitors invariants of other StellarSpend contracts on-chain. The admin
+//! registers checks, such as "the treasury holds enough to cover pending
+//! obligations" or "no spender's allowance exceeds the organization's
+//! limit", and anyone can run them at any time. A check that finds its
+//! invariant broken, or cannot read the values it compares, emits an alarm
+//! event and, if configured, trips a circuit breaker.
+//!
+//! ## Features
+//!
+//! - **Permissionless Runs**: Any keeper or user can trigger a check
+//! - **Alarms**: Failed checks emit the observed and expected amounts
+//! - **Circuit Breaker**: Checks can halt guarded contracts automatically
+//! - **Fail Closed**: A check that cannot read its inputs counts as failed
+#![no_std]
+
+mod interfaces;
+mod types;
+
+use common::versioning;
+use soroban_sdk::{contract, contractimpl, panic_with_error, token, Address, Env, Vec};
+
+pub use crate::interfaces::{
+    CircuitBreaker, CircuitBreakerClient, ObligationSource, ObligationSourceClient,
+};
+pub use crate::types::{
+    AllowanceInvariant, Check, CheckStatus, DataKey, Invariant, SolvencyInvariant, WatchdogEvents,
+    MAX_CHECKS,
+};
+
+/// Error codes for the watchdog contract.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum WatchdogError {
+    /// Contract not initialized
+    NotInitialized = 1,
+    /// Caller is not authorized
+    Unauthorized = 2,
+    /// Contract already initialized
+    AlreadyInitialized = 3,
+    /// No check exists with this ID
+    CheckNotFound = 4,
+    /// Maximum number of checks registered
+    TooManyChecks = 5,
+    /// Invariant parameters are invalid
+    InvalidInvariant = 6,
+    /// Storage was written by a different layout version
+    StorageVersionMismatch = 7,
+    /// Migration source version does not match stored version
+    InvalidMigration = 8,
+}
+
+impl From<WatchdogError> for soroban_sdk::Error {
+    fn from(e: WatchdogError) -> Self {
+        soroban_sdk::Error::from_contract_error(e as u32)
+    }
+}
+
+/// Storage layout version this code expects.
+pub const STORAGE_VERSION: u32 = 1;
+
+#[contract]
+pub struct WatchdogContract;
+
+#[contractimpl]
+impl WatchdogContract {
+    /// Initializes the contract with an admin address.
+    pub fn initialize(env: Env, admin: Address) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic_with_error!(&env, WatchdogError::AlreadyInitialized);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        versioning::set_version(&env, STORAGE_VERSION);
+    }
+
+    /// Migrates storage written by an older version of this contract to the
+    /// current layout (admin only). `from_version` must match the stored
+    /// version.
+    pub fn migrate(env: Env, caller: Address, from_version: u32) {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        versioning::migrate(&env, from_version, STORAGE_VERSION, |_, _| {})
+            .unwrap_or_else(|_| panic_with_error!(&env, WatchdogError::InvalidMigration));
+    }
+
+    /// Returns the storage layout version currently stored.
+    pub fn get_storage_version(env: Env) -> u32 {
+        versioning::stored_version(&env)
+    }
+
+    /// Registers an invariant check (admin only). If `breaker` is set, a
+    /// failed run trips it; the breaker must accept calls from this contract.
+    pub fn register_check(
+        env: Env,
+        caller: Address,
+        invariant: Invariant,
+        breaker: Option<Address>,
+    ) -> u64 {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        if let Invariant::Allowance(allowance) = &invariant {
+            if allowance.limit < 0 {
+                panic_with_error!(&env, WatchdogError::InvalidInvariant);
+            }
+        }
+        let mut checks = Self::get_checks(env.clone());
+        if checks.len() >= MAX_CHECKS {
+            panic_with_error!(&env, WatchdogError::TooManyChecks);
+        }
+
+        let check_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::LastCheckId)
+            .unwrap_or(0)
+            + 1;
+        let check = Check {
+            check_id,
+            invariant,
+            breaker,
+            status: CheckStatus::Unchecked,
+            last_run_at: 0,
+            failures: 0,
+        };
+        checks.push_back(check_id);
+        env.storage()
+            .instance()
+            .set(&DataKey::LastCheckId, &check_id);
+        env.storage().instance().set(&DataKey::Checks, &checks);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Check(check_id), &check);
+        WatchdogEvents::registered(&env, &check);
+
+        check_id
+    }
+
+    /// Removes a check (admin only).
+    pub fn remove_check(env: Env, caller: Address, check_id: u64) {
+        Self::require_storage_version(&env);
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        let mut checks = Self::get_checks(env.clone());
+        let index = checks
+            .first_index_of(check_id)
+            .unwrap_or_else(|| panic_with_error!(&env, WatchdogError::CheckNotFound));
+        checks.remove(index);
+        env.storage().instance().set(&DataKey::Checks, &checks);
+        env.storage().persistent().remove(&DataKey::Check(check_id));
+        WatchdogEvents::removed(&env, check_id);
+    }
+
+    /// Runs one check. Callable by anyone. Returns true if the invariant
+    /// holds; otherwise an alarm is emitted and the check's breaker, if
+    /// any, is tripped.
+    pub fn run_check(env: Env, check_id: u64) -> bool {
+        Self::require_storage_version(&env);
+
+        let check = Self::get_check(env.clone(), check_id)
+            .unwrap_or_else(|| panic_with_error!(&env, WatchdogError::CheckNotFound));
+        Self::run(&env, check)
+    }
+
+    /// Runs every registered check. Callable by anyone. Returns the IDs of
+    /// the checks that failed.
+    pub fn run_all(env: Env) -> Vec<u64> {
+        Self::require_storage_version(&env);
+
+        let mut failed: Vec<u64> = Vec::new(&env);
+        for check_id in Self::get_checks(env.clone()).iter() {
+            if let Some(check) = Self::get_check(env.clone(), check_id) {
+                if !Self::run(&env, check) {
+                    failed.push_back(check_id);
+                }
+            }
+        }
+        failed
+    }
+
+    /// Returns a registered check.
+    pub fn get_check(env: Env, check_id: u64) -> Option<Check> {
+        env.storage().persistent().get(&DataKey::Check(check_id))
+    }
+
+    /// Returns the IDs of the registered checks.
+    pub fn get_checks(env: Env) -> Vec<u64> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Checks)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Returns the admin address.
+    pub fn get_admin(env: Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(&env, WatchdogError::NotInitialized))
+    }
+
+    // Internal helper evaluating a check, recording the outcome and raising
+    // the alarm on failure
+    fn run(env: &Env, mut check: Check) -> bool {
+        let (status, observed, bound) = match Self::evaluate(env, &check.invariant) {
+            Some((observed, bound)) if observed <= bound => (CheckStatus::Healthy, observed, bound),
+            Some((observed, bound)) => (CheckStatus::Violated, observed, bound),
+            None => (CheckStatus::Unavailable, 0, 0),
+        };
+        check.status = status;
+        check.last_run_at = env.ledger().timestamp();
+        let healthy = status == CheckStatus::Healthy;
+        if !healthy {
+            check.failures = check.failures.saturating_add(1);
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::Check(check.check_id), &check);
+
+        if !healthy {
+            // A breaker that refuses the call must not hide the alarm
+            let tripped = check.breaker.as_ref().is_some_and(|breaker| {
+                let client = CircuitBreakerClient::new(env, breaker);
+                let source = env.current_contract_address();
+                matches!(client.try_trip(&source, &check.check_id), Ok(Ok(())))
+            });
+            WatchdogEvents::alarm(env, &check, observed, bound, tripped);
+        }
+        healthy
+    }
+
+    // Internal helper reading the two amounts an invariant compares, as
+    // (observed, bound) where the invariant holds if observed <= bound.
+    // Returns None if a contract it reads from fails.
+    fn evaluate(env: &Env, invariant: &Invariant) -> Option<(i128, i128)> {
+        match invariant {
+            Invariant::Solvency(solvency) => {
+                let owed = ObligationSourceClient::new(env, &solvency.source)
+                    .try_pending_obligations(&solvency.token)
+                    .ok()?
+                    .ok()?;
+                let balance = token::Client::new(env, &solvency.token)
+                    .try_balance(&solvency.treasury)
+                    .ok()?
+                    .ok()?;
+                Some((owed, balance))
+            }
+            Invariant::Allowance(allowance) => {
+                let granted = token::Client::new(env, &allowance.token)
+                    .try_allowance(&allowance.owner, &allowance.spender)
+                    .ok()?
+                    .ok()?;
+                Some((granted, allowance.limit))
+            }
+        }
+    }
+
+    // Internal helper refusing to run against storage written by a different
+    // layout version
+    fn require_storage_version(env: &Env) {
+        versioning::require_version(env, STORAGE_VERSION)
+            .unwrap_or_else(|_| panic_with_error!(env, WatchdogError::StorageVersionMismatch));
+    }
+
+    // Internal helper to verify admin
+    fn require_admin(env: &Env, caller: &Address) {
+        if *caller != Self::get_admin(env.clone()) {
+            panic_with_error!(env, WatchdogError::Unauthorized);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,177 @@
+//! Integration tests for the watchdog contract.
+
+#![cfg(test)]
+
+use crate::{
+    AllowanceInvariant, CheckStatus, Invariant, SolvencyInvariant, WatchdogContract,
+    WatchdogContractClient, WatchdogError,
+};
+use soroban_sdk::{
+    contract, contractimpl,
+    testutils::{Address as _, Ledger},
+    token, vec, Address, Env,
+};
+
+/// Obligation source reporting whatever total the test sets.
+#[contract]
+struct MockObligations;
+
+#[contractimpl]
+impl MockObligations {
+    pub fn set_pending(env: Env, amount: i128) {
+        env.storage().instance().set(&0u32, &amount);
+    }
+
+    pub fn pending_obligations(env: Env, _token: Address) -> i128 {
+        env.storage().instance().get(&0u32).unwrap_or(0)
+    }
+}
+
+/// Circuit breaker remembering the check that tripped it.
+#[contract]
+struct MockBreaker;
+
+#[contractimpl]
+impl MockBreaker {
+    pub fn trip(env: Env, source: Address, check_id: u64) {
+        source.require_auth();
+        env.storage().instance().set(&0u32, &check_id);
+    }
+
+    pub fn tripped_by(env: Env) -> Option<u64> {
+        env.storage().instance().get(&0u32)
+    }
+}
+
+struct Setup {
+    env: Env,
+    admin: Address,
+    treasury: Address,
+    token: Address,
+    obligations: MockObligationsClient<'static>,
+    client: WatchdogContractClient<'static>,
+}
+
+/// Creates a test environment with the contract deployed and initialized and
+/// a treasury holding 1,000 of a token.
+fn setup_test_env() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let client = WatchdogContractClient::new(&env, &env.register(WatchdogContract, ()));
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let treasury = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token).mint(&treasury, &1_000);
+    let obligations = MockObligationsClient::new(&env, &env.register(MockObligations, ()));
+
+    Setup {
+        env,
+        admin,
+        treasury,
+        token,
+        obligations,
+        client,
+    }
+}
+
+fn solvency(s: &Setup) -> Invariant {
+    Invariant::Solvency(SolvencyInvariant {
+        token: s.token.clone(),
+        treasury: s.treasury.clone(),
+        source: s.obligations.address.clone(),
+    })
+}
+
+#[test]
+fn test_solvency_check_trips_breaker_when_underfunded() {
+    let s = setup_test_env();
+    let breaker = MockBreakerClient::new(&s.env, &s.env.register(MockBreaker, ()));
+    let check_id = s
+        .client
+        .register_check(&s.admin, &solvency(&s), &Some(breaker.address.clone()));
+
+    s.obligations.set_pending(&1_000);
+    assert!(s.client.run_check(&check_id));
+    assert_eq!(
+        s.client.get_check(&check_id).unwrap().status,
+        CheckStatus::Healthy
+    );
+    assert_eq!(breaker.tripped_by(), None);
+
+    s.obligations.set_pending(&1_001);
+    assert!(!s.client.run_check(&check_id));
+    let check = s.client.get_check(&check_id).unwrap();
+    assert_eq!(check.status, CheckStatus::Violated);
+    assert_eq!(check.failures, 1);
+    assert_eq!(check.last_run_at, 1_000);
+    assert_eq!(breaker.tripped_by(), Some(check_id));
+}
+
+#[test]
+fn test_allowance_check_and_run_all() {
+    let s = setup_test_env();
+    let spender = Address::generate(&s.env);
+    let allowance = Invariant::Allowance(AllowanceInvariant {
+        token: s.token.clone(),
+        owner: s.treasury.clone(),
+        spender: spender.clone(),
+        limit: 500,
+    });
+    let solvent = s.client.register_check(&s.admin, &solvency(&s), &None);
+    let capped = s.client.register_check(&s.admin, &allowance, &None);
+
+    token::Client::new(&s.env, &s.token).approve(&s.treasury, &spender, &500, &100);
+    assert_eq!(s.client.run_all(), vec![&s.env]);
+
+    token::Client::new(&s.env, &s.token).approve(&s.treasury, &spender, &501, &100);
+    assert_eq!(s.client.run_all(), vec![&s.env, capped]);
+    assert_eq!(
+        s.client.get_check(&solvent).unwrap().status,
+        CheckStatus::Healthy
+    );
+}
+
+#[test]
+fn test_unreadable_source_fails_closed() {
+    let s = setup_test_env();
+    // A contract without `pending_obligations`
+    let not_a_source = s.env.register(MockBreaker, ());
+    let invariant = Invariant::Solvency(SolvencyInvariant {
+        token: s.token.clone(),
+        treasury: s.treasury.clone(),
+        source: not_a_source,
+    });
+    let check_id = s.client.register_check(&s.admin, &invariant, &None);
+
+    assert!(!s.client.run_check(&check_id));
+    assert_eq!(
+        s.client.get_check(&check_id).unwrap().status,
+        CheckStatus::Unavailable
+    );
+}
+
+#[test]
+fn test_remove_check() {
+    let s = setup_test_env();
+    let check_id = s.client.register_check(&s.admin, &solvency(&s), &None);
+
+    s.client.remove_check(&s.admin, &check_id);
+    assert_eq!(s.client.get_checks().len(), 0);
+    let result = s.client.try_run_check(&check_id);
+    assert_eq!(result, Err(Ok(WatchdogError::CheckNotFound.into())));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_register_check_requires_admin() {
+    let s = setup_test_env();
+
+    s.client
+        .register_check(&Address::generate(&s.env), &solvency(&s), &None);
+}
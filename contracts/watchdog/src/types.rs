@@ -0,0 +1,114 @@
+//! Data types and events for the watchdog contract.
+
+use common::events;
+use soroban_sdk::{contracttype, symbol_short, Address, Env};
+
+/// Maximum number of registered checks.
+pub const MAX_CHECKS: u32 = 50;
+
+/// A property that must always hold.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum Invariant {
+    /// The treasury's `token` balance covers the obligations pending at
+    /// `source`
+    Solvency(SolvencyInvariant),
+    /// `spender` may draw at most `limit` of `owner`'s `token`
+    Allowance(AllowanceInvariant),
+}
+
+/// Parameters of [`Invariant::Solvency`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct SolvencyInvariant {
+    pub token: Address,
+    pub treasury: Address,
+    /// Contract implementing `ObligationSource`
+    pub source: Address,
+}
+
+/// Parameters of [`Invariant::Allowance`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct AllowanceInvariant {
+    pub token: Address,
+    pub owner: Address,
+    pub spender: Address,
+    /// Highest allowance the organization permits
+    pub limit: i128,
+}
+
+/// Result of the most recent run of a check.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum CheckStatus {
+    /// Not run since it was registered
+    Unchecked,
+    Healthy,
+    Violated,
+    /// A contract the check reads from failed to answer
+    Unavailable,
+}
+
+/// A registered invariant check.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Check {
+    pub check_id: u64,
+    pub invariant: Invariant,
+    /// Circuit breaker tripped when the check fails, if any
+    pub breaker: Option<Address>,
+    pub status: CheckStatus,
+    /// Ledger timestamp of the most recent run (0 if never run)
+    pub last_run_at: u64,
+    /// Number of runs that found the invariant broken or unverifiable
+    pub failures: u32,
+}
+
+/// Storage keys for the watchdog contract.
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    /// Admin address
+    Admin,
+    /// IDs of the registered checks, ascending
+    Checks,
+    /// ID of the most recently registered check
+    LastCheckId,
+    /// Registered check by ID
+    Check(u64),
+}
+
+/// Event emitters for watchdog operations.
+pub struct WatchdogEvents;
+
+impl WatchdogEvents {
+    /// Emitted when a check is registered.
+    pub fn registered(env: &Env, check: &Check) {
+        let topics = (symbol_short!("watchdog"), symbol_short!("register"));
+        events::publish(env, topics, (check.check_id, check.invariant.clone()));
+    }
+
+    /// Emitted when a check is removed.
+    pub fn removed(env: &Env, check_id: u64) {
+        let topics = (symbol_short!("watchdog"), symbol_short!("removed"));
+        events::publish(env, topics, check_id);
+    }
+
+    /// Emitted when a check finds its invariant broken or cannot verify it.
+    /// `observed` and `bound` are the compared amounts (0 when unavailable).
+    pub fn alarm(env: &Env, check: &Check, observed: i128, bound: i128, breaker_tripped: bool) {
+        let topics = (symbol_short!("watchdog"), symbol_short!("alarm"));
+        events::publish(
+            env,
+            topics,
+            (
+                check.check_id,
+                check.status,
+                observed,
+                bound,
+                breaker_tripped,
+            ),
+        );
+    }
+}
@@ -0,0 +1,68 @@
+//! # Replay Tool
+//!
+//! Reproduces a production escrow state locally by replaying recorded
+//! invocations, in order and at their recorded ledgers, against a fresh `Env`.
+//! Support uses it to rebuild the exact state of a disputed escrow.
+//!
+//! ```text
+//! cargo run -p replay -- <recording.json> [--snapshot <out.json>]
+//! ```
+//!
+//! Every call is checked against the outcome recorded in production and the
+//! replay stops at the first divergence. `--snapshot` writes the resulting
+//! ledger so it can be loaded with `Env::from_ledger_snapshot_file`.
+
+mod recording;
+mod replay;
+mod token;
+
+use replay::{Outcome, Replay};
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (path, snapshot) = match args.as_slice() {
+        [path] => (path, None),
+        [path, flag, out] if flag == "--snapshot" => (path, Some(out)),
+        _ => {
+            eprintln!("usage: replay <recording.json> [--snapshot <out.json>]");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(path, snapshot) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(path: &str, snapshot: Option<&String>) -> Result<(), String> {
+    let json = std::fs::read_to_string(path).map_err(|e| format!("cannot read {path}: {e}"))?;
+    let recording = recording::parse(&json)?;
+
+    let replay = Replay::new(&recording)?;
+    println!("escrow: {:?}", replay.escrow);
+    println!("token:  {:?}", replay.token);
+
+    let result = replay.run(&recording, |i, invocation, outcome| {
+        let shown = match outcome {
+            Outcome::Ok(val) => format!("ok {}", serde_json::to_string(val).unwrap_or_default()),
+            Outcome::Failed(code) => format!("error #{code}"),
+            Outcome::Aborted => "aborted".to_string(),
+        };
+        println!("#{i} {:?}.{} -> {shown}", invocation.contract, invocation.function);
+    });
+
+    // The state up to a divergence is still worth inspecting
+    if let Some(out) = snapshot {
+        replay.env.to_ledger_snapshot_file(out);
+        println!("snapshot written to {out}");
+    }
+    result
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,144 @@
+//! Recording format read by the replay tool.
+//!
+//! A recording is a JSON object holding the escrow admin and the ordered
+//! invocations captured from production:
+//!
+//! ```json
+//! {
+//!   "admin": "G...",
+//!   "escrow": "C...",
+//!   "invocations": [
+//!     {
+//!       "ledger": 1200,
+//!       "timestamp": 1700000000,
+//!       "contract": "escrow",
+//!       "function": "create_escrow",
+//!       "args": [{ "address": "G..." }, { "address": "G..." }, { "i128": { "hi": 0, "lo": 5000000 } }, { "u64": 20000 }]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Arguments use the `ScVal` JSON encoding emitted by the Stellar CLI and RPC,
+//! so captured calls can be pasted in unchanged. `escrow` is optional and pins
+//! the contract to its production address. `ledger` and `timestamp` are
+//! optional and move the ledger forward before the call. An `error` code marks
+//! a call that failed in production with that contract error.
+
+use serde_json::Value;
+use soroban_sdk::xdr::ScVal;
+
+/// Contract a recorded invocation was sent to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Target {
+    /// The escrow contract under investigation
+    Escrow,
+    /// The token the escrow holds
+    Token,
+}
+
+/// A single recorded contract call.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Invocation {
+    /// Ledger sequence the call was included in
+    pub ledger: Option<u32>,
+    /// Ledger close time the call was included in
+    pub timestamp: Option<u64>,
+    /// Contract the call was sent to
+    pub contract: Target,
+    /// Name of the invoked function
+    pub function: String,
+    /// Call arguments in order
+    pub args: Vec<ScVal>,
+    /// Contract error code the call failed with, if it failed
+    pub error: Option<u32>,
+}
+
+/// A full recording to replay.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recording {
+    /// Strkey of the escrow admin passed to `initialize`
+    pub admin: String,
+    /// Strkey of the production escrow contract
+    pub escrow: Option<String>,
+    /// Invocations in the order they were applied
+    pub invocations: Vec<Invocation>,
+}
+
+/// Parses a recording from its JSON text.
+pub fn parse(json: &str) -> Result<Recording, String> {
+    let root: Value = serde_json::from_str(json).map_err(|e| format!("invalid JSON: {e}"))?;
+
+    let admin = string_field(&root, "admin")?
+        .ok_or("recording is missing `admin`")?
+        .to_string();
+    let escrow = string_field(&root, "escrow")?.map(str::to_string);
+
+    let invocations = root
+        .get("invocations")
+        .and_then(Value::as_array)
+        .ok_or("recording is missing `invocations`")?
+        .iter()
+        .enumerate()
+        .map(|(i, call)| parse_invocation(call).map_err(|e| format!("invocation #{i}: {e}")))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Recording {
+        admin,
+        escrow,
+        invocations,
+    })
+}
+
+fn parse_invocation(call: &Value) -> Result<Invocation, String> {
+    let contract = match string_field(call, "contract")? {
+        None | Some("escrow") => Target::Escrow,
+        Some("token") => Target::Token,
+        Some(other) => return Err(format!("unknown contract `{other}`")),
+    };
+    let function = string_field(call, "function")?
+        .ok_or("missing `function`")?
+        .to_string();
+
+    let args = match call.get("args") {
+        None => Vec::new(),
+        Some(Value::Array(args)) => args
+            .iter()
+            .enumerate()
+            .map(|(i, arg)| {
+                serde_json::from_value(arg.clone()).map_err(|e| format!("argument #{i}: {e}"))
+            })
+            .collect::<Result<_, _>>()?,
+        Some(_) => return Err("`args` must be an array".into()),
+    };
+
+    Ok(Invocation {
+        ledger: int_field(call, "ledger")?,
+        timestamp: int_field(call, "timestamp")?,
+        contract,
+        function,
+        args,
+        error: int_field(call, "error")?,
+    })
+}
+
+fn string_field<'a>(value: &'a Value, key: &str) -> Result<Option<&'a str>, String> {
+    match value.get(key) {
+        None => Ok(None),
+        Some(field) => field
+            .as_str()
+            .map(Some)
+            .ok_or_else(|| format!("`{key}` must be a string")),
+    }
+}
+
+fn int_field<T: TryFrom<u64>>(value: &Value, key: &str) -> Result<Option<T>, String> {
+    match value.get(key) {
+        None => Ok(None),
+        Some(field) => field
+            .as_u64()
+            .and_then(|n| T::try_from(n).ok())
+            .map(Some)
+            .ok_or_else(|| format!("`{key}` is out of range")),
+    }
+}
@@ -0,0 +1,147 @@
+//! Replays a recording against a local `Env`.
+//!
+//! The escrow holds a [`ReplayToken`] rather than the production token, so
+//! recorded token calls are limited to `mint`, `balance` and `transfer`.
+
+use crate::recording::{Invocation, Recording, Target};
+use crate::token::ReplayToken;
+use escrow::EscrowContract;
+use soroban_sdk::{
+    testutils::{EnvTestConfig, Ledger},
+    xdr::{ScErrorType, ScVal},
+    Address, Env, Error, InvokeError, Symbol, TryFromVal, Val, Vec,
+};
+
+/// Ledger TTLs used during replay so no entry expires between recorded calls.
+const REPLAY_ENTRY_TTL: u32 = 50_000_000;
+
+/// Result of replaying one invocation.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome {
+    /// The call succeeded and returned this value
+    Ok(ScVal),
+    /// The call failed with this contract error code
+    Failed(u32),
+    /// The call failed outside the contract (auth, budget, host trap)
+    Aborted,
+}
+
+impl Outcome {
+    /// Whether this outcome agrees with what production recorded.
+    pub fn matches(&self, invocation: &Invocation) -> bool {
+        match (self, invocation.error) {
+            (Outcome::Ok(_), None) => true,
+            (Outcome::Failed(code), Some(expected)) => *code == expected,
+            _ => false,
+        }
+    }
+}
+
+/// A local environment with the escrow and its token deployed.
+pub struct Replay {
+    pub env: Env,
+    pub escrow: Address,
+    pub token: Address,
+}
+
+impl Replay {
+    /// Deploys and initializes the escrow as described by `recording`.
+    pub fn new(recording: &Recording) -> Result<Self, String> {
+        let env = Env::new_with_config(EnvTestConfig {
+            capture_snapshot_at_drop: false,
+        });
+        env.mock_all_auths();
+        env.ledger().with_mut(|li| {
+            li.min_persistent_entry_ttl = REPLAY_ENTRY_TTL;
+            li.min_temp_entry_ttl = REPLAY_ENTRY_TTL;
+            li.max_entry_ttl = REPLAY_ENTRY_TTL;
+        });
+
+        let admin = parse_address(&env, &recording.admin)?;
+        let token = env.register(ReplayToken, ());
+        let escrow = match &recording.escrow {
+            Some(strkey) => {
+                env.register_at(&parse_address(&env, strkey)?, EscrowContract, ())
+            }
+            None => env.register(EscrowContract, ()),
+        };
+        escrow::EscrowContractClient::new(&env, &escrow).initialize(&admin, &token);
+
+        Ok(Replay { env, escrow, token })
+    }
+
+    /// Applies one recorded invocation at its recorded ledger.
+    pub fn apply(&self, invocation: &Invocation) -> Result<Outcome, String> {
+        self.env.ledger().with_mut(|li| {
+            if let Some(sequence) = invocation.ledger {
+                li.sequence_number = sequence;
+            }
+            if let Some(timestamp) = invocation.timestamp {
+                li.timestamp = timestamp;
+            }
+        });
+
+        let mut args = Vec::new(&self.env);
+        for (i, arg) in invocation.args.iter().enumerate() {
+            let val = Val::try_from_val(&self.env, arg)
+                .map_err(|_| format!("argument #{i} cannot be converted"))?;
+            args.push_back(val);
+        }
+
+        let contract = match invocation.contract {
+            Target::Escrow => &self.escrow,
+            Target::Token => &self.token,
+        };
+        let function = Symbol::new(&self.env, &invocation.function);
+        let outcome = match self
+            .env
+            .try_invoke_contract::<Val, Error>(contract, &function, args)
+        {
+            Ok(Ok(val)) => Outcome::Ok(
+                ScVal::try_from_val(&self.env, &val)
+                    .map_err(|_| "return value cannot be converted".to_string())?,
+            ),
+            Ok(Err(_)) => Outcome::Aborted,
+            Err(Ok(error)) if error.is_type(ScErrorType::Contract) => {
+                Outcome::Failed(error.get_code())
+            }
+            Err(Err(InvokeError::Contract(code))) => Outcome::Failed(code),
+            Err(_) => Outcome::Aborted,
+        };
+        Ok(outcome)
+    }
+
+    /// Applies every invocation in order, stopping at the first divergence.
+    ///
+    /// `report` is called with each invocation and its outcome as it is applied.
+    pub fn run(
+        &self,
+        recording: &Recording,
+        mut report: impl FnMut(usize, &Invocation, &Outcome),
+    ) -> Result<(), String> {
+        for (i, invocation) in recording.invocations.iter().enumerate() {
+            let outcome = self.apply(invocation).map_err(|e| format!("invocation #{i}: {e}"))?;
+            report(i, invocation, &outcome);
+            if !outcome.matches(invocation) {
+                return Err(format!(
+                    "invocation #{i} ({}) diverged from production: expected {}, got {:?}",
+                    invocation.function,
+                    match invocation.error {
+                        Some(code) => format!("error #{code}"),
+                        None => "success".to_string(),
+                    },
+                    outcome
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_address(env: &Env, strkey: &str) -> Result<Address, String> {
+    let valid = strkey.len() == 56 && (strkey.starts_with('G') || strkey.starts_with('C'));
+    if !valid {
+        return Err(format!("`{strkey}` is not an account or contract strkey"));
+    }
+    Ok(Address::from_str(env, strkey))
+}
@@ -0,0 +1,84 @@
+use crate::recording::{self, Target};
+use crate::replay::{Outcome, Replay};
+use escrow::{EscrowContractClient, EscrowStatus};
+use crate::token::ReplayTokenClient;
+use soroban_sdk::Address;
+
+const DISPUTE: &str = include_str!("../recordings/dispute.json");
+
+#[test]
+fn test_parse_recording() {
+    let recording = recording::parse(DISPUTE).unwrap();
+    assert_eq!(recording.invocations.len(), 8);
+    assert_eq!(recording.escrow, None);
+
+    let mint = &recording.invocations[0];
+    assert_eq!(mint.contract, Target::Token);
+    assert_eq!(mint.function, "mint");
+    assert_eq!(mint.ledger, Some(1000));
+    assert_eq!(mint.args.len(), 2);
+    assert_eq!(recording.invocations[1].contract, Target::Escrow);
+    assert_eq!(recording.invocations[5].error, Some(10));
+}
+
+#[test]
+fn test_parse_rejects_malformed_recording() {
+    assert!(recording::parse("{").is_err());
+    assert!(recording::parse(r#"{"invocations": []}"#).is_err());
+
+    let bad_arg = r#"{"admin": "G", "invocations": [{"function": "f", "args": [{"nope": 1}]}]}"#;
+    let err = recording::parse(bad_arg).unwrap_err();
+    assert!(err.starts_with("invocation #0: argument #0"));
+
+    let bad_target = r#"{"admin": "G", "invocations": [{"contract": "vault", "function": "f"}]}"#;
+    assert!(recording::parse(bad_target).is_err());
+}
+
+#[test]
+fn test_replay_reproduces_dispute_state() {
+    let recording = recording::parse(DISPUTE).unwrap();
+    let replay = Replay::new(&recording).unwrap();
+
+    let mut outcomes = Vec::new();
+    replay
+        .run(&recording, |_, _, outcome| outcomes.push(outcome.clone()))
+        .unwrap();
+    assert_eq!(outcomes[5], Outcome::Failed(10));
+    assert_eq!(outcomes[7], Outcome::Failed(11));
+
+    let client = EscrowContractClient::new(&replay.env, &replay.escrow);
+    let escrow = client.get_escrow(&1).unwrap();
+    assert_eq!(escrow.status, EscrowStatus::Disputed);
+    assert_eq!(escrow.amount, 5_000_000);
+
+    let dispute = client.get_dispute(&1).unwrap();
+    assert_eq!(dispute.opened_at, 1501);
+    assert_eq!(dispute.opened_by, escrow.recipient);
+
+    let token = ReplayTokenClient::new(&replay.env, &replay.token);
+    assert_eq!(token.balance(&replay.escrow), 6_000_000);
+}
+
+#[test]
+fn test_replay_stops_at_divergence() {
+    let mut recording = recording::parse(DISPUTE).unwrap();
+    // Production recorded the valid dispute as failing
+    recording.invocations[6].error = Some(9);
+
+    let replay = Replay::new(&recording).unwrap();
+    let mut applied = 0;
+    let err = replay.run(&recording, |_, _, _| applied += 1).unwrap_err();
+    assert!(err.starts_with("invocation #6 (open_dispute) diverged"));
+    assert_eq!(applied, 7);
+}
+
+#[test]
+fn test_replay_pins_production_escrow_address() {
+    let mut recording = recording::parse(DISPUTE).unwrap();
+    let strkey = "CCUEG6CDZLJO4FZ2CETCWQ65BDRESPMU32JQZPV32XWULAN73B2LUDJ5";
+    recording.escrow = Some(strkey.to_string());
+
+    let replay = Replay::new(&recording).unwrap();
+    assert_eq!(replay.escrow, Address::from_str(&replay.env, strkey));
+    replay.run(&recording, |_, _, _| {}).unwrap();
+}